@@ -1,5 +1,41 @@
 // generated by `sqlx migrate build-script`
+use std::{process::Command, time::SystemTime};
+
+/// Runs a command and returns its trimmed output, if it succeeded.
+fn output(program: &str, args: &[&str]) -> Option<String> {
+    let out = Command::new(program).args(args).output().ok()?;
+    if !out.status.success() {
+        return None;
+    }
+    let out = String::from_utf8(out.stdout).ok()?;
+    Some(out.trim().to_string())
+}
+
 fn main() {
     // trigger recompilation when a new migration is added
     println!("cargo:rerun-if-changed=migrations");
+
+    // build info, see `client::build_info`. Docker builds don't have the
+    // .git directory, so the hash can be passed with `GIT_HASH` instead.
+    println!("cargo:rerun-if-changed=.git/HEAD");
+    println!("cargo:rerun-if-changed=.git/refs");
+    println!("cargo:rerun-if-env-changed=GIT_HASH");
+
+    // an unset build arg still sets the variable, to an empty string
+    let git_hash = std::env::var("GIT_HASH")
+        .ok()
+        .filter(|hash| !hash.is_empty())
+        .map(|hash| hash.chars().take(10).collect())
+        .or_else(|| output("git", &["rev-parse", "--short=10", "HEAD"]))
+        .unwrap_or_else(|| "unknown".to_string());
+    let rustc = std::env::var("RUSTC").unwrap_or_else(|_| "rustc".to_string());
+    let rustc_version = output(&rustc, &["--version"]).unwrap_or_else(|| "unknown".to_string());
+    let built_at = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap()
+        .as_secs();
+
+    println!("cargo:rustc-env=STARBOARD_GIT_HASH={git_hash}");
+    println!("cargo:rustc-env=STARBOARD_RUSTC_VERSION={rustc_version}");
+    println!("cargo:rustc-env=STARBOARD_BUILT_AT={built_at}");
 }
@@ -0,0 +1,2 @@
+-- Add migration script here
+ALTER TABLE starboards ADD COLUMN attach_thread BOOLEAN NOT NULL DEFAULT false;
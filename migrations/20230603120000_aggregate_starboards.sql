@@ -0,0 +1,3 @@
+-- Add migration script here
+ALTER TABLE starboards ADD COLUMN aggregate BOOLEAN NOT NULL DEFAULT false;
+ALTER TABLE starboards ADD COLUMN source_starboards INTEGER[] NOT NULL DEFAULT '{}';
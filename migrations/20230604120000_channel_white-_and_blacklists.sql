@@ -0,0 +1,3 @@
+-- Add migration script here
+ALTER TABLE starboards ADD COLUMN channel_whitelist BIGINT[] NOT NULL DEFAULT '{}';
+ALTER TABLE starboards ADD COLUMN channel_blacklist BIGINT[] NOT NULL DEFAULT '{}';
@@ -0,0 +1,2 @@
+-- Add migration script here
+ALTER TABLE members ADD COLUMN imported_xp REAL NOT NULL DEFAULT 0;
@@ -0,0 +1,3 @@
+-- Add migration script here
+ALTER TABLE starboards ADD COLUMN reply_with_link BOOLEAN NOT NULL DEFAULT false;
+ALTER TABLE starboard_messages ADD COLUMN reply_message_id BIGINT;
@@ -0,0 +1,2 @@
+-- Add migration script here
+ALTER TABLE starboards ADD COLUMN allow_system_messages BOOLEAN NOT NULL DEFAULT false;
@@ -0,0 +1,2 @@
+-- Add migration script here
+ALTER TABLE starboards ADD COLUMN translate_button BOOLEAN NOT NULL DEFAULT false;
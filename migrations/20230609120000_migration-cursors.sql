@@ -0,0 +1,16 @@
+-- Add migration script here
+CREATE TABLE migration_cursors (
+    channel_id BIGINT NOT NULL,
+    guild_id BIGINT NOT NULL,
+    starboard_id INTEGER NOT NULL,
+    before_id BIGINT,
+    scanned INTEGER NOT NULL DEFAULT 0,
+    imported INTEGER NOT NULL DEFAULT 0,
+    finished BOOLEAN NOT NULL DEFAULT false,
+
+    PRIMARY KEY (channel_id),
+    FOREIGN KEY (guild_id) REFERENCES guilds (guild_id)
+        ON DELETE CASCADE,
+    FOREIGN KEY (starboard_id) REFERENCES starboards (id)
+        ON DELETE CASCADE
+);
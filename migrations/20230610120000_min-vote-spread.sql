@@ -0,0 +1,3 @@
+-- Add migration script here
+ALTER TABLE votes ADD COLUMN created_at TIMESTAMPTZ NOT NULL DEFAULT now();
+ALTER TABLE starboards ADD COLUMN min_vote_spread_seconds INTEGER;
@@ -0,0 +1,3 @@
+-- Add migration script here
+ALTER TABLE guilds ADD COLUMN locale TEXT NOT NULL DEFAULT 'en';
+ALTER TABLE users ADD COLUMN locale TEXT;
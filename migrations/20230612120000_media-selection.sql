@@ -0,0 +1,2 @@
+-- Add migration script here
+ALTER TABLE starboards ADD COLUMN media_selection SMALLINT NOT NULL DEFAULT 0;
@@ -0,0 +1,32 @@
+-- Add migration script here
+CREATE TABLE competitions (
+    id SERIAL PRIMARY KEY,
+    guild_id BIGINT NOT NULL,
+    starboard_id INTEGER NOT NULL,
+    announce_channel_id BIGINT NOT NULL,
+    prize TEXT,
+    starts_at TIMESTAMPTZ NOT NULL,
+    ends_at TIMESTAMPTZ NOT NULL,
+    finished BOOLEAN NOT NULL DEFAULT false,
+
+    FOREIGN KEY (guild_id) REFERENCES guilds (guild_id)
+        ON DELETE CASCADE ON UPDATE CASCADE,
+    FOREIGN KEY (starboard_id) REFERENCES starboards (id)
+        ON DELETE CASCADE ON UPDATE CASCADE
+);
+CREATE INDEX competitions__guild_id ON competitions (guild_id);
+CREATE INDEX competitions__starboard_id ON competitions (starboard_id);
+CREATE INDEX competitions__ends_at ON competitions (ends_at) WHERE finished = false;
+
+CREATE TABLE competition_winners (
+    competition_id INTEGER NOT NULL,
+    place SMALLINT NOT NULL,
+    message_id BIGINT NOT NULL,
+    channel_id BIGINT NOT NULL,
+    author_id BIGINT NOT NULL,
+    points INTEGER NOT NULL,
+
+    PRIMARY KEY (competition_id, place),
+    FOREIGN KEY (competition_id) REFERENCES competitions (id)
+        ON DELETE CASCADE ON UPDATE CASCADE
+);
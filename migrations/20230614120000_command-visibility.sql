@@ -0,0 +1,2 @@
+-- Add migration script here
+ALTER TABLE guilds ADD COLUMN command_visibility_overrides JSONB NOT NULL DEFAULT '{}';
@@ -0,0 +1,37 @@
+-- Add migration script here
+
+-- Past races left some messages with more than one post on the same
+-- starboard. Keep the oldest post, and queue the newer ones so the bot can
+-- delete them from Discord (see `core::starboard::dedupe`).
+CREATE TABLE duplicate_posts (
+    starboard_message_id BIGINT NOT NULL,
+    kept_starboard_message_id BIGINT NOT NULL,
+    starboard_id INTEGER NOT NULL,
+    message_id BIGINT NOT NULL,
+    guild_id BIGINT NOT NULL,
+    channel_id BIGINT NOT NULL,
+
+    PRIMARY KEY (starboard_message_id)
+);
+CREATE INDEX duplicate_posts__guild_id ON duplicate_posts USING BTREE ((guild_id));
+
+INSERT INTO duplicate_posts
+    (starboard_message_id, kept_starboard_message_id, starboard_id, message_id, guild_id, channel_id)
+SELECT sm.starboard_message_id, kept.starboard_message_id, sm.starboard_id, sm.message_id,
+    s.guild_id, s.channel_id
+FROM starboard_messages sm
+JOIN starboards s ON s.id = sm.starboard_id
+JOIN (
+    SELECT starboard_id, message_id, MIN(starboard_message_id) AS starboard_message_id
+    FROM starboard_messages
+    GROUP BY starboard_id, message_id
+    HAVING COUNT(*) > 1
+) kept ON kept.starboard_id = sm.starboard_id AND kept.message_id = sm.message_id
+WHERE sm.starboard_message_id <> kept.starboard_message_id;
+
+DELETE FROM starboard_messages
+WHERE starboard_message_id IN (SELECT starboard_message_id FROM duplicate_posts);
+
+-- only one post per message per starboard from now on
+CREATE UNIQUE INDEX sb_messages__starboard_id_message_id
+    ON starboard_messages USING BTREE ((starboard_id), (message_id));
@@ -0,0 +1,2 @@
+-- Add migration script here
+ALTER TABLE starboards ADD COLUMN min_unique_voters SMALLINT;
@@ -0,0 +1,16 @@
+-- Add migration script here
+
+-- Which process runs each shard, so shards can be handed over to another
+-- process without two of them processing the same guilds.
+CREATE TABLE shard_status (
+    shard_id INTEGER NOT NULL,
+    -- identifies the process, see `client::shards::ShardState`
+    owner TEXT,
+    -- the owner should stop starting new work for the shard
+    draining BOOLEAN NOT NULL DEFAULT false,
+    -- the owner finished its in-flight work, so the shard can be taken over
+    drained BOOLEAN NOT NULL DEFAULT false,
+    heartbeat_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+
+    PRIMARY KEY (shard_id)
+);
@@ -0,0 +1,4 @@
+-- Add migration script here
+ALTER TABLE starboards ADD COLUMN min_chars SMALLINT NOT NULL DEFAULT 0;
+ALTER TABLE starboards ADD COLUMN max_chars SMALLINT;
+ALTER TABLE starboards ADD COLUMN require_link BOOLEAN NOT NULL DEFAULT false;
@@ -0,0 +1,3 @@
+-- Add migration script here
+ALTER TABLE guilds ADD COLUMN ignored_webhook_ids BIGINT[] NOT NULL DEFAULT '{}';
+ALTER TABLE guilds ADD COLUMN ignored_application_ids BIGINT[] NOT NULL DEFAULT '{}';
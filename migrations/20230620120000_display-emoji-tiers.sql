@@ -0,0 +1,2 @@
+-- Add migration script here
+ALTER TABLE starboards ADD COLUMN display_emoji_tiers JSONB NOT NULL DEFAULT '[]';
@@ -0,0 +1,16 @@
+-- Add migration script here
+ALTER TABLE starboards ADD COLUMN needs_attention BOOLEAN NOT NULL DEFAULT false;
+ALTER TABLE autostar_channels ADD COLUMN needs_attention BOOLEAN NOT NULL DEFAULT false;
+
+CREATE TABLE emoji_migration_audit (
+    id SERIAL PRIMARY KEY,
+    guild_id BIGINT NOT NULL,
+    -- "starboard", "override" or "autostar"
+    kind TEXT NOT NULL,
+    row_id INTEGER NOT NULL,
+    setting TEXT NOT NULL,
+    old_value TEXT[] NOT NULL,
+    new_value TEXT[] NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+CREATE INDEX emoji_migration_audit__guild_id ON emoji_migration_audit (guild_id);
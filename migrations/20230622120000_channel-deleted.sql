@@ -0,0 +1,3 @@
+-- Add migration script here
+ALTER TABLE starboards ADD COLUMN channel_deleted BOOLEAN NOT NULL DEFAULT false;
+ALTER TABLE autostar_channels ADD COLUMN channel_deleted BOOLEAN NOT NULL DEFAULT false;
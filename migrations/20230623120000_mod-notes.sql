@@ -0,0 +1,11 @@
+-- Add migration script here
+CREATE TABLE mod_notes (
+    id SERIAL PRIMARY KEY,
+    guild_id BIGINT NOT NULL REFERENCES guilds (guild_id) ON DELETE CASCADE ON UPDATE CASCADE,
+    -- always the original message, never a starboard post
+    message_id BIGINT NOT NULL,
+    author_id BIGINT NOT NULL,
+    note TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+CREATE INDEX mod_notes__message_id ON mod_notes (message_id);
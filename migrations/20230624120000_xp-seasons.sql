@@ -0,0 +1,18 @@
+-- Add migration script here
+ALTER TABLE guilds ADD COLUMN xp_season SMALLINT NOT NULL DEFAULT 1;
+-- percentage of XP kept when a new season starts
+ALTER TABLE guilds ADD COLUMN xp_carryover SMALLINT NOT NULL DEFAULT 0;
+ALTER TABLE guilds ADD COLUMN xp_season_channel_id BIGINT;
+
+-- the lifetime XP a member had when the current season started, minus any
+-- carryover. `xp` is the lifetime XP minus this.
+ALTER TABLE members ADD COLUMN season_start_xp REAL NOT NULL DEFAULT 0;
+
+CREATE TABLE xp_seasons (
+    guild_id BIGINT NOT NULL REFERENCES guilds (guild_id) ON DELETE CASCADE ON UPDATE CASCADE,
+    season SMALLINT NOT NULL,
+    user_id BIGINT NOT NULL,
+    xp REAL NOT NULL,
+    PRIMARY KEY (guild_id, season, user_id)
+);
+CREATE INDEX xp_seasons__guild_id_season_xp ON xp_seasons (guild_id, season, xp DESC);
@@ -0,0 +1,2 @@
+-- Add migration script here
+ALTER TABLE starboards ADD COLUMN clone_attachments BOOLEAN NOT NULL DEFAULT false;
@@ -0,0 +1,17 @@
+-- Add migration script here
+ALTER TABLE starboards ADD COLUMN lock_vote_after_mod_removal BOOLEAN NOT NULL DEFAULT false;
+
+CREATE TABLE vote_locks (
+    message_id BIGINT NOT NULL,
+    user_id BIGINT NOT NULL,
+    locked_by BIGINT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    PRIMARY KEY (message_id, user_id),
+    FOREIGN KEY (message_id) REFERENCES messages (message_id)
+        ON DELETE CASCADE
+        ON UPDATE CASCADE,
+    FOREIGN KEY (user_id) REFERENCES users (user_id)
+        ON DELETE CASCADE
+        ON UPDATE CASCADE
+);
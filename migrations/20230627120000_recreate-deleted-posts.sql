@@ -0,0 +1,17 @@
+-- Add migration script here
+ALTER TABLE starboards ADD COLUMN recreate_deleted_posts BOOLEAN NOT NULL DEFAULT true;
+
+CREATE TABLE deleted_posts (
+    message_id BIGINT NOT NULL,
+    starboard_id INTEGER NOT NULL,
+    starboard_message_id BIGINT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    PRIMARY KEY (message_id, starboard_id),
+    FOREIGN KEY (message_id) REFERENCES messages (message_id)
+        ON DELETE CASCADE
+        ON UPDATE CASCADE,
+    FOREIGN KEY (starboard_id) REFERENCES starboards (id)
+        ON DELETE CASCADE
+        ON UPDATE CASCADE
+);
@@ -0,0 +1,3 @@
+-- Add migration script here
+ALTER TABLE autostar_channels ADD COLUMN required_format_regex TEXT;
+ALTER TABLE autostar_channels ADD COLUMN format_example TEXT;
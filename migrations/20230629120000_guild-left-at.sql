@@ -0,0 +1,5 @@
+-- Add migration script here
+ALTER TABLE guilds ADD COLUMN left_at TIMESTAMPTZ;
+CREATE INDEX guilds__left_at ON guilds (left_at) WHERE left_at IS NOT NULL;
+
+CREATE INDEX messages__guild_id ON messages (guild_id);
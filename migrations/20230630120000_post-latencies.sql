@@ -0,0 +1,13 @@
+-- Add migration script here
+CREATE TABLE post_latencies (
+    guild_id BIGINT NOT NULL,
+    hour TIMESTAMPTZ NOT NULL,
+    bucket SMALLINT NOT NULL,
+    count INTEGER NOT NULL,
+
+    PRIMARY KEY (guild_id, hour, bucket),
+    FOREIGN KEY (guild_id) REFERENCES guilds (guild_id)
+        ON DELETE CASCADE
+        ON UPDATE CASCADE
+);
+CREATE INDEX post_latencies__hour ON post_latencies (hour);
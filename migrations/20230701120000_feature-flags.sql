@@ -0,0 +1,7 @@
+-- Add migration script here
+CREATE TABLE feature_flags (
+    name TEXT PRIMARY KEY,
+    rollout SMALLINT NOT NULL CHECK (rollout BETWEEN 0 AND 100),
+    allowlist BIGINT[] NOT NULL DEFAULT '{}',
+    denylist BIGINT[] NOT NULL DEFAULT '{}'
+);
@@ -0,0 +1,2 @@
+-- Add migration script here
+ALTER TABLE starboards ADD COLUMN votes_on_post BOOLEAN NOT NULL DEFAULT true;
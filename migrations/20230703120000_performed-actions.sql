@@ -0,0 +1,17 @@
+-- Add migration script here
+CREATE TABLE performed_actions (
+    message_id BIGINT NOT NULL,
+    starboard_id INTEGER NOT NULL,
+    kind TEXT NOT NULL,
+    render_hash BIGINT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    PRIMARY KEY (message_id, starboard_id, kind, render_hash),
+    FOREIGN KEY (message_id) REFERENCES messages (message_id)
+        ON DELETE CASCADE
+        ON UPDATE CASCADE,
+    FOREIGN KEY (starboard_id) REFERENCES starboards (id)
+        ON DELETE CASCADE
+        ON UPDATE CASCADE
+);
+CREATE INDEX performed_actions__created_at ON performed_actions (created_at);
@@ -0,0 +1,2 @@
+-- Add migration script here
+ALTER TABLE starboards ADD COLUMN vote_buttons BOOLEAN NOT NULL DEFAULT false;
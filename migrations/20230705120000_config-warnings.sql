@@ -0,0 +1,16 @@
+-- Add migration script here
+CREATE TABLE config_warnings (
+    starboard_id INTEGER NOT NULL,
+    guild_id BIGINT NOT NULL,
+    kind TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    PRIMARY KEY (starboard_id, kind),
+    FOREIGN KEY (starboard_id) REFERENCES starboards (id)
+        ON DELETE CASCADE
+        ON UPDATE CASCADE,
+    FOREIGN KEY (guild_id) REFERENCES guilds (guild_id)
+        ON DELETE CASCADE
+        ON UPDATE CASCADE
+);
+CREATE INDEX config_warnings__guild_id ON config_warnings (guild_id);
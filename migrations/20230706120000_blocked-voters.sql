@@ -0,0 +1,13 @@
+-- Add migration script here
+CREATE TABLE blocked_voters (
+    guild_id BIGINT NOT NULL,
+    user_id BIGINT NOT NULL,
+    remove_reactions BOOLEAN NOT NULL DEFAULT false,
+    blocked_by BIGINT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    PRIMARY KEY (guild_id, user_id),
+    FOREIGN KEY (guild_id) REFERENCES guilds (guild_id)
+        ON DELETE CASCADE
+        ON UPDATE CASCADE
+);
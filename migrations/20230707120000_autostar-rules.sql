@@ -0,0 +1,4 @@
+-- Add migration script here
+ALTER TABLE autostar_channels ADD COLUMN max_attachments SMALLINT;
+ALTER TABLE autostar_channels ADD COLUMN matches TEXT;
+ALTER TABLE autostar_channels ADD COLUMN not_matches TEXT;
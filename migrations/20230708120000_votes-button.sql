@@ -0,0 +1,3 @@
+-- Add migration script here
+ALTER TABLE starboards ADD COLUMN show_votes_button BOOLEAN NOT NULL DEFAULT false;
+ALTER TABLE starboards ADD COLUMN private_votes BOOLEAN NOT NULL DEFAULT false;
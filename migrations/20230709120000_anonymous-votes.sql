@@ -0,0 +1,22 @@
+-- Add migration script here
+ALTER TABLE starboards ADD COLUMN anonymous_votes BOOLEAN NOT NULL DEFAULT false;
+ALTER TABLE guilds ADD COLUMN vote_salt BIGINT NOT NULL
+    DEFAULT floor(random() * 4611686018427387904)::BIGINT;
+
+-- anonymous votes store a hash of the voter's id in voter_hash instead of
+-- user_id. The hash is always negative, so that it can't be mistaken for a
+-- real user. voter_id is whichever of the two is set, and identifies the vote.
+ALTER TABLE votes ADD COLUMN voter_hash BIGINT;
+ALTER TABLE votes ADD CONSTRAINT votes_one_voter
+    CHECK ((user_id IS NULL) <> (voter_hash IS NULL));
+ALTER TABLE votes DROP CONSTRAINT votes_pkey;
+ALTER TABLE votes ALTER COLUMN user_id DROP NOT NULL;
+ALTER TABLE votes ADD COLUMN voter_id BIGINT NOT NULL
+    GENERATED ALWAYS AS (COALESCE(user_id, voter_hash)) STORED;
+ALTER TABLE votes ADD PRIMARY KEY (message_id, starboard_id, voter_id);
+
+-- has to match `core::starboard::anonymous::voter_hash`
+CREATE FUNCTION voter_hash(salt BIGINT, user_id BIGINT) RETURNS BIGINT AS $$
+    SELECT ('x' || encode(substring(sha256(int8send(salt) || int8send(user_id)) FROM 1 FOR 8), 'hex'))::BIT(64)::BIGINT
+        | '-9223372036854775808'::BIGINT
+$$ LANGUAGE SQL IMMUTABLE;
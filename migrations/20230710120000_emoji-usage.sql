@@ -0,0 +1,11 @@
+-- Add migration script here
+CREATE TABLE emoji_usage (
+    starboard_id INTEGER NOT NULL,
+    emoji TEXT NOT NULL,
+    count BIGINT NOT NULL DEFAULT 0,
+
+    PRIMARY KEY (starboard_id, emoji),
+    FOREIGN KEY (starboard_id) REFERENCES starboards (id)
+        ON DELETE CASCADE
+        ON UPDATE CASCADE
+);
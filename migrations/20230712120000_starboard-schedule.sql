@@ -0,0 +1,3 @@
+-- Add migration script here
+ALTER TABLE starboards ADD COLUMN enable_at TIMESTAMPTZ;
+ALTER TABLE starboards ADD COLUMN disable_at TIMESTAMPTZ;
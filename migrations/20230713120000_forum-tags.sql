@@ -0,0 +1,5 @@
+-- Add migration script here
+ALTER TABLE starboards ADD COLUMN required_tags BIGINT[] NOT NULL DEFAULT '{}';
+ALTER TABLE starboards ADD COLUMN excluded_tags BIGINT[] NOT NULL DEFAULT '{}';
+ALTER TABLE autostar_channels ADD COLUMN required_tags BIGINT[] NOT NULL DEFAULT '{}';
+ALTER TABLE autostar_channels ADD COLUMN excluded_tags BIGINT[] NOT NULL DEFAULT '{}';
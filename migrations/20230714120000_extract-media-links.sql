@@ -0,0 +1,2 @@
+-- Add migration script here
+ALTER TABLE starboards ADD COLUMN extract_media_links BOOLEAN NOT NULL DEFAULT true;
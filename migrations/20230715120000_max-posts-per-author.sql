@@ -0,0 +1,6 @@
+-- Add migration script here
+ALTER TABLE starboards ADD COLUMN max_posts_per_author_per_day SMALLINT;
+
+-- posts are counted by when they were sent, which is part of their id
+CREATE INDEX sb_messages__starboard_id_sb_message_id
+    ON starboard_messages USING BTREE ((starboard_id), (starboard_message_id));
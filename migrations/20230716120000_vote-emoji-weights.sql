@@ -0,0 +1,3 @@
+-- Add migration script here
+ALTER TABLE starboards ADD COLUMN vote_emoji_weights JSONB NOT NULL DEFAULT '[]';
+ALTER TABLE votes ADD COLUMN weight SMALLINT NOT NULL DEFAULT 1;
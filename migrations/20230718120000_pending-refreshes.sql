@@ -0,0 +1,14 @@
+-- Add migration script here
+
+-- Refreshes skipped while their shard was draining. Whoever owns the shard
+-- next runs them.
+CREATE TABLE pending_refreshes (
+    message_id BIGINT NOT NULL,
+    shard_id INTEGER NOT NULL,
+    is_premium BOOLEAN NOT NULL,
+
+    PRIMARY KEY (message_id),
+    FOREIGN KEY (message_id) REFERENCES messages (message_id)
+        ON DELETE CASCADE ON UPDATE CASCADE
+);
+CREATE INDEX pending_refreshes__shard_id ON pending_refreshes (shard_id);
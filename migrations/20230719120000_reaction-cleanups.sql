@@ -0,0 +1,21 @@
+-- Add migration script here
+
+-- Posts to remove the bot's own vote-emoji reactions from, queued when a
+-- starboard is deleted or its vote emojis change (see
+-- `core::starboard::cleanup`). Rows are deleted as they're handled, so an
+-- interrupted cleanup picks up where it stopped.
+CREATE TABLE reaction_cleanups (
+    starboard_message_id BIGINT NOT NULL,
+    -- the starboard may already be deleted, so these aren't foreign keys
+    starboard_id INTEGER NOT NULL,
+    starboard_name TEXT NOT NULL,
+    guild_id BIGINT NOT NULL,
+    channel_id BIGINT NOT NULL,
+    -- stored emojis
+    emojis TEXT[] NOT NULL,
+
+    PRIMARY KEY (starboard_message_id),
+    FOREIGN KEY (guild_id) REFERENCES guilds (guild_id)
+        ON DELETE CASCADE ON UPDATE CASCADE
+);
+CREATE INDEX reaction_cleanups__starboard_id ON reaction_cleanups USING BTREE ((starboard_id));
@@ -0,0 +1,4 @@
+-- Add migration script here
+-- the emoji a vote was cast with, for the counts on the vote buttons. Votes
+-- from before don't have one.
+ALTER TABLE votes ADD COLUMN emoji TEXT;
@@ -0,0 +1,227 @@
+//! The set of channels with an (unlocked) autostar channel. It's loaded in the
+//! background after startup, and lookups fall back to the database until then.
+
+use std::{
+    collections::HashSet,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    time::Duration,
+};
+
+use dashmap::DashSet;
+use futures::TryStreamExt;
+use moka::future::Cache as MokaCache;
+use sqlx::Row;
+use twilight_model::id::{marker::ChannelMarker, Id};
+
+use crate::{
+    client::bot::StarboardBot,
+    constants,
+    database::AutoStarChannel,
+    errors::StarboardResult,
+    utils::{async_dash::AsyncDashSet, id_as_i64::GetI64, into_id::IntoId},
+};
+
+pub struct AutoStarChannelIds {
+    ids: AsyncDashSet<Id<ChannelMarker>>,
+    loaded: AtomicBool,
+    /// Channels removed while the set is loading. The load might have read
+    /// them before they were deleted, so they're removed again once it's
+    /// done. Also held while changing `ids` outside of the load.
+    removed: Mutex<HashSet<Id<ChannelMarker>>>,
+    /// Database lookups made while the set is still loading, including
+    /// negative ones.
+    checked: MokaCache<Id<ChannelMarker>, bool>,
+}
+
+impl AutoStarChannelIds {
+    pub fn new() -> Self {
+        Self {
+            ids: DashSet::new().into(),
+            loaded: AtomicBool::new(false),
+            removed: Mutex::new(HashSet::new()),
+            checked: MokaCache::builder()
+                .max_capacity(constants::MAX_AUTOSTAR_CHANNEL_CHECKS)
+                .time_to_live(constants::AUTOSTAR_CHANNEL_CHECKS_TTL)
+                .build(),
+        }
+    }
+
+    pub fn is_loaded(&self) -> bool {
+        self.loaded.load(Ordering::Acquire)
+    }
+
+    /// Whether the channel is an autostar channel, if that's known without
+    /// querying the database.
+    pub fn contains_cached(&self, channel_id: Id<ChannelMarker>) -> Option<bool> {
+        if self.ids.contains(&channel_id) {
+            Some(true)
+        } else if self.is_loaded() {
+            Some(false)
+        } else {
+            self.checked.get(&channel_id)
+        }
+    }
+
+    pub async fn contains(
+        &self,
+        pool: &sqlx::PgPool,
+        channel_id: Id<ChannelMarker>,
+    ) -> StarboardResult<bool> {
+        if let Some(contains) = self.contains_cached(channel_id) {
+            return Ok(contains);
+        }
+
+        let exists = AutoStarChannel::exists_in_channel(pool, channel_id.get_i64()).await?;
+        self.checked.insert(channel_id, exists).await;
+        Ok(exists)
+    }
+
+    pub async fn add(&self, channel_id: Id<ChannelMarker>) {
+        self.insert(channel_id);
+        self.checked.insert(channel_id, true).await;
+    }
+
+    fn insert(&self, channel_id: Id<ChannelMarker>) {
+        let mut removed = self.removed.lock().unwrap();
+        removed.remove(&channel_id);
+        self.ids.insert(channel_id);
+    }
+
+    /// Removes the channel, unless it still has another unlocked autostar
+    /// channel.
+    pub async fn remove(
+        &self,
+        pool: &sqlx::PgPool,
+        channel_id: Id<ChannelMarker>,
+    ) -> StarboardResult<()> {
+        if AutoStarChannel::exists_in_channel(pool, channel_id.get_i64()).await? {
+            return Ok(());
+        }
+
+        self.discard(channel_id);
+        self.checked.insert(channel_id, false).await;
+        Ok(())
+    }
+
+    fn discard(&self, channel_id: Id<ChannelMarker>) {
+        let mut removed = self.removed.lock().unwrap();
+        if !self.is_loaded() {
+            removed.insert(channel_id);
+        }
+        self.ids.remove(&channel_id);
+    }
+
+    pub fn len(&self) -> usize {
+        self.ids.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ids.is_empty()
+    }
+
+    async fn load(&self, pool: &sqlx::PgPool) -> sqlx::Result<()> {
+        let mut rows = sqlx::query(
+            "SELECT DISTINCT channel_id FROM autostar_channels
+            WHERE premium_locked=false AND channel_deleted=false",
+        )
+        .fetch(pool);
+        while let Some(row) = rows.try_next().await? {
+            let channel_id: i64 = row.try_get("channel_id")?;
+            self.ids.insert(channel_id.into_id());
+        }
+
+        self.finish_load();
+        self.checked.invalidate_all();
+        Ok(())
+    }
+
+    fn finish_load(&self) {
+        let mut removed = self.removed.lock().unwrap();
+        for channel_id in removed.drain() {
+            self.ids.remove(&channel_id);
+        }
+        self.loaded.store(true, Ordering::Release);
+    }
+}
+
+impl Default for AutoStarChannelIds {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// How long to wait before retrying after `failures` failed loads.
+fn load_retry_delay(failures: u32) -> Duration {
+    constants::AUTOSTAR_CHANNEL_LOAD_RETRY
+        .saturating_mul(2u32.saturating_pow(failures.saturating_sub(1)))
+        .min(constants::MAX_AUTOSTAR_CHANNEL_LOAD_RETRY)
+}
+
+pub async fn task_load_autostar_channel_ids(bot: Arc<StarboardBot>) {
+    let mut failures = 0;
+    while let Err(why) = bot.cache.autostar_channel_ids.load(&bot.pool).await {
+        bot.handle_error(&why.into()).await;
+
+        failures += 1;
+        tokio::time::sleep(load_retry_delay(failures)).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn id(id: u64) -> Id<ChannelMarker> {
+        Id::new(id)
+    }
+
+    #[test]
+    fn removed_during_load() {
+        let ids = AutoStarChannelIds::new();
+        ids.discard(id(1));
+        // the load read the channel before it was deleted
+        ids.ids.insert(id(1));
+        ids.ids.insert(id(2));
+        ids.finish_load();
+
+        assert!(ids.is_loaded());
+        assert_eq!(ids.contains_cached(id(1)), Some(false));
+        assert_eq!(ids.contains_cached(id(2)), Some(true));
+    }
+
+    #[test]
+    fn added_again_during_load() {
+        let ids = AutoStarChannelIds::new();
+        ids.discard(id(1));
+        ids.insert(id(1));
+        ids.finish_load();
+
+        assert_eq!(ids.contains_cached(id(1)), Some(true));
+    }
+
+    #[test]
+    fn removed_after_load() {
+        let ids = AutoStarChannelIds::new();
+        ids.ids.insert(id(1));
+        ids.finish_load();
+        ids.discard(id(1));
+
+        assert_eq!(ids.contains_cached(id(1)), Some(false));
+        assert!(ids.removed.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn retry_backoff() {
+        let base = constants::AUTOSTAR_CHANNEL_LOAD_RETRY;
+        assert_eq!(load_retry_delay(1), base);
+        assert_eq!(load_retry_delay(2), base * 2);
+        assert_eq!(load_retry_delay(3), base * 4);
+        assert_eq!(
+            load_retry_delay(100),
+            constants::MAX_AUTOSTAR_CHANNEL_LOAD_RETRY
+        );
+    }
+}
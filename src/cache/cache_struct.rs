@@ -1,14 +1,21 @@
-use std::{hash::Hash, sync::Arc, time::Duration};
+use std::{
+    collections::HashMap,
+    hash::Hash,
+    sync::{atomic::AtomicU64, Arc},
+    time::Duration,
+};
 
 use dashmap::{DashMap, DashSet};
 use moka::future::Cache as MokaCache;
+use regex::Regex;
 use tokio::sync::RwLock;
 use twilight_gateway::Event;
 use twilight_model::{
     channel::{Channel, ChannelType, Webhook},
     id::{
         marker::{
-            ChannelMarker, EmojiMarker, GuildMarker, MessageMarker, UserMarker, WebhookMarker,
+            ChannelMarker, EmojiMarker, GuildMarker, MessageMarker, TagMarker, UserMarker,
+            WebhookMarker,
         },
         Id,
     },
@@ -18,8 +25,16 @@ use crate::{
     cache::models::channel::CachedChannel,
     client::bot::StarboardBot,
     constants,
-    core::emoji::SimpleEmoji,
+    core::{
+        blocked_voters::BlockedVoters,
+        emoji::SimpleEmoji,
+        features::Flag,
+        ignored_sources::{IgnoredSources, MessageSource},
+    },
+    database::FeatureFlag,
     errors::StarboardResult,
+    interactions::commands::visibility::Visibility,
+    locale::Locale,
     utils::{
         async_dash::{AsyncDashMap, AsyncDashSet},
         get_status::get_status,
@@ -27,6 +42,7 @@ use crate::{
 };
 
 use super::{
+    autostar_channels::AutoStarChannelIds,
     models::{guild::CachedGuild, member::CachedMember, message::CachedMessage, user::CachedUser},
     update::UpdateCache,
 };
@@ -99,17 +115,57 @@ pub struct Cache {
     pub members: MokaCache<(Id<GuildMarker>, Id<UserMarker>), Option<Arc<CachedMember>>>,
 
     // database side
-    pub autostar_channel_ids: AsyncDashSet<Id<ChannelMarker>>,
+    pub autostar_channel_ids: AutoStarChannelIds,
     pub guild_vote_emojis: AsyncDashMap<i64, Vec<SimpleEmoji>>,
-    pub guild_premium: AsyncDashMap<i64, bool>,
+    pub guild_blocked_voters: AsyncDashMap<i64, Arc<BlockedVoters>>,
+    pub guild_premium: MokaCache<i64, bool>,
+    pub guild_locales: AsyncDashMap<i64, Locale>,
+    pub guild_command_visibility: AsyncDashMap<i64, HashMap<String, Visibility>>,
+    pub guild_ignored_sources: AsyncDashMap<i64, Arc<IgnoredSources>>,
+    pub guild_log_channels: AsyncDashMap<i64, Option<i64>>,
+    pub user_locales: MokaCache<i64, Option<Locale>>,
 
     // misc
     pub responses: MokaCache<Id<MessageMarker>, Id<MessageMarker>>,
     pub auto_deleted_posts: RwLock<cached::SizedCache<Id<MessageMarker>, ()>>,
+    /// (original message, target language) -> translated content
+    pub translations: MokaCache<(Id<MessageMarker>, String), String>,
+    /// Messages with a refresh scheduled by `schedule_refresh`.
+    pub deferred_refreshes: AsyncDashSet<Id<MessageMarker>>,
+    /// Reactions the bot removed itself, so the ReactionRemove event can be
+    /// skipped. Keyed by (message, user, stored emoji).
+    pub self_removed_reactions: MokaCache<(Id<MessageMarker>, Id<UserMarker>, String), ()>,
+    /// Channels where removing a reaction failed with 403.
+    pub reaction_remove_forbidden: MokaCache<Id<ChannelMarker>, ()>,
+    /// Channels where fetching a message failed with 403, so that every
+    /// reaction there doesn't fetch again. Cleared when the channel is
+    /// updated, since its permissions may have changed.
+    pub message_fetch_forbidden: MokaCache<Id<ChannelMarker>, ()>,
+    /// Recent message counts per webhook or bot, keyed by (guild, source).
+    pub message_sources: MokaCache<(i64, MessageSource), Arc<AtomicU64>>,
+    /// Messages whose autostar reactions were checked, so that each message
+    /// is only checked (and reacted to again) once.
+    pub autostar_reaction_checks: MokaCache<Id<MessageMarker>, ()>,
+    /// Compiled regexes for autostar channels, keyed by pattern.
+    pub autostar_regexes: MokaCache<String, Arc<Regex>>,
+    /// Post -> (original message, starboard), `None` for messages that
+    /// aren't posts.
+    pub post_originals: MokaCache<Id<MessageMarker>, Option<(i64, i32)>>,
+    /// Feature flag rows, `None` for flags without a row.
+    pub feature_flags: MokaCache<Flag, Option<Arc<FeatureFlag>>>,
+    /// Removed vote emojis the guild owner was already told about, keyed by
+    /// (guild, emoji).
+    pub removed_emoji_warnings: MokaCache<(Id<GuildMarker>, Id<EmojiMarker>), ()>,
+}
+
+impl Default for Cache {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl Cache {
-    pub fn new(autostar_channel_ids: DashSet<Id<ChannelMarker>>) -> Self {
+    pub fn new() -> Self {
         Self {
             guilds: DashMap::new().into(),
             webhooks: DashMap::new().into(),
@@ -117,9 +173,18 @@ impl Cache {
             users: moka_cache(constants::MAX_USERS, constants::USERS_TTI),
             members: moka_cache(constants::MAX_MEMBERS, constants::MEMBERS_TTI),
 
-            autostar_channel_ids: autostar_channel_ids.into(),
+            autostar_channel_ids: AutoStarChannelIds::new(),
             guild_vote_emojis: DashMap::new().into(),
-            guild_premium: DashMap::new().into(),
+            guild_blocked_voters: DashMap::new().into(),
+            guild_premium: MokaCache::builder()
+                .max_capacity(constants::MAX_GUILD_PREMIUM)
+                .time_to_live(constants::GUILD_PREMIUM_TTL)
+                .build(),
+            guild_locales: DashMap::new().into(),
+            guild_command_visibility: DashMap::new().into(),
+            guild_ignored_sources: DashMap::new().into(),
+            guild_log_channels: DashMap::new().into(),
+            user_locales: moka_cache(constants::MAX_USERS, constants::USERS_TTI),
 
             responses: moka_cache(
                 constants::MAX_STORED_RESPONSES,
@@ -128,6 +193,44 @@ impl Cache {
             auto_deleted_posts: RwLock::new(cached::SizedCache::with_size(
                 constants::MAX_STORED_AUTO_DELETES,
             )),
+            translations: moka_cache(constants::MAX_TRANSLATIONS, constants::TRANSLATIONS_TTI),
+            deferred_refreshes: DashSet::new().into(),
+            self_removed_reactions: moka_cache(
+                constants::MAX_SELF_REMOVED_REACTIONS,
+                constants::SELF_REMOVED_REACTIONS_TTI,
+            ),
+            reaction_remove_forbidden: MokaCache::builder()
+                .max_capacity(constants::MAX_REACTION_REMOVE_FORBIDDEN)
+                .time_to_live(constants::REACTION_REMOVE_FORBIDDEN_TTL)
+                .build(),
+            message_fetch_forbidden: MokaCache::builder()
+                .max_capacity(constants::MAX_MESSAGE_FETCH_FORBIDDEN)
+                .time_to_live(constants::MESSAGE_FETCH_FORBIDDEN_TTL)
+                .build(),
+            message_sources: MokaCache::builder()
+                .max_capacity(constants::MAX_MESSAGE_SOURCES)
+                .time_to_live(constants::MESSAGE_SOURCES_TTL)
+                .build(),
+            autostar_reaction_checks: MokaCache::builder()
+                .max_capacity(constants::MAX_AUTOSTAR_REACTION_CHECKS)
+                .time_to_live(constants::AUTOSTAR_REACTION_CHECKS_TTL)
+                .build(),
+            autostar_regexes: moka_cache(
+                constants::MAX_AUTOSTAR_REGEXES,
+                constants::AUTOSTAR_REGEXES_TTI,
+            ),
+            post_originals: moka_cache(
+                constants::MAX_POST_ORIGINALS,
+                constants::POST_ORIGINALS_TTI,
+            ),
+            feature_flags: MokaCache::builder()
+                .max_capacity(constants::MAX_FEATURE_FLAGS)
+                .time_to_live(constants::FEATURE_FLAGS_TTL)
+                .build(),
+            removed_emoji_warnings: MokaCache::builder()
+                .max_capacity(constants::MAX_REMOVED_EMOJI_WARNINGS)
+                .time_to_live(constants::REMOVED_EMOJI_WARNINGS_TTL)
+                .build(),
         }
     }
 
@@ -157,6 +260,23 @@ impl Cache {
         );
     }
 
+    /// Drops everything cached from the database for a guild, so it is read
+    /// again on next use.
+    pub async fn invalidate_guild(&self, guild_id: i64) {
+        self.guild_vote_emojis.remove(&guild_id);
+        self.guild_blocked_voters.remove(&guild_id);
+        self.invalidate_premium(guild_id).await;
+        self.guild_locales.remove(&guild_id);
+        self.guild_command_visibility.remove(&guild_id);
+        self.guild_ignored_sources.remove(&guild_id);
+        self.guild_log_channels.remove(&guild_id);
+    }
+
+    /// Forgets whether a guild has premium, so it is read again on next use.
+    pub async fn invalidate_premium(&self, guild_id: i64) {
+        self.guild_premium.invalidate(&guild_id).await;
+    }
+
     // helper methods
     pub fn guild_emoji_exists(&self, guild_id: Id<GuildMarker>, emoji_id: Id<EmojiMarker>) -> bool {
         self.guilds.with(&guild_id, |_, guild| {
@@ -196,6 +316,104 @@ impl Cache {
         })
     }
 
+    /// The tags of every cached forum channel in a guild, as (id, name).
+    pub fn guild_forum_tags(&self, guild_id: Id<GuildMarker>) -> Vec<(Id<TagMarker>, String)> {
+        self.guilds.with(&guild_id, |_, guild| {
+            let Some(guild) = guild else {
+                return Vec::new();
+            };
+            guild
+                .channels
+                .values()
+                .flat_map(|channel| &channel.available_tags)
+                .map(|tag| (tag.id, tag.name.clone()))
+                .collect()
+        })
+    }
+
+    /// The applied tags of a forum post, or `None` if the channel isn't a
+    /// post in a forum. Archived posts aren't cached, so they're fetched.
+    pub async fn fog_thread_tags(
+        &self,
+        bot: &StarboardBot,
+        guild_id: Id<GuildMarker>,
+        channel_id: Id<ChannelMarker>,
+    ) -> StarboardResult<Option<Vec<Id<TagMarker>>>> {
+        enum CachedTags {
+            NotAPost,
+            Tags(Vec<Id<TagMarker>>),
+            Unknown,
+        }
+
+        let cached = self.guilds.with(&guild_id, |_, guild| {
+            let Some(guild) = guild else {
+                return CachedTags::Unknown;
+            };
+            if guild.channels.contains_key(&channel_id) {
+                return CachedTags::NotAPost;
+            }
+            let Some(parent_id) = guild.active_thread_parents.get(&channel_id) else {
+                return CachedTags::Unknown;
+            };
+
+            match guild.channels.get(parent_id) {
+                Some(parent) if parent.kind == ChannelType::GuildForum => {
+                    let tags = guild.active_thread_tags.get(&channel_id);
+                    CachedTags::Tags(tags.cloned().unwrap_or_default())
+                }
+                Some(_) => CachedTags::NotAPost,
+                None => CachedTags::Unknown,
+            }
+        });
+        match cached {
+            CachedTags::NotAPost => return Ok(None),
+            CachedTags::Tags(tags) => return Ok(Some(tags)),
+            CachedTags::Unknown => (),
+        }
+
+        let thread = match bot.http.channel(channel_id).await {
+            Ok(thread) => thread.model().await?,
+            Err(why) => {
+                return match get_status(&why) {
+                    Some(403 | 404) => Ok(None),
+                    _ => Err(why.into()),
+                }
+            }
+        };
+        let Some(parent_id) = thread.parent_id.filter(|_| thread.kind.is_thread()) else {
+            return Ok(None);
+        };
+        // caches the parent, if it isn't yet
+        self.fog_parent_channel_id(bot, guild_id, parent_id).await?;
+        if !self.is_channel_forum(guild_id, parent_id) {
+            return Ok(None);
+        }
+
+        Ok(Some(thread.applied_tags.unwrap_or_default()))
+    }
+
+    /// Whether threads can be started from messages in this channel. Threads
+    /// aren't cached as channels, so this is also false for them.
+    pub fn can_channel_have_threads(
+        &self,
+        guild_id: Id<GuildMarker>,
+        channel_id: Id<ChannelMarker>,
+    ) -> bool {
+        self.guilds.with(&guild_id, |_, guild| {
+            guild
+                .as_ref()
+                .and_then(|guild| {
+                    guild.channels.get(&channel_id).map(|channel| {
+                        matches!(
+                            channel.kind,
+                            ChannelType::GuildText | ChannelType::GuildAnnouncement
+                        )
+                    })
+                })
+                .unwrap_or(false)
+        })
+    }
+
     pub async fn qualified_channel_ids(
         &self,
         bot: &StarboardBot,
@@ -210,7 +428,7 @@ impl Cache {
 
             let must_fetch = self.guilds.with(&guild_id, |_, guild| {
                 let Some(guild) = &guild else {
-                    eprintln!("Warning: no cached guild.");
+                    tracing::warn!("No cached guild.");
                     return true;
                 };
 
@@ -248,6 +466,7 @@ impl Cache {
         Ok(channel_ids)
     }
 
+    #[tracing::instrument(level = "debug", skip(self, bot))]
     pub async fn fog_user(
         &self,
         bot: &StarboardBot,
@@ -274,6 +493,7 @@ impl Cache {
         Ok(user)
     }
 
+    #[tracing::instrument(level = "debug", skip(self, bot))]
     pub async fn fog_member(
         &self,
         bot: &StarboardBot,
@@ -306,6 +526,7 @@ impl Cache {
         Ok(member)
     }
 
+    #[tracing::instrument(level = "debug", skip(self, bot))]
     pub async fn fog_webhook(
         &self,
         bot: &StarboardBot,
@@ -343,6 +564,7 @@ impl Cache {
         Ok(wh)
     }
 
+    #[tracing::instrument(level = "debug", skip(self, bot))]
     pub async fn fog_message(
         &self,
         bot: &StarboardBot,
@@ -352,6 +574,9 @@ impl Cache {
         if let Some(cached) = self.messages.get(&message_id) {
             return Ok(cached.into());
         }
+        if self.message_fetch_forbidden.contains_key(&channel_id) {
+            return Ok(MessageResult::Forbidden);
+        }
 
         let msg = bot.http.message(channel_id, message_id).await;
         let msg = match msg {
@@ -360,6 +585,7 @@ impl Cache {
                 if status == Some(404) {
                     None
                 } else if status == Some(403) {
+                    self.message_fetch_forbidden.insert(channel_id, ()).await;
                     return Ok(MessageResult::Forbidden);
                 } else {
                     return Err(why.into());
@@ -382,6 +608,31 @@ impl Cache {
         Ok(msg.into())
     }
 
+    /// Forgets that messages in the channel and its active threads couldn't
+    /// be fetched, since their permissions may have changed.
+    pub async fn clear_message_fetch_forbidden(
+        &self,
+        guild_id: Id<GuildMarker>,
+        channel_id: Id<ChannelMarker>,
+    ) {
+        let threads = self.guilds.with(&guild_id, |_, guild| {
+            let Some(guild) = guild else {
+                return Vec::new();
+            };
+            guild
+                .active_thread_parents
+                .iter()
+                .filter(|(_, &parent_id)| parent_id == channel_id)
+                .map(|(&thread_id, _)| thread_id)
+                .collect()
+        });
+
+        self.message_fetch_forbidden.invalidate(&channel_id).await;
+        for thread_id in threads {
+            self.message_fetch_forbidden.invalidate(&thread_id).await;
+        }
+    }
+
     async fn fetch_channel_or_thread_parent(
         &self,
         bot: &StarboardBot,
@@ -414,6 +665,7 @@ impl Cache {
         }
     }
 
+    #[tracing::instrument(level = "debug", skip(self, bot))]
     pub async fn fog_parent_channel_id(
         &self,
         bot: &StarboardBot,
@@ -428,7 +680,10 @@ impl Cache {
             }
 
             if let Some(parent) = guild.active_thread_parents.get(&channel_id) {
-                return Some(*parent);
+                // callers expect the parent to be cached as well
+                if guild.channels_hydrated || guild.channels.contains_key(parent) {
+                    return Some(*parent);
+                }
             }
 
             None
@@ -476,6 +731,7 @@ impl Cache {
         }))
     }
 
+    #[tracing::instrument(level = "debug", skip(self, bot))]
     pub async fn fog_channel_nsfw(
         &self,
         bot: &StarboardBot,
@@ -538,3 +794,137 @@ impl Cache {
         Ok(Some(is_nsfw))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use twilight_model::gateway::payload::incoming::{ChannelUpdate, ThreadUpdate};
+
+    use super::*;
+
+    const GUILD: u64 = 1;
+    const CHANNEL: u64 = 10;
+    const THREAD: u64 = 11;
+    const OTHER_CHANNEL: u64 = 20;
+    const OTHER_THREAD: u64 = 21;
+
+    fn channel(id: u64, kind: u8, parent_id: Option<u64>) -> Channel {
+        serde_json::from_value(serde_json::json!({
+            "id": id.to_string(),
+            "guild_id": GUILD.to_string(),
+            "type": kind,
+            "parent_id": parent_id.map(|id| id.to_string()),
+        }))
+        .unwrap()
+    }
+
+    async fn forbidden_cache() -> Cache {
+        let cache = Cache::new();
+        cache.guilds.insert(
+            Id::new(GUILD),
+            CachedGuild {
+                name: "guild".to_string(),
+                emojis: HashMap::new(),
+                channels: HashMap::new(),
+                roles: HashMap::new(),
+                active_thread_parents: HashMap::from([
+                    (Id::new(THREAD), Id::new(CHANNEL)),
+                    (Id::new(OTHER_THREAD), Id::new(OTHER_CHANNEL)),
+                ]),
+                active_thread_tags: HashMap::new(),
+                channels_hydrated: true,
+            },
+        );
+        for id in [CHANNEL, THREAD, OTHER_CHANNEL, OTHER_THREAD] {
+            cache.message_fetch_forbidden.insert(Id::new(id), ()).await;
+        }
+        cache
+    }
+
+    fn forbidden(cache: &Cache) -> Vec<u64> {
+        let mut ids: Vec<_> = [CHANNEL, THREAD, OTHER_CHANNEL, OTHER_THREAD]
+            .into_iter()
+            .filter(|&id| cache.message_fetch_forbidden.contains_key(&Id::new(id)))
+            .collect();
+        ids.sort_unstable();
+        ids
+    }
+
+    #[tokio::test]
+    async fn channel_update_clears_forbidden() {
+        let cache = forbidden_cache().await;
+        assert_eq!(
+            forbidden(&cache),
+            [CHANNEL, THREAD, OTHER_CHANNEL, OTHER_THREAD]
+        );
+
+        // the channel and its threads, but not other channels
+        ChannelUpdate(channel(CHANNEL, 0, None))
+            .update_cache(&cache)
+            .await;
+        assert_eq!(forbidden(&cache), [OTHER_CHANNEL, OTHER_THREAD]);
+
+        // a 403 in the channel marks it again
+        cache
+            .message_fetch_forbidden
+            .insert(Id::new(CHANNEL), ())
+            .await;
+        assert_eq!(forbidden(&cache), [CHANNEL, OTHER_CHANNEL, OTHER_THREAD]);
+    }
+
+    #[tokio::test]
+    async fn thread_update_clears_forbidden() {
+        let cache = forbidden_cache().await;
+
+        // only the thread, not its parent
+        ThreadUpdate(channel(OTHER_THREAD, 11, Some(OTHER_CHANNEL)))
+            .update_cache(&cache)
+            .await;
+        assert_eq!(forbidden(&cache), [CHANNEL, THREAD, OTHER_CHANNEL]);
+    }
+
+    #[tokio::test]
+    async fn clear_forbidden_unknown_guild() {
+        let cache = forbidden_cache().await;
+
+        // without the guild, its threads aren't known
+        cache
+            .clear_message_fetch_forbidden(Id::new(GUILD + 1), Id::new(CHANNEL))
+            .await;
+        assert_eq!(forbidden(&cache), [THREAD, OTHER_CHANNEL, OTHER_THREAD]);
+    }
+
+    #[tokio::test]
+    async fn premium_is_read_again_after_invalidation() {
+        let cache = Cache::new();
+        cache.guild_premium.insert(1, true).await;
+        cache.guild_premium.insert(2, true).await;
+
+        cache.invalidate_premium(1).await;
+        assert_eq!(cache.guild_premium.get(&1), None);
+        assert_eq!(cache.guild_premium.get(&2), Some(true));
+
+        // a later check caches the new value
+        cache.guild_premium.insert(1, false).await;
+        assert_eq!(cache.guild_premium.get(&1), Some(false));
+    }
+
+    #[tokio::test]
+    async fn invalidating_a_guild_drops_premium() {
+        let cache = Cache::new();
+        cache.guild_premium.insert(1, true).await;
+
+        cache.invalidate_guild(1).await;
+        assert_eq!(cache.guild_premium.get(&1), None);
+    }
+
+    #[tokio::test]
+    async fn invalidating_a_guild_drops_blocked_voters() {
+        let cache = Cache::new();
+        cache
+            .guild_blocked_voters
+            .insert(1, Arc::new(BlockedVoters::from([(2, false)])));
+
+        cache.invalidate_guild(1).await;
+        assert!(!cache.guild_blocked_voters.contains_key(&1));
+    }
+}
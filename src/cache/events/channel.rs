@@ -27,6 +27,10 @@ impl UpdateCache for ChannelDelete {
         cache.guilds.alter(&guild_id, |_, mut guild| {
             guild.channels.remove(&self.id);
 
+            let parents = &guild.active_thread_parents;
+            guild
+                .active_thread_tags
+                .retain(|thread_id, _| parents.get(thread_id) != Some(&self.id));
             guild
                 .active_thread_parents
                 .retain(|_, &mut channel_id| channel_id != self.id);
@@ -49,5 +53,7 @@ impl UpdateCache for ChannelUpdate {
 
             guild
         });
+
+        cache.clear_message_fetch_forbidden(guild_id, self.id).await;
     }
 }
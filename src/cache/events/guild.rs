@@ -3,30 +3,19 @@ use std::collections::HashMap;
 use async_trait::async_trait;
 use twilight_model::gateway::payload::incoming::{GuildCreate, GuildDelete, GuildEmojisUpdate};
 
-use crate::cache::{
-    cache_struct::Cache,
-    models::{channel::CachedChannel, guild::CachedGuild},
-    update::UpdateCache,
-};
+use crate::cache::{cache_struct::Cache, models::guild::CachedGuild, update::UpdateCache};
 
 #[async_trait]
 impl UpdateCache for GuildCreate {
     async fn update_cache(&self, cache: &Cache) {
-        let channels = self
-            .channels
-            .iter()
-            .map(|c| (c.id, CachedChannel::from_channel(None, c)))
-            .collect();
-
+        // the channels and threads are added by `hydrate`
         let guild = CachedGuild {
             name: self.name.clone(),
             emojis: self.emojis.iter().map(|e| (e.id, e.animated)).collect(),
-            channels,
-            active_thread_parents: self
-                .threads
-                .iter()
-                .map(|t| (t.id, t.parent_id.unwrap()))
-                .collect(),
+            channels: HashMap::new(),
+            active_thread_parents: HashMap::new(),
+            active_thread_tags: HashMap::new(),
+            channels_hydrated: false,
             roles: self
                 .roles
                 .iter()
@@ -47,10 +36,10 @@ impl UpdateCache for GuildDelete {
 #[async_trait]
 impl UpdateCache for GuildEmojisUpdate {
     async fn update_cache(&self, cache: &Cache) {
+        // the event has every emoji of the guild, so removed emojis are
+        // dropped here as well
         cache.guilds.alter(&self.guild_id, |_, mut guild| {
-            for emoji in &self.emojis {
-                guild.emojis.insert(emoji.id, emoji.animated);
-            }
+            guild.emojis = self.emojis.iter().map(|e| (e.id, e.animated)).collect();
             guild
         });
     }
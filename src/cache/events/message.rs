@@ -11,7 +11,8 @@ use crate::cache::{cache_struct::Cache, models::message::CachedMessage, update::
 impl UpdateCache for MessageCreate {
     async fn update_cache(&self, cache: &Cache) {
         // We only need to cache created messages if the channel is an autostar channel.
-        if !cache.autostar_channel_ids.contains(&self.channel_id) {
+        // While the set is still loading, unknown channels are cached anyway.
+        if cache.autostar_channel_ids.contains_cached(self.channel_id) == Some(false) {
             return;
         }
 
@@ -80,6 +81,10 @@ impl UpdateCache for MessageUpdate {
             content,
             stickers: cached.stickers.clone(),
             referenced_message: cached.referenced_message,
+            reply: cached.reply.clone(),
+            kind: cached.kind,
+            webhook_id: cached.webhook_id,
+            application_id: cached.application_id,
         };
 
         cache
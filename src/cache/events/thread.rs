@@ -18,6 +18,7 @@ impl UpdateCache for ThreadCreate {
 
         cache.guilds.alter(&guild_id, |_, mut guild| {
             guild.active_thread_parents.insert(self.id, parent_id);
+            guild.set_thread_tags(self.id, self.applied_tags.as_ref());
             guild
         });
     }
@@ -28,6 +29,7 @@ impl UpdateCache for ThreadDelete {
     async fn update_cache(&self, cache: &Cache) {
         cache.guilds.alter(&self.guild_id, |_, mut guild| {
             guild.active_thread_parents.remove(&self.id);
+            guild.active_thread_tags.remove(&self.id);
             guild
         });
         cache.messages.invalidate(&self.id.get().into_id()).await;
@@ -37,6 +39,9 @@ impl UpdateCache for ThreadDelete {
 #[async_trait]
 impl UpdateCache for ThreadUpdate {
     async fn update_cache(&self, cache: &Cache) {
+        // the thread's permissions may have changed
+        cache.message_fetch_forbidden.invalidate(&self.id).await;
+
         let Some(guild_id) = self.guild_id else { return; };
         let Some(thread) = &self.thread_metadata else { return; };
         let Some(parent_id) = self.parent_id else { return; };
@@ -44,8 +49,10 @@ impl UpdateCache for ThreadUpdate {
         cache.guilds.alter(&guild_id, |_, mut guild| {
             if thread.archived {
                 guild.active_thread_parents.remove(&self.id);
+                guild.active_thread_tags.remove(&self.id);
             } else {
                 guild.active_thread_parents.insert(self.id, parent_id);
+                guild.set_thread_tags(self.id, self.applied_tags.as_ref());
             }
 
             guild
@@ -63,10 +70,17 @@ impl UpdateCache for ThreadListSync {
                     .iter()
                     .map(|t| (t.id, t.parent_id.unwrap()))
                     .collect();
+                guild.active_thread_tags.clear();
             } else {
                 // ThreadListSync only syncs threads for the channels it sends,
                 // so any threads belonging to other channels should stay.
                 let channel_ids: HashSet<_> = self.channel_ids.iter().collect();
+                let parents = &guild.active_thread_parents;
+                guild.active_thread_tags.retain(|thread_id, _| {
+                    parents
+                        .get(thread_id)
+                        .map_or(true, |parent_id| !channel_ids.contains(parent_id))
+                });
                 let mut threads: HashMap<_, _> = guild
                     .active_thread_parents
                     .into_iter()
@@ -81,6 +95,9 @@ impl UpdateCache for ThreadListSync {
 
                 guild.active_thread_parents = threads;
             }
+            for thread in &self.threads {
+                guild.set_thread_tags(thread.id, thread.applied_tags.as_ref());
+            }
 
             guild
         });
@@ -0,0 +1,96 @@
+//! Channels and active threads from `GuildCreate`. Large guilds can have
+//! thousands of them, so they're added to the cached guild in chunks in the
+//! background instead of while the event is handled. Until that's done,
+//! `CachedGuild::channels_hydrated` is false and missing channels are fetched.
+
+use std::sync::Arc;
+
+use tracing::Instrument;
+use twilight_gateway::Event;
+use twilight_model::{
+    channel::Channel,
+    id::{marker::GuildMarker, Id},
+};
+
+use crate::{client::bot::StarboardBot, constants};
+
+use super::{models::channel::CachedChannel, Cache};
+
+pub struct GuildChannels {
+    guild_id: Id<GuildMarker>,
+    channels: Vec<Channel>,
+    threads: Vec<Channel>,
+}
+
+/// Moves the channels and threads out of a `GuildCreate`. Nothing that
+/// handles the event after the cache needs them.
+pub fn take_guild_channels(event: &mut Event) -> Option<GuildChannels> {
+    let Event::GuildCreate(event) = event else {
+        return None;
+    };
+
+    Some(GuildChannels {
+        guild_id: event.id,
+        channels: std::mem::take(&mut event.channels),
+        threads: std::mem::take(&mut event.threads),
+    })
+}
+
+pub fn spawn_hydrate_guild(bot: Arc<StarboardBot>, channels: GuildChannels) {
+    let task = async move {
+        hydrate_guild(&bot.cache, channels).await;
+    };
+    tokio::spawn(task.in_current_span());
+}
+
+async fn hydrate_guild(cache: &Cache, channels: GuildChannels) {
+    let GuildChannels {
+        guild_id,
+        channels,
+        threads,
+    } = channels;
+
+    for chunk in channels.chunks(constants::GUILD_HYDRATION_CHUNK_SIZE) {
+        if !cache.guilds.contains_key(&guild_id) {
+            return;
+        }
+        // channel events that arrived in the meantime are newer
+        cache.guilds.alter(&guild_id, |_, mut guild| {
+            for channel in chunk {
+                guild
+                    .channels
+                    .entry(channel.id)
+                    .or_insert_with(|| CachedChannel::from_channel(None, channel));
+            }
+            guild
+        });
+        tokio::task::yield_now().await;
+    }
+
+    for chunk in threads.chunks(constants::GUILD_HYDRATION_CHUNK_SIZE) {
+        cache.guilds.alter(&guild_id, |_, mut guild| {
+            for thread in chunk {
+                let Some(parent_id) = thread.parent_id else {
+                    continue;
+                };
+                guild
+                    .active_thread_parents
+                    .entry(thread.id)
+                    .or_insert(parent_id);
+                if let Some(tags) = thread.applied_tags.as_ref().filter(|t| !t.is_empty()) {
+                    guild
+                        .active_thread_tags
+                        .entry(thread.id)
+                        .or_insert_with(|| tags.clone());
+                }
+            }
+            guild
+        });
+        tokio::task::yield_now().await;
+    }
+
+    cache.guilds.alter(&guild_id, |_, mut guild| {
+        guild.channels_hydrated = true;
+        guild
+    });
+}
@@ -1,6 +1,11 @@
+mod autostar_channels;
 mod cache_struct;
 mod events;
+pub mod hydrate;
 pub mod models;
+mod stats;
 mod update;
 
+pub use autostar_channels::{task_load_autostar_channel_ids, AutoStarChannelIds};
 pub use cache_struct::{Cache, MessageResult};
+pub use stats::{loop_log_cache_stats, CacheStats};
@@ -1,27 +1,49 @@
 use twilight_model::{
     channel::{Channel, ChannelType},
-    id::{marker::ChannelMarker, Id},
+    id::{
+        marker::{ChannelMarker, TagMarker},
+        Id,
+    },
 };
 
+pub struct CachedForumTag {
+    pub id: Id<TagMarker>,
+    pub name: String,
+}
+
 pub struct CachedChannel {
     pub is_nsfw: Option<bool>,
     pub parent_id: Option<Id<ChannelMarker>>,
     pub kind: ChannelType,
+    /// the tags posts can have, for forum channels
+    pub available_tags: Vec<CachedForumTag>,
 }
 
 impl CachedChannel {
     pub fn from_channel(original: Option<&CachedChannel>, new: &Channel) -> Self {
+        let available_tags = new
+            .available_tags
+            .iter()
+            .flatten()
+            .map(|tag| CachedForumTag {
+                id: tag.id,
+                name: tag.name.clone(),
+            })
+            .collect();
+
         if let Some(original) = original {
             Self {
                 is_nsfw: new.nsfw.or(original.is_nsfw),
                 parent_id: new.parent_id,
                 kind: new.kind,
+                available_tags,
             }
         } else {
             Self {
                 is_nsfw: new.nsfw,
                 parent_id: new.parent_id,
                 kind: new.kind,
+                available_tags,
             }
         }
     }
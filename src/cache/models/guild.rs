@@ -1,7 +1,7 @@
 use std::collections::HashMap;
 
 use twilight_model::id::{
-    marker::{ChannelMarker, EmojiMarker, RoleMarker},
+    marker::{ChannelMarker, EmojiMarker, RoleMarker, TagMarker},
     Id,
 };
 
@@ -15,4 +15,22 @@ pub struct CachedGuild {
     pub channels: HashMap<Id<ChannelMarker>, CachedChannel>,
     pub roles: HashMap<Id<RoleMarker>, CachedRole>,
     pub active_thread_parents: HashMap<Id<ChannelMarker>, Id<ChannelMarker>>,
+    /// the applied tags of active forum posts that have any
+    pub active_thread_tags: HashMap<Id<ChannelMarker>, Vec<Id<TagMarker>>>,
+    /// false while the channels and threads from `GuildCreate` are still being
+    /// added, so a missing channel may still exist
+    pub channels_hydrated: bool,
+}
+
+impl CachedGuild {
+    pub fn set_thread_tags(
+        &mut self,
+        thread_id: Id<ChannelMarker>,
+        tags: Option<&Vec<Id<TagMarker>>>,
+    ) {
+        match tags.filter(|tags| !tags.is_empty()) {
+            Some(tags) => self.active_thread_tags.insert(thread_id, tags.clone()),
+            None => self.active_thread_tags.remove(&thread_id),
+        };
+    }
 }
@@ -1,21 +1,22 @@
 use twilight_model::{
     channel::{
-        message::{embed::Embed, sticker::MessageSticker},
+        message::{embed::Embed, sticker::MessageSticker, MessageType},
         Attachment, Message,
     },
     id::{
-        marker::{MessageMarker, UserMarker},
+        marker::{ApplicationMarker, MessageMarker, UserMarker, WebhookMarker},
         Id,
     },
     user::User,
     util::ImageHash,
 };
 
-use crate::utils::system_content::SystemContent;
+use crate::{constants, utils::system_content::SystemContent};
 
 #[derive(Clone)]
 pub struct CachedMessageAuthor {
     pub name: String,
+    pub global_name: Option<String>,
     pub avatar: Option<ImageHash>,
 }
 
@@ -23,6 +24,7 @@ impl From<User> for CachedMessageAuthor {
     fn from(user: User) -> Self {
         Self {
             name: user.name,
+            global_name: user.global_name,
             avatar: user.avatar,
         }
     }
@@ -32,11 +34,46 @@ impl From<&User> for CachedMessageAuthor {
     fn from(user: &User) -> Self {
         Self {
             name: user.name.clone(),
+            global_name: user.global_name.clone(),
             avatar: user.avatar,
         }
     }
 }
 
+/// The message that a message replied to, so that the reply can be shown
+/// without fetching it, and even after it was deleted.
+#[derive(Clone)]
+pub struct CachedReply {
+    pub author_id: Id<UserMarker>,
+    pub author_name: String,
+    /// The start of the content, capped at `MAX_REPLY_SNIPPET_LENGTH`.
+    pub snippet: String,
+}
+
+impl From<&Message> for CachedReply {
+    fn from(msg: &Message) -> Self {
+        let content = msg.system_content();
+        let mut snippet: String = content
+            .chars()
+            .take(constants::MAX_REPLY_SNIPPET_LENGTH)
+            .map(|c| if c == '\n' { ' ' } else { c })
+            .collect();
+        if snippet.len() < content.len() {
+            snippet.push_str("...");
+        }
+
+        Self {
+            author_id: msg.author.id,
+            author_name: msg
+                .author
+                .global_name
+                .clone()
+                .unwrap_or_else(|| msg.author.name.clone()),
+            snippet,
+        }
+    }
+}
+
 pub struct CachedMessage {
     pub author_id: Id<UserMarker>,
     pub author: CachedMessageAuthor,
@@ -45,6 +82,10 @@ pub struct CachedMessage {
     pub stickers: Vec<MessageSticker>,
     pub embeds: Vec<Embed>,
     pub referenced_message: Option<Id<MessageMarker>>,
+    pub reply: Option<CachedReply>,
+    pub kind: MessageType,
+    pub webhook_id: Option<Id<WebhookMarker>>,
+    pub application_id: Option<Id<ApplicationMarker>>,
 }
 
 impl From<Message> for CachedMessage {
@@ -58,6 +99,10 @@ impl From<Message> for CachedMessage {
             content,
             stickers: msg.sticker_items,
             referenced_message: msg.reference.as_ref().and_then(|r| r.message_id),
+            reply: msg.referenced_message.as_deref().map(CachedReply::from),
+            kind: msg.kind,
+            webhook_id: msg.webhook_id,
+            application_id: msg.application_id,
         }
     }
 }
@@ -72,6 +117,106 @@ impl From<&Message> for CachedMessage {
             content: msg.system_content(),
             stickers: msg.sticker_items.clone(),
             referenced_message: msg.reference.as_ref().and_then(|r| r.message_id),
+            reply: msg.referenced_message.as_deref().map(CachedReply::from),
+            kind: msg.kind,
+            webhook_id: msg.webhook_id,
+            application_id: msg.application_id,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message(kind: u8) -> Message {
+        serde_json::from_value(serde_json::json!({
+            "id": "2",
+            "channel_id": "3",
+            "author": {
+                "id": "4",
+                "username": "user",
+                "discriminator": "0",
+                "avatar": null,
+            },
+            "content": "hello",
+            "timestamp": "2023-06-07T12:00:00.000000+00:00",
+            "edited_timestamp": null,
+            "tts": false,
+            "mention_everyone": false,
+            "mentions": [],
+            "mention_roles": [],
+            "attachments": [],
+            "embeds": [],
+            "pinned": false,
+            "type": kind,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn kind_from_gateway() {
+        let msg = message(7);
+        let cached = CachedMessage::from(&msg);
+
+        assert_eq!(cached.kind, MessageType::UserJoin);
+    }
+
+    #[test]
+    fn kind_from_rest() {
+        let cached = CachedMessage::from(message(19));
+
+        assert_eq!(cached.kind, MessageType::Reply);
+    }
+
+    #[test]
+    fn calls_can_be_cached() {
+        let cached = CachedMessage::from(message(3));
+
+        assert_eq!(cached.kind, MessageType::Call);
+        assert_eq!(cached.content, "**user** started a call.");
+    }
+
+    fn reply_to(content: &str, global_name: Option<&str>) -> Message {
+        let mut replied_to = serde_json::to_value(message(0)).unwrap();
+        replied_to["content"] = content.into();
+        replied_to["author"]["global_name"] = global_name.into();
+
+        let mut msg = serde_json::to_value(message(19)).unwrap();
+        msg["referenced_message"] = replied_to;
+        serde_json::from_value(msg).unwrap()
+    }
+
+    #[test]
+    fn keeps_reply() {
+        let cached = CachedMessage::from(reply_to("first\nsecond", Some("Name")));
+        let reply = cached.reply.unwrap();
+
+        assert_eq!(reply.author_id, Id::new(4));
+        assert_eq!(reply.author_name, "Name");
+        assert_eq!(reply.snippet, "first second");
+    }
+
+    #[test]
+    fn reply_falls_back_to_username() {
+        let cached = CachedMessage::from(&reply_to("hi", None));
+
+        assert_eq!(cached.reply.unwrap().author_name, "user");
+    }
+
+    #[test]
+    fn reply_snippet_is_capped() {
+        let max = constants::MAX_REPLY_SNIPPET_LENGTH;
+        let content = "é".repeat(max);
+        let cached = CachedMessage::from(reply_to(&content, None));
+        assert_eq!(cached.reply.unwrap().snippet, content);
+
+        let cached = CachedMessage::from(reply_to(&format!("{content}a"), None));
+        assert_eq!(cached.reply.unwrap().snippet, format!("{content}..."));
+    }
+
+    #[test]
+    fn no_reply() {
+        assert!(CachedMessage::from(message(0)).reply.is_none());
+    }
+}
@@ -0,0 +1,147 @@
+//! Entry counts for the cache, for debugging memory usage.
+
+use std::{cmp::Reverse, fmt, sync::Arc};
+
+use cached::Cached;
+use moka::future::Cache as MokaCache;
+use twilight_model::id::{marker::GuildMarker, Id};
+
+use crate::{client::bot::StarboardBot, constants};
+
+use super::Cache;
+
+pub struct MokaStats {
+    pub entries: u64,
+    pub weighted_size: u64,
+}
+
+impl MokaStats {
+    fn new<K, V>(cache: &MokaCache<K, V>) -> Self
+    where
+        K: std::hash::Hash + Eq + Send + Sync + 'static,
+        V: Clone + Send + Sync + 'static,
+    {
+        Self {
+            entries: cache.entry_count(),
+            weighted_size: cache.weighted_size(),
+        }
+    }
+}
+
+impl fmt::Display for MokaStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} (weighted {})", self.entries, self.weighted_size)
+    }
+}
+
+pub struct GuildStats {
+    pub guild_id: Id<GuildMarker>,
+    pub name: String,
+    pub channels: usize,
+    pub emojis: usize,
+}
+
+pub struct CacheStats {
+    pub guilds: usize,
+    pub channels: usize,
+    pub emojis: usize,
+    /// The guilds with the most cached channels, largest first.
+    pub largest_guilds: Vec<GuildStats>,
+    pub webhooks: usize,
+    pub messages: MokaStats,
+    pub users: MokaStats,
+    pub members: MokaStats,
+    pub responses: MokaStats,
+    pub auto_deleted_posts: usize,
+    pub translations: MokaStats,
+    pub self_removed_reactions: MokaStats,
+    pub autostar_channel_ids: usize,
+    pub guild_vote_emojis: usize,
+    pub guild_blocked_voters: usize,
+    pub guild_premium: MokaStats,
+}
+
+impl Cache {
+    pub async fn stats(&self) -> CacheStats {
+        let (channels, emojis, mut largest_guilds) = self.guilds.fold(
+            (0, 0, Vec::new()),
+            |(channels, emojis, mut all), id, guild| {
+                all.push(GuildStats {
+                    guild_id: *id,
+                    name: guild.name.clone(),
+                    channels: guild.channels.len(),
+                    emojis: guild.emojis.len(),
+                });
+                (
+                    channels + guild.channels.len(),
+                    emojis + guild.emojis.len(),
+                    all,
+                )
+            },
+        );
+        largest_guilds.sort_by_key(|g| Reverse(g.channels));
+        largest_guilds.truncate(constants::CACHE_STATS_LARGEST_GUILDS);
+
+        CacheStats {
+            guilds: self.guilds.len(),
+            channels,
+            emojis,
+            largest_guilds,
+            webhooks: self.webhooks.len(),
+            messages: MokaStats::new(&self.messages),
+            users: MokaStats::new(&self.users),
+            members: MokaStats::new(&self.members),
+            responses: MokaStats::new(&self.responses),
+            auto_deleted_posts: self.auto_deleted_posts.read().await.cache_size(),
+            translations: MokaStats::new(&self.translations),
+            self_removed_reactions: MokaStats::new(&self.self_removed_reactions),
+            autostar_channel_ids: self.autostar_channel_ids.len(),
+            guild_vote_emojis: self.guild_vote_emojis.len(),
+            guild_blocked_voters: self.guild_blocked_voters.len(),
+            guild_premium: MokaStats::new(&self.guild_premium),
+        }
+    }
+}
+
+impl fmt::Display for CacheStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "guilds: {} ({} channels, {} emojis)",
+            self.guilds, self.channels, self.emojis
+        )?;
+        writeln!(f, "webhooks: {}", self.webhooks)?;
+        writeln!(f, "messages: {}", self.messages)?;
+        writeln!(f, "users: {}", self.users)?;
+        writeln!(f, "members: {}", self.members)?;
+        writeln!(f, "responses: {}", self.responses)?;
+        writeln!(f, "auto_deleted_posts: {}", self.auto_deleted_posts)?;
+        writeln!(f, "translations: {}", self.translations)?;
+        writeln!(f, "self_removed_reactions: {}", self.self_removed_reactions)?;
+        writeln!(f, "autostar_channel_ids: {}", self.autostar_channel_ids)?;
+        writeln!(f, "guild_vote_emojis: {}", self.guild_vote_emojis)?;
+        writeln!(f, "guild_blocked_voters: {}", self.guild_blocked_voters)?;
+        writeln!(f, "guild_premium: {}", self.guild_premium)?;
+
+        if !self.largest_guilds.is_empty() {
+            writeln!(f, "largest guilds:")?;
+        }
+        for guild in &self.largest_guilds {
+            writeln!(
+                f,
+                "- {} ({}): {} channels, {} emojis",
+                guild.name, guild.guild_id, guild.channels, guild.emojis
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Logs the cache stats periodically. Only used in development.
+pub async fn loop_log_cache_stats(bot: Arc<StarboardBot>) {
+    loop {
+        tokio::time::sleep(constants::CACHE_STATS_LOG_INTERVAL).await;
+        tracing::info!("Cache stats:\n{}", bot.cache.stats().await);
+    }
+}
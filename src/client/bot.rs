@@ -1,4 +1,8 @@
-use std::{fmt::Debug, sync::Arc, time::Duration};
+use std::{
+    fmt::Debug,
+    sync::{atomic::AtomicBool, Arc},
+    time::Duration,
+};
 
 use chrono::{DateTime, Utc};
 use futures::Future;
@@ -6,21 +10,37 @@ use sqlx::PgPool;
 use tokio::sync::RwLock;
 use twilight_gateway::{Config as GatewayConfig, Intents};
 use twilight_http::client::{Client as HttpClient, InteractionClient};
-use twilight_model::{
-    http::attachment::Attachment,
-    id::{marker::ChannelMarker, Id},
-    oauth::PartialApplication,
-};
+use twilight_model::{http::attachment::Attachment, oauth::PartialApplication};
 use twilight_standby::Standby;
 
 use crate::{
     cache::Cache,
-    client::config::Config,
+    client::{config::Config, metrics::Metrics},
+    core::{
+        discord_limits::LimitStats,
+        guild_log::GuildLogger,
+        starboard::{
+            emoji_usage::EmojiUsageCounter, refresh_stats::RefreshStats, vote_batcher::VoteBatcher,
+        },
+        translate::{translator_from_config, Translator},
+    },
     errors::{StarboardError, StarboardResult},
+    events::queue::EventQueue,
+    locale::{guild_locale, translate, Args},
     utils::into_id::IntoId,
 };
 
-use super::{cooldowns::Cooldowns, locks::Locks};
+use super::{cooldowns::Cooldowns, locks::Locks, shards::ShardState};
+
+pub fn build_http(config: &Config) -> HttpClient {
+    let mut http = HttpClient::builder()
+        .token(config.token.clone())
+        .timeout(Duration::from_secs(30));
+    if let Some(proxy) = &config.proxy {
+        http = http.proxy(proxy.to_owned(), true);
+    }
+    http.build()
+}
 
 pub struct StarboardBot {
     pub http: HttpClient,
@@ -34,6 +54,18 @@ pub struct StarboardBot {
     pub cooldowns: Cooldowns,
     pub locks: Locks,
     pub start: DateTime<Utc>,
+    pub translator: Box<dyn Translator>,
+    /// Set once the bot starts shutting down, so long-running jobs can stop
+    /// early.
+    pub shutting_down: AtomicBool,
+    pub shards: ShardState,
+    pub events: EventQueue,
+    pub refresh_stats: RefreshStats,
+    pub limit_stats: LimitStats,
+    pub vote_batcher: VoteBatcher,
+    pub emoji_usage: EmojiUsageCounter,
+    pub metrics: Metrics,
+    pub guild_log: GuildLogger,
 }
 
 impl Debug for StarboardBot {
@@ -56,13 +88,7 @@ impl StarboardBot {
         let gw_config = GatewayConfig::new(config.token.clone(), intents);
 
         // Setup HTTP connection
-        let mut http = HttpClient::builder()
-            .token(config.token.clone())
-            .timeout(Duration::from_secs(30));
-        if let Some(proxy) = &config.proxy {
-            http = http.proxy(proxy.to_owned(), true);
-        }
-        let http = http.build();
+        let http = build_http(&config);
 
         // Setup database connection
         let pool = PgPool::connect(&config.db_url).await?;
@@ -73,21 +99,11 @@ impl StarboardBot {
             .await
             .expect("failed to run migrations");
 
-        // load autostar channels
-        let asc: Vec<_> = sqlx::query!(
-            "SELECT DISTINCT channel_id FROM autostar_channels WHERE premium_locked=false"
-        )
-        .fetch_all(&pool)
-        .await?
-        .into_iter()
-        .map(|rec| Id::<ChannelMarker>::new(rec.channel_id as u64))
-        .collect();
+        // Setup cache. Autostar channels are loaded once the bot is running
+        // (see `task_load_autostar_channel_ids`).
+        let cache = Cache::new();
 
-        let mut map = dashmap::DashSet::new();
-        map.extend(asc);
-
-        // Setup cache
-        let cache = Cache::new(map);
+        let translator = translator_from_config(&config);
 
         // Return the bot struct
         Ok(Self {
@@ -102,9 +118,28 @@ impl StarboardBot {
             locks: Locks::new(),
             reqwest: reqwest::Client::new(),
             start: Utc::now(),
+            translator,
+            shutting_down: AtomicBool::new(false),
+            shards: ShardState::new(),
+            events: EventQueue::new(),
+            refresh_stats: RefreshStats::default(),
+            limit_stats: LimitStats::default(),
+            vote_batcher: VoteBatcher::default(),
+            emoji_usage: EmojiUsageCounter::default(),
+            metrics: Metrics::default(),
+            guild_log: GuildLogger::default(),
         })
     }
 
+    /// Whether background work for the guild should run in this process.
+    /// Unlike `Config::owns_guild`, this excludes draining shards.
+    pub fn owns_guild(&self, guild_id: u64) -> bool {
+        self.config.owns_guild(guild_id)
+            && !self
+                .shards
+                .is_draining(self.config.shard_for_guild(guild_id))
+    }
+
     pub async fn interaction_client(&self) -> InteractionClient {
         match &*self.application.read().await {
             Some(info) => self.http.interaction(info.id),
@@ -112,17 +147,14 @@ impl StarboardBot {
         }
     }
 
-    pub async fn handle_error(&self, err: &StarboardError) {
-        sentry::capture_error(err);
-
-        let msg = format!("{err:#?}").trim().to_string();
-        let msg = if msg.is_empty() {
-            "Some Error".to_string()
-        } else {
-            msg
-        };
+    /// Looks up a message in the guild's locale.
+    pub async fn t(&self, guild_id: i64, key: &str, args: Args<'_>) -> StarboardResult<String> {
+        let locale = guild_locale(self, guild_id).await?;
+        Ok(translate(locale, key, args))
+    }
 
-        eprintln!("{msg}");
+    pub async fn handle_error(&self, err: &StarboardError) {
+        let msg = self.log_error(err);
 
         let attachment = Attachment::from_bytes("erorr.rs".into(), msg.bytes().collect(), 1);
         let attachments = &[attachment];
@@ -134,14 +166,30 @@ impl StarboardBot {
                 .attachments(attachments);
             let ret = match ret {
                 Ok(ret) => ret,
-                Err(why) => return eprintln!("{why}"),
+                Err(why) => return tracing::error!("{why}"),
             };
             if let Err(why) = ret.await {
-                eprintln!("{why}");
+                tracing::error!("{why}");
             }
         }
     }
 
+    /// Reports the error to sentry and the logs, but not to the error
+    /// channel. Returns the formatted error.
+    pub fn log_error(&self, err: &StarboardError) -> String {
+        sentry::capture_error(err);
+
+        let msg = format!("{err:#?}").trim().to_string();
+        let msg = if msg.is_empty() {
+            "Some Error".to_string()
+        } else {
+            msg
+        };
+
+        tracing::error!("{msg}");
+        msg
+    }
+
     pub async fn catch_future_errors<T, E: Into<StarboardError>>(
         bot: Arc<StarboardBot>,
         future: impl Future<Output = Result<T, E>>,
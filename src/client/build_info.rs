@@ -0,0 +1,38 @@
+//! What was deployed, captured at compile time by `build.rs`.
+
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
+/// The short commit hash, or "unknown" if it wasn't available.
+pub const GIT_HASH: &str = env!("STARBOARD_GIT_HASH");
+pub const RUSTC_VERSION: &str = env!("STARBOARD_RUSTC_VERSION");
+const BUILT_AT: &str = env!("STARBOARD_BUILT_AT");
+
+/// When the binary was built, in seconds since the unix epoch.
+pub fn built_at() -> i64 {
+    BUILT_AT.parse().unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn git_hash() {
+        // short, even when the full hash is passed with `GIT_HASH`
+        assert!(!GIT_HASH.is_empty());
+        assert!(GIT_HASH.len() <= 10);
+        assert!(GIT_HASH == "unknown" || GIT_HASH.chars().all(|c| c.is_ascii_hexdigit()));
+    }
+
+    #[test]
+    fn compiler_and_build_time() {
+        assert!(RUSTC_VERSION == "unknown" || RUSTC_VERSION.starts_with("rustc "));
+
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs() as i64;
+        // 2023-01-01
+        assert!(built_at() > 1_672_531_200);
+        assert!(built_at() <= now);
+    }
+}
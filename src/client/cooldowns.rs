@@ -19,7 +19,11 @@ pub struct Cooldowns {
     pub starboard_custom_cooldown: DynamicMapping<(Id<UserMarker>, i32)>,
     pub message_edit: FixedMapping<Id<ChannelMarker>>,
     pub xp_refresh: FixedMapping<(Id<UserMarker>, Id<GuildMarker>)>,
-    pub vote_recount: FixedMapping<Id<GuildMarker>>,
+    pub vote_recount: FixedMapping<Id<ChannelMarker>>,
+    pub translate: FixedMapping<Id<UserMarker>>,
+    pub reaction_remove: FixedMapping<Id<ChannelMarker>>,
+    pub inspect: FixedMapping<Id<UserMarker>>,
+    pub guild_log: FixedMapping<Id<GuildMarker>>,
 }
 
 impl Cooldowns {
@@ -38,6 +42,11 @@ impl Cooldowns {
         let message_edit = FixedMapping::new(constants::MESSAGE_EDIT.0, constants::MESSAGE_EDIT.1);
         let xp_refresh = FixedMapping::new(constants::XP_REFRESH.0, constants::XP_REFRESH.1);
         let vote_recount = FixedMapping::new(constants::VOTE_RECOUNT.0, constants::VOTE_RECOUNT.1);
+        let translate = FixedMapping::new(constants::TRANSLATE.0, constants::TRANSLATE.1);
+        let reaction_remove =
+            FixedMapping::new(constants::REACTION_REMOVE.0, constants::REACTION_REMOVE.1);
+        let inspect = FixedMapping::new(constants::INSPECT.0, constants::INSPECT.1);
+        let guild_log = FixedMapping::new(constants::GUILD_LOG.0, constants::GUILD_LOG.1);
 
         Self {
             cycle_period,
@@ -47,6 +56,10 @@ impl Cooldowns {
             message_edit,
             xp_refresh,
             vote_recount,
+            translate,
+            reaction_remove,
+            inspect,
+            guild_log,
         }
     }
 
@@ -62,6 +75,10 @@ impl Cooldowns {
                 cooldown.message_edit.cycle();
                 cooldown.xp_refresh.cycle();
                 cooldown.vote_recount.cycle();
+                cooldown.translate.cycle();
+                cooldown.reaction_remove.cycle();
+                cooldown.inspect.cycle();
+                cooldown.guild_log.cycle();
             }
         });
     }
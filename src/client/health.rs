@@ -0,0 +1,295 @@
+//! A small HTTP server for liveness and readiness probes, started when
+//! `HEALTH_PORT` is set:
+//! - `/healthz`: the process is running.
+//! - `/readyz`: every shard of this process is connected and the database is
+//!   reachable.
+//! - `/metrics`: event and command counts, command errors and latencies, and
+//!   cache sizes, in the Prometheus text format.
+
+use std::{
+    convert::Infallible,
+    fmt::Write,
+    net::SocketAddr,
+    sync::{atomic::Ordering, Arc},
+    time::Instant,
+};
+
+use chrono::Utc;
+use hyper::{
+    service::{make_service_fn, service_fn},
+    Body, Method, Request, Response, Server, StatusCode,
+};
+use tokio::sync::Mutex;
+
+use crate::{
+    client::{bot::StarboardBot, metrics::CommandMetrics},
+    constants,
+};
+
+/// The result of the last `SELECT 1`, so that probes don't hit the database
+/// more than once per `HEALTH_DB_CHECK_INTERVAL`.
+#[derive(Default)]
+struct DbCheck {
+    last: Mutex<Option<(Instant, bool)>>,
+}
+
+impl DbCheck {
+    async fn is_reachable(&self, bot: &StarboardBot) -> bool {
+        let mut last = self.last.lock().await;
+        if let Some((at, ok)) = *last {
+            if at.elapsed() < constants::HEALTH_DB_CHECK_INTERVAL {
+                return ok;
+            }
+        }
+
+        let ok = sqlx::query("SELECT 1").execute(&bot.pool).await.is_ok();
+        *last = Some((Instant::now(), ok));
+        ok
+    }
+}
+
+pub async fn serve(bot: Arc<StarboardBot>, port: u16) {
+    let db_check = Arc::new(DbCheck::default());
+    let bot_clone = bot.clone();
+    let make_service = make_service_fn(move |_| {
+        let bot = bot_clone.clone();
+        let db_check = db_check.clone();
+        async move {
+            Ok::<_, Infallible>(service_fn(move |req| {
+                handle(bot.clone(), db_check.clone(), req)
+            }))
+        }
+    });
+
+    let addr = SocketAddr::from(([0, 0, 0, 0], port));
+    let server = match Server::try_bind(&addr) {
+        Ok(server) => server,
+        Err(why) => {
+            tracing::error!("Failed to start the health server on {addr}: {why}");
+            return;
+        }
+    };
+    tracing::info!("Health server listening on {addr}.");
+
+    let shutdown = async move {
+        while !bot.shutting_down.load(Ordering::Relaxed) {
+            tokio::time::sleep(constants::HEALTH_SHUTDOWN_POLL).await;
+        }
+    };
+    let ret = server
+        .serve(make_service)
+        .with_graceful_shutdown(shutdown)
+        .await;
+    if let Err(why) = ret {
+        tracing::error!("Health server failed: {why}");
+    }
+}
+
+async fn handle(
+    bot: Arc<StarboardBot>,
+    db_check: Arc<DbCheck>,
+    req: Request<Body>,
+) -> Result<Response<Body>, Infallible> {
+    if req.method() != Method::GET {
+        return Ok(respond(
+            StatusCode::METHOD_NOT_ALLOWED,
+            "method not allowed",
+        ));
+    }
+
+    let resp = match req.uri().path() {
+        "/healthz" => respond(StatusCode::OK, "ok"),
+        "/readyz" => {
+            let not_ready = not_ready(
+                bot.shutting_down.load(Ordering::Relaxed),
+                bot.shards.connected(),
+                bot.config.shards as usize,
+            );
+            if let Some(why) = not_ready {
+                respond(StatusCode::SERVICE_UNAVAILABLE, &why)
+            } else if !db_check.is_reachable(&bot).await {
+                respond(StatusCode::SERVICE_UNAVAILABLE, "database unreachable")
+            } else {
+                respond(StatusCode::OK, "ok")
+            }
+        }
+        "/metrics" => respond(StatusCode::OK, &metrics(&bot).await),
+        _ => respond(StatusCode::NOT_FOUND, "not found"),
+    };
+
+    Ok(resp)
+}
+
+/// Why the process isn't ready, apart from the database.
+fn not_ready(shutting_down: bool, connected: usize, expected: usize) -> Option<String> {
+    if shutting_down {
+        Some("shutting down".to_string())
+    } else if connected < expected {
+        Some(format!("{connected}/{expected} shards connected"))
+    } else {
+        None
+    }
+}
+
+fn respond(status: StatusCode, body: &str) -> Response<Body> {
+    let mut resp = Response::new(Body::from(body.to_string()));
+    *resp.status_mut() = status;
+    resp
+}
+
+async fn metrics(bot: &StarboardBot) -> String {
+    let mut out = String::new();
+
+    writeln!(out, "# TYPE starboard_uptime_seconds gauge").unwrap();
+    writeln!(
+        out,
+        "starboard_uptime_seconds {}",
+        (Utc::now() - bot.start).num_seconds()
+    )
+    .unwrap();
+
+    writeln!(out, "# TYPE starboard_shards_connected gauge").unwrap();
+    writeln!(out, "starboard_shards_connected {}", bot.shards.connected()).unwrap();
+
+    writeln!(out, "# TYPE starboard_events_total counter").unwrap();
+    for (kind, count) in bot.metrics.events() {
+        writeln!(out, "starboard_events_total{{type=\"{kind}\"}} {count}").unwrap();
+    }
+
+    write_commands(&mut out, &bot.metrics.commands());
+
+    let stats = bot.cache.stats().await;
+    let caches = [
+        ("guilds", stats.guilds as u64),
+        ("channels", stats.channels as u64),
+        ("emojis", stats.emojis as u64),
+        ("webhooks", stats.webhooks as u64),
+        ("messages", stats.messages.entries),
+        ("users", stats.users.entries),
+        ("members", stats.members.entries),
+        ("responses", stats.responses.entries),
+        ("auto_deleted_posts", stats.auto_deleted_posts as u64),
+        ("translations", stats.translations.entries),
+        (
+            "self_removed_reactions",
+            stats.self_removed_reactions.entries,
+        ),
+        ("autostar_channel_ids", stats.autostar_channel_ids as u64),
+        ("guild_vote_emojis", stats.guild_vote_emojis as u64),
+        ("guild_blocked_voters", stats.guild_blocked_voters as u64),
+        ("guild_premium", stats.guild_premium.entries),
+    ];
+    writeln!(out, "# TYPE starboard_cache_entries gauge").unwrap();
+    for (cache, entries) in caches {
+        writeln!(
+            out,
+            "starboard_cache_entries{{cache=\"{cache}\"}} {entries}"
+        )
+        .unwrap();
+    }
+
+    out
+}
+
+/// The command counters and latency histograms.
+fn write_commands(out: &mut String, commands: &[(String, CommandMetrics)]) {
+    writeln!(out, "# TYPE starboard_commands_total counter").unwrap();
+    for (command, metrics) in commands {
+        writeln!(
+            out,
+            "starboard_commands_total{{command=\"{command}\"}} {}",
+            metrics.calls
+        )
+        .unwrap();
+    }
+
+    writeln!(out, "# TYPE starboard_command_errors_total counter").unwrap();
+    for (command, metrics) in commands {
+        writeln!(
+            out,
+            "starboard_command_errors_total{{command=\"{command}\"}} {}",
+            metrics.errors
+        )
+        .unwrap();
+    }
+
+    writeln!(out, "# TYPE starboard_command_duration_seconds histogram").unwrap();
+    for (command, metrics) in commands {
+        let mut seen = 0;
+        for (idx, count) in metrics.latency_buckets.iter().enumerate() {
+            seen += count;
+            let le = match constants::COMMAND_LATENCY_BUCKETS_MS.get(idx) {
+                Some(&bound) => (bound as f64 / 1_000.0).to_string(),
+                None => "+Inf".to_string(),
+            };
+            writeln!(
+                out,
+                "starboard_command_duration_seconds_bucket{{command=\"{command}\",le=\"{le}\"}} \
+                {seen}"
+            )
+            .unwrap();
+        }
+        writeln!(
+            out,
+            "starboard_command_duration_seconds_sum{{command=\"{command}\"}} {}",
+            metrics.latency_sum_ms as f64 / 1_000.0
+        )
+        .unwrap();
+        writeln!(
+            out,
+            "starboard_command_duration_seconds_count{{command=\"{command}\"}} {}",
+            metrics.calls
+        )
+        .unwrap();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ready() {
+        assert_eq!(not_ready(false, 4, 4), None);
+    }
+
+    #[test]
+    fn waits_for_shards() {
+        assert_eq!(
+            not_ready(false, 3, 4).as_deref(),
+            Some("3/4 shards connected")
+        );
+    }
+
+    #[test]
+    fn not_ready_while_shutting_down() {
+        assert_eq!(not_ready(true, 4, 4).as_deref(), Some("shutting down"));
+    }
+
+    #[test]
+    fn command_histograms() {
+        let mut metrics = CommandMetrics::default();
+        metrics.calls = 3;
+        metrics.errors = 1;
+        metrics.latency_sum_ms = 1_500;
+        metrics.latency_buckets[0] = 1;
+        metrics.latency_buckets[4] = 2;
+        let mut out = String::new();
+        write_commands(&mut out, &[("random".to_string(), metrics)]);
+
+        let lines: Vec<_> = out.lines().collect();
+        assert!(lines.contains(&r#"starboard_commands_total{command="random"} 3"#));
+        assert!(lines.contains(&r#"starboard_command_errors_total{command="random"} 1"#));
+        // buckets are cumulative
+        let bucket = |le: &str, count: u64| {
+            format!(
+                r#"starboard_command_duration_seconds_bucket{{command="random",le="{le}"}} {count}"#
+            )
+        };
+        assert!(lines.contains(&bucket("0.05", 1).as_str()));
+        assert!(lines.contains(&bucket("0.5", 1).as_str()));
+        assert!(lines.contains(&bucket("1", 3).as_str()));
+        assert!(lines.contains(&bucket("+Inf", 3).as_str()));
+        assert!(lines.contains(&r#"starboard_command_duration_seconds_sum{command="random"} 1.5"#));
+    }
+}
@@ -1,3 +1,4 @@
+use dashmap::DashSet;
 use twilight_model::id::{marker::MessageMarker, Id};
 
 use crate::utils::dashset_lock::DashSetLock;
@@ -5,8 +6,12 @@ use crate::utils::dashset_lock::DashSetLock;
 #[derive(Default)]
 pub struct Locks {
     pub post_update_lock: DashSetLock<i64>,
+    /// Messages that were refreshed while `post_update_lock` was held.
+    pub post_update_pending: DashSet<i64>,
     pub guild_pr_update: DashSetLock<i64>,
     pub vote_recount: DashSetLock<Id<MessageMarker>>,
+    pub starboard_migrate: DashSetLock<i64>,
+    pub user_purge: DashSetLock<i64>,
 }
 
 impl Locks {
@@ -0,0 +1,297 @@
+//! Counts of the events and commands handled since the bot started, for the
+//! `/metrics` endpoint of `client::health` and `star debug commands`.
+
+use std::{
+    collections::VecDeque,
+    sync::atomic::{AtomicU64, Ordering},
+    time::{Duration, Instant},
+};
+
+use dashmap::DashMap;
+use twilight_gateway::{Event, EventType};
+
+use crate::{constants, core::post_latency::Quantile};
+
+#[derive(Default)]
+pub struct Metrics {
+    events: DashMap<EventType, AtomicU64>,
+    commands: DashMap<String, CommandMetrics>,
+}
+
+#[derive(Clone, Default)]
+pub struct CommandMetrics {
+    pub calls: u64,
+    pub errors: u64,
+    /// Calls per `COMMAND_LATENCY_BUCKETS_MS` bucket, plus one for slower
+    /// calls.
+    pub latency_buckets: [u64; constants::COMMAND_LATENCY_BUCKETS_MS.len() + 1],
+    pub latency_sum_ms: u64,
+    /// When each call in the last `COMMAND_ERROR_WINDOW` finished, and
+    /// whether it failed.
+    recent: VecDeque<(Instant, bool)>,
+    warned_at: Option<Instant>,
+}
+
+/// How a failed command should be reported.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FailureReport {
+    /// Like any other error.
+    Single,
+    /// The command just started failing often. Warn once, with the failures
+    /// and calls in the last `COMMAND_ERROR_WINDOW`.
+    Failing { failures: usize, calls: usize },
+    /// The command was reported as failing recently, so this failure isn't
+    /// reported on its own.
+    Suppressed,
+}
+
+impl CommandMetrics {
+    fn prune(&mut self, now: Instant) {
+        while let Some((at, _)) = self.recent.front() {
+            if now.duration_since(*at) <= constants::COMMAND_ERROR_WINDOW {
+                break;
+            }
+            self.recent.pop_front();
+        }
+    }
+
+    fn record(&mut self, elapsed: Duration, failed: bool) -> Option<FailureReport> {
+        let now = Instant::now();
+        let elapsed_ms = elapsed.as_millis() as i64;
+
+        self.calls += 1;
+        let bucket = constants::COMMAND_LATENCY_BUCKETS_MS
+            .iter()
+            .position(|&bound| elapsed_ms <= bound)
+            .unwrap_or(constants::COMMAND_LATENCY_BUCKETS_MS.len());
+        self.latency_buckets[bucket] += 1;
+        self.latency_sum_ms += elapsed_ms as u64;
+        self.prune(now);
+        self.recent.push_back((now, failed));
+
+        if !failed {
+            return None;
+        }
+        self.errors += 1;
+
+        if self
+            .warned_at
+            .is_some_and(|at| now.duration_since(at) < constants::COMMAND_ERROR_SUPPRESSION)
+        {
+            return Some(FailureReport::Suppressed);
+        }
+
+        let (failures, calls) = self.recent_errors();
+        if failures >= constants::COMMAND_ERROR_MIN_FAILURES
+            && failures as f64 / calls as f64 > constants::COMMAND_ERROR_RATE_THRESHOLD
+        {
+            self.warned_at = Some(now);
+            return Some(FailureReport::Failing { failures, calls });
+        }
+
+        Some(FailureReport::Single)
+    }
+
+    /// (failures, calls) in the last `COMMAND_ERROR_WINDOW`.
+    pub fn recent_errors(&self) -> (usize, usize) {
+        let now = Instant::now();
+        let recent = self
+            .recent
+            .iter()
+            .filter(|(at, _)| now.duration_since(*at) <= constants::COMMAND_ERROR_WINDOW);
+
+        recent.fold((0, 0), |(failures, calls), (_, failed)| {
+            (failures + *failed as usize, calls + 1)
+        })
+    }
+
+    /// The upper bound of the latency bucket that the `q`th quantile falls
+    /// in.
+    pub fn latency_quantile(&self, q: f64) -> Option<Quantile> {
+        if self.calls == 0 {
+            return None;
+        }
+
+        let target = ((self.calls as f64) * q).ceil().max(1.0) as u64;
+        let mut seen = 0;
+        for (idx, count) in self.latency_buckets.iter().enumerate() {
+            seen += count;
+            if seen >= target {
+                return Some(match constants::COMMAND_LATENCY_BUCKETS_MS.get(idx) {
+                    Some(&bound) => Quantile::AtMost(bound),
+                    None => Quantile::Over(*constants::COMMAND_LATENCY_BUCKETS_MS.last().unwrap()),
+                });
+            }
+        }
+
+        None
+    }
+}
+
+impl Metrics {
+    pub fn record_event(&self, event: &Event) {
+        self.events
+            .entry(event.kind())
+            .or_default()
+            .fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// `command` is the qualified name, like "starboards view". Returns how
+    /// to report the failure, if the command failed.
+    pub fn record_command(
+        &self,
+        command: &str,
+        elapsed: Duration,
+        failed: bool,
+    ) -> Option<FailureReport> {
+        if let Some(mut metrics) = self.commands.get_mut(command) {
+            return metrics.record(elapsed, failed);
+        }
+        self.commands
+            .entry(command.to_string())
+            .or_default()
+            .record(elapsed, failed)
+    }
+
+    /// (event name, count), sorted by name.
+    pub fn events(&self) -> Vec<(String, u64)> {
+        let mut events: Vec<_> = self
+            .events
+            .iter()
+            .map(|entry| {
+                let name = match entry.key().name() {
+                    Some(name) => name.to_string(),
+                    None => format!("{:?}", entry.key()),
+                };
+                (name, entry.value().load(Ordering::Relaxed))
+            })
+            .collect();
+        events.sort();
+        events
+    }
+
+    /// (command, metrics), sorted by command.
+    pub fn commands(&self) -> Vec<(String, CommandMetrics)> {
+        let mut commands: Vec<_> = self
+            .commands
+            .iter()
+            .map(|entry| (entry.key().clone(), entry.value().clone()))
+            .collect();
+        commands.sort_by(|a, b| a.0.cmp(&b.0));
+        commands
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn events_by_name() {
+        let metrics = Metrics::default();
+        metrics.record_event(&Event::Resumed);
+        metrics.record_event(&Event::GatewayReconnect);
+        metrics.record_event(&Event::Resumed);
+
+        // gateway events without a dispatch name use their type's name
+        let events = vec![
+            ("GatewayReconnect".to_string(), 1),
+            ("RESUMED".to_string(), 2),
+        ];
+        assert_eq!(metrics.events(), events);
+    }
+
+    #[test]
+    fn command_counts() {
+        let metrics = Metrics::default();
+        let elapsed = Duration::from_millis(10);
+        metrics.record_command("starboards view", elapsed, false);
+        metrics.record_command("starboards view", elapsed, true);
+        metrics.record_command("random", elapsed, false);
+
+        let commands = metrics.commands();
+        let names: Vec<_> = commands.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(names, vec!["random", "starboards view"]);
+        assert_eq!(commands[1].1.calls, 2);
+        assert_eq!(commands[1].1.errors, 1);
+        assert_eq!(commands[0].1.errors, 0);
+    }
+
+    fn ms(ms: u64) -> Duration {
+        Duration::from_millis(ms)
+    }
+
+    #[test]
+    fn latency_buckets() {
+        let mut metrics = CommandMetrics::default();
+        metrics.record(ms(50), false);
+        metrics.record(ms(51), false);
+        metrics.record(ms(60_000), false);
+
+        assert_eq!(metrics.latency_buckets[0], 1);
+        assert_eq!(metrics.latency_buckets[1], 1);
+        assert_eq!(
+            metrics.latency_buckets[constants::COMMAND_LATENCY_BUCKETS_MS.len()],
+            1
+        );
+        assert_eq!(metrics.latency_sum_ms, 60_101);
+    }
+
+    #[test]
+    fn latency_quantiles() {
+        let mut metrics = CommandMetrics::default();
+        assert_eq!(metrics.latency_quantile(0.5), None);
+
+        for _ in 0..9 {
+            metrics.record(ms(10), false);
+        }
+        metrics.record(ms(60_000), false);
+
+        assert_eq!(metrics.latency_quantile(0.5), Some(Quantile::AtMost(50)));
+        assert_eq!(metrics.latency_quantile(0.9), Some(Quantile::AtMost(50)));
+        assert_eq!(metrics.latency_quantile(0.95), Some(Quantile::Over(10_000)));
+    }
+
+    #[test]
+    fn single_failures() {
+        let mut metrics = CommandMetrics::default();
+        assert_eq!(metrics.record(ms(10), false), None);
+        assert_eq!(metrics.record(ms(10), true), Some(FailureReport::Single));
+        assert_eq!(metrics.recent_errors(), (1, 2));
+    }
+
+    #[test]
+    fn failing_commands_are_reported_once() {
+        let min = constants::COMMAND_ERROR_MIN_FAILURES;
+        let mut metrics = CommandMetrics::default();
+        for _ in 1..min {
+            assert_eq!(metrics.record(ms(10), true), Some(FailureReport::Single));
+        }
+
+        assert_eq!(
+            metrics.record(ms(10), true),
+            Some(FailureReport::Failing {
+                failures: min,
+                calls: min
+            })
+        );
+        assert_eq!(
+            metrics.record(ms(10), true),
+            Some(FailureReport::Suppressed)
+        );
+        assert_eq!(metrics.errors, min as u64 + 1);
+    }
+
+    #[test]
+    fn mostly_working_commands_are_not_failing() {
+        let min = constants::COMMAND_ERROR_MIN_FAILURES;
+        let mut metrics = CommandMetrics::default();
+        for _ in 0..min * 2 {
+            metrics.record(ms(10), false);
+        }
+
+        for _ in 0..min {
+            assert_eq!(metrics.record(ms(10), true), Some(FailureReport::Single));
+        }
+    }
+}
@@ -1,5 +1,12 @@
 pub mod bot;
+pub mod build_info;
 pub mod config;
 pub mod cooldowns;
+#[cfg(feature = "health")]
+pub mod health;
 pub mod locks;
+pub mod metrics;
 pub mod runner;
+pub mod seed;
+pub mod self_check;
+pub mod shards;
@@ -1,17 +1,29 @@
-use std::sync::Arc;
+use std::sync::{atomic::Ordering, Arc};
 
 use futures::stream::StreamExt;
 use tokio::signal::unix::{signal, SignalKind};
 use twilight_gateway::{stream, CloseFrame};
 
 use crate::{
-    client::bot::StarboardBot,
+    cache::{loop_log_cache_stats, task_load_autostar_channel_ids},
+    client::{
+        bot::StarboardBot,
+        shards::{claim_shards, loop_shard_status},
+    },
     core::{
+        competitions::loop_finish_competitions,
+        config_drift::loop_check_config_drift,
+        guild_purge::loop_purge_left_guilds,
         posroles::loop_update_posroles,
-        premium::{
-            roles::loop_update_supporter_roles,
+        post_latency::loop_post_latency_alerts,
+        premium::{notify::loop_listen_premium_changes, roles::loop_update_supporter_roles},
+        starboard::{
+            cleanup::loop_cleanup_reactions, companion::loop_expire_performed_actions,
+            dedupe::task_delete_duplicate_posts, schedule::loop_starboard_schedules,
+            vote_batcher::loop_flush_votes,
         },
     },
+    database::ShardStatus,
     events::handle_event,
 };
 
@@ -22,10 +34,10 @@ async fn wait_for_shutdown() {
     let mut interrupt = signal(SignalKind::interrupt()).unwrap();
     tokio::select! {
         _ = terminate.recv() => {
-            println!("Received terminate signal.");
+            tracing::info!("Received terminate signal.");
         }
         _ = interrupt.recv() => {
-            println!("Received interrupt signal.");
+            tracing::info!("Received interrupt signal.");
         }
     }
 }
@@ -34,49 +46,115 @@ pub async fn run(bot: StarboardBot) {
     let bot = Arc::new(bot);
     Cooldowns::start(bot.clone());
 
+    if let Err(why) = claim_shards(&bot).await {
+        bot.handle_error(&why).await;
+        return;
+    }
+
     if bot.config.development {
-        println!("Running bot in development mode.");
+        tracing::info!("Running bot in development mode.");
+        tokio::spawn(loop_log_cache_stats(bot.clone()));
     }
 
     // start background tasks
+    tokio::spawn(task_load_autostar_channel_ids(bot.clone()));
     tokio::spawn(loop_update_posroles(bot.clone()));
     tokio::spawn(loop_update_supporter_roles(bot.clone()));
+    tokio::spawn(loop_listen_premium_changes(bot.clone()));
+    tokio::spawn(loop_finish_competitions(bot.clone()));
+    tokio::spawn(loop_starboard_schedules(bot.clone()));
+    tokio::spawn(task_delete_duplicate_posts(bot.clone()));
+    tokio::spawn(loop_cleanup_reactions(bot.clone()));
+    tokio::spawn(loop_shard_status(bot.clone()));
+    tokio::spawn(loop_purge_left_guilds(bot.clone()));
+    tokio::spawn(loop_post_latency_alerts(bot.clone()));
+    tokio::spawn(loop_expire_performed_actions(bot.clone()));
+    tokio::spawn(loop_check_config_drift(bot.clone()));
+    tokio::spawn(loop_flush_votes(bot.clone()));
+    #[cfg(feature = "health")]
+    if let Some(port) = bot.config.health_port {
+        tokio::spawn(super::health::serve(bot.clone(), port));
+    }
 
     // handle events
+    let shard_range = bot.config.shard_range();
+    tracing::info!(
+        "Running shards {}..{} of {}.",
+        shard_range.start, shard_range.end, bot.config.total_shards
+    );
     let mut shards: Vec<_> = stream::create_range(
-        0..bot.config.shards,
-        bot.config.shards,
+        shard_range,
+        bot.config.total_shards,
         bot.gw_config.clone(),
         |_, b| b.build(),
     )
     .collect();
-    let events = stream::ShardEventStream::new(shards.iter_mut());
-    let mut events = events.take_until(Box::pin(wait_for_shutdown()));
-
-    while let Some((shard, event)) = events.next().await {
-        let event = match event {
-            Ok(event) => event,
-            Err(why) => {
-                let fatal = why.is_fatal();
-                eprintln!("{}: {:#?}", shard.id(), shard.status());
-                bot.handle_error(&why.into()).await;
+    let mut shutdown = Box::pin(wait_for_shutdown());
+
+    'events: loop {
+        let mut events = stream::ShardEventStream::new(shards.iter_mut());
 
-                if fatal {
-                    break;
-                } else {
-                    continue;
+        let released = loop {
+            let (shard, event) = tokio::select! {
+                _ = &mut shutdown => break 'events,
+                next = events.next() => match next {
+                    Some(next) => next,
+                    None => break 'events,
+                },
+            };
+            let event = match event {
+                Ok(event) => event,
+                Err(why) => {
+                    let fatal = why.is_fatal();
+                    tracing::error!("{}: {:#?}", shard.id(), shard.status());
+                    bot.handle_error(&why.into()).await;
+
+                    if fatal {
+                        break 'events;
+                    } else {
+                        continue;
+                    }
                 }
+            };
+
+            bot.shards.track_connection(shard.id().number(), &event);
+            bot.metrics.record_event(&event);
+
+            // another process took over this shard
+            if bot.shards.is_released(shard.id().number()) {
+                break shard.id().number();
             }
+
+            handle_event(shard.id(), event, bot.clone()).await;
         };
 
-        handle_event(shard.id(), event, bot.clone());
+        // the stream borrows every shard, so it has to be rebuilt without
+        // the one that was taken over
+        std::mem::drop(events);
+        if let Some(idx) = shards.iter().position(|s| s.id().number() == released) {
+            let mut shard = shards.remove(idx);
+            if let Err(why) = shard.close(CloseFrame::NORMAL).await {
+                bot.handle_error(&why.into()).await;
+            }
+            tracing::info!("Closed shard {released}, which another process took over.");
+        }
+        if shards.is_empty() {
+            tracing::info!("Every shard was taken over, shutting down.");
+            break;
+        }
     }
 
-    std::mem::drop(events);
+    bot.shutting_down.store(true, Ordering::Relaxed);
     for mut shard in shards {
         if let Err(why) = shard.close(CloseFrame::NORMAL).await {
             bot.handle_error(&why.into()).await;
         };
-        println!("Shard {} shutdown.", shard.id());
+        tracing::info!("Shard {} shutdown.", shard.id());
+    }
+    bot.vote_batcher.flush(&bot).await;
+    bot.emoji_usage.flush(&bot).await;
+
+    if let Err(why) = ShardStatus::release(&bot.pool, &bot.shards.instance_id).await {
+        bot.handle_error(&why.into()).await;
     }
 }
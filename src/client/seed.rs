@@ -0,0 +1,653 @@
+//! Fills the local database with a synthetic guild, so that commands can be
+//! tried out without a populated database. Run the bot with `--seed` to
+//! (re)create the guild, or `--wipe` to only remove it. Only allowed with
+//! `DEVELOPMENT=true`.
+//!
+//! Everything is generated from a fixed seed, so every run produces the same
+//! data. All rows belong to the seeded guild, or are users that are only
+//! members of it, which is how `--wipe` finds them again.
+
+use std::env;
+
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+
+use crate::{
+    core::stats::MemberStats,
+    database::{
+        AutoStarChannel, BlockedVoter, DbGuild, DbMember, DbMessage, DbUser, EmojiMigrationAudit,
+        ModNote, OverrideValues, Starboard, StarboardMessage, StarboardOverride, Vote,
+    },
+    errors::StarboardResult,
+};
+
+use super::config::Config;
+
+const RNG_SEED: u64 = 0x5EED_5EED;
+
+const DISCORD_EPOCH_MS: i64 = 1_420_070_400_000;
+/// When the first seeded message was sent (2023-01-01).
+const SEED_EPOCH_MS: i64 = 1_672_531_200_000;
+/// Ten minutes between each seeded message.
+const MESSAGE_INTERVAL_MS: i64 = 10 * 60 * 1000;
+
+const MESSAGES: i64 = 500;
+const USERS: i64 = 60;
+/// The number of upvotes the most popular message gets.
+const MAX_UPVOTES: f64 = 50.0;
+
+// channels
+const GENERAL: i64 = 1;
+const MEMES: i64 = 2;
+const ART: i64 = 3;
+const SHOWCASE: i64 = 4;
+const STARBOARD: i64 = 5;
+const HALL_OF_FAME: i64 = 6;
+const MEME_BOARD: i64 = 7;
+const SOURCE_CHANNELS: [i64; 4] = [GENERAL, MEMES, ART, SHOWCASE];
+
+/// Users on the blocklist, as `(user, remove_reactions)`. They never vote.
+const BLOCKED_USERS: [(i64, bool); 2] = [(USERS - 2, false), (USERS - 1, true)];
+
+/// What a seeded starboard changes from the default settings.
+struct StarboardFixture {
+    name: &'static str,
+    channel: i64,
+    required: Option<i16>,
+    /// Only these channels, or all of `SOURCE_CHANNELS` if empty.
+    channel_whitelist: &'static [i64],
+    downvote_emojis: &'static [&'static str],
+    display_emoji: Option<&'static str>,
+    xp_multiplier: f32,
+    /// Premium-only settings.
+    clone_attachments: bool,
+    media_selection: i16,
+    /// `(name, channel, required)` for channels with an override.
+    overrides: &'static [(&'static str, i64, i16)],
+}
+
+const STARBOARDS: [StarboardFixture; 3] = [
+    // defaults, with a lower requirement in the art channel
+    StarboardFixture {
+        name: "starboard",
+        channel: STARBOARD,
+        required: None,
+        channel_whitelist: &[],
+        downvote_emojis: &[],
+        display_emoji: None,
+        xp_multiplier: 1.0,
+        clone_attachments: false,
+        media_selection: 0,
+        overrides: &[("art", ART, 2)],
+    },
+    // uses premium-only settings
+    StarboardFixture {
+        name: "hall-of-fame",
+        channel: HALL_OF_FAME,
+        required: Some(15),
+        channel_whitelist: &[],
+        downvote_emojis: &[],
+        display_emoji: None,
+        xp_multiplier: 2.0,
+        clone_attachments: true,
+        media_selection: 2,
+        overrides: &[],
+    },
+    // downvotes, and only for the memes channel
+    StarboardFixture {
+        name: "memes",
+        channel: MEME_BOARD,
+        required: Some(5),
+        channel_whitelist: &[MEMES],
+        downvote_emojis: &["⬇️"],
+        display_emoji: Some("😂"),
+        xp_multiplier: 0.5,
+        clone_attachments: false,
+        media_selection: 0,
+        overrides: &[],
+    },
+];
+
+impl StarboardFixture {
+    fn sources(&self) -> &'static [i64] {
+        if self.channel_whitelist.is_empty() {
+            &SOURCE_CHANNELS
+        } else {
+            self.channel_whitelist
+        }
+    }
+}
+
+/// Settings that `star migrate-emojis` rewrote, as `(starboard or autostar
+/// name, setting, old value, new value)`.
+const EMOJI_MIGRATIONS: [(&str, &str, &[&str], &[&str]); 2] = [
+    ("memes", "display_emoji", &[":joy:"], &["😂"]),
+    ("art", "emojis", &[":art:", "⭐\u{fe0f}"], &["🎨", "⭐"]),
+];
+
+pub enum SeedMode {
+    Seed,
+    Wipe,
+}
+
+impl SeedMode {
+    pub fn from_args() -> Option<Self> {
+        for arg in env::args() {
+            match arg.as_str() {
+                "--seed" => return Some(Self::Seed),
+                "--wipe" => return Some(Self::Wipe),
+                _ => {}
+            }
+        }
+        None
+    }
+}
+
+pub async fn run(config: &Config, mode: SeedMode) -> StarboardResult<()> {
+    assert!(
+        config.development,
+        "--seed and --wipe can only be used with DEVELOPMENT=true."
+    );
+
+    let pool = PgPool::connect(&config.db_url).await?;
+    sqlx::migrate!()
+        .run(&pool)
+        .await
+        .expect("failed to run migrations");
+
+    wipe(&pool).await?;
+    tracing::info!("Wiped the seeded guild {}.", guild_id());
+
+    if let SeedMode::Seed = mode {
+        seed(&pool).await?;
+        tracing::info!("Seeded guild {}.", guild_id());
+    }
+
+    Ok(())
+}
+
+/// A snowflake for `offset_ms` after `SEED_EPOCH_MS`, with `n` in the lower
+/// bits so that ids created at the same time are still unique.
+fn snowflake(offset_ms: i64, n: i64) -> i64 {
+    ((SEED_EPOCH_MS - DISCORD_EPOCH_MS + offset_ms) << 22) | n
+}
+
+fn guild_id() -> i64 {
+    snowflake(0, 0)
+}
+
+fn channel_id(channel: i64) -> i64 {
+    snowflake(0, channel)
+}
+
+fn user_id(user: i64) -> i64 {
+    snowflake(0, 100 + user)
+}
+
+/// Messages start one interval in, so the first doesn't share the guild's id.
+fn message_id(message: i64) -> i64 {
+    snowflake((message + 1) * MESSAGE_INTERVAL_MS, 0)
+}
+
+fn post_id(message: i64, starboard: i64) -> i64 {
+    snowflake((message + 1) * MESSAGE_INTERVAL_MS + 1000, starboard)
+}
+
+/// Deletes the seeded guild, which cascades to everything in it, and its
+/// members' users unless they're also members of another guild. Nothing
+/// outside the seeded guild is touched, even if its ids look seeded.
+async fn wipe(pool: &PgPool) -> StarboardResult<()> {
+    let guild_id = guild_id();
+    let mut tx = pool.begin().await?;
+
+    let user_ids: Vec<i64> =
+        sqlx::query!("SELECT user_id FROM members WHERE guild_id=$1", guild_id)
+            .fetch_all(&mut tx)
+            .await?
+            .into_iter()
+            .map(|r| r.user_id)
+            .collect();
+
+    // not tied to the guild by a foreign key
+    sqlx::query!(
+        "DELETE FROM emoji_migration_audit WHERE guild_id=$1",
+        guild_id
+    )
+    .execute(&mut tx)
+    .await?;
+    sqlx::query!("DELETE FROM guilds WHERE guild_id=$1", guild_id)
+        .execute(&mut tx)
+        .await?;
+    sqlx::query!(
+        "DELETE FROM users WHERE user_id=ANY($1) AND NOT EXISTS
+        (SELECT FROM members WHERE members.user_id=users.user_id)",
+        &user_ids,
+    )
+    .execute(&mut tx)
+    .await?;
+
+    tx.commit().await?;
+    Ok(())
+}
+
+struct SeededStarboard {
+    starboard: Starboard,
+    fixture: &'static StarboardFixture,
+}
+
+impl SeededStarboard {
+    fn required(&self, channel: i64) -> i16 {
+        self.fixture
+            .overrides
+            .iter()
+            .find(|(_, c, _)| *c == channel)
+            .map(|(_, _, required)| *required)
+            .or(self.starboard.settings.required)
+            .unwrap_or(i16::MAX)
+    }
+}
+
+/// A seeded message and who voted on it.
+#[derive(Debug, PartialEq)]
+struct SeededMessage {
+    channel: i64,
+    author: i64,
+    /// The first `upvotes` voters upvoted, the rest downvoted.
+    voters: Vec<i64>,
+    upvotes: usize,
+}
+
+/// The messages to seed. The same every time.
+fn plan_messages() -> Vec<SeededMessage> {
+    let mut rng = Rng(RNG_SEED);
+
+    (0..MESSAGES)
+        .map(|_| {
+            let channel = SOURCE_CHANNELS[rng.below(SOURCE_CHANNELS.len() as u64) as usize];
+            // a few members post most of the messages
+            let author = rng.zipf(USERS as u64, 1.0) as i64;
+
+            // most messages get a vote or two, a few get a lot
+            let rank = rng.below(MESSAGES as u64) + 1;
+            let upvotes = (MAX_UPVOTES / (rank as f64).powf(0.9)) as u64 + rng.below(3);
+            let voters = rng.sample_users(upvotes + 2, author);
+            let upvotes = (upvotes as usize).min(voters.len());
+
+            SeededMessage {
+                channel,
+                author,
+                voters,
+                upvotes,
+            }
+        })
+        .collect()
+}
+
+async fn seed(pool: &PgPool) -> StarboardResult<()> {
+    let guild_id = guild_id();
+
+    DbGuild::create(pool, guild_id).await?;
+    let premium_end: DateTime<Utc> = "2100-01-01T00:00:00Z".parse().unwrap();
+    sqlx::query!(
+        "UPDATE guilds SET premium_end=$1 WHERE guild_id=$2",
+        premium_end,
+        guild_id,
+    )
+    .fetch_all(pool)
+    .await?;
+    DbGuild::set_xp_carryover(pool, guild_id, 25).await?;
+
+    // the last user is a bot
+    for user in 0..=USERS {
+        DbUser::create(pool, user_id(user), user == USERS).await?;
+        DbMember::create(pool, user_id(user), guild_id).await?;
+    }
+    for (user, remove_reactions) in BLOCKED_USERS {
+        BlockedVoter::create(pool, guild_id, user_id(user), remove_reactions, user_id(0)).await?;
+    }
+
+    let starboards = seed_starboards(pool, guild_id).await?;
+    let autostar_channels = seed_autostar_channels(pool, guild_id).await?;
+    seed_emoji_migrations(pool, guild_id, &starboards, &autostar_channels).await?;
+
+    for (message, seeded) in plan_messages().into_iter().enumerate() {
+        let message = message as i64;
+        let orig_id = message_id(message);
+        DbMessage::create(
+            pool,
+            orig_id,
+            guild_id,
+            channel_id(seeded.channel),
+            user_id(seeded.author),
+            false,
+        )
+        .await?;
+
+        for (idx, sb) in starboards.iter().enumerate() {
+            if !sb.fixture.sources().contains(&seeded.channel) {
+                continue;
+            }
+
+            let mut points = 0;
+            for (voter_idx, voter) in seeded.voters.iter().enumerate() {
+                let is_downvote = voter_idx >= seeded.upvotes;
+                if is_downvote && sb.starboard.settings.downvote_emojis.is_empty() {
+                    continue;
+                }
+                Vote::create(
+                    pool,
+                    orig_id,
+                    sb.starboard.id,
+                    user_id(*voter),
+                    user_id(seeded.author),
+                    is_downvote,
+                    1,
+                    None,
+                )
+                .await?;
+                points += if is_downvote { -1 } else { 1 };
+            }
+
+            if points >= i32::from(sb.required(seeded.channel)) {
+                StarboardMessage::create(
+                    pool,
+                    orig_id,
+                    post_id(message, idx as i64),
+                    sb.starboard.id,
+                    points,
+                )
+                .await?;
+            }
+        }
+
+        if seeded.upvotes >= 5 {
+            ModNote::create(
+                pool,
+                guild_id,
+                orig_id,
+                user_id(0),
+                "Seeded note: this one took off.",
+            )
+            .await?;
+        }
+    }
+
+    for user in 0..USERS {
+        let Some(stats) = MemberStats::get(pool, guild_id, user_id(user)).await? else {
+            continue;
+        };
+        DbMember::set_xp(pool, user_id(user), guild_id, stats.season_xp).await?;
+    }
+
+    Ok(())
+}
+
+async fn seed_starboards(pool: &PgPool, guild_id: i64) -> StarboardResult<Vec<SeededStarboard>> {
+    let mut seeded = Vec::new();
+
+    for fixture in &STARBOARDS {
+        let mut starboard = create_starboard(pool, guild_id, fixture.name, fixture.channel).await?;
+        if let Some(required) = fixture.required {
+            starboard.settings.required = Some(required);
+        }
+        starboard.settings.channel_whitelist = fixture
+            .channel_whitelist
+            .iter()
+            .map(|&channel| channel_id(channel))
+            .collect();
+        starboard.settings.downvote_emojis = fixture
+            .downvote_emojis
+            .iter()
+            .map(|emoji| emoji.to_string())
+            .collect();
+        if let Some(emoji) = fixture.display_emoji {
+            starboard.settings.display_emoji = Some(emoji.to_string());
+        }
+        starboard.settings.xp_multiplier = fixture.xp_multiplier;
+        starboard.settings.clone_attachments = fixture.clone_attachments;
+        starboard.settings.media_selection = fixture.media_selection;
+        let starboard = starboard.update_settings(pool).await?.unwrap();
+
+        for &(name, channel, required) in fixture.overrides {
+            let ovr = StarboardOverride::create(pool, guild_id, &name.to_string(), starboard.id)
+                .await?
+                .unwrap();
+            StarboardOverride::set_channels(pool, guild_id, &ovr.name, &[channel_id(channel)])
+                .await?;
+            let values = OverrideValues {
+                required: Some(Some(required)),
+                ..Default::default()
+            };
+            StarboardOverride::update_settings(pool, ovr.id, values).await?;
+        }
+
+        seeded.push(SeededStarboard { starboard, fixture });
+    }
+
+    Ok(seeded)
+}
+
+async fn create_starboard(
+    pool: &PgPool,
+    guild_id: i64,
+    name: &str,
+    channel: i64,
+) -> StarboardResult<Starboard> {
+    let starboard = Starboard::create(pool, &name.to_string(), channel_id(channel), guild_id)
+        .await?
+        .unwrap();
+    Ok(starboard)
+}
+
+async fn seed_autostar_channels(
+    pool: &PgPool,
+    guild_id: i64,
+) -> StarboardResult<Vec<AutoStarChannel>> {
+    let mut seeded = Vec::new();
+
+    let mut asc = AutoStarChannel::create(pool, &"art".to_string(), channel_id(ART), guild_id)
+        .await?
+        .unwrap();
+    asc.require_image = true;
+    asc.emojis = vec!["🎨".to_string(), "⭐".to_string()];
+    seeded.extend(asc.update_settings(pool).await?);
+
+    let mut asc = AutoStarChannel::create(
+        pool,
+        &"showcase".to_string(),
+        channel_id(SHOWCASE),
+        guild_id,
+    )
+    .await?
+    .unwrap();
+    asc.min_chars = 20;
+    asc.delete_invalid = true;
+    seeded.extend(asc.update_settings(pool).await?);
+
+    Ok(seeded)
+}
+
+/// Audit entries, as if `star migrate-emojis` had rewritten some settings.
+async fn seed_emoji_migrations(
+    pool: &PgPool,
+    guild_id: i64,
+    starboards: &[SeededStarboard],
+    autostar_channels: &[AutoStarChannel],
+) -> StarboardResult<()> {
+    for (name, setting, old_value, new_value) in EMOJI_MIGRATIONS {
+        let (kind, row_id) = match starboards.iter().find(|sb| sb.starboard.name == name) {
+            Some(sb) => ("starboard", sb.starboard.id),
+            None => {
+                let asc = autostar_channels.iter().find(|a| a.name == name).unwrap();
+                ("autostar", asc.id)
+            }
+        };
+        let old_value: Vec<_> = old_value.iter().map(|e| e.to_string()).collect();
+        let new_value: Vec<_> = new_value.iter().map(|e| e.to_string()).collect();
+        EmojiMigrationAudit::create(
+            pool, guild_id, kind, row_id, setting, &old_value, &new_value,
+        )
+        .await?;
+    }
+
+    Ok(())
+}
+
+/// SplitMix64. Seeding only needs reproducible numbers, not good ones.
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    fn below(&mut self, max: u64) -> u64 {
+        self.next() % max
+    }
+
+    fn unit(&mut self) -> f64 {
+        (self.next() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// A number below `max`, where 0 is the most likely, 1 half as likely
+    /// (with `exponent` 1) and so on.
+    fn zipf(&mut self, max: u64, exponent: f64) -> u64 {
+        let weights: Vec<f64> = (1..=max).map(|k| 1.0 / (k as f64).powf(exponent)).collect();
+        let mut target = self.unit() * weights.iter().sum::<f64>();
+        for (idx, weight) in weights.iter().enumerate() {
+            target -= weight;
+            if target <= 0.0 {
+                return idx as u64;
+            }
+        }
+        max - 1
+    }
+
+    /// Up to `count` different users, never `exclude` or a blocked user.
+    fn sample_users(&mut self, count: u64, exclude: i64) -> Vec<i64> {
+        let mut users: Vec<_> = (0..USERS)
+            .filter(|u| *u != exclude && !BLOCKED_USERS.iter().any(|(b, _)| b == u))
+            .collect();
+        let count = (count as usize).min(users.len());
+        for idx in 0..count {
+            let swap = idx + self.below((users.len() - idx) as u64) as usize;
+            users.swap(idx, swap);
+        }
+        users.truncate(count);
+        users
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use crate::{core::emoji_migration::canonicalize, database::validation::starboard_settings};
+
+    use super::*;
+
+    #[test]
+    fn rng_is_fixed() {
+        let mut rng = Rng(RNG_SEED);
+        assert_eq!(rng.next(), 11070996037087289157);
+    }
+
+    #[test]
+    fn reseeding_is_reproducible() {
+        assert_eq!(plan_messages(), plan_messages());
+    }
+
+    #[test]
+    fn votes() {
+        let blocked: Vec<_> = BLOCKED_USERS.iter().map(|(user, _)| *user).collect();
+        for msg in plan_messages() {
+            let unique: HashSet<_> = msg.voters.iter().collect();
+            assert_eq!(unique.len(), msg.voters.len());
+            assert!(!msg.voters.contains(&msg.author));
+            assert!(msg.voters.iter().all(|v| !blocked.contains(v)));
+            assert!(msg.upvotes <= msg.voters.len());
+        }
+    }
+
+    #[test]
+    fn few_members_post_most() {
+        let mut counts = [0; USERS as usize];
+        for msg in plan_messages() {
+            counts[msg.author as usize] += 1;
+        }
+        let top: i32 = counts[..10].iter().sum();
+        assert!(top * 2 > MESSAGES as i32);
+        assert_eq!(counts.iter().max(), Some(&counts[0]));
+    }
+
+    #[test]
+    fn ids_are_unique() {
+        let mut ids = vec![guild_id()];
+        ids.extend(
+            [
+                GENERAL,
+                MEMES,
+                ART,
+                SHOWCASE,
+                STARBOARD,
+                HALL_OF_FAME,
+                MEME_BOARD,
+            ]
+            .map(channel_id),
+        );
+        ids.extend((0..=USERS).map(user_id));
+        for message in 0..MESSAGES {
+            ids.push(message_id(message));
+            ids.extend((0..STARBOARDS.len() as i64).map(|sb| post_id(message, sb)));
+        }
+
+        let unique: HashSet<_> = ids.iter().collect();
+        assert_eq!(unique.len(), ids.len());
+    }
+
+    #[test]
+    fn starboards_pass_validation() {
+        for sb in &STARBOARDS {
+            if let Some(required) = sb.required {
+                starboard_settings::validate_required(required.to_string(), None).unwrap();
+            }
+            for (_, channel, required) in sb.overrides {
+                assert!(sb.sources().contains(channel));
+                starboard_settings::validate_required(required.to_string(), None).unwrap();
+            }
+            starboard_settings::validate_xp_multiplier(sb.xp_multiplier).unwrap();
+
+            let downvote_emojis: Vec<_> =
+                sb.downvote_emojis.iter().map(|e| e.to_string()).collect();
+            starboard_settings::validate_vote_emojis(&[], &downvote_emojis, true).unwrap();
+            let whitelist: Vec<_> = sb
+                .channel_whitelist
+                .iter()
+                .map(|&c| channel_id(c))
+                .collect();
+            starboard_settings::validate_channel_lists(&whitelist, &[]).unwrap();
+
+            // stored like the commands would store them
+            for emoji in sb.downvote_emojis.iter().chain(&sb.display_emoji) {
+                assert_eq!(canonicalize(emoji).as_deref(), Some(*emoji));
+            }
+        }
+    }
+
+    #[test]
+    fn emoji_migrations() {
+        for (name, _, old_value, new_value) in EMOJI_MIGRATIONS {
+            assert!(
+                STARBOARDS.iter().any(|sb| sb.name == name) || ["art", "showcase"].contains(&name)
+            );
+
+            // what `star migrate-emojis` would have written
+            let migrated: Vec<_> = old_value.iter().map(|e| canonicalize(e).unwrap()).collect();
+            assert_eq!(migrated, new_value);
+        }
+    }
+}
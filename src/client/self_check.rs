@@ -0,0 +1,185 @@
+//! Checks run at startup, before connecting to the gateway, so that a bad
+//! token or an unreachable database is reported right away, together with
+//! everything else that's wrong.
+
+use std::{collections::HashSet, fmt};
+
+use sqlx::{PgPool, Row};
+use twilight_http::{error::ErrorType, Client as HttpClient};
+
+use crate::utils::{get_status::get_status, into_id::IntoId};
+
+use super::{bot::build_http, config::Config};
+
+/// Every fatal problem found by `self_check`.
+#[derive(Debug)]
+pub struct SelfCheckError {
+    pub problems: Vec<String>,
+}
+
+impl fmt::Display for SelfCheckError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Startup self-check failed:")?;
+        for problem in &self.problems {
+            writeln!(f, "- {problem}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Logs the effective configuration, then checks the token, the database
+/// and the error channel. Problems that the bot can run with are logged as
+/// warnings, the others are returned.
+pub async fn self_check(config: &Config) -> Result<(), SelfCheckError> {
+    log_summary(config);
+
+    let http = build_http(config);
+    let mut problems = Vec::new();
+    let mut warnings = Vec::new();
+
+    check_token(config, &http, &mut problems).await;
+    check_database(config, &mut problems, &mut warnings).await;
+    check_error_channel(config, &http, &mut warnings).await;
+
+    for warning in warnings {
+        tracing::warn!("{warning}");
+    }
+    if problems.is_empty() {
+        tracing::info!("Startup self-check passed.");
+        Ok(())
+    } else {
+        Err(SelfCheckError { problems })
+    }
+}
+
+fn log_summary(config: &Config) {
+    let summary = config.summary();
+    let width = summary
+        .iter()
+        .map(|(name, _)| name.len())
+        .max()
+        .unwrap_or(0);
+
+    let mut table = String::from("Configuration:");
+    for (name, value) in summary {
+        table.push_str(&format!("\n  {name:width$}  {value}"));
+    }
+    tracing::info!("{table}");
+}
+
+async fn check_token(config: &Config, http: &HttpClient, problems: &mut Vec<String>) {
+    let user = match http.current_user().await {
+        Ok(user) => user.model().await,
+        Err(why) if matches!(why.kind(), ErrorType::Unauthorized) => {
+            problems.push("DISCORD_TOKEN was rejected by Discord.".to_string());
+            return;
+        }
+        Err(why) => {
+            problems.push(format!(
+                "Couldn't reach Discord to check DISCORD_TOKEN: {why}"
+            ));
+            return;
+        }
+    };
+
+    match user {
+        Ok(user) if user.id.get() != config.bot_id => problems.push(format!(
+            "BOT_ID is {}, but the token belongs to {} ({}).",
+            config.bot_id, user.name, user.id
+        )),
+        Ok(user) => tracing::info!("Logged in as {} ({}).", user.name, user.id),
+        Err(why) => problems.push(format!("Couldn't read the current user: {why}")),
+    }
+}
+
+/// Migrations are applied by `StarboardBot::new`, so pending migrations are
+/// fine here. Failed ones, or ones this build doesn't know about, aren't.
+async fn check_database(config: &Config, problems: &mut Vec<String>, warnings: &mut Vec<String>) {
+    let pool = match PgPool::connect(&config.db_url).await {
+        Ok(pool) => pool,
+        Err(why) => {
+            problems.push(format!("Couldn't connect to the database: {why}"));
+            return;
+        }
+    };
+
+    let ret = sqlx::query("SELECT to_regclass('_sqlx_migrations') IS NOT NULL AS exists")
+        .fetch_one(&pool)
+        .await;
+    let has_migrations = match ret {
+        Ok(row) => row.get::<bool, _>("exists"),
+        Err(why) => {
+            problems.push(format!("Couldn't query the database: {why}"));
+            return;
+        }
+    };
+
+    let applied: Vec<(i64, bool)> = if has_migrations {
+        let ret = sqlx::query("SELECT version, success FROM _sqlx_migrations")
+            .fetch_all(&pool)
+            .await;
+        match ret {
+            Ok(rows) => rows
+                .iter()
+                .map(|row| (row.get("version"), row.get("success")))
+                .collect(),
+            Err(why) => {
+                problems.push(format!("Couldn't read the applied migrations: {why}"));
+                return;
+            }
+        }
+    } else {
+        Vec::new()
+    };
+
+    let expected: HashSet<i64> = sqlx::migrate!()
+        .migrations
+        .iter()
+        .map(|m| m.version)
+        .collect();
+
+    for (version, success) in &applied {
+        if !success {
+            problems.push(format!(
+                "Migration {version} failed and has to be fixed by hand."
+            ));
+        } else if !expected.contains(version) {
+            warnings.push(format!(
+                "The database has migration {version}, which this build doesn't know. It \
+                might be running a newer version of the bot."
+            ));
+        }
+    }
+
+    let pending = expected
+        .iter()
+        .filter(|version| !applied.iter().any(|(v, _)| v == *version))
+        .count();
+    if pending != 0 {
+        tracing::info!("{pending} migrations will be applied.");
+    }
+
+    pool.close().await;
+}
+
+async fn check_error_channel(config: &Config, http: &HttpClient, warnings: &mut Vec<String>) {
+    let Some(channel_id) = config.error_channel else {
+        warnings.push("ERROR_CHANNEL_ID isn't set, so errors are only logged.".to_string());
+        return;
+    };
+
+    // needs the Send Messages permission, without sending anything
+    let ret = http.create_typing_trigger(channel_id.into_id()).await;
+    match ret {
+        Ok(_) => {}
+        Err(why) => match get_status(&why) {
+            Some(403) => warnings.push(format!(
+                "Can't send messages to the error channel ({channel_id})."
+            )),
+            Some(404) => warnings.push(format!(
+                "The error channel ({channel_id}) doesn't exist, or isn't visible."
+            )),
+            _ => warnings.push(format!("Couldn't check the error channel: {why}")),
+        },
+    }
+}
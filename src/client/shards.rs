@@ -0,0 +1,317 @@
+//! Handing shards over between processes. `star drain` marks shards as
+//! draining, after which their owner stops starting new refreshes for them.
+//! Once its in-flight refreshes are done, the shards are marked drained and a
+//! process started with `--takeover` can claim them. Refreshes skipped in the
+//! meantime are saved, and the new owner runs them.
+
+use std::{
+    collections::HashSet,
+    ops::Range,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+};
+
+use dashmap::DashSet;
+use twilight_gateway::Event;
+
+use crate::{
+    client::bot::StarboardBot,
+    constants,
+    core::starboard::handle::RefreshMessage,
+    database::{PendingRefresh, ShardStatus},
+    errors::{ShardsNotDrainedSnafu, StarboardResult},
+    utils::into_id::IntoId,
+};
+
+pub struct ShardState {
+    /// Identifies this process in the `shard_status` table.
+    pub instance_id: String,
+    draining: DashSet<u64>,
+    released: DashSet<u64>,
+    /// Shards that identified (or resumed) and haven't disconnected since.
+    connected: DashSet<u64>,
+    in_flight: AtomicUsize,
+}
+
+/// Counts a refresh as in-flight until dropped.
+pub struct InFlightGuard<'a>(&'a AtomicUsize);
+
+impl Drop for InFlightGuard<'_> {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+impl ShardState {
+    pub fn new() -> Self {
+        let host = std::env::var("HOSTNAME").unwrap_or_else(|_| "unknown".to_string());
+
+        Self {
+            instance_id: format!("{host}:{}", std::process::id()),
+            draining: DashSet::new(),
+            released: DashSet::new(),
+            connected: DashSet::new(),
+            in_flight: AtomicUsize::new(0),
+        }
+    }
+
+    /// Whether new work for the shard should be skipped.
+    pub fn is_draining(&self, shard_id: u64) -> bool {
+        self.draining.contains(&shard_id) || self.released.contains(&shard_id)
+    }
+
+    /// Whether another process took over the shard, in which case its events
+    /// are ignored.
+    pub fn is_released(&self, shard_id: u64) -> bool {
+        self.released.contains(&shard_id)
+    }
+
+    pub fn track_refresh(&self) -> InFlightGuard<'_> {
+        self.in_flight.fetch_add(1, Ordering::SeqCst);
+        InFlightGuard(&self.in_flight)
+    }
+
+    pub fn in_flight(&self) -> usize {
+        self.in_flight.load(Ordering::SeqCst)
+    }
+
+    /// Keeps track of which shards are connected, from their events.
+    pub fn track_connection(&self, shard_id: u64, event: &Event) {
+        match event {
+            Event::Ready(_) | Event::Resumed => {
+                self.connected.insert(shard_id);
+            }
+            Event::GatewayClose(_) => {
+                self.connected.remove(&shard_id);
+            }
+            _ => (),
+        }
+    }
+
+    pub fn connected(&self) -> usize {
+        self.connected.len()
+    }
+}
+
+impl Default for ShardState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Parses "4..8" (the end being exclusive) or a single shard id like "4".
+pub fn parse_shard_range(inp: &str) -> Option<Range<u64>> {
+    let range = match inp.split_once("..") {
+        Some((start, end)) => start.trim().parse().ok()?..end.trim().parse().ok()?,
+        None => {
+            let shard_id: u64 = inp.trim().parse().ok()?;
+            shard_id..shard_id + 1
+        }
+    };
+
+    if range.is_empty() {
+        None
+    } else {
+        Some(range)
+    }
+}
+
+pub fn shard_ids(range: Range<u64>) -> Vec<i32> {
+    range.map(|id| id as i32).collect()
+}
+
+/// Claims this process's shards before connecting to the gateway. Normally
+/// this waits for shards owned by another live process to be released. With
+/// `--takeover`, the shards have to be drained (or unowned) already.
+pub async fn claim_shards(bot: &StarboardBot) -> StarboardResult<()> {
+    let shard_ids = shard_ids(bot.config.shard_range());
+    let timeout = chrono::Duration::seconds(constants::SHARD_HEARTBEAT_TIMEOUT);
+
+    loop {
+        let ret = ShardStatus::claim(
+            &bot.pool,
+            &shard_ids,
+            &bot.shards.instance_id,
+            bot.config.takeover,
+            timeout,
+        )
+        .await?;
+
+        let blocking = match ret {
+            Ok(()) => return Ok(()),
+            Err(blocking) => blocking,
+        };
+        let blocking = blocking
+            .iter()
+            .map(|row| {
+                format!(
+                    "{} ({})",
+                    row.shard_id,
+                    row.owner.as_deref().unwrap_or_default()
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        if bot.config.takeover {
+            return ShardsNotDrainedSnafu { shards: blocking }.fail();
+        }
+        tracing::info!("Waiting for other processes to release shards: {blocking}.");
+        tokio::time::sleep(constants::SHARD_STATUS_DELAY).await;
+    }
+}
+
+/// Sends heartbeats for this process's shards, picks up `star drain`, and
+/// marks draining shards as drained once no refreshes are in-flight.
+pub async fn loop_shard_status(bot: Arc<StarboardBot>) {
+    loop {
+        tokio::time::sleep(constants::SHARD_STATUS_DELAY).await;
+
+        if let Err(why) = update_shard_status(&bot).await {
+            bot.handle_error(&why).await;
+        }
+    }
+}
+
+async fn update_shard_status(bot: &Arc<StarboardBot>) -> StarboardResult<()> {
+    let owned = ShardStatus::heartbeat(&bot.pool, &bot.shards.instance_id).await?;
+
+    let owned_ids: HashSet<_> = owned.iter().map(|row| row.shard_id as u64).collect();
+    for shard_id in bot.config.shard_range() {
+        if !owned_ids.contains(&shard_id) && bot.shards.released.insert(shard_id) {
+            tracing::info!("Shard {shard_id} was taken over by another process.");
+        }
+    }
+
+    let mut pending = Vec::new();
+    for row in owned.iter().filter(|row| row.draining) {
+        if bot.shards.draining.insert(row.shard_id as u64) {
+            tracing::info!("Draining shard {}.", row.shard_id);
+        }
+        if !row.drained {
+            pending.push(row.shard_id);
+        }
+    }
+
+    if !pending.is_empty() && bot.shards.in_flight() == 0 {
+        // the next owner's refreshes have to see every vote
+        bot.vote_batcher.flush(bot).await;
+        ShardStatus::mark_drained(&bot.pool, &pending, &bot.shards.instance_id).await?;
+        tracing::info!("Shards {pending:?} are drained.");
+    }
+
+    let running: Vec<_> = owned
+        .iter()
+        .filter(|row| !row.draining)
+        .map(|row| row.shard_id)
+        .collect();
+    if !running.is_empty() {
+        run_pending_refreshes(bot, &running).await?;
+    }
+
+    Ok(())
+}
+
+/// Runs the refreshes that the shards' previous owner skipped while
+/// draining them, in the background so that heartbeats aren't held up.
+async fn run_pending_refreshes(bot: &Arc<StarboardBot>, shard_ids: &[i32]) -> StarboardResult<()> {
+    let pending = PendingRefresh::take_by_shards(&bot.pool, shard_ids).await?;
+    if pending.is_empty() {
+        return Ok(());
+    }
+
+    tracing::info!(
+        "Running {} refreshes skipped during a handover.",
+        pending.len()
+    );
+    let bot = bot.clone();
+    tokio::spawn(async move {
+        for row in pending {
+            let mut refresh =
+                RefreshMessage::new(bot.clone(), row.message_id.into_id(), row.is_premium);
+            if let Err(why) = refresh.refresh(false).await {
+                bot.handle_error(&why).await;
+            }
+        }
+    });
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+
+    use super::*;
+
+    #[test]
+    fn shard_ranges() {
+        assert_eq!(parse_shard_range("4..8"), Some(4..8));
+        assert_eq!(parse_shard_range(" 4 .. 8 "), Some(4..8));
+        assert_eq!(parse_shard_range("4"), Some(4..5));
+        assert_eq!(parse_shard_range("8..4"), None);
+        assert_eq!(parse_shard_range("4..4"), None);
+        assert_eq!(parse_shard_range("a..4"), None);
+        assert_eq!(shard_ids(2..5), vec![2, 3, 4]);
+    }
+
+    #[test]
+    fn draining_and_released() {
+        let state = ShardState::new();
+        assert!(!state.is_draining(1));
+
+        state.draining.insert(1);
+        assert!(state.is_draining(1));
+        assert!(!state.is_released(1));
+
+        // released shards don't start new work either
+        state.released.insert(2);
+        assert!(state.is_draining(2));
+        assert!(state.is_released(2));
+    }
+
+    #[test]
+    fn in_flight_refreshes() {
+        let state = ShardState::new();
+        let first = state.track_refresh();
+        let second = state.track_refresh();
+        assert_eq!(state.in_flight(), 2);
+
+        drop(first);
+        assert_eq!(state.in_flight(), 1);
+        drop(second);
+        assert_eq!(state.in_flight(), 0);
+    }
+
+    #[test]
+    fn connections() {
+        let state = ShardState::new();
+        state.track_connection(1, &Event::Resumed);
+        state.track_connection(2, &Event::Resumed);
+        assert_eq!(state.connected(), 2);
+
+        state.track_connection(1, &Event::GatewayClose(None));
+        assert_eq!(state.connected(), 1);
+    }
+
+    #[test]
+    fn shard_owners() {
+        let timeout = chrono::Duration::seconds(constants::SHARD_HEARTBEAT_TIMEOUT);
+        let row = |owner: Option<&str>, heartbeat_at| ShardStatus {
+            shard_id: 0,
+            owner: owner.map(str::to_string),
+            draining: false,
+            drained: false,
+            heartbeat_at,
+        };
+
+        assert!(row(Some("other"), Utc::now()).is_owned_by_other("me", timeout));
+        assert!(!row(Some("me"), Utc::now()).is_owned_by_other("me", timeout));
+        assert!(!row(None, Utc::now()).is_owned_by_other("me", timeout));
+        // the other process stopped sending heartbeats
+        let stale = Utc::now() - timeout - chrono::Duration::seconds(1);
+        assert!(!row(Some("other"), stale).is_owned_by_other("me", timeout));
+    }
+}
@@ -6,6 +6,18 @@ pub const EMBED_DARK_BG: u32 = 0x2B2D31;
 
 pub const BOT_COLOR: u32 = 0xFFE19C;
 
+/// Discord shows up to 4 embeds that share a url as a single gallery.
+pub const MAX_GALLERY_IMAGES: usize = 4;
+/// The most characters a message's content can have.
+pub const MAX_MESSAGE_LENGTH: usize = 2_000;
+/// How many characters of a replied-to message are cached with the reply.
+pub const MAX_REPLY_SNIPPET_LENGTH: usize = 100;
+
+// Attachments, in bytes
+pub const MAX_UPLOAD_ATTACHMENT_SIZE: u64 = 25_000_000;
+/// The largest attachment `clone-attachments` will re-upload.
+pub const MAX_CLONE_ATTACHMENT_SIZE: u64 = 8_000_000;
+
 pub const YEAR_SECONDS: i64 = 31_557_600;
 pub const MONTH_SECONDS: i64 = 2_630_016;
 pub const MONTH_DAYS: u64 = 31;
@@ -27,8 +39,87 @@ pub const DOCS_FILTERS: &str = "https://docs.starboard.best/docs/filters";
 // Tasks
 pub const UPDATE_PRS_DELAY: Duration = Duration::from_secs(60 * 60); //posroles
 pub const CHECK_EXPIRED_PREMIUM: Duration = Duration::from_secs(60 * 60 * 24 * 3650);
+pub const PREMIUM_LISTENER_RETRY: Duration = Duration::from_secs(30);
 pub const UPDATE_PATREON_DELAY: Duration = Duration::from_secs(60 * 60 * 24 * 3650);
 pub const UPDATE_SUPPORTER_ROLES_DELAY: Duration = Duration::from_secs(60 * 60 * 24 * 3650);
+pub const FINISH_COMPETITIONS_DELAY: Duration = Duration::from_secs(60);
+
+// Left guild cleanup
+pub const PURGE_LEFT_GUILDS_DELAY: Duration = Duration::from_secs(60 * 60);
+pub const PURGE_GUILD_BATCH_SIZE: i64 = 1_000;
+pub const PURGE_GUILD_BATCH_DELAY: Duration = Duration::from_millis(100);
+
+// Time to post (premium)
+/// Upper bounds of the latency histogram buckets. Slower posts go in one more
+/// bucket after these.
+pub const POST_LATENCY_BUCKETS_MS: [i64; 9] = [
+    500, 1_000, 2_000, 3_000, 5_000, 10_000, 30_000, 60_000, 300_000,
+];
+pub const POST_LATENCY_SUMMARY_HOURS: i64 = 24;
+pub const POST_LATENCY_RETENTION_HOURS: i64 = 48;
+pub const POST_LATENCY_ALERT_DELAY: Duration = Duration::from_secs(60 * 60);
+pub const POST_LATENCY_ALERT_P95_MS: i64 = 10_000;
+/// Hours with fewer posts than this are too noisy to alert on.
+pub const POST_LATENCY_ALERT_MIN_POSTS: i64 = 10;
+
+// Config drift
+pub const CONFIG_DRIFT_CHECK_DELAY: Duration = Duration::from_secs(60 * 60 * 24 * 7);
+pub const CONFIG_DRIFT_GUILD_DELAY: Duration = Duration::from_millis(500);
+
+// Reaction cleanup
+pub const CLEANUP_REACTIONS_MAX_AGE: Duration = Duration::from_secs(60 * 60 * 24 * 90);
+pub const CLEANUP_REACTIONS_DELAY: Duration = Duration::from_millis(500);
+pub const CLEANUP_REACTIONS_POLL_DELAY: Duration = Duration::from_secs(30);
+pub const CLEANUP_REACTIONS_PAGE_SIZE: i64 = 100;
+/// How many posts of a starboard are cleaned between progress log entries.
+pub const CLEANUP_REACTIONS_REPORT_EVERY: usize = 250;
+
+// Duplicate post cleanup
+pub const DEDUPE_POSTS_PAGE_SIZE: i64 = 100;
+pub const DEDUPE_POSTS_DELAY: Duration = Duration::from_secs(1);
+pub const EMOJI_MIGRATION_REPORT_GUILDS: usize = 20;
+
+// Shard handover
+pub const SHARD_STATUS_DELAY: Duration = Duration::from_secs(10);
+/// Shards whose owner hasn't sent a heartbeat for this long can be claimed.
+pub const SHARD_HEARTBEAT_TIMEOUT: i64 = 60;
+
+// Owner commands
+pub const SQL_MAX_ROWS: usize = 10;
+pub const SQL_STATEMENT_TIMEOUT: Duration = Duration::from_secs(10);
+
+// Event queue
+pub const EVENT_QUEUE_SIZE: usize = 1_000;
+/// Low priority events are dropped once their queue has this many events.
+pub const EVENT_QUEUE_LOW_PRIORITY_LIMIT: usize = 250;
+pub const EVENT_QUEUE_IDLE: Duration = Duration::from_secs(10);
+pub const MAX_CONCURRENT_EVENTS: usize = 500;
+pub const EVENT_DROP_LOG_INTERVAL: u64 = 100;
+pub const EVENT_QUEUE_STATS_DEEPEST: usize = 5;
+
+// Health server
+/// `/readyz` runs `SELECT 1` at most this often.
+pub const HEALTH_DB_CHECK_INTERVAL: Duration = Duration::from_secs(10);
+pub const HEALTH_SHUTDOWN_POLL: Duration = Duration::from_secs(1);
+
+// Command metrics
+/// Upper bounds of the command latency buckets. Slower commands go in one
+/// more bucket after these.
+pub const COMMAND_LATENCY_BUCKETS_MS: [i64; 8] = [50, 100, 250, 500, 1_000, 2_500, 5_000, 10_000];
+pub const COMMAND_ERROR_WINDOW: Duration = Duration::from_secs(60 * 5);
+/// A command is failing once more than this share of its calls in the window
+/// failed.
+pub const COMMAND_ERROR_RATE_THRESHOLD: f64 = 0.5;
+/// Windows with fewer failures than this are too noisy to warn about.
+pub const COMMAND_ERROR_MIN_FAILURES: usize = 5;
+/// After a warning, failures of that command aren't posted one by one for
+/// this long.
+pub const COMMAND_ERROR_SUPPRESSION: Duration = Duration::from_secs(60 * 30);
+
+// Vote batching
+pub const VOTE_BATCH_DELAY: Duration = Duration::from_millis(500);
+/// Votes are written early once this many are waiting.
+pub const VOTE_BATCH_SIZE: usize = 100;
 
 // Cache size
 pub const MAX_MESSAGES: u64 = 50_000;
@@ -41,13 +132,67 @@ pub const MEMBERS_TTI: Duration = Duration::from_secs(60 * 60);
 pub const MAX_STORED_RESPONSES: u64 = 100;
 pub const STORED_RESPONSES_TTI: Duration = Duration::from_secs(60 * 5);
 pub const MAX_STORED_AUTO_DELETES: usize = 1_000;
+pub const MAX_TRANSLATIONS: u64 = 1_000;
+pub const TRANSLATIONS_TTI: Duration = Duration::from_secs(60 * 60);
+pub const MAX_SELF_REMOVED_REACTIONS: u64 = 1_000;
+pub const SELF_REMOVED_REACTIONS_TTI: Duration = Duration::from_secs(60);
+pub const MAX_REACTION_REMOVE_FORBIDDEN: u64 = 10_000;
+pub const REACTION_REMOVE_FORBIDDEN_TTL: Duration = Duration::from_secs(60 * 10);
+pub const MAX_MESSAGE_FETCH_FORBIDDEN: u64 = 10_000;
+pub const MESSAGE_FETCH_FORBIDDEN_TTL: Duration = Duration::from_secs(60 * 10);
+pub const MAX_AUTOSTAR_CHANNEL_CHECKS: u64 = 10_000;
+pub const AUTOSTAR_CHANNEL_CHECKS_TTL: Duration = Duration::from_secs(60 * 5);
+// doubled after every failed load, up to the max
+pub const AUTOSTAR_CHANNEL_LOAD_RETRY: Duration = Duration::from_secs(5);
+pub const MAX_AUTOSTAR_CHANNEL_LOAD_RETRY: Duration = Duration::from_secs(60 * 5);
+pub const MAX_MESSAGE_SOURCES: u64 = 10_000;
+pub const MESSAGE_SOURCES_TTL: Duration = Duration::from_secs(60 * 60 * 24);
+pub const MAX_AUTOSTAR_REACTION_CHECKS: u64 = 10_000;
+pub const AUTOSTAR_REACTION_CHECKS_TTL: Duration = Duration::from_secs(60 * 5);
+pub const MAX_AUTOSTAR_REGEXES: u64 = 1_000;
+pub const AUTOSTAR_REGEXES_TTI: Duration = Duration::from_secs(60 * 60);
+pub const MAX_POST_ORIGINALS: u64 = 50_000;
+pub const POST_ORIGINALS_TTI: Duration = Duration::from_secs(60 * 60);
+pub const MAX_REMOVED_EMOJI_WARNINGS: u64 = 10_000;
+/// A removed vote emoji is only mentioned to the guild owner once in this time.
+pub const REMOVED_EMOJI_WARNINGS_TTL: Duration = Duration::from_secs(60 * 60 * 24);
+pub const MAX_FEATURE_FLAGS: u64 = 100;
+/// How long until a rollout change made by another process is picked up.
+pub const FEATURE_FLAGS_TTL: Duration = Duration::from_secs(60);
+pub const MAX_GUILD_PREMIUM: u64 = 50_000;
+/// Premium changes are also pushed with `NOTIFY`, this is the fallback.
+pub const GUILD_PREMIUM_TTL: Duration = Duration::from_secs(60 * 5);
+
+// Bulk database writes
+pub const BULK_STATEMENT_TIMEOUT: Duration = Duration::from_secs(10);
+pub const BULK_CHUNK_SIZE: usize = 1_000;
+
+// Guild cache warm-up
+/// Channels and threads from `GuildCreate` are cached this many at a time.
+pub const GUILD_HYDRATION_CHUNK_SIZE: usize = 250;
+
+// Cache stats
+pub const CACHE_STATS_LARGEST_GUILDS: usize = 5;
+pub const CACHE_STATS_LOG_INTERVAL: Duration = Duration::from_secs(60 * 10);
+
+// Guild log
+pub const MAX_EMBED_DESC_LENGTH: usize = 4_096;
+/// Longer old and new values of changed settings are cut off.
+pub const MAX_LOGGED_SETTING_LENGTH: usize = 100;
 
 // Cooldowns
 pub const AUTOSTAR_COOLDOWN: (u64, Duration) = (5, Duration::from_secs(20));
 pub const PREM_AUTOSTAR_COOLDOWN: (u64, Duration) = (100, Duration::from_secs(10));
 pub const MESSAGE_EDIT: (u64, Duration) = (2, Duration::from_secs(10));
 pub const XP_REFRESH: (u64, Duration) = (1, Duration::from_secs(60 * 10));
-pub const VOTE_RECOUNT: (u64, Duration) = (5, Duration::from_secs(30));
+pub const VOTE_RECOUNT: (u64, Duration) = (2, Duration::from_secs(60));
+pub const TRANSLATE: (u64, Duration) = (5, Duration::from_secs(60));
+pub const REACTION_REMOVE: (u64, Duration) = (5, Duration::from_secs(5));
+pub const INSPECT: (u64, Duration) = (5, Duration::from_secs(60));
+/// Entries per guild log channel.
+pub const GUILD_LOG: (u64, Duration) = (10, Duration::from_secs(60));
+/// Reaction removals are dropped if they'd have to wait longer than this.
+pub const MAX_REACTION_REMOVE_WAIT: Duration = Duration::from_secs(15);
 
 // Common Validation
 pub const MAX_NAME_LENGTH: u32 = 32;
@@ -59,17 +204,34 @@ pub const MAX_OLDER_THAN: i64 = YEAR_SECONDS * 50;
 // AutoStar Validation
 pub const MAX_MAX_CHARS: i16 = 5_000;
 pub const MAX_MIN_CHARS: i16 = 5_000;
+pub const MAX_FORMAT_EXAMPLE_LENGTH: usize = 500;
+pub const MAX_MAX_ATTACHMENTS: i16 = 10;
+pub const MAX_MIN_VOTE_SPREAD: i64 = 60 * 60;
+pub const MIN_VOTE_SPREAD_VOTES: i64 = 3;
 
 pub const MAX_ASC_EMOJIS: usize = 3;
 pub const MAX_PREM_ASC_EMOJIS: usize = 200;
 pub const MAX_AUTOSTAR: i64 = 3;
 pub const MAX_PREM_AUTOSTAR: i64 = 500;
 
+// AutoStar reactions
+pub const AUTOSTAR_REACT_ATTEMPTS: u32 = 3;
+/// Doubled after every failed attempt.
+pub const AUTOSTAR_REACT_RETRY_DELAY: Duration = Duration::from_secs(1);
+/// How long after reacting the reactions are checked.
+pub const AUTOSTAR_VERIFY_DELAY: Duration = Duration::from_secs(30);
+
+// Mod notes
+pub const MAX_MOD_NOTE_LEN: u16 = 500;
+pub const MAX_MOD_NOTES_PER_MESSAGE: i64 = 20;
+pub const MOD_NOTES_PER_PAGE: usize = 5;
+
 // Starboard Validation
 pub const MIN_REQUIRED: i16 = 1;
 pub const MAX_REQUIRED: i16 = 10_000;
 pub const MIN_REQUIRED_REMOVE: i16 = -10_000;
 pub const MAX_REQUIRED_REMOVE: i16 = 9_999;
+pub const MAX_MAX_POSTS_PER_AUTHOR: i16 = 100;
 pub const MIN_XP_MULTIPLIER: f32 = -10.0;
 pub const MAX_XP_MULTIPLIER: f32 = 10.0;
 pub const MAX_COOLDOWN_CAPACITY: i16 = 3600;
@@ -79,6 +241,8 @@ pub const MAX_COOLDOWN_CAPACITY: i16 = 3600;
 pub const MAX_COOLDOWN_PERIOD: i16 = 3600;
 
 pub const MAX_VOTE_EMOJIS: usize = 3;
+pub const MAX_EMOJI_TIERS: usize = 10;
+pub const MAX_EMOJI_WEIGHT: i16 = 100;
 pub const MAX_PREM_VOTE_EMOJIS: usize = 200;
 pub const MAX_STARBOARDS: i64 = 3;
 pub const MAX_PREM_STARBOARDS: i64 = 200;
@@ -102,8 +266,74 @@ pub const MAX_EXCLUSIVE_GROUPS: i64 = 10;
 // PermRole Validation
 pub const MAX_PERMROLES: i64 = 50;
 
+// Ignored webhooks and applications
+pub const MAX_IGNORED_SOURCES: usize = 50;
+pub const IGNORE_SUGGESTIONS: usize = 10;
+
+// Blocked voters
+pub const MAX_BLOCKED_VOTERS: i64 = 10_000;
+
+// Id imports
+pub const MAX_ID_IMPORT_SIZE: u64 = 512 * 1024;
+pub const MAX_ID_IMPORT_ENTRIES: usize = 10_000;
+pub const MAX_ID_IMPORT_ERRORS: usize = 20;
+pub const BLOCKLIST_PAGE_SIZE: usize = 20;
+
+// Votes button
+pub const VOTES_PAGE_SIZE: usize = 25;
+
 // XP-based Award Role Validation
 pub const MAX_XPROLES: i64 = 50;
 
+// Translation
+pub const MAX_TRANSLATE_CHARS: usize = 1_500;
+
+// XP Import
+pub const MAX_XP_IMPORT_SIZE: u64 = 2 * 1024 * 1024;
+pub const MAX_XP_IMPORT_ROWS: usize = 50_000;
+pub const MAX_XP_IMPORT_ERRORS: usize = 25;
+pub const XP_IMPORT_PREVIEW: usize = 20;
+pub const XPR_BULK_REFRESH_DELAY: Duration = Duration::from_millis(250);
+
+// XP Seasons
+pub const XP_SEASON_ANNOUNCE_TOP: i64 = 10;
+
+// Starboard Migration
+pub const MIGRATE_PAGE_SIZE: u16 = 100;
+pub const MIGRATE_PAGE_DELAY: Duration = Duration::from_secs(2);
+
+// Purging Users
+pub const PURGE_USER_BULK_SIZE: usize = 100;
+pub const PURGE_USER_DELETE_DELAY: Duration = Duration::from_secs(1);
+pub const PURGE_USER_PROGRESS_EVERY: usize = 10;
+/// Discord only bulk deletes messages younger than two weeks. Posts close to
+/// that are deleted one by one.
+pub const BULK_DELETE_MAX_AGE: Duration = Duration::from_secs(60 * 60 * 24 * 13);
+
+// Vote Recounting
+pub const RECOUNT_PAGE_SIZE: u16 = 100;
+
+// Companion actions
+pub const PERFORMED_ACTIONS_RETENTION_HOURS: i64 = 24;
+pub const PERFORMED_ACTIONS_EXPIRE_DELAY: Duration = Duration::from_secs(60 * 60);
+
+// NSFW Channel Toggles
+pub const NSFW_REFRESH_LIMIT: i64 = 100;
+pub const NSFW_REFRESH_DELAY: Duration = Duration::from_millis(250);
+
+// Starboard schedules
+pub const STARBOARD_SCHEDULE_DELAY: Duration = Duration::from_secs(60);
+/// Messages that qualified while a starboard was disabled are sent once it's
+/// enabled again, up to this many.
+pub const ENABLE_REFRESH_LIMIT: i64 = 100;
+pub const ENABLE_REFRESH_DELAY: Duration = Duration::from_millis(250);
+
 // Position-based Award Role Validation
 pub const MAX_POSROLES: i64 = 50;
+
+// Competitions
+pub const MAX_UNFINISHED_COMPETITIONS: i64 = 5;
+pub const MAX_COMPETITION_LENGTH: i64 = 60 * 60 * 24 * 92;
+pub const MAX_COMPETITION_PRIZE_LENGTH: usize = 200;
+pub const COMPETITION_WINNERS: usize = 3;
+pub const COMPETITION_HISTORY_LENGTH: i64 = 10;
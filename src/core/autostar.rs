@@ -1,27 +1,35 @@
 use std::{sync::Arc, time::Duration};
 
+use regex::Regex;
+use tracing::Instrument;
 use twilight_model::id::{
-    marker::{ChannelMarker, GuildMarker, MessageMarker},
+    marker::{ChannelMarker, GuildMarker, MessageMarker, UserMarker},
     Id,
 };
 
 use crate::{
     cache::{models::message::CachedMessage, MessageResult},
     client::bot::StarboardBot,
+    constants,
     core::emoji::{EmojiCommon, SimpleEmoji},
     database::{
         models::autostar_channel_filter_group::AutostarChannelFilterGroup, AutoStarChannel,
     },
     errors::StarboardResult,
-    utils::{id_as_i64::GetI64, notify},
+    utils::{get_status, id_as_i64::GetI64, message_link::fmt_message_link, notify},
 };
 
 use super::{
-    filters::FilterEvaluater, has_image::has_image, premium::is_premium::is_guild_premium,
+    filters::FilterEvaluater,
+    forum_tags::{format_tags, tags_allowed},
+    guild_log::LogEntry,
+    has_image::has_image,
+    ignored_sources::is_ignored_source,
+    premium::is_premium::is_guild_premium,
 };
 
 pub async fn handle(
-    bot: &StarboardBot,
+    bot: &Arc<StarboardBot>,
     guild_id: Id<GuildMarker>,
     autostar_channel_id: Id<ChannelMarker>,
     channel_id: Id<ChannelMarker>,
@@ -32,13 +40,15 @@ pub async fn handle(
     if !bot
         .cache
         .autostar_channel_ids
-        .contains(&autostar_channel_id)
+        .contains(&bot.pool, autostar_channel_id)
+        .await?
     {
         return Ok(());
     }
 
     // Check cooldown
-    if is_guild_premium(bot, guild_id.get_i64(), true).await? {
+    let is_premium = is_guild_premium(bot, guild_id.get_i64(), true).await?;
+    if is_premium {
         if bot
             .cooldowns
             .prem_autostar_send
@@ -53,11 +63,17 @@ pub async fn handle(
 
     // Fetch the autostar channels
     let asc = AutoStarChannel::list_by_channel(&bot.pool, autostar_channel_id.get_i64()).await?;
-    let asc: Vec<_> = asc.into_iter().filter(|a| !a.premium_locked).collect();
+    let asc: Vec<_> = asc
+        .into_iter()
+        .filter(|a| !a.premium_locked && !a.channel_deleted)
+        .collect();
 
     // If none, remove the channel id from the cache
     if asc.is_empty() {
-        bot.cache.autostar_channel_ids.remove(&autostar_channel_id);
+        bot.cache
+            .autostar_channel_ids
+            .remove(&bot.pool, autostar_channel_id)
+            .await?;
         return Ok(());
     }
 
@@ -70,11 +86,23 @@ pub async fn handle(
             msg
         }
     };
+    if is_ignored_source(bot, guild_id.get_i64(), &message).await? {
+        return Ok(());
+    }
 
     // Handle the autostar channels
     let mut to_react = Vec::new();
     for a in asc {
-        let status = get_status(bot, &a, guild_id, channel_id, message_id, message.clone()).await?;
+        let status = get_status(
+            bot,
+            &a,
+            guild_id,
+            channel_id,
+            message_id,
+            message.clone(),
+            is_premium,
+        )
+        .await?;
 
         if matches!(status, Status::InvalidStay) {
             continue;
@@ -88,11 +116,7 @@ pub async fn handle(
                 .await?
                 .map_or(false, |u| !u.is_bot);
             if send {
-                let to_send = {
-                    format!(
-                        "Your message in <#{channel_id}> was deleted for the following reason(s):\n"
-                    ) + &reasons.join("\n")
-                };
+                let to_send = invalid_message_dm(channel_id, &reasons, &message.content);
                 notify::notify(bot, message.author_id, &to_send).await?;
             }
 
@@ -102,14 +126,137 @@ pub async fn handle(
         to_react.extend(Vec::<SimpleEmoji>::from_stored(a.emojis));
     }
 
-    for emoji in to_react {
-        let _ = bot
+    if to_react.is_empty() {
+        return Ok(());
+    }
+    for emoji in &to_react {
+        react_with_retry(bot, guild_id, channel_id, message_id, emoji).await;
+    }
+    spawn_verify_reactions(bot.clone(), guild_id, channel_id, message_id, to_react);
+
+    Ok(())
+}
+
+/// Adds an autostar reaction, retrying errors that are likely to go away.
+/// Returns whether the reaction was added.
+async fn react_with_retry(
+    bot: &StarboardBot,
+    guild_id: Id<GuildMarker>,
+    channel_id: Id<ChannelMarker>,
+    message_id: Id<MessageMarker>,
+    emoji: &SimpleEmoji,
+) -> bool {
+    let mut delay = constants::AUTOSTAR_REACT_RETRY_DELAY;
+    for attempt in 1..=constants::AUTOSTAR_REACT_ATTEMPTS {
+        let ret = bot
             .http
             .create_reaction(channel_id, message_id, &emoji.reactable())
             .await;
+        let why = match ret {
+            Ok(_) => return true,
+            Err(why) => why,
+        };
+
+        let status = get_status::get_status(&why);
+        if !is_retryable(status) {
+            if status == Some(403) {
+                let entry = LogEntry::MissingPermissions {
+                    channel_id,
+                    action: "add autostar reactions".to_string(),
+                };
+                bot.guild_log.send(bot, guild_id, entry).await;
+            }
+            return false;
+        }
+        if attempt == constants::AUTOSTAR_REACT_ATTEMPTS {
+            tracing::warn!(
+                "Adding autostar reaction to {} failed after {} attempts: {}",
+                fmt_message_link(guild_id, channel_id, message_id),
+                attempt,
+                why
+            );
+            return false;
+        }
+
+        tokio::time::sleep(delay).await;
+        delay *= 2;
     }
 
-    Ok(())
+    false
+}
+
+/// Checks that the reactions are still there once the message settled, and
+/// adds missing ones once more.
+fn spawn_verify_reactions(
+    bot: Arc<StarboardBot>,
+    guild_id: Id<GuildMarker>,
+    channel_id: Id<ChannelMarker>,
+    message_id: Id<MessageMarker>,
+    emojis: Vec<SimpleEmoji>,
+) {
+    let task = async move {
+        if bot.cache.autostar_reaction_checks.contains_key(&message_id) {
+            return;
+        }
+        bot.cache
+            .autostar_reaction_checks
+            .insert(message_id, ())
+            .await;
+
+        tokio::time::sleep(constants::AUTOSTAR_VERIFY_DELAY).await;
+
+        let Some(app_id) = bot.application.read().await.as_ref().map(|app| app.id) else {
+            return;
+        };
+        let bot_id: Id<UserMarker> = app_id.cast();
+        for emoji in emojis {
+            match has_reacted(&bot, channel_id, message_id, &emoji, bot_id).await {
+                Ok(true) => (),
+                Ok(false) => {
+                    react_with_retry(&bot, guild_id, channel_id, message_id, &emoji).await;
+                }
+                // the message was deleted, or can't be read anymore
+                Err(_) => return,
+            }
+        }
+    };
+    tokio::spawn(task.in_current_span());
+}
+
+async fn has_reacted(
+    bot: &StarboardBot,
+    channel_id: Id<ChannelMarker>,
+    message_id: Id<MessageMarker>,
+    emoji: &SimpleEmoji,
+    bot_id: Id<UserMarker>,
+) -> StarboardResult<bool> {
+    let users = bot
+        .http
+        .reactions(channel_id, message_id, &emoji.reactable())
+        .after(reactors_after(bot_id))
+        .limit(1)
+        .unwrap()
+        .await?
+        .models()
+        .await?;
+
+    Ok(users.first().is_some_and(|u| u.id == bot_id))
+}
+
+/// Whether adding a reaction might work if tried again.
+fn is_retryable(status: Option<u16>) -> bool {
+    // deleted messages, missing permissions and unknown emojis won't go away
+    // by trying again
+    match status {
+        None => true,
+        Some(status) => status == 429 || status >= 500,
+    }
+}
+
+/// Reactors are listed by id, so listing them after this returns the bot
+/// first if it reacted.
+fn reactors_after(bot_id: Id<UserMarker>) -> Id<UserMarker> {
+    Id::new(bot_id.get() - 1)
 }
 
 enum Status {
@@ -125,7 +272,25 @@ async fn get_status(
     channel_id: Id<ChannelMarker>,
     message_id: Id<MessageMarker>,
     event: Arc<CachedMessage>,
+    is_premium: bool,
 ) -> StarboardResult<Status> {
+    // the format is checked first, so that a message in the wrong format is
+    // only ever told about the format
+    let format_regex = match &asc.required_format_regex {
+        Some(pattern) if is_premium => compiled_regex(bot, asc.id, pattern).await,
+        _ => None,
+    };
+    if let Some(re) = format_regex {
+        if !re.is_match(&event.content) {
+            if !asc.delete_invalid {
+                return Ok(Status::InvalidStay);
+            }
+
+            let reason = format_reason(asc.format_example.as_deref());
+            return Ok(Status::InvalidRemove(vec![reason]));
+        }
+    }
+
     let mut invalid = Vec::new();
 
     if asc.min_chars != 0 && event.content.len() < asc.min_chars as usize {
@@ -141,6 +306,21 @@ async fn get_status(
             ));
         }
     }
+    let (matches, not_matches) = if is_premium {
+        (
+            compiled_pattern(bot, asc.id, asc.matches.as_deref()).await,
+            compiled_pattern(bot, asc.id, asc.not_matches.as_deref()).await,
+        )
+    } else {
+        (None, None)
+    };
+    invalid.extend(rule_reasons(
+        &event.content,
+        event.attachments.len(),
+        asc.max_attachments,
+        matches.as_deref(),
+        not_matches.as_deref(),
+    ));
     if asc.require_image && !has_image(&event.embeds, &event.attachments) {
         tokio::time::sleep(Duration::from_secs(3)).await;
 
@@ -160,6 +340,22 @@ async fn get_status(
         }
     }
 
+    if !asc.required_tags.is_empty() || !asc.excluded_tags.is_empty() {
+        let tags = bot.cache.fog_thread_tags(bot, guild_id, channel_id).await?;
+        if !tags_allowed(&asc.required_tags, &[], tags.as_deref()) {
+            invalid.push(format!(
+                "- Your post must have one of these tags: {}.",
+                format_tags(bot, guild_id, &asc.required_tags),
+            ));
+        }
+        if !tags_allowed(&[], &asc.excluded_tags, tags.as_deref()) {
+            invalid.push(format!(
+                "- Your post can't have these tags: {}.",
+                format_tags(bot, guild_id, &asc.excluded_tags),
+            ));
+        }
+    }
+
     let filter_groups =
         AutostarChannelFilterGroup::list_by_autostar_channel(&bot.pool, asc.id).await?;
     let mut filters = FilterEvaluater::new(
@@ -184,3 +380,237 @@ async fn get_status(
         Ok(Status::InvalidStay)
     }
 }
+
+/// Compiles a regex of an autostar channel. Compiled regexes are cached by
+/// pattern, so editing a setting never leaves a stale regex behind.
+async fn compiled_regex(bot: &StarboardBot, asc_id: i32, pattern: &str) -> Option<Arc<Regex>> {
+    if let Some(re) = bot.cache.autostar_regexes.get(pattern) {
+        return Some(re);
+    }
+
+    let re = match Regex::new(pattern) {
+        Ok(re) => Arc::new(re),
+        Err(why) => {
+            tracing::warn!("Invalid regex for autostar channel {asc_id}: {why}");
+            return None;
+        }
+    };
+    bot.cache
+        .autostar_regexes
+        .insert(pattern.to_string(), re.clone())
+        .await;
+
+    Some(re)
+}
+
+fn format_reason(example: Option<&str>) -> String {
+    let mut reason = "- Your message doesn't match the required format.".to_string();
+    if let Some(example) = example {
+        reason.push_str(&format!(" For example:\n```\n{example}\n```"));
+    }
+    reason
+}
+
+async fn compiled_pattern(
+    bot: &StarboardBot,
+    asc_id: i32,
+    pattern: Option<&str>,
+) -> Option<Arc<Regex>> {
+    compiled_regex(bot, asc_id, pattern?).await
+}
+
+/// The reasons a message breaks the `max-attachments`, `matches` and
+/// `not-matches` rules.
+fn rule_reasons(
+    content: &str,
+    attachments: usize,
+    max_attachments: Option<i16>,
+    matches: Option<&Regex>,
+    not_matches: Option<&Regex>,
+) -> Vec<String> {
+    let mut invalid = Vec::new();
+
+    if let Some(max_attachments) = max_attachments {
+        if attachments > max_attachments as usize {
+            invalid.push(format!(
+                "- Your message cannot have more than {max_attachments} attachment(s).",
+            ));
+        }
+    }
+    if matches.is_some_and(|re| !re.is_match(content)) {
+        invalid.push("- Your message doesn't match the required pattern.".to_string());
+    }
+    if not_matches.is_some_and(|re| re.is_match(content)) {
+        invalid.push("- Your message matches a pattern that isn't allowed.".to_string());
+    }
+
+    invalid
+}
+
+/// The DM sent when a message is deleted for not meeting the requirements.
+/// The message is quoted back, so that it can be fixed and sent again without
+/// typing it out.
+fn invalid_message_dm(channel_id: Id<ChannelMarker>, reasons: &[String], content: &str) -> String {
+    let mut dm = format!(
+        "Your message in <#{channel_id}> was deleted for the following reason(s):\n{}",
+        reasons.join("\n")
+    );
+    if content.is_empty() {
+        return dm;
+    }
+
+    dm.push_str("\n\nHere's what you sent:\n");
+    let mut remaining = constants::MAX_MESSAGE_LENGTH.saturating_sub(dm.chars().count());
+    for line in content.lines() {
+        let line = format!("> {line}\n");
+        let len = line.chars().count();
+        if len > remaining {
+            // leave room for the ellipsis
+            let fits = remaining.saturating_sub(2);
+            dm.extend(line.chars().take(fits));
+            dm.push('…');
+            break;
+        }
+        dm.push_str(&line);
+        remaining -= len;
+    }
+
+    dm
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn channel_id() -> Id<ChannelMarker> {
+        Id::new(123)
+    }
+
+    fn regex(pattern: &str) -> Regex {
+        Regex::new(pattern).unwrap()
+    }
+
+    #[test]
+    fn no_rules() {
+        assert!(rule_reasons("anything", 20, None, None, None).is_empty());
+    }
+
+    #[test]
+    fn attachment_cap() {
+        assert!(rule_reasons("", 2, Some(2), None, None).is_empty());
+        assert_eq!(
+            rule_reasons("", 3, Some(2), None, None),
+            vec!["- Your message cannot have more than 2 attachment(s).".to_string()]
+        );
+        assert_eq!(rule_reasons("", 1, Some(0), None, None).len(), 1);
+    }
+
+    #[test]
+    fn patterns() {
+        let matches = regex("^Title:");
+        let not_matches = regex("(?i)spoiler");
+
+        assert!(rule_reasons("Title: a", 0, None, Some(&matches), Some(&not_matches)).is_empty());
+        assert_eq!(
+            rule_reasons("a", 0, None, Some(&matches), None),
+            vec!["- Your message doesn't match the required pattern.".to_string()]
+        );
+        assert_eq!(
+            rule_reasons(
+                "Title: SPOILER",
+                0,
+                None,
+                Some(&matches),
+                Some(&not_matches)
+            ),
+            vec!["- Your message matches a pattern that isn't allowed.".to_string()]
+        );
+    }
+
+    #[test]
+    fn every_broken_rule() {
+        let matches = regex("^Title:");
+        let not_matches = regex("spoiler");
+
+        assert_eq!(
+            rule_reasons("spoiler", 5, Some(1), Some(&matches), Some(&not_matches)).len(),
+            3
+        );
+    }
+
+    #[test]
+    fn format_reason_with_example() {
+        assert_eq!(
+            format_reason(None),
+            "- Your message doesn't match the required format."
+        );
+        assert_eq!(
+            format_reason(Some("Title: ...")),
+            "- Your message doesn't match the required format. For example:\n```\nTitle: ...\n```"
+        );
+    }
+
+    #[test]
+    fn dm_quotes_the_message() {
+        let dm = invalid_message_dm(
+            channel_id(),
+            &[format_reason(None)],
+            "first line\nsecond line",
+        );
+
+        assert_eq!(
+            dm,
+            "Your message in <#123> was deleted for the following reason(s):\n\
+            - Your message doesn't match the required format.\n\n\
+            Here's what you sent:\n\
+            > first line\n\
+            > second line\n"
+        );
+    }
+
+    #[test]
+    fn dm_without_content() {
+        let dm = invalid_message_dm(channel_id(), &[format_reason(None)], "");
+
+        assert!(!dm.contains("Here's what you sent"));
+    }
+
+    #[test]
+    fn dm_is_cut_off() {
+        let content = "a".repeat(constants::MAX_MESSAGE_LENGTH);
+        let dm = invalid_message_dm(channel_id(), &[format_reason(None)], &content);
+
+        assert!(dm.chars().count() <= constants::MAX_MESSAGE_LENGTH);
+        assert!(dm.ends_with("aa…"));
+    }
+
+    #[test]
+    fn dm_cuts_off_by_chars() {
+        let content = "⭐\n".repeat(constants::MAX_MESSAGE_LENGTH);
+        let dm = invalid_message_dm(channel_id(), &[format_reason(None)], &content);
+
+        assert!(dm.chars().count() <= constants::MAX_MESSAGE_LENGTH);
+        assert!(dm.ends_with('…'));
+    }
+
+    #[test]
+    fn retries_transient_errors() {
+        // network errors have no status
+        assert!(is_retryable(None));
+        assert!(is_retryable(Some(429)));
+        assert!(is_retryable(Some(500)));
+        assert!(is_retryable(Some(503)));
+    }
+
+    #[test]
+    fn gives_up_on_client_errors() {
+        assert!(!is_retryable(Some(400)));
+        assert!(!is_retryable(Some(403)));
+        assert!(!is_retryable(Some(404)));
+    }
+
+    #[test]
+    fn bot_is_listed_first() {
+        assert_eq!(reactors_after(Id::new(100)), Id::new(99));
+    }
+}
@@ -0,0 +1,50 @@
+//! Users whose votes never count in a guild (`/blocklist`). Their reactions
+//! are ignored (or removed) as soon as they're added, and recounts leave them
+//! out, but votes they cast before being blocked are kept until then.
+
+use std::{collections::HashMap, sync::Arc};
+
+use crate::{client::bot::StarboardBot, database::BlockedVoter, errors::StarboardResult};
+
+/// Blocked user id -> whether their vote reactions are removed.
+pub type BlockedVoters = HashMap<i64, bool>;
+
+pub async fn get_blocked_voters(
+    bot: &StarboardBot,
+    guild_id: i64,
+) -> StarboardResult<Arc<BlockedVoters>> {
+    let cached = bot
+        .cache
+        .guild_blocked_voters
+        .with(&guild_id, |_, blocked| {
+            blocked.as_ref().map(|b| b.value().clone())
+        });
+    if let Some(blocked) = cached {
+        return Ok(blocked);
+    }
+
+    let blocked: BlockedVoters = BlockedVoter::list_by_guild(&bot.pool, guild_id)
+        .await?
+        .into_iter()
+        .map(|b| (b.user_id, b.remove_reactions))
+        .collect();
+    let blocked = Arc::new(blocked);
+    bot.cache
+        .guild_blocked_voters
+        .insert(guild_id, blocked.clone());
+
+    Ok(blocked)
+}
+
+/// `None` if the user isn't blocked, otherwise whether their vote reactions
+/// should be removed.
+pub async fn blocked_voter(
+    bot: &StarboardBot,
+    guild_id: i64,
+    user_id: i64,
+) -> StarboardResult<Option<bool>> {
+    Ok(get_blocked_voters(bot, guild_id)
+        .await?
+        .get(&user_id)
+        .copied())
+}
@@ -0,0 +1,247 @@
+//! Ending competitions and announcing their winners.
+
+use std::{collections::HashMap, sync::Arc};
+
+use chrono::{DateTime, Utc};
+use twilight_model::channel::message::{embed::Embed, AllowedMentions};
+use twilight_util::builder::embed::EmbedFieldBuilder;
+
+use crate::{
+    client::bot::StarboardBot,
+    constants,
+    core::vote_weights::vote_points,
+    database::{
+        models::competition::{CompetitionVote, CompetitionWinner},
+        Competition, DbMessage, Starboard,
+    },
+    errors::StarboardResult,
+    utils::{embed, get_status::get_status, into_id::IntoId, message_link::fmt_message_link},
+};
+
+pub async fn loop_finish_competitions(bot: Arc<StarboardBot>) {
+    loop {
+        tokio::time::sleep(constants::FINISH_COMPETITIONS_DELAY).await;
+
+        let due = match Competition::list_due(&bot.pool).await {
+            Ok(due) => due,
+            Err(why) => {
+                bot.handle_error(&why.into()).await;
+                continue;
+            }
+        };
+
+        for competition in due {
+            if !bot.owns_guild(competition.guild_id as u64) {
+                continue;
+            }
+            if let Err(why) = finish_competition(&bot, &competition).await {
+                bot.handle_error(&why).await;
+            }
+        }
+    }
+}
+
+/// Records the winners of a competition that has ended, then announces them.
+pub async fn finish_competition(
+    bot: &StarboardBot,
+    competition: &Competition,
+) -> StarboardResult<()> {
+    let Some(starboard) = Starboard::get(&bot.pool, competition.starboard_id).await? else {
+        // the starboard was deleted, which also deletes the competition
+        return Ok(());
+    };
+
+    let vote_starboard_ids = if starboard.aggregate {
+        starboard.source_starboards.clone()
+    } else {
+        vec![starboard.id]
+    };
+    let votes = competition
+        .list_votes(&bot.pool, &vote_starboard_ids)
+        .await?;
+    let ranked = rank_by_points(
+        &votes,
+        competition.starts_at,
+        competition.ends_at,
+        constants::COMPETITION_WINNERS,
+    );
+
+    let mut winners = Vec::new();
+    for (message_id, points) in ranked {
+        let Some(msg) = DbMessage::get(&bot.pool, message_id).await? else {
+            continue;
+        };
+        winners.push(CompetitionWinner {
+            competition_id: competition.id,
+            place: winners.len() as i16 + 1,
+            message_id,
+            channel_id: msg.channel_id,
+            author_id: msg.author_id,
+            points,
+        });
+    }
+    competition.finish(&bot.pool, &winners).await?;
+
+    let embed = build_results_embed(competition, &starboard.name, &winners);
+    let ret = bot
+        .http
+        .create_message(competition.announce_channel_id.into_id())
+        .allowed_mentions(Some(&AllowedMentions::default()))
+        .embeds(&[embed])?
+        .await;
+
+    match ret {
+        Ok(_) => Ok(()),
+        // the bot can't post in the channel anymore, but the winners are
+        // still listed in `/competition history`.
+        Err(why) if matches!(get_status(&why), Some(403 | 404)) => Ok(()),
+        Err(why) => Err(why.into()),
+    }
+}
+
+/// The messages that gained the most points during the competition, as
+/// (message id, points), best first. Votes cast outside the window never
+/// count, so neither do points a message had before it started. Messages
+/// that didn't gain any points can't win.
+pub fn rank_by_points(
+    votes: &[CompetitionVote],
+    starts_at: DateTime<Utc>,
+    ends_at: DateTime<Utc>,
+    limit: usize,
+) -> Vec<(i64, i32)> {
+    let mut points: HashMap<i64, i32> = HashMap::new();
+    for vote in votes {
+        if vote.created_at < starts_at || vote.created_at >= ends_at {
+            continue;
+        }
+        *points.entry(vote.message_id).or_default() += vote_points(vote.is_downvote, vote.weight);
+    }
+
+    let mut ranked: Vec<_> = points.into_iter().filter(|(_, p)| *p > 0).collect();
+    ranked.sort_by_key(|(message_id, points)| (-points, *message_id));
+    ranked.truncate(limit);
+    ranked
+}
+
+pub fn place_emoji(place: i16) -> &'static str {
+    match place {
+        1 => "🥇",
+        2 => "🥈",
+        3 => "🥉",
+        _ => "🏅",
+    }
+}
+
+pub fn format_winner(competition: &Competition, winner: &CompetitionWinner) -> String {
+    format!(
+        "{} <@{}> with {} points ([jump]({}))",
+        place_emoji(winner.place),
+        winner.author_id,
+        winner.points,
+        fmt_message_link(competition.guild_id, winner.channel_id, winner.message_id),
+    )
+}
+
+fn build_results_embed(
+    competition: &Competition,
+    starboard_name: &str,
+    winners: &[CompetitionWinner],
+) -> Embed {
+    let mut description = format!(
+        "The competition on '{}' ended <t:{}:R>.",
+        starboard_name,
+        competition.ends_at.timestamp()
+    );
+    if let Some(prize) = &competition.prize {
+        description.push_str(&format!("\n**Prize:** {prize}"));
+    }
+
+    let results = if winners.is_empty() {
+        "No posts gained points during this competition.".to_string()
+    } else {
+        winners
+            .iter()
+            .map(|w| format_winner(competition, w))
+            .collect::<Vec<_>>()
+            .join("\n")
+    };
+
+    embed::build()
+        .title("🏆 Competition Results")
+        .description(description)
+        .field(EmbedFieldBuilder::new("Winners", results))
+        .build()
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    fn at(day: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2023, 7, day, 0, 0, 0).unwrap()
+    }
+
+    fn vote(message_id: i64, day: u32, is_downvote: bool, weight: i16) -> CompetitionVote {
+        CompetitionVote {
+            message_id,
+            is_downvote,
+            weight,
+            created_at: at(day),
+        }
+    }
+
+    fn rank(votes: &[CompetitionVote]) -> Vec<(i64, i32)> {
+        rank_by_points(votes, at(10), at(20), constants::COMPETITION_WINNERS)
+    }
+
+    #[test]
+    fn earlier_points_dont_count() {
+        // 1 had 5 points before the competition, but only gained 1
+        let mut votes: Vec<_> = (0..5).map(|_| vote(1, 5, false, 1)).collect();
+        votes.push(vote(1, 12, false, 1));
+        votes.push(vote(2, 12, false, 1));
+        votes.push(vote(2, 13, false, 1));
+
+        assert_eq!(rank(&votes), vec![(2, 2), (1, 1)]);
+    }
+
+    #[test]
+    fn late_votes_dont_count() {
+        let votes = vec![
+            vote(1, 11, false, 1),
+            vote(2, 11, false, 1),
+            vote(2, 20, false, 1),
+            vote(2, 25, false, 1),
+        ];
+
+        assert_eq!(rank(&votes), vec![(1, 1), (2, 1)]);
+    }
+
+    #[test]
+    fn downvotes_and_weights() {
+        let votes = vec![
+            vote(1, 11, false, 3),
+            vote(1, 11, true, 1),
+            vote(2, 11, false, 1),
+            vote(3, 11, true, 2),
+        ];
+
+        assert_eq!(rank(&votes), vec![(1, 2), (2, 1)]);
+    }
+
+    #[test]
+    fn only_the_top_win() {
+        let votes: Vec<_> = (1..=5)
+            .flat_map(|id| (0..id).map(move |_| vote(id, 15, false, 1)))
+            .collect();
+
+        assert_eq!(rank(&votes), vec![(5, 5), (4, 4), (3, 3)]);
+    }
+
+    #[test]
+    fn no_votes() {
+        assert!(rank(&[]).is_empty());
+    }
+}
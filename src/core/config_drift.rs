@@ -0,0 +1,378 @@
+//! Starboard settings that can't work in their channel anymore, because the
+//! channel changed type or the bot lost a permission. These are found once a
+//! week (or with `star check-config`), stored as `ConfigWarning`s and shown in
+//! `/starboards view`. Settings are never changed here, and warnings go away
+//! once a check finds the problem fixed.
+
+use std::sync::Arc;
+
+use twilight_model::{
+    channel::{Channel, ChannelType},
+    guild::Permissions,
+    id::{
+        marker::{ChannelMarker, GuildMarker, RoleMarker, UserMarker},
+        Id,
+    },
+};
+use twilight_util::permission_calculator::PermissionCalculator;
+
+use crate::{
+    client::bot::StarboardBot,
+    constants,
+    database::{ConfigWarning, Starboard, StarboardOverride},
+    errors::StarboardResult,
+    utils::{get_status::get_status, id_as_i64::GetI64, into_id::IntoId},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Drift {
+    /// `use-webhook` is on, but the channel can't have webhooks.
+    WebhookChannelType,
+    /// `use-webhook` is on, but the bot can't manage webhooks.
+    WebhookPermission,
+    /// `clone-attachments` is on, but the bot can't attach files.
+    CloneAttachmentsPermission,
+    /// `attach-thread` is on, but the channel can't have threads.
+    ThreadChannelType,
+    /// `attach-thread` is on, but the bot can't create threads.
+    ThreadPermission,
+}
+
+impl Drift {
+    pub const ALL: [Self; 5] = [
+        Self::WebhookChannelType,
+        Self::WebhookPermission,
+        Self::CloneAttachmentsPermission,
+        Self::ThreadChannelType,
+        Self::ThreadPermission,
+    ];
+
+    /// The name stored in the database.
+    pub fn kind(self) -> &'static str {
+        match self {
+            Self::WebhookChannelType => "webhook-channel-type",
+            Self::WebhookPermission => "webhook-permission",
+            Self::CloneAttachmentsPermission => "clone-attachments-permission",
+            Self::ThreadChannelType => "thread-channel-type",
+            Self::ThreadPermission => "thread-permission",
+        }
+    }
+
+    pub fn from_kind(kind: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|d| d.kind() == kind)
+    }
+
+    pub fn describe(self) -> &'static str {
+        match self {
+            Self::WebhookChannelType => {
+                "`use-webhook` is enabled, but this channel can't have webhooks."
+            }
+            Self::WebhookPermission => {
+                "`use-webhook` is enabled, but I don't have the Manage Webhooks permission here."
+            }
+            Self::CloneAttachmentsPermission => {
+                "`clone-attachments` is enabled, but I don't have the Attach Files permission here."
+            }
+            Self::ThreadChannelType => {
+                "`attach-thread` is enabled, but threads can't be created in this channel."
+            }
+            Self::ThreadPermission => {
+                "`attach-thread` is enabled, but I don't have the Create Public Threads \
+                permission here."
+            }
+        }
+    }
+}
+
+/// The features that are checked. A feature counts as enabled if the
+/// starboard or any of its overrides enables it.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct DriftFeatures {
+    pub use_webhook: bool,
+    pub clone_attachments: bool,
+    pub attach_thread: bool,
+}
+
+/// Finds the problems with `features` for a starboard in a channel of type
+/// `kind`, where the bot has `permissions`.
+pub fn find_drift(
+    features: DriftFeatures,
+    kind: ChannelType,
+    permissions: Permissions,
+) -> Vec<Drift> {
+    let mut found = Vec::new();
+
+    if features.use_webhook {
+        if !can_have_webhooks(kind) {
+            found.push(Drift::WebhookChannelType);
+        } else if !permissions.contains(Permissions::MANAGE_WEBHOOKS) {
+            found.push(Drift::WebhookPermission);
+        }
+    }
+    if features.clone_attachments && !permissions.contains(Permissions::ATTACH_FILES) {
+        found.push(Drift::CloneAttachmentsPermission);
+    }
+    if features.attach_thread {
+        if !matches!(
+            kind,
+            ChannelType::GuildText | ChannelType::GuildAnnouncement
+        ) {
+            found.push(Drift::ThreadChannelType);
+        } else if !permissions.contains(Permissions::CREATE_PUBLIC_THREADS) {
+            found.push(Drift::ThreadPermission);
+        }
+    }
+
+    found
+}
+
+/// Threads use the webhooks of their parent channel.
+fn can_have_webhooks(kind: ChannelType) -> bool {
+    kind.is_thread()
+        || matches!(
+            kind,
+            ChannelType::GuildText
+                | ChannelType::GuildAnnouncement
+                | ChannelType::GuildForum
+                | ChannelType::GuildVoice
+                | ChannelType::GuildStageVoice
+        )
+}
+
+async fn drift_features(
+    bot: &StarboardBot,
+    starboard: &Starboard,
+) -> StarboardResult<DriftFeatures> {
+    let mut features = DriftFeatures {
+        use_webhook: starboard.settings.use_webhook,
+        clone_attachments: starboard.settings.clone_attachments,
+        attach_thread: starboard.settings.attach_thread,
+    };
+
+    for ov in StarboardOverride::list_by_starboard(&bot.pool, starboard.id).await? {
+        let values = ov.get_overrides()?;
+        features.use_webhook |= values.use_webhook == Some(true);
+        features.clone_attachments |= values.clone_attachments == Some(true);
+        features.attach_thread |= values.attach_thread == Some(true);
+    }
+
+    Ok(features)
+}
+
+async fn fetch_channel(
+    bot: &StarboardBot,
+    channel_id: Id<ChannelMarker>,
+) -> StarboardResult<Option<Channel>> {
+    match bot.http.channel(channel_id).await {
+        Ok(channel) => Ok(Some(channel.model().await?)),
+        Err(why) => match get_status(&why) {
+            Some(403) | Some(404) => Ok(None),
+            _ => Err(why.into()),
+        },
+    }
+}
+
+/// What's needed to calculate the bot's permissions in any channel of a
+/// guild.
+struct GuildPermissions {
+    guild_id: Id<GuildMarker>,
+    bot_id: Id<UserMarker>,
+    everyone: Permissions,
+    bot_roles: Vec<(Id<RoleMarker>, Permissions)>,
+}
+
+impl GuildPermissions {
+    async fn fetch(bot: &StarboardBot, guild_id: Id<GuildMarker>) -> StarboardResult<Option<Self>> {
+        let Some(bot_id) = bot
+            .application
+            .read()
+            .await
+            .as_ref()
+            .map(|app| app.id.cast())
+        else {
+            return Ok(None);
+        };
+        let Some(member) = bot.cache.fog_member(bot, guild_id, bot_id).await? else {
+            return Ok(None);
+        };
+        let roles = bot.http.roles(guild_id).await?.models().await?;
+
+        let mut everyone = Permissions::empty();
+        let mut bot_roles = Vec::new();
+        for role in roles {
+            if role.id.cast() == guild_id {
+                everyone = role.permissions;
+            } else if member.roles.contains(&role.id) {
+                bot_roles.push((role.id, role.permissions));
+            }
+        }
+
+        Ok(Some(Self {
+            guild_id,
+            bot_id,
+            everyone,
+            bot_roles,
+        }))
+    }
+
+    fn in_channel(&self, channel: &Channel) -> Permissions {
+        PermissionCalculator::new(self.guild_id, self.bot_id, self.everyone, &self.bot_roles)
+            .in_channel(
+                channel.kind,
+                channel.permission_overwrites.as_deref().unwrap_or_default(),
+            )
+    }
+}
+
+/// Checks every starboard of a guild and stores what was found. Returns the
+/// number of starboards with warnings, or `None` if the guild couldn't be
+/// checked.
+pub async fn check_guild(
+    bot: &StarboardBot,
+    guild_id: Id<GuildMarker>,
+) -> StarboardResult<Option<usize>> {
+    let Some(perms) = GuildPermissions::fetch(bot, guild_id).await? else {
+        return Ok(None);
+    };
+
+    let mut with_warnings = 0;
+    for starboard in Starboard::list_by_guild(&bot.pool, guild_id.get_i64()).await? {
+        if starboard.channel_deleted {
+            continue;
+        }
+        let Some(channel) = fetch_channel(bot, starboard.channel_id.into_id()).await? else {
+            continue;
+        };
+        // threads have the permissions of their parent
+        let perm_channel = match channel.parent_id {
+            Some(parent_id) if channel.kind.is_thread() => {
+                match fetch_channel(bot, parent_id).await? {
+                    Some(parent) => parent,
+                    None => continue,
+                }
+            }
+            _ => channel.clone(),
+        };
+
+        let features = drift_features(bot, &starboard).await?;
+        let found = find_drift(features, channel.kind, perms.in_channel(&perm_channel));
+        if !found.is_empty() {
+            with_warnings += 1;
+        }
+
+        let kinds: Vec<_> = found.into_iter().map(|d| d.kind().to_string()).collect();
+        ConfigWarning::set_for_starboard(&bot.pool, starboard.id, starboard.guild_id, &kinds)
+            .await?;
+    }
+
+    Ok(Some(with_warnings))
+}
+
+pub async fn loop_check_config_drift(bot: Arc<StarboardBot>) {
+    loop {
+        tokio::time::sleep(constants::CONFIG_DRIFT_CHECK_DELAY).await;
+
+        let guild_ids = bot.cache.guilds.fold(Vec::new(), |mut ids, id, _| {
+            ids.push(*id);
+            ids
+        });
+        for guild_id in guild_ids {
+            if !bot.owns_guild(guild_id.get()) {
+                continue;
+            }
+            if let Err(why) = check_guild(&bot, guild_id).await {
+                bot.handle_error(&why).await;
+            }
+            tokio::time::sleep(constants::CONFIG_DRIFT_GUILD_DELAY).await;
+        }
+    }
+}
+
+/// The descriptions of the stored warnings for a starboard.
+pub async fn describe_warnings(
+    bot: &StarboardBot,
+    starboard_id: i32,
+) -> StarboardResult<Vec<&'static str>> {
+    let warnings = ConfigWarning::list_by_starboard(&bot.pool, starboard_id).await?;
+    Ok(warnings
+        .iter()
+        .filter_map(|w| Drift::from_kind(&w.kind))
+        .map(Drift::describe)
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALL_FEATURES: DriftFeatures = DriftFeatures {
+        use_webhook: true,
+        clone_attachments: true,
+        attach_thread: true,
+    };
+
+    fn allowed() -> Permissions {
+        Permissions::MANAGE_WEBHOOKS
+            | Permissions::ATTACH_FILES
+            | Permissions::CREATE_PUBLIC_THREADS
+    }
+
+    #[test]
+    fn no_drift() {
+        assert!(find_drift(ALL_FEATURES, ChannelType::GuildText, allowed()).is_empty());
+        assert!(find_drift(
+            DriftFeatures::default(),
+            ChannelType::GuildCategory,
+            Permissions::empty()
+        )
+        .is_empty());
+    }
+
+    #[test]
+    fn missing_permissions() {
+        assert_eq!(
+            find_drift(ALL_FEATURES, ChannelType::GuildText, Permissions::empty()),
+            vec![
+                Drift::WebhookPermission,
+                Drift::CloneAttachmentsPermission,
+                Drift::ThreadPermission,
+            ]
+        );
+    }
+
+    #[test]
+    fn channel_type_beats_permissions() {
+        // the permission doesn't matter if the channel can't have it anyway
+        assert_eq!(
+            find_drift(
+                ALL_FEATURES,
+                ChannelType::GuildCategory,
+                Permissions::empty()
+            ),
+            vec![
+                Drift::WebhookChannelType,
+                Drift::CloneAttachmentsPermission,
+                Drift::ThreadChannelType,
+            ]
+        );
+    }
+
+    #[test]
+    fn forums_and_threads() {
+        // webhooks work in forums and threads, attached threads don't
+        for kind in [ChannelType::GuildForum, ChannelType::PublicThread] {
+            assert_eq!(
+                find_drift(ALL_FEATURES, kind, allowed()),
+                vec![Drift::ThreadChannelType]
+            );
+        }
+    }
+
+    #[test]
+    fn kinds_round_trip() {
+        for drift in Drift::ALL {
+            assert_eq!(Drift::from_kind(drift.kind()), Some(drift));
+        }
+        assert_eq!(Drift::from_kind("unknown"), None);
+    }
+}
@@ -0,0 +1,80 @@
+//! Starboards and autostar channels whose channel was deleted. They're
+//! skipped (instead of failing on every vote) until they're moved to another
+//! channel, or the channel comes back.
+
+use twilight_model::id::{
+    marker::{ChannelMarker, GuildMarker},
+    Id,
+};
+
+use crate::{
+    client::bot::StarboardBot,
+    database::{AutoStarChannel, Starboard},
+    errors::StarboardResult,
+    utils::{id_as_i64::GetI64, notify::notify},
+};
+
+pub async fn handle_channel_delete(
+    bot: &StarboardBot,
+    guild_id: Id<GuildMarker>,
+    channel_id: Id<ChannelMarker>,
+) -> StarboardResult<()> {
+    let starboards = Starboard::set_channel_deleted(&bot.pool, channel_id.get_i64(), true).await?;
+    let autostar =
+        AutoStarChannel::set_channel_deleted(&bot.pool, channel_id.get_i64(), true).await?;
+    if !autostar.is_empty() {
+        bot.cache
+            .autostar_channel_ids
+            .remove(&bot.pool, channel_id)
+            .await?;
+    }
+
+    // only rows that weren't marked already are returned, so this is only
+    // sent once per deletion
+    if starboards.is_empty() && autostar.is_empty() {
+        return Ok(());
+    }
+
+    let guild = bot.http.guild(guild_id).await?.model().await?;
+    let mut message = format!("A channel in **{}** was deleted.", guild.name);
+    if !starboards.is_empty() {
+        message.push_str(&format!(
+            "\n\nThese starboards won't post anything until they're moved to another channel \
+            with `/starboards move`: {}.",
+            fmt_names(&starboards),
+        ));
+    }
+    if !autostar.is_empty() {
+        message.push_str(&format!(
+            "\n\nThese autostar channels won't do anything anymore, and can be deleted with \
+            `/autostar delete`: {}.",
+            fmt_names(&autostar),
+        ));
+    }
+    notify(bot, guild.owner_id, &message).await?;
+
+    Ok(())
+}
+
+/// Unmarks the starboards and autostar channels of a channel that came back.
+pub async fn handle_channel_create(
+    bot: &StarboardBot,
+    channel_id: Id<ChannelMarker>,
+) -> StarboardResult<()> {
+    Starboard::set_channel_deleted(&bot.pool, channel_id.get_i64(), false).await?;
+    let autostar =
+        AutoStarChannel::set_channel_deleted(&bot.pool, channel_id.get_i64(), false).await?;
+    if !autostar.is_empty() {
+        bot.cache.autostar_channel_ids.add(channel_id).await;
+    }
+
+    Ok(())
+}
+
+fn fmt_names(names: &[String]) -> String {
+    names
+        .iter()
+        .map(|name| format!("'{name}'"))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
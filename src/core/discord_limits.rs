@@ -0,0 +1,167 @@
+//! Limits Discord puts on the resources the bot creates. Creating a resource
+//! at its limit fails with an unhelpful 400, so the counts are checked first
+//! where the cache has them, and Discord's limit errors are mapped to the same
+//! `StarboardError::DiscordLimit`.
+
+use std::{
+    fmt,
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+use twilight_http::{api_error::ApiError, error::ErrorType};
+use twilight_model::id::{marker::GuildMarker, Id};
+
+use crate::{
+    client::bot::StarboardBot,
+    errors::{DiscordLimitSnafu, StarboardError},
+};
+
+/// Webhooks per channel.
+pub const MAX_CHANNEL_WEBHOOKS: usize = 10;
+/// Active threads per guild, including forum posts.
+pub const MAX_ACTIVE_THREADS: usize = 1_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Limit {
+    ChannelWebhooks,
+    ActiveThreads,
+}
+
+impl Limit {
+    /// Maps a Discord error code to the limit it's about.
+    pub fn from_code(code: u64) -> Option<Self> {
+        match code {
+            // maximum webhooks for a channel, and for a guild
+            30007 | 30058 => Some(Self::ChannelWebhooks),
+            // maximum active threads, and active announcement threads
+            160006 | 160007 => Some(Self::ActiveThreads),
+            _ => None,
+        }
+    }
+
+    /// The limit Discord returned an error for, if any.
+    pub fn from_error(error: &twilight_http::Error) -> Option<Self> {
+        let ErrorType::Response {
+            error: ApiError::General(error),
+            ..
+        } = error.kind()
+        else {
+            return None;
+        };
+
+        Self::from_code(error.code)
+    }
+
+    /// Explains the limit and how to get under it, for users.
+    pub fn describe(self) -> &'static str {
+        match self {
+            Self::ChannelWebhooks => concat!(
+                "This channel has too many webhooks. Delete some in channel settings > ",
+                "integrations > webhooks, or turn off `use-webhook`."
+            ),
+            Self::ActiveThreads => concat!(
+                "This server has too many active threads. Archive some threads or forum ",
+                "posts, or turn off `attach-thread`."
+            ),
+        }
+    }
+}
+
+impl fmt::Display for Limit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.describe())
+    }
+}
+
+impl From<Limit> for StarboardError {
+    fn from(limit: Limit) -> Self {
+        DiscordLimitSnafu { limit }.build()
+    }
+}
+
+/// Turns Discord's limit errors into `StarboardError::DiscordLimit`, leaving
+/// other errors as they are.
+pub fn map_limit_error(bot: &StarboardBot, error: twilight_http::Error) -> StarboardError {
+    match Limit::from_error(&error) {
+        Some(limit) => {
+            bot.limit_stats
+                .discord_errors
+                .fetch_add(1, Ordering::Relaxed);
+            limit.into()
+        }
+        None => error.into(),
+    }
+}
+
+/// Fails if the guild can't have another active thread.
+pub fn check_active_threads(bot: &StarboardBot, guild_id: Id<GuildMarker>) -> Result<(), Limit> {
+    let active = bot.cache.guilds.with(&guild_id, |_, guild| {
+        guild.as_ref().map(|g| g.active_thread_parents.len())
+    });
+
+    if active.unwrap_or(0) >= MAX_ACTIVE_THREADS {
+        bot.limit_stats.avoided.fetch_add(1, Ordering::Relaxed);
+        return Err(Limit::ActiveThreads);
+    }
+
+    Ok(())
+}
+
+#[derive(Default)]
+pub struct LimitStats {
+    /// Creations skipped because the count was already at the limit.
+    pub avoided: AtomicU64,
+    /// Existing resources used instead of creating new ones.
+    pub reused: AtomicU64,
+    /// Limit errors returned by Discord anyway.
+    pub discord_errors: AtomicU64,
+}
+
+impl fmt::Display for LimitStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "avoided: {}", self.avoided.load(Ordering::Relaxed))?;
+        writeln!(f, "reused: {}", self.reused.load(Ordering::Relaxed))?;
+        writeln!(
+            f,
+            "discord errors: {}",
+            self.discord_errors.load(Ordering::Relaxed)
+        )?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn limit_codes() {
+        assert_eq!(Limit::from_code(30007), Some(Limit::ChannelWebhooks));
+        assert_eq!(Limit::from_code(30058), Some(Limit::ChannelWebhooks));
+        assert_eq!(Limit::from_code(160006), Some(Limit::ActiveThreads));
+        assert_eq!(Limit::from_code(160007), Some(Limit::ActiveThreads));
+        assert_eq!(Limit::from_code(50013), None);
+    }
+
+    #[test]
+    fn limit_errors() {
+        for limit in [Limit::ChannelWebhooks, Limit::ActiveThreads] {
+            let error = StarboardError::from(limit);
+
+            assert_eq!(error.discord_limit(), Some(limit));
+        }
+    }
+
+    #[test]
+    fn stats() {
+        let stats = LimitStats::default();
+        stats.avoided.store(2, Ordering::Relaxed);
+        stats.reused.store(1, Ordering::Relaxed);
+
+        assert_eq!(
+            stats.to_string(),
+            "avoided: 2\nreused: 1\ndiscord errors: 0\n"
+        );
+    }
+}
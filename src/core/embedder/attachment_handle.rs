@@ -21,10 +21,13 @@ pub struct AttachmentHandle {
 }
 
 impl AttachmentHandle {
+    /// Downloads the file, returning `None` if it's larger than `max_size`
+    /// bytes.
     pub async fn as_attachment(
         &self,
         bot: &StarboardBot,
         id: u64,
+        max_size: u64,
     ) -> StarboardResult<Option<Attachment>> {
         // this should always be a proxy url, but we do this to make 100%
         // sure that there isn't a bug that could potentially leak the VPS ip.
@@ -41,12 +44,15 @@ impl AttachmentHandle {
             }
         }
 
-        // we only want to download files under 8mb
         let head = bot.reqwest.head(&self.url).send().await?;
-        let bytes = &head.headers()["content-length"];
-        let bytes = bytes.to_str().unwrap().parse::<i64>().unwrap();
-
-        if bytes > 25_000_000 {
+        let Some(bytes) = head
+            .headers()
+            .get("content-length")
+            .and_then(|bytes| bytes.to_str().ok()?.parse::<u64>().ok())
+        else {
+            return Ok(None);
+        };
+        if bytes > max_size {
             return Ok(None);
         }
 
@@ -113,16 +119,27 @@ impl AttachmentHandle {
 
 #[async_trait]
 pub trait VecAttachments {
-    async fn as_attachments(&self, bot: &StarboardBot) -> (Vec<Attachment>, Vec<StarboardError>);
+    async fn as_attachments(
+        &self,
+        bot: &StarboardBot,
+        max_size: u64,
+    ) -> (Vec<Attachment>, Vec<StarboardError>);
 }
 
 #[async_trait]
 impl VecAttachments for Vec<AttachmentHandle> {
-    async fn as_attachments(&self, bot: &StarboardBot) -> (Vec<Attachment>, Vec<StarboardError>) {
+    async fn as_attachments(
+        &self,
+        bot: &StarboardBot,
+        max_size: u64,
+    ) -> (Vec<Attachment>, Vec<StarboardError>) {
         let mut attachments = Vec::new();
         let mut errors = Vec::new();
         for (current_id, attachment) in self.iter().enumerate() {
-            match attachment.as_attachment(bot, current_id as u64).await {
+            match attachment
+                .as_attachment(bot, current_id as u64, max_size)
+                .await
+            {
                 Err(why) => errors.push(why),
                 Ok(Some(file)) => attachments.push(file),
                 Ok(None) => {}
@@ -4,11 +4,14 @@ use lazy_static::lazy_static;
 use regex::Regex;
 use twilight_model::{
     channel::message::{
-        component::{ActionRow, Button, ButtonStyle},
+        component::{ActionRow, Button, ButtonStyle, SelectMenu, SelectMenuOption},
         embed::Embed,
-        Component,
+        Component, ReactionType,
+    },
+    id::{
+        marker::{EmojiMarker, MessageMarker},
+        Id,
     },
-    id::{marker::MessageMarker, Id},
     util::Timestamp,
 };
 use twilight_util::{
@@ -21,8 +24,14 @@ use twilight_util::{
 use crate::{
     cache::{models::message::CachedMessage, MessageResult},
     constants,
-    core::emoji::{EmojiCommon, SimpleEmoji},
+    core::{
+        emoji::{clean_emoji, EmojiCommon, SimpleEmoji},
+        emoji_tiers::{parse_tiers, tier_emoji},
+        vote_weights::emoji_weight,
+    },
+    database::models::vote::EmojiPoints,
     errors::StarboardResult,
+    interactions::custom_id::CustomId,
     utils::{
         avatar::ImageHashAvatar, id_as_i64::GetI64, into_id::IntoId, message_link::fmt_message_link,
     },
@@ -38,6 +47,9 @@ lazy_static! {
     .unwrap();
 }
 
+/// What a post is edited into once its message is trashed.
+pub const TRASHED_CONTENT: &str = "This post was trashed.";
+
 pub struct FullBuiltStarboardEmbed {
     pub top_content: String,
     pub embeds: Vec<Embed>,
@@ -60,9 +72,17 @@ impl BuiltStarboardEmbed {
         force_partial: bool,
         watermark: bool,
     ) -> StarboardResult<Self> {
+        if handle.orig_sql_message.trashed {
+            return Ok(Self::trashed());
+        }
+
         if let MessageResult::Ok(orig) = &handle.orig_message {
             if !force_partial {
-                let parsed = ParsedMessage::parse(orig);
+                let parsed = ParsedMessage::parse(
+                    orig,
+                    handle.config.resolved.media_selection,
+                    handle.config.resolved.extract_media_links,
+                );
 
                 let built = Self::Full(FullBuiltStarboardEmbed {
                     top_content: Self::build_top_content(handle),
@@ -80,6 +100,16 @@ impl BuiltStarboardEmbed {
         Ok(built)
     }
 
+    /// The placeholder a trashed post keeps its place with.
+    pub fn trashed() -> Self {
+        Self::Full(FullBuiltStarboardEmbed {
+            top_content: TRASHED_CONTENT.to_string(),
+            embeds: Vec::new(),
+            upload_attachments: Vec::new(),
+            components: Vec::new(),
+        })
+    }
+
     pub fn build_go_to_message_button(handle: &Embedder) -> Option<Button> {
         if handle.config.resolved.go_to_message != 2 {
             return None;
@@ -99,20 +129,230 @@ impl BuiltStarboardEmbed {
         })
     }
 
-    pub fn build_components(handle: &Embedder) -> Vec<Component> {
-        let Some(gtm) = Self::build_go_to_message_button(handle) else {
+    pub fn build_translate_button(handle: &Embedder) -> Option<Button> {
+        if !handle.config.resolved.translate_button {
+            return None;
+        }
+
+        Some(Button {
+            custom_id: Some(CustomId::Translate.encode()),
+            disabled: false,
+            emoji: None,
+            label: Some("Translate".to_string()),
+            style: ButtonStyle::Secondary,
+            url: None,
+        })
+    }
+
+    /// The upvote and downvote emojis with their points, or `None` if the
+    /// post has no vote buttons.
+    fn vote_emoji_counts(handle: &Embedder) -> Option<(EmojiCounts<'_>, EmojiCounts<'_>)> {
+        let (upvotes, downvotes) = handle.vote_counts?;
+        let resolved = &handle.config.resolved;
+        if !resolved.vote_buttons {
+            return None;
+        }
+
+        Some((
+            emoji_counts(
+                &resolved.upvote_emojis,
+                upvotes,
+                &handle.emoji_points,
+                false,
+            ),
+            emoji_counts(
+                &resolved.downvote_emojis,
+                downvotes,
+                &handle.emoji_points,
+                true,
+            ),
+        ))
+    }
+
+    fn is_emoji_animated(handle: &Embedder, emoji_id: Id<EmojiMarker>) -> bool {
+        handle
+            .bot
+            .cache
+            .is_emoji_animated(handle.config.starboard.guild_id.into_id(), emoji_id)
+            .unwrap_or(false)
+    }
+
+    pub fn build_vote_buttons(handle: &Embedder) -> Vec<Button> {
+        let Some((upvotes, downvotes)) = Self::vote_emoji_counts(handle) else {
             return vec![];
         };
 
-        vec![Component::ActionRow(ActionRow {
-            components: vec![Component::Button(gtm)],
-        })]
+        // the first emoji of each direction gets a button, the others are
+        // in the select menu
+        upvotes
+            .into_iter()
+            .take(1)
+            .chain(downvotes.into_iter().take(1))
+            .map(|(emoji, count)| {
+                Self::vote_button(emoji, count, |id| Self::is_emoji_animated(handle, id))
+            })
+            .collect()
+    }
+
+    /// A select menu with every vote emoji, for starboards with more vote
+    /// emojis than there are vote buttons.
+    pub fn build_vote_select(handle: &Embedder) -> Option<SelectMenu> {
+        let (upvotes, downvotes) = Self::vote_emoji_counts(handle)?;
+        if upvotes.len() <= 1 && downvotes.len() <= 1 {
+            return None;
+        }
+
+        let weights = &handle.config.resolved.vote_emoji_weights;
+        let options = upvotes
+            .into_iter()
+            .map(|(emoji, count)| (emoji, count, false))
+            .chain(
+                downvotes
+                    .into_iter()
+                    .map(|(emoji, count)| (emoji, count, true)),
+            )
+            // Discord's limit for options
+            .take(25)
+            .map(|(emoji, count, is_downvote)| {
+                let weight = emoji_weight(weights, &SimpleEmoji::from_stored(emoji.clone()));
+                Self::vote_option(emoji, is_downvote, count, weight, |id| {
+                    Self::is_emoji_animated(handle, id)
+                })
+            })
+            .collect();
+
+        Some(SelectMenu {
+            custom_id: CustomId::VoteSelect.encode(),
+            disabled: false,
+            max_values: Some(1),
+            min_values: Some(1),
+            options,
+            placeholder: Some("Vote with another emoji".to_string()),
+        })
+    }
+
+    fn vote_reaction(
+        emoji: &str,
+        is_animated: impl FnOnce(Id<EmojiMarker>) -> bool,
+    ) -> ReactionType {
+        match emoji.parse() {
+            Ok(id) => ReactionType::Custom {
+                animated: is_animated(id),
+                id,
+                name: None,
+            },
+            Err(_) => ReactionType::Unicode {
+                name: emoji.to_string(),
+            },
+        }
+    }
+
+    /// A vote button for a stored emoji, labelled with its count.
+    fn vote_button(
+        emoji: &str,
+        count: i32,
+        is_animated: impl FnOnce(Id<EmojiMarker>) -> bool,
+    ) -> Button {
+        Button {
+            custom_id: Some(
+                CustomId::Vote {
+                    emoji: emoji.to_string(),
+                }
+                .encode(),
+            ),
+            disabled: false,
+            emoji: Some(Self::vote_reaction(emoji, is_animated)),
+            label: Some(count.to_string()),
+            style: ButtonStyle::Secondary,
+            url: None,
+        }
+    }
+
+    /// An option of the vote select menu for a stored emoji, labelled with its
+    /// count. The value is the stored emoji.
+    fn vote_option(
+        emoji: &str,
+        is_downvote: bool,
+        count: i32,
+        weight: i16,
+        is_animated: impl FnOnce(Id<EmojiMarker>) -> bool,
+    ) -> SelectMenuOption {
+        let direction = if is_downvote { "Downvote" } else { "Upvote" };
+
+        SelectMenuOption {
+            default: false,
+            description: (weight != 1).then(|| format!("Worth {weight} points")),
+            emoji: Some(Self::vote_reaction(emoji, is_animated)),
+            label: format!("{direction}: {count}"),
+            value: emoji.to_string(),
+        }
+    }
+
+    pub fn build_votes_button(handle: &Embedder) -> Option<Button> {
+        // like the vote buttons, only for posts on starboards that aren't
+        // aggregates
+        handle.vote_counts?;
+        if !handle.config.resolved.show_votes_button {
+            return None;
+        }
+
+        Some(Button {
+            custom_id: Some(
+                CustomId::Votes {
+                    message_id: handle.orig_sql_message.message_id,
+                    starboard_id: handle.config.starboard.id,
+                }
+                .encode(),
+            ),
+            disabled: false,
+            emoji: Some(ReactionType::Unicode {
+                name: "🗳️".to_string(),
+            }),
+            label: Some("Votes".to_string()),
+            style: ButtonStyle::Secondary,
+            url: None,
+        })
+    }
+
+    pub fn build_components(handle: &Embedder) -> Vec<Component> {
+        let mut rows = Vec::new();
+
+        let buttons: Vec<_> = Self::build_vote_buttons(handle)
+            .into_iter()
+            .chain(
+                [
+                    Self::build_votes_button(handle),
+                    Self::build_go_to_message_button(handle),
+                    Self::build_translate_button(handle),
+                ]
+                .into_iter()
+                .flatten(),
+            )
+            .map(Component::Button)
+            .collect();
+        if !buttons.is_empty() {
+            rows.push(Component::ActionRow(ActionRow {
+                components: buttons,
+            }));
+        }
+
+        if let Some(select) = Self::build_vote_select(handle) {
+            rows.push(Component::ActionRow(ActionRow {
+                components: vec![Component::SelectMenu(select)],
+            }));
+        }
+
+        rows
     }
 
     pub fn build_top_content(handle: &Embedder) -> String {
         let mut top_content = String::new();
 
-        if let Some(emoji) = handle.config.resolved.display_emoji.clone() {
+        let tiers = parse_tiers(&handle.config.resolved.display_emoji_tiers);
+        let emoji = tier_emoji(&tiers, handle.points)
+            .map(str::to_string)
+            .or_else(|| handle.config.resolved.display_emoji.clone());
+        if let Some(emoji) = emoji {
             let emoji = SimpleEmoji::from_stored(emoji);
             top_content.push_str(
                 &emoji.into_readable(&handle.bot, handle.config.starboard.guild_id.into_id()),
@@ -170,6 +410,7 @@ impl BuiltStarboardEmbed {
         if let Some(e) = Self::build_primary_embed(handle, orig, parsed, watermark, false).await? {
             embeds.push(e);
         }
+        embeds.extend(Self::build_gallery_embeds(handle, parsed));
 
         if handle.config.resolved.extra_embeds {
             for e in &parsed.embeds {
@@ -196,6 +437,22 @@ impl BuiltStarboardEmbed {
         Ok(embeds)
     }
 
+    /// Embeds that share the primary embed's url, so Discord shows their
+    /// images in one gallery.
+    pub fn build_gallery_embeds(handle: &Embedder, parsed: &ParsedMessage) -> Vec<Embed> {
+        let link = fmt_message_link(
+            handle.config.starboard.guild_id,
+            handle.orig_sql_message.channel_id,
+            handle.orig_sql_message.message_id,
+        );
+
+        parsed
+            .gallery
+            .iter()
+            .map(|image| EmbedBuilder::new().url(&link).image(image.clone()).build())
+            .collect()
+    }
+
     pub async fn build_replied_embed(handle: &Embedder) -> StarboardResult<Option<Embed>> {
         if !handle.config.resolved.replied_to {
             return Ok(None);
@@ -205,7 +462,8 @@ impl BuiltStarboardEmbed {
             None => return Ok(None),
             Some(msg) => msg,
         };
-        let reply_parsed = ParsedMessage::parse(ref_msg);
+        let reply_parsed =
+            ParsedMessage::parse(ref_msg, 0, handle.config.resolved.extract_media_links);
         Self::build_primary_embed(handle, ref_msg, &reply_parsed, false, true).await
     }
 
@@ -265,6 +523,20 @@ impl BuiltStarboardEmbed {
         // main description
         let mut description = String::new();
 
+        // the replied-to message gets its own embed if it still exists,
+        // otherwise show what's left of it.
+        let shows_reply = handle.config.resolved.replied_to && handle.referenced_message.is_some();
+        match &orig.reply {
+            Some(reply) if !is_reply && !shows_reply => {
+                write!(description, "Replying to **@{}**", reply.author_name).unwrap();
+                if !reply.snippet.is_empty() {
+                    write!(description, ": {}", reply.snippet).unwrap();
+                }
+                description.push('\n');
+            }
+            _ => (),
+        }
+
         if let Some(name_str) = &parsed.sticker_names_str {
             description.push_str(name_str);
             description.push('\n');
@@ -312,7 +584,12 @@ impl BuiltStarboardEmbed {
             urls.extend(parsed.urls.embedded.iter().map(|url| url.to_md()));
         }
 
-        if (handle.config.resolved.attachments_list || is_reply) && !urls.is_empty() {
+        // with clone-attachments, files that weren't cloned are linked to instead
+        if (handle.config.resolved.attachments_list
+            || handle.is_premium && handle.config.resolved.clone_attachments
+            || is_reply)
+            && !urls.is_empty()
+        {
             let mut field = String::new();
 
             for next in urls {
@@ -332,6 +609,9 @@ impl BuiltStarboardEmbed {
         if let Some(image) = &parsed.primary_image {
             embed = embed.image(image.clone());
         }
+        if !is_reply && !parsed.gallery.is_empty() {
+            embed = embed.url(&link);
+        }
 
         // timestamp
         embed = embed.timestamp(Timestamp::from_micros(mid.timestamp() * 1000).unwrap());
@@ -392,6 +672,7 @@ impl BuiltStarboardEmbed {
             .or(author.avatar.map(|av| av.global_avatar(author_id)));
         let mut name = member
             .and_then(|m| m.nickname.clone())
+            .or(author.global_name)
             .unwrap_or(author.name);
 
         if is_reply {
@@ -401,3 +682,130 @@ impl BuiltStarboardEmbed {
         Ok((name, avatar))
     }
 }
+
+/// Vote emojis with their points, see `emoji_counts`.
+type EmojiCounts<'a> = Vec<(&'a String, i32)>;
+
+/// The points from each of `emojis`, given the weighted `total` of their
+/// direction. Votes without a stored emoji, or with one that was removed
+/// from `emojis`, count for the first emoji.
+fn emoji_counts<'a>(
+    emojis: &'a [String],
+    total: i32,
+    emoji_points: &[EmojiPoints],
+    is_downvote: bool,
+) -> EmojiCounts<'a> {
+    let points = |emoji: &String| -> i32 {
+        emoji_points
+            .iter()
+            .filter(|p| p.is_downvote == is_downvote)
+            .filter(|p| {
+                p.emoji
+                    .as_deref()
+                    .is_some_and(|e| clean_emoji(e) == clean_emoji(emoji))
+            })
+            .map(|p| p.points)
+            .sum()
+    };
+
+    let others: Vec<_> = emojis.iter().skip(1).map(|e| (e, points(e))).collect();
+    let first = total - others.iter().map(|(_, points)| points).sum::<i32>();
+    emojis
+        .first()
+        .map(|e| (e, first))
+        .into_iter()
+        .chain(others)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unicode_vote_button() {
+        let button = BuiltStarboardEmbed::vote_button("⭐", 7, |_| unreachable!());
+
+        assert_eq!(
+            button.emoji,
+            Some(ReactionType::Unicode {
+                name: "⭐".to_string()
+            })
+        );
+        assert_eq!(button.label.as_deref(), Some("7"));
+        assert_eq!(
+            button.custom_id.map(|id| CustomId::decode(&id)),
+            Some(Ok(CustomId::Vote {
+                emoji: "⭐".to_string()
+            }))
+        );
+    }
+
+    #[test]
+    fn custom_vote_button() {
+        let button = BuiltStarboardEmbed::vote_button("123", -2, |id| id.get() == 123);
+
+        assert_eq!(
+            button.emoji,
+            Some(ReactionType::Custom {
+                animated: true,
+                id: Id::new(123),
+                name: None,
+            })
+        );
+        assert_eq!(button.label.as_deref(), Some("-2"));
+    }
+
+    fn points(emoji: Option<&str>, is_downvote: bool, points: i32) -> EmojiPoints {
+        EmojiPoints {
+            emoji: emoji.map(str::to_string),
+            is_downvote,
+            points,
+        }
+    }
+
+    #[test]
+    fn counts_per_emoji() {
+        let emojis = vec!["⭐".to_string(), "💎".to_string()];
+        let emoji_points = vec![
+            points(Some("⭐"), false, 2),
+            points(Some("💎"), false, 6),
+            points(Some("👎"), true, 1),
+        ];
+
+        assert_eq!(
+            emoji_counts(&emojis, 8, &emoji_points, false),
+            vec![(&emojis[0], 2), (&emojis[1], 6)]
+        );
+    }
+
+    #[test]
+    fn unknown_emojis_count_for_the_first() {
+        let emojis = vec!["⭐".to_string(), "💎".to_string()];
+        // votes from before emojis were stored, and with a removed emoji
+        let emoji_points = vec![
+            points(None, false, 1),
+            points(Some("👍"), false, 2),
+            points(Some("💎"), false, 3),
+        ];
+
+        assert_eq!(
+            emoji_counts(&emojis, 6, &emoji_points, false),
+            vec![(&emojis[0], 3), (&emojis[1], 3)]
+        );
+        assert_eq!(emoji_counts(&[], 6, &emoji_points, false), vec![]);
+    }
+
+    #[test]
+    fn vote_select_option() {
+        let option = BuiltStarboardEmbed::vote_option("💎", false, 6, 3, |_| unreachable!());
+
+        assert_eq!(option.label, "Upvote: 6");
+        assert_eq!(option.description.as_deref(), Some("Worth 3 points"));
+        assert_eq!(option.value, "💎");
+
+        let option = BuiltStarboardEmbed::vote_option("👎", true, 1, 1, |_| unreachable!());
+        assert_eq!(option.label, "Downvote: 1");
+        assert_eq!(option.description, None);
+    }
+}
@@ -0,0 +1,187 @@
+//! Re-uploads small images from the original message, so that starboard posts
+//! keep showing them after the original (and its CDN links) are gone.
+
+use twilight_model::{channel::message::Embed, http::attachment::Attachment};
+
+use crate::{
+    cache::models::message::CachedMessage, client::bot::StarboardBot, constants,
+    errors::StarboardError,
+};
+
+use super::AttachmentHandle;
+
+/// The filename a cloned attachment is uploaded as. It's based on the index of
+/// the attachment, so that edits can find the file again.
+fn cloned_filename(idx: usize, filename: &str) -> String {
+    format!("{idx}_{filename}")
+}
+
+/// Downloads the images shown in `embeds` and points the embeds at the
+/// uploaded files. Images that are too large or fail to download keep their
+/// original url.
+pub async fn clone_images(
+    bot: &StarboardBot,
+    orig: &CachedMessage,
+    embeds: &mut [Embed],
+    first_id: u64,
+) -> (Vec<Attachment>, Vec<StarboardError>) {
+    let mut files = Vec::new();
+    let mut errors = Vec::new();
+
+    for (idx, attachment) in orig.attachments.iter().enumerate() {
+        if attachment.size > constants::MAX_CLONE_ATTACHMENT_SIZE {
+            continue;
+        }
+        let handle = AttachmentHandle::from_attachment(attachment);
+        if handle.embedable_image().is_none() || !shows_image(embeds, &handle.url) {
+            continue;
+        }
+
+        let id = first_id + files.len() as u64;
+        let mut file = match handle
+            .as_attachment(bot, id, constants::MAX_CLONE_ATTACHMENT_SIZE)
+            .await
+        {
+            Ok(Some(file)) => file,
+            Ok(None) => continue,
+            Err(why) => {
+                errors.push(why);
+                continue;
+            }
+        };
+
+        file.filename = cloned_filename(idx, &handle.filename);
+        set_image_url(embeds, &handle.url, &file.filename);
+        files.push(file);
+    }
+
+    (files, errors)
+}
+
+/// Points the embeds at images that were cloned when the post was sent, since
+/// rebuilt embeds link to the original attachments again.
+pub fn relink_cloned_images(orig: &CachedMessage, post: &CachedMessage, embeds: &mut [Embed]) {
+    for (idx, attachment) in orig.attachments.iter().enumerate() {
+        let filename = cloned_filename(idx, &attachment.filename);
+        if post.attachments.iter().any(|a| a.filename == filename) {
+            set_image_url(embeds, &attachment.url, &filename);
+        }
+    }
+}
+
+/// Whether an attachment can be cloned as a file on the starboard post. Only
+/// images and videos are, the rest are linked to.
+pub fn is_clonable(handle: &AttachmentHandle) -> bool {
+    handle
+        .content_type
+        .as_deref()
+        .is_some_and(|ct| ct.starts_with("image") || ct.starts_with("video"))
+}
+
+fn shows_image(embeds: &[Embed], url: &str) -> bool {
+    embeds
+        .iter()
+        .filter_map(|e| e.image.as_ref())
+        .any(|image| image.url == url)
+}
+
+fn set_image_url(embeds: &mut [Embed], url: &str, filename: &str) {
+    for image in embeds.iter_mut().filter_map(|e| e.image.as_mut()) {
+        if image.url == url {
+            image.url = format!("attachment://{filename}");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{json, Value};
+    use twilight_model::channel::Message;
+    use twilight_util::builder::embed::{EmbedBuilder, ImageSource};
+
+    use super::*;
+
+    fn message(attachments: Vec<Value>) -> CachedMessage {
+        let msg: Message = serde_json::from_value(json!({
+            "id": "2",
+            "channel_id": "3",
+            "author": {"id": "4", "username": "user", "discriminator": "0", "avatar": null},
+            "content": "",
+            "timestamp": "2023-06-20T12:00:00.000000+00:00",
+            "edited_timestamp": null,
+            "tts": false,
+            "mention_everyone": false,
+            "mentions": [],
+            "mention_roles": [],
+            "attachments": attachments,
+            "embeds": [],
+            "pinned": false,
+            "type": 0,
+        }))
+        .unwrap();
+        msg.into()
+    }
+
+    fn attachment(id: u64, filename: &str) -> Value {
+        json!({
+            "id": id.to_string(),
+            "filename": filename,
+            "size": 1000,
+            "url": format!("https://cdn.discordapp.com/{id}/{filename}"),
+            "proxy_url": format!("https://media.discordapp.net/{id}/{filename}"),
+        })
+    }
+
+    fn image_embed(url: &str) -> Embed {
+        EmbedBuilder::new()
+            .image(ImageSource::url(url).unwrap())
+            .build()
+    }
+
+    fn image_url(embed: &Embed) -> &str {
+        &embed.image.as_ref().unwrap().url
+    }
+
+    fn handle(content_type: Option<&str>) -> AttachmentHandle {
+        AttachmentHandle {
+            filename: "file".to_string(),
+            content_type: content_type.map(str::to_string),
+            url: "https://cdn.discordapp.com/file".to_string(),
+        }
+    }
+
+    #[test]
+    fn clonable_types() {
+        assert!(is_clonable(&handle(Some("image/png"))));
+        assert!(is_clonable(&handle(Some("video/mp4"))));
+        assert!(!is_clonable(&handle(Some("text/plain"))));
+        assert!(!is_clonable(&handle(None)));
+    }
+
+    #[test]
+    fn points_embeds_at_the_file() {
+        let url = "https://media.discordapp.net/1/cat.png";
+        let mut embeds = [image_embed(url), image_embed("https://example.com/dog.png")];
+        assert!(shows_image(&embeds, url));
+
+        set_image_url(&mut embeds, url, &cloned_filename(0, "cat.png"));
+        assert_eq!(image_url(&embeds[0]), "attachment://0_cat.png");
+        assert_eq!(image_url(&embeds[1]), "https://example.com/dog.png");
+        assert!(!shows_image(&embeds, url));
+    }
+
+    #[test]
+    fn relinks_cloned_images() {
+        let orig = message(vec![attachment(1, "cat.png"), attachment(2, "dog.png")]);
+        // only the first image was cloned
+        let post = message(vec![attachment(3, "0_cat.png")]);
+        let mut embeds = [
+            image_embed(&orig.attachments[0].url),
+            image_embed(&orig.attachments[1].url),
+        ];
+
+        relink_cloned_images(&orig, &post, &mut embeds);
+        assert_eq!(image_url(&embeds[0]), "attachment://0_cat.png");
+        assert_eq!(image_url(&embeds[1]), orig.attachments[1].url);
+    }
+}
@@ -54,7 +54,7 @@ pub fn get_gif_url(url: &str, provider: &str) -> Option<String> {
             Some(format!("{origin}/{gif_id}-size_restricted.gif"))
         }
         other => {
-            eprintln!("Unkown GIFV provider: {other}\n{url}");
+            tracing::warn!("Unkown GIFV provider: {other}\n{url}");
             None
         }
     }
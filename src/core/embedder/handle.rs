@@ -1,24 +1,40 @@
 use std::sync::Arc;
 
-use twilight_model::id::{marker::MessageMarker, Id};
+use twilight_model::{
+    channel::{message::embed::Embed, Message},
+    id::{marker::MessageMarker, Id},
+};
 
 use crate::{
     cache::{models::message::CachedMessage, MessageResult},
     client::bot::StarboardBot,
+    constants,
     core::{
+        discord_limits::{check_active_threads, map_limit_error},
+        features::{is_feature_enabled, Flag},
         premium::is_premium::is_guild_premium,
         starboard::{config::StarboardConfig, webhooks::get_valid_webhook},
     },
-    database::{DbMessage, Starboard},
+    database::{models::vote::EmojiPoints, DbMessage, Starboard},
     errors::StarboardResult,
     utils::{get_status::get_status, id_as_i64::GetI64, into_id::IntoId},
 };
 
-use super::{attachment_handle::VecAttachments, builder::BuiltStarboardEmbed};
+use super::{
+    attachment_handle::VecAttachments,
+    builder::{BuiltStarboardEmbed, TRASHED_CONTENT},
+    clone,
+};
 
 pub struct Embedder {
     pub bot: Arc<StarboardBot>,
     pub points: i32,
+    /// The weighted upvotes and downvotes for the vote buttons. Only set for posts on
+    /// starboards that aren't aggregates, which are the ones buttons can vote on.
+    pub vote_counts: Option<(i32, i32)>,
+    /// The points per emoji, for the labels of the vote buttons. Only fetched
+    /// when the post has vote buttons.
+    pub emoji_points: Vec<EmojiPoints>,
     pub config: Arc<StarboardConfig>,
     pub orig_message: MessageResult,
     pub orig_sql_message: Arc<DbMessage>,
@@ -35,26 +51,91 @@ impl Embedder {
         BuiltStarboardEmbed::build(self, force_partial, watermark).await
     }
 
-    pub async fn send(
-        &self,
-        bot: &StarboardBot,
-    ) -> StarboardResult<twilight_model::channel::Message> {
+    #[tracing::instrument(skip_all, fields(
+        starboard_id = self.config.starboard.id,
+        message_id = self.orig_sql_message.message_id,
+    ))]
+    pub async fn send(&self, bot: &StarboardBot) -> StarboardResult<Message> {
+        let msg = self.send_post(bot).await?;
+
+        if self.config.resolved.attach_thread {
+            if let Err(why) = self.attach_thread(bot, &msg).await {
+                tracing::warn!(
+                    "Failed to attach a thread to starboard message {}: {why}",
+                    msg.id
+                );
+            }
+        }
+
+        Ok(msg)
+    }
+
+    async fn attach_thread(&self, bot: &StarboardBot, msg: &Message) -> StarboardResult<()> {
+        let guild_id = self.config.starboard.guild_id.into_id();
+        if !bot.cache.can_channel_have_threads(guild_id, msg.channel_id) {
+            return Ok(());
+        }
+
+        let author = msg
+            .embeds
+            .first()
+            .and_then(|embed| embed.author.as_ref())
+            .map_or("Unknown User", |author| &*author.name);
+        let content = match &self.orig_message {
+            MessageResult::Ok(orig) => &*orig.content,
+            _ => "",
+        };
+
+        let name = thread_name(author, content);
+        check_active_threads(bot, guild_id)?;
+        bot.http
+            .create_thread_from_message(msg.channel_id, msg.id, &name)?
+            .await
+            .map_err(|why| map_limit_error(bot, why))?;
+
+        Ok(())
+    }
+
+    async fn send_post(&self, bot: &StarboardBot) -> StarboardResult<Message> {
         let guild_id = self.config.starboard.guild_id.into_id();
         let sb_channel_id = self.config.starboard.channel_id.into_id();
+        let use_webhook = self.config.resolved.use_webhook
+            && is_feature_enabled(bot, Flag::WebhookPosting, self.config.starboard.guild_id)
+                .await?;
 
-        let built = match self
-            .build(false, self.config.resolved.use_webhook && !self.is_premium)
-            .await?
-        {
+        let mut built = match self.build(false, use_webhook && !self.is_premium).await? {
             BuiltStarboardEmbed::Full(built) => built,
             BuiltStarboardEmbed::Partial(_) => panic!("Tried to send an unbuildable message."),
         };
 
         let attachments = if self.is_premium {
-            let (attachments, errors) = built.upload_attachments.as_attachments(bot).await;
+            let clone_attachments = self.config.resolved.clone_attachments;
+            let max_size = if clone_attachments {
+                built.upload_attachments.retain(clone::is_clonable);
+                constants::MAX_CLONE_ATTACHMENT_SIZE
+            } else {
+                constants::MAX_UPLOAD_ATTACHMENT_SIZE
+            };
+
+            let (mut attachments, errors) =
+                built.upload_attachments.as_attachments(bot, max_size).await;
             for e in errors {
                 bot.handle_error(&e).await;
             }
+
+            match &self.orig_message {
+                MessageResult::Ok(orig) if clone_attachments => {
+                    let first_id = built.upload_attachments.len() as u64;
+                    let (cloned, errors) =
+                        clone::clone_images(bot, orig, &mut built.embeds, first_id).await;
+                    for e in errors {
+                        bot.handle_error(&e).await;
+                    }
+                    attachments.extend(cloned);
+                }
+                _ => {}
+            }
+
             Some(attachments)
         } else {
             None
@@ -70,20 +151,20 @@ impl Embedder {
                 content = "Click to see attachments";
             }
 
-            let mut desc = format!("{name}: {content}");
-            if desc.len() > 100 {
-                desc = desc[..100 - 3].to_string() + "...";
-            }
-
-            Some(desc)
+            Some(thread_name(name, content))
         } else {
             None
         };
 
-        if self.config.resolved.use_webhook {
+        if use_webhook {
             loop {
-                if let Some(wh) = get_valid_webhook(bot, &self.config.starboard, true, true).await?
-                {
+                let wh = match get_valid_webhook(bot, &self.config.starboard, true, true).await {
+                    // fall back to sending normally, like when the webhook
+                    // couldn't be created
+                    Err(why) if why.discord_limit().is_some() => None,
+                    ret => ret?,
+                };
+                if let Some(wh) = wh {
                     let parent = bot
                         .cache
                         .fog_parent_channel_id(bot, guild_id, sb_channel_id)
@@ -128,6 +209,7 @@ impl Embedder {
         }
 
         if let Some(name) = forum_post_name {
+            check_active_threads(bot, guild_id)?;
             let mut ret = bot
                 .http
                 .create_forum_thread(sb_channel_id, &name)
@@ -140,7 +222,8 @@ impl Embedder {
                 ret = ret.attachments(attachments)?;
             };
 
-            Ok(ret.await?.model().await?.message)
+            let ret = ret.await.map_err(|why| map_limit_error(bot, why))?;
+            Ok(ret.model().await?.message)
         } else {
             let mut ret = bot
                 .http
@@ -157,6 +240,10 @@ impl Embedder {
         }
     }
 
+    #[tracing::instrument(skip(self, bot), fields(
+        starboard_id = self.config.starboard.id,
+        message_id = self.orig_sql_message.message_id,
+    ))]
     pub async fn edit(
         &self,
         bot: &StarboardBot,
@@ -202,9 +289,21 @@ impl Embedder {
         };
 
         let is_prem = is_guild_premium(bot, self.config.starboard.guild_id, true).await?;
+        // a partial update would leave an untrashed post without its embeds
+        let force_partial = force_partial && !is_trashed_placeholder(&msg.content, &msg.embeds);
+        let trashed = self.orig_sql_message.trashed;
 
         match self.build(force_partial, wh.is_some() && !is_prem).await? {
-            BuiltStarboardEmbed::Full(built) => {
+            BuiltStarboardEmbed::Full(mut built) => {
+                match &self.orig_message {
+                    MessageResult::Ok(orig)
+                        if is_prem && self.config.resolved.clone_attachments =>
+                    {
+                        clone::relink_cloned_images(orig, &msg, &mut built.embeds);
+                    }
+                    _ => {}
+                }
+
                 if let Some(wh) = wh {
                     let mut ud = bot
                         .http
@@ -216,15 +315,24 @@ impl Embedder {
                     if is_thread || is_forum {
                         ud = ud.thread_id(real_channel_id);
                     }
+                    if trashed {
+                        ud = ud.keep_attachment_ids(&[]);
+                    }
 
                     ud.await?;
                 } else {
-                    bot.http
+                    let mut ud = bot
+                        .http
                         .update_message(real_channel_id, message_id)
                         .content(Some(&built.top_content))?
                         .embeds(Some(&built.embeds))?
-                        .components(Some(&built.components))?
-                        .await?;
+                        .components(Some(&built.components))?;
+
+                    if trashed {
+                        ud = ud.keep_attachment_ids(&[]);
+                    }
+
+                    ud.await?;
                 }
             }
             BuiltStarboardEmbed::Partial(built) => {
@@ -251,6 +359,10 @@ impl Embedder {
         Ok(false)
     }
 
+    #[tracing::instrument(skip(self, bot), fields(
+        starboard_id = self.config.starboard.id,
+        message_id = self.orig_sql_message.message_id,
+    ))]
     pub async fn delete(
         &self,
         bot: &StarboardBot,
@@ -277,6 +389,13 @@ impl Embedder {
             MessageResult::Missing => return Ok(true),
         };
 
+        // a forum post's id is also its starter message's. Deleting only the
+        // message would leave an empty post, so delete the whole post when
+        // allowed to
+        if is_forum && bot.http.delete_channel(real_channel_id).await.is_ok() {
+            return Ok(true);
+        }
+
         if let Some(wh_id) = self.config.starboard.webhook_id {
             if wh_id == msg.author_id.get_i64() {
                 if let Some(wh) =
@@ -304,6 +423,7 @@ impl Embedder {
 
                     let ret = ud.await;
                     if ret.is_ok() {
+                        self.archive_thread(bot, message_id).await;
                         return Ok(true);
                     }
                 }
@@ -311,7 +431,104 @@ impl Embedder {
         }
 
         let ret = bot.http.delete_message(real_channel_id, message_id).await;
+        if ret.is_ok() {
+            self.archive_thread(bot, message_id).await;
+        }
 
         Ok(ret.is_ok())
     }
+
+    /// Threads started from a message share its id and outlive it, so archive
+    /// the thread (if there was one) once the starboard message is gone.
+    async fn archive_thread(&self, bot: &StarboardBot, message_id: Id<MessageMarker>) {
+        if !self.config.resolved.attach_thread
+            || !bot.cache.can_channel_have_threads(
+                self.config.starboard.guild_id.into_id(),
+                self.config.starboard.channel_id.into_id(),
+            )
+        {
+            return;
+        }
+
+        let _ = bot
+            .http
+            .update_thread(message_id.cast())
+            .archived(true)
+            .await;
+    }
+}
+
+/// Whether a post was edited into the placeholder of a trashed message.
+fn is_trashed_placeholder(content: &str, embeds: &[Embed]) -> bool {
+    embeds.is_empty() && content == TRASHED_CONTENT
+}
+
+/// Builds a thread name from the author and a snippet of their message, with
+/// markdown and newlines stripped, fitting Discord's 100 character limit.
+fn thread_name(author: &str, content: &str) -> String {
+    let snippet: String = content
+        .chars()
+        .filter(|c| !matches!(c, '*' | '_' | '~' | '`' | '|' | '>' | '#'))
+        .collect();
+    let snippet = snippet.split_whitespace().collect::<Vec<_>>().join(" ");
+
+    let name = if snippet.is_empty() {
+        author.to_string()
+    } else {
+        format!("{author}: {snippet}")
+    };
+
+    if name.chars().count() > 100 {
+        name.chars().take(100 - 3).collect::<String>() + "..."
+    } else {
+        name
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn trashed_placeholder() {
+        assert!(is_trashed_placeholder(TRASHED_CONTENT, &[]));
+        assert!(!is_trashed_placeholder("⭐ **3** | <#1>", &[]));
+
+        let embed = twilight_util::builder::embed::EmbedBuilder::new()
+            .description(TRASHED_CONTENT)
+            .build();
+        assert!(!is_trashed_placeholder(TRASHED_CONTENT, &[embed]));
+    }
+
+    #[test]
+    fn strips_markdown() {
+        assert_eq!(
+            thread_name("user", "**look** at\n\n> this `code`"),
+            "user: look at this code"
+        );
+    }
+
+    #[test]
+    fn empty_content() {
+        assert_eq!(thread_name("user", ""), "user");
+        assert_eq!(thread_name("user", "** __ **"), "user");
+    }
+
+    #[test]
+    fn cut_at_100_chars() {
+        let name = thread_name("user", &"a".repeat(200));
+
+        assert_eq!(name.chars().count(), 100);
+        assert!(name.ends_with("aaa..."));
+        assert_eq!(thread_name("user", &"a".repeat(94)).chars().count(), 100);
+    }
+
+    #[test]
+    fn cut_between_chars() {
+        // cutting by bytes would land inside a character
+        let name = thread_name("user", &"⭐".repeat(200));
+
+        assert_eq!(name.chars().count(), 100);
+        assert!(name.ends_with("⭐..."));
+    }
 }
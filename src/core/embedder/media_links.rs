@@ -0,0 +1,260 @@
+//! GIF and image links in a message's content, for messages that are just a
+//! link and would otherwise show up on the starboard as a bare URL.
+
+use lazy_static::lazy_static;
+use regex::Regex;
+
+use crate::cache::models::message::CachedMessage;
+
+use super::{
+    image_only_embed::maybe_get_attachment_handle, imgur::modify_imgur_url, AttachmentHandle,
+};
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum MediaLink {
+    /// A link straight to the image.
+    Direct(String),
+    /// A tenor or giphy page. The image is only known from the embed Discord
+    /// generates for it.
+    Page,
+}
+
+/// Whether `url` points to a GIF or image that can be shown in an embed.
+pub fn match_media_url(url: &str) -> Option<MediaLink> {
+    lazy_static! {
+        static ref TENOR: Regex =
+            Regex::new(r#"^https://(?:media|c)\.tenor\.com/[\w-]+/[\w.-]+\.gif$"#).unwrap();
+        static ref GIPHY: Regex = Regex::new(
+            r#"^https://(?:i|media\d*)\.giphy\.com/media/(?:[\w.-]+/)*[\w.-]+\.(?:gif|webp)$"#
+        )
+        .unwrap();
+        static ref IMGUR: Regex =
+            Regex::new(r#"^https://i\.imgur\.com/\w+\.(?:png|jpe?g|gif|gifv|webp)$"#).unwrap();
+        static ref DISCORD: Regex = Regex::new(concat!(
+            r#"^https://(?:cdn\.discordapp\.com|media\.discordapp\.net)/attachments/\d+/\d+/"#,
+            r#"[^/?#]+\.(?:png|jpe?g|gif|webp)(?:\?\S*)?$"#
+        ))
+        .unwrap();
+        static ref PAGE: Regex =
+            Regex::new(r#"^https://(?:www\.)?(?:tenor\.com/view|giphy\.com/gifs)/[\w-]+/?$"#)
+                .unwrap();
+    }
+
+    // signed discord links need their query, the others don't
+    if DISCORD.is_match(url) {
+        return Some(MediaLink::Direct(url.to_string()));
+    }
+
+    let url = url.split(['?', '#']).next().unwrap();
+    if TENOR.is_match(url) || GIPHY.is_match(url) {
+        return Some(MediaLink::Direct(url.to_string()));
+    }
+    if IMGUR.is_match(url) {
+        let url = url.strip_suffix('v').unwrap_or(url);
+        return Some(MediaLink::Direct(
+            modify_imgur_url(url).unwrap_or_else(|| url.to_string()),
+        ));
+    }
+    if PAGE.is_match(url) {
+        return Some(MediaLink::Page);
+    }
+
+    None
+}
+
+/// The first media link in the content that can be shown as an image.
+/// Links inside spoilers are skipped, and pages are resolved using the
+/// message's embeds.
+pub fn find_media_link(orig: &CachedMessage) -> Option<AttachmentHandle> {
+    lazy_static! {
+        static ref URL: Regex = Regex::new(r#"https://[^\s<>|]+"#).unwrap();
+    }
+
+    for found in URL.find_iter(&orig.content) {
+        if orig.content[..found.start()].matches("||").count() % 2 == 1 {
+            continue;
+        }
+
+        let handle = match match_media_url(found.as_str()) {
+            None => continue,
+            Some(MediaLink::Direct(url)) => AttachmentHandle {
+                filename: url
+                    .split('?')
+                    .next()
+                    .and_then(|url| url.split('/').next_back())
+                    .unwrap_or("image")
+                    .to_string(),
+                content_type: Some("image".to_string()),
+                url,
+            },
+            Some(MediaLink::Page) => {
+                let page = found.as_str().trim_end_matches('/');
+                let embed = orig.embeds.iter().find(|e| {
+                    e.url
+                        .as_deref()
+                        .is_some_and(|url| url.trim_end_matches('/') == page)
+                });
+                let Some(handle) = embed.and_then(maybe_get_attachment_handle) else {
+                    continue;
+                };
+                handle
+            }
+        };
+
+        if handle.embedable_image().is_some() {
+            return Some(handle);
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{json, Value};
+    use twilight_model::channel::Message;
+
+    use super::*;
+
+    fn message(content: &str, embeds: Vec<Value>) -> CachedMessage {
+        let msg: Message = serde_json::from_value(json!({
+            "id": "2",
+            "channel_id": "3",
+            "author": {"id": "4", "username": "user", "discriminator": "0", "avatar": null},
+            "content": content,
+            "timestamp": "2023-07-14T12:00:00.000000+00:00",
+            "edited_timestamp": null,
+            "tts": false,
+            "mention_everyone": false,
+            "mentions": [],
+            "mention_roles": [],
+            "attachments": [],
+            "embeds": embeds,
+            "pinned": false,
+            "type": 0,
+        }))
+        .unwrap();
+        msg.into()
+    }
+
+    fn found_url(msg: &CachedMessage) -> Option<String> {
+        find_media_link(msg).map(|handle| handle.url)
+    }
+
+    fn direct(url: &str) -> Option<MediaLink> {
+        Some(MediaLink::Direct(url.to_string()))
+    }
+
+    #[test]
+    fn tenor() {
+        assert_eq!(
+            match_media_url("https://media.tenor.com/abc-123/cat.gif"),
+            direct("https://media.tenor.com/abc-123/cat.gif")
+        );
+        assert_eq!(
+            match_media_url("https://c.tenor.com/abc/cat.gif?width=100"),
+            direct("https://c.tenor.com/abc/cat.gif")
+        );
+        assert_eq!(
+            match_media_url("https://tenor.com/view/cat-dance-12345"),
+            Some(MediaLink::Page)
+        );
+        assert_eq!(match_media_url("https://media.tenor.com/abc/cat.mp4"), None);
+    }
+
+    #[test]
+    fn giphy() {
+        assert_eq!(
+            match_media_url("https://media.giphy.com/media/abc123/giphy.gif"),
+            direct("https://media.giphy.com/media/abc123/giphy.gif")
+        );
+        // newer links have a versioned id before the gif's
+        assert_eq!(
+            match_media_url("https://media2.giphy.com/media/v1.abc/abc123/giphy.webp#x"),
+            direct("https://media2.giphy.com/media/v1.abc/abc123/giphy.webp")
+        );
+        assert_eq!(
+            match_media_url("https://giphy.com/gifs/cat-abc123/"),
+            Some(MediaLink::Page)
+        );
+    }
+
+    #[test]
+    fn imgur() {
+        assert_eq!(
+            match_media_url("https://i.imgur.com/abc.png"),
+            direct("https://i.imgur.com/abc.png")
+        );
+        // gifv is a video page, the gif is next to it
+        assert_eq!(
+            match_media_url("https://i.imgur.com/abc.gifv"),
+            direct("https://i.imgur.com/abc.gif")
+        );
+        assert_eq!(
+            match_media_url("https://i.imgur.com/abch.jpg"),
+            direct("https://i.imgur.com/abc.jpg")
+        );
+        assert_eq!(match_media_url("https://imgur.com/gallery/abc"), None);
+    }
+
+    #[test]
+    fn discord_keeps_query() {
+        let url = "https://cdn.discordapp.com/attachments/1/2/cat.png?ex=1&is=2&hm=3";
+        assert_eq!(match_media_url(url), direct(url));
+        assert_eq!(
+            match_media_url("https://media.discordapp.net/attachments/1/2/cat.txt"),
+            None
+        );
+    }
+
+    #[test]
+    fn other_links() {
+        assert_eq!(match_media_url("https://example.com/cat.gif"), None);
+        assert_eq!(match_media_url("http://media.tenor.com/abc/cat.gif"), None);
+        assert_eq!(
+            match_media_url("https://media.tenor.com.evil.com/abc/cat.gif"),
+            None
+        );
+    }
+
+    #[test]
+    fn first_media_link() {
+        let msg = message(
+            "look https://example.com/a.gif https://media.tenor.com/abc/cat.gif",
+            vec![],
+        );
+        let handle = find_media_link(&msg).unwrap();
+
+        assert_eq!(handle.url, "https://media.tenor.com/abc/cat.gif");
+        assert_eq!(handle.filename, "cat.gif");
+    }
+
+    #[test]
+    fn spoilers_are_skipped() {
+        let msg = message("||https://media.tenor.com/abc/cat.gif||", vec![]);
+        assert_eq!(found_url(&msg), None);
+
+        let msg = message("||secret|| https://media.tenor.com/abc/cat.gif", vec![]);
+        assert_eq!(
+            found_url(&msg).as_deref(),
+            Some("https://media.tenor.com/abc/cat.gif")
+        );
+    }
+
+    #[test]
+    fn pages_use_their_embed() {
+        let page = "https://tenor.com/view/cat-dance-12345";
+        let embed = json!({
+            "type": "gifv",
+            "url": page,
+            "provider": {"name": "Tenor"},
+            "thumbnail": {"url": "https://media.tenor.com/abcD/cat.png"},
+        });
+
+        let msg = message(&format!("{page}/"), vec![embed]);
+        assert!(found_url(&msg).is_some());
+        // Discord hasn't embedded it yet
+        let msg = message(page, vec![]);
+        assert_eq!(found_url(&msg), None);
+    }
+}
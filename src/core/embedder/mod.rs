@@ -1,9 +1,11 @@
 pub mod attachment_handle;
 pub mod builder;
+mod clone;
 mod gifv;
 mod handle;
 pub mod image_only_embed;
 mod imgur;
+mod media_links;
 mod parser;
 mod youtube;
 
@@ -4,11 +4,12 @@ use twilight_model::channel::message::{
 };
 use twilight_util::builder::embed::ImageSource;
 
-use crate::cache::models::message::CachedMessage;
+use crate::{cache::models::message::CachedMessage, constants};
 
 use super::{
     image_only_embed::maybe_get_attachment_handle,
     imgur::{modify_imgur_embed, ImgurResult},
+    media_links::find_media_link,
     youtube::modify_yt_embed,
     AttachmentHandle,
 };
@@ -46,12 +47,25 @@ pub struct ParsedMessage {
     pub primary_image: Option<ImageSource>,
     pub embeds: Vec<Embed>,
     pub upload_attachments: Vec<AttachmentHandle>,
+    /// Extra images shown next to the primary image, when `media_selection`
+    /// is set to show all images.
+    pub gallery: Vec<ImageSource>,
 }
 
 impl ParsedMessage {
-    pub fn parse(orig: &CachedMessage) -> Self {
-        let (sticker_names_str, primary_image, url_list, embeds, upload_attachments) =
-            Self::parse_attachments(orig);
+    pub fn parse(orig: &CachedMessage, media_selection: i16, extract_media_links: bool) -> Self {
+        let mut gallery = Vec::new();
+        let (sticker_names_str, mut primary_image, url_list, mut embeds, upload_attachments) =
+            Self::parse_attachments(orig, media_selection, &mut gallery);
+
+        // messages that are just a GIF link, before or without Discord's embed
+        if extract_media_links && orig.attachments.is_empty() && primary_image.is_none() {
+            if let Some(handle) = find_media_link(orig) {
+                // the same image may already be an extra embed
+                embeds.retain(|e| !e.image.as_ref().is_some_and(|i| i.url == handle.url));
+                primary_image = handle.embedable_image();
+            }
+        }
 
         Self {
             sticker_names_str,
@@ -59,11 +73,55 @@ impl ParsedMessage {
             urls: url_list,
             embeds,
             upload_attachments,
+            gallery,
+        }
+    }
+
+    /// The index of the image attachment with the highest resolution. Ties
+    /// go to whichever was attached first.
+    pub fn largest_image_attachment(orig: &CachedMessage) -> Option<usize> {
+        let mut largest: Option<(usize, u64)> = None;
+        for (idx, attachment) in orig.attachments.iter().enumerate() {
+            let handle = AttachmentHandle::from_attachment(attachment);
+            if handle.embedable_image().is_none() {
+                continue;
+            }
+
+            let size = attachment.width.unwrap_or(0) * attachment.height.unwrap_or(0);
+            if !largest.is_some_and(|(_, largest_size)| size <= largest_size) {
+                largest = Some((idx, size));
+            }
+        }
+
+        largest.map(|(idx, _)| idx)
+    }
+
+    /// The index of the image-only embed to use as the primary image. Images
+    /// are preferred over GIF links.
+    pub fn preferred_image_embed(orig: &CachedMessage) -> Option<usize> {
+        let mut gif = None;
+        for (idx, embed) in orig.embeds.iter().enumerate() {
+            let Some(attachment) = maybe_get_attachment_handle(embed) else {
+                continue;
+            };
+            if attachment.embedable_image().is_none() {
+                continue;
+            }
+
+            if &*embed.kind == "gifv" {
+                gif.get_or_insert(idx);
+            } else {
+                return Some(idx);
+            }
         }
+
+        gif
     }
 
     pub fn parse_attachments(
         orig: &CachedMessage,
+        media_selection: i16,
+        gallery: &mut Vec<ImageSource>,
     ) -> (
         Option<StickerNames>,
         Option<PrimaryImage>,
@@ -76,9 +134,33 @@ impl ParsedMessage {
         let mut upload_attachments = Vec::new();
         let mut urls = AttachmentUrls::default();
 
-        for attachment in &orig.attachments {
+        // 1 = largest, 2 = all (gallery)
+        let show_gallery = media_selection == 2;
+        let mut attachments: Vec<_> = orig.attachments.iter().collect();
+        let mut preferred_embed = None;
+        if media_selection == 1 {
+            if let Some(idx) = Self::largest_image_attachment(orig) {
+                let largest = attachments.remove(idx);
+                attachments.insert(0, largest);
+            } else {
+                preferred_embed = Self::preferred_image_embed(orig);
+            }
+        }
+
+        for attachment in attachments {
             let handle = AttachmentHandle::from_attachment(attachment);
 
+            if show_gallery
+                && primary_image.is_some()
+                && gallery.len() < constants::MAX_GALLERY_IMAGES - 1
+            {
+                if let Some(image) = handle.embedable_image() {
+                    urls.embedded.push(handle.attachment_list_item());
+                    gallery.push(image);
+                    continue;
+                }
+            }
+
             if primary_image.is_none() {
                 if let Some(image) = handle.embedable_image() {
                     urls.embedded.push(handle.attachment_list_item());
@@ -95,7 +177,7 @@ impl ParsedMessage {
             upload_attachments.push(handle);
         }
 
-        for embed in &orig.embeds {
+        for (idx, embed) in orig.embeds.iter().enumerate() {
             // handle imgur
             if let Some(provider) = &embed.provider {
                 if matches!(provider.name.as_deref(), Some("Imgur")) {
@@ -113,8 +195,17 @@ impl ParsedMessage {
             // handle embeds that are purely attachments
             if let Some(attachment) = maybe_get_attachment_handle(embed) {
                 if let Some(image) = attachment.embedable_image() {
-                    if primary_image.is_none() && embeds.is_empty() {
+                    let is_primary = match preferred_embed {
+                        Some(preferred) => preferred == idx,
+                        None => embeds.is_empty(),
+                    };
+                    if primary_image.is_none() && is_primary {
                         primary_image.replace(image);
+                    } else if show_gallery
+                        && primary_image.is_some()
+                        && gallery.len() < constants::MAX_GALLERY_IMAGES - 1
+                    {
+                        gallery.push(image);
                     } else {
                         embeds.push(attachment.as_embed().unwrap());
                     }
@@ -188,11 +279,15 @@ impl ParsedMessage {
                     StickerFormatType::Lottie => {
                         sticker_names.push(format!("Sticker: **{}**", sticker.name));
                     }
-                    StickerFormatType::Apng | StickerFormatType::Png => {
+                    StickerFormatType::Apng | StickerFormatType::Png | StickerFormatType::Gif => {
+                        let ext = match sticker.format_type {
+                            StickerFormatType::Gif => "gif",
+                            _ => "png",
+                        };
                         let handle = AttachmentHandle {
-                            filename: format!("{}.png", sticker.name),
-                            content_type: Some("image/png".to_string()),
-                            url: format!("https://cdn.discordapp.com/stickers/{}.png", sticker.id),
+                            filename: format!("{}.{ext}", sticker.name),
+                            content_type: Some(format!("image/{ext}")),
+                            url: format!("https://cdn.discordapp.com/stickers/{}.{ext}", sticker.id),
                         };
 
                         if primary_image.is_none() {
@@ -210,10 +305,10 @@ impl ParsedMessage {
                         upload_attachments.push(handle);
                     }
                     StickerFormatType::Unknown(format) => {
-                        eprintln!("Unkown sticker format type {format}.")
+                        tracing::warn!("Unkown sticker format type {format}.")
                     }
                     unhandled => {
-                        eprintln!("Twilight added sticker format type {unhandled:?}.");
+                        tracing::warn!("Twilight added sticker format type {unhandled:?}.");
                     }
                 }
             }
@@ -236,3 +331,191 @@ impl ParsedMessage {
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{json, Value};
+    use twilight_model::channel::{message::sticker::MessageSticker, Message};
+
+    use super::*;
+
+    fn message(attachments: Vec<Value>, embeds: Vec<Value>) -> CachedMessage {
+        let msg: Message = serde_json::from_value(json!({
+            "id": "2",
+            "channel_id": "3",
+            "author": {"id": "4", "username": "user", "discriminator": "0", "avatar": null},
+            "content": "",
+            "timestamp": "2023-06-12T12:00:00.000000+00:00",
+            "edited_timestamp": null,
+            "tts": false,
+            "mention_everyone": false,
+            "mentions": [],
+            "mention_roles": [],
+            "attachments": attachments,
+            "embeds": embeds,
+            "pinned": false,
+            "type": 0,
+        }))
+        .unwrap();
+        msg.into()
+    }
+
+    fn image(id: u64, width: u64, height: u64) -> Value {
+        json!({
+            "id": id.to_string(),
+            "filename": format!("{id}.png"),
+            "size": 1000,
+            "url": format!("https://cdn.discordapp.com/{id}.png"),
+            "proxy_url": format!("https://media.discordapp.net/{id}.png"),
+            "content_type": "image/png",
+            "width": width,
+            "height": height,
+        })
+    }
+
+    fn text_file(id: u64) -> Value {
+        json!({
+            "id": id.to_string(),
+            "filename": format!("{id}.txt"),
+            "size": 1000,
+            "url": format!("https://cdn.discordapp.com/{id}.txt"),
+            "proxy_url": format!("https://media.discordapp.net/{id}.txt"),
+            "content_type": "text/plain",
+        })
+    }
+
+    fn image_embed(url: &str) -> Value {
+        json!({"type": "image", "thumbnail": {"url": url}})
+    }
+
+    fn gif_embed() -> Value {
+        json!({
+            "type": "gifv",
+            "provider": {"name": "Tenor"},
+            "thumbnail": {"url": "https://media.tenor.com/abcD/cat.png"},
+        })
+    }
+
+    fn url(id: u64) -> ImageSource {
+        ImageSource::url(format!("https://cdn.discordapp.com/{id}.png")).unwrap()
+    }
+
+    #[test]
+    fn largest_image() {
+        let msg = message(
+            vec![
+                image(1, 10, 10),
+                text_file(2),
+                image(3, 100, 50),
+                image(4, 50, 50),
+            ],
+            vec![],
+        );
+
+        assert_eq!(ParsedMessage::largest_image_attachment(&msg), Some(2));
+        assert_eq!(
+            ParsedMessage::parse(&msg, 1, false).primary_image,
+            Some(url(3))
+        );
+        // first by default
+        assert_eq!(
+            ParsedMessage::parse(&msg, 0, false).primary_image,
+            Some(url(1))
+        );
+    }
+
+    #[test]
+    fn largest_image_ties() {
+        let msg = message(vec![image(1, 20, 10), image(2, 10, 20)], vec![]);
+
+        assert_eq!(ParsedMessage::largest_image_attachment(&msg), Some(0));
+    }
+
+    #[test]
+    fn no_image_attachments() {
+        let msg = message(vec![text_file(1)], vec![]);
+
+        assert_eq!(ParsedMessage::largest_image_attachment(&msg), None);
+    }
+
+    #[test]
+    fn images_over_gifs() {
+        let msg = message(
+            vec![],
+            vec![gif_embed(), image_embed("https://example.com/a.png")],
+        );
+        assert_eq!(ParsedMessage::preferred_image_embed(&msg), Some(1));
+
+        let msg = message(vec![], vec![gif_embed()]);
+        assert_eq!(ParsedMessage::preferred_image_embed(&msg), Some(0));
+    }
+
+    #[test]
+    fn gallery() {
+        let attachments = (1..=6).map(|id| image(id, 10, 10)).collect();
+        let parsed = ParsedMessage::parse(&message(attachments, vec![]), 2, false);
+
+        assert_eq!(parsed.primary_image, Some(url(1)));
+        assert_eq!(parsed.gallery, vec![url(2), url(3), url(4)]);
+        assert_eq!(parsed.gallery.len(), constants::MAX_GALLERY_IMAGES - 1);
+    }
+
+    #[test]
+    fn no_gallery_by_default() {
+        let attachments = (1..=3).map(|id| image(id, 10, 10)).collect();
+        let parsed = ParsedMessage::parse(&message(attachments, vec![]), 0, false);
+
+        assert!(parsed.gallery.is_empty());
+    }
+
+    fn sticker(id: u64, format_type: u8) -> MessageSticker {
+        serde_json::from_value(json!({
+            "id": id.to_string(),
+            "name": format!("sticker{id}"),
+            "format_type": format_type,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn gif_stickers_are_images() {
+        let mut msg = message(vec![], vec![]);
+        msg.stickers = vec![sticker(5, 4)];
+        let parsed = ParsedMessage::parse(&msg, 0, false);
+
+        let url = ImageSource::url("https://cdn.discordapp.com/stickers/5.gif").unwrap();
+        assert_eq!(parsed.primary_image, Some(url));
+        assert_eq!(parsed.sticker_names_str, None);
+    }
+
+    #[test]
+    fn lottie_stickers_are_named() {
+        let mut msg = message(vec![], vec![]);
+        msg.stickers = vec![sticker(5, 3)];
+        let parsed = ParsedMessage::parse(&msg, 0, false);
+
+        assert_eq!(parsed.primary_image, None);
+        assert_eq!(
+            parsed.sticker_names_str.as_deref(),
+            Some("Sticker: **sticker5**")
+        );
+    }
+
+    #[test]
+    fn media_links() {
+        let mut msg = message(vec![], vec![]);
+        msg.content = "https://media.tenor.com/abc/cat.gif".to_string();
+        let gif = ImageSource::url("https://media.tenor.com/abc/cat.gif").unwrap();
+
+        assert_eq!(ParsedMessage::parse(&msg, 0, true).primary_image, Some(gif));
+        assert_eq!(ParsedMessage::parse(&msg, 0, false).primary_image, None);
+
+        // attachments win over links
+        let mut msg = message(vec![image(1, 10, 10)], vec![]);
+        msg.content = "https://media.tenor.com/abc/cat.gif".to_string();
+        assert_eq!(
+            ParsedMessage::parse(&msg, 0, true).primary_image,
+            Some(url(1))
+        );
+    }
+}
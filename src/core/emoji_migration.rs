@@ -0,0 +1,426 @@
+//! Rewrites emoji settings that were stored in older formats ("name:id",
+//! "<:name:id>", shortcodes, or unicode with a stray variation selector) into
+//! the format `SimpleEmoji::from_user_input` stores, so that they match the
+//! reactions they were meant for again. Entries that can't be parsed are kept
+//! as they are, and their starboard or autostar channel is flagged with
+//! `needs_attention`.
+
+use std::{
+    collections::{BTreeMap, HashSet},
+    fmt,
+};
+
+use lazy_static::lazy_static;
+use regex::Regex;
+use twilight_model::id::{marker::EmojiMarker, Id};
+
+use crate::{
+    client::bot::StarboardBot,
+    constants,
+    core::emoji::clean_emoji,
+    database::{AutoStarChannel, EmojiMigrationAudit, Starboard, StarboardOverride},
+    errors::StarboardResult,
+};
+
+/// The canonical form of a stored emoji, or `None` if it can't be parsed.
+pub fn canonicalize(raw: &str) -> Option<String> {
+    lazy_static! {
+        static ref CUSTOM: Regex = Regex::new(r"^(<a?:)?\w+:(?P<id>\d+)>?$").unwrap();
+    }
+
+    let raw = raw.trim();
+
+    if raw.chars().all(|c| c.is_ascii_digit()) {
+        return raw.parse::<Id<EmojiMarker>>().ok().map(|id| id.to_string());
+    }
+    if let Some(caps) = CUSTOM.captures(raw) {
+        return caps["id"]
+            .parse::<Id<EmojiMarker>>()
+            .ok()
+            .map(|id| id.to_string());
+    }
+    if emojis::get(raw).is_some() {
+        return Some(raw.to_string());
+    }
+    if emojis::get(clean_emoji(raw)).is_some() {
+        return Some(clean_emoji(raw).to_string());
+    }
+
+    let shortcode = raw.trim_matches(':');
+    emojis::get_by_shortcode(shortcode).map(|emoji| emoji.as_str().to_string())
+}
+
+struct FixedList {
+    emojis: Vec<String>,
+    changed: usize,
+    unparseable: usize,
+}
+
+fn fix_list(list: &[String]) -> FixedList {
+    let mut fixed = FixedList {
+        emojis: Vec::with_capacity(list.len()),
+        changed: 0,
+        unparseable: 0,
+    };
+
+    for raw in list {
+        match canonicalize(raw) {
+            Some(emoji) => {
+                if &emoji != raw {
+                    fixed.changed += 1;
+                }
+                fixed.emojis.push(emoji);
+            }
+            None => {
+                fixed.unparseable += 1;
+                fixed.emojis.push(raw.clone());
+            }
+        }
+    }
+
+    fixed
+}
+
+#[derive(Debug, Default)]
+pub struct GuildEmojiReport {
+    /// Emojis that were (or would be) rewritten.
+    pub changed: usize,
+    /// Emojis that were kept because they couldn't be parsed.
+    pub unparseable: usize,
+}
+
+#[derive(Debug, Default)]
+pub struct EmojiMigrationReport {
+    pub written: bool,
+    pub guilds: BTreeMap<i64, GuildEmojiReport>,
+}
+
+impl EmojiMigrationReport {
+    fn add(&mut self, guild_id: i64, fixed: &FixedList) {
+        if fixed.changed == 0 && fixed.unparseable == 0 {
+            return;
+        }
+
+        let guild = self.guilds.entry(guild_id).or_default();
+        guild.changed += fixed.changed;
+        guild.unparseable += fixed.unparseable;
+    }
+}
+
+impl fmt::Display for EmojiMigrationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let changed: usize = self.guilds.values().map(|g| g.changed).sum();
+        let unparseable: usize = self.guilds.values().map(|g| g.unparseable).sum();
+
+        writeln!(
+            f,
+            "{} {changed} emojis, kept {unparseable} unparseable emojis, in {} guilds.",
+            if self.written {
+                "Rewrote"
+            } else {
+                "Would rewrite"
+            },
+            self.guilds.len(),
+        )?;
+        for (guild_id, guild) in self
+            .guilds
+            .iter()
+            .take(constants::EMOJI_MIGRATION_REPORT_GUILDS)
+        {
+            writeln!(
+                f,
+                "- {guild_id}: {} changed, {} unparseable",
+                guild.changed, guild.unparseable
+            )?;
+        }
+        if self.guilds.len() > constants::EMOJI_MIGRATION_REPORT_GUILDS {
+            writeln!(
+                f,
+                "...and {} more guilds.",
+                self.guilds.len() - constants::EMOJI_MIGRATION_REPORT_GUILDS
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Checks every starboard, override and autostar channel, rewriting their
+/// emojis if `write` is set. A dry run goes through exactly the same steps,
+/// so its report matches what a real run would do.
+pub async fn migrate_emojis(
+    bot: &StarboardBot,
+    write: bool,
+) -> StarboardResult<EmojiMigrationReport> {
+    let mut report = EmojiMigrationReport {
+        written: write,
+        ..Default::default()
+    };
+    let mut attention = HashSet::new();
+
+    for ov in StarboardOverride::list_all(&bot.pool).await? {
+        let mut overrides = ov.overrides.clone();
+        let mut unparseable = false;
+
+        for setting in ["upvote_emojis", "downvote_emojis", "display_emoji"] {
+            let Some(value) = overrides.get(setting) else {
+                continue;
+            };
+            let old: Vec<String> = match value {
+                serde_json::Value::String(emoji) => vec![emoji.clone()],
+                serde_json::Value::Array(_) => serde_json::from_value(value.clone())?,
+                _ => continue,
+            };
+
+            let is_string = value.is_string();
+
+            let fixed = fix_list(&old);
+            report.add(ov.guild_id, &fixed);
+            unparseable |= fixed.unparseable != 0;
+            if fixed.changed == 0 {
+                continue;
+            }
+
+            overrides[setting] = if is_string {
+                serde_json::Value::String(fixed.emojis[0].clone())
+            } else {
+                fixed.emojis.clone().into()
+            };
+            if write {
+                EmojiMigrationAudit::create(
+                    &bot.pool,
+                    ov.guild_id,
+                    "override",
+                    ov.id,
+                    setting,
+                    &old,
+                    &fixed.emojis,
+                )
+                .await?;
+            }
+        }
+
+        if unparseable {
+            attention.insert(ov.starboard_id);
+        }
+        if write && overrides != ov.overrides {
+            StarboardOverride::update_settings_raw(&bot.pool, ov.id, overrides).await?;
+            bot.cache.guild_vote_emojis.remove(&ov.guild_id);
+        }
+    }
+
+    for sb in Starboard::list_all(&bot.pool).await? {
+        let settings = &sb.settings;
+        let display_emoji: Vec<_> = settings.display_emoji.iter().cloned().collect();
+
+        let upvote = fix_list(&settings.upvote_emojis);
+        let downvote = fix_list(&settings.downvote_emojis);
+        let display = fix_list(&display_emoji);
+
+        let mut changed = false;
+        for (setting, old, fixed) in [
+            ("upvote_emojis", &settings.upvote_emojis, &upvote),
+            ("downvote_emojis", &settings.downvote_emojis, &downvote),
+            ("display_emoji", &display_emoji, &display),
+        ] {
+            report.add(sb.guild_id, fixed);
+            if fixed.unparseable != 0 {
+                attention.insert(sb.id);
+            }
+            if fixed.changed == 0 {
+                continue;
+            }
+
+            changed = true;
+            if write {
+                EmojiMigrationAudit::create(
+                    &bot.pool,
+                    sb.guild_id,
+                    "starboard",
+                    sb.id,
+                    setting,
+                    old,
+                    &fixed.emojis,
+                )
+                .await?;
+            }
+        }
+
+        if !write {
+            continue;
+        }
+        if changed {
+            Starboard::set_emojis(
+                &bot.pool,
+                sb.id,
+                &upvote.emojis,
+                &downvote.emojis,
+                display.emojis.first().map(|e| e.as_str()),
+            )
+            .await?;
+            bot.cache.guild_vote_emojis.remove(&sb.guild_id);
+        }
+        let needs_attention = attention.contains(&sb.id);
+        if needs_attention != sb.needs_attention {
+            Starboard::set_needs_attention(&bot.pool, sb.id, needs_attention).await?;
+        }
+    }
+
+    for asc in AutoStarChannel::list_all(&bot.pool).await? {
+        let fixed = fix_list(&asc.emojis);
+        report.add(asc.guild_id, &fixed);
+
+        let needs_attention = fixed.unparseable != 0;
+        if !write || (fixed.changed == 0 && needs_attention == asc.needs_attention) {
+            continue;
+        }
+
+        if fixed.changed != 0 {
+            EmojiMigrationAudit::create(
+                &bot.pool,
+                asc.guild_id,
+                "autostar",
+                asc.id,
+                "emojis",
+                &asc.emojis,
+                &fixed.emojis,
+            )
+            .await?;
+        }
+        AutoStarChannel::set_emojis_raw(&bot.pool, asc.id, &fixed.emojis, needs_attention).await?;
+    }
+
+    Ok(report)
+}
+
+/// Clears `needs_attention` once every emoji of the starboard and its
+/// overrides can be parsed, e.g. after they were set again.
+pub async fn refresh_needs_attention(bot: &StarboardBot, starboard_id: i32) -> StarboardResult<()> {
+    let Some(sb) = Starboard::get(&bot.pool, starboard_id).await? else {
+        return Ok(());
+    };
+    if !sb.needs_attention {
+        return Ok(());
+    }
+
+    let mut lists = vec![
+        sb.settings.upvote_emojis,
+        sb.settings.downvote_emojis,
+        sb.settings.display_emoji.into_iter().collect(),
+    ];
+    for ov in StarboardOverride::list_by_starboard(&bot.pool, starboard_id).await? {
+        let ov = ov.get_overrides()?;
+        lists.extend(ov.upvote_emojis);
+        lists.extend(ov.downvote_emojis);
+        lists.extend(ov.display_emoji.map(|emoji| emoji.into_iter().collect()));
+    }
+
+    if lists.iter().all(|list| fix_list(list).unparseable == 0) {
+        Starboard::set_needs_attention(&bot.pool, starboard_id, false).await?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn list(emojis: &[&str]) -> Vec<String> {
+        emojis.iter().map(|e| e.to_string()).collect()
+    }
+
+    #[test]
+    fn custom_emojis() {
+        let id = Some("123456789012345678".to_string());
+
+        assert_eq!(canonicalize("123456789012345678"), id);
+        assert_eq!(canonicalize("star:123456789012345678"), id);
+        assert_eq!(canonicalize("<:star:123456789012345678>"), id);
+        assert_eq!(canonicalize("<a:star:123456789012345678>"), id);
+        assert_eq!(canonicalize(" <:star:123456789012345678> "), id);
+    }
+
+    #[test]
+    fn unicode_emojis() {
+        assert_eq!(canonicalize("⭐"), Some("⭐".to_string()));
+        assert_eq!(canonicalize("⭐\u{fe0f}"), Some("⭐".to_string()));
+        assert_eq!(canonicalize(":star:"), Some("⭐".to_string()));
+        assert_eq!(canonicalize("star"), Some("⭐".to_string()));
+    }
+
+    #[test]
+    fn unparseable() {
+        assert_eq!(canonicalize("not an emoji"), None);
+        assert_eq!(canonicalize(":not_a_shortcode:"), None);
+        assert_eq!(canonicalize("0"), None);
+    }
+
+    #[test]
+    fn fixes_lists() {
+        let fixed = fix_list(&list(&["⭐", ":gem:", "<:a:123>", "???"]));
+
+        assert_eq!(fixed.emojis, list(&["⭐", "💎", "123", "???"]));
+        assert_eq!(fixed.changed, 2);
+        assert_eq!(fixed.unparseable, 1);
+    }
+
+    #[test]
+    fn report_skips_clean_lists() {
+        let mut report = EmojiMigrationReport::default();
+        report.add(1, &fix_list(&list(&["⭐", "123"])));
+
+        assert!(report.guilds.is_empty());
+    }
+
+    #[test]
+    fn report_adds_up_guilds() {
+        let mut report = EmojiMigrationReport::default();
+        report.add(1, &fix_list(&list(&[":star:", "???"])));
+        report.add(1, &fix_list(&list(&[":gem:"])));
+        report.add(2, &fix_list(&list(&["???"])));
+
+        assert_eq!(report.guilds[&1].changed, 2);
+        assert_eq!(report.guilds[&1].unparseable, 1);
+        assert_eq!(report.guilds[&2].changed, 0);
+        assert_eq!(report.guilds[&2].unparseable, 1);
+    }
+
+    #[test]
+    fn dry_run_matches_write() {
+        let lists = [list(&[":star:", "???"]), list(&["<:a:123>"])];
+
+        let mut dry_run = EmojiMigrationReport::default();
+        let mut written = EmojiMigrationReport {
+            written: true,
+            ..Default::default()
+        };
+        for fixed in lists.iter().map(|l| fix_list(l)) {
+            dry_run.add(1, &fixed);
+            written.add(1, &fixed);
+        }
+
+        let dry_run = dry_run.to_string();
+        let written = written.to_string();
+        assert!(
+            dry_run.starts_with("Would rewrite 2 emojis, kept 1 unparseable emojis, in 1 guilds.")
+        );
+        assert_eq!(dry_run.replacen("Would rewrite", "Rewrote", 1), written);
+    }
+
+    #[test]
+    fn report_caps_guilds() {
+        let mut report = EmojiMigrationReport::default();
+        let extra = 3;
+        for guild_id in 0..(constants::EMOJI_MIGRATION_REPORT_GUILDS + extra) {
+            report.add(guild_id as i64, &fix_list(&list(&[":star:"])));
+        }
+
+        let report = report.to_string();
+        assert_eq!(
+            report.lines().filter(|l| l.starts_with("- ")).count(),
+            constants::EMOJI_MIGRATION_REPORT_GUILDS
+        );
+        assert!(report.ends_with(&format!("...and {extra} more guilds.\n")));
+    }
+}
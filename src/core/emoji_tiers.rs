@@ -0,0 +1,70 @@
+//! Different display emojis depending on how many points a post has, e.g.
+//! ⭐ -> 🌟 -> ✨.
+
+use serde::{Deserialize, Serialize};
+use twilight_model::id::{marker::GuildMarker, Id};
+
+use crate::{
+    client::bot::StarboardBot,
+    core::emoji::{EmojiCommon, SimpleEmoji},
+};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EmojiTier {
+    /// The points a post needs for this emoji.
+    pub points: i32,
+    /// A stored `SimpleEmoji`.
+    pub emoji: String,
+}
+
+/// The tiers stored in `display_emoji_tiers`, sorted by points.
+pub fn parse_tiers(value: &serde_json::Value) -> Vec<EmojiTier> {
+    serde_json::from_value(value.clone()).unwrap_or_default()
+}
+
+/// The emoji of the highest tier the post reached, if any.
+pub fn tier_emoji(tiers: &[EmojiTier], points: i32) -> Option<&str> {
+    tiers
+        .iter()
+        .rev()
+        .find(|tier| tier.points <= points)
+        .map(|tier| tier.emoji.as_str())
+}
+
+/// Parses input like "10 🌟, 25 ✨". Validation is left to
+/// `validate_emoji_tiers`.
+pub fn tiers_from_user_input(
+    input: &str,
+    bot: &StarboardBot,
+    guild_id: Id<GuildMarker>,
+) -> Result<Vec<EmojiTier>, String> {
+    let mut tiers = Vec::new();
+    for part in input.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+        let err = || format!("I couldn't read `{part}`. Use something like `10 🌟, 25 ✨`.");
+
+        let (points, emoji) = part.split_once(char::is_whitespace).ok_or_else(err)?;
+        let points = points.trim().parse().map_err(|_| err())?;
+        let mut emojis = SimpleEmoji::from_user_input(emoji, bot, guild_id);
+        if emojis.len() != 1 {
+            return Err(err());
+        }
+
+        tiers.push(EmojiTier {
+            points,
+            emoji: emojis.pop().unwrap().into_stored(),
+        });
+    }
+
+    Ok(tiers)
+}
+
+pub fn format_tiers(bot: &StarboardBot, guild_id: Id<GuildMarker>, tiers: &[EmojiTier]) -> String {
+    tiers
+        .iter()
+        .map(|tier| {
+            let emoji = SimpleEmoji::from_stored(tier.emoji.clone()).into_readable(bot, guild_id);
+            format!("{} {emoji}", tier.points)
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
@@ -0,0 +1,205 @@
+//! Feature flags, for rolling out risky features to some guilds first.
+//!
+//! Whether a guild has a feature is decided by, in order: the flag's
+//! denylist, its allowlist, and its rollout percentage. For the percentage,
+//! every guild is put in one of 100 buckets by hashing its id with the flag's
+//! name, so a guild always lands in the same bucket (and raising the rollout
+//! only ever adds guilds), while different flags pick different guilds.
+//!
+//! Flags are cached for `FEATURE_FLAGS_TTL`, so changes made through
+//! `star feature` reach the other processes within that time.
+
+use std::{fmt, str::FromStr, sync::Arc};
+
+use crate::{
+    client::bot::StarboardBot, database::FeatureFlag, errors::StarboardResult, utils::fnv::fnv1a,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Flag {
+    /// Deleting the data of guilds the bot left, see `core::guild_purge`.
+    GuildPurge,
+    /// Refreshing recent posts when a channel's NSFW flag changes, see
+    /// `core::starboard::nsfw`.
+    NsfwRefresh,
+    /// Sending posts through the starboard's webhook (`use-webhook`), see
+    /// `core::embedder`. Posts that were already sent through it are still
+    /// edited through it.
+    WebhookPosting,
+}
+
+impl Flag {
+    pub const ALL: [Self; 3] = [Self::GuildPurge, Self::NsfwRefresh, Self::WebhookPosting];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::GuildPurge => "guild-purge",
+            Self::NsfwRefresh => "nsfw-refresh",
+            Self::WebhookPosting => "webhook-posting",
+        }
+    }
+
+    /// The rollout for flags without a row. Flags start dark, so a feature
+    /// only reaches guilds once an operator raises its rollout (or allowlists
+    /// them) with `star feature set`.
+    pub fn default_rollout(self) -> i16 {
+        match self {
+            Self::GuildPurge | Self::NsfwRefresh | Self::WebhookPosting => 0,
+        }
+    }
+}
+
+impl fmt::Display for Flag {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for Flag {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|flag| flag.name() == s)
+            .ok_or(())
+    }
+}
+
+/// The guild's bucket for a flag, 0-99.
+pub fn rollout_bucket(flag: Flag, guild_id: i64) -> i16 {
+    let hash = fnv1a(flag.name().bytes().chain(guild_id.to_le_bytes()));
+    (hash % 100) as i16
+}
+
+/// Whether a guild has the feature, given the flag's row.
+pub fn is_enabled(flag: Flag, row: Option<&FeatureFlag>, guild_id: i64) -> bool {
+    let Some(row) = row else {
+        return rollout_bucket(flag, guild_id) < flag.default_rollout();
+    };
+
+    if row.denylist.contains(&guild_id) {
+        false
+    } else if row.allowlist.contains(&guild_id) {
+        true
+    } else {
+        rollout_bucket(flag, guild_id) < row.rollout
+    }
+}
+
+pub async fn get_flag(bot: &StarboardBot, flag: Flag) -> StarboardResult<Option<Arc<FeatureFlag>>> {
+    if let Some(row) = bot.cache.feature_flags.get(&flag) {
+        return Ok(row);
+    }
+
+    let row = FeatureFlag::get(&bot.pool, flag.name())
+        .await?
+        .map(Arc::new);
+    bot.cache.feature_flags.insert(flag, row.clone()).await;
+
+    Ok(row)
+}
+
+pub async fn is_feature_enabled(
+    bot: &StarboardBot,
+    flag: Flag,
+    guild_id: i64,
+) -> StarboardResult<bool> {
+    let row = get_flag(bot, flag).await?;
+    Ok(is_enabled(flag, row.as_deref(), guild_id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GUILD: i64 = 123456789012345678;
+
+    fn row(rollout: i16, allowlist: Vec<i64>, denylist: Vec<i64>) -> FeatureFlag {
+        FeatureFlag {
+            name: Flag::GuildPurge.name().to_string(),
+            rollout,
+            allowlist,
+            denylist,
+        }
+    }
+
+    #[test]
+    fn bucket_is_stable() {
+        // buckets are shared by every process, so they must never change
+        assert_eq!(rollout_bucket(Flag::GuildPurge, GUILD), 8);
+        assert_eq!(rollout_bucket(Flag::NsfwRefresh, GUILD), 81);
+        assert_eq!(rollout_bucket(Flag::GuildPurge, 0), 38);
+    }
+
+    #[test]
+    fn bucket_spread() {
+        let mut counts = [0; 100];
+        for guild_id in 0..10_000 {
+            let bucket = rollout_bucket(Flag::WebhookPosting, guild_id);
+            assert!((0..100).contains(&bucket));
+            counts[bucket as usize] += 1;
+        }
+        assert!(counts.iter().all(|&c| (50..150).contains(&c)));
+    }
+
+    #[test]
+    fn rollout_only_adds() {
+        for guild_id in 0..1_000 {
+            let enabled: Vec<_> = (0..=100)
+                .map(|rollout| {
+                    is_enabled(
+                        Flag::GuildPurge,
+                        Some(&row(rollout, vec![], vec![])),
+                        guild_id,
+                    )
+                })
+                .collect();
+            assert!(!enabled[0]);
+            assert!(enabled[100]);
+            assert!(enabled.windows(2).all(|w| w[1] || !w[0]));
+        }
+    }
+
+    #[test]
+    fn lists_take_precedence() {
+        assert!(is_enabled(
+            Flag::GuildPurge,
+            Some(&row(0, vec![GUILD], vec![])),
+            GUILD
+        ));
+        assert!(!is_enabled(
+            Flag::GuildPurge,
+            Some(&row(100, vec![], vec![GUILD])),
+            GUILD
+        ));
+        // the denylist wins over the allowlist
+        assert!(!is_enabled(
+            Flag::GuildPurge,
+            Some(&row(100, vec![GUILD], vec![GUILD])),
+            GUILD
+        ));
+        // other guilds fall through to the rollout
+        assert!(!is_enabled(
+            Flag::GuildPurge,
+            Some(&row(0, vec![GUILD], vec![])),
+            GUILD + 1
+        ));
+    }
+
+    #[test]
+    fn missing_row_uses_default() {
+        for flag in Flag::ALL {
+            assert_eq!(flag.default_rollout(), 0);
+            assert!(!is_enabled(flag, None, GUILD));
+        }
+    }
+
+    #[test]
+    fn flag_names() {
+        for flag in Flag::ALL {
+            assert_eq!(flag.name().parse(), Ok(flag));
+        }
+        assert_eq!("unknown".parse::<Flag>(), Err(()));
+    }
+}
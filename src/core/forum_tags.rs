@@ -0,0 +1,41 @@
+//! Tag requirements for forum posts. They only apply to messages in posts of
+//! a forum channel; other messages always meet them.
+
+use twilight_model::id::{
+    marker::{GuildMarker, TagMarker},
+    Id,
+};
+
+use crate::{client::bot::StarboardBot, utils::id_as_i64::GetI64};
+
+/// Whether a post with these tags meets the requirements. `None` means the
+/// message isn't in a forum post.
+pub fn tags_allowed(required: &[i64], excluded: &[i64], tags: Option<&[Id<TagMarker>]>) -> bool {
+    let Some(tags) = tags else {
+        return true;
+    };
+
+    if tags.iter().any(|tag| excluded.contains(&tag.get_i64())) {
+        return false;
+    }
+    required.is_empty() || tags.iter().any(|tag| required.contains(&tag.get_i64()))
+}
+
+/// The names of the tags, for settings. Tags of forums that aren't cached (or
+/// were deleted) show as their id.
+pub fn format_tags(bot: &StarboardBot, guild_id: Id<GuildMarker>, tags: &[i64]) -> String {
+    if tags.is_empty() {
+        return "none".to_string();
+    }
+
+    let names = bot.cache.guild_forum_tags(guild_id);
+    tags.iter()
+        .map(
+            |id| match names.iter().find(|(tag_id, _)| tag_id.get_i64() == *id) {
+                Some((_, name)) => format!("`{name}`"),
+                None => format!("`{id}`"),
+            },
+        )
+        .collect::<Vec<_>>()
+        .join(", ")
+}
@@ -0,0 +1,480 @@
+//! Structured logs of notable events, posted to a guild's log channel
+//! (`/settings log-channel`). Nothing is logged for guilds without one, and
+//! entries are dropped while a guild is over the `GUILD_LOG` cooldown, so a
+//! mass event can't flood the channel.
+
+use std::sync::Arc;
+
+use dashmap::DashMap;
+use serde::Serialize;
+use tracing::Instrument;
+use twilight_model::{
+    channel::message::Embed,
+    id::{
+        marker::{ChannelMarker, GuildMarker, UserMarker},
+        Id,
+    },
+};
+use twilight_util::builder::embed::{EmbedBuilder, EmbedFieldBuilder, EmbedFooterBuilder};
+
+use crate::{
+    client::bot::StarboardBot,
+    constants,
+    database::DbGuild,
+    errors::StarboardResult,
+    utils::{embed, id_as_i64::GetI64, into_id::IntoId},
+};
+
+pub enum LogEntry {
+    PostCreated {
+        starboard: String,
+        points: i32,
+        message_link: String,
+        post_link: String,
+    },
+    PostRemoved {
+        starboard: String,
+        points: i32,
+        message_link: String,
+    },
+    MessageTrashed {
+        moderator: Id<UserMarker>,
+        message_link: String,
+        reason: Option<String>,
+    },
+    MessageUntrashed {
+        moderator: Id<UserMarker>,
+        message_link: String,
+    },
+    MessageForced {
+        moderator: Id<UserMarker>,
+        message_link: String,
+        /// `None` for all starboards.
+        starboard: Option<String>,
+    },
+    MessageUnforced {
+        moderator: Id<UserMarker>,
+        message_link: String,
+        starboard: Option<String>,
+    },
+    SettingsChanged {
+        moderator: Id<UserMarker>,
+        /// What was changed, e.g. "starboard 'stars'".
+        target: String,
+        changes: Vec<SettingChange>,
+    },
+    /// Vote emojis that were deleted from the guild, as lines like
+    /// "starboard 'stars': emoji `123`".
+    MissingEmojis { lines: Vec<String> },
+    MissingPermissions {
+        channel_id: Id<ChannelMarker>,
+        /// What the bot tried to do, e.g. "send a post".
+        action: String,
+    },
+    /// A starboard was enabled or disabled by `/starboards schedule`.
+    StarboardScheduled { starboard: String, enabled: bool },
+    /// Progress on removing the bot's reactions from a starboard's posts.
+    /// `remaining` is 0 once it's done.
+    ReactionCleanup {
+        starboard: String,
+        cleaned: usize,
+        remaining: i64,
+    },
+}
+
+pub struct SettingChange {
+    pub name: String,
+    pub old: String,
+    pub new: String,
+}
+
+impl LogEntry {
+    fn embed(&self) -> EmbedBuilder {
+        let starboard_name = |name: &Option<String>| match name {
+            Some(name) => format!("'{name}'"),
+            None => "all starboards".to_string(),
+        };
+
+        match self {
+            Self::PostCreated {
+                starboard,
+                points,
+                message_link,
+                post_link,
+            } => embed::build().title("Post Created").description(format!(
+                "A [message]({message_link}) reached '{starboard}' with {points} points. \
+                [Jump to post]({post_link})"
+            )),
+            Self::PostRemoved {
+                starboard,
+                points,
+                message_link,
+            } => embed::build().title("Post Removed").description(format!(
+                "The post for a [message]({message_link}) was removed from '{starboard}' at \
+                {points} points."
+            )),
+            Self::MessageTrashed {
+                moderator,
+                message_link,
+                reason,
+            } => {
+                let emb = embed::build().title("Message Trashed").description(format!(
+                    "<@{moderator}> trashed a [message]({message_link})."
+                ));
+                match reason {
+                    Some(reason) => emb.field(EmbedFieldBuilder::new("Reason", reason)),
+                    None => emb,
+                }
+            }
+            Self::MessageUntrashed {
+                moderator,
+                message_link,
+            } => embed::build()
+                .title("Message Untrashed")
+                .description(format!(
+                    "<@{moderator}> untrashed a [message]({message_link})."
+                )),
+            Self::MessageForced {
+                moderator,
+                message_link,
+                starboard,
+            } => embed::build().title("Message Forced").description(format!(
+                "<@{moderator}> forced a [message]({message_link}) to {}.",
+                starboard_name(starboard)
+            )),
+            Self::MessageUnforced {
+                moderator,
+                message_link,
+                starboard,
+            } => embed::build()
+                .title("Message Unforced")
+                .description(format!(
+                    "<@{moderator}> unforced a [message]({message_link}) from {}.",
+                    starboard_name(starboard)
+                )),
+            Self::SettingsChanged {
+                moderator,
+                target,
+                changes,
+            } => {
+                let mut description = format!("<@{moderator}> changed the settings of {target}:");
+                for change in changes {
+                    description.push_str(&format!(
+                        "\n- `{}`: {} → {}",
+                        change.name, change.old, change.new
+                    ));
+                }
+                embed::build()
+                    .title("Settings Changed")
+                    .description(truncate(description, constants::MAX_EMBED_DESC_LENGTH))
+            }
+            Self::MissingEmojis { lines } => {
+                let mut description = "A custom emoji was deleted, but it's still used to vote. \
+                    Votes with it can't be cast anymore:"
+                    .to_string();
+                for line in lines {
+                    description.push_str(&format!("\n- {line}"));
+                }
+                description.push_str("\n\nUse `/starboards doctor` to see every missing emoji.");
+                embed::build()
+                    .title("Vote Emoji Deleted")
+                    .description(truncate(description, constants::MAX_EMBED_DESC_LENGTH))
+            }
+            Self::MissingPermissions { channel_id, action } => embed::build()
+                .title("Missing Permissions")
+                .description(format!(
+                    "I couldn't {action} in <#{channel_id}>. Make sure I have the permissions I \
+                    need in that channel."
+                )),
+            Self::StarboardScheduled { starboard, enabled } => {
+                let (title, action) = match enabled {
+                    true => ("Starboard Enabled", "enabled"),
+                    false => ("Starboard Disabled", "disabled"),
+                };
+                embed::build().title(title).description(format!(
+                    "Starboard '{starboard}' was {action} as scheduled."
+                ))
+            }
+            Self::ReactionCleanup {
+                starboard,
+                cleaned,
+                remaining,
+            } => match remaining {
+                0 => embed::build()
+                    .title("Reaction Cleanup Finished")
+                    .description(format!(
+                        "I removed my reactions from the posts of '{starboard}'."
+                    )),
+                _ => embed::build()
+                    .title("Reaction Cleanup Progress")
+                    .description(format!(
+                        "I removed my reactions from {cleaned} more posts of '{starboard}'. \
+                        {remaining} posts are left."
+                    )),
+            },
+        }
+    }
+}
+
+fn truncate(mut text: String, max: usize) -> String {
+    if text.chars().count() > max {
+        text = text.chars().take(max - 1).collect();
+        text.push('…');
+    }
+    text
+}
+
+/// The settings that differ between `old` and `new`, compared field by field
+/// as JSON. Fields missing on one side (unset overrides) show as "unset".
+pub fn diff_settings(old: &impl Serialize, new: &impl Serialize) -> Vec<SettingChange> {
+    let fmt = |value: Option<&serde_json::Value>| match value {
+        None => "unset".to_string(),
+        Some(serde_json::Value::Null) => "none".to_string(),
+        Some(serde_json::Value::String(s)) => s.clone(),
+        Some(other) => other.to_string(),
+    };
+
+    let old = serde_json::to_value(old).unwrap();
+    let new = serde_json::to_value(new).unwrap();
+    let (Some(old), Some(new)) = (old.as_object(), new.as_object()) else {
+        return Vec::new();
+    };
+
+    let mut names: Vec<_> = old.keys().chain(new.keys()).collect();
+    names.sort();
+    names.dedup();
+
+    names
+        .into_iter()
+        .filter(|name| old.get(*name) != new.get(*name))
+        .map(|name| SettingChange {
+            name: name.replace('_', "-"),
+            old: truncate(fmt(old.get(name)), constants::MAX_LOGGED_SETTING_LENGTH),
+            new: truncate(fmt(new.get(name)), constants::MAX_LOGGED_SETTING_LENGTH),
+        })
+        .collect()
+}
+
+#[derive(Default)]
+pub struct GuildLogger {
+    /// How many entries were dropped per guild because of the cooldown. The
+    /// next entry that's sent mentions them.
+    skipped: DashMap<Id<GuildMarker>, u64>,
+}
+
+impl GuildLogger {
+    /// The guild's log channel, if it has one.
+    pub async fn channel(
+        &self,
+        bot: &StarboardBot,
+        guild_id: Id<GuildMarker>,
+    ) -> StarboardResult<Option<Id<ChannelMarker>>> {
+        let guild_id = guild_id.get_i64();
+        let cached = bot
+            .cache
+            .guild_log_channels
+            .with(&guild_id, |_, channel| channel.as_ref().map(|c| *c.value()));
+        let channel_id = match cached {
+            Some(channel_id) => channel_id,
+            None => {
+                let channel_id = DbGuild::get_log_channel_id(&bot.pool, guild_id).await?;
+                bot.cache.guild_log_channels.insert(guild_id, channel_id);
+                channel_id
+            }
+        };
+
+        Ok(channel_id.map(|id| id.into_id()))
+    }
+
+    pub async fn set_channel(
+        &self,
+        bot: &StarboardBot,
+        guild_id: Id<GuildMarker>,
+        channel_id: Option<Id<ChannelMarker>>,
+    ) -> StarboardResult<()> {
+        let guild_id = guild_id.get_i64();
+        let channel_id = channel_id.map(|id| id.get_i64());
+        DbGuild::create(&bot.pool, guild_id).await?;
+        DbGuild::set_log_channel_id(&bot.pool, guild_id, channel_id).await?;
+        bot.cache.guild_log_channels.insert(guild_id, channel_id);
+
+        Ok(())
+    }
+
+    /// Logs the settings that differ between `old` and `new`, if any.
+    pub fn log_settings(
+        &self,
+        bot: &Arc<StarboardBot>,
+        guild_id: Id<GuildMarker>,
+        moderator: Option<Id<UserMarker>>,
+        target: String,
+        old: &impl Serialize,
+        new: &impl Serialize,
+    ) {
+        let Some(moderator) = moderator else {
+            return;
+        };
+        let changes = diff_settings(old, new);
+        if changes.is_empty() {
+            return;
+        }
+
+        let entry = LogEntry::SettingsChanged {
+            moderator,
+            target,
+            changes,
+        };
+        self.log(bot, guild_id, entry);
+    }
+
+    /// Logs `entry` in the background.
+    pub fn log(&self, bot: &Arc<StarboardBot>, guild_id: Id<GuildMarker>, entry: LogEntry) {
+        let bot = bot.clone();
+        let task = async move {
+            bot.guild_log.send(&bot, guild_id, entry).await;
+        };
+        tokio::spawn(task.in_current_span());
+    }
+
+    /// Logs `entry` and returns whether the guild has a log channel, even if
+    /// the entry was dropped or couldn't be sent.
+    pub async fn send(
+        &self,
+        bot: &StarboardBot,
+        guild_id: Id<GuildMarker>,
+        entry: LogEntry,
+    ) -> bool {
+        let channel_id = match self.channel(bot, guild_id).await {
+            Ok(Some(channel_id)) => channel_id,
+            Ok(None) => return false,
+            Err(why) => {
+                bot.handle_error(&why).await;
+                return false;
+            }
+        };
+
+        if bot.cooldowns.guild_log.trigger(&guild_id).is_some() {
+            *self.skipped.entry(guild_id).or_default() += 1;
+            return true;
+        }
+
+        let mut emb = entry.embed();
+        if let Some((_, skipped)) = self.skipped.remove(&guild_id) {
+            emb = emb.footer(EmbedFooterBuilder::new(format!(
+                "{skipped} earlier entries were skipped to avoid flooding this channel."
+            )));
+        }
+        let emb: Embed = emb.build();
+
+        let ret = match bot.http.create_message(channel_id).embeds(&[emb]) {
+            Ok(req) => req.await.map(|_| ()).map_err(|why| why.to_string()),
+            Err(why) => Err(why.to_string()),
+        };
+        if let Err(why) = ret {
+            // usually the channel was deleted or the bot can't send there
+            tracing::debug!("Couldn't log to {channel_id} in {guild_id}: {why}");
+        }
+
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn description(entry: LogEntry) -> String {
+        entry.embed().build().description.unwrap()
+    }
+
+    #[test]
+    fn truncates_by_chars() {
+        assert_eq!(truncate("⭐⭐⭐".to_string(), 3), "⭐⭐⭐");
+        assert_eq!(truncate("⭐⭐⭐⭐".to_string(), 3), "⭐⭐…");
+    }
+
+    #[test]
+    fn changed_settings() {
+        let old = json!({"color": 1, "display_emoji": "⭐", "private": false});
+        let new = json!({"color": 2, "display_emoji": "⭐", "private": null});
+        let changes = diff_settings(&old, &new);
+
+        let changes: Vec<_> = changes
+            .iter()
+            .map(|c| (c.name.as_str(), c.old.as_str(), c.new.as_str()))
+            .collect();
+        assert_eq!(
+            changes,
+            vec![("color", "1", "2"), ("private", "false", "none")]
+        );
+    }
+
+    #[test]
+    fn unset_overrides() {
+        let changes = diff_settings(&json!({}), &json!({"display_emoji": "💎"}));
+
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].name, "display-emoji");
+        assert_eq!(changes[0].old, "unset");
+        assert_eq!(changes[0].new, "💎");
+    }
+
+    #[test]
+    fn long_settings_are_cut_off() {
+        let long = "a".repeat(constants::MAX_LOGGED_SETTING_LENGTH + 1);
+        let changes = diff_settings(&json!({"x": ""}), &json!({ "x": long }));
+
+        assert_eq!(
+            changes[0].new.chars().count(),
+            constants::MAX_LOGGED_SETTING_LENGTH
+        );
+        assert!(changes[0].new.ends_with('…'));
+    }
+
+    #[test]
+    fn settings_entry() {
+        let entry = LogEntry::SettingsChanged {
+            moderator: Id::new(1),
+            target: "starboard 'stars'".to_string(),
+            changes: diff_settings(&json!({"color": 1}), &json!({"color": 2})),
+        };
+
+        assert_eq!(
+            description(entry),
+            "<@1> changed the settings of starboard 'stars':\n- `color`: 1 → 2"
+        );
+    }
+
+    #[test]
+    fn forced_to_all_starboards() {
+        let entry = |starboard: Option<&str>| LogEntry::MessageForced {
+            moderator: Id::new(1),
+            message_link: "link".to_string(),
+            starboard: starboard.map(str::to_string),
+        };
+
+        assert_eq!(
+            description(entry(None)),
+            "<@1> forced a [message](link) to all starboards."
+        );
+        assert_eq!(
+            description(entry(Some("stars"))),
+            "<@1> forced a [message](link) to 'stars'."
+        );
+    }
+
+    #[test]
+    fn cleanup_finished() {
+        let entry = |remaining| LogEntry::ReactionCleanup {
+            starboard: "stars".to_string(),
+            cleaned: 5,
+            remaining,
+        };
+
+        let title = |entry: LogEntry| entry.embed().build().title.unwrap();
+        assert_eq!(title(entry(3)), "Reaction Cleanup Progress");
+        assert_eq!(title(entry(0)), "Reaction Cleanup Finished");
+    }
+}
@@ -0,0 +1,115 @@
+//! When the bot is removed from a guild, its data is kept for
+//! `GUILD_RETENTION_DAYS` in case it's added back, then purged.
+
+use std::sync::Arc;
+
+use chrono::Utc;
+use twilight_model::id::{marker::GuildMarker, Id};
+
+use crate::{
+    client::bot::StarboardBot,
+    constants,
+    core::features::{is_feature_enabled, Flag},
+    database::{DbGuild, DbMessage, Vote},
+    errors::StarboardResult,
+    utils::{id_as_i64::GetI64, into_id::IntoId},
+};
+
+pub async fn handle_guild_create(
+    bot: &StarboardBot,
+    guild_id: Id<GuildMarker>,
+) -> StarboardResult<()> {
+    DbGuild::set_left_at(&bot.pool, guild_id.get_i64(), None).await?;
+    Ok(())
+}
+
+/// Should only be called if the bot was removed from the guild, not if the
+/// guild is unavailable.
+pub async fn handle_guild_delete(
+    bot: &StarboardBot,
+    guild_id: Id<GuildMarker>,
+) -> StarboardResult<()> {
+    DbGuild::set_left_at(&bot.pool, guild_id.get_i64(), Some(Utc::now())).await?;
+    Ok(())
+}
+
+pub async fn loop_purge_left_guilds(bot: Arc<StarboardBot>) {
+    loop {
+        tokio::time::sleep(constants::PURGE_LEFT_GUILDS_DELAY).await;
+
+        if let Err(why) = purge_left_guilds(&bot).await {
+            bot.handle_error(&why).await;
+        }
+    }
+}
+
+async fn purge_left_guilds(bot: &StarboardBot) -> StarboardResult<()> {
+    let before = Utc::now() - chrono::Duration::days(bot.config.guild_retention_days);
+    let guild_ids = DbGuild::list_left_before(&bot.pool, before).await?;
+
+    for guild_id in guild_ids {
+        if !bot.owns_guild(guild_id as u64)
+            || !is_feature_enabled(bot, Flag::GuildPurge, guild_id).await?
+        {
+            continue;
+        }
+        // in case the GuildCreate was missed
+        if bot.cache.guilds.contains_key(&guild_id.into_id()) {
+            DbGuild::set_left_at(&bot.pool, guild_id, None).await?;
+            continue;
+        }
+
+        purge_guild(bot, guild_id).await?;
+    }
+
+    Ok(())
+}
+
+pub struct PurgedGuild {
+    pub votes: u64,
+    pub messages: u64,
+}
+
+/// Deletes everything stored for a guild. Votes and messages are deleted in
+/// batches so that no single statement holds its locks for long, and the
+/// rest is deleted with the guild.
+pub async fn purge_guild(bot: &StarboardBot, guild_id: i64) -> StarboardResult<PurgedGuild> {
+    let mut purged = PurgedGuild {
+        votes: 0,
+        messages: 0,
+    };
+
+    loop {
+        let deleted =
+            Vote::delete_batch_by_guild(&bot.pool, guild_id, constants::PURGE_GUILD_BATCH_SIZE)
+                .await?;
+        purged.votes += deleted;
+        if deleted < constants::PURGE_GUILD_BATCH_SIZE as u64 {
+            break;
+        }
+        tokio::time::sleep(constants::PURGE_GUILD_BATCH_DELAY).await;
+    }
+
+    loop {
+        let deleted = DbMessage::delete_batch_by_guild(
+            &bot.pool,
+            guild_id,
+            constants::PURGE_GUILD_BATCH_SIZE,
+        )
+        .await?;
+        purged.messages += deleted;
+        if deleted < constants::PURGE_GUILD_BATCH_SIZE as u64 {
+            break;
+        }
+        tokio::time::sleep(constants::PURGE_GUILD_BATCH_DELAY).await;
+    }
+
+    DbGuild::delete(&bot.pool, guild_id).await?;
+    tracing::info!(
+        "Purged guild {guild_id} ({} votes, {} messages).",
+        purged.votes,
+        purged.messages
+    );
+
+    Ok(purged)
+}
@@ -1,5 +1,13 @@
+use lazy_static::lazy_static;
+use regex::Regex;
 use twilight_model::channel::{message::embed::Embed, Attachment};
 
+use crate::cache::models::message::CachedMessage;
+
+lazy_static! {
+    static ref LINK_REGEX: Regex = Regex::new(r"https?://[^\s<>]+\.[^\s<>]+").unwrap();
+}
+
 pub fn has_image(embeds: &Vec<Embed>, attachments: &Vec<Attachment>) -> bool {
     for attachment in attachments {
         if let Some(content_type) = &attachment.content_type {
@@ -17,3 +25,17 @@ pub fn has_image(embeds: &Vec<Embed>, attachments: &Vec<Attachment>) -> bool {
 
     false
 }
+
+/// Like `has_image`, but stickers count as well.
+pub fn message_has_image(msg: &CachedMessage) -> bool {
+    !msg.stickers.is_empty() || has_image(&msg.embeds, &msg.attachments)
+}
+
+pub fn has_link(content: &str) -> bool {
+    LINK_REGEX.is_match(content)
+}
+
+/// The length of a message's text, in characters.
+pub fn text_length(content: &str) -> usize {
+    content.trim().chars().count()
+}
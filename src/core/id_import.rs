@@ -0,0 +1,228 @@
+//! Importing lists of ids from a file, for `/blocklist import` and
+//! `/utils ignore import`. The parser is tolerant of the formats people paste
+//! from other bots: one id per line or separated by commas, semicolons or
+//! spaces, with mentions, a byte order mark and CRLF line endings.
+
+use std::{collections::HashSet, fmt::Write};
+
+use twilight_model::channel::Attachment;
+
+use crate::{client::bot::StarboardBot, constants, errors::StarboardResult};
+
+#[derive(Debug, Default)]
+pub struct ParsedIds {
+    /// The valid ids, in the order they appeared, without duplicates.
+    pub ids: Vec<i64>,
+    /// The number of ids that appeared more than once.
+    pub duplicates: usize,
+    /// Examples of invalid entries, capped at `MAX_ID_IMPORT_ERRORS`.
+    pub errors: Vec<String>,
+    /// The total number of invalid entries.
+    pub error_count: usize,
+}
+
+impl ParsedIds {
+    fn invalid(&mut self, line: usize, entry: &str) {
+        self.error_count += 1;
+        if self.errors.len() < constants::MAX_ID_IMPORT_ERRORS {
+            let entry = if entry.chars().count() > 32 {
+                format!("{}...", entry.chars().take(32).collect::<String>())
+            } else {
+                entry.to_string()
+            };
+            self.errors
+                .push(format!("line {line}: invalid id `{entry}`"));
+        }
+    }
+
+    /// Describes the import for confirmation. `already_present` is the
+    /// number of ids that are already on the list.
+    pub fn summary(&self, already_present: usize) -> String {
+        let mut summary = format!(
+            "Valid entries: {}\nAlready present: {}\nNew: {}\n",
+            self.ids.len(),
+            already_present,
+            self.ids.len() - already_present,
+        );
+        if self.duplicates != 0 {
+            writeln!(summary, "Duplicates in the file: {}", self.duplicates).unwrap();
+        }
+        writeln!(summary, "Invalid entries: {}", self.error_count).unwrap();
+
+        if !self.errors.is_empty() {
+            summary.push_str("```\n");
+            for error in &self.errors {
+                writeln!(summary, "{error}").unwrap();
+            }
+            summary.push_str("```");
+            let hidden = self.error_count - self.errors.len();
+            if hidden != 0 {
+                write!(summary, "...and {hidden} more.").unwrap();
+            }
+        }
+
+        summary
+    }
+}
+
+/// Parses one entry, which can be a plain id or a mention.
+fn parse_entry(entry: &str) -> Option<i64> {
+    let id = entry
+        .strip_prefix("<@")
+        .and_then(|e| e.strip_suffix('>'))
+        .map(|e| e.trim_start_matches('!'))
+        .unwrap_or(entry);
+
+    match id.parse::<u64>() {
+        Ok(id) if id != 0 && id <= i64::MAX as u64 => Some(id as i64),
+        _ => None,
+    }
+}
+
+/// Parses a list of ids. A non-numeric first entry is treated as a header.
+/// Returns an error if there are more than `MAX_ID_IMPORT_ENTRIES` ids.
+pub fn parse_ids(input: &str) -> Result<ParsedIds, String> {
+    let input = input.strip_prefix('\u{feff}').unwrap_or(input);
+
+    let mut parsed = ParsedIds::default();
+    let mut seen = HashSet::new();
+    let mut first_entry = true;
+
+    for (idx, line) in input.lines().enumerate() {
+        let entries = line
+            .split(|c: char| c == ',' || c == ';' || c.is_whitespace())
+            .map(|e| e.trim_matches('"'))
+            .filter(|e| !e.is_empty());
+
+        for entry in entries {
+            let is_first_entry = first_entry;
+            first_entry = false;
+
+            let Some(id) = parse_entry(entry) else {
+                if !is_first_entry || entry.chars().any(|c| c.is_ascii_digit()) {
+                    parsed.invalid(idx + 1, entry);
+                }
+                continue;
+            };
+            if !seen.insert(id) {
+                parsed.duplicates += 1;
+                continue;
+            }
+
+            if parsed.ids.len() >= constants::MAX_ID_IMPORT_ENTRIES {
+                return Err(format!(
+                    "You can only import up to {} ids at once.",
+                    constants::MAX_ID_IMPORT_ENTRIES
+                ));
+            }
+            parsed.ids.push(id);
+        }
+    }
+
+    Ok(parsed)
+}
+
+/// Downloads an uploaded list. Returns `None` if it's too large.
+pub async fn download(bot: &StarboardBot, file: &Attachment) -> StarboardResult<Option<String>> {
+    if file.size > constants::MAX_ID_IMPORT_SIZE {
+        return Ok(None);
+    }
+
+    let file = bot.reqwest.get(&file.url).send().await?.bytes().await?;
+    Ok(Some(String::from_utf8_lossy(&file).into_owned()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn separators_and_mentions() {
+        let input = "\u{feff}1, 2;3 4\r\n<@5>\r\n<@!6>\n\"7\"\n";
+        let parsed = parse_ids(input).unwrap();
+
+        assert_eq!(parsed.ids, vec![1, 2, 3, 4, 5, 6, 7]);
+        assert_eq!(parsed.error_count, 0);
+    }
+
+    #[test]
+    fn header_is_skipped() {
+        let parsed = parse_ids("user_id\n1\n2").unwrap();
+        assert_eq!(parsed.ids, vec![1, 2]);
+        assert_eq!(parsed.error_count, 0);
+
+        // a numeric-looking first entry is an invalid id, not a header
+        let parsed = parse_ids("1x\n2").unwrap();
+        assert_eq!(parsed.ids, vec![2]);
+        assert_eq!(parsed.errors, vec!["line 1: invalid id `1x`"]);
+    }
+
+    #[test]
+    fn invalid_entries() {
+        let parsed = parse_ids("1\nabc\n0\n9223372036854775808").unwrap();
+
+        assert_eq!(parsed.ids, vec![1]);
+        assert_eq!(
+            parsed.errors,
+            vec![
+                "line 2: invalid id `abc`",
+                "line 3: invalid id `0`",
+                "line 4: invalid id `9223372036854775808`",
+            ]
+        );
+    }
+
+    #[test]
+    fn duplicates_are_counted() {
+        let parsed = parse_ids("1, 2, 1\n<@2>").unwrap();
+
+        assert_eq!(parsed.ids, vec![1, 2]);
+        assert_eq!(parsed.duplicates, 2);
+    }
+
+    #[test]
+    fn errors_are_capped() {
+        let input = vec!["x"; constants::MAX_ID_IMPORT_ERRORS + 6].join("\n");
+        let parsed = parse_ids(&format!("1\n{input}")).unwrap();
+
+        assert_eq!(parsed.errors.len(), constants::MAX_ID_IMPORT_ERRORS);
+        assert_eq!(parsed.error_count, constants::MAX_ID_IMPORT_ERRORS + 6);
+        assert!(parsed.summary(0).ends_with("...and 6 more."));
+    }
+
+    #[test]
+    fn long_entries_are_shortened() {
+        let entry = "x".repeat(40);
+        let parsed = parse_ids(&format!("1 {entry}")).unwrap();
+
+        assert_eq!(
+            parsed.errors,
+            vec![format!("line 1: invalid id `{}...`", "x".repeat(32))]
+        );
+    }
+
+    #[test]
+    fn entries_are_capped() {
+        let input = (1..=constants::MAX_ID_IMPORT_ENTRIES as i64)
+            .map(|id| id.to_string())
+            .collect::<Vec<_>>()
+            .join("\n");
+        assert!(parse_ids(&input).is_ok());
+        assert!(parse_ids(&format!("{input}\n0\n1")).is_ok());
+        assert!(parse_ids(&format!("{input}\n99999")).is_err());
+    }
+
+    #[test]
+    fn summary() {
+        let parsed = parse_ids("1, 2, 2, x").unwrap();
+
+        assert_eq!(
+            parsed.summary(1),
+            concat!(
+                "Valid entries: 2\nAlready present: 1\nNew: 1\n",
+                "Duplicates in the file: 1\nInvalid entries: 1\n",
+                "```\nline 1: invalid id `x`\n```"
+            )
+        );
+    }
+}
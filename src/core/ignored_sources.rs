@@ -0,0 +1,241 @@
+//! Webhooks and applications (e.g. bridges and logging bots) whose messages
+//! are never starboarded or autostarred in a guild.
+
+use std::{
+    cmp::Reverse,
+    collections::HashSet,
+    fmt,
+    sync::{atomic::Ordering, Arc},
+};
+
+use twilight_model::{
+    channel::Message,
+    id::{marker::GuildMarker, Id},
+};
+
+use crate::{
+    cache::models::message::CachedMessage, client::bot::StarboardBot, database::DbGuild,
+    errors::StarboardResult, utils::id_as_i64::GetI64,
+};
+
+#[derive(Debug, Default)]
+pub struct IgnoredSources {
+    pub webhook_ids: HashSet<i64>,
+    pub application_ids: HashSet<i64>,
+}
+
+impl IgnoredSources {
+    pub fn is_empty(&self) -> bool {
+        self.webhook_ids.is_empty() && self.application_ids.is_empty()
+    }
+
+    pub fn is_ignored(&self, message: &CachedMessage) -> bool {
+        if let Some(webhook_id) = message.webhook_id {
+            if self.webhook_ids.contains(&webhook_id.get_i64()) {
+                return true;
+            }
+        }
+        if let Some(application_id) = message.application_id {
+            if self.application_ids.contains(&application_id.get_i64()) {
+                return true;
+            }
+        }
+
+        // a bot's user id is the same as its application id
+        self.application_ids.contains(&message.author_id.get_i64())
+    }
+}
+
+pub async fn get_ignored_sources(
+    bot: &StarboardBot,
+    guild_id: i64,
+) -> StarboardResult<Arc<IgnoredSources>> {
+    let cached = bot
+        .cache
+        .guild_ignored_sources
+        .with(&guild_id, |_, sources| {
+            sources.as_ref().map(|s| s.value().clone())
+        });
+    if let Some(sources) = cached {
+        return Ok(sources);
+    }
+
+    let sources = match DbGuild::get(&bot.pool, guild_id).await? {
+        None => IgnoredSources::default(),
+        Some(guild) => IgnoredSources {
+            webhook_ids: guild.ignored_webhook_ids.into_iter().collect(),
+            application_ids: guild.ignored_application_ids.into_iter().collect(),
+        },
+    };
+    let sources = Arc::new(sources);
+    bot.cache
+        .guild_ignored_sources
+        .insert(guild_id, sources.clone());
+
+    Ok(sources)
+}
+
+/// Whether the message was sent by an ignored webhook or application.
+pub async fn is_ignored_source(
+    bot: &StarboardBot,
+    guild_id: i64,
+    message: &CachedMessage,
+) -> StarboardResult<bool> {
+    Ok(get_ignored_sources(bot, guild_id)
+        .await?
+        .is_ignored(message))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MessageSource {
+    Webhook(i64),
+    Application(i64),
+}
+
+impl MessageSource {
+    pub fn of(message: &Message) -> Option<Self> {
+        if let Some(webhook_id) = message.webhook_id {
+            Some(Self::Webhook(webhook_id.get_i64()))
+        } else if let Some(application_id) = message.application_id {
+            Some(Self::Application(application_id.get_i64()))
+        } else if message.author.bot {
+            Some(Self::Application(message.author.id.get_i64()))
+        } else {
+            None
+        }
+    }
+}
+
+impl fmt::Display for MessageSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Webhook(id) => write!(f, "webhook `{id}`"),
+            Self::Application(id) => write!(f, "app <@{id}> (`{id}`)"),
+        }
+    }
+}
+
+/// Counts messages from webhooks and bots, so `/utils ignore list` can
+/// suggest what to ignore. Counts expire a day after a source is first seen.
+pub async fn count_message(bot: &StarboardBot, guild_id: Id<GuildMarker>, message: &Message) {
+    let Some(source) = MessageSource::of(message) else {
+        return;
+    };
+
+    bot.cache
+        .message_sources
+        .get_with((guild_id.get_i64(), source), async { Default::default() })
+        .await
+        .fetch_add(1, Ordering::Relaxed);
+}
+
+/// The sources that sent the most messages in the guild recently, most
+/// first.
+pub fn top_message_sources(
+    bot: &StarboardBot,
+    guild_id: i64,
+    limit: usize,
+) -> Vec<(MessageSource, u64)> {
+    let mut sources: Vec<_> = bot
+        .cache
+        .message_sources
+        .iter()
+        .filter(|(key, _)| key.0 == guild_id)
+        .map(|(key, count)| (key.1, count.load(Ordering::Relaxed)))
+        .collect();
+    sources.sort_by_key(|(_, count)| Reverse(*count));
+    sources.truncate(limit);
+    sources
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn message(webhook_id: Option<&str>, application_id: Option<&str>, bot: bool) -> Message {
+        serde_json::from_value(json!({
+            "id": "2",
+            "channel_id": "3",
+            "author": {
+                "id": "4",
+                "username": "user",
+                "discriminator": "0",
+                "avatar": null,
+                "bot": bot,
+            },
+            "content": "",
+            "timestamp": "2023-06-19T12:00:00.000000+00:00",
+            "edited_timestamp": null,
+            "tts": false,
+            "mention_everyone": false,
+            "mentions": [],
+            "mention_roles": [],
+            "attachments": [],
+            "embeds": [],
+            "pinned": false,
+            "type": 0,
+            "webhook_id": webhook_id,
+            "application_id": application_id,
+        }))
+        .unwrap()
+    }
+
+    fn sources(webhook_ids: &[i64], application_ids: &[i64]) -> IgnoredSources {
+        IgnoredSources {
+            webhook_ids: webhook_ids.iter().copied().collect(),
+            application_ids: application_ids.iter().copied().collect(),
+        }
+    }
+
+    #[test]
+    fn webhooks_from_gateway() {
+        let msg = message(Some("10"), None, true);
+        let cached = CachedMessage::from(&msg);
+
+        assert!(sources(&[10], &[]).is_ignored(&cached));
+        assert!(!sources(&[11], &[]).is_ignored(&cached));
+    }
+
+    #[test]
+    fn applications_from_rest() {
+        let cached = CachedMessage::from(message(None, Some("20"), true));
+
+        assert!(sources(&[], &[20]).is_ignored(&cached));
+        assert!(!sources(&[20], &[]).is_ignored(&cached));
+    }
+
+    #[test]
+    fn bots_by_user_id() {
+        let cached = CachedMessage::from(message(None, None, true));
+
+        assert!(sources(&[], &[4]).is_ignored(&cached));
+    }
+
+    #[test]
+    fn users_are_never_ignored_without_sources() {
+        let cached = CachedMessage::from(message(None, None, false));
+
+        assert!(IgnoredSources::default().is_empty());
+        assert!(!IgnoredSources::default().is_ignored(&cached));
+        assert!(!sources(&[10], &[20]).is_ignored(&cached));
+    }
+
+    #[test]
+    fn message_sources() {
+        assert_eq!(
+            MessageSource::of(&message(Some("10"), Some("20"), true)),
+            Some(MessageSource::Webhook(10))
+        );
+        assert_eq!(
+            MessageSource::of(&message(None, Some("20"), true)),
+            Some(MessageSource::Application(20))
+        );
+        assert_eq!(
+            MessageSource::of(&message(None, None, true)),
+            Some(MessageSource::Application(4))
+        );
+        assert_eq!(MessageSource::of(&message(None, None, false)), None);
+    }
+}
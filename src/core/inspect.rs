@@ -0,0 +1,499 @@
+//! Compares what the cache, the database and Discord know about a message, so
+//! that reports like "the bot says 7 stars but there are 9 reactions" can be
+//! tracked down. Used by `star inspect`.
+
+use std::{fmt, str::FromStr, sync::Arc};
+
+use twilight_model::{
+    channel::Message,
+    id::{
+        marker::{ChannelMarker, MessageMarker},
+        Id,
+    },
+};
+
+use crate::{
+    cache::models::message::CachedMessage,
+    client::bot::StarboardBot,
+    core::{
+        emoji::SimpleEmoji,
+        premium::is_premium::is_guild_premium,
+        starboard::{config::StarboardConfig, handle::RefreshMessage},
+    },
+    database::{DbMessage, StarboardMessage, Vote},
+    errors::StarboardResult,
+    interactions::custom_id::CustomId,
+    utils::{get_status::get_status, id_as_i64::GetI64, into_id::IntoId},
+};
+
+/// What the cache has for a message.
+pub enum CacheState {
+    Missing,
+    CachedAsDeleted,
+    Cached(Arc<CachedMessage>),
+}
+
+pub struct LiveReaction {
+    pub emoji: SimpleEmoji,
+    /// Reactions, not counting the bot's own.
+    pub count: i64,
+    /// How each starboard treats the emoji, e.g. "upvote on 'main'".
+    pub classification: Vec<String>,
+}
+
+pub struct StarboardInspection {
+    pub name: String,
+    /// The points stored in the database.
+    pub points: i32,
+    /// Upvote reactions minus downvote reactions, ignoring requirements.
+    pub live_points: i64,
+    pub post_id: Option<i64>,
+    /// Whether the post is missing from the starboard channel.
+    pub post_missing: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mismatch {
+    /// The cached message differs from the one on Discord.
+    CacheStale,
+    /// The stored points don't match the reactions.
+    VotesMissing,
+    /// A post is stored for the message, but it was deleted.
+    OrphanedPost,
+}
+
+impl Mismatch {
+    pub fn fix(self) -> InspectFix {
+        match self {
+            Self::CacheStale => InspectFix::Refresh,
+            Self::VotesMissing => InspectFix::Recount,
+            Self::OrphanedPost => InspectFix::Relink,
+        }
+    }
+}
+
+impl fmt::Display for Mismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let desc = match self {
+            Self::CacheStale => "cache is stale",
+            Self::VotesMissing => "stored points don't match the reactions",
+            Self::OrphanedPost => "a stored post was deleted",
+        };
+        f.write_str(desc)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InspectFix {
+    /// Rebuild the votes from the reactions.
+    Recount,
+    /// Drop the cached message and refresh the posts.
+    Refresh,
+    /// Forget deleted posts and refresh, so they're sent again.
+    Relink,
+}
+
+impl InspectFix {
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Recount => "Recount",
+            Self::Refresh => "Refresh",
+            Self::Relink => "Relink",
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Recount => "recount",
+            Self::Refresh => "refresh",
+            Self::Relink => "relink",
+        }
+    }
+
+    pub fn custom_id(self, channel_id: i64, message_id: i64) -> String {
+        CustomId::InspectFix {
+            fix: self,
+            channel_id,
+            message_id,
+        }
+        .encode()
+    }
+}
+
+impl FromStr for InspectFix {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "recount" => Ok(Self::Recount),
+            "refresh" => Ok(Self::Refresh),
+            "relink" => Ok(Self::Relink),
+            _ => Err(()),
+        }
+    }
+}
+
+pub struct Inspection {
+    pub channel_id: i64,
+    pub message_id: i64,
+    pub cache: CacheState,
+    /// `None` if the message isn't on Discord anymore.
+    pub live_content: Option<String>,
+    pub db_message: Option<DbMessage>,
+    pub reactions: Vec<LiveReaction>,
+    pub starboards: Vec<StarboardInspection>,
+    pub mismatches: Vec<Mismatch>,
+}
+
+impl Inspection {
+    pub fn fixes(&self) -> Vec<InspectFix> {
+        let mut fixes: Vec<_> = self.mismatches.iter().map(|m| m.fix()).collect();
+        fixes.dedup();
+        fixes
+    }
+}
+
+/// Gathers everything known about a message. Starboard posts are resolved to
+/// their original message first.
+pub async fn inspect_message(
+    bot: &StarboardBot,
+    channel_id: Id<ChannelMarker>,
+    message_id: Id<MessageMarker>,
+) -> StarboardResult<Inspection> {
+    let db_message = DbMessage::get_original(&bot.pool, message_id.get_i64()).await?;
+    let (channel_id, message_id) = match &db_message {
+        Some(orig) => (orig.channel_id, orig.message_id),
+        None => (channel_id.get_i64(), message_id.get_i64()),
+    };
+
+    let cache = match bot.cache.messages.get(&message_id.into_id()) {
+        None => CacheState::Missing,
+        Some(None) => CacheState::CachedAsDeleted,
+        Some(Some(msg)) => CacheState::Cached(msg),
+    };
+
+    let live = bot
+        .http
+        .message(channel_id.into_id(), message_id.into_id())
+        .await;
+    let live = match live {
+        Ok(msg) => Some(msg.model().await?),
+        Err(why) => {
+            if !matches!(get_status(&why), Some(403 | 404)) {
+                return Err(why.into());
+            }
+            None
+        }
+    };
+
+    let configs = match &db_message {
+        Some(orig) => {
+            StarboardConfig::list_for_channel(
+                bot,
+                orig.guild_id.into_id(),
+                orig.channel_id.into_id(),
+            )
+            .await?
+        }
+        None => Vec::new(),
+    };
+    let configs: Vec<_> = configs
+        .into_iter()
+        .filter(|c| !c.starboard.channel_deleted)
+        .collect();
+
+    let mut reactions = Vec::new();
+    for reaction in live.iter().flat_map(|msg| &msg.reactions) {
+        let emoji = SimpleEmoji::from(reaction.emoji.clone());
+        let mut classification = Vec::new();
+        for config in &configs {
+            let name = &config.starboard.name;
+            if config.resolved.upvote_emojis.iter().any(|e| emoji == *e) {
+                classification.push(format!("upvote on '{name}'"));
+            } else if config.resolved.downvote_emojis.iter().any(|e| emoji == *e) {
+                classification.push(format!("downvote on '{name}'"));
+            }
+        }
+
+        reactions.push(LiveReaction {
+            emoji,
+            count: reaction.count as i64 - i64::from(reaction.me),
+            classification,
+        });
+    }
+
+    let mut starboards = Vec::new();
+    for config in &configs {
+        let points = Vote::count(&bot.pool, message_id, config.starboard.id)
+            .await?
+            .points;
+
+        let mut live_points = 0;
+        for reaction in &reactions {
+            if config
+                .resolved
+                .upvote_emojis
+                .iter()
+                .any(|e| reaction.emoji == *e)
+            {
+                live_points += reaction.count;
+            } else if config
+                .resolved
+                .downvote_emojis
+                .iter()
+                .any(|e| reaction.emoji == *e)
+            {
+                live_points -= reaction.count;
+            }
+        }
+
+        let post =
+            StarboardMessage::get_by_starboard(&bot.pool, message_id, config.starboard.id).await?;
+        let post_missing = match &post {
+            None => false,
+            Some(post) => is_post_missing(bot, config, post).await?,
+        };
+
+        starboards.push(StarboardInspection {
+            name: config.starboard.name.clone(),
+            points,
+            live_points,
+            post_id: post.map(|p| p.starboard_message_id),
+            post_missing,
+        });
+    }
+
+    let mismatches = find_mismatches(&cache, live.as_ref(), &starboards);
+
+    Ok(Inspection {
+        channel_id,
+        message_id,
+        cache,
+        live_content: live.map(|msg| msg.content),
+        db_message,
+        reactions,
+        starboards,
+        mismatches,
+    })
+}
+
+/// What doesn't add up between the cache, the database and the message on
+/// Discord (`None` if it's gone).
+fn find_mismatches(
+    cache: &CacheState,
+    live: Option<&Message>,
+    starboards: &[StarboardInspection],
+) -> Vec<Mismatch> {
+    let mut mismatches = Vec::new();
+    let cache_stale = match (cache, live) {
+        (CacheState::CachedAsDeleted, Some(_)) => true,
+        (CacheState::Cached(_), None) => true,
+        (CacheState::Cached(cached), Some(live)) => {
+            cached.content != live.content || cached.attachments.len() != live.attachments.len()
+        }
+        _ => false,
+    };
+    if cache_stale {
+        mismatches.push(Mismatch::CacheStale);
+    }
+    if live.is_some()
+        && starboards
+            .iter()
+            .any(|sb| i64::from(sb.points) != sb.live_points)
+    {
+        mismatches.push(Mismatch::VotesMissing);
+    }
+    if starboards.iter().any(|sb| sb.post_missing) {
+        mismatches.push(Mismatch::OrphanedPost);
+    }
+
+    mismatches
+}
+
+async fn is_post_missing(
+    bot: &StarboardBot,
+    config: &StarboardConfig,
+    post: &StarboardMessage,
+) -> StarboardResult<bool> {
+    let post = bot
+        .cache
+        .fog_message(
+            bot,
+            config.starboard.channel_id.into_id(),
+            post.starboard_message_id.into_id(),
+        )
+        .await?;
+
+    Ok(post.is_missing())
+}
+
+/// Forgets the stored posts that were deleted, so that the next refresh sends
+/// them again. Returns how many were forgotten.
+pub async fn unlink_missing_posts(bot: &StarboardBot, orig: &DbMessage) -> StarboardResult<usize> {
+    let configs =
+        StarboardConfig::list_for_channel(bot, orig.guild_id.into_id(), orig.channel_id.into_id())
+            .await?;
+
+    let mut unlinked = 0;
+    for config in configs {
+        if config.starboard.channel_deleted {
+            continue;
+        }
+        let Some(post) =
+            StarboardMessage::get_by_starboard(&bot.pool, orig.message_id, config.starboard.id)
+                .await?
+        else {
+            continue;
+        };
+
+        if is_post_missing(bot, &config, &post).await? {
+            StarboardMessage::delete(&bot.pool, post.starboard_message_id).await?;
+            unlinked += 1;
+        }
+    }
+
+    Ok(unlinked)
+}
+
+/// Refreshes the posts for a message, dropping the cached message first.
+pub async fn refresh_message(bot: Arc<StarboardBot>, orig: DbMessage) -> StarboardResult<()> {
+    bot.cache
+        .messages
+        .invalidate(&orig.message_id.into_id())
+        .await;
+
+    let is_premium = is_guild_premium(&bot, orig.guild_id, true).await?;
+    let mut refresh = RefreshMessage::new(bot, orig.message_id.into_id(), is_premium);
+    refresh.set_sql_message(orig);
+    refresh.refresh(true).await?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message(content: &str) -> Message {
+        serde_json::from_value(serde_json::json!({
+            "id": "2",
+            "channel_id": "3",
+            "author": {
+                "id": "4",
+                "username": "user",
+                "discriminator": "0",
+                "avatar": null,
+            },
+            "content": content,
+            "timestamp": "2023-06-07T12:00:00.000000+00:00",
+            "edited_timestamp": null,
+            "tts": false,
+            "mention_everyone": false,
+            "mentions": [],
+            "mention_roles": [],
+            "attachments": [],
+            "embeds": [],
+            "pinned": false,
+            "type": 0,
+        }))
+        .unwrap()
+    }
+
+    fn cached(content: &str) -> CacheState {
+        CacheState::Cached(Arc::new(CachedMessage::from(&message(content))))
+    }
+
+    fn starboard(points: i32, live_points: i64, post_missing: bool) -> StarboardInspection {
+        StarboardInspection {
+            name: "main".to_string(),
+            points,
+            live_points,
+            post_id: Some(1),
+            post_missing,
+        }
+    }
+
+    #[test]
+    fn nothing_wrong() {
+        let live = message("hello");
+
+        assert!(
+            find_mismatches(&cached("hello"), Some(&live), &[starboard(3, 3, false)]).is_empty()
+        );
+        assert!(find_mismatches(&CacheState::Missing, Some(&live), &[]).is_empty());
+        assert!(find_mismatches(&CacheState::CachedAsDeleted, None, &[]).is_empty());
+    }
+
+    #[test]
+    fn stale_cache() {
+        let live = message("edited");
+
+        assert_eq!(
+            find_mismatches(&cached("hello"), Some(&live), &[]),
+            vec![Mismatch::CacheStale]
+        );
+        assert_eq!(
+            find_mismatches(&CacheState::CachedAsDeleted, Some(&live), &[]),
+            vec![Mismatch::CacheStale]
+        );
+        assert_eq!(
+            find_mismatches(&cached("hello"), None, &[]),
+            vec![Mismatch::CacheStale]
+        );
+    }
+
+    #[test]
+    fn missing_votes() {
+        let live = message("hello");
+
+        assert_eq!(
+            find_mismatches(
+                &CacheState::Missing,
+                Some(&live),
+                &[starboard(3, 3, false), starboard(7, 9, false)]
+            ),
+            vec![Mismatch::VotesMissing]
+        );
+        // without the message, the reactions can't be compared
+        assert!(find_mismatches(&CacheState::Missing, None, &[starboard(7, 9, false)]).is_empty());
+    }
+
+    #[test]
+    fn orphaned_post() {
+        assert_eq!(
+            find_mismatches(&CacheState::Missing, None, &[starboard(3, 3, true)]),
+            vec![Mismatch::OrphanedPost]
+        );
+    }
+
+    #[test]
+    fn suggested_fixes() {
+        let inspection = Inspection {
+            channel_id: 3,
+            message_id: 2,
+            cache: CacheState::Missing,
+            live_content: None,
+            db_message: None,
+            reactions: Vec::new(),
+            starboards: Vec::new(),
+            mismatches: vec![
+                Mismatch::CacheStale,
+                Mismatch::VotesMissing,
+                Mismatch::OrphanedPost,
+            ],
+        };
+
+        assert_eq!(
+            inspection.fixes(),
+            vec![InspectFix::Refresh, InspectFix::Recount, InspectFix::Relink]
+        );
+    }
+
+    #[test]
+    fn fix_names_round_trip() {
+        for fix in [InspectFix::Recount, InspectFix::Refresh, InspectFix::Relink] {
+            assert_eq!(fix.name().parse(), Ok(fix));
+        }
+        assert_eq!("delete".parse::<InspectFix>(), Err(()));
+    }
+}
@@ -1,11 +1,33 @@
 pub mod autostar;
+pub mod blocked_voters;
+pub mod competitions;
+pub mod config_drift;
+pub mod deleted_channels;
+pub mod discord_limits;
 pub mod embedder;
 pub mod emoji;
+pub mod emoji_migration;
+pub mod emoji_tiers;
+pub mod features;
 pub mod filters;
+pub mod forum_tags;
+pub mod guild_log;
+pub mod guild_purge;
 pub mod has_image;
+pub mod id_import;
+pub mod ignored_sources;
+pub mod inspect;
+pub mod mod_notes;
 pub mod permroles;
 pub mod posroles;
+pub mod post_latency;
 pub mod premium;
+pub mod removed_emojis;
 pub mod starboard;
 pub mod stats;
+pub mod translate;
+pub mod user_purge;
+pub mod vote_weights;
+pub mod xp_seasons;
+pub mod xp_transfer;
 pub mod xproles;
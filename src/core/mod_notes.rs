@@ -0,0 +1,46 @@
+//! Notes that moderators leave on messages. They're stored for the original
+//! message, so notes added from a starboard post show up on the original
+//! (and the other way around).
+
+use crate::database::{ModNote, StarboardMessage};
+
+/// The original message, if `message_id` is a starboard post.
+pub async fn original_message_id(pool: &sqlx::PgPool, message_id: i64) -> sqlx::Result<i64> {
+    Ok(StarboardMessage::get(pool, message_id)
+        .await?
+        .map_or(message_id, |sb_msg| sb_msg.message_id))
+}
+
+pub fn format_note(note: &ModNote) -> String {
+    format!(
+        "`#{}` by <@{}> <t:{}:R>\n{}",
+        note.id,
+        note.author_id,
+        note.created_at.timestamp(),
+        note.note,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeZone, Utc};
+
+    use super::*;
+
+    #[test]
+    fn formats_note() {
+        let note = ModNote {
+            id: 7,
+            guild_id: 1,
+            message_id: 2,
+            author_id: 3,
+            note: "reposted from elsewhere".to_string(),
+            created_at: Utc.timestamp_opt(1_690_000_000, 0).unwrap(),
+        };
+
+        assert_eq!(
+            format_note(&note),
+            "`#7` by <@3> <t:1690000000:R>\nreposted from elsewhere"
+        );
+    }
+}
@@ -41,6 +41,9 @@ pub async fn loop_update_posroles(bot: Arc<StarboardBot>) {
 
         let mut tasks = Vec::new();
         for guild in guilds {
+            if !bot.owns_guild(guild.guild_id as u64) {
+                continue;
+            }
             let is_prem = match is_guild_premium(&bot, guild.guild_id, true).await {
                 Ok(is_prem) => is_prem,
                 Err(why) => {
@@ -0,0 +1,316 @@
+//! "Time to post" for premium guilds: how long it takes from the vote that
+//! made a message qualify until its post is sent. Latencies are rolled up
+//! into an hourly histogram per guild in `post_latencies`, with a fixed set
+//! of buckets, so that the amount of data stays bounded.
+
+use std::{collections::BTreeMap, fmt, sync::Arc};
+
+use chrono::{DateTime, Duration, DurationRound, Utc};
+
+use crate::{
+    client::bot::StarboardBot,
+    constants,
+    database::{PostLatency, Vote},
+    errors::StarboardResult,
+    utils::into_id::IntoId,
+};
+
+use super::starboard::config::StarboardConfig;
+
+/// The bucket for a latency in milliseconds.
+pub fn latency_bucket(latency_ms: i64) -> i16 {
+    constants::POST_LATENCY_BUCKETS_MS
+        .iter()
+        .position(|&bound| latency_ms <= bound)
+        .unwrap_or(constants::POST_LATENCY_BUCKETS_MS.len()) as i16
+}
+
+fn hour_of(time: DateTime<Utc>) -> DateTime<Utc> {
+    time.duration_trunc(Duration::hours(1)).unwrap_or(time)
+}
+
+/// A latency quantile, as precise as the buckets allow.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Quantile {
+    AtMost(i64),
+    /// Slower than the largest bucket.
+    Over(i64),
+}
+
+impl Quantile {
+    fn exceeds(self, threshold_ms: i64) -> bool {
+        match self {
+            Self::AtMost(ms) => ms > threshold_ms,
+            Self::Over(_) => true,
+        }
+    }
+}
+
+impl fmt::Display for Quantile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (prefix, ms) = match *self {
+            Self::AtMost(ms) => ("≤", ms),
+            Self::Over(ms) => (">", ms),
+        };
+        if ms < 1_000 {
+            write!(f, "{prefix}{ms}ms")
+        } else if ms < 60_000 {
+            write!(f, "{prefix}{}s", ms / 1_000)
+        } else {
+            write!(f, "{prefix}{}m", ms / 60_000)
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct LatencyHistogram {
+    counts: Vec<i64>,
+}
+
+impl LatencyHistogram {
+    pub fn from_rows<'a>(rows: impl IntoIterator<Item = &'a PostLatency>) -> Self {
+        let mut counts = vec![0; constants::POST_LATENCY_BUCKETS_MS.len() + 1];
+        for row in rows {
+            if let Some(count) = counts.get_mut(row.bucket as usize) {
+                *count += i64::from(row.count);
+            }
+        }
+
+        Self { counts }
+    }
+
+    pub fn total(&self) -> i64 {
+        self.counts.iter().sum()
+    }
+
+    /// The upper bound of the bucket that the `q`th quantile falls in.
+    pub fn quantile(&self, q: f64) -> Option<Quantile> {
+        let total = self.total();
+        if total == 0 {
+            return None;
+        }
+
+        let target = ((total as f64) * q).ceil().max(1.0) as i64;
+        let mut seen = 0;
+        for (idx, count) in self.counts.iter().enumerate() {
+            seen += count;
+            if seen >= target {
+                return Some(match constants::POST_LATENCY_BUCKETS_MS.get(idx) {
+                    Some(&bound) => Quantile::AtMost(bound),
+                    None => Quantile::Over(*constants::POST_LATENCY_BUCKETS_MS.last().unwrap()),
+                });
+            }
+        }
+
+        None
+    }
+}
+
+/// Records the time to post for a post that was just sent. Only premium
+/// guilds are tracked, and starboards where the delay is expected (vote
+/// spread, aggregate starboards) are skipped.
+pub async fn record_time_to_post(
+    bot: &StarboardBot,
+    config: &StarboardConfig,
+    guild_id: i64,
+    message_id: i64,
+    is_premium: bool,
+) -> StarboardResult<()> {
+    if !is_premium
+        || config.starboard.aggregate
+        || config.resolved.min_vote_spread_seconds.is_some()
+    {
+        return Ok(());
+    }
+
+    let Some(voted_at) = Vote::last_created_at(&bot.pool, message_id, config.starboard.id).await?
+    else {
+        return Ok(());
+    };
+
+    let now = Utc::now();
+    let latency_ms = (now - voted_at).num_milliseconds().max(0);
+    PostLatency::record(
+        &bot.pool,
+        guild_id,
+        hour_of(now),
+        latency_bucket(latency_ms),
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// "p50 ≤1s, p95 ≤3s over 42 posts", for the last 24 hours.
+pub async fn summarize_time_to_post(
+    bot: &StarboardBot,
+    guild_id: i64,
+) -> StarboardResult<Option<String>> {
+    let since = hour_of(Utc::now()) - Duration::hours(constants::POST_LATENCY_SUMMARY_HOURS - 1);
+    let rows = PostLatency::list_by_guild_since(&bot.pool, guild_id, since).await?;
+    let histogram = LatencyHistogram::from_rows(&rows);
+
+    let (Some(p50), Some(p95)) = (histogram.quantile(0.5), histogram.quantile(0.95)) else {
+        return Ok(None);
+    };
+
+    Ok(Some(format!(
+        "p50 {p50}, p95 {p95} over {} posts",
+        histogram.total()
+    )))
+}
+
+/// Every hour, reports premium guilds whose posts were slow during the last
+/// hour to the error channel, and forgets old latencies.
+pub async fn loop_post_latency_alerts(bot: Arc<StarboardBot>) {
+    loop {
+        tokio::time::sleep(constants::POST_LATENCY_ALERT_DELAY).await;
+
+        if let Err(why) = check_post_latencies(&bot).await {
+            bot.handle_error(&why).await;
+        }
+    }
+}
+
+async fn check_post_latencies(bot: &StarboardBot) -> StarboardResult<()> {
+    let current_hour = hour_of(Utc::now());
+    PostLatency::delete_before(
+        &bot.pool,
+        current_hour - Duration::hours(constants::POST_LATENCY_RETENTION_HOURS),
+    )
+    .await?;
+
+    let Some(error_channel) = bot.config.error_channel else {
+        return Ok(());
+    };
+
+    let rows = PostLatency::list_by_hour(&bot.pool, current_hour - Duration::hours(1)).await?;
+    let mut by_guild: BTreeMap<i64, Vec<PostLatency>> = BTreeMap::new();
+    for row in rows {
+        by_guild.entry(row.guild_id).or_default().push(row);
+    }
+
+    for (guild_id, rows) in by_guild {
+        if !bot.owns_guild(guild_id as u64) {
+            continue;
+        }
+
+        let histogram = LatencyHistogram::from_rows(&rows);
+        if histogram.total() < constants::POST_LATENCY_ALERT_MIN_POSTS {
+            continue;
+        }
+        let Some(p95) = histogram.quantile(0.95) else {
+            continue;
+        };
+        if !p95.exceeds(constants::POST_LATENCY_ALERT_P95_MS) {
+            continue;
+        }
+
+        let msg = format!(
+            "Slow posts in guild {guild_id} during the last hour: p95 {p95} over {} posts.",
+            histogram.total()
+        );
+        tracing::warn!("{msg}");
+        bot.http
+            .create_message(error_channel.into_id())
+            .content(&msg)?
+            .await?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    fn histogram(counts: &[(i16, i32)]) -> LatencyHistogram {
+        let rows: Vec<_> = counts
+            .iter()
+            .map(|&(bucket, count)| PostLatency {
+                guild_id: 1,
+                hour: Utc.with_ymd_and_hms(2023, 6, 30, 12, 0, 0).unwrap(),
+                bucket,
+                count,
+            })
+            .collect();
+        LatencyHistogram::from_rows(&rows)
+    }
+
+    #[test]
+    fn buckets() {
+        assert_eq!(latency_bucket(0), 0);
+        assert_eq!(latency_bucket(500), 0);
+        assert_eq!(latency_bucket(501), 1);
+        assert_eq!(latency_bucket(10_000), 5);
+        assert_eq!(latency_bucket(300_000), 8);
+        assert_eq!(latency_bucket(300_001), 9);
+    }
+
+    #[test]
+    fn hours() {
+        let time = Utc.with_ymd_and_hms(2023, 6, 30, 12, 34, 56).unwrap();
+
+        assert_eq!(
+            hour_of(time),
+            Utc.with_ymd_and_hms(2023, 6, 30, 12, 0, 0).unwrap()
+        );
+    }
+
+    #[test]
+    fn rows_add_up() {
+        // rows for the same bucket come from different hours
+        let histogram = histogram(&[(0, 3), (0, 2), (4, 1), (9, 4)]);
+
+        assert_eq!(histogram.total(), 10);
+        assert_eq!(histogram.counts[0], 5);
+        assert_eq!(histogram.counts[4], 1);
+        assert_eq!(histogram.counts[9], 4);
+    }
+
+    #[test]
+    fn unknown_buckets_are_ignored() {
+        assert_eq!(histogram(&[(10, 3), (0, 1)]).total(), 1);
+    }
+
+    #[test]
+    fn quantiles() {
+        // 50 posts within 500ms, 45 within 2s, 5 within 30s
+        let histogram = histogram(&[(0, 50), (2, 45), (6, 5)]);
+
+        assert_eq!(histogram.quantile(0.5), Some(Quantile::AtMost(500)));
+        assert_eq!(histogram.quantile(0.51), Some(Quantile::AtMost(2_000)));
+        assert_eq!(histogram.quantile(0.95), Some(Quantile::AtMost(2_000)));
+        assert_eq!(histogram.quantile(0.96), Some(Quantile::AtMost(30_000)));
+        assert_eq!(histogram.quantile(0.0), Some(Quantile::AtMost(500)));
+    }
+
+    #[test]
+    fn slowest_bucket() {
+        let histogram = histogram(&[(0, 1), (9, 1)]);
+
+        assert_eq!(histogram.quantile(0.95), Some(Quantile::Over(300_000)));
+    }
+
+    #[test]
+    fn no_posts() {
+        assert_eq!(histogram(&[]).quantile(0.5), None);
+    }
+
+    #[test]
+    fn exceeds_threshold() {
+        assert!(Quantile::AtMost(30_000).exceeds(10_000));
+        assert!(!Quantile::AtMost(10_000).exceeds(10_000));
+        assert!(Quantile::Over(300_000).exceeds(10_000));
+    }
+
+    #[test]
+    fn formats_quantiles() {
+        assert_eq!(Quantile::AtMost(500).to_string(), "≤500ms");
+        assert_eq!(Quantile::AtMost(2_000).to_string(), "≤2s");
+        assert_eq!(Quantile::AtMost(300_000).to_string(), "≤5m");
+        assert_eq!(Quantile::Over(300_000).to_string(), ">5m");
+    }
+}
@@ -8,6 +8,7 @@ use crate::{
 
 use super::{
     is_premium::is_guild_premium,
+    notify::notify_premium_changed,
     redeem::{redeem_premium, RedeemPremiumResult},
 };
 
@@ -31,6 +32,7 @@ async fn check_expired_premium(bot: Arc<StarboardBot>) -> StarboardResult<()> {
     .await?;
 
     for guild in expired_guilds {
+        notify_premium_changed(&bot, guild.guild_id).await?;
         tokio::spawn(StarboardBot::catch_future_errors(
             bot.clone(),
             process_expired_guild(bot.clone(), guild.guild_id),
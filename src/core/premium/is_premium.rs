@@ -6,16 +6,13 @@ pub async fn is_guild_premium(
     allow_cache: bool,
 ) -> StarboardResult<bool> {
     if allow_cache {
-        let cached = bot.cache.guild_premium.with(&guild_id, |_, is_premium| {
-            is_premium.as_ref().map(|v| *v.value())
-        });
-        if let Some(cached) = cached {
+        if let Some(cached) = bot.cache.guild_premium.get(&guild_id) {
             return Ok(cached);
         };
     }
 
     let is_premium = true;
 
-    bot.cache.guild_premium.insert(guild_id, is_premium);
+    bot.cache.guild_premium.insert(guild_id, is_premium).await;
     Ok(is_premium)
 }
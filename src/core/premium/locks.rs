@@ -24,7 +24,8 @@ pub async fn refresh_premium_locks(
     for row in unlocked_asc_channel_ids {
         bot.cache
             .autostar_channel_ids
-            .insert(row.channel_id.into_id());
+            .add(row.channel_id.into_id())
+            .await;
     }
 
     // if premium, just return
@@ -1,6 +1,7 @@
 pub mod expire;
 pub mod is_premium;
 pub mod locks;
+pub mod notify;
 pub mod patreon;
 pub mod redeem;
 pub mod roles;
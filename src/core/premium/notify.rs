@@ -0,0 +1,53 @@
+//! Premium can be changed by another process, like the website, so changes
+//! are announced on a Postgres channel. Every bot process listens to it and
+//! drops its cached value for the guild. The payload is the guild id.
+
+use std::sync::Arc;
+
+use sqlx::postgres::PgListener;
+
+use crate::{client::bot::StarboardBot, constants, errors::StarboardResult};
+
+pub const PREMIUM_CHANNEL: &str = "premium_changed";
+
+/// Invalidates the cached premium status of a guild, in this process and
+/// every other one listening.
+pub async fn notify_premium_changed(bot: &StarboardBot, guild_id: i64) -> StarboardResult<()> {
+    bot.cache.invalidate_premium(guild_id).await;
+    sqlx::query!(
+        "SELECT pg_notify($1, $2)",
+        PREMIUM_CHANNEL,
+        guild_id.to_string(),
+    )
+    .fetch_one(&bot.pool)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn loop_listen_premium_changes(bot: Arc<StarboardBot>) {
+    loop {
+        if let Err(why) = listen_premium_changes(&bot).await {
+            bot.handle_error(&why).await;
+        }
+
+        // anything changed while disconnected is picked up by the TTL
+        tokio::time::sleep(constants::PREMIUM_LISTENER_RETRY).await;
+    }
+}
+
+async fn listen_premium_changes(bot: &StarboardBot) -> StarboardResult<()> {
+    let mut listener = PgListener::connect_with(&bot.pool).await?;
+    listener.listen(PREMIUM_CHANNEL).await?;
+
+    loop {
+        let notification = listener.recv().await?;
+        match notification.payload().parse() {
+            Ok(guild_id) => bot.cache.invalidate_premium(guild_id).await,
+            Err(_) => tracing::warn!(
+                "Invalid {PREMIUM_CHANNEL} payload: {:?}",
+                notification.payload()
+            ),
+        }
+    }
+}
@@ -21,7 +21,7 @@ struct PatronData {
 
 pub async fn patreon_loop(bot: Arc<StarboardBot>) {
     if bot.config.patreon_token.is_none() {
-        return eprintln!("Warning: no Patreon token set.");
+        return tracing::warn!("No Patreon token set.");
     }
 
     loop {
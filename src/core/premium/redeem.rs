@@ -7,7 +7,9 @@ use crate::{
     errors::StarboardResult,
 };
 
-use super::{is_premium::is_guild_premium, locks::refresh_premium_locks};
+use super::{
+    is_premium::is_guild_premium, locks::refresh_premium_locks, notify::notify_premium_changed,
+};
 
 #[derive(PartialEq, Eq)]
 pub enum RedeemPremiumResult {
@@ -83,7 +85,7 @@ pub async fn redeem_premium(
     // commit the transaction and return
     tx.commit().await?;
 
-    bot.cache.guild_premium.remove(&guild_id);
+    notify_premium_changed(bot, guild_id).await?;
     refresh_premium_locks(bot, guild_id, is_guild_premium(bot, guild_id, true).await?).await?;
 
     Ok(RedeemPremiumResult::Ok)
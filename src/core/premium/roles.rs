@@ -46,6 +46,9 @@ pub async fn update_supporter_roles(
     let Some(guild_id) = bot.config.main_guild else {
         return Ok(());
     };
+    if !bot.owns_guild(guild_id) {
+        return Ok(());
+    }
     let supporter_role = bot.config.supporter_role.map(|r| r.into_id());
     let patron_role = bot.config.patron_role.map(|r| r.into_id());
     let guild_id = guild_id.into_id();
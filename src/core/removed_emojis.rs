@@ -0,0 +1,250 @@
+//! Custom vote emojis that were deleted from their guild. Votes with them
+//! can't be cast anymore, so the guild's log channel (or the owner, if it has
+//! none) is told which starboards still use them, and `/starboards doctor`
+//! lists them.
+
+use std::{collections::HashMap, sync::Arc};
+
+use tracing::Instrument;
+use twilight_gateway::Event;
+use twilight_model::{
+    guild::Emoji,
+    id::{
+        marker::{EmojiMarker, GuildMarker},
+        Id,
+    },
+};
+
+use crate::{
+    client::bot::StarboardBot,
+    core::guild_log::LogEntry,
+    database::{Starboard, StarboardOverride},
+    errors::StarboardResult,
+    utils::{id_as_i64::GetI64, notify::notify},
+};
+
+/// A vote emoji of a starboard, or of one of its overrides, that doesn't
+/// exist anymore.
+pub struct MissingEmoji {
+    pub starboard: String,
+    pub override_name: Option<String>,
+    pub emoji_id: Id<EmojiMarker>,
+}
+
+impl MissingEmoji {
+    pub fn location(&self) -> String {
+        match &self.override_name {
+            None => format!("starboard '{}'", self.starboard),
+            Some(name) => format!("override '{}' of '{}'", name, self.starboard),
+        }
+    }
+}
+
+/// The custom emojis removed by this event, if any. Must be called before the
+/// cache is updated with the event.
+pub fn removed_emojis(
+    bot: &StarboardBot,
+    event: &Event,
+) -> Option<(Id<GuildMarker>, Vec<Id<EmojiMarker>>)> {
+    let Event::GuildEmojisUpdate(event) = event else {
+        return None;
+    };
+
+    let removed = bot.cache.guilds.with(&event.guild_id, |_, guild| {
+        Some(deleted_emojis(&guild.as_ref()?.emojis, &event.emojis))
+    })?;
+
+    (!removed.is_empty()).then_some((event.guild_id, removed))
+}
+
+/// Finds the vote emojis of a guild's starboards and overrides for which
+/// `exists` returns false.
+pub async fn find_missing(
+    bot: &StarboardBot,
+    guild_id: Id<GuildMarker>,
+    exists: impl Fn(Id<EmojiMarker>) -> bool,
+) -> StarboardResult<Vec<MissingEmoji>> {
+    let starboards = Starboard::list_by_guild(&bot.pool, guild_id.get_i64()).await?;
+    let overrides = StarboardOverride::list_by_guild(&bot.pool, guild_id.get_i64()).await?;
+
+    let mut missing = Vec::new();
+    for sb in &starboards {
+        let emojis = [
+            sb.settings.upvote_emojis.as_slice(),
+            sb.settings.downvote_emojis.as_slice(),
+        ];
+        for emoji_id in emojis.into_iter().flat_map(|e| missing_in(e, &exists)) {
+            missing.push(MissingEmoji {
+                starboard: sb.name.clone(),
+                override_name: None,
+                emoji_id,
+            });
+        }
+    }
+    for ov in overrides {
+        let Some(sb) = starboards.iter().find(|sb| sb.id == ov.starboard_id) else {
+            continue;
+        };
+        let Ok(values) = ov.get_overrides() else {
+            continue;
+        };
+
+        let emojis = [values.upvote_emojis, values.downvote_emojis];
+        for emoji_id in emojis.iter().flatten().flat_map(|e| missing_in(e, &exists)) {
+            missing.push(MissingEmoji {
+                starboard: sb.name.clone(),
+                override_name: Some(ov.name.clone()),
+                emoji_id,
+            });
+        }
+    }
+
+    Ok(missing)
+}
+
+/// The cached emojis that aren't in the guild's new emojis.
+fn deleted_emojis(
+    cached: &HashMap<Id<EmojiMarker>, bool>,
+    emojis: &[Emoji],
+) -> Vec<Id<EmojiMarker>> {
+    cached
+        .keys()
+        .filter(|id| !emojis.iter().any(|e| e.id == **id))
+        .copied()
+        .collect()
+}
+
+/// The custom emojis in a list of stored vote emojis that don't exist.
+fn missing_in(emojis: &[String], exists: impl Fn(Id<EmojiMarker>) -> bool) -> Vec<Id<EmojiMarker>> {
+    emojis
+        .iter()
+        .filter_map(|e| e.parse().ok())
+        .filter(|id| !exists(*id))
+        .collect()
+}
+
+/// The missing emojis to warn about, each once, skipping those that were
+/// already warned about.
+fn to_warn(
+    missing: &[MissingEmoji],
+    already_warned: impl Fn(Id<EmojiMarker>) -> bool,
+) -> Vec<Id<EmojiMarker>> {
+    let mut warned = Vec::new();
+    for m in missing {
+        if !warned.contains(&m.emoji_id) && !already_warned(m.emoji_id) {
+            warned.push(m.emoji_id);
+        }
+    }
+    warned
+}
+
+/// Tells the guild about starboards that vote with the removed emojis. Each
+/// emoji is only mentioned once a day.
+pub fn spawn_removed_emojis_warning(
+    bot: Arc<StarboardBot>,
+    guild_id: Id<GuildMarker>,
+    removed: Vec<Id<EmojiMarker>>,
+) {
+    let task = async move {
+        if let Err(why) = warn_removed_emojis(&bot, guild_id, &removed).await {
+            bot.handle_error(&why).await;
+        }
+    };
+    tokio::spawn(task.in_current_span());
+}
+
+async fn warn_removed_emojis(
+    bot: &StarboardBot,
+    guild_id: Id<GuildMarker>,
+    removed: &[Id<EmojiMarker>],
+) -> StarboardResult<()> {
+    let mut missing = find_missing(bot, guild_id, |id| !removed.contains(&id)).await?;
+
+    let warnings = &bot.cache.removed_emoji_warnings;
+    let warned = to_warn(&missing, |id| warnings.contains_key(&(guild_id, id)));
+    missing.retain(|m| warned.contains(&m.emoji_id));
+    if missing.is_empty() {
+        return Ok(());
+    }
+    for emoji_id in warned {
+        warnings.insert((guild_id, emoji_id), ()).await;
+    }
+
+    let lines: Vec<_> = missing
+        .iter()
+        .map(|m| format!("{}: emoji `{}`", m.location(), m.emoji_id))
+        .collect();
+    let entry = LogEntry::MissingEmojis {
+        lines: lines.clone(),
+    };
+    if bot.guild_log.send(bot, guild_id, entry).await {
+        return Ok(());
+    }
+
+    let guild = bot.http.guild(guild_id).await?.model().await?;
+    let mut message = format!(
+        "A custom emoji was deleted from **{}**, but it's still used to vote. Votes with it \
+        can't be cast anymore:",
+        guild.name
+    );
+    for line in lines {
+        message.push_str(&format!("\n- {line}"));
+    }
+    message.push_str("\n\nUse `/starboards doctor` to see every missing emoji.");
+    notify(bot, guild.owner_id, &message).await?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn missing(emoji_id: u64, override_name: Option<&str>) -> MissingEmoji {
+        MissingEmoji {
+            starboard: "sb".to_string(),
+            override_name: override_name.map(str::to_string),
+            emoji_id: Id::new(emoji_id),
+        }
+    }
+
+    #[test]
+    fn location() {
+        assert_eq!(missing(1, None).location(), "starboard 'sb'");
+        assert_eq!(missing(1, Some("ov")).location(), "override 'ov' of 'sb'");
+    }
+
+    #[test]
+    fn deleted() {
+        let cached = HashMap::from([(Id::new(1), false), (Id::new(2), true)]);
+        let emojis: Vec<Emoji> = serde_json::from_value(serde_json::json!([{
+            "id": "2",
+            "name": "two",
+            "animated": true,
+            "available": true,
+            "managed": false,
+            "require_colons": true,
+            "roles": [],
+        }]))
+        .unwrap();
+
+        assert_eq!(deleted_emojis(&cached, &emojis), vec![Id::new(1)]);
+        assert_eq!(deleted_emojis(&cached, &emojis[..0]).len(), 2);
+    }
+
+    #[test]
+    fn unicode_emojis_are_never_missing() {
+        let emojis = ["⭐".to_string(), "1".to_string(), "2".to_string()];
+
+        assert_eq!(missing_in(&emojis, |id| id.get() == 2), vec![Id::new(1)]);
+        assert!(missing_in(&emojis, |_| true).is_empty());
+    }
+
+    #[test]
+    fn warns_once_per_emoji() {
+        let missing = [missing(1, None), missing(1, Some("ov")), missing(2, None)];
+
+        assert_eq!(to_warn(&missing, |_| false), vec![Id::new(1), Id::new(2)]);
+        assert_eq!(to_warn(&missing, |id| id.get() == 1), vec![Id::new(2)]);
+    }
+}
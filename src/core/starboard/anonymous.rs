@@ -0,0 +1,125 @@
+//! Anonymous votes. Votes on starboards with `anonymous-votes` store a hash
+//! of the voter's id, salted per guild, in `voter_hash` instead of the id in
+//! `user_id`. Code outside the database passes both around as voter ids,
+//! which are negative for hashes. Whether a vote is valid is still decided
+//! with the real id, so self-votes, blocked voters and permroles work the
+//! same, but the stored votes can't be traced back to anyone.
+
+use sha2::{Digest, Sha256};
+
+use crate::{
+    client::bot::StarboardBot,
+    database::{DbGuild, Starboard},
+    errors::StarboardResult,
+};
+
+/// Hashes a voter's id. The result is always negative, so it can't be
+/// mistaken for a real id. Has to match the `voter_hash` SQL function.
+pub fn voter_hash(salt: i64, user_id: i64) -> i64 {
+    let mut hasher = Sha256::new();
+    hasher.update(salt.to_be_bytes());
+    hasher.update(user_id.to_be_bytes());
+    let digest = hasher.finalize();
+
+    let mut first = [0; 8];
+    first.copy_from_slice(&digest[..8]);
+    i64::from_be_bytes(first) | i64::MIN
+}
+
+/// The id that `user_id`'s votes on `starboard` are stored with.
+pub async fn stored_voter_id(
+    bot: &StarboardBot,
+    starboard: &Starboard,
+    user_id: i64,
+) -> StarboardResult<i64> {
+    if !starboard.settings.anonymous_votes {
+        return Ok(user_id);
+    }
+
+    let salt = DbGuild::get_vote_salt(&bot.pool, starboard.guild_id)
+        .await?
+        .unwrap();
+    Ok(voter_hash(salt, user_id))
+}
+
+/// Every id that `user_id`'s votes on a starboard might be stored with, the
+/// one new votes use first. Votes cast while `anonymous-votes` was enabled
+/// keep their hashed id after it's disabled, so those still have to count as
+/// the user's.
+pub fn voter_ids(anonymous: bool, salt: Option<i64>, user_id: i64) -> Vec<i64> {
+    match (anonymous, salt) {
+        (true, Some(salt)) => vec![voter_hash(salt, user_id)],
+        (false, Some(salt)) => vec![user_id, voter_hash(salt, user_id)],
+        (_, None) => vec![user_id],
+    }
+}
+
+/// Whether changing `anonymous-votes` from `was` to `now` hashes the votes
+/// already on the starboard. Only enabling it does, disabling it only affects
+/// new votes.
+pub fn anonymizes_existing(was: bool, now: bool) -> bool {
+    now && !was
+}
+
+/// See `voter_ids`.
+pub async fn stored_voter_ids(
+    bot: &StarboardBot,
+    starboard: &Starboard,
+    user_id: i64,
+) -> StarboardResult<Vec<i64>> {
+    let salt = DbGuild::get_vote_salt(&bot.pool, starboard.guild_id).await?;
+    Ok(voter_ids(starboard.settings.anonymous_votes, salt, user_id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hash_matches_sql() {
+        // sha256(int8send(42) || int8send(123456789012345678)), as computed by
+        // the `voter_hash` SQL function
+        assert_eq!(voter_hash(42, 123456789012345678), -2338163666725609993);
+    }
+
+    #[test]
+    fn hash_dedupes_votes() {
+        assert_eq!(voter_hash(1, 100), voter_hash(1, 100));
+        assert_ne!(voter_hash(1, 100), voter_hash(1, 101));
+        assert_ne!(voter_hash(1, 100), voter_hash(2, 100));
+    }
+
+    #[test]
+    fn hash_is_never_a_user_id() {
+        for user_id in [0, 1, 100, i64::MAX] {
+            assert!(voter_hash(7, user_id) < 0);
+        }
+    }
+
+    #[test]
+    fn anonymous_voter_ids() {
+        assert_eq!(voter_ids(true, Some(1), 100), vec![voter_hash(1, 100)]);
+    }
+
+    #[test]
+    fn voter_ids_after_disabling() {
+        assert_eq!(
+            voter_ids(false, Some(1), 100),
+            vec![100, voter_hash(1, 100)]
+        );
+    }
+
+    #[test]
+    fn voter_ids_without_salt() {
+        assert_eq!(voter_ids(false, None, 100), vec![100]);
+        assert_eq!(voter_ids(true, None, 100), vec![100]);
+    }
+
+    #[test]
+    fn only_enabling_anonymizes() {
+        assert!(anonymizes_existing(false, true));
+        assert!(!anonymizes_existing(true, true));
+        assert!(!anonymizes_existing(true, false));
+        assert!(!anonymizes_existing(false, false));
+    }
+}
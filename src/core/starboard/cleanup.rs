@@ -0,0 +1,252 @@
+//! Removes the reactions Starboard added to posts once their emojis aren't
+//! vote emojis anymore, either because the starboard was deleted or because
+//! its vote emojis changed. The posts are queued in the database and removed
+//! from the queue as they're handled, so a restart picks up where it stopped.
+
+use std::{collections::HashMap, sync::Arc};
+
+use chrono::{DateTime, Utc};
+use twilight_model::id::{
+    marker::{ChannelMarker, MessageMarker},
+    Id,
+};
+
+use crate::{
+    client::bot::StarboardBot,
+    constants,
+    core::{
+        emoji::{EmojiCommon, SimpleEmoji},
+        guild_log::LogEntry,
+    },
+    database::{ReactionCleanup, Starboard, StarboardSettings},
+    errors::StarboardResult,
+    utils::{into_id::IntoId, snowflake_age::snowflake_at},
+};
+
+use super::dedupe::post_channel_id;
+
+/// The upvote and downvote emojis of a starboard.
+pub fn vote_emojis(settings: &StarboardSettings) -> Vec<String> {
+    let mut emojis = settings.upvote_emojis.clone();
+    emojis.extend(settings.downvote_emojis.iter().cloned());
+    emojis
+}
+
+/// Queues the starboard's posts so that the bot's reactions with `emojis` are
+/// removed. Posts older than `CLEANUP_REACTIONS_MAX_AGE` are skipped, and
+/// reactions from users are never touched. Returns the number of posts
+/// queued.
+pub async fn queue_cleanup(
+    bot: &StarboardBot,
+    starboard: &Starboard,
+    emojis: &[String],
+) -> StarboardResult<u64> {
+    if emojis.is_empty() {
+        return Ok(0);
+    }
+
+    let queued = ReactionCleanup::queue(
+        &bot.pool,
+        starboard.id,
+        &starboard.name,
+        starboard.guild_id,
+        starboard.channel_id,
+        emojis,
+        oldest_post_id(Utc::now()),
+    )
+    .await?;
+    Ok(queued)
+}
+
+/// Queues a cleanup for the vote emojis that were removed from the starboard,
+/// and stops any queued cleanup for the ones it uses again. Returns the
+/// number of posts queued.
+pub async fn queue_removed_emojis(
+    bot: &StarboardBot,
+    old: &StarboardSettings,
+    new: &Starboard,
+) -> StarboardResult<u64> {
+    let new_emojis = vote_emojis(&new.settings);
+    ReactionCleanup::keep_emojis(&bot.pool, new.id, &new_emojis).await?;
+
+    let removed = removed_emojis(&vote_emojis(old), &new_emojis);
+    queue_cleanup(bot, new, &removed).await
+}
+
+pub async fn loop_cleanup_reactions(bot: Arc<StarboardBot>) {
+    loop {
+        if let Err(why) = cleanup_reactions(&bot).await {
+            bot.handle_error(&why).await;
+        }
+
+        tokio::time::sleep(constants::CLEANUP_REACTIONS_POLL_DELAY).await;
+    }
+}
+
+struct Progress {
+    guild_id: i64,
+    starboard_name: String,
+    cleaned: usize,
+}
+
+/// Handles the queued posts in the guilds this cluster owns, newest first,
+/// and reports the progress of each starboard to its guild's log.
+async fn cleanup_reactions(bot: &Arc<StarboardBot>) -> StarboardResult<()> {
+    let oldest = oldest_post_id(Utc::now());
+    let mut progress = HashMap::<i32, Progress>::new();
+    let mut before = i64::MAX;
+
+    loop {
+        let posts =
+            ReactionCleanup::list_before(&bot.pool, before, constants::CLEANUP_REACTIONS_PAGE_SIZE)
+                .await?;
+        let Some(last) = posts.last() else {
+            break;
+        };
+        before = last.starboard_message_id;
+
+        for post in posts {
+            if !bot.owns_guild(post.guild_id as u64) {
+                continue;
+            }
+
+            // posts that got too old while they were queued are just dropped
+            if post.starboard_message_id >= oldest {
+                cleanup_post(bot, &post).await;
+                tokio::time::sleep(constants::CLEANUP_REACTIONS_DELAY).await;
+            }
+            ReactionCleanup::delete(&bot.pool, post.starboard_message_id).await?;
+
+            let entry = progress
+                .entry(post.starboard_id)
+                .or_insert_with(|| Progress {
+                    guild_id: post.guild_id,
+                    starboard_name: post.starboard_name.clone(),
+                    cleaned: 0,
+                });
+            entry.cleaned += 1;
+            if !should_report(entry.cleaned) {
+                continue;
+            }
+
+            let remaining =
+                ReactionCleanup::count_by_starboard(&bot.pool, post.starboard_id).await?;
+            // finished starboards are reported once the run is over
+            if remaining != 0 {
+                log_progress(bot, entry, remaining);
+            }
+        }
+    }
+
+    for (starboard_id, entry) in progress {
+        let remaining = ReactionCleanup::count_by_starboard(&bot.pool, starboard_id).await?;
+        if remaining == 0 {
+            log_progress(bot, &entry, 0);
+        }
+    }
+
+    Ok(())
+}
+
+async fn cleanup_post(bot: &StarboardBot, post: &ReactionCleanup) {
+    let message_id: Id<MessageMarker> = post.starboard_message_id.into_id();
+    let channel_id: Id<ChannelMarker> = post.channel_id.into_id();
+    let is_forum = bot
+        .cache
+        .is_channel_forum(post.guild_id.into_id(), channel_id);
+    let channel_id = post_channel_id(channel_id, message_id, is_forum);
+
+    for emoji in Vec::<SimpleEmoji>::from_stored(post.emojis.clone()) {
+        // failures (e.g. the post was deleted) are ignored
+        let _ = bot
+            .http
+            .delete_current_user_reaction(channel_id, message_id, &emoji.reactable())
+            .await;
+    }
+}
+
+fn log_progress(bot: &Arc<StarboardBot>, progress: &Progress, remaining: i64) {
+    let entry = LogEntry::ReactionCleanup {
+        starboard: progress.starboard_name.clone(),
+        cleaned: progress.cleaned,
+        remaining,
+    };
+    bot.guild_log.log(bot, progress.guild_id.into_id(), entry);
+}
+
+/// The emojis in `old` that aren't in `new`.
+fn removed_emojis(old: &[String], new: &[String]) -> Vec<String> {
+    let mut removed = Vec::new();
+    for emoji in old {
+        if !new.contains(emoji) && !removed.contains(emoji) {
+            removed.push(emoji.clone());
+        }
+    }
+    removed
+}
+
+/// The oldest post whose reactions are still cleaned up at `now`.
+fn oldest_post_id(now: DateTime<Utc>) -> i64 {
+    let max_age =
+        chrono::Duration::milliseconds(constants::CLEANUP_REACTIONS_MAX_AGE.as_millis() as i64);
+    snowflake_at(now - max_age)
+}
+
+/// Whether to log a starboard's progress after `cleaned` of its posts.
+fn should_report(cleaned: usize) -> bool {
+    cleaned % constants::CLEANUP_REACTIONS_REPORT_EVERY == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn emojis(emojis: &[&str]) -> Vec<String> {
+        emojis.iter().map(|e| e.to_string()).collect()
+    }
+
+    #[test]
+    fn removed() {
+        let old = emojis(&["⭐", "123", "👎"]);
+        let new = emojis(&["⭐", "👎", "💎"]);
+
+        assert_eq!(removed_emojis(&old, &new), emojis(&["123"]));
+        assert_eq!(removed_emojis(&new, &old), emojis(&["💎"]));
+    }
+
+    #[test]
+    fn nothing_removed() {
+        let old = emojis(&["⭐"]);
+
+        assert!(removed_emojis(&old, &old).is_empty());
+        assert!(removed_emojis(&[], &old).is_empty());
+    }
+
+    #[test]
+    fn removed_once() {
+        // the same emoji can be an upvote and a downvote emoji
+        let old = emojis(&["⭐", "⭐"]);
+
+        assert_eq!(removed_emojis(&old, &[]), emojis(&["⭐"]));
+    }
+
+    #[test]
+    fn oldest_post() {
+        let now = Utc::now();
+        let max_age = chrono::Duration::days(90);
+
+        assert_eq!(oldest_post_id(now), snowflake_at(now - max_age));
+        assert!(oldest_post_id(now) < snowflake_at(now));
+    }
+
+    #[test]
+    fn reports() {
+        let every = constants::CLEANUP_REACTIONS_REPORT_EVERY;
+
+        assert!(!should_report(1));
+        assert!(!should_report(every - 1));
+        assert!(should_report(every));
+        assert!(!should_report(every + 1));
+        assert!(should_report(every * 2));
+    }
+}
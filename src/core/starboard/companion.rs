@@ -0,0 +1,121 @@
+//! Companion actions are the side effects of a refresh besides the post
+//! itself, like the link reply. A refresh can be retried after a partial
+//! failure, and two refreshes of a message can overlap, so each action is
+//! claimed in `performed_actions` before it's performed. Its id is made of the
+//! message, the starboard, the kind of action and a hash of what's sent.
+
+use std::{future::Future, sync::Arc};
+
+use chrono::{Duration, Utc};
+
+use crate::{
+    client::bot::StarboardBot, constants, database::PerformedAction, errors::StarboardResult,
+    utils::fnv::fnv1a,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompanionAction {
+    /// The reply to the original message, see `link_reply`.
+    LinkReply,
+}
+
+impl CompanionAction {
+    pub fn kind(self) -> &'static str {
+        match self {
+            Self::LinkReply => "link_reply",
+        }
+    }
+}
+
+/// Performs `action` unless it was already performed with the same
+/// `rendered` content. If it fails, the claim is released so that a retry
+/// can perform it. Returns `None` if it was already performed.
+pub async fn perform_once<T, F, Fut>(
+    bot: &StarboardBot,
+    message_id: i64,
+    starboard_id: i32,
+    kind: CompanionAction,
+    rendered: &str,
+    action: F,
+) -> StarboardResult<Option<T>>
+where
+    F: FnOnce() -> Fut,
+    Fut: Future<Output = StarboardResult<T>>,
+{
+    let render_hash = render_hash(rendered);
+    let claimed = PerformedAction::claim(
+        &bot.pool,
+        message_id,
+        starboard_id,
+        kind.kind(),
+        render_hash,
+    )
+    .await?;
+    if !claimed {
+        return Ok(None);
+    }
+
+    match action().await {
+        Ok(ret) => Ok(Some(ret)),
+        Err(why) => {
+            PerformedAction::release(
+                &bot.pool,
+                message_id,
+                starboard_id,
+                kind.kind(),
+                render_hash,
+            )
+            .await?;
+            Err(why)
+        }
+    }
+}
+
+/// Stored as a BIGINT, so the same content always gets the same claim.
+fn render_hash(rendered: &str) -> i64 {
+    fnv1a(rendered.bytes()) as i64
+}
+
+/// Forgets performed actions after `PERFORMED_ACTIONS_RETENTION_HOURS`.
+pub async fn loop_expire_performed_actions(bot: Arc<StarboardBot>) {
+    loop {
+        tokio::time::sleep(constants::PERFORMED_ACTIONS_EXPIRE_DELAY).await;
+
+        let before = Utc::now() - Duration::hours(constants::PERFORMED_ACTIONS_RETENTION_HOURS);
+        if let Err(why) = PerformedAction::delete_before(&bot.pool, before).await {
+            bot.handle_error(&why.into()).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn same_content_same_claim() {
+        let label = "View on #starboard";
+        let link = "https://discord.com/channels/1/2/3";
+
+        assert_eq!(
+            render_hash(&format!("{label}\n{link}")),
+            render_hash("View on #starboard\nhttps://discord.com/channels/1/2/3")
+        );
+    }
+
+    #[test]
+    fn new_content_new_claim() {
+        // a recreated post has a new link, so it gets its own reply
+        assert_ne!(
+            render_hash("View on #starboard\nhttps://discord.com/channels/1/2/3"),
+            render_hash("View on #starboard\nhttps://discord.com/channels/1/2/4")
+        );
+    }
+
+    #[test]
+    fn hashes_fit_in_bigint() {
+        // the top bit is set, so it's stored as a negative number
+        assert_eq!(render_hash(""), 0xcbf2_9ce4_8422_2325_u64 as i64);
+        assert!(render_hash("") < 0);
+    }
+}
@@ -1,13 +1,16 @@
 use std::cmp::Ordering;
 
 use twilight_model::id::{
-    marker::{ChannelMarker, GuildMarker},
+    marker::{ChannelMarker, GuildMarker, TagMarker},
     Id,
 };
 
 use crate::{
     client::bot::StarboardBot,
-    core::emoji::{EmojiCommon, SimpleEmoji},
+    core::{
+        emoji::{EmojiCommon, SimpleEmoji},
+        forum_tags::tags_allowed,
+    },
     database::{
         helpers::settings::overrides::call_with_override_settings, Starboard, StarboardOverride,
         StarboardSettings,
@@ -68,6 +71,37 @@ impl StarboardConfig {
         })
     }
 
+    /// Whether votes count in a channel, given its qualified channel ids (the
+    /// channel itself, followed by its parents).
+    pub fn is_channel_allowed(&self, channel_ids: &[i64]) -> bool {
+        let res = &self.resolved;
+        if channel_ids
+            .iter()
+            .any(|id| res.channel_blacklist.contains(id))
+        {
+            return false;
+        }
+
+        res.channel_whitelist.is_empty()
+            || channel_ids
+                .iter()
+                .any(|id| res.channel_whitelist.contains(id))
+    }
+
+    pub fn uses_tags(&self) -> bool {
+        !self.resolved.required_tags.is_empty() || !self.resolved.excluded_tags.is_empty()
+    }
+
+    /// Whether votes count on a message, given the tags of the forum post
+    /// it's in (`None` outside of forums).
+    pub fn is_tags_allowed(&self, tags: Option<&[Id<TagMarker>]>) -> bool {
+        tags_allowed(
+            &self.resolved.required_tags,
+            &self.resolved.excluded_tags,
+            tags,
+        )
+    }
+
     pub async fn list_for_channel(
         bot: &StarboardBot,
         guild_id: Id<GuildMarker>,
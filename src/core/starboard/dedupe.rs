@@ -0,0 +1,140 @@
+//! Deletes the duplicate posts queued by the `dedupe-posts` migration. Only
+//! the oldest post for each message was kept in the database, so the newer
+//! ones just need to be removed from Discord.
+
+use std::sync::Arc;
+
+use twilight_model::id::{
+    marker::{ChannelMarker, MessageMarker},
+    Id,
+};
+
+use crate::{
+    client::bot::StarboardBot,
+    constants,
+    database::DuplicatePost,
+    errors::StarboardResult,
+    utils::{get_status::get_status, into_id::IntoId},
+};
+
+pub async fn task_delete_duplicate_posts(bot: Arc<StarboardBot>) {
+    match delete_duplicate_posts(&bot, None).await {
+        Ok(0) => (),
+        Ok(count) => tracing::info!("Deleted {count} duplicate starboard posts."),
+        Err(why) => bot.handle_error(&why).await,
+    }
+}
+
+/// Deletes queued duplicate posts in the guilds this cluster owns, or only in
+/// `guild_id` if given. Posts that fail to delete are logged and stay queued
+/// for the next run. Returns the number of posts that were handled.
+pub async fn delete_duplicate_posts(
+    bot: &StarboardBot,
+    guild_id: Option<i64>,
+) -> StarboardResult<usize> {
+    let mut count = 0;
+    let mut after = 0;
+
+    loop {
+        let posts = DuplicatePost::list_after(
+            &bot.pool,
+            guild_id,
+            after,
+            constants::DEDUPE_POSTS_PAGE_SIZE,
+        )
+        .await?;
+        let Some(last) = posts.last() else {
+            break;
+        };
+        after = last.starboard_message_id;
+
+        for post in posts {
+            if !bot.owns_guild(post.guild_id as u64) {
+                continue;
+            }
+
+            if let Err(why) = delete_post(bot, &post).await {
+                bot.handle_error(&why).await;
+                tokio::time::sleep(constants::DEDUPE_POSTS_DELAY).await;
+                continue;
+            }
+            DuplicatePost::delete(&bot.pool, post.starboard_message_id).await?;
+            tracing::info!(
+                "Merged duplicate post {} into {} (starboard {}, message {}).",
+                post.starboard_message_id,
+                post.kept_starboard_message_id,
+                post.starboard_id,
+                post.message_id,
+            );
+            count += 1;
+
+            tokio::time::sleep(constants::DEDUPE_POSTS_DELAY).await;
+        }
+    }
+
+    Ok(count)
+}
+
+async fn delete_post(bot: &StarboardBot, post: &DuplicatePost) -> StarboardResult<()> {
+    let message_id: Id<MessageMarker> = post.starboard_message_id.into_id();
+    let channel_id: Id<ChannelMarker> = post.channel_id.into_id();
+    let is_forum = bot
+        .cache
+        .is_channel_forum(post.guild_id.into_id(), channel_id);
+    let channel_id = post_channel_id(channel_id, message_id, is_forum);
+
+    let ret = bot.http.delete_message(channel_id, message_id).await;
+    match ret {
+        Ok(_) => Ok(()),
+        Err(why) if is_already_gone(get_status(&why)) => Ok(()),
+        Err(why) => Err(why.into()),
+    }
+}
+
+/// The channel a post is in. Posts on forum starboards are the first message
+/// of their own thread.
+pub fn post_channel_id(
+    channel_id: Id<ChannelMarker>,
+    message_id: Id<MessageMarker>,
+    is_forum: bool,
+) -> Id<ChannelMarker> {
+    if is_forum {
+        message_id.cast()
+    } else {
+        channel_id
+    }
+}
+
+/// Whether the post is already deleted, or the bot can't see its channel
+/// anymore, so there's nothing left to do.
+fn is_already_gone(status: Option<u16>) -> bool {
+    matches!(status, Some(403 | 404))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn forum_posts_are_their_own_thread() {
+        let channel_id = Id::new(1);
+        let message_id = Id::new(2);
+
+        assert_eq!(post_channel_id(channel_id, message_id, false), channel_id);
+        assert_eq!(post_channel_id(channel_id, message_id, true), Id::new(2));
+    }
+
+    #[test]
+    fn gone_posts() {
+        assert!(is_already_gone(Some(403)));
+        assert!(is_already_gone(Some(404)));
+    }
+
+    #[test]
+    fn failed_deletes() {
+        // these are logged and retried on the next run
+        assert!(!is_already_gone(Some(429)));
+        assert!(!is_already_gone(Some(500)));
+        assert!(!is_already_gone(None));
+    }
+}
@@ -0,0 +1,59 @@
+use std::{sync::Arc, time::Duration};
+
+use chrono::{DateTime, Utc};
+use twilight_model::id::{marker::MessageMarker, Id};
+
+use crate::client::bot::StarboardBot;
+
+use super::handle::RefreshMessage;
+
+/// Refreshes a message once `at` has passed, e.g. when it's only waiting for
+/// its vote spread. Only one refresh is scheduled per message at a time; the
+/// refresh itself reschedules if another starboard is still waiting.
+pub fn schedule_refresh(
+    bot: Arc<StarboardBot>,
+    message_id: Id<MessageMarker>,
+    at: DateTime<Utc>,
+    is_premium: bool,
+) {
+    if !bot.cache.deferred_refreshes.insert(message_id) {
+        return;
+    }
+
+    tokio::spawn(async move {
+        tokio::time::sleep(refresh_delay(at, Utc::now())).await;
+
+        bot.cache.deferred_refreshes.remove(&message_id);
+        let mut refresh = RefreshMessage::new(bot.clone(), message_id, is_premium);
+        if let Err(why) = refresh.refresh(true).await {
+            bot.handle_error(&why).await;
+        }
+    });
+}
+
+/// How long to wait before refreshing at `at`, with a little extra so that
+/// the requirement is met by the time it's checked.
+fn refresh_delay(at: DateTime<Utc>, now: DateTime<Utc>) -> Duration {
+    (at - now).to_std().unwrap_or_default() + Duration::from_secs(1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn delay_until_ready() {
+        let now = Utc::now();
+        let at = now + chrono::Duration::seconds(30);
+
+        assert_eq!(refresh_delay(at, now), Duration::from_secs(31));
+    }
+
+    #[test]
+    fn already_ready() {
+        let now = Utc::now();
+        let at = now - chrono::Duration::seconds(30);
+
+        assert_eq!(refresh_delay(at, now), Duration::from_secs(1));
+    }
+}
@@ -0,0 +1,67 @@
+//! Counts which emojis votes are cast with, for `/starboards emoji-stats`.
+//! Counts are kept in memory and written along with the vote batches.
+
+use std::{collections::HashMap, sync::Mutex};
+
+use crate::{
+    client::bot::StarboardBot,
+    core::emoji::{EmojiCommon, SimpleEmoji},
+    database::EmojiUsage,
+};
+
+#[derive(Default)]
+pub struct EmojiUsageCounter {
+    /// (starboard, stored emoji) -> new votes
+    counts: Mutex<HashMap<(i32, String), i64>>,
+}
+
+impl EmojiUsageCounter {
+    pub fn record(&self, starboard_id: i32, emoji: &SimpleEmoji) {
+        let key = (starboard_id, emoji.clone().into_stored());
+        *self.counts.lock().unwrap().entry(key).or_default() += 1;
+    }
+
+    /// Adds the waiting counts to the database. If that fails they're
+    /// dropped, since they're only statistics.
+    pub async fn flush(&self, bot: &StarboardBot) {
+        let counts = std::mem::take(&mut *self.counts.lock().unwrap());
+        if counts.is_empty() {
+            return;
+        }
+
+        let mut starboard_ids = Vec::with_capacity(counts.len());
+        let mut emojis = Vec::with_capacity(counts.len());
+        let mut values = Vec::with_capacity(counts.len());
+        for ((starboard_id, emoji), count) in counts {
+            starboard_ids.push(starboard_id);
+            emojis.push(emoji);
+            values.push(count);
+        }
+
+        if let Err(why) = EmojiUsage::add_batch(&bot.pool, &starboard_ids, &emojis, &values).await {
+            tracing::warn!("Writing emoji usage failed: {why}");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_per_starboard_and_emoji() {
+        let counter = EmojiUsageCounter::default();
+        let star = SimpleEmoji::from_stored("⭐".to_string());
+        let gem = SimpleEmoji::from_stored("💎".to_string());
+        counter.record(1, &star);
+        counter.record(1, &star);
+        counter.record(1, &gem);
+        counter.record(2, &star);
+
+        let counts = counter.counts.lock().unwrap();
+        assert_eq!(counts.len(), 3);
+        assert_eq!(counts[&(1, "⭐".to_string())], 2);
+        assert_eq!(counts[&(1, "💎".to_string())], 1);
+        assert_eq!(counts[&(2, "⭐".to_string())], 1);
+    }
+}
@@ -1,59 +1,38 @@
-use std::{collections::HashMap, sync::Arc};
+use std::{
+    collections::HashMap,
+    sync::{atomic::Ordering, Arc},
+};
 
 use cached::Cached;
 use twilight_model::id::{marker::MessageMarker, Id};
 
 use crate::{
-    cache::MessageResult,
+    cache::{models::message::CachedMessage, MessageResult},
     client::bot::StarboardBot,
     core::{
         embedder::Embedder,
         emoji::{EmojiCommon, SimpleEmoji},
+        emoji_tiers::{parse_tiers, tier_emoji},
+        guild_log::LogEntry,
+        post_latency::record_time_to_post,
+    },
+    database::{
+        models::vote::VoteCount, DbMessage, DeletedPost, PendingRefresh, StarboardMessage, Vote,
     },
-    database::{DbMessage, StarboardMessage, Vote},
     errors::StarboardResult,
-    utils::{id_as_i64::GetI64, into_id::IntoId},
+    utils::{id_as_i64::GetI64, into_id::IntoId, message_link::fmt_message_link},
 };
 
 use super::{
     config::StarboardConfig,
-    msg_status::{get_message_status, MessageStatus},
+    deferred_refresh::schedule_refresh,
+    link_reply::{delete_link_reply, send_link_reply},
+    message::cache_post_original,
+    msg_status::{
+        apply_author_limit, get_message_status, get_vote_spread, is_message_nsfw, MessageStatus,
+    },
 };
 
-async fn refresh_exclusive_group(
-    refresh: RefreshMessage,
-    configs: Vec<Arc<StarboardConfig>>,
-    force: bool,
-) -> StarboardResult<()> {
-    let mut refreshers = Vec::new();
-    for config in configs {
-        let mut ref_sb = RefreshStarboard::new(refresh.clone(), config);
-        let sort_key = (
-            ref_sb.config.resolved.exclusive_group_priority,
-            ref_sb.has_message_on_starboard().await?,
-        );
-
-        refreshers.push((sort_key, ref_sb));
-    }
-
-    refreshers.sort_by(|left, right| right.0.cmp(&left.0));
-
-    let mut message_exists = false;
-    for (_, mut ref_sb) in refreshers {
-        let ret = ref_sb.refresh(force, message_exists).await;
-
-        message_exists = match ret {
-            Err(why) => {
-                refresh.bot.handle_error(&why).await;
-                continue;
-            }
-            Ok(has_message) => has_message || message_exists,
-        };
-    }
-
-    Ok(())
-}
-
 #[derive(Clone)]
 pub struct RefreshMessage {
     bot: Arc<StarboardBot>,
@@ -61,6 +40,7 @@ pub struct RefreshMessage {
     message_id: Id<MessageMarker>,
     sql_message: Option<Arc<DbMessage>>,
     orig_message: Option<MessageResult>,
+    ref_message: Option<Option<Arc<CachedMessage>>>,
     configs: Option<Arc<Vec<Arc<StarboardConfig>>>>,
     is_premium: bool,
 }
@@ -77,6 +57,7 @@ impl RefreshMessage {
             configs: None,
             sql_message: None,
             orig_message: None,
+            ref_message: None,
             is_premium,
         }
     }
@@ -84,17 +65,81 @@ impl RefreshMessage {
     pub async fn refresh(&mut self, force: bool) -> StarboardResult<bool> {
         let orig = self.get_sql_message().await?;
         let clone = self.bot.clone();
+
+        // the shard is being handed over, so only finish refreshes that were
+        // already running. New ones are saved for whoever owns the shard next.
+        let _in_flight = clone.shards.track_refresh();
+        let shard_id = clone.config.shard_for_guild(orig.guild_id as u64);
+        if clone.shards.is_draining(shard_id) {
+            PendingRefresh::create(
+                &clone.pool,
+                orig.message_id,
+                shard_id as i32,
+                self.is_premium,
+            )
+            .await?;
+            return Ok(false);
+        }
+
         let guard = clone.locks.post_update_lock.lock(orig.message_id);
         if guard.is_none() {
+            // the running refresh goes again once it's done, so that votes
+            // cast in the meantime aren't missed
+            clone.locks.post_update_pending.insert(orig.message_id);
             return Ok(false);
         }
 
+        self.sync_nsfw().await?;
+        loop {
+            clone.locks.post_update_pending.remove(&orig.message_id);
+            self.bot
+                .refresh_stats
+                .refreshes
+                .fetch_add(1, Ordering::Relaxed);
+
+            // votes from reactions might still be waiting to be written
+            self.bot
+                .vote_batcher
+                .flush_message(&self.bot, orig.message_id)
+                .await;
+            let plan = self.plan().await?;
+
+            let mut tasks = Vec::new();
+            for planned in plan {
+                let mut refresh = RefreshStarboard::new(self.to_owned(), planned.config.clone());
+                tasks.push(tokio::spawn(async move {
+                    refresh.execute(planned, force).await
+                }));
+            }
+
+            for t in tasks {
+                if let Ok(Err(why)) = t.await {
+                    self.bot.handle_error(&why).await;
+                }
+            }
+
+            if !clone.locks.post_update_pending.contains(&orig.message_id) {
+                break;
+            }
+        }
+
+        Ok(true)
+    }
+
+    /// Loads everything the starboards share once, then decides what to do
+    /// on each of them. Starboards in an exclusive group are planned in order
+    /// of priority, so that only the first one that keeps the message has
+    /// it.
+    async fn plan(&mut self) -> StarboardResult<Vec<PlannedRefresh>> {
+        // loaded here so that every starboard's task shares them
+        self.get_orig_message().await?;
+        self.get_ref_message().await?;
         let configs = self.get_configs().await?;
+
         let mut lone = Vec::new();
         let mut grouped = HashMap::new();
-
         for c in configs.iter() {
-            if !c.resolved.enabled || c.starboard.premium_locked {
+            if !c.resolved.enabled || c.starboard.premium_locked || c.starboard.channel_deleted {
                 continue;
             }
 
@@ -108,29 +153,113 @@ impl RefreshMessage {
             }
         }
 
-        let mut tasks = Vec::new();
-
-        for group in grouped.into_values() {
-            tasks.push(tokio::spawn(refresh_exclusive_group(
-                self.to_owned(),
-                group,
-                force,
-            )));
-        }
+        let mut plan = Vec::new();
         for config in lone {
-            let mut refresh = RefreshStarboard::new(self.to_owned(), config.to_owned());
-            tasks.push(tokio::spawn(async move {
-                refresh.refresh(force, false).await.map(|_| ())
-            }));
+            let sb_msg = self.get_starboard_message(&config).await?;
+            plan.push(self.plan_starboard(config, sb_msg, false).await?);
         }
+        for group in grouped.into_values() {
+            let mut members = Vec::new();
+            for config in group {
+                let sb_msg = self.get_starboard_message(&config).await?;
+                let sort_key = (config.resolved.exclusive_group_priority, sb_msg.is_some());
+                members.push((sort_key, config, sb_msg));
+            }
+            members.sort_by(|left, right| right.0.cmp(&left.0));
 
-        for t in tasks {
-            if let Ok(Err(why)) = t.await {
-                self.bot.handle_error(&why).await;
+            let mut taken = false;
+            for (_, config, sb_msg) in members {
+                let planned = self.plan_starboard(config, sb_msg, taken).await?;
+                taken |= planned.keeps_post();
+                plan.push(planned);
             }
         }
 
-        Ok(true)
+        Ok(plan)
+    }
+
+    async fn plan_starboard(
+        &mut self,
+        config: Arc<StarboardConfig>,
+        sb_msg: Option<StarboardMessage>,
+        violates_exclusive_group: bool,
+    ) -> StarboardResult<PlannedRefresh> {
+        self.bot
+            .refresh_stats
+            .starboards
+            .fetch_add(1, Ordering::Relaxed);
+
+        let orig = self.get_sql_message().await?;
+        let orig_message = self.get_orig_message().await?;
+        let count = self.get_count(&config, orig.message_id).await?;
+        let vote_spread = get_vote_spread(&self.bot, &config, orig.message_id).await?;
+
+        let action = get_message_status(
+            &self.bot,
+            &config,
+            &orig,
+            &orig_message,
+            count,
+            vote_spread,
+            violates_exclusive_group,
+            self.is_premium,
+        )
+        .await?;
+        let action =
+            apply_author_limit(&self.bot, &config, &orig, action, sb_msg.is_some()).await?;
+
+        let action = match action {
+            // the vote spread only holds back new posts
+            MessageStatus::AwaitingVoteSpread(_) if sb_msg.is_some() => {
+                MessageStatus::Update(config.resolved.link_edits)
+            }
+            MessageStatus::AwaitingVoteSpread(ready_at)
+            | MessageStatus::AwaitingAuthorLimit(ready_at) => {
+                schedule_refresh(
+                    self.bot.clone(),
+                    orig.message_id.into_id(),
+                    ready_at,
+                    self.is_premium,
+                );
+                MessageStatus::NoAction
+            }
+            action => action,
+        };
+
+        Ok(PlannedRefresh {
+            config,
+            count,
+            sb_msg,
+            action,
+        })
+    }
+
+    async fn get_count(
+        &self,
+        config: &StarboardConfig,
+        message_id: i64,
+    ) -> sqlx::Result<VoteCount> {
+        self.bot
+            .refresh_stats
+            .count_queries
+            .fetch_add(1, Ordering::Relaxed);
+
+        let pool = &self.bot.pool;
+        let starboard = &config.starboard;
+        if starboard.aggregate {
+            Vote::count_max(pool, message_id, &starboard.source_starboards).await
+        } else {
+            Vote::count(pool, message_id, starboard.id).await
+        }
+    }
+
+    async fn get_starboard_message(
+        &mut self,
+        config: &StarboardConfig,
+    ) -> sqlx::Result<Option<StarboardMessage>> {
+        let orig = self.get_sql_message().await?;
+        StarboardMessage::get_by_starboard(&self.bot.pool, orig.message_id, config.starboard.id)
+            .await
     }
 
     // caching methods
@@ -166,12 +295,32 @@ impl RefreshMessage {
         Ok(self.sql_message.as_ref().unwrap().clone())
     }
 
+    /// Updates the stored NSFW flag if the channel was toggled since.
+    async fn sync_nsfw(&mut self) -> StarboardResult<()> {
+        let orig = self.get_sql_message().await?;
+        let is_nsfw = is_message_nsfw(&self.bot, &orig).await?;
+        if is_nsfw == orig.is_nsfw {
+            return Ok(());
+        }
+
+        if let Some(orig) = DbMessage::set_nsfw(&self.bot.pool, orig.message_id, is_nsfw).await? {
+            self.set_sql_message(orig);
+        }
+
+        Ok(())
+    }
+
     pub fn set_orig_message(&mut self, message: MessageResult) {
         self.orig_message.replace(message);
     }
 
     async fn get_orig_message(&mut self) -> StarboardResult<MessageResult> {
         if self.orig_message.is_none() {
+            self.bot
+                .refresh_stats
+                .context_loads
+                .fetch_add(1, Ordering::Relaxed);
+
             let sql_message = self.get_sql_message().await?;
             let orig_message = self
                 .bot
@@ -188,6 +337,57 @@ impl RefreshMessage {
 
         Ok(self.orig_message.as_ref().unwrap().clone())
     }
+
+    /// The message the original replied to, if any.
+    async fn get_ref_message(&mut self) -> StarboardResult<Option<Arc<CachedMessage>>> {
+        if let Some(ref_message) = &self.ref_message {
+            return Ok(ref_message.clone());
+        }
+
+        let orig_message = self.get_orig_message().await?;
+        let ref_message = match &orig_message {
+            MessageResult::Ok(msg) => match msg.referenced_message {
+                Some(id) => {
+                    let sql_message = self.get_sql_message().await?;
+                    self.bot
+                        .cache
+                        .fog_message(&self.bot, sql_message.channel_id.into_id(), id)
+                        .await?
+                        .into_option()
+                }
+                None => None,
+            },
+            _ => None,
+        };
+        self.ref_message.replace(ref_message.clone());
+
+        Ok(ref_message)
+    }
+}
+
+/// What a refresh decided to do on one starboard.
+struct PlannedRefresh {
+    config: Arc<StarboardConfig>,
+    count: VoteCount,
+    /// The post that existed when the refresh was planned.
+    sb_msg: Option<StarboardMessage>,
+    action: MessageStatus,
+}
+
+impl PlannedRefresh {
+    /// Whether the message will still (or newly) have a post on this
+    /// starboard, for exclusive groups.
+    fn keeps_post(&self) -> bool {
+        keeps_post(&self.action, self.sb_msg.is_some())
+    }
+}
+
+fn keeps_post(action: &MessageStatus, has_post: bool) -> bool {
+    match action {
+        MessageStatus::Remove => false,
+        MessageStatus::Send(_) => true,
+        _ => has_post,
+    }
 }
 
 struct RefreshStarboard {
@@ -200,89 +400,57 @@ impl RefreshStarboard {
         Self { refresh, config }
     }
 
-    pub async fn has_message_on_starboard(&mut self) -> StarboardResult<bool> {
-        Ok(self.get_starboard_message().await?.is_some())
-    }
-
-    pub async fn refresh(
-        &mut self,
-        force: bool,
-        violates_exclusive_group: bool,
-    ) -> StarboardResult<bool> {
-        // I use a loop because recursion inside async functions requires another crate :(
-        let mut tries = 0;
-        loop {
-            if tries == 2 {
-                return Ok(false);
-            }
-            tries += 1;
-            let (retry, exists) = self.refresh_one(force, violates_exclusive_group).await?;
-            match retry {
-                true => continue,
-                false => return Ok(exists),
-            }
+    /// Carries out what was planned for this starboard.
+    async fn execute(&mut self, planned: PlannedRefresh, force: bool) -> StarboardResult<()> {
+        let PlannedRefresh {
+            count,
+            sb_msg,
+            action,
+            ..
+        } = planned;
+        if matches!(action, MessageStatus::NoAction) && sb_msg.is_none() {
+            return Ok(());
         }
-    }
 
-    async fn refresh_one(
-        &mut self,
-        force: bool,
-        violates_exclusive_group: bool,
-    ) -> StarboardResult<(bool, bool)> {
         let orig = self.refresh.get_sql_message().await?;
-        let points = Vote::count(
-            &self.refresh.bot.pool,
-            orig.message_id,
-            self.config.starboard.id,
-        )
-        .await?;
-
-        let orig_message = self.refresh.get_orig_message().await?;
-        let sql_message = self.refresh.get_sql_message().await?;
-        let ref_msg = if let MessageResult::Ok(msg) = &orig_message {
-            if let Some(id) = msg.referenced_message {
-                self.refresh
-                    .bot
-                    .cache
-                    .fog_message(&self.refresh.bot, sql_message.channel_id.into_id(), id)
-                    .await?
-                    .into_option()
-            } else {
-                None
-            }
+        let points = count.points;
+        let vote_counts =
+            (!self.config.starboard.aggregate).then_some((count.upvotes, count.downvotes));
+        let emoji_points = if vote_counts.is_some() && self.config.resolved.vote_buttons {
+            Vote::count_by_emoji(
+                &self.refresh.bot.pool,
+                orig.message_id,
+                self.config.starboard.id,
+            )
+            .await?
         } else {
-            None
+            Vec::new()
         };
-
-        let sb_msg = self.get_starboard_message().await?;
         let embedder = Embedder {
             bot: self.refresh.bot.clone(),
             points,
+            vote_counts,
+            emoji_points,
             config: self.config.clone(),
-            orig_message,
-            referenced_message: ref_msg,
-            orig_sql_message: sql_message,
+            orig_message: self.refresh.get_orig_message().await?,
+            referenced_message: self.refresh.get_ref_message().await?,
+            orig_sql_message: orig.clone(),
             is_premium: self.refresh.is_premium,
         };
 
-        let action = get_message_status(
-            &self.refresh.bot,
-            &self.config,
-            &orig,
-            &embedder.orig_message,
-            points,
-            violates_exclusive_group,
-            self.refresh.is_premium,
-        )
-        .await?;
-
         if let Some(sb_msg) = sb_msg {
             if !force
                 && points == sb_msg.last_known_point_count as i32
                 && !matches!(action, MessageStatus::Remove)
             {
-                return Ok((false, true));
+                return Ok(());
             }
+            // always show a new tier, even if edits are on cooldown
+            let tier_changed = {
+                let tiers = parse_tiers(&self.config.resolved.display_emoji_tiers);
+                tier_emoji(&tiers, sb_msg.last_known_point_count as i32)
+                    != tier_emoji(&tiers, points)
+            };
             StarboardMessage::set_last_point_count(
                 &self.refresh.bot.pool,
                 sb_msg.starboard_message_id,
@@ -290,7 +458,7 @@ impl RefreshStarboard {
             )
             .await?;
 
-            let (retry, deleted) = match action {
+            let deleted = match action {
                 MessageStatus::Remove => {
                     let sb_message_id = sb_msg.starboard_message_id.into_id();
                     self.refresh
@@ -300,19 +468,19 @@ impl RefreshStarboard {
                         .write()
                         .await
                         .cache_set(sb_message_id, ());
-                    let deleted = embedder.delete(&self.refresh.bot, sb_message_id).await?;
-                    (false, deleted)
+                    embedder.delete(&self.refresh.bot, sb_message_id).await?
                 }
                 MessageStatus::Send(full_update) | MessageStatus::Update(full_update) => {
-                    if self
-                        .refresh
-                        .bot
-                        .cooldowns
-                        .message_edit
-                        .trigger(&self.config.starboard.channel_id.into_id())
-                        .is_some()
+                    if !tier_changed
+                        && self
+                            .refresh
+                            .bot
+                            .cooldowns
+                            .message_edit
+                            .trigger(&self.config.starboard.channel_id.into_id())
+                            .is_some()
                     {
-                        (false, false)
+                        false
                     } else {
                         let deleted = embedder
                             .edit(
@@ -321,75 +489,237 @@ impl RefreshStarboard {
                                 !full_update,
                             )
                             .await?;
-                        (deleted, deleted)
+                        if deleted {
+                            self.mark_if_manually_deleted(&orig, &sb_msg).await?;
+                        }
+                        deleted
+                    }
+                }
+                // moderation shouldn't wait for the edit cooldown
+                MessageStatus::Trashed => {
+                    let deleted = embedder
+                        .edit(
+                            &self.refresh.bot,
+                            sb_msg.starboard_message_id.into_id(),
+                            false,
+                        )
+                        .await?;
+                    if deleted {
+                        self.mark_if_manually_deleted(&orig, &sb_msg).await?;
                     }
+                    deleted
                 }
+                MessageStatus::NoAction
+                | MessageStatus::AwaitingVoteSpread(_)
+                | MessageStatus::AwaitingAuthorLimit(_) => false,
             };
 
-            if deleted {
-                StarboardMessage::delete(&self.refresh.bot.pool, sb_msg.starboard_message_id)
-                    .await?;
+            if !deleted {
+                return Ok(());
             }
-
-            Ok((retry, !deleted))
-        } else {
-            if !matches!(action, MessageStatus::Send(_)) {
-                return Ok((false, false));
+            StarboardMessage::delete(&self.refresh.bot.pool, sb_msg.starboard_message_id).await?;
+            delete_link_reply(&self.refresh.bot, orig.channel_id.into_id(), &sb_msg).await;
+            if matches!(action, MessageStatus::Remove) {
+                self.log(LogEntry::PostRemoved {
+                    starboard: self.config.starboard.name.clone(),
+                    points,
+                    message_link: fmt_message_link(orig.guild_id, orig.channel_id, orig.message_id),
+                });
             }
 
-            let msg = embedder.send(&self.refresh.bot).await;
-            let msg = match msg {
-                Ok(msg) => msg,
-                Err(why) => {
-                    if why.http_status() == Some(403) {
-                        return Ok((false, false));
-                    } else {
-                        return Err(why);
-                    }
-                }
-            };
+            // a post that was meant to stay but turned out to be missing
+            // is sent again below
+        }
 
-            StarboardMessage::create(
+        if !matches!(action, MessageStatus::Send(_)) {
+            return Ok(());
+        }
+        if !self.config.resolved.recreate_deleted_posts
+            && DeletedPost::exists(
                 &self.refresh.bot.pool,
                 orig.message_id,
-                msg.id.get_i64(),
                 self.config.starboard.id,
-                points,
             )
-            .await?;
+            .await?
+        {
+            return Ok(());
+        }
 
-            let mut to_react: Vec<SimpleEmoji> = Vec::new();
-            if self.config.resolved.autoreact_upvote {
-                to_react.extend(Vec::<SimpleEmoji>::from_stored(
-                    self.config.resolved.upvote_emojis.clone(),
-                ));
-            }
-            if self.config.resolved.autoreact_downvote {
-                to_react.extend(Vec::<SimpleEmoji>::from_stored(
-                    self.config.resolved.downvote_emojis.clone(),
-                ));
-            }
+        self.send(&embedder, &orig, points).await
+    }
 
-            for emoji in to_react {
-                let _ = self
-                    .refresh
-                    .bot
-                    .http
-                    .create_reaction(msg.channel_id, msg.id, &emoji.reactable())
-                    .await;
+    async fn send(
+        &self,
+        embedder: &Embedder,
+        orig: &DbMessage,
+        points: i32,
+    ) -> StarboardResult<()> {
+        let msg = embedder.send(&self.refresh.bot).await;
+        let msg = match msg {
+            Ok(msg) => msg,
+            Err(why) => {
+                if why.http_status() == Some(403) {
+                    self.log(LogEntry::MissingPermissions {
+                        channel_id: self.config.starboard.channel_id.into_id(),
+                        action: format!("send a post to '{}'", self.config.starboard.name),
+                    });
+                    return Ok(());
+                } else if let Some(limit) = why.discord_limit() {
+                    tracing::warn!(
+                        "Couldn't send to starboard {}: {limit}",
+                        self.config.starboard.id
+                    );
+                    return Ok(());
+                } else {
+                    return Err(why);
+                }
             }
+        };
 
-            Ok((false, true))
+        let created = StarboardMessage::create(
+            &self.refresh.bot.pool,
+            orig.message_id,
+            msg.id.get_i64(),
+            self.config.starboard.id,
+            points,
+        )
+        .await?;
+        if created.is_none() {
+            // another refresh posted this message first
+            let _ = self
+                .refresh
+                .bot
+                .http
+                .delete_message(msg.channel_id, msg.id)
+                .await;
+            return Ok(());
         }
-    }
-
-    async fn get_starboard_message(&mut self) -> sqlx::Result<Option<StarboardMessage>> {
-        let orig = self.refresh.get_sql_message().await?;
-        StarboardMessage::get_by_starboard(
+        cache_post_original(
+            &self.refresh.bot,
+            msg.id,
+            orig.message_id,
+            self.config.starboard.id,
+        )
+        .await;
+        DeletedPost::delete(
             &self.refresh.bot.pool,
             orig.message_id,
             self.config.starboard.id,
         )
+        .await?;
+        if let Err(why) = record_time_to_post(
+            &self.refresh.bot,
+            &self.config,
+            orig.guild_id,
+            orig.message_id,
+            self.refresh.is_premium,
+        )
         .await
+        {
+            self.refresh.bot.handle_error(&why).await;
+        }
+
+        self.log(LogEntry::PostCreated {
+            starboard: self.config.starboard.name.clone(),
+            points,
+            message_link: fmt_message_link(orig.guild_id, orig.channel_id, orig.message_id),
+            post_link: fmt_message_link(orig.guild_id, msg.channel_id, msg.id),
+        });
+
+        if self.config.resolved.reply_with_link {
+            if let Err(why) = send_link_reply(&self.refresh.bot, &self.config, orig, &msg).await {
+                tracing::warn!("Failed to reply with a link to {}: {why}", msg.id);
+            }
+        }
+
+        let mut to_react: Vec<SimpleEmoji> = Vec::new();
+        if self.config.resolved.autoreact_upvote {
+            to_react.extend(Vec::<SimpleEmoji>::from_stored(
+                self.config.resolved.upvote_emojis.clone(),
+            ));
+        }
+        if self.config.resolved.autoreact_downvote {
+            to_react.extend(Vec::<SimpleEmoji>::from_stored(
+                self.config.resolved.downvote_emojis.clone(),
+            ));
+        }
+
+        for emoji in to_react {
+            let _ = self
+                .refresh
+                .bot
+                .http
+                .create_reaction(msg.channel_id, msg.id, &emoji.reactable())
+                .await;
+        }
+
+        Ok(())
+    }
+
+    fn log(&self, entry: LogEntry) {
+        let bot = &self.refresh.bot;
+        bot.guild_log
+            .log(bot, self.config.starboard.guild_id.into_id(), entry);
+    }
+
+    /// Remembers a post that turned out to be missing, unless the bot deleted
+    /// it itself.
+    async fn mark_if_manually_deleted(
+        &self,
+        orig: &DbMessage,
+        sb_msg: &StarboardMessage,
+    ) -> sqlx::Result<()> {
+        let auto_deleted = self
+            .refresh
+            .bot
+            .cache
+            .auto_deleted_posts
+            .write()
+            .await
+            .cache_get(&sb_msg.starboard_message_id.into_id())
+            .is_some();
+        if !auto_deleted {
+            DeletedPost::create(
+                &self.refresh.bot.pool,
+                orig.message_id,
+                self.config.starboard.id,
+                sb_msg.starboard_message_id,
+            )
+            .await?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+
+    use super::*;
+
+    #[test]
+    fn sent_posts_are_kept() {
+        assert!(keeps_post(&MessageStatus::Send(true), false));
+        assert!(keeps_post(&MessageStatus::Send(false), true));
+    }
+
+    #[test]
+    fn removed_posts_are_not_kept() {
+        assert!(!keeps_post(&MessageStatus::Remove, true));
+        assert!(!keeps_post(&MessageStatus::Remove, false));
+    }
+
+    #[test]
+    fn other_actions_keep_what_exists() {
+        for action in [
+            MessageStatus::Update(true),
+            MessageStatus::Trashed,
+            MessageStatus::NoAction,
+            MessageStatus::AwaitingVoteSpread(Utc::now()),
+        ] {
+            assert!(keeps_post(&action, true));
+            assert!(!keeps_post(&action, false));
+        }
     }
 }
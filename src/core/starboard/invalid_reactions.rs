@@ -0,0 +1,109 @@
+//! Removing reactions that can't count as votes (the
+//! `remove-invalid-reactions` setting).
+
+use std::sync::Arc;
+
+use tracing::Instrument;
+use twilight_model::id::{
+    marker::{ChannelMarker, MessageMarker, UserMarker},
+    Id,
+};
+
+use crate::{
+    client::bot::StarboardBot,
+    constants,
+    core::emoji::{EmojiCommon, SimpleEmoji},
+    errors::StarboardResult,
+    utils::get_status::get_status,
+};
+
+/// Removes a reaction, waiting on the per-channel cooldown if needed. The
+/// removal is remembered, so that the resulting ReactionRemove event can be
+/// skipped by `was_self_removed`.
+///
+/// Channels where the bot lacks Manage Messages are skipped for a while
+/// after the first 403.
+pub async fn remove_invalid_reaction(
+    bot: &StarboardBot,
+    channel_id: Id<ChannelMarker>,
+    message_id: Id<MessageMarker>,
+    emoji: &SimpleEmoji,
+    user_id: Id<UserMarker>,
+) -> StarboardResult<()> {
+    if bot
+        .cache
+        .reaction_remove_forbidden
+        .contains_key(&channel_id)
+    {
+        return Ok(());
+    }
+
+    while let Some(retry_after) = bot.cooldowns.reaction_remove.trigger(&channel_id) {
+        if retry_after > constants::MAX_REACTION_REMOVE_WAIT {
+            return Ok(());
+        }
+        tokio::time::sleep(retry_after).await;
+    }
+
+    let key = (message_id, user_id, emoji.clone().into_stored());
+    bot.cache
+        .self_removed_reactions
+        .insert(key.clone(), ())
+        .await;
+
+    let ret = bot
+        .http
+        .delete_reaction(channel_id, message_id, &emoji.reactable(), user_id)
+        .await;
+
+    if let Err(why) = ret {
+        bot.cache.self_removed_reactions.invalidate(&key).await;
+
+        match get_status(&why) {
+            Some(403) => {
+                bot.cache
+                    .reaction_remove_forbidden
+                    .insert(channel_id, ())
+                    .await;
+            }
+            Some(404) => (),
+            _ => return Err(why.into()),
+        }
+    }
+
+    Ok(())
+}
+
+/// Runs `remove_invalid_reaction` in the background, so that waiting on the
+/// cooldown doesn't hold up the message's event queue.
+pub fn spawn_remove_invalid_reaction(
+    bot: Arc<StarboardBot>,
+    channel_id: Id<ChannelMarker>,
+    message_id: Id<MessageMarker>,
+    emoji: SimpleEmoji,
+    user_id: Id<UserMarker>,
+) {
+    let task = async move {
+        let ret = remove_invalid_reaction(&bot, channel_id, message_id, &emoji, user_id).await;
+        if let Err(why) = ret {
+            bot.handle_error(&why).await;
+        }
+    };
+    tokio::spawn(task.in_current_span());
+}
+
+/// Whether this reaction was removed by `remove_invalid_reaction`. Each
+/// removal is only matched once.
+pub async fn was_self_removed(
+    bot: &StarboardBot,
+    message_id: Id<MessageMarker>,
+    emoji: &SimpleEmoji,
+    user_id: Id<UserMarker>,
+) -> bool {
+    let key = (message_id, user_id, emoji.clone().into_stored());
+    bot.cache
+        .self_removed_reactions
+        .remove(&key)
+        .await
+        .is_some()
+}
@@ -9,12 +9,12 @@ use twilight_model::{
 use crate::{
     client::bot::StarboardBot,
     core::premium::is_premium::is_guild_premium,
-    database::{DbMessage, Starboard, StarboardMessage, StarboardOverride},
+    database::{DbMessage, DeletedPost, Starboard, StarboardMessage, StarboardOverride},
     errors::StarboardResult,
     utils::{id_as_i64::GetI64, into_id::IntoId},
 };
 
-use super::{config::StarboardConfig, handle::RefreshMessage};
+use super::{config::StarboardConfig, handle::RefreshMessage, link_reply::delete_link_reply};
 
 pub async fn handle_message_update(
     bot: Arc<StarboardBot>,
@@ -65,6 +65,7 @@ pub async fn handle_message_delete(
         let Some(sb_msg) = StarboardMessage::delete(&bot.pool, message_id_i64).await? else {
             break 'out false;
         };
+        delete_link_reply(&bot, msg.channel_id.into_id(), &sb_msg).await;
 
         // handle the `on-delete` setting for the corresponding starboard
         let Some(sb) = Starboard::get(&bot.pool, sb_msg.starboard_id).await? else {
@@ -88,6 +89,16 @@ pub async fn handle_message_delete(
 
         let config = StarboardConfig::new(sb, &channel_ids, overrides)?;
 
+        // posts the bot deleted were skipped above, so this one was deleted by
+        // hand. Whether it's sent again is up to `recreate_deleted_posts`.
+        DeletedPost::create(
+            &bot.pool,
+            msg.message_id,
+            config.starboard.id,
+            sb_msg.starboard_message_id,
+        )
+        .await?;
+
         match config.resolved.on_delete {
             0 => false,         // refresh
             1 => return Ok(()), // ignore
@@ -0,0 +1,117 @@
+//! Replies to the original message with a button linking to its starboard
+//! message (the `reply-with-link` setting).
+
+use twilight_model::{
+    channel::{
+        message::{
+            component::{ActionRow, Button, ButtonStyle},
+            AllowedMentions, Component,
+        },
+        Message,
+    },
+    id::{marker::ChannelMarker, Id},
+};
+
+use crate::{
+    client::bot::StarboardBot,
+    database::{DbMessage, StarboardMessage},
+    errors::StarboardResult,
+    utils::{
+        get_status::get_status, id_as_i64::GetI64, into_id::IntoId, message_link::fmt_message_link,
+    },
+};
+
+use super::{
+    companion::{perform_once, CompanionAction},
+    config::StarboardConfig,
+};
+
+/// Sends the reply and stores its id, unless the same reply was already sent.
+/// Does nothing if the bot can't send messages in the original channel.
+pub async fn send_link_reply(
+    bot: &StarboardBot,
+    config: &StarboardConfig,
+    orig: &DbMessage,
+    post: &Message,
+) -> StarboardResult<()> {
+    let label = bot
+        .t(
+            config.starboard.guild_id,
+            "link-reply.view",
+            &[("starboard", &config.starboard.name)],
+        )
+        .await?;
+    let post_link = fmt_message_link(config.starboard.guild_id, post.channel_id, post.id);
+    let rendered = format!("{label}\n{post_link}");
+
+    let reply = perform_once(
+        bot,
+        orig.message_id,
+        config.starboard.id,
+        CompanionAction::LinkReply,
+        &rendered,
+        || send_reply(bot, orig, label, post_link),
+    )
+    .await?;
+    let Some(Some(reply)) = reply else {
+        return Ok(());
+    };
+
+    StarboardMessage::set_reply_message_id(&bot.pool, post.id.get_i64(), reply.id.get_i64())
+        .await?;
+    bot.cache.responses.insert(post.id, reply.id).await;
+
+    Ok(())
+}
+
+async fn send_reply(
+    bot: &StarboardBot,
+    orig: &DbMessage,
+    label: String,
+    post_link: String,
+) -> StarboardResult<Option<Message>> {
+    let button = Button {
+        custom_id: None,
+        disabled: false,
+        emoji: None,
+        label: Some(label),
+        style: ButtonStyle::Link,
+        url: Some(post_link),
+    };
+    let components = [Component::ActionRow(ActionRow {
+        components: vec![Component::Button(button)],
+    })];
+
+    let ret = bot
+        .http
+        .create_message(orig.channel_id.into_id())
+        .reply(orig.message_id.into_id())
+        .fail_if_not_exists(false)
+        .allowed_mentions(Some(&AllowedMentions::default()))
+        .components(&components)?
+        .await;
+
+    match ret {
+        Ok(reply) => Ok(Some(reply.model().await?)),
+        Err(why) if get_status(&why) == Some(403) => Ok(None),
+        Err(why) => Err(why.into()),
+    }
+}
+
+/// Deletes the reply linking to a starboard message, if there was one.
+pub async fn delete_link_reply(
+    bot: &StarboardBot,
+    channel_id: Id<ChannelMarker>,
+    sb_msg: &StarboardMessage,
+) {
+    let post_id = sb_msg.starboard_message_id.into_id();
+    let reply_id = match bot.cache.responses.remove(&post_id).await {
+        Some(reply_id) => reply_id,
+        None => match sb_msg.reply_message_id {
+            Some(reply_id) => reply_id.into_id(),
+            None => return,
+        },
+    };
+
+    let _ = bot.http.delete_message(channel_id, reply_id).await;
+}
@@ -0,0 +1,298 @@
+//! Votes cast without a reaction, like with the vote buttons on a post or
+//! `/vote`. They go through the same checks as reactions and end up as the
+//! same vote rows, so a user has one vote per message and starboard however
+//! it was cast.
+
+use std::sync::Arc;
+
+use twilight_model::id::{
+    marker::{GuildMarker, UserMarker},
+    Id,
+};
+
+use crate::{
+    client::bot::StarboardBot,
+    core::{
+        blocked_voters::blocked_voter,
+        emoji::{clean_emoji, EmojiCommon, SimpleEmoji},
+        premium::is_premium::is_guild_premium,
+        stats::refresh_xp,
+        vote_weights::emoji_weight,
+    },
+    database::{DbMember, DbMessage, DbUser, Vote},
+    errors::StarboardResult,
+    utils::{id_as_i64::GetI64, into_id::IntoId},
+};
+
+use super::{
+    anonymous::stored_voter_ids,
+    config::StarboardConfig,
+    handle::RefreshMessage,
+    vote_status::{VoteContext, VoteStatus},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ManualVoteResult {
+    /// The vote was added, changed from an upvote to a downvote or back, or
+    /// cast with another emoji.
+    Added { is_downvote: bool },
+    /// The user already voted with this emoji, so the vote was removed.
+    Removed,
+    /// The user already voted with this emoji, and nothing changed.
+    Unchanged,
+    /// The vote doesn't count, for the same reasons a reaction wouldn't.
+    NotAllowed,
+}
+
+/// Toggles a user's vote with `emoji` on a message, for one starboard. Voting
+/// with another emoji of the same direction changes the vote like adding
+/// another reaction would, see `core::vote_weights::clamp_vote`.
+pub async fn toggle_vote(
+    bot: Arc<StarboardBot>,
+    guild_id: Id<GuildMarker>,
+    user_id: Id<UserMarker>,
+    orig: DbMessage,
+    starboard_id: i32,
+    emoji: &SimpleEmoji,
+) -> StarboardResult<ManualVoteResult> {
+    apply_vote(bot, guild_id, user_id, orig, starboard_id, emoji, true).await
+}
+
+/// Votes with `emoji` on a message for one starboard, replacing an opposite
+/// vote by the user.
+pub async fn cast_vote(
+    bot: Arc<StarboardBot>,
+    guild_id: Id<GuildMarker>,
+    user_id: Id<UserMarker>,
+    orig: DbMessage,
+    starboard_id: i32,
+    emoji: &SimpleEmoji,
+) -> StarboardResult<ManualVoteResult> {
+    apply_vote(bot, guild_id, user_id, orig, starboard_id, emoji, false).await
+}
+
+async fn apply_vote(
+    bot: Arc<StarboardBot>,
+    guild_id: Id<GuildMarker>,
+    user_id: Id<UserMarker>,
+    orig: DbMessage,
+    starboard_id: i32,
+    emoji: &SimpleEmoji,
+    toggle: bool,
+) -> StarboardResult<ManualVoteResult> {
+    if blocked_voter(&bot, guild_id.get_i64(), user_id.get_i64())
+        .await?
+        .is_some()
+    {
+        return Ok(ManualVoteResult::NotAllowed);
+    }
+
+    let author = DbUser::get(&bot.pool, orig.author_id).await?.unwrap();
+
+    let configs = configs_for(&bot, guild_id, &orig, starboard_id).await?;
+    let vote = VoteContext {
+        emoji,
+        reactor_id: user_id,
+        message_id: orig.message_id.into_id(),
+        channel_id: orig.channel_id.into_id(),
+        message_author_id: orig.author_id.into_id(),
+        message_author_is_bot: author.is_bot,
+        message_has_image: None,
+        message_is_frozen: orig.frozen,
+    };
+    let status = VoteStatus::get_vote_status(&bot, vote, &configs).await?;
+
+    let VoteStatus::Valid((upvote, downvote)) = status else {
+        return Ok(ManualVoteResult::NotAllowed);
+    };
+    if upvote.is_empty() && downvote.is_empty() {
+        return Ok(ManualVoteResult::NotAllowed);
+    }
+    let is_downvote = upvote.is_empty();
+
+    let user_id = user_id.get_i64();
+    let voter_ids = stored_voter_ids(&bot, &configs[0].starboard, user_id).await?;
+    bot.vote_batcher.flush_message(&bot, orig.message_id).await;
+    let existing = Vote::get_any(&bot.pool, orig.message_id, starboard_id, &voter_ids).await?;
+    let existing = existing.map(|v| (v.is_downvote, v.emoji));
+    let stored_emoji = emoji.clone().into_stored();
+    let result = vote_result(existing, is_downvote, &stored_emoji, toggle);
+    match result {
+        ManualVoteResult::Unchanged => return Ok(result),
+        ManualVoteResult::Removed => {
+            Vote::delete_any(&bot.pool, orig.message_id, starboard_id, &voter_ids).await?;
+        }
+        _ => {
+            DbUser::create(&bot.pool, user_id, false).await?;
+            DbMember::create(&bot.pool, user_id, guild_id.get_i64()).await?;
+            // a vote stored with another of the user's ids is replaced
+            Vote::delete_any(&bot.pool, orig.message_id, starboard_id, &voter_ids[1..]).await?;
+            Vote::create(
+                &bot.pool,
+                orig.message_id,
+                starboard_id,
+                voter_ids[0],
+                orig.author_id,
+                is_downvote,
+                emoji_weight(&configs[0].resolved.vote_emoji_weights, emoji),
+                Some(&stored_emoji),
+            )
+            .await?;
+            bot.emoji_usage.record(starboard_id, emoji);
+        }
+    }
+
+    refresh_after_vote(bot, guild_id, orig, configs).await?;
+
+    Ok(result)
+}
+
+/// What voting in a direction with a stored emoji does, given the user's
+/// existing vote (whether it's a downvote, and its emoji). Voting with the
+/// same emoji again removes the vote when toggling. Votes from before emojis
+/// were stored match any emoji of their direction.
+fn vote_result(
+    existing: Option<(bool, Option<String>)>,
+    is_downvote: bool,
+    emoji: &str,
+    toggle: bool,
+) -> ManualVoteResult {
+    let same_emoji = |existing: &Option<String>| {
+        existing
+            .as_deref()
+            .map_or(true, |e| clean_emoji(e) == clean_emoji(emoji))
+    };
+    match existing {
+        Some((was_downvote, existing_emoji))
+            if was_downvote == is_downvote && same_emoji(&existing_emoji) =>
+        {
+            if toggle {
+                ManualVoteResult::Removed
+            } else {
+                ManualVoteResult::Unchanged
+            }
+        }
+        _ => ManualVoteResult::Added { is_downvote },
+    }
+}
+
+/// Removes a user's vote on a message for one starboard, however it was cast.
+/// Returns whether there was a vote to remove.
+pub async fn remove_vote(
+    bot: Arc<StarboardBot>,
+    guild_id: Id<GuildMarker>,
+    user_id: Id<UserMarker>,
+    orig: DbMessage,
+    starboard_id: i32,
+) -> StarboardResult<bool> {
+    let configs = configs_for(&bot, guild_id, &orig, starboard_id).await?;
+    let Some(config) = configs.first() else {
+        return Ok(false);
+    };
+
+    let voter_ids = stored_voter_ids(&bot, &config.starboard, user_id.get_i64()).await?;
+    bot.vote_batcher.flush_message(&bot, orig.message_id).await;
+    let removed = Vote::delete_any(&bot.pool, orig.message_id, starboard_id, &voter_ids).await?;
+    if removed.is_empty() {
+        return Ok(false);
+    }
+
+    refresh_after_vote(bot, guild_id, orig, configs).await?;
+
+    Ok(true)
+}
+
+async fn configs_for(
+    bot: &StarboardBot,
+    guild_id: Id<GuildMarker>,
+    orig: &DbMessage,
+    starboard_id: i32,
+) -> StarboardResult<Vec<StarboardConfig>> {
+    let mut configs =
+        StarboardConfig::list_for_channel(bot, guild_id, orig.channel_id.into_id()).await?;
+    configs.retain(|c| c.starboard.id == starboard_id);
+    Ok(configs)
+}
+
+async fn refresh_after_vote(
+    bot: Arc<StarboardBot>,
+    guild_id: Id<GuildMarker>,
+    orig: DbMessage,
+    configs: Vec<StarboardConfig>,
+) -> StarboardResult<()> {
+    let author_id = orig.author_id;
+    let is_premium = is_guild_premium(&bot, guild_id.get_i64(), true).await?;
+    let mut refresh = RefreshMessage::new(bot.clone(), orig.message_id.into_id(), is_premium);
+    refresh.set_configs(configs.into_iter().map(Arc::new).collect());
+    refresh.set_sql_message(orig);
+    refresh.refresh(false).await?;
+
+    refresh_xp(&bot, guild_id, author_id.into_id()).await?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn existing(is_downvote: bool, emoji: &str) -> Option<(bool, Option<String>)> {
+        Some((is_downvote, Some(emoji.to_string())))
+    }
+
+    #[test]
+    fn new_vote() {
+        assert_eq!(
+            vote_result(None, false, "⭐", true),
+            ManualVoteResult::Added { is_downvote: false }
+        );
+        assert_eq!(
+            vote_result(None, true, "👎", false),
+            ManualVoteResult::Added { is_downvote: true }
+        );
+    }
+
+    #[test]
+    fn flipped_vote() {
+        assert_eq!(
+            vote_result(existing(false, "⭐"), true, "👎", true),
+            ManualVoteResult::Added { is_downvote: true }
+        );
+        assert_eq!(
+            vote_result(existing(true, "👎"), false, "⭐", false),
+            ManualVoteResult::Added { is_downvote: false }
+        );
+    }
+
+    #[test]
+    fn same_vote() {
+        assert_eq!(
+            vote_result(existing(false, "⭐"), false, "⭐", true),
+            ManualVoteResult::Removed
+        );
+        assert_eq!(
+            vote_result(existing(true, "👎"), true, "👎", false),
+            ManualVoteResult::Unchanged
+        );
+        // emojis are compared without their variation selector
+        assert_eq!(
+            vote_result(existing(false, "❤\u{fe0f}"), false, "❤", true),
+            ManualVoteResult::Removed
+        );
+    }
+
+    #[test]
+    fn toggles_per_emoji() {
+        // another emoji of the same direction adds to the vote instead of
+        // removing it
+        assert_eq!(
+            vote_result(existing(false, "⭐"), false, "💎", true),
+            ManualVoteResult::Added { is_downvote: false }
+        );
+        // votes without a stored emoji match any emoji
+        assert_eq!(
+            vote_result(Some((false, None)), false, "💎", true),
+            ManualVoteResult::Removed
+        );
+    }
+}
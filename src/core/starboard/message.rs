@@ -5,11 +5,43 @@ use twilight_model::id::{
 
 use crate::{
     client::bot::StarboardBot,
-    database::{DbMember, DbMessage, DbUser},
+    database::{DbMember, DbMessage, DbUser, StarboardMessage},
     errors::StarboardResult,
     utils::id_as_i64::GetI64,
 };
 
+/// The original message and the starboard of a post, or `None` if the message
+/// isn't a post.
+pub async fn get_post_original(
+    bot: &StarboardBot,
+    message_id: Id<MessageMarker>,
+) -> StarboardResult<Option<(i64, i32)>> {
+    if let Some(cached) = bot.cache.post_originals.get(&message_id) {
+        return Ok(cached);
+    }
+
+    let post = StarboardMessage::get(&bot.pool, message_id.get_i64())
+        .await?
+        .map(|post| (post.message_id, post.starboard_id));
+    bot.cache.post_originals.insert(message_id, post).await;
+
+    Ok(post)
+}
+
+/// Caches a post that was just created, in case it was cached as not being a
+/// post before.
+pub async fn cache_post_original(
+    bot: &StarboardBot,
+    post_id: Id<MessageMarker>,
+    orig_id: i64,
+    starboard_id: i32,
+) {
+    bot.cache
+        .post_originals
+        .insert(post_id, Some((orig_id, starboard_id)))
+        .await;
+}
+
 pub async fn get_or_create_original(
     bot: &StarboardBot,
     guild_id: Id<GuildMarker>,
@@ -20,7 +52,11 @@ pub async fn get_or_create_original(
     let channel_id_i64 = channel_id.get_i64();
     let message_id_i64 = message_id.get_i64();
 
-    if let Some(orig) = DbMessage::get_original(&bot.pool, message_id_i64).await? {
+    let orig_id = match get_post_original(bot, message_id).await? {
+        Some((orig_id, _)) => orig_id,
+        None => message_id_i64,
+    };
+    if let Some(orig) = DbMessage::get(&bot.pool, orig_id).await? {
         return Ok((Some(orig), None));
     }
 
@@ -0,0 +1,161 @@
+//! Importing posts from an existing starboard channel, such as one run by an
+//! older version of this bot or by another starboard bot.
+
+use lazy_static::lazy_static;
+use regex::Regex;
+use twilight_model::{
+    channel::{message::component::Component, Message},
+    id::{marker::GuildMarker, Id},
+};
+
+use crate::{
+    client::bot::StarboardBot,
+    constants,
+    database::{MigrationCursor, StarboardMessage},
+    errors::StarboardResult,
+    utils::{id_as_i64::GetI64, into_id::IntoId, message_link::find_message_link},
+};
+
+use super::message::{cache_post_original, get_or_create_original};
+
+#[derive(Debug)]
+pub struct ParsedPost {
+    pub channel_id: i64,
+    pub message_id: i64,
+    pub points: i32,
+}
+
+/// Parses a starboard post. Both this bot's format (`⭐ **5 |** <#channel>`)
+/// and the common `⭐ 5 #channel` layout are supported, as long as there's a
+/// jump link to the original message somewhere in the post.
+pub fn parse_post(msg: &Message) -> Option<ParsedPost> {
+    lazy_static! {
+        static ref CUSTOM_EMOJI: Regex = Regex::new(r"<a?:\w+:\d+>").unwrap();
+        static ref POINTS: Regex = Regex::new(r"^[^\d<#]*?(\d+)").unwrap();
+    }
+
+    if !msg.author.bot {
+        return None;
+    }
+
+    let content = CUSTOM_EMOJI.replace_all(&msg.content, "");
+    let points = POINTS
+        .captures(&content)?
+        .get(1)
+        .unwrap()
+        .as_str()
+        .parse::<i32>()
+        .ok()?
+        .min(i16::MAX as i32);
+
+    let (channel_id, message_id) = find_jump_link(msg)?;
+
+    Some(ParsedPost {
+        channel_id,
+        message_id,
+        points,
+    })
+}
+
+fn find_jump_link(msg: &Message) -> Option<(i64, i64)> {
+    if let Some(link) = find_message_link(&msg.content) {
+        return Some(link);
+    }
+
+    for embed in &msg.embeds {
+        let texts = embed
+            .url
+            .iter()
+            .chain(embed.description.iter())
+            .chain(embed.fields.iter().map(|f| &f.value));
+        for text in texts {
+            if let Some(link) = find_message_link(text) {
+                return Some(link);
+            }
+        }
+    }
+
+    for row in &msg.components {
+        let Component::ActionRow(row) = row else {
+            continue;
+        };
+        for comp in &row.components {
+            let Component::Button(button) = comp else {
+                continue;
+            };
+            if let Some(link) = button.url.as_deref().and_then(find_message_link) {
+                return Some(link);
+            }
+        }
+    }
+
+    None
+}
+
+/// Scans one page of the channel, older than the cursor, and imports every
+/// post it recognizes. The cursor is saved afterwards, so an interrupted
+/// migration can be resumed.
+pub async fn migrate_page(
+    bot: &StarboardBot,
+    guild_id: Id<GuildMarker>,
+    cursor: &mut MigrationCursor,
+) -> StarboardResult<()> {
+    let req = bot
+        .http
+        .channel_messages(cursor.channel_id.into_id())
+        .limit(constants::MIGRATE_PAGE_SIZE)?;
+    let messages = match cursor.before_id {
+        Some(before_id) => req.before(before_id.into_id()).await?,
+        None => req.await?,
+    }
+    .models()
+    .await?;
+
+    let mut imported = 0;
+    for msg in &messages {
+        let Some(post) = parse_post(msg) else {
+            continue;
+        };
+        if StarboardMessage::get(&bot.pool, msg.id.get_i64())
+            .await?
+            .is_some()
+        {
+            continue;
+        }
+
+        let (orig, _) = get_or_create_original(
+            bot,
+            guild_id,
+            post.channel_id.into_id(),
+            post.message_id.into_id(),
+        )
+        .await?;
+        // the original was deleted, or we can't see it
+        let Some(orig) = orig else {
+            continue;
+        };
+
+        let created = StarboardMessage::create(
+            &bot.pool,
+            orig.message_id,
+            msg.id.get_i64(),
+            cursor.starboard_id,
+            post.points,
+        )
+        .await?;
+        if created.is_some() {
+            cache_post_original(bot, msg.id, orig.message_id, cursor.starboard_id).await;
+            imported += 1;
+        }
+    }
+
+    cursor.scanned += messages.len() as i32;
+    cursor.imported += imported;
+    if let Some(last) = messages.last() {
+        cursor.before_id = Some(last.id.get_i64());
+    }
+    cursor.finished = messages.len() < constants::MIGRATE_PAGE_SIZE as usize;
+    cursor.save(&bot.pool).await?;
+
+    Ok(())
+}
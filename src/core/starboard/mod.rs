@@ -1,9 +1,25 @@
+pub mod anonymous;
+pub mod cleanup;
+pub mod companion;
 pub mod config;
+pub mod dedupe;
+pub mod emoji_usage;
+pub mod deferred_refresh;
 pub mod handle;
+pub mod invalid_reactions;
 pub mod link_events;
+pub mod link_reply;
+pub mod manual_vote;
 pub mod message;
+pub mod migrate;
 pub mod msg_status;
+pub mod nsfw;
 pub mod reaction_events;
 pub mod recount;
+pub mod refresh_stats;
+pub mod schedule;
+pub mod simulation;
+pub mod vote_batcher;
+pub mod vote_locks;
 pub mod vote_status;
 pub mod webhooks;
@@ -1,6 +1,19 @@
+use chrono::{DateTime, Utc};
+
 use crate::{
-    cache::MessageResult, client::bot::StarboardBot, database::DbMessage, errors::StarboardResult,
-    utils::into_id::IntoId,
+    cache::MessageResult,
+    client::bot::StarboardBot,
+    constants,
+    database::{
+        models::vote::{VoteCount, VoteSpread},
+        DbMessage, StarboardMessage, Vote,
+    },
+    errors::StarboardResult,
+    utils::{
+        into_id::IntoId,
+        snowflake_age::{snowflake_at, snowflake_time},
+        system_content::is_renderable,
+    },
 };
 
 use super::config::StarboardConfig;
@@ -10,21 +23,170 @@ pub enum MessageStatus {
     /// true -> full update, false -> partial update
     Update(bool),
     Remove,
+    /// The message was trashed. An existing post is edited into a
+    /// placeholder that keeps its place, and no new post is sent.
+    Trashed,
     /// true -> full update, false -> partial update
     Send(bool),
+    /// Leave the starboard message (if any) untouched.
+    NoAction,
+    /// The message would be sent, but its votes don't meet
+    /// `min_vote_spread_seconds` yet. Re-evaluate at the given time.
+    AwaitingVoteSpread(DateTime<Utc>),
+    /// The message would be sent, but its author already got
+    /// `max_posts_per_author_per_day` posts. Votes keep counting, and it's
+    /// re-evaluated once the author is back under the limit, at the given
+    /// time.
+    AwaitingAuthorLimit(DateTime<Utc>),
+}
+
+/// Fetches the vote spread for a message, if the starboard needs it.
+pub async fn get_vote_spread(
+    bot: &StarboardBot,
+    config: &StarboardConfig,
+    message_id: i64,
+) -> sqlx::Result<Option<VoteSpread>> {
+    if config.resolved.min_vote_spread_seconds.is_none() {
+        return Ok(None);
+    }
+
+    let starboard = &config.starboard;
+    if starboard.aggregate {
+        Vote::spread(&bot.pool, message_id, &starboard.source_starboards).await
+    } else {
+        Vote::spread(&bot.pool, message_id, &[starboard.id]).await
+    }
+}
+
+/// Returns when the vote spread requirement will be met, if it isn't yet.
+/// Messages with fewer than `MIN_VOTE_SPREAD_VOTES` upvotes are exempt.
+fn vote_spread_ready_at(
+    min_spread: Option<i32>,
+    spread: Option<VoteSpread>,
+    now: DateTime<Utc>,
+) -> Option<DateTime<Utc>> {
+    let min_spread = min_spread?;
+    let spread = spread?;
+    if spread.upvotes < constants::MIN_VOTE_SPREAD_VOTES {
+        return None;
+    }
+
+    let ready_at = spread.first + chrono::Duration::seconds(min_spread as i64);
+    if now >= ready_at {
+        None
+    } else {
+        Some(ready_at)
+    }
+}
+
+/// Holds back a new post while the author is at
+/// `max_posts_per_author_per_day`. Existing and forced posts are never held
+/// back.
+pub async fn apply_author_limit(
+    bot: &StarboardBot,
+    config: &StarboardConfig,
+    message: &DbMessage,
+    status: MessageStatus,
+    has_post: bool,
+) -> StarboardResult<MessageStatus> {
+    if has_post
+        || !matches!(status, MessageStatus::Send(_))
+        || message.forced_to.contains(&config.starboard.id)
+    {
+        return Ok(status);
+    }
+
+    match author_limit_ready_at(bot, config, message.author_id).await? {
+        Some(ready_at) => Ok(MessageStatus::AwaitingAuthorLimit(ready_at)),
+        None => Ok(status),
+    }
 }
 
+/// When the author will be back under `max_posts_per_author_per_day`, if
+/// they're at the limit now.
+async fn author_limit_ready_at(
+    bot: &StarboardBot,
+    config: &StarboardConfig,
+    author_id: i64,
+) -> StarboardResult<Option<DateTime<Utc>>> {
+    let Some(max) = config.resolved.max_posts_per_author_per_day else {
+        return Ok(None);
+    };
+
+    let window = chrono::Duration::hours(24);
+    let posts = StarboardMessage::list_recent_by_author(
+        &bot.pool,
+        config.starboard.id,
+        author_id,
+        snowflake_at(Utc::now() - window),
+    )
+    .await?;
+
+    let Some(expiring) = expiring_post(&posts, max) else {
+        return Ok(None);
+    };
+
+    Ok(Some(snowflake_time(expiring) + window))
+}
+
+/// The post that has to leave the window before the author gets another
+/// one, if they're at `max`. `posts` are the author's posts in the window,
+/// oldest first.
+fn expiring_post(posts: &[i64], max: i16) -> Option<i64> {
+    posts
+        .len()
+        .checked_sub(max as usize)
+        .and_then(|idx| posts.get(idx))
+        .copied()
+}
+
+/// Whether a message is NSFW, going by its channel's current state. Channels
+/// can be toggled after the message was stored, so the stored flag is only
+/// used if the channel is gone.
+pub async fn is_message_nsfw(bot: &StarboardBot, message: &DbMessage) -> StarboardResult<bool> {
+    let is_nsfw = bot
+        .cache
+        .fog_channel_nsfw(
+            bot,
+            message.guild_id.into_id(),
+            message.channel_id.into_id(),
+        )
+        .await?;
+
+    Ok(resolve_nsfw(is_nsfw, message.is_nsfw))
+}
+
+/// The channel's NSFW flag, or the stored one if the channel is gone.
+fn resolve_nsfw(channel_nsfw: Option<bool>, stored: bool) -> bool {
+    channel_nsfw.unwrap_or(stored)
+}
+
+#[allow(clippy::too_many_arguments)]
 pub async fn get_message_status(
     bot: &StarboardBot,
     config: &StarboardConfig,
     message: &DbMessage,
     message_obj: &MessageResult,
-    points: i32,
+    count: VoteCount,
+    vote_spread: Option<VoteSpread>,
     violates_exclusive_group: bool,
     is_premium: bool,
 ) -> StarboardResult<MessageStatus> {
     let deleted = matches!(message_obj, MessageResult::Missing);
 
+    // some message types (e.g. call notifications) can't be rendered at all
+    if let MessageResult::Ok(msg) = message_obj {
+        if !is_renderable(msg.kind) {
+            return Ok(MessageStatus::NoAction);
+        }
+    }
+
+    // nothing of a trashed message is shown, so it can't leak into a
+    // channel that isn't NSFW either
+    if message.trashed {
+        return Ok(MessageStatus::Trashed);
+    }
+
     let guild_id = config.starboard.guild_id.into_id();
     let sb_channel_id = config.starboard.channel_id.into_id();
     let sb_is_nsfw = bot
@@ -37,10 +199,9 @@ pub async fn get_message_status(
         None => return Ok(MessageStatus::Update(config.resolved.link_edits)),
     };
 
-    if (deleted && config.resolved.link_deletes)
-        || (message.is_nsfw && !sb_is_nsfw)
-        || message.trashed
-    {
+    let is_nsfw = is_message_nsfw(bot, message).await?;
+
+    if (deleted && config.resolved.link_deletes) || (is_nsfw && !sb_is_nsfw) {
         return Ok(MessageStatus::Remove);
     }
 
@@ -56,7 +217,7 @@ pub async fn get_message_status(
     }
 
     if let Some(required_remove) = config.resolved.required_remove {
-        if points <= required_remove as i32 {
+        if count.points <= required_remove as i32 {
             return Ok(MessageStatus::Remove);
         }
     }
@@ -64,7 +225,14 @@ pub async fn get_message_status(
     if let Some(required) = config.resolved.required {
         if validate_regex(config, message_obj, is_premium) {
             #[allow(clippy::collapsible_if)]
-            if points >= required as i32 {
+            if meets_required(required, config.resolved.min_unique_voters, count) {
+                if let Some(ready_at) = vote_spread_ready_at(
+                    config.resolved.min_vote_spread_seconds,
+                    vote_spread,
+                    Utc::now(),
+                ) {
+                    return Ok(MessageStatus::AwaitingVoteSpread(ready_at));
+                }
                 return Ok(MessageStatus::Send(config.resolved.link_edits));
             }
         }
@@ -73,6 +241,21 @@ pub async fn get_message_status(
     Ok(MessageStatus::Update(config.resolved.link_edits))
 }
 
+/// Whether enough different users upvoted the message (`min-unique-voters`),
+/// regardless of its points.
+pub fn has_unique_voters(config: &StarboardConfig, count: VoteCount) -> bool {
+    enough_unique_voters(config.resolved.min_unique_voters, count)
+}
+
+fn enough_unique_voters(min_unique_voters: Option<i16>, count: VoteCount) -> bool {
+    min_unique_voters.map_or(true, |min| count.unique_voters >= min as i32)
+}
+
+/// Whether the message has enough points and unique voters to be sent.
+fn meets_required(required: i16, min_unique_voters: Option<i16>, count: VoteCount) -> bool {
+    enough_unique_voters(min_unique_voters, count) && count.points >= required as i32
+}
+
 fn validate_regex(config: &StarboardConfig, message_obj: &MessageResult, is_premium: bool) -> bool {
     if !is_premium {
         return true;
@@ -103,3 +286,108 @@ fn validate_regex(config: &StarboardConfig, message_obj: &MessageResult, is_prem
 
     true
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn count(points: i32, unique_voters: i32) -> VoteCount {
+        VoteCount {
+            points,
+            unique_voters,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn single_heavy_voter_fails() {
+        // one vote worth 5 points
+        assert!(!meets_required(5, Some(3), count(5, 1)));
+    }
+
+    #[test]
+    fn distributed_votes_pass() {
+        assert!(meets_required(5, Some(3), count(5, 5)));
+        assert!(meets_required(5, Some(3), count(5, 3)));
+    }
+
+    #[test]
+    fn points_still_needed() {
+        assert!(!meets_required(5, Some(3), count(4, 4)));
+    }
+
+    #[test]
+    fn without_min_unique_voters() {
+        assert!(meets_required(5, None, count(5, 1)));
+        assert!(!meets_required(5, None, count(4, 4)));
+    }
+
+    fn spread(upvotes: i64, first: DateTime<Utc>) -> Option<VoteSpread> {
+        Some(VoteSpread { upvotes, first })
+    }
+
+    #[test]
+    fn waits_for_vote_spread() {
+        let now = Utc::now();
+        let first = now - chrono::Duration::seconds(20);
+
+        assert_eq!(
+            vote_spread_ready_at(Some(60), spread(5, first), now),
+            Some(first + chrono::Duration::seconds(60))
+        );
+    }
+
+    #[test]
+    fn spread_out_votes() {
+        let now = Utc::now();
+        let first = now - chrono::Duration::seconds(60);
+
+        assert_eq!(vote_spread_ready_at(Some(60), spread(5, first), now), None);
+    }
+
+    #[test]
+    fn few_votes_are_exempt() {
+        let now = Utc::now();
+        let votes = constants::MIN_VOTE_SPREAD_VOTES;
+
+        assert_eq!(
+            vote_spread_ready_at(Some(60), spread(votes - 1, now), now),
+            None
+        );
+        assert!(vote_spread_ready_at(Some(60), spread(votes, now), now).is_some());
+    }
+
+    #[test]
+    fn vote_spread_disabled() {
+        let now = Utc::now();
+
+        assert_eq!(vote_spread_ready_at(None, spread(5, now), now), None);
+        assert_eq!(vote_spread_ready_at(Some(60), None, now), None);
+    }
+
+    #[test]
+    fn nsfw_follows_the_channel() {
+        assert!(resolve_nsfw(Some(true), false));
+        assert!(!resolve_nsfw(Some(false), true));
+    }
+
+    #[test]
+    fn nsfw_falls_back_to_stored() {
+        assert!(resolve_nsfw(None, true));
+        assert!(!resolve_nsfw(None, false));
+    }
+
+    #[test]
+    fn under_author_limit() {
+        assert_eq!(expiring_post(&[], 1), None);
+        assert_eq!(expiring_post(&[1, 2], 3), None);
+    }
+
+    #[test]
+    fn at_author_limit() {
+        assert_eq!(expiring_post(&[1, 2, 3], 3), Some(1));
+        // lowering the limit can leave an author over it
+        assert_eq!(expiring_post(&[1, 2, 3], 2), Some(2));
+        assert_eq!(expiring_post(&[1, 2, 3], 1), Some(3));
+    }
+}
@@ -0,0 +1,132 @@
+//! Channels can be toggled NSFW after messages in them were starred. When
+//! that happens, the recent starred messages are refreshed so that posts on
+//! SFW starboards are removed (or sent again, if the channel became SFW).
+
+use std::sync::Arc;
+
+use tracing::Instrument;
+use twilight_gateway::Event;
+use twilight_model::id::{
+    marker::{ChannelMarker, GuildMarker},
+    Id,
+};
+
+use crate::{
+    client::bot::StarboardBot,
+    constants,
+    core::{
+        features::{is_feature_enabled, Flag},
+        premium::is_premium::is_guild_premium,
+    },
+    database::DbMessage,
+    errors::StarboardResult,
+    utils::{id_as_i64::GetI64, into_id::IntoId},
+};
+
+use super::handle::RefreshMessage;
+
+/// The channel whose NSFW flag is changed by this event, if any. Must be
+/// called before the cache is updated with the event.
+pub fn nsfw_toggled_channel(
+    bot: &StarboardBot,
+    event: &Event,
+) -> Option<(Id<GuildMarker>, Id<ChannelMarker>)> {
+    let Event::ChannelUpdate(event) = event else {
+        return None;
+    };
+    let guild_id = event.guild_id?;
+    let is_nsfw = event.nsfw?;
+
+    let was_nsfw = bot.cache.guilds.with(&guild_id, |_, guild| {
+        guild.as_ref()?.channels.get(&event.id).map(|c| c.is_nsfw)
+    })?;
+
+    nsfw_flipped(was_nsfw, is_nsfw).then_some((guild_id, event.id))
+}
+
+/// Whether the channel's NSFW flag changed. Channels whose flag wasn't known
+/// aren't treated as toggled.
+fn nsfw_flipped(was_nsfw: Option<bool>, is_nsfw: bool) -> bool {
+    was_nsfw.is_some_and(|was_nsfw| was_nsfw != is_nsfw)
+}
+
+/// Spawns a refresh of the recent starred messages in a channel (and its
+/// active threads) after its NSFW flag changed.
+pub fn spawn_nsfw_refresh(
+    bot: Arc<StarboardBot>,
+    guild_id: Id<GuildMarker>,
+    channel_id: Id<ChannelMarker>,
+) {
+    let task = async move {
+        if let Err(why) = refresh_nsfw_channel(&bot, guild_id, channel_id).await {
+            bot.handle_error(&why).await;
+        }
+    };
+    tokio::spawn(task.in_current_span());
+}
+
+async fn refresh_nsfw_channel(
+    bot: &Arc<StarboardBot>,
+    guild_id: Id<GuildMarker>,
+    channel_id: Id<ChannelMarker>,
+) -> StarboardResult<()> {
+    if !is_feature_enabled(bot, Flag::NsfwRefresh, guild_id.get_i64()).await? {
+        return Ok(());
+    }
+
+    let mut channel_ids = vec![channel_id.get_i64()];
+    bot.cache.guilds.with(&guild_id, |_, guild| {
+        let Some(guild) = guild else {
+            return;
+        };
+        for (thread_id, parent_id) in guild.active_thread_parents.iter() {
+            if *parent_id == channel_id {
+                channel_ids.push(thread_id.get_i64());
+            }
+        }
+    });
+
+    let messages =
+        DbMessage::list_recent_voted(&bot.pool, &channel_ids, constants::NSFW_REFRESH_LIMIT)
+            .await?;
+    if messages.is_empty() {
+        return Ok(());
+    }
+
+    tracing::info!(
+        "Refreshing {} messages after an NSFW toggle.",
+        messages.len()
+    );
+
+    let is_premium = is_guild_premium(bot, guild_id.get_i64(), true).await?;
+    for orig in messages {
+        let mut refresh = RefreshMessage::new(bot.clone(), orig.message_id.into_id(), is_premium);
+        refresh.set_sql_message(orig);
+        if let Err(why) = refresh.refresh(true).await {
+            bot.handle_error(&why).await;
+        }
+
+        tokio::time::sleep(constants::NSFW_REFRESH_DELAY).await;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn flips() {
+        assert!(nsfw_flipped(Some(false), true));
+        assert!(nsfw_flipped(Some(true), false));
+    }
+
+    #[test]
+    fn no_flip() {
+        assert!(!nsfw_flipped(Some(true), true));
+        assert!(!nsfw_flipped(Some(false), false));
+        assert!(!nsfw_flipped(None, true));
+        assert!(!nsfw_flipped(None, false));
+    }
+}
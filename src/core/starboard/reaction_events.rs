@@ -1,22 +1,43 @@
 use std::sync::Arc;
 
-use twilight_model::gateway::payload::incoming::{ReactionAdd, ReactionRemove};
+use tracing::Instrument;
+use twilight_model::{
+    gateway::payload::incoming::{ReactionAdd, ReactionRemove},
+    id::{
+        marker::{GuildMarker, UserMarker},
+        Id,
+    },
+};
 
 use crate::{
     client::bot::StarboardBot,
-    core::{emoji::SimpleEmoji, premium::is_premium::is_guild_premium, stats::refresh_xp},
-    database::{DbMember, DbMessage, DbUser, Vote},
+    core::{
+        blocked_voters::blocked_voter,
+        emoji::{EmojiCommon, SimpleEmoji},
+        premium::is_premium::is_guild_premium,
+        stats::refresh_xp,
+        vote_weights::emoji_weight,
+    },
+    database::{models::vote::BufferedVote, DbMember, DbMessage, DbUser, Vote, VoteLock},
     errors::StarboardResult,
     utils::{id_as_i64::GetI64, into_id::IntoId},
 };
 
 use super::{
+    anonymous::{stored_voter_id, stored_voter_ids},
     config::StarboardConfig,
     handle::RefreshMessage,
-    message::get_or_create_original,
+    invalid_reactions::{spawn_remove_invalid_reaction, was_self_removed},
+    message::{get_or_create_original, get_post_original},
     vote_status::{VoteContext, VoteStatus},
 };
 
+/// A reaction on a post only counts as a vote for the post's starboard, and
+/// only if it has `votes_on_post` enabled.
+fn retain_post_configs(configs: &mut Vec<StarboardConfig>, starboard_id: i32) {
+    configs.retain(|c| c.starboard.id == starboard_id && c.resolved.votes_on_post);
+}
+
 pub async fn handle_reaction_add(
     bot: Arc<StarboardBot>,
     event: Box<ReactionAdd>,
@@ -56,7 +77,20 @@ pub async fn handle_reaction_add(
     if !StarboardConfig::is_guild_vote_emoji(&bot, guild_id.get_i64(), &emoji).await? {
         return Ok(());
     }
+    if let Some(remove) = blocked_voter(&bot, guild_id.get_i64(), event.user_id.get_i64()).await? {
+        if remove {
+            spawn_remove_invalid_reaction(
+                bot.clone(),
+                event.channel_id,
+                event.message_id,
+                emoji,
+                event.user_id,
+            );
+        }
+        return Ok(());
+    }
 
+    let post = get_post_original(&bot, event.message_id).await?;
     let (Some(orig_msg), author_is_bot) = get_or_create_original(&bot, guild_id, event.channel_id, event.message_id).await? else {
         return Ok(());
     };
@@ -70,8 +104,11 @@ pub async fn handle_reaction_add(
         }
     };
 
-    let configs =
+    let mut configs =
         StarboardConfig::list_for_channel(&bot, guild_id, orig_msg.channel_id.into_id()).await?;
+    if let Some((_, starboard_id)) = post {
+        retain_post_configs(&mut configs, starboard_id);
+    }
     let vote = VoteContext {
         emoji: &emoji,
         reactor_id: event.user_id,
@@ -87,18 +124,28 @@ pub async fn handle_reaction_add(
     // for future user, since orig_msg is moved
     let author_id = orig_msg.author_id;
 
+    let mut refresh = None;
     match status {
         VoteStatus::Ignore => (),
         VoteStatus::Remove => {
-            let _ = bot
-                .http
-                .delete_reaction(
-                    event.channel_id,
-                    event.message_id,
-                    &emoji.reactable(),
-                    event.user_id,
-                )
-                .await;
+            spawn_remove_invalid_reaction(
+                bot.clone(),
+                event.channel_id,
+                event.message_id,
+                emoji.clone(),
+                event.user_id,
+            );
+
+            let lock = configs.iter().any(|c| {
+                c.resolved.lock_vote_after_mod_removal
+                    && (c.resolved.upvote_emojis.iter().any(|e| emoji == *e)
+                        || c.resolved.downvote_emojis.iter().any(|e| emoji == *e))
+            });
+            if lock {
+                let reactor_user_id = reactor_member.user.id.get_i64();
+                DbUser::create(&bot.pool, reactor_user_id, reactor_member.user.bot).await?;
+                VoteLock::create(&bot.pool, orig_msg.message_id, reactor_user_id, None).await?;
+            }
         }
         VoteStatus::Valid((upvote, downvote)) => {
             // create reactor data
@@ -106,39 +153,34 @@ pub async fn handle_reaction_add(
             DbUser::create(&bot.pool, reactor_user_id, reactor_member.user.bot).await?;
             DbMember::create(&bot.pool, reactor_user_id, guild_id.get_i64()).await?;
 
-            // create the votes
-            for config in &upvote {
-                Vote::create(
-                    &bot.pool,
-                    orig_msg.message_id,
-                    config.starboard.id,
-                    reactor_user_id,
-                    orig_msg.author_id,
-                    false,
-                )
-                .await?;
-            }
-            for config in &downvote {
-                Vote::create(
-                    &bot.pool,
-                    orig_msg.message_id,
-                    config.starboard.id,
-                    reactor_user_id,
-                    orig_msg.author_id,
-                    true,
-                )
-                .await?;
+            // create the votes. They're written when the message is
+            // refreshed below, at the latest.
+            let votes = upvote
+                .iter()
+                .map(|c| (c, false))
+                .chain(downvote.iter().map(|c| (c, true)));
+            for (config, is_downvote) in votes {
+                bot.vote_batcher.push(BufferedVote {
+                    message_id: orig_msg.message_id,
+                    starboard_id: config.starboard.id,
+                    voter_id: stored_voter_id(&bot, &config.starboard, reactor_user_id).await?,
+                    target_author_id: orig_msg.author_id,
+                    is_downvote,
+                    weight: emoji_weight(&config.resolved.vote_emoji_weights, &emoji),
+                    emoji: emoji.clone().into_stored(),
+                });
+                bot.emoji_usage.record(config.starboard.id, &emoji);
             }
 
             let is_premium = is_guild_premium(&bot, guild_id.get_i64(), true).await?;
-            let mut refresh = RefreshMessage::new(bot.clone(), event.message_id, is_premium);
-            refresh.set_configs(configs.into_iter().map(Arc::new).collect());
-            refresh.set_sql_message(orig_msg);
-            refresh.refresh(false).await?;
+            let mut new = RefreshMessage::new(bot.clone(), event.message_id, is_premium);
+            new.set_configs(configs.into_iter().map(Arc::new).collect());
+            new.set_sql_message(orig_msg);
+            refresh = Some(new);
         }
     }
 
-    refresh_xp(&bot, guild_id, author_id.into_id()).await?;
+    spawn_refresh(bot, refresh, guild_id, author_id.into_id());
 
     Ok(())
 }
@@ -152,15 +194,27 @@ pub async fn handle_reaction_remove(
         Some(guild_id) => guild_id,
     };
 
-    let orig = match DbMessage::get_original(&bot.pool, event.message_id.get_i64()).await? {
+    let emoji = SimpleEmoji::from(event.emoji.clone());
+    if was_self_removed(&bot, event.message_id, &emoji, event.user_id).await {
+        return Ok(());
+    }
+
+    let post = get_post_original(&bot, event.message_id).await?;
+    let orig_id = match post {
+        Some((orig_id, _)) => orig_id,
+        None => event.message_id.get_i64(),
+    };
+    let orig = match DbMessage::get(&bot.pool, orig_id).await? {
         None => return Ok(()),
         Some(orig) => orig,
     };
     let author = DbUser::get(&bot.pool, orig.author_id).await?.unwrap();
 
-    let emoji = SimpleEmoji::from(event.emoji.clone());
-    let configs =
+    let mut configs =
         StarboardConfig::list_for_channel(&bot, guild_id, orig.channel_id.into_id()).await?;
+    if let Some((_, starboard_id)) = post {
+        retain_post_configs(&mut configs, starboard_id);
+    }
     let vote = VoteContext {
         emoji: &emoji,
         reactor_id: event.user_id,
@@ -173,24 +227,52 @@ pub async fn handle_reaction_remove(
     };
     let status = VoteStatus::get_vote_status(&bot, vote, &configs).await?;
 
+    let mut refresh = None;
     match status {
         VoteStatus::Valid((upvote, downvote)) => {
             let user_id = event.user_id.get_i64();
             let all_configs: Vec<_> = upvote.into_iter().chain(downvote).collect();
+            // a vote still waiting to be written would come back otherwise
+            bot.vote_batcher.flush_message(&bot, orig.message_id).await;
             for config in &all_configs {
-                Vote::delete(&bot.pool, orig.message_id, config.starboard.id, user_id).await?;
+                let voter_ids = stored_voter_ids(&bot, &config.starboard, user_id).await?;
+                Vote::delete_any(&bot.pool, orig.message_id, config.starboard.id, &voter_ids)
+                    .await?;
             }
 
             let is_premim = is_guild_premium(&bot, guild_id.get_i64(), true).await?;
-            let mut refresh = RefreshMessage::new(bot.clone(), event.message_id, is_premim);
-            refresh.set_sql_message(orig);
-            refresh.set_configs(configs.into_iter().map(Arc::new).collect());
-            refresh.refresh(false).await?;
+            let mut new = RefreshMessage::new(bot.clone(), event.message_id, is_premim);
+            new.set_sql_message(orig);
+            new.set_configs(configs.into_iter().map(Arc::new).collect());
+            refresh = Some(new);
         }
         VoteStatus::Ignore | VoteStatus::Remove => (),
     }
 
-    refresh_xp(&bot, guild_id, author.user_id.into_id()).await?;
+    spawn_refresh(bot, refresh, guild_id, author.user_id.into_id());
 
     Ok(())
 }
+
+/// Refreshes the message and the author's XP in the background. The vote is
+/// already written (or batched) by now, so this doesn't need the event
+/// ordering, and its Discord calls would otherwise hold up the message's
+/// queue. Refreshes that overlap are merged by `RefreshMessage::refresh`.
+fn spawn_refresh(
+    bot: Arc<StarboardBot>,
+    refresh: Option<RefreshMessage>,
+    guild_id: Id<GuildMarker>,
+    author_id: Id<UserMarker>,
+) {
+    let task = async move {
+        if let Some(mut refresh) = refresh {
+            if let Err(why) = refresh.refresh(false).await {
+                bot.handle_error(&why).await;
+            }
+        }
+        if let Err(why) = refresh_xp(&bot, guild_id, author_id).await {
+            bot.handle_error(&why).await;
+        }
+    };
+    tokio::spawn(task.in_current_span());
+}
@@ -1,19 +1,29 @@
-use std::{sync::Arc, time::Duration};
+use std::{collections::HashMap, sync::Arc, time::Duration};
 
-use twilight_model::id::{
-    marker::{ChannelMarker, GuildMarker, MessageMarker},
-    Id,
+use twilight_model::{
+    id::{
+        marker::{ChannelMarker, GuildMarker, MessageMarker},
+        Id,
+    },
+    user::User,
 };
 
 use crate::{
     client::bot::StarboardBot,
-    core::{emoji::SimpleEmoji, premium::is_premium::is_guild_premium},
-    database::{DbMember, DbMessage, DbUser, Vote},
+    constants,
+    core::{
+        blocked_voters::{get_blocked_voters, BlockedVoters},
+        emoji::{EmojiCommon, SimpleEmoji},
+        premium::is_premium::is_guild_premium,
+        vote_weights::emoji_weight,
+    },
+    database::{models::vote::NewVote, DbMember, DbMessage, DbUser, Vote},
     errors::StarboardResult,
-    utils::{id_as_i64::GetI64, into_id::IntoId},
+    utils::{get_status::get_status, id_as_i64::GetI64, into_id::IntoId},
 };
 
 use super::{
+    anonymous::stored_voter_id,
     config::StarboardConfig,
     handle::RefreshMessage,
     message::get_or_create_original,
@@ -24,26 +34,42 @@ pub enum RecountResult {
     Cooldown(Duration),
     AlreadyRecounting,
     UnkownMessage,
-    Done,
+    Done(Vec<RecountedStarboard>),
 }
 
+/// The points a message had on a starboard before and after recounting.
+pub struct RecountedStarboard {
+    pub name: String,
+    pub before: i32,
+    pub after: i32,
+}
+
+/// Rebuilds the votes on a message from the reactions on the original, so
+/// that votes that were missed (e.g. during downtime) or whose reactions were
+/// removed are corrected. The requirements of each starboard are checked
+/// again for every reaction.
 pub async fn recount_votes(
     bot: Arc<StarboardBot>,
     guild_id: Id<GuildMarker>,
     channel_id: Id<ChannelMarker>,
     message_id: Id<MessageMarker>,
 ) -> StarboardResult<RecountResult> {
-    let Some(_guard) = bot.locks.vote_recount.lock(message_id) else {
+    let orig = get_or_create_original(&bot, guild_id, channel_id, message_id).await?;
+    let (Some(orig), author_is_bot) = orig else {
+        return Ok(RecountResult::UnkownMessage);
+    };
+
+    let Some(_guard) = bot.locks.vote_recount.lock(orig.message_id.into_id()) else {
         return Ok(RecountResult::AlreadyRecounting);
     };
-    if let Some(retry) = bot.cooldowns.vote_recount.trigger(&guild_id) {
+    if let Some(retry) = bot
+        .cooldowns
+        .vote_recount
+        .trigger(&orig.channel_id.into_id())
+    {
         return Ok(RecountResult::Cooldown(retry));
     }
 
-    let orig = get_or_create_original(&bot, guild_id, channel_id, message_id).await?;
-    let (Some(orig), author_is_bot) = orig else {
-        return Ok(RecountResult::UnkownMessage);
-    };
     let author_is_bot = match author_is_bot {
         Some(val) => val,
         None => {
@@ -56,67 +82,117 @@ pub async fn recount_votes(
 
     let configs =
         StarboardConfig::list_for_channel(&bot, guild_id, orig.channel_id.into_id()).await?;
+    let configs: Vec<_> = configs
+        .into_iter()
+        .filter(|c| !c.starboard.channel_deleted)
+        .collect();
+    let starboard_ids: Vec<_> = configs.iter().map(|c| c.starboard.id).collect();
 
     let guild_id_i64 = guild_id.get_i64();
 
     let orig_obj = bot
         .http
-        .message(channel_id, message_id)
-        .await?
-        .model()
-        .await?;
+        .message(orig.channel_id.into_id(), orig.message_id.into_id())
+        .await;
+    let orig_obj = match orig_obj {
+        Ok(msg) => msg.model().await?,
+        Err(why) => {
+            if matches!(get_status(&why), Some(403 | 404)) {
+                return Ok(RecountResult::UnkownMessage);
+            }
+            return Err(why.into());
+        }
+    };
+
+    let mut votes = Vec::new();
+    let mut voted = HashMap::new();
     for reaction in orig_obj.reactions {
         let emoji = SimpleEmoji::from(reaction.emoji);
 
         let is_vote = StarboardConfig::is_guild_vote_emoji(&bot, guild_id_i64, &emoji).await?;
 
         if is_vote {
-            recount_votes_reaction(
+            collect_reaction_votes(
                 &bot,
-                (channel_id, message_id),
                 &orig,
                 &configs,
                 guild_id,
                 author_is_bot,
                 emoji,
+                (&mut votes, &mut voted),
             )
             .await?;
         }
     }
 
+    // waiting votes would otherwise be written on top of the recount
+    bot.vote_batcher.flush_message(&bot, orig.message_id).await;
+    let mut before = Vec::with_capacity(configs.len());
+    for config in &configs {
+        before.push(Vote::count(&bot.pool, orig.message_id, config.starboard.id).await?);
+    }
+
+    Vote::replace_for_message(
+        &bot.pool,
+        orig.message_id,
+        orig.author_id,
+        &starboard_ids,
+        &votes,
+    )
+    .await?;
+
+    let mut recounted = Vec::with_capacity(configs.len());
+    for (config, before) in configs.iter().zip(before) {
+        let after = Vote::count(&bot.pool, orig.message_id, config.starboard.id).await?;
+        recounted.push(RecountedStarboard {
+            name: config.starboard.name.clone(),
+            before: before.points,
+            after: after.points,
+        });
+    }
+
     let is_premium = is_guild_premium(&bot, guild_id_i64, true).await?;
-    let mut refresh = RefreshMessage::new(bot.clone(), message_id, is_premium);
+    let mut refresh = RefreshMessage::new(bot.clone(), orig.message_id.into_id(), is_premium);
     refresh.set_sql_message(orig);
     refresh.refresh(false).await?;
 
-    Ok(RecountResult::Done)
+    Ok(RecountResult::Done(recounted))
 }
 
-async fn recount_votes_reaction(
+async fn collect_reaction_votes(
     bot: &StarboardBot,
-    refreshing: (Id<ChannelMarker>, Id<MessageMarker>),
     orig: &DbMessage,
     configs: &[StarboardConfig],
     guild_id: Id<GuildMarker>,
     author_is_bot: bool,
     emoji: SimpleEmoji,
+    (votes, voted): (&mut Vec<NewVote>, &mut HashMap<(i32, i64), usize>),
 ) -> StarboardResult<()> {
+    let blocked = get_blocked_voters(bot, guild_id.get_i64()).await?;
     let mut last_user = None;
     let reactable = emoji.reactable();
     loop {
-        let mut reactions = bot.http.reactions(refreshing.0, refreshing.1, &reactable);
+        let mut reactions = bot
+            .http
+            .reactions(
+                orig.channel_id.into_id(),
+                orig.message_id.into_id(),
+                &reactable,
+            )
+            .limit(constants::RECOUNT_PAGE_SIZE)?;
         if let Some(last_user) = last_user {
             reactions = reactions.after(last_user);
         }
         let reactions = reactions.await?.model().await?;
 
-        if reactions.is_empty() {
+        let Some(last) = reactions.last() else {
             break;
-        }
-        last_user = Some(reactions.last().unwrap().id);
+        };
+        last_user = Some(last.id);
+        let is_last_page = reactions.len() < constants::RECOUNT_PAGE_SIZE as usize;
 
         for user in reactions {
-            if user.bot {
+            if !is_counted(&user, &blocked) {
                 continue;
             }
 
@@ -133,36 +209,81 @@ async fn recount_votes_reaction(
             let status = VoteStatus::get_vote_status(bot, vote, configs).await?;
 
             let VoteStatus::Valid((upvotes, downvotes)) = status else {
-            continue;
-        };
+                continue;
+            };
 
             let user_id = user.id.get_i64();
             DbUser::create(&bot.pool, user_id, user.bot).await?;
             DbMember::create(&bot.pool, user_id, guild_id.get_i64()).await?;
 
-            for config in &upvotes {
-                Vote::create(
-                    &bot.pool,
-                    orig.message_id,
-                    config.starboard.id,
-                    user_id,
-                    orig.author_id,
-                    false,
-                )
-                .await?;
-            }
-            for config in &downvotes {
-                Vote::create(
-                    &bot.pool,
-                    orig.message_id,
-                    config.starboard.id,
-                    user_id,
-                    orig.author_id,
-                    true,
-                )
-                .await?;
+            for (configs, is_downvote) in [(upvotes, false), (downvotes, true)] {
+                for config in configs {
+                    let weight = emoji_weight(&config.resolved.vote_emoji_weights, &emoji);
+
+                    // a user can react with several vote emojis, but only
+                    // gets one vote per starboard, worth the heaviest emoji.
+                    // The first emoji's direction wins.
+                    if let Some(&idx) = voted.get(&(config.starboard.id, user_id)) {
+                        let vote = &mut votes[idx];
+                        if vote.is_downvote == is_downvote && weight > vote.weight {
+                            vote.weight = weight;
+                            vote.emoji = emoji.clone().into_stored();
+                        }
+                        continue;
+                    }
+                    voted.insert((config.starboard.id, user_id), votes.len());
+                    votes.push(NewVote {
+                        starboard_id: config.starboard.id,
+                        voter_id: stored_voter_id(bot, &config.starboard, user_id).await?,
+                        is_downvote,
+                        weight,
+                        emoji: emoji.clone().into_stored(),
+                    });
+                }
             }
         }
+
+        if is_last_page {
+            break;
+        }
     }
     Ok(())
 }
+
+/// Bots and blocked users never get a vote from a recount, even if they
+/// voted before they were blocked.
+fn is_counted(user: &User, blocked: &BlockedVoters) -> bool {
+    !user.bot && !blocked.contains_key(&user.id.get_i64())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn user(id: u64, bot: bool) -> User {
+        serde_json::from_value(serde_json::json!({
+            "id": id.to_string(),
+            "username": "user",
+            "discriminator": "0",
+            "avatar": null,
+            "bot": bot,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn counts_users() {
+        let blocked = BlockedVoters::from([(2, false)]);
+
+        assert!(is_counted(&user(1, false), &blocked));
+    }
+
+    #[test]
+    fn skips_bots_and_blocked_users() {
+        let blocked = BlockedVoters::from([(2, false), (3, true)]);
+
+        assert!(!is_counted(&user(1, true), &blocked));
+        assert!(!is_counted(&user(2, false), &blocked));
+        assert!(!is_counted(&user(3, false), &blocked));
+    }
+}
@@ -0,0 +1,71 @@
+use std::{
+    fmt,
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+/// Counts the work done by refreshes since the bot started, so that the
+/// queries per refresh can be compared between versions.
+#[derive(Default)]
+pub struct RefreshStats {
+    /// Refreshes that got past the lock and were planned.
+    pub refreshes: AtomicU64,
+    /// Times the original message was loaded from the cache or Discord.
+    pub context_loads: AtomicU64,
+    /// Starboards that a refresh decided on.
+    pub starboards: AtomicU64,
+    /// Points queries.
+    pub count_queries: AtomicU64,
+}
+
+impl fmt::Display for RefreshStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let refreshes = self.refreshes.load(Ordering::Relaxed);
+        let per_refresh = |value: &AtomicU64| {
+            let value = value.load(Ordering::Relaxed);
+            let avg = if refreshes == 0 {
+                0.0
+            } else {
+                value as f64 / refreshes as f64
+            };
+            format!("{value} ({avg:.2} per refresh)")
+        };
+
+        writeln!(f, "refreshes: {refreshes}")?;
+        writeln!(f, "message loads: {}", per_refresh(&self.context_loads))?;
+        writeln!(f, "starboards: {}", per_refresh(&self.starboards))?;
+        writeln!(f, "points queries: {}", per_refresh(&self.count_queries))?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn averages_per_refresh() {
+        let stats = RefreshStats::default();
+        stats.refreshes.store(4, Ordering::Relaxed);
+        stats.context_loads.store(4, Ordering::Relaxed);
+        stats.starboards.store(10, Ordering::Relaxed);
+        stats.count_queries.store(10, Ordering::Relaxed);
+
+        assert_eq!(
+            stats.to_string(),
+            "refreshes: 4\n\
+            message loads: 4 (1.00 per refresh)\n\
+            starboards: 10 (2.50 per refresh)\n\
+            points queries: 10 (2.50 per refresh)\n"
+        );
+    }
+
+    #[test]
+    fn no_refreshes_yet() {
+        let stats = RefreshStats::default();
+
+        assert!(stats
+            .to_string()
+            .contains("points queries: 0 (0.00 per refresh)"));
+    }
+}
@@ -0,0 +1,129 @@
+//! Enabling and disabling starboards at a scheduled time. A disabled
+//! starboard keeps recording votes, so once it's enabled again the messages
+//! that qualified in the meantime are sent.
+
+use std::sync::Arc;
+
+use tracing::Instrument;
+
+use crate::{
+    client::bot::StarboardBot,
+    constants,
+    core::{guild_log::LogEntry, premium::is_premium::is_guild_premium},
+    database::{DbMessage, Starboard, StarboardOverride},
+    errors::StarboardResult,
+    utils::into_id::IntoId,
+};
+
+use super::handle::RefreshMessage;
+
+pub async fn loop_starboard_schedules(bot: Arc<StarboardBot>) {
+    loop {
+        tokio::time::sleep(constants::STARBOARD_SCHEDULE_DELAY).await;
+
+        let due = match Starboard::list_schedule_due(&bot.pool).await {
+            Ok(due) => due,
+            Err(why) => {
+                bot.handle_error(&why.into()).await;
+                continue;
+            }
+        };
+
+        for starboard in due {
+            if !bot.owns_guild(starboard.guild_id as u64) {
+                continue;
+            }
+            if let Err(why) = apply_schedule(&bot, starboard).await {
+                bot.handle_error(&why).await;
+            }
+        }
+    }
+}
+
+async fn apply_schedule(bot: &Arc<StarboardBot>, starboard: Starboard) -> StarboardResult<()> {
+    let now = chrono::Utc::now();
+    let enable_at = starboard.enable_at.filter(|at| *at <= now);
+    let disable_at = starboard.disable_at.filter(|at| *at <= now);
+
+    // if both have passed, the later one wins
+    let enabled = match (enable_at, disable_at) {
+        (Some(enable_at), Some(disable_at)) => enable_at > disable_at,
+        (Some(_), None) => true,
+        (None, Some(_)) => false,
+        (None, None) => return Ok(()),
+    };
+
+    Starboard::finish_schedule(&bot.pool, starboard.id, enabled).await?;
+    if enabled == starboard.settings.enabled {
+        return Ok(());
+    }
+
+    let entry = LogEntry::StarboardScheduled {
+        starboard: starboard.name.clone(),
+        enabled,
+    };
+    bot.guild_log.log(bot, starboard.guild_id.into_id(), entry);
+    if enabled {
+        spawn_enable_refresh(bot.clone(), starboard);
+    }
+
+    Ok(())
+}
+
+/// Sends the messages that qualified for a starboard while it was disabled.
+pub fn spawn_enable_refresh(bot: Arc<StarboardBot>, starboard: Starboard) {
+    let task = async move {
+        if let Err(why) = refresh_unposted(&bot, &starboard).await {
+            bot.handle_error(&why).await;
+        }
+    };
+    tokio::spawn(task.in_current_span());
+}
+
+async fn refresh_unposted(bot: &Arc<StarboardBot>, starboard: &Starboard) -> StarboardResult<()> {
+    // overrides can lower the requirement, the refresh checks the rest
+    let mut required = starboard.settings.required;
+    for ov in StarboardOverride::list_by_starboard(&bot.pool, starboard.id).await? {
+        let Ok(values) = ov.get_overrides() else {
+            continue;
+        };
+        if let Some(Some(ov_required)) = values.required {
+            required = Some(required.map_or(ov_required, |r| r.min(ov_required)));
+        }
+    }
+    let Some(required) = required else {
+        return Ok(());
+    };
+
+    // votes that are still waiting to be written count as well
+    bot.vote_batcher.flush(bot).await;
+    let messages = DbMessage::list_unposted(
+        &bot.pool,
+        starboard.id,
+        required as i64,
+        constants::ENABLE_REFRESH_LIMIT,
+    )
+    .await?;
+    if messages.is_empty() {
+        return Ok(());
+    }
+
+    tracing::info!(
+        "Refreshing {} messages after starboard {} was enabled.",
+        messages.len(),
+        starboard.id
+    );
+
+    let is_premium = is_guild_premium(bot, starboard.guild_id, true).await?;
+    for orig in messages {
+        let mut refresh = RefreshMessage::new(bot.clone(), orig.message_id.into_id(), is_premium);
+        refresh.set_sql_message(orig);
+        if let Err(why) = refresh.refresh(true).await {
+            bot.handle_error(&why).await;
+        }
+
+        tokio::time::sleep(constants::ENABLE_REFRESH_DELAY).await;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,346 @@
+//! Dry-run of the vote pipeline, for explaining what a vote would do without
+//! writing to the database or touching Discord.
+
+use chrono::Utc;
+use twilight_model::id::{
+    marker::{ChannelMarker, GuildMarker, MessageMarker, UserMarker},
+    Id,
+};
+
+use crate::{
+    client::bot::StarboardBot,
+    core::{
+        blocked_voters::blocked_voter,
+        emoji::SimpleEmoji,
+        premium::is_premium::is_guild_premium,
+        vote_weights::{clamp_vote, emoji_weight, vote_points},
+    },
+    database::{
+        models::vote::{VoteCount, VoteSpread},
+        DbMessage, StarboardMessage, Vote,
+    },
+    errors::StarboardResult,
+    utils::{id_as_i64::GetI64, into_id::IntoId},
+};
+
+use super::{
+    anonymous::stored_voter_id,
+    config::StarboardConfig,
+    msg_status::{
+        apply_author_limit, get_message_status, get_vote_spread, has_unique_voters, MessageStatus,
+    },
+    vote_status::{VoteContext, VoteStatus},
+};
+
+/// Collects every decision made while simulating a vote.
+#[derive(Default)]
+pub struct SimulationContext {
+    pub steps: Vec<String>,
+}
+
+impl SimulationContext {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn step(&mut self, step: impl Into<String>) {
+        self.steps.push(step.into());
+    }
+}
+
+/// Records a step, only building it if a simulation is running.
+pub fn trace(sim: &mut Option<&mut SimulationContext>, step: impl FnOnce() -> String) {
+    if let Some(sim) = sim {
+        sim.step(step());
+    }
+}
+
+/// Walks through what would happen if `reactor_id` reacted to a message with
+/// `emoji`. Exclusive groups and cooldown consumption are not applied.
+pub async fn simulate_vote(
+    bot: &StarboardBot,
+    guild_id: Id<GuildMarker>,
+    channel_id: Id<ChannelMarker>,
+    message_id: Id<MessageMarker>,
+    reactor_id: Id<UserMarker>,
+    emoji: &SimpleEmoji,
+) -> StarboardResult<SimulationContext> {
+    let mut sim = SimulationContext::new();
+
+    if !StarboardConfig::is_guild_vote_emoji(bot, guild_id.get_i64(), emoji).await? {
+        sim.step("The emoji isn't used by any starboard, so the reaction is ignored.");
+        return Ok(sim);
+    }
+    sim.step("The emoji is used by at least one starboard.");
+
+    if let Some(remove) = blocked_voter(bot, guild_id.get_i64(), reactor_id.get_i64()).await? {
+        sim.step(if remove {
+            "The user is on the blocklist, so the reaction would be removed."
+        } else {
+            "The user is on the blocklist, so the reaction is ignored."
+        });
+        return Ok(sim);
+    }
+
+    let orig_message = bot.cache.fog_message(bot, channel_id, message_id).await?;
+    let sql_message = match DbMessage::get_original(&bot.pool, message_id.get_i64()).await? {
+        Some(sql_message) => {
+            sim.step("The message is already known to Starboard.");
+            sql_message
+        }
+        None => {
+            let Some(msg) = orig_message.as_option() else {
+                sim.step("I couldn't fetch the message, so the reaction is ignored.");
+                return Ok(sim);
+            };
+            sim.step("The message is new to Starboard and would be saved.");
+
+            let is_nsfw = bot
+                .cache
+                .fog_channel_nsfw(bot, guild_id, channel_id)
+                .await?
+                .unwrap_or(false);
+            DbMessage {
+                message_id: message_id.get_i64(),
+                guild_id: guild_id.get_i64(),
+                channel_id: channel_id.get_i64(),
+                author_id: msg.author_id.get_i64(),
+                is_nsfw,
+                forced_to: Vec::new(),
+                trashed: false,
+                trash_reason: None,
+                frozen: false,
+            }
+        }
+    };
+
+    let author_is_bot = bot
+        .cache
+        .fog_user(bot, sql_message.author_id.into_id())
+        .await?
+        .is_some_and(|user| user.is_bot);
+
+    let configs =
+        StarboardConfig::list_for_channel(bot, guild_id, sql_message.channel_id.into_id()).await?;
+    let vote = VoteContext {
+        emoji,
+        reactor_id,
+        message_id: sql_message.message_id.into_id(),
+        channel_id: sql_message.channel_id.into_id(),
+        message_author_id: sql_message.author_id.into_id(),
+        message_author_is_bot: author_is_bot,
+        message_has_image: None,
+        message_is_frozen: sql_message.frozen,
+    };
+    let status = VoteStatus::simulate_vote_status(bot, vote, &configs, &mut sim).await?;
+
+    let VoteStatus::Valid((upvote, downvote)) = status else {
+        return Ok(sim);
+    };
+
+    let is_premium = is_guild_premium(bot, guild_id.get_i64(), true).await?;
+    let votes = upvote
+        .into_iter()
+        .map(|config| (config, false))
+        .chain(downvote.into_iter().map(|config| (config, true)));
+
+    for (config, is_downvote) in votes {
+        let count = Vote::count(&bot.pool, sql_message.message_id, config.starboard.id).await?;
+        let points = count.points;
+        let voter_id = stored_voter_id(bot, &config.starboard, reactor_id.get_i64()).await?;
+        let existing = Vote::get(
+            &bot.pool,
+            sql_message.message_id,
+            config.starboard.id,
+            voter_id,
+        )
+        .await?;
+        let is_new_upvote = !is_downvote && existing.as_ref().map(|v| v.is_downvote) != Some(false);
+        let weight = emoji_weight(&config.resolved.vote_emoji_weights, emoji);
+        let existing_vote = existing.as_ref().map(|v| (v.is_downvote, v.weight));
+        let new_count = count_after_vote(count, existing_vote, (is_downvote, weight));
+        let new_points = new_count.points;
+
+        let mut vote_spread = get_vote_spread(bot, config, sql_message.message_id).await?;
+        if is_new_upvote && config.resolved.min_vote_spread_seconds.is_some() {
+            vote_spread = Some(match vote_spread {
+                Some(spread) => VoteSpread {
+                    upvotes: spread.upvotes + 1,
+                    ..spread
+                },
+                None => VoteSpread {
+                    upvotes: 1,
+                    first: Utc::now(),
+                },
+            });
+        }
+
+        let on_starboard = StarboardMessage::get_by_starboard(
+            &bot.pool,
+            sql_message.message_id,
+            config.starboard.id,
+        )
+        .await?
+        .is_some();
+        let status = get_message_status(
+            bot,
+            config,
+            &sql_message,
+            &orig_message,
+            new_count,
+            vote_spread,
+            false,
+            is_premium,
+        )
+        .await?;
+        let status = apply_author_limit(bot, config, &sql_message, status, on_starboard).await?;
+
+        let spread_action;
+        let action = match (status, on_starboard) {
+            (MessageStatus::Send(_), false) => "would be sent to the starboard",
+            (MessageStatus::Send(_) | MessageStatus::Update(_), true) => {
+                "would be updated on the starboard"
+            }
+            (MessageStatus::Update(_), false) => "would stay off the starboard",
+            (MessageStatus::Remove, true) => "would be removed from the starboard",
+            (MessageStatus::Remove, false) => "would stay off the starboard",
+            (MessageStatus::Trashed, true) => "is trashed, so its post would stay a placeholder",
+            (MessageStatus::Trashed, false) => "is trashed, so it would stay off the starboard",
+            (MessageStatus::AwaitingVoteSpread(_), true) => "would be updated on the starboard",
+            (MessageStatus::AwaitingVoteSpread(ready_at), false) => {
+                spread_action = format!(
+                    "would be sent to the starboard <t:{}:R>, once its votes are spread \
+                    out enough (min-vote-spread)",
+                    ready_at.timestamp()
+                );
+                &spread_action
+            }
+            (MessageStatus::AwaitingAuthorLimit(ready_at), _) => {
+                spread_action = format!(
+                    "would be sent to the starboard <t:{}:R>, once its author is back under \
+                    max-posts-per-author-per-day",
+                    ready_at.timestamp()
+                );
+                &spread_action
+            }
+            (MessageStatus::NoAction, _) => {
+                "can't be shown on a starboard, so nothing would change"
+            }
+        };
+
+        sim.step(format!(
+            "'{}': points {points} -> {new_points}, the message {action}.",
+            config.starboard.name
+        ));
+        if let Some(min) = config.resolved.min_unique_voters {
+            if !has_unique_voters(config, new_count) {
+                sim.step(format!(
+                    "'{}': only {} of the {min} different upvoters it needs (min-unique-voters).",
+                    config.starboard.name, new_count.unique_voters
+                ));
+            }
+        }
+    }
+
+    Ok(sim)
+}
+
+/// The count of a message after a user votes with `vote`, given the vote
+/// they already had. Both are (is_downvote, weight).
+fn count_after_vote(
+    count: VoteCount,
+    existing: Option<(bool, i16)>,
+    vote: (bool, i16),
+) -> VoteCount {
+    let (new_is_downvote, new_weight) = clamp_vote(existing, vote);
+    let was_upvote = existing.map(|(is_downvote, _)| is_downvote) == Some(false);
+
+    let (mut upvotes, mut downvotes) = (count.upvotes, count.downvotes);
+    match existing {
+        Some((true, weight)) => downvotes -= weight as i32,
+        Some((false, weight)) => upvotes -= weight as i32,
+        None => {}
+    }
+    if new_is_downvote {
+        downvotes += new_weight as i32;
+    } else {
+        upvotes += new_weight as i32;
+    }
+
+    VoteCount {
+        points: count.points - existing.map_or(0, |(d, w)| vote_points(d, w))
+            + vote_points(new_is_downvote, new_weight),
+        unique_voters: match (was_upvote, new_is_downvote) {
+            (false, false) => count.unique_voters + 1,
+            (true, true) => count.unique_voters - 1,
+            _ => count.unique_voters,
+        },
+        upvotes,
+        downvotes,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn count(upvotes: i32, downvotes: i32, unique_voters: i32) -> VoteCount {
+        VoteCount {
+            points: upvotes - downvotes,
+            unique_voters,
+            upvotes,
+            downvotes,
+        }
+    }
+
+    #[test]
+    fn new_votes() {
+        assert_eq!(
+            count_after_vote(count(3, 0, 3), None, (false, 1)),
+            count(4, 0, 4)
+        );
+        assert_eq!(
+            count_after_vote(count(3, 0, 3), None, (false, 3)),
+            count(6, 0, 4)
+        );
+        assert_eq!(
+            count_after_vote(count(3, 0, 3), None, (true, 2)),
+            count(3, 2, 3)
+        );
+    }
+
+    #[test]
+    fn stacked_votes_are_clamped() {
+        // voting again in the same direction keeps the heaviest weight
+        assert_eq!(
+            count_after_vote(count(3, 0, 1), Some((false, 3)), (false, 1)),
+            count(3, 0, 1)
+        );
+        assert_eq!(
+            count_after_vote(count(1, 0, 1), Some((false, 1)), (false, 3)),
+            count(3, 0, 1)
+        );
+    }
+
+    #[test]
+    fn flipped_votes() {
+        // an upvote turning into a downvote loses its upvoter
+        assert_eq!(
+            count_after_vote(count(2, 0, 1), Some((false, 2)), (true, 1)),
+            count(0, 1, 0)
+        );
+        assert_eq!(
+            count_after_vote(count(0, 1, 0), Some((true, 1)), (false, 2)),
+            count(2, 0, 1)
+        );
+    }
+
+    #[test]
+    fn traces_only_while_simulating() {
+        let mut sim = SimulationContext::new();
+        trace(&mut Some(&mut sim), || "step".to_string());
+        assert_eq!(sim.steps, vec!["step".to_string()]);
+
+        trace(&mut None, || panic!("built a step without a simulation"));
+    }
+}
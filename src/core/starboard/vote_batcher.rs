@@ -0,0 +1,245 @@
+//! Writes votes from reactions in batches, instead of one INSERT per vote.
+//!
+//! Votes wait in memory for up to `VOTE_BATCH_DELAY`, or until
+//! `VOTE_BATCH_SIZE` are waiting. Anything that needs the exact votes on a
+//! message (refreshes, recounts, removing or toggling a vote) calls
+//! `flush_message` first.
+
+use std::{
+    collections::HashMap,
+    fmt,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+};
+
+use tokio::sync::Notify;
+
+use crate::{
+    client::bot::StarboardBot,
+    constants,
+    core::vote_weights::{clamp_vote, keeps_emoji},
+    database::{models::vote::BufferedVote, Vote},
+};
+
+/// (message, starboard, user)
+type VoteKey = (i64, i32, i64);
+
+#[derive(Default)]
+pub struct VoteBatcher {
+    /// Later votes by the same user are merged into earlier ones, since a
+    /// batch can only change each row once.
+    buffer: Mutex<HashMap<VoteKey, BufferedVote>>,
+    /// Held while a batch is written, so that a flush only returns once
+    /// every vote taken before it is in the database.
+    writing: tokio::sync::Mutex<()>,
+    full: Notify,
+
+    buffered: AtomicU64,
+    flushes: AtomicU64,
+    conflicts: AtomicU64,
+}
+
+impl VoteBatcher {
+    pub fn push(&self, vote: BufferedVote) {
+        let len = {
+            let mut buffer = self.buffer.lock().unwrap();
+            let key = (vote.message_id, vote.starboard_id, vote.voter_id);
+            let existing = buffer.get(&key).map(|v| (v.is_downvote, v.weight));
+            let new = (vote.is_downvote, vote.weight);
+            let (is_downvote, weight) = clamp_vote(existing, new);
+            let emoji = match buffer.remove(&key) {
+                Some(old) if keeps_emoji(existing, new) => old.emoji,
+                _ => vote.emoji,
+            };
+            buffer.insert(
+                key,
+                BufferedVote {
+                    is_downvote,
+                    weight,
+                    emoji,
+                    ..vote
+                },
+            );
+            buffer.len()
+        };
+        self.buffered.fetch_add(1, Ordering::Relaxed);
+
+        if len >= constants::VOTE_BATCH_SIZE {
+            self.full.notify_one();
+        }
+    }
+
+    /// Writes every waiting vote.
+    pub async fn flush(&self, bot: &StarboardBot) {
+        let _writing = self.writing.lock().await;
+        let votes: Vec<_> = std::mem::take(&mut *self.buffer.lock().unwrap())
+            .into_values()
+            .collect();
+        if votes.is_empty() {
+            return;
+        }
+
+        self.flushes.fetch_add(1, Ordering::Relaxed);
+        match Vote::upsert_batch(&bot.pool, &votes).await {
+            Ok(conflicts) => {
+                self.conflicts.fetch_add(conflicts, Ordering::Relaxed);
+            }
+            // one bad vote (for example for a starboard that was just
+            // deleted) shouldn't lose the others
+            Err(why) => {
+                tracing::warn!("Writing {} votes at once failed: {why}", votes.len());
+                for vote in votes {
+                    if let Err(why) = Vote::create(
+                        &bot.pool,
+                        vote.message_id,
+                        vote.starboard_id,
+                        vote.voter_id,
+                        vote.target_author_id,
+                        vote.is_downvote,
+                        vote.weight,
+                        Some(&vote.emoji),
+                    )
+                    .await
+                    {
+                        bot.handle_error(&why.into()).await;
+                    }
+                }
+            }
+        }
+    }
+
+    /// Writes the waiting votes if any of them are on `message_id`. Other
+    /// messages' votes are written along with them.
+    pub async fn flush_message(&self, bot: &StarboardBot, message_id: i64) {
+        let waiting = self
+            .buffer
+            .lock()
+            .unwrap()
+            .keys()
+            .any(|(msg, _, _)| *msg == message_id);
+
+        if waiting {
+            self.flush(bot).await;
+        } else {
+            // a flush that already took this message's votes might still be
+            // writing them
+            let _writing = self.writing.lock().await;
+        }
+    }
+
+    pub fn stats(&self) -> VoteBatcherStats {
+        VoteBatcherStats {
+            waiting: self.buffer.lock().unwrap().len(),
+            buffered: self.buffered.load(Ordering::Relaxed),
+            flushes: self.flushes.load(Ordering::Relaxed),
+            conflicts: self.conflicts.load(Ordering::Relaxed),
+        }
+    }
+}
+
+pub async fn loop_flush_votes(bot: Arc<StarboardBot>) {
+    loop {
+        tokio::select! {
+            _ = tokio::time::sleep(constants::VOTE_BATCH_DELAY) => {},
+            _ = bot.vote_batcher.full.notified() => {},
+        }
+
+        bot.vote_batcher.flush(&bot).await;
+        bot.emoji_usage.flush(&bot).await;
+    }
+}
+
+pub struct VoteBatcherStats {
+    pub waiting: usize,
+    pub buffered: u64,
+    pub flushes: u64,
+    pub conflicts: u64,
+}
+
+impl fmt::Display for VoteBatcherStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "waiting: {}", self.waiting)?;
+        writeln!(f, "buffered: {}", self.buffered)?;
+        writeln!(f, "flushes: {}", self.flushes)?;
+        writeln!(f, "conflicts: {}", self.conflicts)?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vote(voter_id: i64, is_downvote: bool, weight: i16) -> BufferedVote {
+        BufferedVote {
+            message_id: 1,
+            starboard_id: 2,
+            voter_id,
+            target_author_id: 3,
+            is_downvote,
+            weight,
+            emoji: if weight > 1 { "💎" } else { "⭐" }.to_string(),
+        }
+    }
+
+    fn waiting(batcher: &VoteBatcher, user_id: i64) -> (bool, i16, String) {
+        let buffer = batcher.buffer.lock().unwrap();
+        let vote = &buffer[&(1, 2, user_id)];
+        (vote.is_downvote, vote.weight, vote.emoji.clone())
+    }
+
+    #[test]
+    fn merges_same_user() {
+        let batcher = VoteBatcher::default();
+        batcher.push(vote(10, false, 3));
+        batcher.push(vote(10, false, 1));
+        batcher.push(vote(11, false, 1));
+
+        let stats = batcher.stats();
+        assert_eq!(stats.waiting, 2);
+        assert_eq!(stats.buffered, 3);
+        // the heaviest emoji is kept along with its weight
+        assert_eq!(waiting(&batcher, 10), (false, 3, "💎".to_string()));
+    }
+
+    #[test]
+    fn other_direction_replaces() {
+        let batcher = VoteBatcher::default();
+        batcher.push(vote(10, false, 3));
+        batcher.push(vote(10, true, 1));
+
+        assert_eq!(batcher.stats().waiting, 1);
+        assert_eq!(waiting(&batcher, 10), (true, 1, "⭐".to_string()));
+    }
+
+    #[tokio::test]
+    async fn notifies_when_full() {
+        let batcher = VoteBatcher::default();
+        for user_id in 0..constants::VOTE_BATCH_SIZE as i64 {
+            batcher.push(vote(user_id, false, 1));
+        }
+
+        // the stored permit wakes the next waiter right away
+        tokio::time::timeout(std::time::Duration::from_secs(1), batcher.full.notified())
+            .await
+            .unwrap();
+    }
+
+    #[test]
+    fn stats_display() {
+        let stats = VoteBatcherStats {
+            waiting: 1,
+            buffered: 2,
+            flushes: 3,
+            conflicts: 4,
+        };
+
+        assert_eq!(
+            stats.to_string(),
+            "waiting: 1\nbuffered: 2\nflushes: 3\nconflicts: 4\n"
+        );
+    }
+}
@@ -0,0 +1,100 @@
+//! Lets moderators remove a user's vote on a message and stop them from
+//! voting on it again ("Remove & lock vote"). Discord sends the same event
+//! whether a reaction was removed by the user or by a moderator, so the lock
+//! has to be explicit.
+
+use std::{collections::HashSet, sync::Arc};
+
+use twilight_model::id::{marker::UserMarker, Id};
+
+use crate::{
+    client::bot::StarboardBot,
+    core::{
+        emoji::{EmojiCommon, SimpleEmoji},
+        premium::is_premium::is_guild_premium,
+        stats::refresh_xp,
+    },
+    database::{DbMessage, DbUser, Vote, VoteLock},
+    errors::StarboardResult,
+    utils::{id_as_i64::GetI64, into_id::IntoId},
+};
+
+use super::{
+    anonymous::stored_voter_ids, config::StarboardConfig, handle::RefreshMessage,
+    invalid_reactions::remove_invalid_reaction,
+};
+
+pub enum LockVoteResult {
+    /// The number of votes that were removed.
+    Locked(usize),
+    AlreadyLocked,
+    UnknownUser,
+}
+
+/// Locks a user's vote on a message, removing their votes and any of their
+/// reactions with vote emojis. `orig` has to be the original message.
+pub async fn lock_vote(
+    bot: Arc<StarboardBot>,
+    orig: DbMessage,
+    user_id: Id<UserMarker>,
+    locked_by: Id<UserMarker>,
+) -> StarboardResult<LockVoteResult> {
+    let Some(user) = bot.cache.fog_user(&bot, user_id).await? else {
+        return Ok(LockVoteResult::UnknownUser);
+    };
+    DbUser::create(&bot.pool, user_id.get_i64(), user.is_bot).await?;
+
+    let lock = VoteLock::create(
+        &bot.pool,
+        orig.message_id,
+        user_id.get_i64(),
+        Some(locked_by.get_i64()),
+    )
+    .await?;
+    if lock.is_none() {
+        return Ok(LockVoteResult::AlreadyLocked);
+    }
+
+    let guild_id = orig.guild_id.into_id();
+    let channel_id = orig.channel_id.into_id();
+    let configs = StarboardConfig::list_for_channel(&bot, guild_id, channel_id).await?;
+
+    // votes still waiting in the batcher would otherwise be written after
+    // they were deleted
+    bot.vote_batcher.flush_message(&bot, orig.message_id).await;
+
+    let mut removed = Vote::delete_by_user(&bot.pool, orig.message_id, user_id.get_i64())
+        .await?
+        .len();
+    for config in &configs {
+        let voter_ids = stored_voter_ids(&bot, &config.starboard, user_id.get_i64()).await?;
+        removed += Vote::delete_any(&bot.pool, orig.message_id, config.starboard.id, &voter_ids)
+            .await?
+            .len();
+    }
+    let emojis: HashSet<_> = configs
+        .iter()
+        .flat_map(|c| {
+            c.resolved
+                .upvote_emojis
+                .iter()
+                .chain(&c.resolved.downvote_emojis)
+        })
+        .cloned()
+        .collect();
+    for emoji in emojis {
+        let emoji = SimpleEmoji::from_stored(emoji);
+        remove_invalid_reaction(&bot, channel_id, orig.message_id.into_id(), &emoji, user_id)
+            .await?;
+    }
+
+    let author_id = orig.author_id.into_id();
+    let is_premium = is_guild_premium(&bot, orig.guild_id, true).await?;
+    let mut refresh = RefreshMessage::new(bot.clone(), orig.message_id.into_id(), is_premium);
+    refresh.set_sql_message(orig);
+    refresh.refresh(false).await?;
+
+    refresh_xp(&bot, guild_id, author_id).await?;
+
+    Ok(LockVoteResult::Locked(removed))
+}
@@ -10,14 +10,26 @@ use twilight_model::id::{
 use crate::{
     client::bot::StarboardBot,
     core::{
-        emoji::SimpleEmoji, filters::FilterEvaluater, has_image::has_image, permroles::Permissions,
+        emoji::SimpleEmoji,
+        filters::FilterEvaluater,
+        has_image::{has_link, message_has_image, text_length},
+        ignored_sources::is_ignored_source,
+        permroles::Permissions,
     },
-    database::models::starboard_filter_group::StarboardFilterGroup,
+    database::{models::starboard_filter_group::StarboardFilterGroup, VoteLock},
     errors::StarboardResult,
-    utils::{into_id::IntoId, snowflake_age::SnowflakeAge},
+    utils::{
+        id_as_i64::GetI64,
+        into_id::IntoId,
+        snowflake_age::SnowflakeAge,
+        system_content::{is_renderable, is_user_message},
+    },
 };
 
-use super::config::StarboardConfig;
+use super::{
+    config::StarboardConfig,
+    simulation::{trace, SimulationContext},
+};
 
 pub struct VoteContext<'a> {
     pub emoji: &'a SimpleEmoji,
@@ -42,21 +54,92 @@ impl<'a> VoteStatus<'a> {
         bot: &StarboardBot,
         vote: VoteContext<'_>,
         configs: &'a [StarboardConfig],
+    ) -> StarboardResult<VoteStatus<'a>> {
+        Self::evaluate(bot, vote, configs, None).await
+    }
+
+    /// Same as `get_vote_status`, but without triggering cooldowns. Every
+    /// decision made is recorded to `sim`.
+    pub async fn simulate_vote_status(
+        bot: &StarboardBot,
+        vote: VoteContext<'_>,
+        configs: &'a [StarboardConfig],
+        sim: &mut SimulationContext,
+    ) -> StarboardResult<VoteStatus<'a>> {
+        Self::evaluate(bot, vote, configs, Some(sim)).await
+    }
+
+    async fn evaluate(
+        bot: &StarboardBot,
+        vote: VoteContext<'_>,
+        configs: &'a [StarboardConfig],
+        mut sim: Option<&mut SimulationContext>,
     ) -> StarboardResult<VoteStatus<'a>> {
         if vote.message_is_frozen {
+            trace(&mut sim, || {
+                "Message is frozen, so the vote is ignored.".into()
+            });
             return Ok(VoteStatus::Ignore);
         }
 
+        if VoteLock::exists(
+            &bot.pool,
+            vote.message_id.get_i64(),
+            vote.reactor_id.get_i64(),
+        )
+        .await?
+        {
+            let remove = configs.iter().all(|c| c.resolved.remove_invalid_reactions);
+            trace(&mut sim, || {
+                "A moderator locked the reactor's vote on this message.".into()
+            });
+            return Ok(if remove {
+                VoteStatus::Remove
+            } else {
+                VoteStatus::Ignore
+            });
+        }
+
+        let message = bot
+            .cache
+            .fog_message(bot, vote.channel_id, vote.message_id)
+            .await?
+            .into_option();
+        if let (Some(msg), Some(config)) = (&message, configs.first()) {
+            if is_ignored_source(bot, config.starboard.guild_id, msg).await? {
+                trace(&mut sim, || {
+                    "Message is from an ignored webhook or app, so the vote is ignored.".into()
+                });
+                return Ok(VoteStatus::Ignore);
+            }
+        }
+
         let message_has_image = match vote.message_has_image {
             Some(val) => Some(val),
-            None => bot
+            None => message.as_ref().map(|msg| message_has_image(msg)),
+        };
+        let message_kind = message.as_ref().map(|msg| msg.kind);
+        let message_length = message.as_ref().map(|msg| text_length(&msg.content));
+        let message_has_link = message.as_ref().map(|msg| has_link(&msg.content));
+
+        let channel_ids: Vec<i64> = match configs.first() {
+            None => Vec::new(),
+            Some(config) => bot
                 .cache
-                .fog_message(bot, vote.channel_id, vote.message_id)
+                .qualified_channel_ids(bot, config.starboard.guild_id.into_id(), vote.channel_id)
                 .await?
-                .into_option()
-                .as_ref()
-                .as_ref()
-                .map(|msg| has_image(&msg.embeds, &msg.attachments)),
+                .into_iter()
+                .map(|id| id.get_i64())
+                .collect(),
+        };
+        // only looked up when needed, since archived posts are fetched
+        let thread_tags = match configs.iter().find(|c| c.uses_tags()) {
+            None => None,
+            Some(config) => {
+                bot.cache
+                    .fog_thread_tags(bot, config.starboard.guild_id.into_id(), vote.channel_id)
+                    .await?
+            }
         };
 
         let mut invalid_exists = false;
@@ -68,10 +151,18 @@ impl<'a> VoteStatus<'a> {
             Downvote,
         }
 
-        let eval_config = |config: &'a StarboardConfig| -> Option<(&'a StarboardConfig, VoteType)> {
-            // skip disabled configurations
-            if !config.resolved.enabled || config.starboard.premium_locked {
-                return None;
+        let mut eval_config = |config: &'a StarboardConfig| -> Result<VoteType, &'static str> {
+            // a disabled starboard still records votes, so that messages
+            // that qualify in the meantime are sent once it's enabled again.
+            // Overrides that disable it in a channel ignore votes there.
+            if !config.resolved.enabled && config.starboard.settings.enabled {
+                return Err("starboard is disabled in this channel");
+            }
+            if config.starboard.premium_locked {
+                return Err("starboard is premium-locked");
+            }
+            if config.starboard.channel_deleted {
+                return Err("starboard channel was deleted");
             }
 
             let vote_type = if config
@@ -89,7 +180,7 @@ impl<'a> VoteStatus<'a> {
             {
                 VoteType::Downvote
             } else {
-                return None;
+                return Err("not an upvote or downvote emoji");
             };
 
             // respect the `remove_invalid_reactions` setting
@@ -110,6 +201,18 @@ impl<'a> VoteStatus<'a> {
 
             let images_valid = !config.resolved.require_image || (message_has_image == Some(true));
 
+            let length_valid = {
+                let min_valid = config.resolved.min_chars <= 0
+                    || message_length.is_some_and(|len| len >= config.resolved.min_chars as usize);
+                let max_valid = match config.resolved.max_chars {
+                    None => true,
+                    Some(max) => message_length.is_some_and(|len| len <= max as usize),
+                };
+                min_valid && max_valid
+            };
+
+            let links_valid = !config.resolved.require_link || (message_has_link == Some(true));
+
             let time_valid = {
                 let min_age_valid = if min_age <= 0 {
                     true
@@ -124,20 +227,65 @@ impl<'a> VoteStatus<'a> {
                 min_age_valid && max_age_valid
             };
 
-            if self_vote_valid && bots_valid && images_valid && time_valid {
-                Some((config, vote_type))
+            let channel_valid = config.is_channel_allowed(&channel_ids);
+            let tags_valid = config.is_tags_allowed(thread_tags.as_deref());
+
+            let kind_valid = match message_kind {
+                None => Ok(()),
+                Some(kind) if !is_renderable(kind) => Err("this message type can't be starboarded"),
+                Some(kind) if !is_user_message(kind) && !config.resolved.allow_system_messages => {
+                    Err("system messages are not allowed")
+                }
+                Some(_) => Ok(()),
+            };
+
+            let invalid_reason = if let Err(reason) = kind_valid {
+                Some(reason)
+            } else if !channel_valid {
+                Some("votes don't count in this channel")
+            } else if !tags_valid {
+                Some("the post doesn't have the required tags")
+            } else if !self_vote_valid {
+                Some("self-votes are not allowed")
+            } else if !bots_valid {
+                Some("votes on messages from bots are not allowed")
+            } else if !images_valid {
+                Some("message has no image")
+            } else if !length_valid {
+                Some("message text is too short or too long")
+            } else if !links_valid {
+                Some("message has no link")
+            } else if !time_valid {
+                Some("message is too old or too new")
             } else {
-                invalid_exists = true;
                 None
+            };
+
+            match invalid_reason {
+                None => Ok(vote_type),
+                Some(reason) => {
+                    invalid_exists = true;
+                    Err(reason)
+                }
             }
         };
 
+        let mut evaluated = Vec::new();
+        for config in configs {
+            match eval_config(config) {
+                Ok(vote_type) => evaluated.push((config, vote_type)),
+                Err(reason) => trace(&mut sim, || {
+                    format!("'{}': skipped, {reason}.", config.starboard.name)
+                }),
+            }
+        }
+
         let mut upvote = Vec::new();
         let mut downvote = Vec::new();
 
         let mut invalid_exists_2 = false;
 
-        for (config, vote_type) in configs.iter().filter_map(eval_config) {
+        for (config, vote_type) in evaluated {
             // check reactor/author role permissions
             let reactor_perms = Permissions::get_permissions(
                 bot,
@@ -155,6 +303,12 @@ impl<'a> VoteStatus<'a> {
             .await?;
 
             if !reactor_perms.give_votes || !author_perms.receive_votes {
+                trace(&mut sim, || {
+                    format!(
+                        "'{}': invalid, blocked by PermRoles (reactor can vote: {}, author can receive votes: {}).",
+                        config.starboard.name, reactor_perms.give_votes, author_perms.receive_votes,
+                    )
+                });
                 invalid_exists_2 = true;
                 continue;
             }
@@ -175,26 +329,46 @@ impl<'a> VoteStatus<'a> {
             let status = evaluater.status().await?;
 
             if !status {
+                trace(&mut sim, || {
+                    format!("'{}': invalid, failed filters.", config.starboard.name)
+                });
                 invalid_exists_2 = true;
                 continue;
             }
 
             // check cooldown
-            if config.resolved.cooldown_enabled
-                && bot
-                    .cooldowns
-                    .starboard_custom_cooldown
-                    .trigger(
-                        &(vote.reactor_id, config.starboard.id),
-                        config.resolved.cooldown_count as u64,
-                        Duration::from_secs(config.resolved.cooldown_period as u64),
-                    )
-                    .is_some()
-            {
-                invalid_exists_2 = true;
-                continue;
+            if config.resolved.cooldown_enabled {
+                let key = (vote.reactor_id, config.starboard.id);
+                let capacity = config.resolved.cooldown_count as u64;
+                let period = Duration::from_secs(config.resolved.cooldown_period as u64);
+                let cooldown = &bot.cooldowns.starboard_custom_cooldown;
+
+                let on_cooldown = if sim.is_some() {
+                    !cooldown.can_trigger(&key, capacity, period)
+                } else {
+                    cooldown.trigger(&key, capacity, period).is_some()
+                };
+
+                if on_cooldown {
+                    trace(&mut sim, || {
+                        format!(
+                            "'{}': invalid, reactor is on cooldown.",
+                            config.starboard.name
+                        )
+                    });
+                    invalid_exists_2 = true;
+                    continue;
+                }
             }
 
+            trace(&mut sim, || {
+                let kind = match vote_type {
+                    VoteType::Upvote => "upvote",
+                    VoteType::Downvote => "downvote",
+                };
+                format!("'{}': valid {kind}.", config.starboard.name)
+            });
+
             if vote_type == VoteType::Upvote {
                 upvote.push(config);
             } else {
@@ -202,14 +376,26 @@ impl<'a> VoteStatus<'a> {
             }
         }
 
-        if upvote.is_empty() && downvote.is_empty() {
+        let status = if upvote.is_empty() && downvote.is_empty() {
             if (invalid_exists || invalid_exists_2) && allow_remove {
-                Ok(VoteStatus::Remove)
+                VoteStatus::Remove
             } else {
-                Ok(VoteStatus::Ignore)
+                VoteStatus::Ignore
             }
         } else {
-            Ok(VoteStatus::Valid((upvote, downvote)))
-        }
+            VoteStatus::Valid((upvote, downvote))
+        };
+
+        trace(&mut sim, || match &status {
+            VoteStatus::Ignore => "Result: the reaction is ignored.".into(),
+            VoteStatus::Remove => "Result: the reaction is removed as invalid.".into(),
+            VoteStatus::Valid((up, down)) => format!(
+                "Result: counted as an upvote on {} and a downvote on {} starboard(s).",
+                up.len(),
+                down.len()
+            ),
+        });
+
+        Ok(status)
     }
 }
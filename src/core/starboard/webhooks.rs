@@ -1,25 +1,34 @@
-use std::sync::Arc;
+use std::sync::{atomic::Ordering, Arc};
 
-use twilight_model::channel::Webhook;
+use twilight_model::{
+    channel::Webhook,
+    id::{
+        marker::{ApplicationMarker, ChannelMarker},
+        Id,
+    },
+};
 
 use crate::{
     client::bot::StarboardBot,
+    core::discord_limits::{Limit, MAX_CHANNEL_WEBHOOKS},
     database::Starboard,
-    errors::StarboardResult,
+    errors::{DiscordLimitSnafu, StarboardResult},
     utils::{id_as_i64::GetI64, into_id::IntoId},
 };
 
 pub async fn create_webhook(bot: &StarboardBot, starboard: &Starboard) -> &'static str {
-    if get_valid_webhook(bot, starboard, true, false).await.is_ok() {
-        concat!(
+    match get_valid_webhook(bot, starboard, true, false).await {
+        Ok(_) => concat!(
             "I created a webhook in the starboard channel. You can change the username ",
             "and avatar by going to channel settings > integrations > webhooks."
-        )
-    } else {
-        concat!(
-            "I tried to create a webhook, but couldn't. Please make sure I have the ",
-            "necessary permissions."
-        )
+        ),
+        Err(why) => match why.discord_limit() {
+            Some(limit) => limit.describe(),
+            None => concat!(
+                "I tried to create a webhook, but couldn't. Please make sure I have the ",
+                "necessary permissions."
+            ),
+        },
     }
 }
 
@@ -55,13 +64,27 @@ pub async fn get_valid_webhook(
         .await?;
     let Some(webhook_channel) = webhook_channel else { return Ok(None); };
 
-    let name = format!("Webhook for '{}'", starboard.name);
-    let wh = bot.http.create_webhook(webhook_channel, &name)?;
+    let wh = match reusable_webhook(bot, webhook_channel).await? {
+        Some(wh) => wh,
+        None => {
+            let name = format!("Webhook for '{}'", starboard.name);
+            let wh = bot.http.create_webhook(webhook_channel, &name)?;
 
-    let Ok(wh) = wh.await else {
-        return Ok(None);
+            let wh = match wh.await {
+                Ok(wh) => wh,
+                Err(why) => {
+                    let Some(limit) = Limit::from_error(&why) else {
+                        return Ok(None);
+                    };
+                    bot.limit_stats
+                        .discord_errors
+                        .fetch_add(1, Ordering::Relaxed);
+                    return DiscordLimitSnafu { limit }.fail();
+                }
+            };
+            Arc::new(wh.model().await?)
+        }
     };
-    let wh = Arc::new(wh.model().await?);
 
     bot.cache.webhooks.insert(wh.id, wh.clone());
 
@@ -69,3 +92,85 @@ pub async fn get_valid_webhook(
 
     Ok(Some(wh))
 }
+
+/// When the channel is at the webhook limit, finds one of the bot's own
+/// webhooks in it to use instead of creating another. Fails if there isn't
+/// one.
+async fn reusable_webhook(
+    bot: &StarboardBot,
+    channel_id: Id<ChannelMarker>,
+) -> StarboardResult<Option<Arc<Webhook>>> {
+    // without Manage Webhooks, creating the webhook fails anyway
+    let Ok(webhooks) = bot.http.channel_webhooks(channel_id).await else {
+        return Ok(None);
+    };
+    let webhooks = webhooks.models().await?;
+    if webhooks.len() < MAX_CHANNEL_WEBHOOKS {
+        return Ok(None);
+    }
+
+    let app_id = bot.application.read().await.as_ref().map(|app| app.id);
+    match own_webhook(webhooks, app_id) {
+        Some(wh) => {
+            bot.limit_stats.reused.fetch_add(1, Ordering::Relaxed);
+            Ok(Some(Arc::new(wh)))
+        }
+        None => {
+            bot.limit_stats.avoided.fetch_add(1, Ordering::Relaxed);
+            DiscordLimitSnafu {
+                limit: Limit::ChannelWebhooks,
+            }
+            .fail()
+        }
+    }
+}
+
+/// A webhook the bot made (and so has the token of).
+fn own_webhook(webhooks: Vec<Webhook>, app_id: Option<Id<ApplicationMarker>>) -> Option<Webhook> {
+    webhooks
+        .into_iter()
+        .find(|wh| wh.token.is_some() && app_id.is_some() && wh.application_id == app_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn webhook(id: u64, application_id: Option<u64>, token: Option<&str>) -> Webhook {
+        serde_json::from_value(serde_json::json!({
+            "id": id.to_string(),
+            "type": 1,
+            "channel_id": "2",
+            "application_id": application_id.map(|id| id.to_string()),
+            "token": token,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn finds_own_webhook() {
+        let webhooks = vec![
+            webhook(10, None, Some("token")),
+            webhook(11, Some(5), None),
+            webhook(12, Some(3), Some("token")),
+            webhook(13, Some(5), Some("token")),
+        ];
+
+        assert_eq!(
+            own_webhook(webhooks, Some(Id::new(5))).map(|wh| wh.id),
+            Some(Id::new(13))
+        );
+    }
+
+    #[test]
+    fn no_own_webhook() {
+        let webhooks = vec![
+            webhook(10, None, Some("token")),
+            webhook(11, Some(3), Some("token")),
+        ];
+
+        assert!(own_webhook(webhooks.clone(), Some(Id::new(5))).is_none());
+        // without the application, no webhook can be told apart as the bot's
+        assert!(own_webhook(webhooks, None).is_none());
+    }
+}
@@ -10,11 +10,14 @@ use crate::{
     utils::id_as_i64::GetI64,
 };
 
-use super::{premium::is_premium::is_guild_premium, xproles::refresh_xpr};
+use super::{premium::is_premium::is_guild_premium, xp_seasons::season_xp, xproles::refresh_xpr};
 
 #[derive(Default)]
 pub struct MemberStats {
+    /// Lifetime XP, across all seasons.
     pub xp: f32,
+    /// XP earned in the current season, plus any carryover.
+    pub season_xp: f32,
     pub given_upvotes: i64,
     pub given_downvotes: i64,
     pub received_upvotes: i64,
@@ -34,6 +37,12 @@ impl MemberStats {
             return Ok(None);
         }
 
+        let mut season_start_xp = 0.0;
+        if let Some(member) = DbMember::get(pool, guild_id, user_id).await? {
+            stats.xp += member.imported_xp;
+            season_start_xp = member.season_start_xp;
+        }
+
         for sb in starboards {
             if sb.settings.private {
                 continue;
@@ -51,6 +60,7 @@ impl MemberStats {
 
             stats.xp += (received_upvotes - received_downvotes) as f32 * sb.settings.xp_multiplier;
         }
+        stats.season_xp = season_xp(stats.xp, season_start_xp);
 
         Ok(Some(stats))
     }
@@ -138,11 +148,18 @@ pub async fn refresh_xp(
         return Ok(());
     }
 
-    let Some(stats) = MemberStats::get(&bot.pool, guild_id.get_i64(), user_id.get_i64()).await? else {
+    let Some(stats) = MemberStats::get(&bot.pool, guild_id.get_i64(), user_id.get_i64()).await?
+    else {
         return Ok(());
     };
 
-    DbMember::set_xp(&bot.pool, user_id.get_i64(), guild_id.get_i64(), stats.xp).await?;
+    DbMember::set_xp(
+        &bot.pool,
+        user_id.get_i64(),
+        guild_id.get_i64(),
+        stats.season_xp,
+    )
+    .await?;
 
     if is_guild_premium(bot, guild_id.get_i64(), true).await? {
         refresh_xpr(bot, guild_id, user_id).await?;
@@ -0,0 +1,204 @@
+//! Pluggable machine translation, used by the "Translate" button on
+//! starboard messages.
+
+use async_trait::async_trait;
+use moka::future::Cache as MokaCache;
+use reqwest::header::CONTENT_TYPE;
+use twilight_model::id::{marker::MessageMarker, Id};
+
+use crate::{client::config::Config, constants};
+
+#[async_trait]
+pub trait Translator: Send + Sync {
+    /// Translates `text` into `target` (e.g. "en"). The error is shown to the
+    /// user as-is.
+    async fn translate(&self, text: &str, target: &str) -> Result<String, String>;
+}
+
+/// Used when no translation backend is configured.
+pub struct NoopTranslator;
+
+#[async_trait]
+impl Translator for NoopTranslator {
+    async fn translate(&self, _text: &str, _target: &str) -> Result<String, String> {
+        Err("Translation isn't configured for this bot.".to_string())
+    }
+}
+
+/// Translates using a LibreTranslate-compatible HTTP API.
+pub struct LibreTranslator {
+    client: reqwest::Client,
+    url: String,
+    api_key: Option<String>,
+}
+
+#[async_trait]
+impl Translator for LibreTranslator {
+    async fn translate(&self, text: &str, target: &str) -> Result<String, String> {
+        let mut body = serde_json::json!({
+            "q": text,
+            "source": "auto",
+            "target": target,
+            "format": "text",
+        });
+        if let Some(api_key) = &self.api_key {
+            body["api_key"] = api_key.clone().into();
+        }
+
+        let ret: reqwest::Result<_> = async {
+            self.client
+                .post(format!("{}/translate", self.url.trim_end_matches('/')))
+                .header(CONTENT_TYPE, "application/json")
+                .body(body.to_string())
+                .send()
+                .await?
+                .error_for_status()?
+                .bytes()
+                .await
+        }
+        .await;
+
+        let unavailable = || "The translation service is unavailable right now.".to_string();
+        let bytes = ret.map_err(|why| {
+            tracing::warn!("Translation failed: {why}");
+            unavailable()
+        })?;
+
+        let value: serde_json::Value = serde_json::from_slice(&bytes).map_err(|_| unavailable())?;
+        match value["translatedText"].as_str() {
+            Some(translated) => Ok(translated.to_string()),
+            None => Err(unavailable()),
+        }
+    }
+}
+
+pub fn translator_from_config(config: &Config) -> Box<dyn Translator> {
+    match &config.translate_url {
+        Some(url) => Box::new(LibreTranslator {
+            client: reqwest::Client::new(),
+            url: url.clone(),
+            api_key: config.translate_api_key.clone(),
+        }),
+        None => Box::new(NoopTranslator),
+    }
+}
+
+/// Translates a message's `content` into `target` and caches the result.
+/// Failed translations aren't cached, so the next click tries again.
+pub async fn translate_and_cache(
+    translator: &dyn Translator,
+    cache: &MokaCache<(Id<MessageMarker>, String), String>,
+    message_id: Id<MessageMarker>,
+    target: String,
+    content: &str,
+) -> Result<String, String> {
+    let translated = translator.translate(content, &target).await?;
+    cache.insert((message_id, target), translated.clone()).await;
+
+    Ok(translated)
+}
+
+/// Converts a Discord locale (e.g. "pt-BR") into a language code ("pt").
+pub fn target_language(locale: &str) -> String {
+    locale.split('-').next().unwrap_or(locale).to_lowercase()
+}
+
+/// Truncates the text to `MAX_TRANSLATE_CHARS` characters.
+pub fn truncate_for_translation(text: &str) -> String {
+    if text.chars().count() > constants::MAX_TRANSLATE_CHARS {
+        text.chars()
+            .take(constants::MAX_TRANSLATE_CHARS - 3)
+            .collect::<String>()
+            + "..."
+    } else {
+        text.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use crate::client::cooldowns::Cooldowns;
+
+    use super::*;
+
+    #[derive(Default)]
+    struct CountingTranslator {
+        calls: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl Translator for CountingTranslator {
+        async fn translate(&self, text: &str, target: &str) -> Result<String, String> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            Ok(format!("{text} ({target})"))
+        }
+    }
+
+    #[tokio::test]
+    async fn cached_for_the_language() {
+        let translator = CountingTranslator::default();
+        let cache = MokaCache::new(10);
+        let message_id = Id::new(1);
+
+        let translated = translate_and_cache(
+            &translator,
+            &cache,
+            message_id,
+            target_language("pt-BR"),
+            "hello",
+        )
+        .await;
+        assert_eq!(translated.as_deref(), Ok("hello (pt)"));
+
+        // a click from another Portuguese locale is a cache hit
+        let key = (message_id, target_language("pt-PT"));
+        assert_eq!(cache.get(&key).as_deref(), Some("hello (pt)"));
+        assert_eq!(cache.get(&(message_id, "en".to_string())), None);
+        assert_eq!(translator.calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn failures_are_not_cached() {
+        let cache = MokaCache::new(10);
+        let message_id = Id::new(1);
+
+        let translated =
+            translate_and_cache(&NoopTranslator, &cache, message_id, "en".to_string(), "hi").await;
+        assert_eq!(
+            translated,
+            Err("Translation isn't configured for this bot.".to_string())
+        );
+        assert_eq!(cache.get(&(message_id, "en".to_string())), None);
+    }
+
+    #[test]
+    fn languages() {
+        assert_eq!(target_language("en-US"), "en");
+        assert_eq!(target_language("fr"), "fr");
+        assert_eq!(target_language("zh-TW"), "zh");
+    }
+
+    #[test]
+    fn truncation() {
+        let long = "a".repeat(constants::MAX_TRANSLATE_CHARS + 1);
+        let truncated = truncate_for_translation(&long);
+
+        assert_eq!(truncated.chars().count(), constants::MAX_TRANSLATE_CHARS);
+        assert!(truncated.ends_with("..."));
+        assert_eq!(truncate_for_translation("short"), "short");
+    }
+
+    #[test]
+    fn cooldown() {
+        let cooldowns = Cooldowns::new();
+        let user_id = Id::new(1);
+
+        for _ in 0..constants::TRANSLATE.0 {
+            assert!(cooldowns.translate.trigger(&user_id).is_none());
+        }
+        assert!(cooldowns.translate.trigger(&user_id).is_some());
+        assert!(cooldowns.translate.trigger(&Id::new(2)).is_none());
+    }
+}
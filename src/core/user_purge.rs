@@ -0,0 +1,228 @@
+//! Deletes the starboard posts of a user's messages, for `/utils purge-user`.
+//! Posts are deleted in batches, so that the command can report progress and
+//! stop between them.
+
+use std::collections::HashMap;
+
+use cached::Cached;
+use twilight_model::id::{
+    marker::{ChannelMarker, MessageMarker},
+    Id,
+};
+
+use crate::{
+    client::bot::StarboardBot,
+    constants,
+    core::starboard::link_reply::delete_link_reply,
+    database::{DeletedPost, StarboardMessage},
+    errors::StarboardResult,
+    utils::{
+        get_status::{get_error_code, get_status},
+        into_id::IntoId,
+        snowflake_age::SnowflakeAge,
+    },
+};
+
+/// Posts in one starboard channel that are deleted together.
+pub struct PurgeBatch {
+    /// For forum starboards, the post's own thread.
+    pub channel_id: Id<ChannelMarker>,
+    /// Posts on forum starboards are the first message of their own thread,
+    /// so they're deleted one by one, along with the thread.
+    pub is_forum: bool,
+    /// The posts, with the channel of each original message.
+    pub posts: Vec<(StarboardMessage, i64)>,
+}
+
+/// Groups posts into batches. Recent posts in the same channel are bulk
+/// deleted, older posts and forum posts are deleted one by one. Posts on
+/// starboards that aren't in `channels` (starboard id -> channel id and
+/// whether it's a forum) are skipped.
+pub fn plan_batches(
+    posts: Vec<(StarboardMessage, i64)>,
+    channels: &HashMap<i32, (i64, bool)>,
+) -> Vec<PurgeBatch> {
+    let mut recent: HashMap<i64, Vec<_>> = HashMap::new();
+    let mut batches = Vec::new();
+
+    for (post, orig_channel_id) in posts {
+        let Some(&(channel_id, is_forum)) = channels.get(&post.starboard_id) else {
+            continue;
+        };
+        let post_id: Id<MessageMarker> = post.starboard_message_id.into_id();
+        if is_forum {
+            batches.push(PurgeBatch {
+                channel_id: post_id.cast(),
+                is_forum: true,
+                posts: vec![(post, orig_channel_id)],
+            });
+        } else if post_id.age() < constants::BULK_DELETE_MAX_AGE {
+            recent
+                .entry(channel_id)
+                .or_default()
+                .push((post, orig_channel_id));
+        } else {
+            batches.push(PurgeBatch {
+                channel_id: channel_id.into_id(),
+                is_forum: false,
+                posts: vec![(post, orig_channel_id)],
+            });
+        }
+    }
+
+    for (channel_id, posts) in recent {
+        let mut posts = posts.into_iter().peekable();
+        while posts.peek().is_some() {
+            batches.push(PurgeBatch {
+                channel_id: channel_id.into_id(),
+                is_forum: false,
+                posts: posts
+                    .by_ref()
+                    .take(constants::PURGE_USER_BULK_SIZE)
+                    .collect(),
+            });
+        }
+    }
+
+    batches
+}
+
+/// Deletes a batch of posts and their rows. If `record_deleted`, the posts
+/// are remembered as deleted by hand, so `recreate-deleted-posts` decides
+/// whether they're sent again. Returns the number of posts deleted.
+pub async fn delete_batch(
+    bot: &StarboardBot,
+    batch: &PurgeBatch,
+    record_deleted: bool,
+) -> StarboardResult<usize> {
+    let post_ids: Vec<Id<MessageMarker>> = batch
+        .posts
+        .iter()
+        .map(|(post, _)| post.starboard_message_id.into_id())
+        .collect();
+
+    {
+        let mut auto_deleted = bot.cache.auto_deleted_posts.write().await;
+        for post_id in &post_ids {
+            auto_deleted.cache_set(*post_id, ());
+        }
+    }
+
+    // deleting only the starter message would leave an empty forum post
+    let deleted_thread = batch.is_forum
+        && match bot.http.delete_channel(batch.channel_id).await {
+            Ok(_) => true,
+            Err(why) => is_gone(&why),
+        };
+
+    let ret = if deleted_thread {
+        Ok(())
+    } else if post_ids.len() == 1 {
+        bot.http
+            .delete_message(batch.channel_id, post_ids[0])
+            .await
+            .map(|_| ())
+    } else {
+        bot.http
+            .delete_messages(batch.channel_id, &post_ids)?
+            .await
+            .map(|_| ())
+    };
+    match ret {
+        Ok(()) => (),
+        Err(why) if is_gone(&why) => (),
+        Err(why) => return Err(why.into()),
+    }
+
+    for (post, orig_channel_id) in &batch.posts {
+        if record_deleted {
+            DeletedPost::create(
+                &bot.pool,
+                post.message_id,
+                post.starboard_id,
+                post.starboard_message_id,
+            )
+            .await?;
+        }
+        StarboardMessage::delete(&bot.pool, post.starboard_message_id).await?;
+        delete_link_reply(bot, (*orig_channel_id).into_id(), post).await;
+    }
+
+    Ok(batch.posts.len())
+}
+
+/// Whether the posts or their channel are already gone.
+fn is_gone(error: &twilight_http::Error) -> bool {
+    // unknown channel, unknown message
+    get_status(error) == Some(404) && matches!(get_error_code(error), Some(10003 | 10008))
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, Utc};
+
+    use super::*;
+    use crate::utils::snowflake_age::snowflake_at;
+
+    fn post(starboard_id: i32, days_old: i64, n: i64) -> (StarboardMessage, i64) {
+        let post_id = snowflake_at(Utc::now() - Duration::days(days_old)) + n;
+        let post = StarboardMessage {
+            message_id: n,
+            starboard_id,
+            starboard_message_id: post_id,
+            last_known_point_count: 0,
+            reply_message_id: None,
+        };
+        (post, 1)
+    }
+
+    fn channels() -> HashMap<i32, (i64, bool)> {
+        HashMap::from([(1, (10, false)), (2, (20, true))])
+    }
+
+    #[test]
+    fn recent_posts_are_bulk_deleted() {
+        let posts = (0..constants::PURGE_USER_BULK_SIZE as i64 + 1)
+            .map(|n| post(1, 1, n))
+            .collect();
+        let batches = plan_batches(posts, &channels());
+
+        let mut sizes: Vec<_> = batches.iter().map(|b| b.posts.len()).collect();
+        sizes.sort();
+        assert_eq!(sizes, vec![1, constants::PURGE_USER_BULK_SIZE]);
+        assert!(batches
+            .iter()
+            .all(|b| b.channel_id.get() == 10 && !b.is_forum));
+    }
+
+    #[test]
+    fn old_posts_are_deleted_one_by_one() {
+        let posts = vec![post(1, 30, 0), post(1, 30, 1)];
+        let batches = plan_batches(posts, &channels());
+
+        assert_eq!(batches.len(), 2);
+        assert!(batches.iter().all(|b| b.posts.len() == 1));
+    }
+
+    #[test]
+    fn forum_posts_delete_their_thread() {
+        let posts = vec![post(2, 1, 0), post(2, 1, 1)];
+        let batches = plan_batches(posts, &channels());
+
+        assert_eq!(batches.len(), 2);
+        for batch in batches {
+            assert!(batch.is_forum);
+            assert_eq!(batch.posts.len(), 1);
+            assert_eq!(
+                batch.channel_id.get() as i64,
+                batch.posts[0].0.starboard_message_id
+            );
+        }
+    }
+
+    #[test]
+    fn unknown_starboards_are_skipped() {
+        let batches = plan_batches(vec![post(3, 1, 0)], &channels());
+        assert!(batches.is_empty());
+    }
+}
@@ -0,0 +1,173 @@
+//! Vote emojis worth more than one point, e.g. ⭐ for 1 and 💎 for 3.
+//!
+//! Each user has at most one vote per message and starboard, no matter how
+//! many vote emojis they react with. That vote is worth the weight of the
+//! heaviest emoji they voted with in its direction, so stacking emojis never
+//! adds up. Voting in the other direction replaces the vote, weight included.
+
+use serde::{Deserialize, Serialize};
+use twilight_model::id::{marker::GuildMarker, Id};
+
+use crate::{
+    client::bot::StarboardBot,
+    core::emoji::{EmojiCommon, SimpleEmoji},
+};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EmojiWeight {
+    /// A stored `SimpleEmoji`.
+    pub emoji: String,
+    /// How many points a vote with this emoji is worth. Downvotes subtract
+    /// it.
+    pub weight: i16,
+}
+
+/// The weights stored in `vote_emoji_weights`.
+pub fn parse_weights(value: &serde_json::Value) -> Vec<EmojiWeight> {
+    serde_json::from_value(value.clone()).unwrap_or_default()
+}
+
+/// The weight of a vote with `emoji`. Emojis without a weight are worth 1.
+pub fn emoji_weight(value: &serde_json::Value, emoji: &SimpleEmoji) -> i16 {
+    parse_weights(value)
+        .into_iter()
+        .find(|w| *emoji == w.emoji)
+        .map(|w| w.weight)
+        .unwrap_or(1)
+}
+
+/// The vote a user ends up with after voting with `new` while they already
+/// had `existing`. Both are (is_downvote, weight).
+pub fn clamp_vote(existing: Option<(bool, i16)>, new: (bool, i16)) -> (bool, i16) {
+    match existing {
+        Some((was_downvote, weight)) if was_downvote == new.0 => (new.0, weight.max(new.1)),
+        _ => new,
+    }
+}
+
+/// Whether the vote a user ends up with (see `clamp_vote`) keeps the emoji
+/// of `existing`, because that emoji's weight is the one that counts.
+pub fn keeps_emoji(existing: Option<(bool, i16)>, new: (bool, i16)) -> bool {
+    matches!(existing, Some((was_downvote, weight)) if was_downvote == new.0 && weight >= new.1)
+}
+
+/// The points a vote adds to a message.
+pub fn vote_points(is_downvote: bool, weight: i16) -> i32 {
+    if is_downvote {
+        -(weight as i32)
+    } else {
+        weight as i32
+    }
+}
+
+/// Parses input like "⭐=1, 💎=3". Validation is left to
+/// `validate_emoji_weights`.
+pub fn weights_from_user_input(
+    input: &str,
+    bot: &StarboardBot,
+    guild_id: Id<GuildMarker>,
+) -> Result<Vec<EmojiWeight>, String> {
+    let mut weights = Vec::new();
+    for part in input.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+        let err = || format!("I couldn't read `{part}`. Use something like `⭐=1, 💎=3`.");
+
+        let (emoji, weight) = part.rsplit_once('=').ok_or_else(err)?;
+        let weight = weight.trim().parse().map_err(|_| err())?;
+        let mut emojis = SimpleEmoji::from_user_input(emoji.trim(), bot, guild_id);
+        if emojis.len() != 1 {
+            return Err(err());
+        }
+
+        weights.push(EmojiWeight {
+            emoji: emojis.pop().unwrap().into_stored(),
+            weight,
+        });
+    }
+
+    Ok(weights)
+}
+
+pub fn format_weights(
+    bot: &StarboardBot,
+    guild_id: Id<GuildMarker>,
+    weights: &[EmojiWeight],
+) -> String {
+    weights
+        .iter()
+        .map(|w| {
+            let emoji = SimpleEmoji::from_stored(w.emoji.clone()).into_readable(bot, guild_id);
+            format!("{emoji}={}", w.weight)
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn weights() -> serde_json::Value {
+        json!([{"emoji": "⭐", "weight": 1}, {"emoji": "💎", "weight": 3}])
+    }
+
+    #[test]
+    fn parses_weights() {
+        assert_eq!(
+            parse_weights(&weights()),
+            vec![
+                EmojiWeight {
+                    emoji: "⭐".to_string(),
+                    weight: 1
+                },
+                EmojiWeight {
+                    emoji: "💎".to_string(),
+                    weight: 3
+                },
+            ]
+        );
+        assert!(parse_weights(&json!(null)).is_empty());
+        assert!(parse_weights(&json!({"emoji": "⭐"})).is_empty());
+    }
+
+    #[test]
+    fn emoji_weights() {
+        let weight =
+            |emoji: &str| emoji_weight(&weights(), &SimpleEmoji::from_stored(emoji.to_string()));
+
+        assert_eq!(weight("💎"), 3);
+        assert_eq!(weight("⭐"), 1);
+        // emojis without a weight
+        assert_eq!(weight("👍"), 1);
+    }
+
+    #[test]
+    fn heaviest_emoji_counts() {
+        assert_eq!(clamp_vote(None, (false, 3)), (false, 3));
+        assert_eq!(clamp_vote(Some((false, 3)), (false, 1)), (false, 3));
+        assert_eq!(clamp_vote(Some((false, 1)), (false, 3)), (false, 3));
+    }
+
+    #[test]
+    fn heaviest_emoji_is_kept() {
+        assert!(keeps_emoji(Some((false, 3)), (false, 1)));
+        assert!(keeps_emoji(Some((false, 2)), (false, 2)));
+        assert!(!keeps_emoji(Some((false, 1)), (false, 3)));
+        // a flipped vote always takes the new emoji
+        assert!(!keeps_emoji(Some((true, 3)), (false, 1)));
+        assert!(!keeps_emoji(None, (false, 1)));
+    }
+
+    #[test]
+    fn other_direction_replaces() {
+        assert_eq!(clamp_vote(Some((false, 3)), (true, 1)), (true, 1));
+        assert_eq!(clamp_vote(Some((true, 5)), (false, 2)), (false, 2));
+    }
+
+    #[test]
+    fn points() {
+        assert_eq!(vote_points(false, 3), 3);
+        assert_eq!(vote_points(true, 3), -3);
+    }
+}
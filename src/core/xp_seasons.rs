@@ -0,0 +1,181 @@
+//! XP seasons. Starting a new season archives every member's XP into
+//! `xp_seasons`, then resets it, keeping the guild's carryover percentage.
+//! Lifetime XP is never lost, since live XP is stored relative to the XP a
+//! member had when the season started.
+
+use std::sync::Arc;
+
+use twilight_model::{
+    channel::message::{embed::Embed, AllowedMentions},
+    id::{marker::GuildMarker, Id},
+};
+
+use crate::{
+    client::bot::StarboardBot,
+    constants,
+    core::{competitions::place_emoji, xproles::refresh_xpr_for_members},
+    database::{DbGuild, XPSeason},
+    errors::StarboardResult,
+    utils::{embed, get_status::get_status, id_as_i64::GetI64, into_id::IntoId},
+};
+
+/// The fraction of XP kept for a carryover percentage.
+pub fn carryover_fraction(carryover: i16) -> f32 {
+    f32::from(carryover.clamp(0, 100)) / 100.0
+}
+
+/// A member's `(season_start_xp, xp)` once a season closes, keeping
+/// `carryover` (0 to 1) of their XP. Their lifetime XP, the sum of both,
+/// stays the same. `XPSeason::close_season` does the same in SQL.
+pub fn carry_over(season_start_xp: f32, xp: f32, carryover: f32) -> (f32, f32) {
+    (season_start_xp + xp * (1.0 - carryover), xp * carryover)
+}
+
+/// The XP earned in the current season, plus any carryover.
+pub fn season_xp(lifetime_xp: f32, season_start_xp: f32) -> f32 {
+    lifetime_xp - season_start_xp
+}
+
+pub struct ClosedSeason {
+    pub season: i16,
+    pub top: Vec<XPSeason>,
+    /// Whether the top members were announced.
+    pub announced: bool,
+}
+
+/// Closes the current season and starts a new one, then announces the top
+/// members of the closed season and refreshes award roles.
+pub async fn start_new_season(
+    bot: Arc<StarboardBot>,
+    guild_id: Id<GuildMarker>,
+) -> StarboardResult<ClosedSeason> {
+    let guild_id_i64 = guild_id.get_i64();
+    DbGuild::create(&bot.pool, guild_id_i64).await?;
+    let guild = DbGuild::get(&bot.pool, guild_id_i64).await?.unwrap();
+
+    let season = XPSeason::close_season(
+        &bot.pool,
+        guild_id_i64,
+        carryover_fraction(guild.xp_carryover),
+    )
+    .await?;
+    let top = XPSeason::list_by_xp(
+        &bot.pool,
+        guild_id_i64,
+        season,
+        constants::XP_SEASON_ANNOUNCE_TOP,
+    )
+    .await?;
+
+    let announced = match guild.xp_season_channel_id {
+        None => false,
+        Some(channel_id) => announce_season(&bot, channel_id, season, &top).await?,
+    };
+
+    // members that kept XP may still qualify for some roles, but everyone
+    // with XP before the reset could have lost some
+    let user_ids = XPSeason::list_by_xp(&bot.pool, guild_id_i64, season, i64::MAX)
+        .await?
+        .into_iter()
+        .map(|m| m.user_id)
+        .collect();
+    tokio::spawn(refresh_xpr_for_members(bot.clone(), guild_id, user_ids));
+
+    Ok(ClosedSeason {
+        season,
+        top,
+        announced,
+    })
+}
+
+async fn announce_season(
+    bot: &StarboardBot,
+    channel_id: i64,
+    season: i16,
+    top: &[XPSeason],
+) -> StarboardResult<bool> {
+    let ret = bot
+        .http
+        .create_message(channel_id.into_id())
+        .allowed_mentions(Some(&AllowedMentions::default()))
+        .embeds(&[build_season_embed(season, top)])?
+        .await;
+
+    match ret {
+        Ok(_) => Ok(true),
+        Err(why) if matches!(get_status(&why), Some(403 | 404)) => Ok(false),
+        Err(why) => Err(why.into()),
+    }
+}
+
+pub fn format_season_member(place: usize, member: &XPSeason) -> String {
+    let place = i16::try_from(place).unwrap_or(i16::MAX);
+    format!(
+        "{} <@{}> with {} XP",
+        place_emoji(place),
+        member.user_id,
+        member.xp
+    )
+}
+
+fn build_season_embed(season: i16, top: &[XPSeason]) -> Embed {
+    let description = if top.is_empty() {
+        "Nobody earned XP this season.".to_string()
+    } else {
+        top.iter()
+            .enumerate()
+            .map(|(idx, member)| format_season_member(idx + 1, member))
+            .collect::<Vec<_>>()
+            .join("\n")
+    };
+
+    embed::build()
+        .title(format!("🏆 Season {season} has ended!"))
+        .description(description)
+        .build()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fraction() {
+        assert_eq!(carryover_fraction(0), 0.0);
+        assert_eq!(carryover_fraction(25), 0.25);
+        assert_eq!(carryover_fraction(100), 1.0);
+        assert_eq!(carryover_fraction(-5), 0.0);
+        assert_eq!(carryover_fraction(150), 1.0);
+    }
+
+    #[test]
+    fn carry_over_keeps_fraction() {
+        assert_eq!(carry_over(0.0, 100.0, 0.0), (100.0, 0.0));
+        assert_eq!(carry_over(0.0, 100.0, 0.25), (75.0, 25.0));
+        assert_eq!(carry_over(0.0, 100.0, 1.0), (0.0, 100.0));
+        // downvoted members keep part of their negative XP too
+        assert_eq!(carry_over(10.0, -20.0, 0.5), (0.0, -10.0));
+    }
+
+    #[test]
+    fn lifetime_xp_preserved() {
+        let mut lifetime = 0.0;
+        let (mut start, mut xp) = (0.0, 0.0);
+        for earned in [120.0, 40.0, 0.0, 300.0] {
+            lifetime += earned;
+            xp = season_xp(lifetime, start);
+            (start, xp) = carry_over(start, xp, carryover_fraction(25));
+            assert_eq!(start + xp, lifetime);
+        }
+        assert_eq!(xp, season_xp(lifetime, start));
+    }
+
+    #[test]
+    fn season_xp_after_close() {
+        // 200 lifetime XP, 50% carried over
+        let (start, xp) = carry_over(0.0, 200.0, 0.5);
+        assert_eq!(season_xp(200.0, start), xp);
+        // then 30 more in the new season
+        assert_eq!(season_xp(230.0, start), 130.0);
+    }
+}
@@ -0,0 +1,291 @@
+//! Importing and exporting member XP as CSV, for servers migrating from
+//! another leveling bot.
+
+use std::{collections::HashSet, fmt::Write};
+
+use futures::TryStreamExt;
+
+use crate::{client::bot::StarboardBot, constants, database::DbMember, errors::StarboardResult};
+
+#[derive(Clone, Copy, Debug)]
+pub enum ImportMode {
+    /// Use the imported XP, discarding the current XP.
+    Replace,
+    /// Add the imported XP to the current XP.
+    Add,
+    /// Use whichever is higher.
+    KeepHigher,
+}
+
+impl ImportMode {
+    pub fn merge(self, current: f32, imported: f32) -> f32 {
+        match self {
+            Self::Replace => imported,
+            Self::Add => current + imported,
+            Self::KeepHigher => current.max(imported),
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Replace => "Replace",
+            Self::Add => "Add",
+            Self::KeepHigher => "Keep Higher",
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct ImportRow {
+    pub user_id: i64,
+    pub xp: f32,
+}
+
+#[derive(Debug, Default)]
+pub struct ParsedImport {
+    pub rows: Vec<ImportRow>,
+    /// Examples of invalid rows, capped at `MAX_XP_IMPORT_ERRORS`.
+    pub errors: Vec<String>,
+    /// The total number of invalid rows.
+    pub error_count: usize,
+}
+
+impl ParsedImport {
+    fn invalid(&mut self, line: usize, reason: String) {
+        self.error_count += 1;
+        if self.errors.len() < constants::MAX_XP_IMPORT_ERRORS {
+            self.errors.push(format!("line {line}: {reason}"));
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct XPChange {
+    pub user_id: i64,
+    pub current: f32,
+    pub new: f32,
+}
+
+/// Splits a single CSV line into its fields, handling quoted fields and
+/// escaped ("") quotes.
+fn split_fields(line: &str) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut current = String::new();
+    let mut in_quotes = false;
+    let mut chars = line.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if in_quotes && chars.peek() == Some(&'"') => {
+                chars.next();
+                current.push('"');
+            }
+            '"' => in_quotes = !in_quotes,
+            ',' if !in_quotes => fields.push(std::mem::take(&mut current)),
+            c => current.push(c),
+        }
+    }
+    fields.push(current);
+
+    fields.into_iter().map(|f| f.trim().to_string()).collect()
+}
+
+fn truncate_field(field: &str) -> String {
+    if field.chars().count() > 32 {
+        format!("{}...", field.chars().take(32).collect::<String>())
+    } else {
+        field.to_string()
+    }
+}
+
+/// Parses a `user_id,xp` CSV file. Blank lines, a byte order mark, and a
+/// header row are ignored. Returns an error if there are too many rows.
+pub fn parse_csv(input: &str) -> Result<ParsedImport, String> {
+    let input = input.strip_prefix('\u{feff}').unwrap_or(input);
+
+    let mut parsed = ParsedImport::default();
+    let mut seen = HashSet::new();
+    let mut first_row = true;
+
+    for (idx, line) in input.lines().enumerate() {
+        let line_number = idx + 1;
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let is_first_row = first_row;
+        first_row = false;
+
+        let fields = split_fields(line);
+        if fields.len() < 2 {
+            parsed.invalid(
+                line_number,
+                "expected two columns (user_id, xp)".to_string(),
+            );
+            continue;
+        }
+
+        let Ok(user_id) = fields[0].parse::<u64>() else {
+            if !is_first_row {
+                parsed.invalid(
+                    line_number,
+                    format!("invalid user id `{}`", truncate_field(&fields[0])),
+                );
+            }
+            // otherwise, this is probably the header
+            continue;
+        };
+        if user_id == 0 || user_id > i64::MAX as u64 {
+            parsed.invalid(line_number, format!("invalid user id `{user_id}`"));
+            continue;
+        }
+
+        let xp = match fields[1].parse::<f32>() {
+            Ok(xp) if xp.is_finite() && xp >= 0.0 => xp,
+            _ => {
+                parsed.invalid(
+                    line_number,
+                    format!("invalid xp `{}`", truncate_field(&fields[1])),
+                );
+                continue;
+            }
+        };
+
+        let user_id = user_id as i64;
+        if !seen.insert(user_id) {
+            parsed.invalid(line_number, format!("duplicate user id `{user_id}`"));
+            continue;
+        }
+
+        if parsed.rows.len() >= constants::MAX_XP_IMPORT_ROWS {
+            return Err(format!(
+                "You can only import up to {} rows at once.",
+                constants::MAX_XP_IMPORT_ROWS
+            ));
+        }
+        parsed.rows.push(ImportRow { user_id, xp });
+    }
+
+    Ok(parsed)
+}
+
+/// Compares the imported rows against the current XP for each member.
+pub async fn plan_import(
+    pool: &sqlx::PgPool,
+    guild_id: i64,
+    rows: &[ImportRow],
+    mode: ImportMode,
+) -> StarboardResult<Vec<XPChange>> {
+    let user_ids: Vec<_> = rows.iter().map(|r| r.user_id).collect();
+    let current = DbMember::list_xp_by_users(pool, guild_id, &user_ids).await?;
+
+    let changes = rows
+        .iter()
+        .map(|row| {
+            let current = current.get(&row.user_id).copied().unwrap_or(0.0);
+            XPChange {
+                user_id: row.user_id,
+                current,
+                new: mode.merge(current, row.xp),
+            }
+        })
+        .collect();
+
+    Ok(changes)
+}
+
+/// Applies the changes in one transaction. Returns false if the bot started
+/// shutting down first, in which case nothing was changed.
+pub async fn apply_import(
+    bot: &StarboardBot,
+    guild_id: i64,
+    changes: &[XPChange],
+) -> StarboardResult<bool> {
+    let changes: Vec<_> = changes
+        .iter()
+        .map(|c| (c.user_id, c.new, c.new - c.current))
+        .collect();
+
+    let applied = DbMember::import_xp(&bot.pool, guild_id, &changes, &bot.shutting_down).await?;
+
+    Ok(applied)
+}
+
+/// Exports all members with XP as a `user_id,xp` CSV file.
+pub async fn export_csv(pool: &sqlx::PgPool, guild_id: i64) -> StarboardResult<String> {
+    let mut csv = "user_id,xp\n".to_string();
+
+    let mut members = DbMember::stream_by_xp(pool, guild_id);
+    while let Some(member) = members.try_next().await? {
+        writeln!(csv, "{},{}", member.user_id, member.xp).unwrap();
+    }
+
+    Ok(csv)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rows(parsed: &ParsedImport) -> Vec<(i64, f32)> {
+        parsed.rows.iter().map(|r| (r.user_id, r.xp)).collect()
+    }
+
+    #[test]
+    fn merge_modes() {
+        assert_eq!(ImportMode::Replace.merge(10.0, 4.0), 4.0);
+        assert_eq!(ImportMode::Add.merge(10.0, 4.0), 14.0);
+        assert_eq!(ImportMode::KeepHigher.merge(10.0, 4.0), 10.0);
+        assert_eq!(ImportMode::KeepHigher.merge(4.0, 10.0), 10.0);
+    }
+
+    #[test]
+    fn quoted_fields() {
+        assert_eq!(split_fields(r#""1", "2.5""#), vec!["1", "2.5"]);
+        assert_eq!(split_fields(r#""a,b",c"#), vec!["a,b", "c"]);
+        assert_eq!(split_fields(r#""say ""hi""",1"#), vec![r#"say "hi""#, "1"]);
+    }
+
+    #[test]
+    fn tolerant_parsing() {
+        let input = "\u{feff}user_id,xp\r\n\r\n1,10\r\n\"2\",\"2.5\"\r\n";
+        let parsed = parse_csv(input).unwrap();
+
+        assert_eq!(rows(&parsed), vec![(1, 10.0), (2, 2.5)]);
+        assert_eq!(parsed.error_count, 0);
+    }
+
+    #[test]
+    fn reports_malformed_rows() {
+        let input = "1,10\nabc,5\n2\n3,-1\n4,nan\n1,3\n0,1";
+        let parsed = parse_csv(input).unwrap();
+
+        assert_eq!(rows(&parsed), vec![(1, 10.0)]);
+        assert_eq!(parsed.error_count, 6);
+        assert_eq!(parsed.errors[0], "line 2: invalid user id `abc`");
+        assert_eq!(
+            parsed.errors[1],
+            "line 3: expected two columns (user_id, xp)"
+        );
+        assert_eq!(parsed.errors[4], "line 6: duplicate user id `1`");
+    }
+
+    #[test]
+    fn caps_error_examples() {
+        let input = "x,1\n".repeat(constants::MAX_XP_IMPORT_ERRORS + 5);
+        let parsed = parse_csv(&input).unwrap();
+
+        // the first row is taken as a header
+        assert_eq!(parsed.error_count, constants::MAX_XP_IMPORT_ERRORS + 4);
+        assert_eq!(parsed.errors.len(), constants::MAX_XP_IMPORT_ERRORS);
+    }
+
+    #[test]
+    fn caps_rows() {
+        let input: String = (1..=constants::MAX_XP_IMPORT_ROWS + 1)
+            .map(|id| format!("{id},1\n"))
+            .collect();
+
+        assert!(parse_csv(&input).is_err());
+    }
+}
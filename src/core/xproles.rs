@@ -1,3 +1,5 @@
+use std::sync::Arc;
+
 use twilight_model::id::{
     marker::{GuildMarker, UserMarker},
     Id,
@@ -5,11 +7,14 @@ use twilight_model::id::{
 
 use crate::{
     client::bot::StarboardBot,
+    constants,
     database::{DbMember, XPRole},
     errors::StarboardResult,
     utils::{id_as_i64::GetI64, into_id::IntoId},
 };
 
+use super::premium::is_premium::is_guild_premium;
+
 pub async fn refresh_xpr(
     bot: &StarboardBot,
     guild_id: Id<GuildMarker>,
@@ -21,7 +26,8 @@ pub async fn refresh_xpr(
     let member_roles = &member.roles;
 
     let xproles = XPRole::list_by_guild(&bot.pool, guild_id.get_i64()).await?;
-    let Some(member) = DbMember::get(&bot.pool, guild_id.get_i64(), user_id.get_i64()).await? else {
+    let Some(member) = DbMember::get(&bot.pool, guild_id.get_i64(), user_id.get_i64()).await?
+    else {
         return Ok(());
     };
 
@@ -50,3 +56,32 @@ pub async fn refresh_xpr(
 
     Ok(())
 }
+
+/// Refreshes the XP-based award roles for many members at once, e.g. after
+/// an XP import or a new season. This is paced, so it should be spawned.
+pub async fn refresh_xpr_for_members(
+    bot: Arc<StarboardBot>,
+    guild_id: Id<GuildMarker>,
+    user_ids: Vec<i64>,
+) {
+    let result: StarboardResult<()> = async {
+        if !is_guild_premium(&bot, guild_id.get_i64(), true).await? {
+            return Ok(());
+        }
+        if XPRole::count(&bot.pool, guild_id.get_i64()).await? == 0 {
+            return Ok(());
+        }
+
+        for user_id in user_ids {
+            refresh_xpr(&bot, guild_id, user_id.into_id()).await?;
+            tokio::time::sleep(constants::XPR_BULK_REFRESH_DELAY).await;
+        }
+
+        Ok(())
+    }
+    .await;
+
+    if let Err(why) = result {
+        tracing::error!("Failed to refresh award roles for {guild_id}: {why}");
+    }
+}
@@ -0,0 +1,144 @@
+//! Helpers for large multi-row writes. Rows are split into chunks, each
+//! written in its own transaction with a statement timeout, so that a big job
+//! never holds locks for long.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use sqlx::{Postgres, Transaction};
+
+use crate::constants;
+
+pub struct BulkChunks<'a, T> {
+    pool: &'a sqlx::PgPool,
+    items: &'a [T],
+    chunk_size: usize,
+    position: usize,
+    cancel: Option<&'a AtomicBool>,
+}
+
+impl<'a, T> BulkChunks<'a, T> {
+    pub fn new(pool: &'a sqlx::PgPool, items: &'a [T], chunk_size: usize) -> Self {
+        assert!(chunk_size > 0, "chunk_size must be greater than 0.");
+
+        Self {
+            pool,
+            items,
+            chunk_size,
+            position: 0,
+            cancel: None,
+        }
+    }
+
+    /// Stop handing out chunks once `flag` is set (e.g. during shutdown).
+    pub fn cancel_on(mut self, flag: &'a AtomicBool) -> Self {
+        self.cancel = Some(flag);
+        self
+    }
+
+    /// Begins a transaction for the next chunk, with `BULK_STATEMENT_TIMEOUT`
+    /// applied. The caller is responsible for committing it. Returns `None`
+    /// once every chunk was handed out, or if the job was cancelled.
+    pub async fn next(
+        &mut self,
+    ) -> sqlx::Result<Option<(Transaction<'static, Postgres>, &'a [T])>> {
+        if self.is_complete() {
+            return Ok(None);
+        }
+        if self.position != 0 {
+            // let other tasks run between chunks
+            tokio::task::yield_now().await;
+        }
+        let Some(chunk) = self.take_chunk() else {
+            return Ok(None);
+        };
+
+        let tx = begin_bulk(self.pool).await?;
+
+        Ok(Some((tx, chunk)))
+    }
+
+    /// The next chunk, unless every chunk was handed out or the job was
+    /// cancelled.
+    fn take_chunk(&mut self) -> Option<&'a [T]> {
+        if self.is_complete() || self.cancel.is_some_and(|c| c.load(Ordering::Relaxed)) {
+            return None;
+        }
+
+        let end = (self.position + self.chunk_size).min(self.items.len());
+        let chunk = &self.items[self.position..end];
+        self.position = end;
+
+        Some(chunk)
+    }
+
+    /// The number of items handed out so far, and the total.
+    pub fn progress(&self) -> (usize, usize) {
+        (self.position, self.items.len())
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.position >= self.items.len()
+    }
+}
+
+/// Begins a transaction with `BULK_STATEMENT_TIMEOUT` applied, for jobs that
+/// must be written all at once but can still be split into several
+/// statements.
+pub async fn begin_bulk(pool: &sqlx::PgPool) -> sqlx::Result<Transaction<'static, Postgres>> {
+    let mut tx = pool.begin().await?;
+    sqlx::query(&format!(
+        "SET LOCAL statement_timeout = {}",
+        constants::BULK_STATEMENT_TIMEOUT.as_millis()
+    ))
+    .execute(&mut tx)
+    .await?;
+
+    Ok(tx)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pool() -> sqlx::PgPool {
+        // never connects, since only the chunking is tested
+        sqlx::PgPool::connect_lazy("postgres://localhost/starboard").unwrap()
+    }
+
+    fn chunks<T>(bulk: &mut BulkChunks<'_, T>) -> Vec<usize> {
+        std::iter::from_fn(|| bulk.take_chunk().map(<[T]>::len)).collect()
+    }
+
+    #[tokio::test]
+    async fn chunk_boundaries() {
+        let pool = pool();
+        let items: Vec<_> = (0..10).collect();
+
+        let mut bulk = BulkChunks::new(&pool, &items, 4);
+        assert_eq!(chunks(&mut bulk), vec![4, 4, 2]);
+        assert!(bulk.is_complete());
+        assert_eq!(bulk.progress(), (10, 10));
+
+        let mut bulk = BulkChunks::new(&pool, &items, 5);
+        assert_eq!(chunks(&mut bulk), vec![5, 5]);
+
+        let mut bulk = BulkChunks::new(&pool, &items[..0], 5);
+        assert!(chunks(&mut bulk).is_empty());
+    }
+
+    #[tokio::test]
+    async fn cancelled_between_chunks() {
+        let pool = pool();
+        let items: Vec<_> = (0..10).collect();
+        let cancel = AtomicBool::new(false);
+        let mut bulk = BulkChunks::new(&pool, &items, 4).cancel_on(&cancel);
+
+        assert_eq!(bulk.take_chunk(), Some(&items[..4]));
+        cancel.store(true, Ordering::Relaxed);
+
+        // the chunks handed out so far stay counted as progress
+        assert_eq!(bulk.take_chunk(), None);
+        assert_eq!(bulk.progress(), (4, 10));
+        assert!(!bulk.is_complete());
+    }
+}
@@ -8,7 +8,14 @@ macro_rules! call_with_autostar_settings {
             min_chars,
             max_chars,
             require_image,
-            delete_invalid
+            delete_invalid,
+            required_format_regex,
+            format_example,
+            max_attachments,
+            matches,
+            not_matches,
+            required_tags,
+            excluded_tags
         )
     };
 }
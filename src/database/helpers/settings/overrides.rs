@@ -5,21 +5,37 @@ macro_rules! call_with_override_settings {
                 $extra_arg,
             )*
             display_emoji,
+            display_emoji_tiers,
             ping_author,
             use_server_profile,
             extra_embeds,
             use_webhook,
+            translate_button,
+            vote_buttons,
+            show_votes_button,
             color,
             go_to_message,
             attachments_list,
             replied_to,
+            media_selection,
+            extract_media_links,
+            clone_attachments,
             required,
             required_remove,
+            min_unique_voters,
             upvote_emojis,
             downvote_emojis,
+            vote_emoji_weights,
             self_vote,
+            votes_on_post,
             allow_bots,
             require_image,
+            min_chars,
+            max_chars,
+            require_link,
+            allow_system_messages,
+            min_vote_spread_seconds,
+            max_posts_per_author_per_day,
             older_than,
             newer_than,
             matches,
@@ -28,9 +44,13 @@ macro_rules! call_with_override_settings {
             autoreact_upvote,
             autoreact_downvote,
             remove_invalid_reactions,
+            lock_vote_after_mod_removal,
             link_deletes,
             link_edits,
             on_delete,
+            recreate_deleted_posts,
+            attach_thread,
+            reply_with_link,
             cooldown_enabled,
             cooldown_count,
             cooldown_period,
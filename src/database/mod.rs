@@ -1,12 +1,18 @@
+pub mod bulk;
 pub mod helpers;
 pub mod models;
 pub mod validation;
 
 pub use models::{
-    autostar_channel::AutoStarChannel, exclusive_group::ExclusiveGroup, guild::DbGuild,
-    member::DbMember, message::DbMessage, patron::Patron, permrole::PermRole,
-    permrole_starboard::PermRoleStarboard, posrole::PosRole, starboard::Starboard,
+    autostar_channel::AutoStarChannel, blocked_voter::BlockedVoter, competition::Competition,
+    config_warning::ConfigWarning, deleted_post::DeletedPost, duplicate_post::DuplicatePost,
+    emoji_migration_audit::EmojiMigrationAudit, emoji_usage::EmojiUsage,
+    exclusive_group::ExclusiveGroup, feature_flag::FeatureFlag, guild::DbGuild, member::DbMember,
+    message::DbMessage, migration_cursor::MigrationCursor, mod_note::ModNote, patron::Patron,
+    pending_refresh::PendingRefresh, performed_action::PerformedAction, permrole::PermRole,
+    permrole_starboard::PermRoleStarboard, posrole::PosRole, post_latency::PostLatency,
+    reaction_cleanup::ReactionCleanup, shard_status::ShardStatus, starboard::Starboard,
     starboard_message::StarboardMessage, starboard_override::StarboardOverride,
     starboard_override_values::OverrideValues, starboard_settings::StarboardSettings, user::DbUser,
-    vote::Vote, xprole::XPRole,
+    vote::Vote, vote_lock::VoteLock, xp_season::XPSeason, xprole::XPRole,
 };
@@ -16,12 +16,33 @@ pub struct AutoStarChannel {
     pub guild_id: i64,
 
     pub premium_locked: bool,
+    /// The channel was deleted, so the autostar channel does nothing.
+    pub channel_deleted: bool,
 
     pub emojis: Vec<String>,
     pub min_chars: i16,
     pub max_chars: Option<i16>,
     pub require_image: bool,
     pub delete_invalid: bool,
+    /// (premium) Messages that don't match are treated like any other
+    /// invalid message. Checked before the other requirements.
+    pub required_format_regex: Option<String>,
+    /// Sent to users whose message didn't match `required_format_regex`.
+    pub format_example: Option<String>,
+    /// Messages with more attachments than this are invalid.
+    pub max_attachments: Option<i16>,
+    /// (premium) Messages must match this...
+    pub matches: Option<String>,
+    /// (premium) ...and must not match this.
+    pub not_matches: Option<String>,
+    /// Forum posts need one of these tags, and none of the excluded ones.
+    /// Other messages aren't affected.
+    pub required_tags: Vec<i64>,
+    pub excluded_tags: Vec<i64>,
+
+    /// Some of the emojis couldn't be parsed by `star migrate-emojis`, and
+    /// were kept as they were.
+    pub needs_attention: bool,
 }
 
 impl AutoStarChannel {
@@ -118,6 +139,38 @@ impl AutoStarChannel {
         .map(|r| r.count.unwrap())
     }
 
+    /// Whether the channel has an autostar channel that isn't premium-locked.
+    pub async fn exists_in_channel(pool: &sqlx::PgPool, channel_id: i64) -> sqlx::Result<bool> {
+        sqlx::query!(
+            r#"SELECT EXISTS(
+                SELECT 1 FROM autostar_channels
+                WHERE channel_id=$1 AND premium_locked=false AND channel_deleted=false
+            ) AS "exists!""#,
+            channel_id,
+        )
+        .fetch_one(pool)
+        .await
+        .map(|r| r.exists)
+    }
+
+    /// Marks the autostar channels in a channel as (no longer) deleted,
+    /// returning the names of the autostar channels that changed.
+    pub async fn set_channel_deleted(
+        pool: &sqlx::PgPool,
+        channel_id: i64,
+        channel_deleted: bool,
+    ) -> sqlx::Result<Vec<String>> {
+        sqlx::query!(
+            "UPDATE autostar_channels SET channel_deleted=$1
+            WHERE channel_id=$2 AND channel_deleted!=$1 RETURNING name",
+            channel_deleted,
+            channel_id,
+        )
+        .fetch_all(pool)
+        .await
+        .map(|rows| rows.into_iter().map(|r| r.name).collect())
+    }
+
     pub async fn list_by_channel(pool: &sqlx::PgPool, channel_id: i64) -> sqlx::Result<Vec<Self>> {
         sqlx::query_as!(
             Self,
@@ -143,6 +196,30 @@ impl AutoStarChannel {
         .await
     }
 
+    pub async fn list_all(pool: &sqlx::PgPool) -> sqlx::Result<Vec<Self>> {
+        sqlx::query_as!(Self, "SELECT * FROM autostar_channels ORDER BY id")
+            .fetch_all(pool)
+            .await
+    }
+
+    /// Rewrites the emojis of an autostar channel, for `star migrate-emojis`.
+    pub async fn set_emojis_raw(
+        pool: &sqlx::PgPool,
+        id: i32,
+        emojis: &[String],
+        needs_attention: bool,
+    ) -> sqlx::Result<()> {
+        sqlx::query!(
+            "UPDATE autostar_channels SET emojis=$1, needs_attention=$2 WHERE id=$3",
+            emojis,
+            needs_attention,
+            id,
+        )
+        .execute(pool)
+        .await
+        .map(|_| ())
+    }
+
     // validation
     pub fn set_emojis(&mut self, val: Vec<String>, premium: bool) -> Result<(), String> {
         let limit = if premium {
@@ -206,4 +283,32 @@ impl AutoStarChannel {
             }
         }
     }
+
+    pub fn set_max_attachments(&mut self, val: Option<i16>) -> Result<(), String> {
+        match val {
+            Some(val) if val < 0 => Err("`max-attachments` cannot be less than 0.".to_string()),
+            Some(val) if val > constants::MAX_MAX_ATTACHMENTS => Err(format!(
+                "`max-attachments` cannot be greater than {}.",
+                constants::MAX_MAX_ATTACHMENTS
+            )),
+            val => {
+                self.max_attachments = val;
+                Ok(())
+            }
+        }
+    }
+
+    pub fn set_format_example(&mut self, val: Option<String>) -> Result<(), String> {
+        if let Some(val) = &val {
+            if val.chars().count() > constants::MAX_FORMAT_EXAMPLE_LENGTH {
+                return Err(format!(
+                    "`format-example` cannot be longer than {} characters.",
+                    constants::MAX_FORMAT_EXAMPLE_LENGTH
+                ));
+            }
+        }
+
+        self.format_example = val;
+        Ok(())
+    }
 }
@@ -0,0 +1,107 @@
+use std::sync::atomic::AtomicBool;
+
+use chrono::{DateTime, Utc};
+
+use crate::{constants, database::bulk::BulkChunks};
+
+/// A user whose votes never count in a guild.
+#[derive(Debug)]
+pub struct BlockedVoter {
+    pub guild_id: i64,
+    pub user_id: i64,
+    /// Whether their vote reactions are removed, instead of just ignored.
+    pub remove_reactions: bool,
+    pub blocked_by: i64,
+    pub created_at: DateTime<Utc>,
+}
+
+impl BlockedVoter {
+    /// Blocks the user, or updates `remove_reactions` if they already are.
+    pub async fn create(
+        pool: &sqlx::PgPool,
+        guild_id: i64,
+        user_id: i64,
+        remove_reactions: bool,
+        blocked_by: i64,
+    ) -> sqlx::Result<Self> {
+        sqlx::query_as!(
+            Self,
+            "INSERT INTO blocked_voters (guild_id, user_id, remove_reactions, blocked_by)
+            VALUES ($1, $2, $3, $4) ON CONFLICT (guild_id, user_id)
+            DO UPDATE SET remove_reactions=$3 RETURNING *",
+            guild_id,
+            user_id,
+            remove_reactions,
+            blocked_by,
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    /// Blocks many users at once, skipping users that are already blocked.
+    /// Returns the number of users that were handled, which is less than the
+    /// total if `cancel` was set.
+    pub async fn create_bulk(
+        pool: &sqlx::PgPool,
+        guild_id: i64,
+        user_ids: &[i64],
+        remove_reactions: bool,
+        blocked_by: i64,
+        cancel: &AtomicBool,
+    ) -> sqlx::Result<usize> {
+        let mut chunks =
+            BulkChunks::new(pool, user_ids, constants::BULK_CHUNK_SIZE).cancel_on(cancel);
+
+        while let Some((mut tx, chunk)) = chunks.next().await? {
+            sqlx::query!(
+                "INSERT INTO blocked_voters (guild_id, user_id, remove_reactions, blocked_by)
+                SELECT $1, UNNEST($2::BIGINT[]), $3, $4 ON CONFLICT (guild_id, user_id) DO NOTHING",
+                guild_id,
+                chunk,
+                remove_reactions,
+                blocked_by,
+            )
+            .execute(&mut tx)
+            .await?;
+
+            tx.commit().await?;
+        }
+
+        Ok(chunks.progress().0)
+    }
+
+    pub async fn delete(
+        pool: &sqlx::PgPool,
+        guild_id: i64,
+        user_id: i64,
+    ) -> sqlx::Result<Option<Self>> {
+        sqlx::query_as!(
+            Self,
+            "DELETE FROM blocked_voters WHERE guild_id=$1 AND user_id=$2 RETURNING *",
+            guild_id,
+            user_id,
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn list_by_guild(pool: &sqlx::PgPool, guild_id: i64) -> sqlx::Result<Vec<Self>> {
+        sqlx::query_as!(
+            Self,
+            "SELECT * FROM blocked_voters WHERE guild_id=$1 ORDER BY created_at",
+            guild_id,
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn count_by_guild(pool: &sqlx::PgPool, guild_id: i64) -> sqlx::Result<i64> {
+        sqlx::query!(
+            r#"SELECT COUNT(*) AS "count!" FROM blocked_voters WHERE guild_id=$1"#,
+            guild_id,
+        )
+        .fetch_one(pool)
+        .await
+        .map(|r| r.count)
+    }
+}
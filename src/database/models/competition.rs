@@ -0,0 +1,202 @@
+use chrono::{DateTime, Utc};
+
+/// A time-boxed contest for the posts that gain the most points on a
+/// starboard.
+#[derive(Debug)]
+pub struct Competition {
+    pub id: i32,
+    pub guild_id: i64,
+    pub starboard_id: i32,
+    pub announce_channel_id: i64,
+    pub prize: Option<String>,
+    pub starts_at: DateTime<Utc>,
+    pub ends_at: DateTime<Utc>,
+    /// Set once the winners were recorded.
+    pub finished: bool,
+}
+
+/// A vote cast during a competition.
+#[derive(Debug, Clone)]
+pub struct CompetitionVote {
+    pub message_id: i64,
+    pub is_downvote: bool,
+    pub weight: i16,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug)]
+pub struct CompetitionWinner {
+    pub competition_id: i32,
+    pub place: i16,
+    pub message_id: i64,
+    pub channel_id: i64,
+    pub author_id: i64,
+    pub points: i32,
+}
+
+impl Competition {
+    pub async fn create(
+        con: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        guild_id: i64,
+        starboard_id: i32,
+        announce_channel_id: i64,
+        prize: Option<&str>,
+        starts_at: DateTime<Utc>,
+        ends_at: DateTime<Utc>,
+    ) -> sqlx::Result<Self> {
+        sqlx::query_as!(
+            Self,
+            "INSERT INTO competitions
+            (guild_id, starboard_id, announce_channel_id, prize, starts_at, ends_at)
+            VALUES ($1, $2, $3, $4, $5, $6) RETURNING *",
+            guild_id,
+            starboard_id,
+            announce_channel_id,
+            prize,
+            starts_at,
+            ends_at,
+        )
+        .fetch_one(con)
+        .await
+    }
+
+    /// Keeps other transactions from creating competitions on the starboard
+    /// until this one ends, so that two overlapping ones can't both pass
+    /// the overlap check.
+    pub async fn lock_starboard(
+        con: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        starboard_id: i32,
+    ) -> sqlx::Result<()> {
+        sqlx::query!(
+            "SELECT FROM starboards WHERE id=$1 FOR NO KEY UPDATE",
+            starboard_id
+        )
+        .execute(con)
+        .await?;
+
+        Ok(())
+    }
+
+    /// The competitions on a starboard that end after `after`, which are
+    /// the only ones a competition starting then can overlap with.
+    pub async fn list_by_starboard_ending_after(
+        con: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        starboard_id: i32,
+        after: DateTime<Utc>,
+    ) -> sqlx::Result<Vec<Self>> {
+        sqlx::query_as!(
+            Self,
+            "SELECT * FROM competitions WHERE starboard_id=$1 AND ends_at > $2
+            ORDER BY starts_at",
+            starboard_id,
+            after,
+        )
+        .fetch_all(con)
+        .await
+    }
+
+    pub async fn count_unfinished_by_guild(
+        con: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        guild_id: i64,
+    ) -> sqlx::Result<i64> {
+        sqlx::query!(
+            "SELECT count(*) as count FROM competitions WHERE guild_id=$1 AND finished=false",
+            guild_id,
+        )
+        .fetch_one(con)
+        .await
+        .map(|r| r.count.unwrap())
+    }
+
+    pub async fn list_finished_by_guild(
+        pool: &sqlx::PgPool,
+        guild_id: i64,
+        limit: i64,
+    ) -> sqlx::Result<Vec<Self>> {
+        sqlx::query_as!(
+            Self,
+            "SELECT * FROM competitions WHERE guild_id=$1 AND finished=true
+            ORDER BY ends_at DESC LIMIT $2",
+            guild_id,
+            limit,
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Competitions that have ended but don't have winners yet.
+    pub async fn list_due(pool: &sqlx::PgPool) -> sqlx::Result<Vec<Self>> {
+        sqlx::query_as!(
+            Self,
+            "SELECT * FROM competitions WHERE finished=false AND ends_at <= now()"
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// The votes cast on `vote_starboard_ids` during the competition, on
+    /// messages that aren't trashed and have a post on the starboard.
+    /// Points a post had before the competition started aren't included.
+    pub async fn list_votes(
+        &self,
+        pool: &sqlx::PgPool,
+        vote_starboard_ids: &[i32],
+    ) -> sqlx::Result<Vec<CompetitionVote>> {
+        sqlx::query_as!(
+            CompetitionVote,
+            "SELECT v.message_id, v.is_downvote, v.weight, v.created_at FROM votes v
+            JOIN messages m ON m.message_id = v.message_id
+            WHERE v.starboard_id = ANY($1) AND v.created_at >= $2 AND v.created_at < $3
+            AND m.trashed = false AND EXISTS (
+                SELECT 1 FROM starboard_messages s
+                WHERE s.message_id = v.message_id AND s.starboard_id = $4
+            )",
+            vote_starboard_ids,
+            self.starts_at,
+            self.ends_at,
+            self.starboard_id,
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Records the winners and marks the competition as finished.
+    pub async fn finish(
+        &self,
+        pool: &sqlx::PgPool,
+        winners: &[CompetitionWinner],
+    ) -> sqlx::Result<()> {
+        let mut tx = pool.begin().await?;
+
+        for winner in winners {
+            sqlx::query!(
+                "INSERT INTO competition_winners
+                (competition_id, place, message_id, channel_id, author_id, points)
+                VALUES ($1, $2, $3, $4, $5, $6) ON CONFLICT DO NOTHING",
+                winner.competition_id,
+                winner.place,
+                winner.message_id,
+                winner.channel_id,
+                winner.author_id,
+                winner.points,
+            )
+            .execute(&mut tx)
+            .await?;
+        }
+        sqlx::query!("UPDATE competitions SET finished=true WHERE id=$1", self.id)
+            .execute(&mut tx)
+            .await?;
+
+        tx.commit().await
+    }
+
+    pub async fn list_winners(&self, pool: &sqlx::PgPool) -> sqlx::Result<Vec<CompetitionWinner>> {
+        sqlx::query_as!(
+            CompetitionWinner,
+            "SELECT * FROM competition_winners WHERE competition_id=$1 ORDER BY place",
+            self.id,
+        )
+        .fetch_all(pool)
+        .await
+    }
+}
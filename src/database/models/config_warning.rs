@@ -0,0 +1,56 @@
+use chrono::{DateTime, Utc};
+
+/// A setting of a starboard that can't work in its channel anymore, like
+/// webhooks without the Manage Webhooks permission. See `core::config_drift`.
+#[derive(Debug)]
+pub struct ConfigWarning {
+    pub starboard_id: i32,
+    pub guild_id: i64,
+    pub kind: String,
+    pub created_at: DateTime<Utc>,
+}
+
+impl ConfigWarning {
+    /// Replaces the warnings of a starboard with `kinds`. Warnings that are
+    /// still found keep their `created_at`.
+    pub async fn set_for_starboard(
+        pool: &sqlx::PgPool,
+        starboard_id: i32,
+        guild_id: i64,
+        kinds: &[String],
+    ) -> sqlx::Result<()> {
+        let mut tx = pool.begin().await?;
+
+        sqlx::query!(
+            "DELETE FROM config_warnings WHERE starboard_id=$1 AND NOT kind=ANY($2)",
+            starboard_id,
+            kinds,
+        )
+        .execute(&mut tx)
+        .await?;
+        sqlx::query!(
+            "INSERT INTO config_warnings (starboard_id, guild_id, kind)
+            SELECT $1, $2, UNNEST($3::TEXT[]) ON CONFLICT DO NOTHING",
+            starboard_id,
+            guild_id,
+            kinds,
+        )
+        .execute(&mut tx)
+        .await?;
+
+        tx.commit().await
+    }
+
+    pub async fn list_by_starboard(
+        pool: &sqlx::PgPool,
+        starboard_id: i32,
+    ) -> sqlx::Result<Vec<Self>> {
+        sqlx::query_as!(
+            Self,
+            "SELECT * FROM config_warnings WHERE starboard_id=$1 ORDER BY kind",
+            starboard_id,
+        )
+        .fetch_all(pool)
+        .await
+    }
+}
@@ -0,0 +1,65 @@
+use chrono::{DateTime, Utc};
+
+/// A post that was deleted by hand, rather than by the bot. Whether it's sent
+/// again depends on the starboard's `recreate_deleted_posts` setting.
+#[derive(Debug)]
+pub struct DeletedPost {
+    /// The original message.
+    pub message_id: i64,
+    pub starboard_id: i32,
+    /// The post that was deleted.
+    pub starboard_message_id: i64,
+    pub created_at: DateTime<Utc>,
+}
+
+impl DeletedPost {
+    pub async fn create(
+        pool: &sqlx::PgPool,
+        message_id: i64,
+        starboard_id: i32,
+        starboard_message_id: i64,
+    ) -> sqlx::Result<Self> {
+        sqlx::query_as!(
+            Self,
+            "INSERT INTO deleted_posts (message_id, starboard_id, starboard_message_id)
+            VALUES ($1, $2, $3) ON CONFLICT (message_id, starboard_id)
+            DO UPDATE SET starboard_message_id=$3, created_at=NOW() RETURNING *",
+            message_id,
+            starboard_id,
+            starboard_message_id,
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn delete(
+        pool: &sqlx::PgPool,
+        message_id: i64,
+        starboard_id: i32,
+    ) -> sqlx::Result<Option<Self>> {
+        sqlx::query_as!(
+            Self,
+            "DELETE FROM deleted_posts WHERE message_id=$1 AND starboard_id=$2 RETURNING *",
+            message_id,
+            starboard_id,
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn exists(
+        pool: &sqlx::PgPool,
+        message_id: i64,
+        starboard_id: i32,
+    ) -> sqlx::Result<bool> {
+        let row = sqlx::query!(
+            "SELECT EXISTS(SELECT 1 FROM deleted_posts WHERE message_id=$1 AND starboard_id=$2)",
+            message_id,
+            starboard_id,
+        )
+        .fetch_one(pool)
+        .await?;
+
+        Ok(row.exists.unwrap_or(false))
+    }
+}
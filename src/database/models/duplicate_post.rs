@@ -0,0 +1,46 @@
+/// A newer post for a message that already had one on the same starboard.
+/// The rows are queued by a migration, and the Discord messages are deleted
+/// by `core::starboard::dedupe`.
+#[derive(Debug)]
+pub struct DuplicatePost {
+    pub starboard_message_id: i64,
+    /// The (oldest) post that was kept.
+    pub kept_starboard_message_id: i64,
+    pub starboard_id: i32,
+    pub message_id: i64,
+    pub guild_id: i64,
+    pub channel_id: i64,
+}
+
+impl DuplicatePost {
+    /// Queued posts after `after`, optionally only for one guild.
+    pub async fn list_after(
+        pool: &sqlx::PgPool,
+        guild_id: Option<i64>,
+        after: i64,
+        limit: i64,
+    ) -> sqlx::Result<Vec<Self>> {
+        sqlx::query_as!(
+            Self,
+            "SELECT * FROM duplicate_posts
+            WHERE starboard_message_id > $1 AND ($2::bigint IS NULL OR guild_id=$2)
+            ORDER BY starboard_message_id LIMIT $3",
+            after,
+            guild_id,
+            limit,
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn delete(pool: &sqlx::PgPool, starboard_message_id: i64) -> sqlx::Result<()> {
+        sqlx::query!(
+            "DELETE FROM duplicate_posts WHERE starboard_message_id=$1",
+            starboard_message_id,
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,41 @@
+/// A setting that `star migrate-emojis` rewrote, so the change can be traced
+/// (or undone by hand) later.
+#[derive(Debug)]
+pub struct EmojiMigrationAudit {
+    pub id: i32,
+    pub guild_id: i64,
+    /// "starboard", "override" or "autostar".
+    pub kind: String,
+    pub row_id: i32,
+    pub setting: String,
+    pub old_value: Vec<String>,
+    pub new_value: Vec<String>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl EmojiMigrationAudit {
+    pub async fn create(
+        pool: &sqlx::PgPool,
+        guild_id: i64,
+        kind: &str,
+        row_id: i32,
+        setting: &str,
+        old_value: &[String],
+        new_value: &[String],
+    ) -> sqlx::Result<()> {
+        sqlx::query!(
+            "INSERT INTO emoji_migration_audit
+            (guild_id, kind, row_id, setting, old_value, new_value)
+            VALUES ($1, $2, $3, $4, $5, $6)",
+            guild_id,
+            kind,
+            row_id,
+            setting,
+            old_value,
+            new_value,
+        )
+        .execute(pool)
+        .await
+        .map(|_| ())
+    }
+}
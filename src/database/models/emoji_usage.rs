@@ -0,0 +1,44 @@
+/// How many votes were cast with an emoji on a starboard. Rows are kept when
+/// the emoji is removed from the starboard.
+#[derive(Debug)]
+pub struct EmojiUsage {
+    pub starboard_id: i32,
+    pub emoji: String,
+    pub count: i64,
+}
+
+impl EmojiUsage {
+    /// Adds to the counts. The slices are zipped together.
+    pub async fn add_batch(
+        pool: &sqlx::PgPool,
+        starboard_ids: &[i32],
+        emojis: &[String],
+        counts: &[i64],
+    ) -> sqlx::Result<()> {
+        sqlx::query!(
+            "INSERT INTO emoji_usage (starboard_id, emoji, count)
+            SELECT * FROM UNNEST($1::INT[], $2::TEXT[], $3::BIGINT[])
+            ON CONFLICT (starboard_id, emoji) DO UPDATE SET count = emoji_usage.count + EXCLUDED.count",
+            starboard_ids,
+            emojis,
+            counts,
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn list_by_starboard(
+        pool: &sqlx::PgPool,
+        starboard_id: i32,
+    ) -> sqlx::Result<Vec<Self>> {
+        sqlx::query_as!(
+            Self,
+            "SELECT * FROM emoji_usage WHERE starboard_id=$1 ORDER BY count DESC",
+            starboard_id,
+        )
+        .fetch_all(pool)
+        .await
+    }
+}
@@ -0,0 +1,94 @@
+/// The rollout of a feature, see `core::features`. Features without a row
+/// use their default rollout.
+#[derive(Debug)]
+pub struct FeatureFlag {
+    pub name: String,
+    /// The percentage of guilds that have the feature, 0-100.
+    pub rollout: i16,
+    /// Guilds that have the feature regardless of `rollout`.
+    pub allowlist: Vec<i64>,
+    /// Guilds that never have the feature. Takes precedence over `allowlist`.
+    pub denylist: Vec<i64>,
+}
+
+impl FeatureFlag {
+    pub async fn create(pool: &sqlx::PgPool, name: &str, rollout: i16) -> sqlx::Result<()> {
+        sqlx::query!(
+            "INSERT INTO feature_flags (name, rollout) VALUES ($1, $2)
+            ON CONFLICT DO NOTHING",
+            name,
+            rollout,
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn get(pool: &sqlx::PgPool, name: &str) -> sqlx::Result<Option<Self>> {
+        sqlx::query_as!(Self, "SELECT * FROM feature_flags WHERE name=$1", name)
+            .fetch_optional(pool)
+            .await
+    }
+
+    pub async fn list_all(pool: &sqlx::PgPool) -> sqlx::Result<Vec<Self>> {
+        sqlx::query_as!(Self, "SELECT * FROM feature_flags ORDER BY name")
+            .fetch_all(pool)
+            .await
+    }
+
+    pub async fn set_rollout(pool: &sqlx::PgPool, name: &str, rollout: i16) -> sqlx::Result<()> {
+        sqlx::query!(
+            "INSERT INTO feature_flags (name, rollout) VALUES ($1, $2)
+            ON CONFLICT (name) DO UPDATE SET rollout=$2",
+            name,
+            rollout,
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Moves the guild to the allowlist. The row must exist.
+    pub async fn allow(pool: &sqlx::PgPool, name: &str, guild_id: i64) -> sqlx::Result<()> {
+        sqlx::query!(
+            "UPDATE feature_flags SET allowlist=array_append(array_remove(allowlist, $2), $2),
+            denylist=array_remove(denylist, $2) WHERE name=$1",
+            name,
+            guild_id,
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Moves the guild to the denylist. The row must exist.
+    pub async fn deny(pool: &sqlx::PgPool, name: &str, guild_id: i64) -> sqlx::Result<()> {
+        sqlx::query!(
+            "UPDATE feature_flags SET denylist=array_append(array_remove(denylist, $2), $2),
+            allowlist=array_remove(allowlist, $2) WHERE name=$1",
+            name,
+            guild_id,
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Removes the guild from both lists, so the rollout applies to it again.
+    pub async fn unlist(pool: &sqlx::PgPool, name: &str, guild_id: i64) -> sqlx::Result<()> {
+        sqlx::query!(
+            "UPDATE feature_flags SET allowlist=array_remove(allowlist, $2),
+            denylist=array_remove(denylist, $2) WHERE name=$1",
+            name,
+            guild_id,
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(())
+    }
+}
@@ -4,6 +4,27 @@ use chrono::{DateTime, Utc};
 pub struct DbGuild {
     pub guild_id: i64,
     pub premium_end: Option<DateTime<Utc>>,
+    /// A `Locale` code.
+    pub locale: String,
+    /// Qualified command name -> "public" or "ephemeral".
+    pub command_visibility_overrides: serde_json::Value,
+    /// Messages from these webhooks are never starboarded or autostarred.
+    pub ignored_webhook_ids: Vec<i64>,
+    /// Same as `ignored_webhook_ids`, for bots and applications.
+    pub ignored_application_ids: Vec<i64>,
+    /// The current XP season, starting at 1.
+    pub xp_season: i16,
+    /// The percentage of XP members keep when a new season starts.
+    pub xp_carryover: i16,
+    /// Where the top members of a season are announced when it ends.
+    pub xp_season_channel_id: Option<i64>,
+    /// When the bot was removed from the guild. The guild's data is purged
+    /// once this is older than `GUILD_RETENTION_DAYS`.
+    pub left_at: Option<DateTime<Utc>>,
+    /// Hashed with the voter's id for votes on anonymous starboards.
+    pub vote_salt: i64,
+    /// Where notable events in the guild are logged, see `core::guild_log`.
+    pub log_channel_id: Option<i64>,
 }
 
 impl DbGuild {
@@ -22,4 +43,173 @@ impl DbGuild {
             .fetch_optional(pool)
             .await
     }
+
+    pub async fn delete(pool: &sqlx::PgPool, guild_id: i64) -> sqlx::Result<Option<Self>> {
+        sqlx::query_as!(
+            Self,
+            "DELETE FROM guilds WHERE guild_id=$1 RETURNING *",
+            guild_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn set_left_at(
+        pool: &sqlx::PgPool,
+        guild_id: i64,
+        left_at: Option<DateTime<Utc>>,
+    ) -> sqlx::Result<()> {
+        sqlx::query!(
+            "UPDATE guilds SET left_at=$1 WHERE guild_id=$2
+            AND left_at IS DISTINCT FROM $1",
+            left_at,
+            guild_id,
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Guilds the bot left before `before`.
+    pub async fn list_left_before(
+        pool: &sqlx::PgPool,
+        before: DateTime<Utc>,
+    ) -> sqlx::Result<Vec<i64>> {
+        sqlx::query!(
+            "SELECT guild_id FROM guilds WHERE left_at < $1 ORDER BY left_at",
+            before,
+        )
+        .fetch_all(pool)
+        .await
+        .map(|rows| rows.into_iter().map(|r| r.guild_id).collect())
+    }
+
+    pub async fn set_locale(pool: &sqlx::PgPool, guild_id: i64, locale: &str) -> sqlx::Result<()> {
+        sqlx::query!(
+            "UPDATE guilds SET locale=$1 WHERE guild_id=$2",
+            locale,
+            guild_id,
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn set_command_visibility_overrides(
+        pool: &sqlx::PgPool,
+        guild_id: i64,
+        overrides: serde_json::Value,
+    ) -> sqlx::Result<()> {
+        sqlx::query!(
+            "UPDATE guilds SET command_visibility_overrides=$1 WHERE guild_id=$2",
+            overrides,
+            guild_id,
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn set_ignored_webhook_ids(
+        pool: &sqlx::PgPool,
+        guild_id: i64,
+        webhook_ids: &[i64],
+    ) -> sqlx::Result<()> {
+        sqlx::query!(
+            "UPDATE guilds SET ignored_webhook_ids=$1 WHERE guild_id=$2",
+            webhook_ids,
+            guild_id,
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn set_ignored_application_ids(
+        pool: &sqlx::PgPool,
+        guild_id: i64,
+        application_ids: &[i64],
+    ) -> sqlx::Result<()> {
+        sqlx::query!(
+            "UPDATE guilds SET ignored_application_ids=$1 WHERE guild_id=$2",
+            application_ids,
+            guild_id,
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn set_xp_carryover(
+        pool: &sqlx::PgPool,
+        guild_id: i64,
+        xp_carryover: i16,
+    ) -> sqlx::Result<()> {
+        sqlx::query!(
+            "UPDATE guilds SET xp_carryover=$1 WHERE guild_id=$2",
+            xp_carryover,
+            guild_id,
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn get_vote_salt(pool: &sqlx::PgPool, guild_id: i64) -> sqlx::Result<Option<i64>> {
+        sqlx::query!("SELECT vote_salt FROM guilds WHERE guild_id=$1", guild_id)
+            .fetch_optional(pool)
+            .await
+            .map(|r| r.map(|r| r.vote_salt))
+    }
+
+    pub async fn set_xp_season_channel_id(
+        pool: &sqlx::PgPool,
+        guild_id: i64,
+        channel_id: Option<i64>,
+    ) -> sqlx::Result<()> {
+        sqlx::query!(
+            "UPDATE guilds SET xp_season_channel_id=$1 WHERE guild_id=$2",
+            channel_id,
+            guild_id,
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn get_log_channel_id(
+        pool: &sqlx::PgPool,
+        guild_id: i64,
+    ) -> sqlx::Result<Option<i64>> {
+        sqlx::query!(
+            "SELECT log_channel_id FROM guilds WHERE guild_id=$1",
+            guild_id
+        )
+        .fetch_optional(pool)
+        .await
+        .map(|r| r.and_then(|r| r.log_channel_id))
+    }
+
+    pub async fn set_log_channel_id(
+        pool: &sqlx::PgPool,
+        guild_id: i64,
+        channel_id: Option<i64>,
+    ) -> sqlx::Result<()> {
+        sqlx::query!(
+            "UPDATE guilds SET log_channel_id=$1 WHERE guild_id=$2",
+            channel_id,
+            guild_id,
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
 }
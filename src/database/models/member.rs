@@ -1,11 +1,23 @@
+use std::{
+    collections::HashMap,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
 use futures::stream::BoxStream;
 
+use crate::{constants, database::bulk::begin_bulk};
+
 #[derive(Debug)]
 pub struct DbMember {
     pub user_id: i64,
     pub guild_id: i64,
     pub xp: f32,
     pub autoredeem_enabled: bool,
+    /// XP imported from another bot, which is added on top of vote-based XP.
+    pub imported_xp: f32,
+    /// The lifetime XP the member had when the current season started, minus
+    /// any carryover. `xp` is the lifetime XP minus this.
+    pub season_start_xp: f32,
 }
 
 impl DbMember {
@@ -42,6 +54,82 @@ impl DbMember {
         .await
     }
 
+    /// Creates any missing users/members and sets their XP. Each change is
+    /// `(user_id, xp, delta)`, where `delta` is added to `imported_xp` so that
+    /// the XP survives later refreshes. The changes are written in chunks, but
+    /// all in one transaction, so an import is either applied completely or
+    /// not at all. Returns false if `cancel` was set partway through, in which
+    /// case nothing was written.
+    pub async fn import_xp(
+        pool: &sqlx::PgPool,
+        guild_id: i64,
+        changes: &[(i64, f32, f32)],
+        cancel: &AtomicBool,
+    ) -> sqlx::Result<bool> {
+        let mut tx = begin_bulk(pool).await?;
+
+        for chunk in changes.chunks(constants::BULK_CHUNK_SIZE) {
+            if cancel.load(Ordering::Relaxed) {
+                // dropping the transaction rolls it back
+                return Ok(false);
+            }
+
+            let user_ids: Vec<_> = chunk.iter().map(|c| c.0).collect();
+            let xp: Vec<_> = chunk.iter().map(|c| c.1).collect();
+            let deltas: Vec<_> = chunk.iter().map(|c| c.2).collect();
+
+            sqlx::query!(
+                "INSERT INTO users (user_id, is_bot) SELECT UNNEST($1::BIGINT[]), false
+                ON CONFLICT DO NOTHING",
+                &user_ids,
+            )
+            .execute(&mut tx)
+            .await?;
+            sqlx::query!(
+                "INSERT INTO members (user_id, guild_id) SELECT UNNEST($1::BIGINT[]), $2
+                ON CONFLICT DO NOTHING",
+                &user_ids,
+                guild_id,
+            )
+            .execute(&mut tx)
+            .await?;
+            sqlx::query!(
+                "UPDATE members SET xp=data.xp, imported_xp=members.imported_xp + data.delta
+                FROM UNNEST($2::BIGINT[], $3::REAL[], $4::REAL[]) AS data(user_id, xp, delta)
+                WHERE members.guild_id=$1 AND members.user_id=data.user_id",
+                guild_id,
+                &user_ids,
+                &xp,
+                &deltas,
+            )
+            .execute(&mut tx)
+            .await?;
+
+            // let other tasks run between chunks
+            tokio::task::yield_now().await;
+        }
+
+        tx.commit().await?;
+
+        Ok(true)
+    }
+
+    pub async fn list_xp_by_users(
+        pool: &sqlx::PgPool,
+        guild_id: i64,
+        user_ids: &[i64],
+    ) -> sqlx::Result<HashMap<i64, f32>> {
+        let rows = sqlx::query!(
+            "SELECT user_id, xp FROM members WHERE guild_id=$1 AND user_id=ANY($2)",
+            guild_id,
+            user_ids,
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(rows.into_iter().map(|r| (r.user_id, r.xp)).collect())
+    }
+
     pub async fn set_autoredeem_enabled(
         pool: &sqlx::PgPool,
         user_id: i64,
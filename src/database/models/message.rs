@@ -1,6 +1,6 @@
 use crate::database::StarboardMessage;
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct DbMessage {
     pub message_id: i64,
     pub guild_id: i64,
@@ -69,6 +69,85 @@ impl DbMessage {
         .await
     }
 
+    pub async fn set_nsfw(
+        pool: &sqlx::PgPool,
+        message_id: i64,
+        is_nsfw: bool,
+    ) -> sqlx::Result<Option<Self>> {
+        sqlx::query_as!(
+            Self,
+            "UPDATE messages SET is_nsfw=$1 WHERE message_id=$2 RETURNING *",
+            is_nsfw,
+            message_id,
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// The most recent messages in these channels that have votes, newest
+    /// first.
+    pub async fn list_recent_voted(
+        pool: &sqlx::PgPool,
+        channel_ids: &[i64],
+        limit: i64,
+    ) -> sqlx::Result<Vec<Self>> {
+        sqlx::query_as!(
+            Self,
+            "SELECT * FROM messages WHERE channel_id=ANY($1) AND EXISTS(
+                SELECT 1 FROM votes WHERE votes.message_id=messages.message_id
+            ) ORDER BY message_id DESC LIMIT $2",
+            channel_ids,
+            limit,
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Messages with at least `min_points` on a starboard but no post on it,
+    /// e.g. because they qualified while it was disabled.
+    pub async fn list_unposted(
+        pool: &sqlx::PgPool,
+        starboard_id: i32,
+        min_points: i64,
+        limit: i64,
+    ) -> sqlx::Result<Vec<Self>> {
+        sqlx::query_as!(
+            Self,
+            "SELECT * FROM messages WHERE trashed=false AND message_id IN (
+                SELECT message_id FROM votes WHERE starboard_id=$1 GROUP BY message_id
+                HAVING SUM(CASE WHEN is_downvote THEN -weight ELSE weight END) >= $2
+            ) AND NOT EXISTS(
+                SELECT 1 FROM starboard_messages
+                WHERE starboard_messages.message_id=messages.message_id
+                AND starboard_messages.starboard_id=$1
+            ) ORDER BY message_id DESC LIMIT $3",
+            starboard_id,
+            min_points,
+            limit,
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Deletes up to `limit` of a guild's messages, returning how many were
+    /// deleted.
+    pub async fn delete_batch_by_guild(
+        pool: &sqlx::PgPool,
+        guild_id: i64,
+        limit: i64,
+    ) -> sqlx::Result<u64> {
+        sqlx::query!(
+            "DELETE FROM messages WHERE message_id IN (
+                SELECT message_id FROM messages WHERE guild_id=$1 LIMIT $2
+            )",
+            guild_id,
+            limit,
+        )
+        .execute(pool)
+        .await
+        .map(|r| r.rows_affected())
+    }
+
     pub async fn set_trashed(
         pool: &sqlx::PgPool,
         message_id: i64,
@@ -86,6 +165,26 @@ impl DbMessage {
         .await
     }
 
+    /// Trashes all of a user's messages in a guild, returning how many were
+    /// trashed.
+    pub async fn trash_by_author(
+        pool: &sqlx::PgPool,
+        guild_id: i64,
+        author_id: i64,
+        reason: &str,
+    ) -> sqlx::Result<u64> {
+        sqlx::query!(
+            "UPDATE messages SET trashed=true, trash_reason=$1
+            WHERE guild_id=$2 AND author_id=$3 AND trashed=false",
+            reason,
+            guild_id,
+            author_id,
+        )
+        .execute(pool)
+        .await
+        .map(|r| r.rows_affected())
+    }
+
     pub async fn list_trashed(pool: &sqlx::PgPool, guild_id: i64) -> sqlx::Result<Vec<Self>> {
         sqlx::query_as!(
             Self,
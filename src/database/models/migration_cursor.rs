@@ -0,0 +1,62 @@
+/// Tracks how far `/utils migrate` got through an old starboard channel, so
+/// that it can pick up where it left off.
+#[derive(Debug)]
+pub struct MigrationCursor {
+    pub channel_id: i64,
+    pub guild_id: i64,
+    pub starboard_id: i32,
+    /// The oldest message scanned so far.
+    pub before_id: Option<i64>,
+    pub scanned: i32,
+    pub imported: i32,
+    pub finished: bool,
+}
+
+impl MigrationCursor {
+    /// Gets the cursor for a channel, creating it if it doesn't exist.
+    pub async fn get_or_create(
+        pool: &sqlx::PgPool,
+        channel_id: i64,
+        guild_id: i64,
+        starboard_id: i32,
+    ) -> sqlx::Result<Self> {
+        sqlx::query_as!(
+            Self,
+            "INSERT INTO migration_cursors (channel_id, guild_id, starboard_id)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (channel_id) DO UPDATE SET starboard_id=$3
+            RETURNING *",
+            channel_id,
+            guild_id,
+            starboard_id,
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn delete(pool: &sqlx::PgPool, channel_id: i64) -> sqlx::Result<Option<Self>> {
+        sqlx::query_as!(
+            Self,
+            "DELETE FROM migration_cursors WHERE channel_id=$1 RETURNING *",
+            channel_id,
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn save(&self, pool: &sqlx::PgPool) -> sqlx::Result<()> {
+        sqlx::query!(
+            "UPDATE migration_cursors SET before_id=$1, scanned=$2, imported=$3, finished=$4
+            WHERE channel_id=$5",
+            self.before_id,
+            self.scanned,
+            self.imported,
+            self.finished,
+            self.channel_id,
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+}
@@ -1,15 +1,30 @@
 pub mod autostar_channel;
 pub mod autostar_channel_filter_group;
+pub mod blocked_voter;
+pub mod competition;
+pub mod config_warning;
+pub mod deleted_post;
+pub mod duplicate_post;
+pub mod emoji_migration_audit;
+pub mod emoji_usage;
 pub mod exclusive_group;
+pub mod feature_flag;
 pub mod filter;
 pub mod filter_group;
 pub mod guild;
 pub mod member;
 pub mod message;
+pub mod migration_cursor;
+pub mod mod_note;
 pub mod patron;
+pub mod pending_refresh;
+pub mod performed_action;
 pub mod permrole;
 pub mod permrole_starboard;
 pub mod posrole;
+pub mod post_latency;
+pub mod reaction_cleanup;
+pub mod shard_status;
 pub mod starboard;
 pub mod starboard_filter_group;
 pub mod starboard_message;
@@ -18,4 +33,6 @@ pub mod starboard_override_values;
 pub mod starboard_settings;
 pub mod user;
 pub mod vote;
+pub mod vote_lock;
+pub mod xp_season;
 pub mod xprole;
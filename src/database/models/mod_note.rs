@@ -0,0 +1,66 @@
+use chrono::{DateTime, Utc};
+
+/// A note left by a moderator on a message. Only ever shown to moderators.
+#[derive(Debug)]
+pub struct ModNote {
+    pub id: i32,
+    pub guild_id: i64,
+    /// The original message, even if the note was added from a starboard post.
+    pub message_id: i64,
+    pub author_id: i64,
+    pub note: String,
+    pub created_at: DateTime<Utc>,
+}
+
+impl ModNote {
+    pub async fn create(
+        pool: &sqlx::PgPool,
+        guild_id: i64,
+        message_id: i64,
+        author_id: i64,
+        note: &str,
+    ) -> sqlx::Result<Self> {
+        sqlx::query_as!(
+            Self,
+            "INSERT INTO mod_notes (guild_id, message_id, author_id, note)
+            VALUES ($1, $2, $3, $4) RETURNING *",
+            guild_id,
+            message_id,
+            author_id,
+            note,
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn delete(pool: &sqlx::PgPool, guild_id: i64, id: i32) -> sqlx::Result<Option<Self>> {
+        sqlx::query_as!(
+            Self,
+            "DELETE FROM mod_notes WHERE guild_id=$1 AND id=$2 RETURNING *",
+            guild_id,
+            id,
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn list_by_message(pool: &sqlx::PgPool, message_id: i64) -> sqlx::Result<Vec<Self>> {
+        sqlx::query_as!(
+            Self,
+            "SELECT * FROM mod_notes WHERE message_id=$1 ORDER BY id",
+            message_id,
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn count_by_message(pool: &sqlx::PgPool, message_id: i64) -> sqlx::Result<i64> {
+        sqlx::query!(
+            r#"SELECT COUNT(*) AS "count!" FROM mod_notes WHERE message_id=$1"#,
+            message_id,
+        )
+        .fetch_one(pool)
+        .await
+        .map(|r| r.count)
+    }
+}
@@ -0,0 +1,39 @@
+/// A refresh that was skipped because its shard was draining.
+#[derive(Debug)]
+pub struct PendingRefresh {
+    pub message_id: i64,
+    pub shard_id: i32,
+    pub is_premium: bool,
+}
+
+impl PendingRefresh {
+    pub async fn create(
+        pool: &sqlx::PgPool,
+        message_id: i64,
+        shard_id: i32,
+        is_premium: bool,
+    ) -> sqlx::Result<()> {
+        sqlx::query!(
+            "INSERT INTO pending_refreshes (message_id, shard_id, is_premium)
+            VALUES ($1, $2, $3) ON CONFLICT DO NOTHING",
+            message_id,
+            shard_id,
+            is_premium,
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Removes and returns the pending refreshes for the shards.
+    pub async fn take_by_shards(pool: &sqlx::PgPool, shard_ids: &[i32]) -> sqlx::Result<Vec<Self>> {
+        sqlx::query_as!(
+            Self,
+            "DELETE FROM pending_refreshes WHERE shard_id = ANY($1) RETURNING *",
+            shard_ids,
+        )
+        .fetch_all(pool)
+        .await
+    }
+}
@@ -0,0 +1,70 @@
+use chrono::{DateTime, Utc};
+
+/// A companion side effect (like the link reply) that was performed for a
+/// post, so that retried or overlapping refreshes don't perform it again.
+/// See `core::starboard::companion`.
+#[derive(Debug)]
+pub struct PerformedAction {
+    /// The original message.
+    pub message_id: i64,
+    pub starboard_id: i32,
+    pub kind: String,
+    /// A hash of what was sent, so that different content isn't deduplicated.
+    pub render_hash: i64,
+    pub created_at: DateTime<Utc>,
+}
+
+impl PerformedAction {
+    /// Records the action, returning false if it was already recorded.
+    pub async fn claim(
+        pool: &sqlx::PgPool,
+        message_id: i64,
+        starboard_id: i32,
+        kind: &str,
+        render_hash: i64,
+    ) -> sqlx::Result<bool> {
+        let row = sqlx::query!(
+            "INSERT INTO performed_actions (message_id, starboard_id, kind, render_hash)
+            VALUES ($1, $2, $3, $4) ON CONFLICT DO NOTHING RETURNING message_id",
+            message_id,
+            starboard_id,
+            kind,
+            render_hash,
+        )
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(row.is_some())
+    }
+
+    pub async fn release(
+        pool: &sqlx::PgPool,
+        message_id: i64,
+        starboard_id: i32,
+        kind: &str,
+        render_hash: i64,
+    ) -> sqlx::Result<()> {
+        sqlx::query!(
+            "DELETE FROM performed_actions WHERE message_id=$1 AND starboard_id=$2
+            AND kind=$3 AND render_hash=$4",
+            message_id,
+            starboard_id,
+            kind,
+            render_hash,
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn delete_before(pool: &sqlx::PgPool, before: DateTime<Utc>) -> sqlx::Result<u64> {
+        sqlx::query!(
+            "DELETE FROM performed_actions WHERE created_at < $1",
+            before
+        )
+        .execute(pool)
+        .await
+        .map(|r| r.rows_affected())
+    }
+}
@@ -0,0 +1,68 @@
+use chrono::{DateTime, Utc};
+
+/// How many posts in a guild were sent within a latency bucket during an
+/// hour. See `core::post_latency`.
+#[derive(Debug)]
+pub struct PostLatency {
+    pub guild_id: i64,
+    /// The start of the hour.
+    pub hour: DateTime<Utc>,
+    /// An index into `POST_LATENCY_BUCKETS_MS`, or its length for slower
+    /// posts.
+    pub bucket: i16,
+    pub count: i32,
+}
+
+impl PostLatency {
+    pub async fn record(
+        pool: &sqlx::PgPool,
+        guild_id: i64,
+        hour: DateTime<Utc>,
+        bucket: i16,
+    ) -> sqlx::Result<()> {
+        sqlx::query!(
+            "INSERT INTO post_latencies (guild_id, hour, bucket, count) VALUES ($1, $2, $3, 1)
+            ON CONFLICT (guild_id, hour, bucket)
+            DO UPDATE SET count = post_latencies.count + 1",
+            guild_id,
+            hour,
+            bucket,
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn list_by_guild_since(
+        pool: &sqlx::PgPool,
+        guild_id: i64,
+        since: DateTime<Utc>,
+    ) -> sqlx::Result<Vec<Self>> {
+        sqlx::query_as!(
+            Self,
+            "SELECT * FROM post_latencies WHERE guild_id=$1 AND hour >= $2",
+            guild_id,
+            since,
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn list_by_hour(pool: &sqlx::PgPool, hour: DateTime<Utc>) -> sqlx::Result<Vec<Self>> {
+        sqlx::query_as!(
+            Self,
+            "SELECT * FROM post_latencies WHERE hour=$1 ORDER BY guild_id",
+            hour,
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn delete_before(pool: &sqlx::PgPool, before: DateTime<Utc>) -> sqlx::Result<u64> {
+        sqlx::query!("DELETE FROM post_latencies WHERE hour < $1", before)
+            .execute(pool)
+            .await
+            .map(|r| r.rows_affected())
+    }
+}
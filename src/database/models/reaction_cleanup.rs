@@ -0,0 +1,116 @@
+/// A post to remove the bot's own vote-emoji reactions from. The rows are
+/// queued when a starboard is deleted or its vote emojis change, and handled
+/// by `core::starboard::cleanup`.
+#[derive(Debug)]
+pub struct ReactionCleanup {
+    pub starboard_message_id: i64,
+    pub starboard_id: i32,
+    /// The name the starboard had when the cleanup was queued.
+    pub starboard_name: String,
+    pub guild_id: i64,
+    pub channel_id: i64,
+    /// Stored `SimpleEmoji`s.
+    pub emojis: Vec<String>,
+}
+
+impl ReactionCleanup {
+    /// Queues the starboard's posts at or after `min_post_id`. Posts that are
+    /// already queued get the new emojis added to theirs. Returns the number
+    /// of posts queued.
+    pub async fn queue(
+        pool: &sqlx::PgPool,
+        starboard_id: i32,
+        starboard_name: &str,
+        guild_id: i64,
+        channel_id: i64,
+        emojis: &[String],
+        min_post_id: i64,
+    ) -> sqlx::Result<u64> {
+        let ret = sqlx::query!(
+            "INSERT INTO reaction_cleanups
+            (starboard_message_id, starboard_id, starboard_name, guild_id, channel_id, emojis)
+            SELECT starboard_message_id, $1, $2, $3, $4, $5 FROM starboard_messages
+            WHERE starboard_id=$1 AND starboard_message_id >= $6
+            ON CONFLICT (starboard_message_id) DO UPDATE
+            SET emojis=ARRAY(
+                SELECT DISTINCT UNNEST(reaction_cleanups.emojis || EXCLUDED.emojis)
+            )",
+            starboard_id,
+            starboard_name,
+            guild_id,
+            channel_id,
+            emojis,
+            min_post_id,
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(ret.rows_affected())
+    }
+
+    /// Stops removing `emojis` from the starboard's queued posts, e.g.
+    /// because they're vote emojis again. Posts with nothing left to remove
+    /// are dropped.
+    pub async fn keep_emojis(
+        pool: &sqlx::PgPool,
+        starboard_id: i32,
+        emojis: &[String],
+    ) -> sqlx::Result<()> {
+        sqlx::query!(
+            "UPDATE reaction_cleanups
+            SET emojis=ARRAY(SELECT UNNEST(emojis) EXCEPT SELECT UNNEST($2::text[]))
+            WHERE starboard_id=$1",
+            starboard_id,
+            emojis,
+        )
+        .execute(pool)
+        .await?;
+        sqlx::query!(
+            "DELETE FROM reaction_cleanups WHERE starboard_id=$1 AND cardinality(emojis)=0",
+            starboard_id,
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Queued posts older than `before`, newest first.
+    pub async fn list_before(
+        pool: &sqlx::PgPool,
+        before: i64,
+        limit: i64,
+    ) -> sqlx::Result<Vec<Self>> {
+        sqlx::query_as!(
+            Self,
+            "SELECT * FROM reaction_cleanups WHERE starboard_message_id < $1
+            ORDER BY starboard_message_id DESC LIMIT $2",
+            before,
+            limit,
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// How many of the starboard's posts are still queued.
+    pub async fn count_by_starboard(pool: &sqlx::PgPool, starboard_id: i32) -> sqlx::Result<i64> {
+        sqlx::query!(
+            r#"SELECT COUNT(*) as "count!" FROM reaction_cleanups WHERE starboard_id=$1"#,
+            starboard_id,
+        )
+        .fetch_one(pool)
+        .await
+        .map(|r| r.count)
+    }
+
+    pub async fn delete(pool: &sqlx::PgPool, starboard_message_id: i64) -> sqlx::Result<()> {
+        sqlx::query!(
+            "DELETE FROM reaction_cleanups WHERE starboard_message_id=$1",
+            starboard_message_id,
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+}
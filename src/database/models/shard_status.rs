@@ -0,0 +1,133 @@
+use chrono::{DateTime, Utc};
+
+/// Who runs a shard, and whether it is being handed over to another process.
+#[derive(Debug)]
+pub struct ShardStatus {
+    pub shard_id: i32,
+    pub owner: Option<String>,
+    /// The owner should stop starting new work for the shard.
+    pub draining: bool,
+    /// The owner finished its in-flight work, so the shard can be taken over.
+    pub drained: bool,
+    pub heartbeat_at: DateTime<Utc>,
+}
+
+impl ShardStatus {
+    /// Whether another live process owns the shard. Owners that stopped
+    /// sending heartbeats (e.g. because they crashed) don't count.
+    pub fn is_owned_by_other(&self, owner: &str, timeout: chrono::Duration) -> bool {
+        match &self.owner {
+            Some(current) => current != owner && self.heartbeat_at > Utc::now() - timeout,
+            None => false,
+        }
+    }
+
+    /// Claims the shards for `owner`. If any of them are owned by another
+    /// live process, nothing is claimed and those shards are returned
+    /// instead. With `takeover`, shards that were drained can be claimed
+    /// from their owner.
+    pub async fn claim(
+        pool: &sqlx::PgPool,
+        shard_ids: &[i32],
+        owner: &str,
+        takeover: bool,
+        timeout: chrono::Duration,
+    ) -> sqlx::Result<Result<(), Vec<Self>>> {
+        let mut tx = pool.begin().await?;
+
+        sqlx::query!(
+            "INSERT INTO shard_status (shard_id) SELECT * FROM UNNEST($1::int[])
+            ON CONFLICT DO NOTHING",
+            shard_ids,
+        )
+        .execute(&mut tx)
+        .await?;
+        let rows = sqlx::query_as!(
+            Self,
+            "SELECT * FROM shard_status WHERE shard_id = ANY($1) ORDER BY shard_id FOR UPDATE",
+            shard_ids,
+        )
+        .fetch_all(&mut tx)
+        .await?;
+
+        let blocking: Vec<_> = rows
+            .into_iter()
+            .filter(|row| row.is_owned_by_other(owner, timeout) && !(takeover && row.drained))
+            .collect();
+        if !blocking.is_empty() {
+            tx.rollback().await?;
+            return Ok(Err(blocking));
+        }
+
+        sqlx::query!(
+            "UPDATE shard_status SET owner=$2, draining=false, drained=false, heartbeat_at=now()
+            WHERE shard_id = ANY($1)",
+            shard_ids,
+            owner,
+        )
+        .execute(&mut tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(Ok(()))
+    }
+
+    /// Refreshes the heartbeat of every shard owned by `owner`, returning
+    /// them.
+    pub async fn heartbeat(pool: &sqlx::PgPool, owner: &str) -> sqlx::Result<Vec<Self>> {
+        sqlx::query_as!(
+            Self,
+            "UPDATE shard_status SET heartbeat_at=now() WHERE owner=$1 RETURNING *",
+            owner,
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Marks owned shards as draining, returning the ids that were marked.
+    pub async fn set_draining(pool: &sqlx::PgPool, shard_ids: &[i32]) -> sqlx::Result<Vec<i32>> {
+        sqlx::query!(
+            "UPDATE shard_status SET draining=true
+            WHERE shard_id = ANY($1) AND owner IS NOT NULL RETURNING shard_id",
+            shard_ids,
+        )
+        .fetch_all(pool)
+        .await
+        .map(|rows| rows.into_iter().map(|r| r.shard_id).collect())
+    }
+
+    pub async fn mark_drained(
+        pool: &sqlx::PgPool,
+        shard_ids: &[i32],
+        owner: &str,
+    ) -> sqlx::Result<()> {
+        sqlx::query!(
+            "UPDATE shard_status SET drained=true
+            WHERE shard_id = ANY($1) AND owner=$2 AND draining=true",
+            shard_ids,
+            owner,
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Gives up every shard owned by `owner`, e.g. on shutdown.
+    pub async fn release(pool: &sqlx::PgPool, owner: &str) -> sqlx::Result<()> {
+        sqlx::query!(
+            "UPDATE shard_status SET owner=NULL, draining=false, drained=false WHERE owner=$1",
+            owner,
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn list(pool: &sqlx::PgPool) -> sqlx::Result<Vec<Self>> {
+        sqlx::query_as!(Self, "SELECT * FROM shard_status ORDER BY shard_id")
+            .fetch_all(pool)
+            .await
+    }
+}
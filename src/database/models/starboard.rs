@@ -1,3 +1,4 @@
+use chrono::{DateTime, Utc};
 use sqlx::Row;
 
 use crate::database::{
@@ -16,6 +17,23 @@ pub struct Starboard {
 
     pub webhook_id: Option<i64>,
     pub premium_locked: bool,
+    /// The channel was deleted, so nothing is sent to it until it's moved with
+    /// `/starboards move`.
+    pub channel_deleted: bool,
+
+    /// Aggregate starboards have no emojis of their own, and instead use the
+    /// highest points a message has on any of `source_starboards`.
+    pub aggregate: bool,
+    pub source_starboards: Vec<i32>,
+
+    /// Some of the emojis (or those of an override) couldn't be parsed by
+    /// `star migrate-emojis`, and were kept as they were.
+    pub needs_attention: bool,
+
+    /// When the starboard is enabled or disabled by `/starboards schedule`.
+    /// Cleared once it happens.
+    pub enable_at: Option<DateTime<Utc>>,
+    pub disable_at: Option<DateTime<Utc>>,
 
     pub settings: StarboardSettings,
 }
@@ -33,6 +51,12 @@ macro_rules! starboard_from_record {
             guild_id: $record.guild_id,
             webhook_id: $record.webhook_id,
             premium_locked: $record.premium_locked,
+            channel_deleted: $record.channel_deleted,
+            aggregate: $record.aggregate,
+            source_starboards: $record.source_starboards,
+            needs_attention: $record.needs_attention,
+            enable_at: $record.enable_at,
+            disable_at: $record.disable_at,
             settings: call_with_starboard_settings!(settings_from_record, $record),
         }
     }};
@@ -53,6 +77,12 @@ macro_rules! starboard_from_row {
             guild_id: $record.get("guild_id"),
             webhook_id: $record.get("webhook_id"),
             premium_locked: $record.get("premium_locked"),
+            channel_deleted: $record.get("channel_deleted"),
+            aggregate: $record.get("aggregate"),
+            source_starboards: $record.get("source_starboards"),
+            needs_attention: $record.get("needs_attention"),
+            enable_at: $record.get("enable_at"),
+            disable_at: $record.get("disable_at"),
             settings: call_with_starboard_settings!(settings_from_row, $record),
         }
     }};
@@ -143,6 +173,161 @@ impl Starboard {
         .map(|_| ())
     }
 
+    /// Marks the starboards in a channel as (no longer) deleted, returning the
+    /// names of the starboards that changed.
+    pub async fn set_channel_deleted(
+        pool: &sqlx::PgPool,
+        channel_id: i64,
+        channel_deleted: bool,
+    ) -> sqlx::Result<Vec<String>> {
+        sqlx::query!(
+            "UPDATE starboards SET channel_deleted=$1
+            WHERE channel_id=$2 AND channel_deleted!=$1 RETURNING name",
+            channel_deleted,
+            channel_id,
+        )
+        .fetch_all(pool)
+        .await
+        .map(|rows| rows.into_iter().map(|r| r.name).collect())
+    }
+
+    /// Moves a starboard to another channel. The webhook belongs to the old
+    /// channel, so it's forgotten.
+    pub async fn set_channel(
+        pool: &sqlx::PgPool,
+        starboard_id: i32,
+        channel_id: i64,
+    ) -> sqlx::Result<()> {
+        sqlx::query!(
+            "UPDATE starboards SET channel_id=$1, channel_deleted=false, webhook_id=NULL
+            WHERE id=$2",
+            channel_id,
+            starboard_id,
+        )
+        .execute(pool)
+        .await
+        .map(|_| ())
+    }
+
+    pub async fn set_aggregate(
+        pool: &sqlx::PgPool,
+        starboard_id: i32,
+        aggregate: bool,
+        source_starboards: &[i32],
+    ) -> sqlx::Result<()> {
+        sqlx::query!(
+            "UPDATE starboards SET aggregate=$1, source_starboards=$2 WHERE id=$3",
+            aggregate,
+            source_starboards,
+            starboard_id,
+        )
+        .execute(pool)
+        .await
+        .map(|_| ())
+    }
+
+    /// Removes a starboard from the sources of any aggregate starboards,
+    /// returning the names of the starboards that were changed.
+    pub async fn remove_aggregate_source(
+        pool: &sqlx::PgPool,
+        guild_id: i64,
+        starboard_id: i32,
+    ) -> sqlx::Result<Vec<String>> {
+        sqlx::query!(
+            "UPDATE starboards SET source_starboards=array_remove(source_starboards, $1)
+            WHERE guild_id=$2 AND $1=ANY(source_starboards) RETURNING name",
+            starboard_id,
+            guild_id,
+        )
+        .fetch_all(pool)
+        .await
+        .map(|rows| rows.into_iter().map(|r| r.name).collect())
+    }
+
+    /// Rewrites the emoji settings of a starboard, for `star migrate-emojis`.
+    pub async fn set_emojis(
+        pool: &sqlx::PgPool,
+        starboard_id: i32,
+        upvote_emojis: &[String],
+        downvote_emojis: &[String],
+        display_emoji: Option<&str>,
+    ) -> sqlx::Result<()> {
+        sqlx::query!(
+            "UPDATE starboards SET upvote_emojis=$1, downvote_emojis=$2, display_emoji=$3
+            WHERE id=$4",
+            upvote_emojis,
+            downvote_emojis,
+            display_emoji,
+            starboard_id,
+        )
+        .execute(pool)
+        .await
+        .map(|_| ())
+    }
+
+    pub async fn set_needs_attention(
+        pool: &sqlx::PgPool,
+        starboard_id: i32,
+        needs_attention: bool,
+    ) -> sqlx::Result<()> {
+        sqlx::query!(
+            "UPDATE starboards SET needs_attention=$1 WHERE id=$2",
+            needs_attention,
+            starboard_id,
+        )
+        .execute(pool)
+        .await
+        .map(|_| ())
+    }
+
+    pub async fn set_schedule(
+        pool: &sqlx::PgPool,
+        starboard_id: i32,
+        enable_at: Option<DateTime<Utc>>,
+        disable_at: Option<DateTime<Utc>>,
+    ) -> sqlx::Result<()> {
+        sqlx::query!(
+            "UPDATE starboards SET enable_at=$1, disable_at=$2 WHERE id=$3",
+            enable_at,
+            disable_at,
+            starboard_id,
+        )
+        .execute(pool)
+        .await
+        .map(|_| ())
+    }
+
+    /// Starboards with a scheduled enable or disable that has passed.
+    pub async fn list_schedule_due(pool: &sqlx::PgPool) -> sqlx::Result<Vec<Self>> {
+        sqlx::query!("SELECT * FROM starboards WHERE enable_at <= now() OR disable_at <= now()")
+            .fetch_all(pool)
+            .await
+            .map(|rows| {
+                rows.into_iter()
+                    .map(|row| starboard_from_record!(row))
+                    .collect()
+            })
+    }
+
+    /// Sets `enabled` and clears the scheduled times that have passed.
+    pub async fn finish_schedule(
+        pool: &sqlx::PgPool,
+        starboard_id: i32,
+        enabled: bool,
+    ) -> sqlx::Result<()> {
+        sqlx::query!(
+            "UPDATE starboards SET enabled=$1,
+            enable_at=CASE WHEN enable_at <= now() THEN NULL ELSE enable_at END,
+            disable_at=CASE WHEN disable_at <= now() THEN NULL ELSE disable_at END
+            WHERE id=$2",
+            enabled,
+            starboard_id,
+        )
+        .execute(pool)
+        .await
+        .map(|_| ())
+    }
+
     pub async fn rename(
         pool: &sqlx::PgPool,
         name: &String,
@@ -205,4 +390,15 @@ impl Starboard {
                     .collect()
             })
     }
+
+    pub async fn list_all(pool: &sqlx::PgPool) -> sqlx::Result<Vec<Self>> {
+        sqlx::query!("SELECT * FROM starboards ORDER BY id")
+            .fetch_all(pool)
+            .await
+            .map(|rows| {
+                rows.into_iter()
+                    .map(|row| starboard_from_record!(row))
+                    .collect()
+            })
+    }
 }
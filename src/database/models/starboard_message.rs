@@ -4,6 +4,8 @@ pub struct StarboardMessage {
     pub starboard_id: i32,
     pub starboard_message_id: i64,
     pub last_known_point_count: i16,
+    /// The bot's reply to the original message, linking to this post.
+    pub reply_message_id: Option<i64>,
 }
 
 impl StarboardMessage {
@@ -54,6 +56,65 @@ impl StarboardMessage {
         .await
     }
 
+    /// The posts of a user's messages in a guild, optionally only on one
+    /// starboard, with the channel of each original message.
+    pub async fn list_by_author(
+        pool: &sqlx::PgPool,
+        guild_id: i64,
+        author_id: i64,
+        starboard_id: Option<i32>,
+    ) -> sqlx::Result<Vec<(Self, i64)>> {
+        let rows = sqlx::query!(
+            "SELECT sm.message_id, sm.starboard_id, sm.starboard_message_id,
+            sm.last_known_point_count, sm.reply_message_id, m.channel_id
+            FROM starboard_messages sm JOIN messages m ON m.message_id=sm.message_id
+            WHERE m.guild_id=$1 AND m.author_id=$2 AND ($3::INT IS NULL OR sm.starboard_id=$3)
+            ORDER BY sm.starboard_message_id",
+            guild_id,
+            author_id,
+            starboard_id,
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|r| {
+                let post = Self {
+                    message_id: r.message_id,
+                    starboard_id: r.starboard_id,
+                    starboard_message_id: r.starboard_message_id,
+                    last_known_point_count: r.last_known_point_count,
+                    reply_message_id: r.reply_message_id,
+                };
+                (post, r.channel_id)
+            })
+            .collect())
+    }
+
+    /// The ids of the posts of a user's messages on a starboard, sent at or
+    /// after the post id `since`, oldest first.
+    pub async fn list_recent_by_author(
+        pool: &sqlx::PgPool,
+        starboard_id: i32,
+        author_id: i64,
+        since: i64,
+    ) -> sqlx::Result<Vec<i64>> {
+        let rows = sqlx::query!(
+            "SELECT sm.starboard_message_id
+            FROM starboard_messages sm JOIN messages m ON m.message_id=sm.message_id
+            WHERE sm.starboard_id=$1 AND sm.starboard_message_id>=$2 AND m.author_id=$3
+            ORDER BY sm.starboard_message_id",
+            starboard_id,
+            since,
+            author_id,
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(rows.into_iter().map(|r| r.starboard_message_id).collect())
+    }
+
     pub async fn get_by_starboard(
         pool: &sqlx::PgPool,
         message_id: i64,
@@ -84,4 +145,20 @@ impl StarboardMessage {
         .fetch_optional(pool)
         .await
     }
+
+    pub async fn set_reply_message_id(
+        pool: &sqlx::PgPool,
+        starboard_message_id: i64,
+        reply_message_id: i64,
+    ) -> sqlx::Result<()> {
+        sqlx::query!(
+            "UPDATE starboard_messages SET reply_message_id=$1 WHERE starboard_message_id=$2",
+            reply_message_id,
+            starboard_message_id,
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(())
+    }
 }
@@ -173,6 +173,12 @@ impl StarboardOverride {
         .await
     }
 
+    pub async fn list_all(pool: &sqlx::PgPool) -> sqlx::Result<Vec<Self>> {
+        sqlx::query_as!(Self, "SELECT * FROM overrides ORDER BY id")
+            .fetch_all(pool)
+            .await
+    }
+
     pub fn get_overrides(&self) -> serde_json::Result<OverrideValues> {
         serde_json::from_value(self.overrides.clone())
     }
@@ -17,10 +17,14 @@ pub struct OverrideValues {
     // General Style
     #[serde(deserialize_with = "null_to_some_none", default)]
     pub display_emoji: Option<Option<String>>,
+    pub display_emoji_tiers: Option<serde_json::Value>,
     pub ping_author: Option<bool>,
     pub use_server_profile: Option<bool>,
     pub extra_embeds: Option<bool>,
     pub use_webhook: Option<bool>,
+    pub translate_button: Option<bool>,
+    pub vote_buttons: Option<bool>,
+    pub show_votes_button: Option<bool>,
 
     // Embed Style
     #[serde(deserialize_with = "null_to_some_none", default)]
@@ -28,17 +32,33 @@ pub struct OverrideValues {
     pub go_to_message: Option<i16>,
     pub attachments_list: Option<bool>,
     pub replied_to: Option<bool>,
+    pub media_selection: Option<i16>,
+    pub extract_media_links: Option<bool>,
+    pub clone_attachments: Option<bool>,
 
     // Requirements
     #[serde(deserialize_with = "null_to_some_none", default)]
     pub required: Option<Option<i16>>,
     #[serde(deserialize_with = "null_to_some_none", default)]
     pub required_remove: Option<Option<i16>>,
+    #[serde(deserialize_with = "null_to_some_none", default)]
+    pub min_unique_voters: Option<Option<i16>>,
     pub upvote_emojis: Option<Vec<String>>,
     pub downvote_emojis: Option<Vec<String>>,
+    pub vote_emoji_weights: Option<serde_json::Value>,
     pub self_vote: Option<bool>,
+    pub votes_on_post: Option<bool>,
     pub allow_bots: Option<bool>,
     pub require_image: Option<bool>,
+    pub min_chars: Option<i16>,
+    #[serde(deserialize_with = "null_to_some_none", default)]
+    pub max_chars: Option<Option<i16>>,
+    pub require_link: Option<bool>,
+    pub allow_system_messages: Option<bool>,
+    #[serde(deserialize_with = "null_to_some_none", default)]
+    pub min_vote_spread_seconds: Option<Option<i32>>,
+    #[serde(deserialize_with = "null_to_some_none", default)]
+    pub max_posts_per_author_per_day: Option<Option<i16>>,
     pub older_than: Option<i64>,
     pub newer_than: Option<i64>,
     #[serde(deserialize_with = "null_to_some_none", default)]
@@ -51,9 +71,13 @@ pub struct OverrideValues {
     pub autoreact_upvote: Option<bool>,
     pub autoreact_downvote: Option<bool>,
     pub remove_invalid_reactions: Option<bool>,
+    pub lock_vote_after_mod_removal: Option<bool>,
     pub link_deletes: Option<bool>,
     pub link_edits: Option<bool>,
     pub on_delete: Option<i16>,
+    pub recreate_deleted_posts: Option<bool>,
+    pub attach_thread: Option<bool>,
+    pub reply_with_link: Option<bool>,
     pub cooldown_enabled: Option<bool>,
     pub cooldown_count: Option<i16>,
     pub cooldown_period: Option<i16>,
@@ -1,41 +1,109 @@
-#[derive(Clone, Debug)]
+use serde::Serialize;
+
+#[derive(Clone, Debug, Serialize)]
 pub struct StarboardSettings {
     // General Style
     pub display_emoji: Option<String>,
+    /// `[{"points": 10, "emoji": "🌟"}, ...]`, see `core::emoji_tiers`. The
+    /// highest tier a post reached replaces `display_emoji`.
+    pub display_emoji_tiers: serde_json::Value,
     pub ping_author: bool,
     pub use_server_profile: bool,
     pub extra_embeds: bool,
     pub use_webhook: bool,
+    /// Add a button that translates the post for whoever clicks it.
+    pub translate_button: bool,
+    /// Add buttons to the post that vote with the first upvote and downvote
+    /// emoji, for people who can't react.
+    pub vote_buttons: bool,
+    /// Add a button that lists who voted on the post.
+    pub show_votes_button: bool,
 
     // Embed Style
     pub color: Option<i32>,
     pub go_to_message: i16,
     pub attachments_list: bool,
     pub replied_to: bool,
+    /// Which image(s) to show when a message has several: 0 for the first,
+    /// 1 for the largest, 2 for a gallery of up to 4.
+    pub media_selection: i16,
+    /// Show GIF and image links in the content (tenor, giphy, imgur, Discord's
+    /// CDN) as the embed's image when the message has no attachments.
+    pub extract_media_links: bool,
+    /// Re-upload small images and videos to the post instead of linking to
+    /// the original, so they survive the original being deleted.
+    pub clone_attachments: bool,
 
     // Requirements
     pub required: Option<i16>,
     pub required_remove: Option<i16>,
+    /// How many different users must upvote a message, regardless of how
+    /// many points it has.
+    pub min_unique_voters: Option<i16>,
     pub upvote_emojis: Vec<String>,
     pub downvote_emojis: Vec<String>,
+    /// `[{"emoji": "💎", "weight": 3}, ...]`, see `core::vote_weights`.
+    /// Vote emojis without a weight are worth 1 point.
+    pub vote_emoji_weights: serde_json::Value,
     pub self_vote: bool,
+    /// Whether reactions on a post count as votes on its original message.
+    pub votes_on_post: bool,
     pub allow_bots: bool,
+    /// Stickers and images in embeds count as well as attachments.
     pub require_image: bool,
+    /// How many characters of text a message needs. Only the text counts,
+    /// so an image with no text has 0 characters.
+    pub min_chars: i16,
+    pub max_chars: Option<i16>,
+    pub require_link: bool,
+    /// Whether system messages (joins, boosts, pins...) can be starred.
+    pub allow_system_messages: bool,
+    /// The minimum time between the first and most recent upvote for a
+    /// message to be sent. Messages with fewer than 3 upvotes are exempt.
+    pub min_vote_spread_seconds: Option<i32>,
+    /// How many posts one author can get on this starboard in 24 hours.
+    /// Messages past the limit keep their votes and are posted once the
+    /// author's oldest post in the window is 24 hours old, if they still
+    /// qualify then.
+    pub max_posts_per_author_per_day: Option<i16>,
     pub older_than: i64,
     pub newer_than: i64,
     pub matches: Option<String>,
     pub not_matches: Option<String>,
+    /// Channels (or categories) votes count in. Empty means all channels.
+    pub channel_whitelist: Vec<i64>,
+    /// Channels (or categories) votes never count in.
+    pub channel_blacklist: Vec<i64>,
+    /// Forum posts need one of these tags for votes to count. Messages
+    /// outside of forums aren't affected.
+    pub required_tags: Vec<i64>,
+    /// Votes don't count on forum posts with any of these tags.
+    pub excluded_tags: Vec<i64>,
 
     // Behavior
     pub enabled: bool,
     pub autoreact_upvote: bool,
     pub autoreact_downvote: bool,
     pub remove_invalid_reactions: bool,
+    /// Whether removing an invalid reaction also locks the reactor's vote on
+    /// the message, like "Remove & lock vote".
+    pub lock_vote_after_mod_removal: bool,
     pub link_deletes: bool,
     pub link_edits: bool,
     /// 0=repost, 1=ignore, 2=trash-all, 3=freeze-all
     pub on_delete: i16,
+    /// Whether a post that was deleted by hand is sent again once the message
+    /// qualifies. Posts deleted by the bot are always sent again.
+    pub recreate_deleted_posts: bool,
+    pub attach_thread: bool,
+    /// Reply to the original message with a link to the starboard message.
+    pub reply_with_link: bool,
     pub private: bool,
+    /// The votes button only shows totals, not who voted.
+    pub private_votes: bool,
+    /// Votes store a salted hash of the voter's id instead of the id, so no
+    /// one can see who voted.
+    pub anonymous_votes: bool,
     pub xp_multiplier: f32,
     pub cooldown_enabled: bool,
     pub cooldown_count: i16,
@@ -6,6 +6,8 @@ pub struct DbUser {
     pub donated_cents: i64,
     /// 0=none, 1=active, 2=declined, 3=former
     pub patreon_status: i16,
+    /// A `Locale` code, overriding the guild's locale.
+    pub locale: Option<String>,
 }
 
 impl DbUser {
@@ -56,4 +58,19 @@ impl DbUser {
         .await?;
         Ok(())
     }
+
+    pub async fn set_locale(
+        pool: &sqlx::PgPool,
+        user_id: i64,
+        locale: Option<&str>,
+    ) -> sqlx::Result<()> {
+        sqlx::query!(
+            "UPDATE users SET locale=$1 WHERE user_id=$2",
+            locale,
+            user_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
 }
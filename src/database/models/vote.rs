@@ -1,31 +1,132 @@
+use chrono::{DateTime, Utc};
+
 #[derive(Debug)]
 pub struct Vote {
     pub message_id: i64,
     pub starboard_id: i32,
-    pub user_id: i64,
+    /// The voter, unless the vote is anonymous.
+    pub user_id: Option<i64>,
+    /// The hashed voter of an anonymous vote, see `core::starboard::anonymous`.
+    pub voter_hash: Option<i64>,
+    /// Whichever of `user_id` and `voter_hash` is set. Votes are looked up by
+    /// this, so the functions below take voter ids rather than user ids.
+    pub voter_id: i64,
+
+    pub target_author_id: i64,
+    pub is_downvote: bool,
+    pub created_at: DateTime<Utc>,
+    /// How many points the vote is worth, see `core::vote_weights`.
+    pub weight: i16,
+    /// The stored emoji the vote was cast with. Votes from before emojis
+    /// were stored don't have one.
+    pub emoji: Option<String>,
+}
+
+/// The points on a message, and how many different users upvoted it.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct VoteCount {
+    pub points: i32,
+    pub unique_voters: i32,
+    /// The weighted upvotes and downvotes, so `points` is `upvotes -
+    /// downvotes`.
+    pub upvotes: i32,
+    pub downvotes: i32,
+}
+
+impl VoteCount {
+    /// The count with the most points, more unique voters breaking ties.
+    /// Both values come from the same count.
+    pub fn max(counts: impl IntoIterator<Item = Self>) -> Self {
+        counts
+            .into_iter()
+            .max_by_key(|c| (c.points, c.unique_voters))
+            .unwrap_or_default()
+    }
+}
+
+/// The points from the votes cast with one emoji, see `Vote::count_by_emoji`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EmojiPoints {
+    pub emoji: Option<String>,
+    pub is_downvote: bool,
+    pub points: i32,
+}
+
+/// The upvotes on a message, and when the first one was cast.
+#[derive(Debug, Clone, Copy)]
+pub struct VoteSpread {
+    pub upvotes: i64,
+    pub first: DateTime<Utc>,
+}
+
+/// A vote to insert when rebuilding the votes on a message.
+#[derive(Debug, Clone)]
+pub struct NewVote {
+    pub starboard_id: i32,
+    pub voter_id: i64,
+    pub is_downvote: bool,
+    pub weight: i16,
+    pub emoji: String,
+}
 
+/// A vote waiting in the `VoteBatcher` to be written.
+#[derive(Debug, Clone)]
+pub struct BufferedVote {
+    pub message_id: i64,
+    pub starboard_id: i32,
+    pub voter_id: i64,
     pub target_author_id: i64,
     pub is_downvote: bool,
+    pub weight: i16,
+    pub emoji: String,
 }
 
 impl Vote {
+    /// Deletes up to `limit` of the votes on a guild's starboards, returning
+    /// how many were deleted.
+    pub async fn delete_batch_by_guild(
+        pool: &sqlx::PgPool,
+        guild_id: i64,
+        limit: i64,
+    ) -> sqlx::Result<u64> {
+        sqlx::query!(
+            "DELETE FROM votes WHERE (message_id, starboard_id, voter_id) IN (
+                SELECT message_id, starboard_id, voter_id FROM votes WHERE starboard_id IN (
+                    SELECT id FROM starboards WHERE guild_id=$1
+                ) LIMIT $2
+            )",
+            guild_id,
+            limit,
+        )
+        .execute(pool)
+        .await
+        .map(|r| r.rows_affected())
+    }
+
+    #[allow(clippy::too_many_arguments)]
     pub async fn create(
         pool: &sqlx::PgPool,
         message_id: i64,
         starboard_id: i32,
-        user_id: i64,
+        voter_id: i64,
         target_author_id: i64,
         is_downvote: bool,
+        weight: i16,
+        emoji: Option<&str>,
     ) -> sqlx::Result<Option<()>> {
         let create = sqlx::query!(
-            "INSERT INTO VOTES (message_id, starboard_id, user_id, target_author_id, is_downvote)
-            VALUES ($1, $2, $3, $4, $5)
+            "INSERT INTO VOTES (message_id, starboard_id, user_id, voter_hash, target_author_id,
+                is_downvote, weight, emoji)
+            VALUES ($1, $2, CASE WHEN $3::bigint >= 0 THEN $3 END,
+                CASE WHEN $3::bigint < 0 THEN $3 END, $4, $5, $6, $7)
             ON CONFLICT DO NOTHING",
             message_id,
             starboard_id,
-            user_id,
+            voter_id,
             target_author_id,
             is_downvote,
+            weight,
+            emoji,
         )
         .fetch_optional(pool)
         .await?;
@@ -34,12 +135,18 @@ impl Vote {
             return Ok(Some(()));
         }
 
+        // see `core::vote_weights::clamp_vote` and `keeps_emoji`
         sqlx::query!(
-            "UPDATE votes SET is_downvote=$1 WHERE message_id=$2 AND starboard_id=$3 AND user_id=$4",
+            "UPDATE votes SET is_downvote=$1,
+                weight=CASE WHEN is_downvote=$1 THEN GREATEST(weight, $5) ELSE $5 END,
+                emoji=CASE WHEN is_downvote=$1 AND weight >= $5 THEN emoji ELSE $6 END
+            WHERE message_id=$2 AND starboard_id=$3 AND voter_id=$4",
             is_downvote,
             message_id,
             starboard_id,
-            user_id,
+            voter_id,
+            weight,
+            emoji,
         )
         .fetch_optional(pool)
         .await?;
@@ -47,48 +154,429 @@ impl Vote {
         Ok(Some(()))
     }
 
+    /// Inserts many votes at once, updating the ones that already exist.
+    /// Returns how many already existed. Each vote may only be in `votes`
+    /// once.
+    pub async fn upsert_batch(pool: &sqlx::PgPool, votes: &[BufferedVote]) -> sqlx::Result<u64> {
+        let message_ids: Vec<_> = votes.iter().map(|v| v.message_id).collect();
+        let starboard_ids: Vec<_> = votes.iter().map(|v| v.starboard_id).collect();
+        let voter_ids: Vec<_> = votes.iter().map(|v| v.voter_id).collect();
+        let target_author_ids: Vec<_> = votes.iter().map(|v| v.target_author_id).collect();
+        let is_downvote: Vec<_> = votes.iter().map(|v| v.is_downvote).collect();
+        let weights: Vec<_> = votes.iter().map(|v| v.weight).collect();
+        let emojis: Vec<_> = votes.iter().map(|v| v.emoji.clone()).collect();
+
+        let mut tx = pool.begin().await?;
+
+        // a vote from while `anonymous-votes` was enabled is replaced by the
+        // new one, instead of counting twice
+        sqlx::query!(
+            "DELETE FROM votes h
+            USING UNNEST($1::bigint[], $2::int[], $3::bigint[]) AS v(message_id, starboard_id,
+                voter_id), starboards s, guilds g
+            WHERE v.voter_id >= 0 AND s.id=v.starboard_id AND NOT s.anonymous_votes
+            AND g.guild_id=s.guild_id AND h.message_id=v.message_id
+            AND h.starboard_id=v.starboard_id
+            AND h.voter_hash=voter_hash(g.vote_salt, v.voter_id)",
+            &message_ids,
+            &starboard_ids,
+            &voter_ids,
+        )
+        .execute(&mut tx)
+        .await?;
+
+        // see `core::vote_weights::clamp_vote` and `keeps_emoji`
+        let rows = sqlx::query!(
+            r#"INSERT INTO votes (message_id, starboard_id, user_id, voter_hash, target_author_id,
+                is_downvote, weight, emoji)
+            SELECT v.message_id, v.starboard_id, CASE WHEN v.voter_id >= 0 THEN v.voter_id END,
+                CASE WHEN v.voter_id < 0 THEN v.voter_id END, v.target_author_id, v.is_downvote,
+                v.weight, v.emoji
+            FROM UNNEST($1::bigint[], $2::int[], $3::bigint[], $4::bigint[], $5::bool[],
+                $6::smallint[], $7::text[])
+                AS v(message_id, starboard_id, voter_id, target_author_id, is_downvote, weight,
+                    emoji)
+            ON CONFLICT (message_id, starboard_id, voter_id)
+                DO UPDATE SET is_downvote=EXCLUDED.is_downvote,
+                weight=CASE WHEN votes.is_downvote=EXCLUDED.is_downvote
+                    THEN GREATEST(votes.weight, EXCLUDED.weight) ELSE EXCLUDED.weight END,
+                emoji=CASE WHEN votes.is_downvote=EXCLUDED.is_downvote
+                    AND votes.weight >= EXCLUDED.weight THEN votes.emoji ELSE EXCLUDED.emoji END
+            RETURNING (xmax <> 0) AS "updated!""#,
+            &message_ids,
+            &starboard_ids,
+            &voter_ids,
+            &target_author_ids,
+            &is_downvote,
+            &weights,
+            &emojis,
+        )
+        .fetch_all(&mut tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(rows.iter().filter(|r| r.updated).count() as u64)
+    }
+
+    /// Replaces the votes on a message for the given starboards, deleting
+    /// and inserting in one transaction so the points never drop in between.
+    pub async fn replace_for_message(
+        pool: &sqlx::PgPool,
+        message_id: i64,
+        target_author_id: i64,
+        starboard_ids: &[i32],
+        votes: &[NewVote],
+    ) -> sqlx::Result<()> {
+        let vote_starboard_ids: Vec<_> = votes.iter().map(|v| v.starboard_id).collect();
+        let voter_ids: Vec<_> = votes.iter().map(|v| v.voter_id).collect();
+        let is_downvote: Vec<_> = votes.iter().map(|v| v.is_downvote).collect();
+        let weights: Vec<_> = votes.iter().map(|v| v.weight).collect();
+        let emojis: Vec<_> = votes.iter().map(|v| v.emoji.clone()).collect();
+
+        let mut tx = pool.begin().await?;
+
+        sqlx::query!(
+            "DELETE FROM votes WHERE message_id=$1 AND starboard_id=ANY($2)",
+            message_id,
+            starboard_ids,
+        )
+        .execute(&mut tx)
+        .await?;
+        sqlx::query!(
+            "INSERT INTO votes (message_id, starboard_id, user_id, voter_hash, target_author_id,
+                is_downvote, weight, emoji)
+            SELECT $1, v.starboard_id, CASE WHEN v.voter_id >= 0 THEN v.voter_id END,
+                CASE WHEN v.voter_id < 0 THEN v.voter_id END, $2, v.is_downvote, v.weight, v.emoji
+            FROM UNNEST($3::int[], $4::bigint[], $5::bool[], $6::smallint[], $7::text[])
+                AS v(starboard_id, voter_id, is_downvote, weight, emoji)
+            ON CONFLICT DO NOTHING",
+            message_id,
+            target_author_id,
+            &vote_starboard_ids,
+            &voter_ids,
+            &is_downvote,
+            &weights,
+            &emojis,
+        )
+        .execute(&mut tx)
+        .await?;
+
+        tx.commit().await
+    }
+
+    /// When the latest vote on a message was added.
+    pub async fn last_created_at(
+        pool: &sqlx::PgPool,
+        message_id: i64,
+        starboard_id: i32,
+    ) -> sqlx::Result<Option<DateTime<Utc>>> {
+        sqlx::query!(
+            "SELECT MAX(created_at) AS last FROM votes WHERE message_id=$1 AND starboard_id=$2",
+            message_id,
+            starboard_id,
+        )
+        .fetch_one(pool)
+        .await
+        .map(|r| r.last)
+    }
+
     pub async fn count(
         pool: &sqlx::PgPool,
         message_id: i64,
         starboard_id: i32,
-    ) -> sqlx::Result<i32> {
-        let upvotes = sqlx::query!(
-            "SELECT COUNT(*) as count FROM votes WHERE message_id=$1 AND starboard_id=$2
-            AND is_downvote=false",
+    ) -> sqlx::Result<VoteCount> {
+        let row = sqlx::query!(
+            r#"SELECT COALESCE(SUM(CASE WHEN is_downvote THEN -weight ELSE weight END), 0) as "points!",
+                COUNT(DISTINCT voter_id) FILTER (WHERE is_downvote=false) as "unique_voters!",
+                COALESCE(SUM(weight) FILTER (WHERE NOT is_downvote), 0) as "upvotes!",
+                COALESCE(SUM(weight) FILTER (WHERE is_downvote), 0) as "downvotes!"
+            FROM votes WHERE message_id=$1 AND starboard_id=$2"#,
             message_id,
             starboard_id
         )
         .fetch_one(pool)
         .await?;
-        let downvotes = sqlx::query!(
-            "SELECT COUNT(*) as count FROM votes WHERE message_id=$1 AND starboard_id=$2
-            AND is_downvote=true",
+
+        Ok(VoteCount {
+            points: row.points.try_into().unwrap(),
+            unique_voters: row.unique_voters.try_into().unwrap(),
+            upvotes: row.upvotes.try_into().unwrap(),
+            downvotes: row.downvotes.try_into().unwrap(),
+        })
+    }
+
+    /// The count on whichever of the starboards the message has the most
+    /// points on, where a starboard without votes counts as 0 points.
+    pub async fn count_max(
+        pool: &sqlx::PgPool,
+        message_id: i64,
+        starboard_ids: &[i32],
+    ) -> sqlx::Result<VoteCount> {
+        let rows = sqlx::query!(
+            r#"SELECT SUM(CASE WHEN is_downvote THEN -weight ELSE weight END) as "points!",
+                COUNT(DISTINCT voter_id) FILTER (WHERE is_downvote=false) as "unique_voters!",
+                COALESCE(SUM(weight) FILTER (WHERE NOT is_downvote), 0) as "upvotes!",
+                COALESCE(SUM(weight) FILTER (WHERE is_downvote), 0) as "downvotes!"
+            FROM votes WHERE message_id=$1 AND starboard_id=ANY($2)
+            GROUP BY starboard_id"#,
             message_id,
-            starboard_id
+            starboard_ids,
+        )
+        .fetch_all(pool)
+        .await?;
+
+        let mut counts: Vec<_> = rows
+            .into_iter()
+            .map(|r| VoteCount {
+                points: r.points.try_into().unwrap(),
+                unique_voters: r.unique_voters.try_into().unwrap(),
+                upvotes: r.upvotes.try_into().unwrap(),
+                downvotes: r.downvotes.try_into().unwrap(),
+            })
+            .collect();
+        if counts.len() < starboard_ids.len() {
+            counts.push(VoteCount::default());
+        }
+
+        Ok(VoteCount::max(counts))
+    }
+
+    /// The points on a message for one starboard, per emoji and direction.
+    pub async fn count_by_emoji(
+        pool: &sqlx::PgPool,
+        message_id: i64,
+        starboard_id: i32,
+    ) -> sqlx::Result<Vec<EmojiPoints>> {
+        let rows = sqlx::query!(
+            r#"SELECT emoji, is_downvote, SUM(weight) as "points!" FROM votes
+            WHERE message_id=$1 AND starboard_id=$2
+            GROUP BY emoji, is_downvote"#,
+            message_id,
+            starboard_id,
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|r| EmojiPoints {
+                emoji: r.emoji,
+                is_downvote: r.is_downvote,
+                points: r.points.try_into().unwrap(),
+            })
+            .collect())
+    }
+
+    /// The number of upvotes on a message across the given starboards, and
+    /// when the first one was cast. Returns `None` if there are no upvotes.
+    pub async fn spread(
+        pool: &sqlx::PgPool,
+        message_id: i64,
+        starboard_ids: &[i32],
+    ) -> sqlx::Result<Option<VoteSpread>> {
+        let row = sqlx::query!(
+            r#"SELECT COUNT(*) as "upvotes!", MIN(created_at) as first
+            FROM votes WHERE message_id=$1 AND starboard_id=ANY($2) AND is_downvote=false"#,
+            message_id,
+            starboard_ids,
         )
         .fetch_one(pool)
         .await?;
 
-        Ok({ upvotes.count.unwrap() - downvotes.count.unwrap() }
-            .try_into()
-            .unwrap())
+        Ok(row.first.map(|first| VoteSpread {
+            upvotes: row.upvotes,
+            first,
+        }))
+    }
+
+    pub async fn get(
+        pool: &sqlx::PgPool,
+        message_id: i64,
+        starboard_id: i32,
+        voter_id: i64,
+    ) -> sqlx::Result<Option<Self>> {
+        sqlx::query_as!(
+            Self,
+            "SELECT * FROM votes WHERE message_id=$1 AND starboard_id=$2 AND voter_id=$3",
+            message_id,
+            starboard_id,
+            voter_id,
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn list_by_message(
+        pool: &sqlx::PgPool,
+        message_id: i64,
+        starboard_id: i32,
+    ) -> sqlx::Result<Vec<Self>> {
+        sqlx::query_as!(
+            Self,
+            "SELECT * FROM votes WHERE message_id=$1 AND starboard_id=$2
+            ORDER BY created_at",
+            message_id,
+            starboard_id,
+        )
+        .fetch_all(pool)
+        .await
     }
 
     pub async fn delete(
         pool: &sqlx::PgPool,
         message_id: i64,
         starboard_id: i32,
-        user_id: i64,
+        voter_id: i64,
     ) -> sqlx::Result<Option<Self>> {
         sqlx::query_as!(
             Self,
-            "DELETE FROM votes WHERE message_id=$1 AND starboard_id=$2 AND user_id=$3
+            "DELETE FROM votes WHERE message_id=$1 AND starboard_id=$2 AND voter_id=$3
             RETURNING *",
             message_id,
             starboard_id,
-            user_id,
+            voter_id,
         )
         .fetch_optional(pool)
         .await
     }
+
+    /// The vote by any of `voter_ids`, see `core::starboard::anonymous::voter_ids`.
+    pub async fn get_any(
+        pool: &sqlx::PgPool,
+        message_id: i64,
+        starboard_id: i32,
+        voter_ids: &[i64],
+    ) -> sqlx::Result<Option<Self>> {
+        sqlx::query_as!(
+            Self,
+            "SELECT * FROM votes WHERE message_id=$1 AND starboard_id=$2 AND voter_id=ANY($3)
+            LIMIT 1",
+            message_id,
+            starboard_id,
+            voter_ids,
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Deletes the votes by any of `voter_ids`, see
+    /// `core::starboard::anonymous::voter_ids`.
+    pub async fn delete_any(
+        pool: &sqlx::PgPool,
+        message_id: i64,
+        starboard_id: i32,
+        voter_ids: &[i64],
+    ) -> sqlx::Result<Vec<Self>> {
+        sqlx::query_as!(
+            Self,
+            "DELETE FROM votes WHERE message_id=$1 AND starboard_id=$2 AND voter_id=ANY($3)
+            RETURNING *",
+            message_id,
+            starboard_id,
+            voter_ids,
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Deletes a user's votes on a message, on every starboard.
+    pub async fn delete_by_user(
+        pool: &sqlx::PgPool,
+        message_id: i64,
+        user_id: i64,
+    ) -> sqlx::Result<Vec<Self>> {
+        sqlx::query_as!(
+            Self,
+            "DELETE FROM votes WHERE message_id=$1 AND user_id=$2 RETURNING *",
+            message_id,
+            user_id,
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Replaces the user ids on a starboard with their hashes, for when
+    /// `anonymous-votes` is enabled. Returns the number of votes changed.
+    pub async fn anonymize_starboard(pool: &sqlx::PgPool, starboard_id: i32) -> sqlx::Result<u64> {
+        let mut tx = pool.begin().await?;
+
+        // users who still have an anonymous vote from before the setting was
+        // last disabled keep only that one
+        sqlx::query!(
+            "DELETE FROM votes v USING starboards s, guilds g
+            WHERE v.starboard_id=$1 AND s.id=v.starboard_id AND g.guild_id=s.guild_id
+            AND v.user_id IS NOT NULL AND EXISTS (
+                SELECT 1 FROM votes h WHERE h.message_id=v.message_id
+                AND h.starboard_id=v.starboard_id
+                AND h.voter_hash=voter_hash(g.vote_salt, v.user_id)
+            )",
+            starboard_id,
+        )
+        .execute(&mut tx)
+        .await?;
+        let changed = sqlx::query!(
+            "UPDATE votes SET user_id=NULL, voter_hash=voter_hash(g.vote_salt, votes.user_id)
+            FROM starboards s, guilds g
+            WHERE votes.starboard_id=$1 AND s.id=votes.starboard_id
+            AND g.guild_id=s.guild_id AND votes.user_id IS NOT NULL",
+            starboard_id,
+        )
+        .execute(&mut tx)
+        .await?
+        .rows_affected();
+
+        tx.commit().await?;
+
+        Ok(changed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn count(points: i32, unique_voters: i32) -> VoteCount {
+        VoteCount {
+            points,
+            unique_voters,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn max_keeps_values_together() {
+        // the unique voters come from the starboard with the most points,
+        // not from another one
+        let max = VoteCount::max([count(10, 1), count(3, 3)]);
+        assert_eq!(max, count(10, 1));
+    }
+
+    #[test]
+    fn max_breaks_ties_with_unique_voters() {
+        let max = VoteCount::max([count(5, 1), count(5, 4), count(2, 2)]);
+        assert_eq!(max, count(5, 4));
+    }
+
+    #[test]
+    fn max_of_nothing() {
+        assert_eq!(VoteCount::max([]), count(0, 0));
+    }
+
+    #[test]
+    fn missing_starboards_count_as_zero() {
+        let max = VoteCount::max([count(-3, 0), VoteCount::default()]);
+        assert_eq!(max, count(0, 0));
+    }
+
+    #[test]
+    fn max_follows_a_source_losing_votes() {
+        // once the best source loses its votes, the aggregate falls back to
+        // the next one and may drop below its own required points
+        let before = VoteCount::max([count(8, 8), count(4, 4)]);
+        let after = VoteCount::max([VoteCount::default(), count(4, 4)]);
+
+        assert_eq!(before, count(8, 8));
+        assert_eq!(after, count(4, 4));
+    }
 }
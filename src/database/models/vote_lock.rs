@@ -0,0 +1,61 @@
+use chrono::{DateTime, Utc};
+
+/// Stops a user from voting on a message again, after a moderator removed
+/// their vote.
+#[derive(Debug)]
+pub struct VoteLock {
+    /// The original message.
+    pub message_id: i64,
+    pub user_id: i64,
+    /// The moderator that locked the vote, or `None` if the bot did while
+    /// removing an invalid reaction.
+    pub locked_by: Option<i64>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl VoteLock {
+    pub async fn create(
+        pool: &sqlx::PgPool,
+        message_id: i64,
+        user_id: i64,
+        locked_by: Option<i64>,
+    ) -> sqlx::Result<Option<Self>> {
+        sqlx::query_as!(
+            Self,
+            "INSERT INTO vote_locks (message_id, user_id, locked_by) VALUES ($1, $2, $3)
+            ON CONFLICT DO NOTHING RETURNING *",
+            message_id,
+            user_id,
+            locked_by,
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn delete(
+        pool: &sqlx::PgPool,
+        message_id: i64,
+        user_id: i64,
+    ) -> sqlx::Result<Option<Self>> {
+        sqlx::query_as!(
+            Self,
+            "DELETE FROM vote_locks WHERE message_id=$1 AND user_id=$2 RETURNING *",
+            message_id,
+            user_id,
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn exists(pool: &sqlx::PgPool, message_id: i64, user_id: i64) -> sqlx::Result<bool> {
+        let row = sqlx::query!(
+            "SELECT EXISTS(SELECT 1 FROM vote_locks WHERE message_id=$1 AND user_id=$2)",
+            message_id,
+            user_id,
+        )
+        .fetch_one(pool)
+        .await?;
+
+        Ok(row.exists.unwrap_or(false))
+    }
+}
@@ -0,0 +1,76 @@
+/// A member's XP at the end of an XP season.
+#[derive(Debug)]
+pub struct XPSeason {
+    pub guild_id: i64,
+    pub season: i16,
+    pub user_id: i64,
+    pub xp: f32,
+}
+
+impl XPSeason {
+    /// Archives the current season's XP of every member, then starts a new
+    /// season where each member keeps `carryover` (0 to 1) of their XP.
+    /// Returns the number of the season that was closed.
+    pub async fn close_season(
+        pool: &sqlx::PgPool,
+        guild_id: i64,
+        carryover: f32,
+    ) -> sqlx::Result<i16> {
+        let mut tx = pool.begin().await?;
+
+        // lock the guild so that two seasons can't be closed at once
+        let season = sqlx::query!(
+            "SELECT xp_season FROM guilds WHERE guild_id=$1 FOR UPDATE",
+            guild_id,
+        )
+        .fetch_one(&mut tx)
+        .await?
+        .xp_season;
+
+        sqlx::query!(
+            "INSERT INTO xp_seasons (guild_id, season, user_id, xp)
+            SELECT guild_id, $2, user_id, xp FROM members WHERE guild_id=$1 AND xp != 0",
+            guild_id,
+            season,
+        )
+        .execute(&mut tx)
+        .await?;
+        // see `core::xp_seasons::carry_over`
+        sqlx::query!(
+            "UPDATE members SET season_start_xp=season_start_xp + xp * (1 - $2::REAL),
+                xp=xp * $2::REAL
+            WHERE guild_id=$1",
+            guild_id,
+            carryover,
+        )
+        .execute(&mut tx)
+        .await?;
+        sqlx::query!(
+            "UPDATE guilds SET xp_season=xp_season + 1 WHERE guild_id=$1",
+            guild_id,
+        )
+        .execute(&mut tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(season)
+    }
+
+    pub async fn list_by_xp(
+        pool: &sqlx::PgPool,
+        guild_id: i64,
+        season: i16,
+        limit: i64,
+    ) -> sqlx::Result<Vec<Self>> {
+        sqlx::query_as!(
+            Self,
+            "SELECT * FROM xp_seasons WHERE guild_id=$1 AND season=$2 AND xp > 0
+            ORDER BY xp DESC LIMIT $3",
+            guild_id,
+            season,
+            limit,
+        )
+        .fetch_all(pool)
+        .await
+    }
+}
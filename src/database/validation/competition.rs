@@ -0,0 +1,160 @@
+use chrono::{DateTime, Duration, FixedOffset, NaiveDate, NaiveDateTime, TimeZone, Utc};
+
+use crate::constants;
+
+/// Parses a UTC offset like "+02:00", "-5" or "UTC". An empty input means UTC.
+pub fn parse_utc_offset(inp: Option<&str>) -> Result<FixedOffset, String> {
+    let utc = FixedOffset::east_opt(0).unwrap();
+    let Some(inp) = inp.map(str::trim) else {
+        return Ok(utc);
+    };
+    let inp = inp
+        .strip_prefix("UTC")
+        .or_else(|| inp.strip_prefix("GMT"))
+        .unwrap_or(inp);
+    if inp.is_empty() {
+        return Ok(utc);
+    }
+
+    let err = || format!("`{inp}` isn't a valid UTC offset. Try something like `+02:00`.");
+
+    let (sign, rest) = if let Some(rest) = inp.strip_prefix('+') {
+        (1, rest)
+    } else if let Some(rest) = inp.strip_prefix('-') {
+        (-1, rest)
+    } else {
+        return Err(err());
+    };
+    let (hours, minutes) = rest.split_once(':').unwrap_or((rest, "0"));
+    let hours: i32 = hours.parse().map_err(|_| err())?;
+    let minutes: i32 = minutes.parse().map_err(|_| err())?;
+    if hours > 14 || minutes >= 60 {
+        return Err(err());
+    }
+
+    FixedOffset::east_opt(sign * (hours * 3600 + minutes * 60)).ok_or_else(err)
+}
+
+/// Parses "YYYY-MM-DD" or "YYYY-MM-DD HH:MM" in the given offset. A date
+/// alone means midnight.
+pub fn parse_competition_time(inp: &str, offset: FixedOffset) -> Result<DateTime<Utc>, String> {
+    let inp = inp.trim();
+    let naive = NaiveDateTime::parse_from_str(inp, "%Y-%m-%d %H:%M")
+        .ok()
+        .or_else(|| {
+            NaiveDate::parse_from_str(inp, "%Y-%m-%d")
+                .ok()
+                .and_then(|date| date.and_hms_opt(0, 0, 0))
+        });
+    let Some(naive) = naive else {
+        return Err(format!(
+            "I couldn't read `{inp}` as a date. Use `YYYY-MM-DD` or `YYYY-MM-DD HH:MM`."
+        ));
+    };
+
+    match offset.from_local_datetime(&naive).single() {
+        Some(time) => Ok(time.with_timezone(&Utc)),
+        None => Err(format!("`{inp}` isn't a valid time.")),
+    }
+}
+
+/// Whether two competitions' windows overlap. A competition may start right
+/// when another one ends.
+pub fn windows_overlap(
+    a: (DateTime<Utc>, DateTime<Utc>),
+    b: (DateTime<Utc>, DateTime<Utc>),
+) -> bool {
+    a.0 < b.1 && b.0 < a.1
+}
+
+pub fn validate_competition_window(
+    starts_at: DateTime<Utc>,
+    ends_at: DateTime<Utc>,
+    now: DateTime<Utc>,
+) -> Result<(), String> {
+    if ends_at <= starts_at {
+        return Err("The competition has to end after it starts.".to_string());
+    }
+    if ends_at <= now {
+        return Err("The competition has to end in the future.".to_string());
+    }
+    if ends_at - starts_at > Duration::seconds(constants::MAX_COMPETITION_LENGTH) {
+        return Err(format!(
+            "Competitions can last at most {} days.",
+            constants::MAX_COMPETITION_LENGTH / (60 * 60 * 24)
+        ));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(day: u32, hour: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2023, 7, day, hour, 0, 0).unwrap()
+    }
+
+    #[test]
+    fn overlapping_windows() {
+        assert!(windows_overlap(
+            (at(1, 0), at(10, 0)),
+            (at(5, 0), at(15, 0))
+        ));
+        assert!(windows_overlap(
+            (at(5, 0), at(15, 0)),
+            (at(1, 0), at(10, 0))
+        ));
+        assert!(windows_overlap((at(1, 0), at(20, 0)), (at(5, 0), at(6, 0))));
+        assert!(windows_overlap((at(1, 0), at(2, 0)), (at(1, 0), at(2, 0))));
+    }
+
+    #[test]
+    fn separate_windows() {
+        assert!(!windows_overlap((at(1, 0), at(2, 0)), (at(3, 0), at(4, 0))));
+        assert!(!windows_overlap((at(3, 0), at(4, 0)), (at(1, 0), at(2, 0))));
+    }
+
+    #[test]
+    fn back_to_back_windows() {
+        assert!(!windows_overlap((at(1, 0), at(2, 0)), (at(2, 0), at(3, 0))));
+        assert!(!windows_overlap((at(2, 0), at(3, 0)), (at(1, 0), at(2, 0))));
+    }
+
+    #[test]
+    fn window_validation() {
+        let now = at(1, 0);
+        assert!(validate_competition_window(at(2, 0), at(3, 0), now).is_ok());
+        // already running
+        assert!(validate_competition_window(at(1, 0), at(3, 0), at(2, 0)).is_ok());
+        assert!(validate_competition_window(at(3, 0), at(2, 0), now).is_err());
+        assert!(validate_competition_window(at(2, 0), at(2, 0), now).is_err());
+        assert!(validate_competition_window(at(1, 0), at(1, 0), at(2, 0)).is_err());
+    }
+
+    #[test]
+    fn utc_offsets() {
+        let east = |secs| FixedOffset::east_opt(secs).unwrap();
+        assert_eq!(parse_utc_offset(None), Ok(east(0)));
+        assert_eq!(parse_utc_offset(Some("UTC")), Ok(east(0)));
+        assert_eq!(parse_utc_offset(Some("+02:00")), Ok(east(2 * 3600)));
+        assert_eq!(parse_utc_offset(Some("UTC-5")), Ok(east(-5 * 3600)));
+        assert_eq!(parse_utc_offset(Some("+05:30")), Ok(east(5 * 3600 + 1800)));
+        assert!(parse_utc_offset(Some("2")).is_err());
+        assert!(parse_utc_offset(Some("+15")).is_err());
+        assert!(parse_utc_offset(Some("+01:60")).is_err());
+    }
+
+    #[test]
+    fn competition_times() {
+        let utc = FixedOffset::east_opt(0).unwrap();
+        let plus_two = FixedOffset::east_opt(2 * 3600).unwrap();
+        assert_eq!(parse_competition_time("2023-07-05", utc), Ok(at(5, 0)));
+        assert_eq!(
+            parse_competition_time("2023-07-05 12:00", plus_two),
+            Ok(at(5, 10))
+        );
+        assert!(parse_competition_time("05/07/2023", utc).is_err());
+    }
+}
@@ -0,0 +1,48 @@
+//! Parsing forum tags from user input.
+
+use twilight_model::id::{marker::GuildMarker, Id};
+
+use crate::{client::bot::StarboardBot, utils::id_as_i64::GetI64};
+
+/// Parses a comma-separated list of tag names or ids from any of the guild's
+/// forums. Names are matched case-insensitively, so a name that several
+/// forums use matches the tag in each of them. "none" clears the list.
+pub fn parse_forum_tags(
+    bot: &StarboardBot,
+    guild_id: Id<GuildMarker>,
+    inp: &str,
+) -> Result<Vec<i64>, String> {
+    if inp.trim() == "none" {
+        return Ok(Vec::new());
+    }
+
+    let tags = bot.cache.guild_forum_tags(guild_id);
+    let mut ret = Vec::new();
+    for item in inp.split(',').map(str::trim).filter(|i| !i.is_empty()) {
+        let matching: Vec<_> = tags
+            .iter()
+            .filter(|(id, name)| id.to_string() == item || name.eq_ignore_ascii_case(item))
+            .map(|(id, _)| id.get_i64())
+            .collect();
+        if matching.is_empty() {
+            return Err(format!(
+                "No forum in this server has a tag called `{item}`."
+            ));
+        }
+
+        for id in matching {
+            if !ret.contains(&id) {
+                ret.push(id);
+            }
+        }
+    }
+
+    Ok(ret)
+}
+
+pub fn validate_tag_lists(required: &[i64], excluded: &[i64]) -> Result<(), String> {
+    match required.iter().find(|id| excluded.contains(id)) {
+        None => Ok(()),
+        Some(_) => Err("A tag can't be in both `required-tags` and `excluded-tags`.".to_string()),
+    }
+}
@@ -1,6 +1,9 @@
 pub mod color;
+pub mod competition;
 pub mod cooldown;
+pub mod forum_tags;
 pub mod mentions;
+pub mod mod_note;
 pub mod name;
 pub mod regex;
 pub mod starboard_settings;
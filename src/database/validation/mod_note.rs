@@ -0,0 +1,66 @@
+use crate::constants;
+
+pub fn validate_mod_note(note: &str) -> Result<String, String> {
+    let note = note.trim();
+    if note.is_empty() {
+        return Err("The note can't be empty.".to_string());
+    }
+    if note.chars().count() > constants::MAX_MOD_NOTE_LEN as usize {
+        return Err(format!(
+            "The note cannot be longer than {} characters.",
+            constants::MAX_MOD_NOTE_LEN
+        ));
+    }
+
+    Ok(note.to_string())
+}
+
+pub fn validate_mod_note_count(count: i64) -> Result<(), String> {
+    if count >= constants::MAX_MOD_NOTES_PER_MESSAGE {
+        Err(format!(
+            "A message can only have up to {} notes. Delete one with `/utils delete-mod-note` \
+            first.",
+            constants::MAX_MOD_NOTES_PER_MESSAGE
+        ))
+    } else {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn note_is_trimmed() {
+        assert_eq!(validate_mod_note("  spam  \n"), Ok("spam".to_string()));
+    }
+
+    #[test]
+    fn empty_note() {
+        assert!(validate_mod_note("").is_err());
+        assert!(validate_mod_note(" \n ").is_err());
+    }
+
+    #[test]
+    fn note_length_cap() {
+        let max = constants::MAX_MOD_NOTE_LEN as usize;
+
+        assert!(validate_mod_note(&"a".repeat(max)).is_ok());
+        assert!(validate_mod_note(&"a".repeat(max + 1)).is_err());
+        // whitespace around the note doesn't count
+        assert!(validate_mod_note(&format!(" {} ", "a".repeat(max))).is_ok());
+        // characters are counted, not bytes
+        assert!(validate_mod_note(&"⭐".repeat(max)).is_ok());
+    }
+
+    #[test]
+    fn note_count_cap() {
+        let max = constants::MAX_MOD_NOTES_PER_MESSAGE;
+
+        assert!(validate_mod_note_count(0).is_ok());
+        assert!(validate_mod_note_count(max - 1).is_ok());
+        assert!(validate_mod_note_count(max).is_err());
+        assert!(validate_mod_note_count(max + 1).is_err());
+    }
+}
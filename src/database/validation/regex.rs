@@ -21,3 +21,54 @@ pub fn validate_regex(input: String, is_premium: bool) -> Result<Option<String>,
         }
     }
 }
+
+/// Like `validate_regex`, but for the `required-format` setting of autostar
+/// channels.
+pub fn validate_format_regex(input: String, is_premium: bool) -> Result<Option<String>, String> {
+    if !is_premium {
+        return Err("The `required-format` setting requires premium.".to_string());
+    }
+
+    if input.len() > constants::MAX_REGEX_LENGTH as usize {
+        return Err(format!(
+            "The `required-format` setting must be under {} characters.",
+            constants::MAX_REGEX_LENGTH,
+        ));
+    }
+
+    if input == ".*" {
+        Ok(None)
+    } else {
+        match regex::Regex::new(&input) {
+            Ok(_) => Ok(Some(input)),
+            Err(why) => Err(format!("```\n{why}\n```")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn format_needs_premium() {
+        assert!(validate_format_regex("^Title:".to_string(), false).is_err());
+    }
+
+    #[test]
+    fn valid_format() {
+        assert_eq!(
+            validate_format_regex("^Title:".to_string(), true),
+            Ok(Some("^Title:".to_string()))
+        );
+        // matches everything, so it's the same as no format
+        assert_eq!(validate_format_regex(".*".to_string(), true), Ok(None));
+    }
+
+    #[test]
+    fn invalid_format() {
+        assert!(validate_format_regex("(".to_string(), true).is_err());
+        let too_long = "a".repeat(constants::MAX_REGEX_LENGTH as usize + 1);
+        assert!(validate_format_regex(too_long, true).is_err());
+    }
+}
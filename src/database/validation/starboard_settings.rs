@@ -4,7 +4,12 @@
 
 use std::collections::HashSet;
 
-use crate::constants;
+use crate::{
+    constants,
+    core::{emoji_tiers::EmojiTier, vote_weights::EmojiWeight},
+};
+
+use super::time_delta::parse_time_delta;
 
 pub fn none_or_number(val: String) -> Result<Option<i16>, String> {
     if val == "none" {
@@ -70,6 +75,166 @@ pub fn validate_required_remove(val: String, required: Option<i16>) -> Result<Op
     }
 }
 
+pub fn validate_min_unique_voters(val: String) -> Result<Option<i16>, String> {
+    let Some(val) = none_or_number(val)? else {
+        return Ok(None);
+    };
+
+    if val < constants::MIN_REQUIRED {
+        Err(format!(
+            "`min-unique-voters` cannot be less than {}.",
+            constants::MIN_REQUIRED
+        ))
+    } else if val > constants::MAX_REQUIRED {
+        Err(format!(
+            "`min-unique-voters` cannot be greater than {}.",
+            constants::MAX_REQUIRED
+        ))
+    } else {
+        Ok(Some(val))
+    }
+}
+
+/// Warns when `min-unique-voters` is higher than `required` while every
+/// vote is worth 1 point, since then it's what decides when messages are
+/// sent. With weights it can still make sense, so this isn't an error.
+pub fn min_unique_voters_warning(
+    min_unique_voters: Option<i16>,
+    required: Option<i16>,
+    weights: &[EmojiWeight],
+) -> Option<String> {
+    let (min, required) = (min_unique_voters?, required?);
+    if min <= required || weights.iter().any(|w| w.weight != 1) {
+        return None;
+    }
+
+    Some(format!(
+        concat!(
+            "Note: `min-unique-voters` ({}) is higher than `required` ({}), and every vote is ",
+            "worth 1 point, so messages need {} upvotes."
+        ),
+        min, required, min
+    ))
+}
+
+pub fn validate_max_posts_per_author(val: String) -> Result<Option<i16>, String> {
+    let Some(val) = none_or_number(val)? else {
+        return Ok(None);
+    };
+
+    if val < 1 {
+        Err("`max-posts-per-author-per-day` cannot be less than 1.".to_string())
+    } else if val > constants::MAX_MAX_POSTS_PER_AUTHOR {
+        Err(format!(
+            "`max-posts-per-author-per-day` cannot be greater than {}.",
+            constants::MAX_MAX_POSTS_PER_AUTHOR
+        ))
+    } else {
+        Ok(Some(val))
+    }
+}
+
+pub fn validate_min_chars(val: i64) -> Result<i16, String> {
+    if val < 0 {
+        Err("`min-chars` cannot be less than 0.".to_string())
+    } else if val > constants::MAX_MIN_CHARS as i64 {
+        Err(format!(
+            "`min-chars` cannot be greater than {}.",
+            constants::MAX_MIN_CHARS
+        ))
+    } else {
+        Ok(val as i16)
+    }
+}
+
+pub fn validate_max_chars(val: String) -> Result<Option<i16>, String> {
+    let Some(val) = none_or_number(val)? else {
+        return Ok(None);
+    };
+
+    if val < 0 {
+        Err("`max-chars` cannot be less than 0.".to_string())
+    } else if val > constants::MAX_MAX_CHARS {
+        Err(format!(
+            "`max-chars` cannot be greater than {}.",
+            constants::MAX_MAX_CHARS
+        ))
+    } else {
+        Ok(Some(val))
+    }
+}
+
+pub fn validate_char_limits(min_chars: i16, max_chars: Option<i16>) -> Result<(), String> {
+    match max_chars {
+        Some(max_chars) if min_chars > max_chars => {
+            Err("`min-chars` cannot be greater than `max-chars`.".to_string())
+        }
+        _ => Ok(()),
+    }
+}
+
+pub fn validate_emoji_tiers(tiers: &[EmojiTier]) -> Result<(), String> {
+    if tiers.len() > constants::MAX_EMOJI_TIERS {
+        return Err(format!(
+            "You can only have up to {} emoji tiers.",
+            constants::MAX_EMOJI_TIERS
+        ));
+    }
+    if tiers
+        .windows(2)
+        .any(|pair| pair[0].points >= pair[1].points)
+    {
+        return Err("The points for each emoji tier must be strictly increasing.".to_string());
+    }
+    if tiers.iter().any(|tier| tier.points < 0) {
+        return Err("Emoji tiers cannot be for less than 0 points.".to_string());
+    }
+
+    Ok(())
+}
+
+pub fn validate_emoji_weights(weights: &[EmojiWeight]) -> Result<(), String> {
+    if weights.len() > constants::MAX_PREM_VOTE_EMOJIS {
+        return Err(format!(
+            "You can only set weights for up to {} emojis.",
+            constants::MAX_PREM_VOTE_EMOJIS
+        ));
+    }
+    let unique: HashSet<_> = weights.iter().map(|w| &w.emoji).collect();
+    if unique.len() != weights.len() {
+        return Err("Each emoji can only have one weight.".to_string());
+    }
+    if weights
+        .iter()
+        .any(|w| w.weight < 1 || w.weight > constants::MAX_EMOJI_WEIGHT)
+    {
+        return Err(format!(
+            "Emoji weights must be between 1 and {}.",
+            constants::MAX_EMOJI_WEIGHT
+        ));
+    }
+
+    Ok(())
+}
+
+pub fn validate_min_vote_spread(val: &str) -> Result<Option<i32>, String> {
+    if val == "none" {
+        return Ok(None);
+    }
+
+    let seconds = parse_time_delta(val)?;
+    if seconds <= 0 {
+        Ok(None)
+    } else if seconds > constants::MAX_MIN_VOTE_SPREAD {
+        Err(format!(
+            "`min-vote-spread` cannot be greater than {} minutes.",
+            constants::MAX_MIN_VOTE_SPREAD / 60
+        ))
+    } else {
+        Ok(Some(seconds as i32))
+    }
+}
+
 pub fn validate_xp_multiplier(val: f32) -> Result<(), String> {
     if val > constants::MAX_XP_MULTIPLIER {
         Err(format!(
@@ -141,3 +306,113 @@ pub fn validate_vote_emojis(
 
     Ok(())
 }
+
+pub fn validate_aggregate_emojis(upvote: &[String], downvote: &[String]) -> Result<(), String> {
+    if upvote.is_empty() && downvote.is_empty() {
+        Ok(())
+    } else {
+        Err(concat!(
+            "Aggregate starboards can't have their own emojis. They use the points from ",
+            "their source starboards instead."
+        )
+        .to_string())
+    }
+}
+
+pub fn validate_channel_lists(whitelist: &[i64], blacklist: &[i64]) -> Result<(), String> {
+    match whitelist.iter().find(|id| blacklist.contains(id)) {
+        None => Ok(()),
+        Some(id) => Err(format!(
+            "<#{id}> can't be in both `channel-whitelist` and `channel-blacklist`."
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn weight(weight: i16) -> EmojiWeight {
+        EmojiWeight {
+            emoji: "⭐".to_string(),
+            weight,
+        }
+    }
+
+    #[test]
+    fn min_unique_voters_bounds() {
+        assert_eq!(validate_min_unique_voters("none".to_string()), Ok(None));
+        assert_eq!(validate_min_unique_voters("3".to_string()), Ok(Some(3)));
+        assert!(validate_min_unique_voters("0".to_string()).is_err());
+        assert!(validate_min_unique_voters("a".to_string()).is_err());
+        assert!(validate_min_unique_voters((constants::MAX_REQUIRED + 1).to_string()).is_err());
+    }
+
+    #[test]
+    fn max_posts_per_author_bounds() {
+        let max = constants::MAX_MAX_POSTS_PER_AUTHOR;
+        assert_eq!(validate_max_posts_per_author("none".to_string()), Ok(None));
+        assert_eq!(validate_max_posts_per_author("1".to_string()), Ok(Some(1)));
+        assert_eq!(
+            validate_max_posts_per_author(max.to_string()),
+            Ok(Some(max))
+        );
+        assert!(validate_max_posts_per_author("0".to_string()).is_err());
+        assert!(validate_max_posts_per_author((max + 1).to_string()).is_err());
+    }
+
+    #[test]
+    fn emoji_weight_bounds() {
+        assert_eq!(validate_emoji_weights(&[weight(1)]), Ok(()));
+        assert_eq!(
+            validate_emoji_weights(&[weight(constants::MAX_EMOJI_WEIGHT)]),
+            Ok(())
+        );
+        assert!(validate_emoji_weights(&[weight(0)]).is_err());
+        assert!(validate_emoji_weights(&[weight(constants::MAX_EMOJI_WEIGHT + 1)]).is_err());
+    }
+
+    #[test]
+    fn one_weight_per_emoji() {
+        assert!(validate_emoji_weights(&[weight(1), weight(2)]).is_err());
+    }
+
+    #[test]
+    fn emoji_weight_count() {
+        let weights: Vec<_> = (0..=constants::MAX_PREM_VOTE_EMOJIS)
+            .map(|idx| EmojiWeight {
+                emoji: idx.to_string(),
+                weight: 1,
+            })
+            .collect();
+
+        assert!(validate_emoji_weights(&weights[1..]).is_ok());
+        assert!(validate_emoji_weights(&weights).is_err());
+    }
+
+    #[test]
+    fn warns_when_above_required() {
+        assert!(min_unique_voters_warning(Some(5), Some(3), &[]).is_some());
+        assert!(min_unique_voters_warning(Some(5), Some(3), &[weight(1)]).is_some());
+    }
+
+    #[test]
+    fn no_warning_otherwise() {
+        assert_eq!(min_unique_voters_warning(Some(3), Some(3), &[]), None);
+        assert_eq!(min_unique_voters_warning(Some(2), Some(3), &[]), None);
+        assert_eq!(min_unique_voters_warning(None, Some(3), &[]), None);
+        assert_eq!(min_unique_voters_warning(Some(5), None, &[]), None);
+        // weights complicate it
+        assert_eq!(
+            min_unique_voters_warning(Some(5), Some(3), &[weight(1), weight(3)]),
+            None
+        );
+    }
+
+    #[test]
+    fn aggregates_have_no_emojis() {
+        assert!(validate_aggregate_emojis(&[], &[]).is_ok());
+        assert!(validate_aggregate_emojis(&["⭐".to_string()], &[]).is_err());
+        assert!(validate_aggregate_emojis(&[], &["👎".to_string()]).is_err());
+    }
+}
@@ -76,6 +76,15 @@ pub fn parse_time_delta(inp: &str) -> Result<i64, String> {
         seconds += value * conversion;
     }
 
+    // a trailing number without a unit (e.g. "5") would otherwise be dropped
+    if let Some(carry) = carry {
+        if carry.chars().any(|c| c != '0') {
+            return Err(format!(
+                "Please specify a unit for `{carry}` (e.g. `{carry} hours`)."
+            ));
+        }
+    }
+
     Ok(seconds)
 }
 
@@ -113,3 +122,49 @@ pub fn validate_relative_duration(
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn human_durations() {
+        assert_eq!(parse_time_delta("2 days"), Ok(2 * 60 * 60 * 24));
+        assert_eq!(parse_time_delta("3h"), Ok(3 * 60 * 60));
+        assert_eq!(parse_time_delta("1 hour 30m"), Ok(90 * 60));
+        assert_eq!(parse_time_delta("1w 1s"), Ok(60 * 60 * 24 * 7 + 1));
+        assert_eq!(
+            parse_time_delta("2 months"),
+            Ok(2 * constants::MONTH_SECONDS)
+        );
+    }
+
+    #[test]
+    fn zero_disables() {
+        assert_eq!(parse_time_delta("0"), Ok(0));
+        assert_eq!(parse_time_delta("0s"), Ok(0));
+    }
+
+    #[test]
+    fn trailing_number_needs_a_unit() {
+        assert!(parse_time_delta("5").is_err());
+        assert!(parse_time_delta("1h 5").is_err());
+    }
+
+    #[test]
+    fn unknown_units() {
+        assert!(parse_time_delta("5 parsecs").is_err());
+        assert!(parse_time_delta("h").is_err());
+    }
+
+    #[test]
+    fn relative_durations() {
+        assert!(validate_relative_duration(Some(60 * 60), Some(60)).is_ok());
+        assert!(validate_relative_duration(Some(60), Some(60 * 60)).is_err());
+        assert!(validate_relative_duration(Some(60), Some(60)).is_err());
+        // 0 disables either side
+        assert!(validate_relative_duration(Some(0), Some(60)).is_ok());
+        assert!(validate_relative_duration(Some(-1), None).is_err());
+        assert!(validate_relative_duration(None, Some(constants::MAX_OLDER_THAN + 1)).is_err());
+    }
+}
@@ -1,6 +1,6 @@
 use snafu::Backtrace;
 
-use crate::utils::get_status::get_status;
+use crate::{core::discord_limits::Limit, utils::get_status::get_status};
 
 pub type StarboardResult<T> = Result<T, StarboardError>;
 
@@ -27,6 +27,11 @@ pub enum StarboardError {
         backtrace: Backtrace,
     },
     #[snafu(context(false))]
+    ChannelValidationError {
+        source: twilight_validate::channel::ChannelValidationError,
+        backtrace: Backtrace,
+    },
+    #[snafu(context(false))]
     ValidationError {
         source: twilight_validate::request::ValidationError,
         backtrace: Backtrace,
@@ -66,6 +71,18 @@ pub enum StarboardError {
         source: regex::Error,
         backtrace: Backtrace,
     },
+    /// `--takeover` was used, but the shards weren't drained first.
+    #[snafu(
+        display("Can't take over shards that weren't drained: {shards}."),
+        visibility(pub)
+    )]
+    ShardsNotDrained {
+        shards: String,
+        backtrace: Backtrace,
+    },
+    /// Creating something would go over one of Discord's limits.
+    #[snafu(display("{limit}"), visibility(pub))]
+    DiscordLimit { limit: Limit, backtrace: Backtrace },
 }
 
 impl StarboardError {
@@ -75,4 +92,11 @@ impl StarboardError {
             _ => None,
         }
     }
+
+    pub fn discord_limit(&self) -> Option<Limit> {
+        match &self {
+            Self::DiscordLimit { limit, .. } => Some(*limit),
+            _ => None,
+        }
+    }
 }
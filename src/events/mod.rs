@@ -1,9 +1,11 @@
 use std::{sync::Arc, time::Duration};
 
+use tracing::{Instrument, Span};
 use twilight_gateway::{Event, ShardId};
+use twilight_model::application::interaction::InteractionType;
 
 use crate::{
-    cache::models::message::CachedMessage,
+    cache::{self, models::message::CachedMessage},
     client::bot::StarboardBot,
     core,
     errors::StarboardResult,
@@ -11,15 +13,71 @@ use crate::{
     utils::into_id::IntoId,
 };
 
-pub fn handle_event(shard_id: ShardId, event: Event, bot: Arc<StarboardBot>) {
-    tokio::spawn(internal_handle_event(shard_id, event, bot));
+pub mod queue;
+
+/// Queues the event. Only waits if the event's queue is full.
+pub async fn handle_event(shard_id: ShardId, event: Event, bot: Arc<StarboardBot>) {
+    bot.events.dispatch(&bot, shard_id, event).await;
 }
 
 async fn internal_handle_event(shard_id: ShardId, event: Event, bot: Arc<StarboardBot>) {
+    let span = event_span(shard_id, &event);
+    handle_event_in_span(shard_id, event, bot)
+        .instrument(span)
+        .await;
+}
+
+/// A span for handling an event, with the ids it's about so that everything
+/// logged while handling it can be traced back.
+fn event_span(shard_id: ShardId, event: &Event) -> Span {
+    let (channel_id, message_id) = match event {
+        Event::MessageCreate(event) => (Some(event.channel_id), Some(event.id)),
+        Event::MessageUpdate(event) => (Some(event.channel_id), Some(event.id)),
+        Event::MessageDelete(event) => (Some(event.channel_id), Some(event.id)),
+        Event::MessageDeleteBulk(event) => (Some(event.channel_id), None),
+        Event::ReactionAdd(event) => (Some(event.channel_id), Some(event.message_id)),
+        Event::ReactionRemove(event) => (Some(event.channel_id), Some(event.message_id)),
+        Event::ReactionRemoveAll(event) => (Some(event.channel_id), Some(event.message_id)),
+        Event::ReactionRemoveEmoji(event) => (Some(event.channel_id), Some(event.message_id)),
+        Event::ThreadCreate(event) => (Some(event.id), None),
+        Event::ChannelCreate(event) => (Some(event.id), None),
+        Event::ChannelUpdate(event) => (Some(event.id), None),
+        Event::ChannelDelete(event) => (Some(event.id), None),
+        Event::InteractionCreate(int) => (int.channel.as_ref().map(|c| c.id), None),
+        _ => (None, None),
+    };
+
+    tracing::info_span!(
+        "event",
+        kind = ?event.kind(),
+        shard = shard_id.number(),
+        guild_id = event.guild_id().map(|id| id.get()),
+        channel_id = channel_id.map(|id| id.get()),
+        message_id = message_id.map(|id| id.get()),
+    )
+}
+
+async fn handle_event_in_span(shard_id: ShardId, mut event: Event, bot: Arc<StarboardBot>) {
+    let nsfw_toggled = core::starboard::nsfw::nsfw_toggled_channel(&bot, &event);
+    let removed_emojis = core::removed_emojis::removed_emojis(&bot, &event);
+    let guild_channels = cache::hydrate::take_guild_channels(&mut event);
     bot.cache.update(&event).await;
-    bot.standby.process(&event);
+    if let Some(guild_channels) = guild_channels {
+        cache::hydrate::spawn_hydrate_guild(bot.clone(), guild_channels);
+    }
+    if let Some((guild_id, channel_id)) = nsfw_toggled {
+        core::starboard::nsfw::spawn_nsfw_refresh(bot.clone(), guild_id, channel_id);
+    }
+    if let Some((guild_id, removed)) = removed_emojis {
+        core::removed_emojis::spawn_removed_emojis_warning(bot.clone(), guild_id, removed);
+    }
+    // components that a view is waiting for are handled by that view
+    let claimed = bot.standby.process(&event).matched() > 0;
+    if claimed && is_component_interaction(&event) {
+        return;
+    }
 
-    let ret = tokio::spawn(match_events(shard_id, event, bot.clone())).await;
+    let ret = tokio::spawn(match_events(shard_id, event, bot.clone()).in_current_span()).await;
 
     match ret {
         Ok(ret) => match ret {
@@ -30,6 +88,13 @@ async fn internal_handle_event(shard_id: ShardId, event: Event, bot: Arc<Starboa
     }
 }
 
+fn is_component_interaction(event: &Event) -> bool {
+    matches!(
+        event,
+        Event::InteractionCreate(int) if int.kind == InteractionType::MessageComponent
+    )
+}
+
 async fn match_events(
     shard_id: ShardId,
     event: Event,
@@ -38,7 +103,7 @@ async fn match_events(
     match event {
         Event::InteractionCreate(int) => handle_interaction(int.0, bot).await?,
         Event::Ready(info) => {
-            println!("Shard {} connected.", shard_id);
+            tracing::info!("Shard {} connected.", shard_id);
             if bot.application.read().await.is_none() {
                 bot.application.write().await.replace(info.application);
                 post_commands(bot).await;
@@ -54,11 +119,15 @@ async fn match_events(
 
             // the initial message doesn't exist right away, so we need
             // to wait a few seconds. Otherwise, the message won't
-            // receive its reaction.
-            tokio::time::sleep(Duration::from_secs(2)).await;
+            // receive its reaction. This runs outside of the guild's event
+            // queue so the wait doesn't hold up the guild's other events.
+            let autostar = async move {
+                tokio::time::sleep(Duration::from_secs(2)).await;
 
-            if bot.cache.is_channel_forum(guild_id, parent_id) {
-                core::autostar::handle(
+                if !bot.cache.is_channel_forum(guild_id, parent_id) {
+                    return;
+                }
+                let ret = core::autostar::handle(
                     &bot,
                     guild_id,
                     parent_id,
@@ -66,8 +135,12 @@ async fn match_events(
                     event.id.get().into_id(),
                     None,
                 )
-                .await?;
-            }
+                .await;
+                if let Err(why) = ret {
+                    bot.handle_error(&why).await;
+                }
+            };
+            tokio::spawn(autostar.in_current_span());
         }
         Event::MessageCreate(event) => {
             if event.content == format!("<@{}>", bot.config.bot_id) {
@@ -87,6 +160,9 @@ async fn match_events(
             let message_id = event.id;
             let author_id = event.author.id;
             let guild_id = event.guild_id;
+            if let Some(guild_id) = guild_id {
+                core::ignored_sources::count_message(&bot, guild_id, &event.0).await;
+            }
             let msg: Arc<CachedMessage> = Arc::new(event.0.into());
 
             if let Some(guild_id) = guild_id {
@@ -136,9 +212,25 @@ async fn match_events(
             core::starboard::link_events::handle_message_delete(bot, event.id).await?;
         }
         Event::ThreadDelete(event) => {
+            core::deleted_channels::handle_channel_delete(&bot, event.guild_id, event.id).await?;
             core::starboard::link_events::handle_message_delete(bot, event.id.get().into_id())
                 .await?;
         }
+        Event::GuildCreate(event) => {
+            core::guild_purge::handle_guild_create(&bot, event.id).await?;
+        }
+        // unavailable means an outage, not that the bot was removed
+        Event::GuildDelete(event) if !event.unavailable => {
+            core::guild_purge::handle_guild_delete(&bot, event.id).await?;
+        }
+        Event::ChannelDelete(event) => {
+            if let Some(guild_id) = event.guild_id {
+                core::deleted_channels::handle_channel_delete(&bot, guild_id, event.id).await?;
+            }
+        }
+        Event::ChannelCreate(event) => {
+            core::deleted_channels::handle_channel_create(&bot, event.id).await?;
+        }
         _ => {}
     }
 
@@ -0,0 +1,274 @@
+//! Queues gateway events per message, so events for the same message are
+//! handled in order (e.g. a reaction being added and then removed), while
+//! different messages are handled concurrently. Events that aren't about a
+//! message are queued per guild.
+//!
+//! Handlers on the ordered path should only do what needs ordering, like
+//! writing votes. Slow work (refreshing posts, removing reactions while on
+//! cooldown) is spawned so that it doesn't hold up the queue.
+
+use std::{
+    cmp::Reverse,
+    fmt,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+};
+
+use dashmap::DashMap;
+use tokio::sync::{
+    mpsc::{self, error::TrySendError},
+    Semaphore,
+};
+use twilight_gateway::{Event, ShardId};
+use twilight_model::id::{
+    marker::{GuildMarker, MessageMarker},
+    Id,
+};
+
+use crate::{client::bot::StarboardBot, constants};
+
+use super::internal_handle_event;
+
+type QueuedEvent = (ShardId, Event);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EventPriority {
+    /// Dropped when a queue fills up.
+    Low,
+    /// Never dropped. When the queue is full, the gateway waits for room.
+    High,
+}
+
+/// Which queue an event goes through.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum QueueKey {
+    Message(Id<MessageMarker>),
+    Guild(Id<GuildMarker>),
+}
+
+impl QueueKey {
+    pub fn of(event: &Event, guild_id: Id<GuildMarker>) -> Self {
+        let message_id = match event {
+            Event::MessageCreate(event) => event.id,
+            Event::MessageUpdate(event) => event.id,
+            Event::MessageDelete(event) => event.id,
+            Event::ReactionAdd(event) => event.message_id,
+            Event::ReactionRemove(event) => event.message_id,
+            Event::ReactionRemoveAll(event) => event.message_id,
+            Event::ReactionRemoveEmoji(event) => event.message_id,
+            _ => return Self::Guild(guild_id),
+        };
+
+        Self::Message(message_id)
+    }
+}
+
+impl fmt::Display for QueueKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Message(id) => write!(f, "message {id}"),
+            Self::Guild(id) => write!(f, "guild {id}"),
+        }
+    }
+}
+
+impl EventPriority {
+    pub fn of(event: &Event) -> Self {
+        match event {
+            Event::PresenceUpdate(_)
+            | Event::TypingStart(_)
+            | Event::MemberAdd(_)
+            | Event::MemberUpdate(_)
+            | Event::MemberRemove(_)
+            | Event::MemberChunk(_)
+            | Event::VoiceStateUpdate(_)
+            | Event::InviteCreate(_)
+            | Event::InviteDelete(_) => Self::Low,
+            _ => Self::High,
+        }
+    }
+}
+
+pub struct EventQueue {
+    queues: DashMap<QueueKey, mpsc::Sender<QueuedEvent>>,
+    /// Limits how many events are handled at once, across all queues.
+    permits: Semaphore,
+    dropped_low: AtomicU64,
+    /// How often the gateway had to wait for room in a full queue.
+    backpressured: AtomicU64,
+}
+
+impl Default for EventQueue {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl EventQueue {
+    pub fn new() -> Self {
+        Self {
+            queues: DashMap::new(),
+            permits: Semaphore::new(constants::MAX_CONCURRENT_EVENTS),
+            dropped_low: AtomicU64::new(0),
+            backpressured: AtomicU64::new(0),
+        }
+    }
+
+    pub async fn dispatch(&self, bot: &Arc<StarboardBot>, shard_id: ShardId, event: Event) {
+        // Interactions skip the queue. Commands can wait for button clicks,
+        // which would otherwise be queued behind the command itself.
+        if let Event::InteractionCreate(_) = event {
+            tokio::spawn(internal_handle_event(shard_id, event, bot.clone()));
+            return;
+        }
+
+        let Some(guild_id) = event.guild_id() else {
+            tokio::spawn(handle_with_permit(shard_id, event, bot.clone()));
+            return;
+        };
+
+        let key = QueueKey::of(&event, guild_id);
+        let priority = EventPriority::of(&event);
+        let mut queued = (shard_id, event);
+        loop {
+            let tx = {
+                let queue = self.queues.entry(key).or_insert_with(|| {
+                    let (tx, rx) = mpsc::channel(constants::EVENT_QUEUE_SIZE);
+                    tokio::spawn(run_queue(bot.clone(), key, rx));
+                    tx
+                });
+
+                let depth = queue.max_capacity() - queue.capacity();
+                if priority == EventPriority::Low
+                    && depth >= constants::EVENT_QUEUE_LOW_PRIORITY_LIMIT
+                {
+                    self.log_dropped(key);
+                    return;
+                }
+
+                match queue.try_send(queued) {
+                    Ok(()) => return,
+                    Err(TrySendError::Full(event)) => {
+                        if priority == EventPriority::Low {
+                            self.log_dropped(key);
+                            return;
+                        }
+                        queued = event;
+                        queue.clone()
+                    }
+                    Err(TrySendError::Closed(event)) => {
+                        // the worker stopped unexpectedly, so start a new one
+                        drop(queue);
+                        self.queues.remove(&key);
+                        queued = event;
+                        continue;
+                    }
+                }
+            };
+
+            // Votes must not be lost, so wait for room instead. This holds up
+            // the shard's other events too, which is the point.
+            self.backpressured.fetch_add(1, Ordering::Relaxed);
+            match tx.send(queued).await {
+                Ok(()) => return,
+                Err(mpsc::error::SendError(event)) => {
+                    // the worker went idle and stopped while we waited
+                    self.queues.remove_if(&key, |_, tx| tx.is_closed());
+                    queued = event;
+                }
+            }
+        }
+    }
+
+    fn log_dropped(&self, key: QueueKey) {
+        let count = self.dropped_low.fetch_add(1, Ordering::Relaxed) + 1;
+        if count % constants::EVENT_DROP_LOG_INTERVAL == 1 {
+            tracing::warn!(
+                "Event queue for {key} is full, dropped a low priority event ({count} total)."
+            );
+        }
+    }
+
+    pub fn stats(&self) -> EventQueueStats {
+        let mut deepest: Vec<_> = self
+            .queues
+            .iter()
+            .map(|q| (*q.key(), q.max_capacity() - q.capacity()))
+            .collect();
+        let queued = deepest.iter().map(|(_, depth)| depth).sum();
+        deepest.retain(|(_, depth)| *depth != 0);
+        deepest.sort_by_key(|(_, depth)| Reverse(*depth));
+        deepest.truncate(constants::EVENT_QUEUE_STATS_DEEPEST);
+
+        EventQueueStats {
+            queues: self.queues.len(),
+            queued,
+            deepest,
+            available_permits: self.permits.available_permits(),
+            dropped_low: self.dropped_low.load(Ordering::Relaxed),
+            backpressured: self.backpressured.load(Ordering::Relaxed),
+        }
+    }
+}
+
+async fn handle_with_permit(shard_id: ShardId, event: Event, bot: Arc<StarboardBot>) {
+    let _permit = bot.events.permits.acquire().await.unwrap();
+    internal_handle_event(shard_id, event, bot.clone()).await;
+}
+
+async fn run_queue(bot: Arc<StarboardBot>, key: QueueKey, mut rx: mpsc::Receiver<QueuedEvent>) {
+    loop {
+        match tokio::time::timeout(constants::EVENT_QUEUE_IDLE, rx.recv()).await {
+            Ok(Some((shard_id, event))) => handle_with_permit(shard_id, event, bot.clone()).await,
+            Ok(None) => return,
+            Err(_) => {
+                // Stop the worker once the queue is idle. An event that is
+                // sent after this gets `Closed` back, and `dispatch` starts a
+                // new worker for it.
+                let removed = bot
+                    .events
+                    .queues
+                    .remove_if(&key, |_, tx| tx.capacity() == tx.max_capacity());
+                if removed.is_some() {
+                    return;
+                }
+            }
+        }
+    }
+}
+
+pub struct EventQueueStats {
+    pub queues: usize,
+    pub queued: usize,
+    /// The queues with the most events, deepest first.
+    pub deepest: Vec<(QueueKey, usize)>,
+    pub available_permits: usize,
+    pub dropped_low: u64,
+    pub backpressured: u64,
+}
+
+impl fmt::Display for EventQueueStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "queues: {}", self.queues)?;
+        writeln!(f, "queued events: {}", self.queued)?;
+        writeln!(
+            f,
+            "available permits: {}/{}",
+            self.available_permits,
+            constants::MAX_CONCURRENT_EVENTS
+        )?;
+        writeln!(f, "dropped low priority: {}", self.dropped_low)?;
+        writeln!(f, "waited for room: {}", self.backpressured)?;
+
+        if !self.deepest.is_empty() {
+            writeln!(f, "deepest queues:")?;
+        }
+        for (key, depth) in &self.deepest {
+            writeln!(f, "- {key}: {depth}")?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,17 @@
+use twilight_model::application::command::CommandOptionChoice;
+
+use crate::interactions::commands::visibility::{all_command_names, policy};
+
+use super::best_matches::best_matches_as_choices;
+
+/// Commands whose visibility can be overridden.
+pub fn command_name_autocomplete(focused: &str) -> Vec<CommandOptionChoice> {
+    let names = all_command_names();
+    let names: Vec<&str> = names
+        .iter()
+        .map(|n| n.as_str())
+        .filter(|n| policy(n).overridable)
+        .collect();
+
+    best_matches_as_choices(focused, &names, None)
+}
@@ -8,6 +8,7 @@ use crate::{errors::StarboardResult, interactions::context::CommandCtx};
 
 use super::{
     autoredeem::autoredeem_autocomplete, autostar_name::autostar_name_autocomplete,
+    command_name::command_name_autocomplete,
     exclusive_group_name::exclusive_group_name_autocomplete,
     filter_group::filter_group_name_autocomplete, override_name::override_name_autocomplete,
     starboard_name::starboard_name_autocomplete,
@@ -64,6 +65,13 @@ pub async fn handle_autocomplete(ctx: CommandCtx) -> StarboardResult<()> {
         "moststarred starboard" => starboard_name_autocomplete(&ctx, focused).await?,
         "utils force starboard" => starboard_name_autocomplete(&ctx, focused).await?,
         "utils unforce starboard" => starboard_name_autocomplete(&ctx, focused).await?,
+        "utils migrate starboard" => starboard_name_autocomplete(&ctx, focused).await?,
+        "utils purge-user starboard" => starboard_name_autocomplete(&ctx, focused).await?,
+        "competition create starboard" => starboard_name_autocomplete(&ctx, focused).await?,
+        "vote up starboard" => starboard_name_autocomplete(&ctx, focused).await?,
+        "vote down starboard" => starboard_name_autocomplete(&ctx, focused).await?,
+        "vote remove starboard" => starboard_name_autocomplete(&ctx, focused).await?,
+        "utils visibility command" => command_name_autocomplete(focused),
         // premium
         "premium autoredeem disable server" => autoredeem_autocomplete(&ctx, focused).await?,
         "premium-locks move-autostar from" => autostar_name_autocomplete(&ctx, focused).await?,
@@ -97,7 +105,17 @@ pub async fn handle_autocomplete(ctx: CommandCtx) -> StarboardResult<()> {
         "starboards edit behavior exclusive-group" => {
             exclusive_group_name_autocomplete(&ctx, focused).await?
         }
+        "starboards edit aggregate name" => starboard_name_autocomplete(&ctx, focused).await?,
+        "starboards edit aggregate add-source" => {
+            starboard_name_autocomplete(&ctx, focused).await?
+        }
+        "starboards edit aggregate remove-source" => {
+            starboard_name_autocomplete(&ctx, focused).await?
+        }
         "starboards rename current-name" => starboard_name_autocomplete(&ctx, focused).await?,
+        "starboards move name" => starboard_name_autocomplete(&ctx, focused).await?,
+        "starboards schedule name" => starboard_name_autocomplete(&ctx, focused).await?,
+        "starboards emoji-stats name" => starboard_name_autocomplete(&ctx, focused).await?,
         "starboards filters add starboard" => starboard_name_autocomplete(&ctx, focused).await?,
         "starboards filters add filter-group" => {
             filter_group_name_autocomplete(&ctx, focused).await?
@@ -127,6 +145,9 @@ pub async fn handle_autocomplete(ctx: CommandCtx) -> StarboardResult<()> {
         "exclusive-groups rename original-name" => {
             exclusive_group_name_autocomplete(&ctx, focused).await?
         }
+        "exclusive-groups add starboard" => starboard_name_autocomplete(&ctx, focused).await?,
+        "exclusive-groups add group" => exclusive_group_name_autocomplete(&ctx, focused).await?,
+        "exclusive-groups remove starboard" => starboard_name_autocomplete(&ctx, focused).await?,
         // filter groups
         "filters create-filter group" => filter_group_name_autocomplete(&ctx, focused).await?,
         "filters delete-filter group" => filter_group_name_autocomplete(&ctx, focused).await?,
@@ -1,6 +1,7 @@
 mod autoredeem;
 mod autostar_name;
 mod best_matches;
+mod command_name;
 mod exclusive_group_name;
 mod filter_group;
 pub mod handle;
@@ -73,7 +73,7 @@ impl CreateAutoStarChannel {
             )
             .await?;
         } else {
-            ctx.bot.cache.autostar_channel_ids.insert(self.channel.id);
+            ctx.bot.cache.autostar_channel_ids.add(self.channel.id).await;
 
             ctx.respond_str(
                 &format!("Created autostar channel '{name}' in <#{channel_id}>."),
@@ -6,7 +6,7 @@ use crate::{
     errors::StarboardResult,
     get_guild_id,
     interactions::context::CommandCtx,
-    utils::{id_as_i64::GetI64, views::confirm},
+    utils::{id_as_i64::GetI64, into_id::IntoId, views::confirm},
 };
 
 #[derive(CreateCommand, CommandModel)]
@@ -42,6 +42,13 @@ impl DeleteAutoStarChannel {
             is_guild_premium(&ctx.bot, guild_id.get_i64(), true).await?,
         )
         .await?;
+        if let Some(asc) = &ret {
+            ctx.bot
+                .cache
+                .autostar_channel_ids
+                .remove(&ctx.bot.pool, asc.channel_id.into_id())
+                .await?;
+        }
         if ret.is_none() {
             btn_ctx
                 .edit_str("No autostar channel with that name was found.", true)
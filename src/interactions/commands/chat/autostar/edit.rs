@@ -5,7 +5,13 @@ use crate::{
         emoji::{EmojiCommon, SimpleEmoji},
         premium::is_premium::is_guild_premium,
     },
-    database::AutoStarChannel,
+    database::{
+        validation::{
+            self,
+            forum_tags::{parse_forum_tags, validate_tag_lists},
+        },
+        AutoStarChannel,
+    },
     errors::StarboardResult,
     get_guild_id,
     interactions::context::CommandCtx,
@@ -32,6 +38,26 @@ pub struct EditAutoStar {
     /// Whether to delete messages that don't meet requirements.
     #[command(rename = "delete-invalid")]
     delete_invalid: Option<bool>,
+    /// (Premium) A regex that messages must match, checked before anything else. Use ".*" to disable.
+    #[command(rename = "required-format")]
+    required_format: Option<String>,
+    /// An example of the required format, sent to users whose message didn't match. Use "none" to remove.
+    #[command(rename = "format-example", max_length = 500)]
+    format_example: Option<String>,
+    /// The maximum number of attachments a message can have. Set to -1 to disable.
+    #[command(rename = "max-attachments", min_value = -1, max_value = 10)]
+    max_attachments: Option<i64>,
+    /// (Premium) A regex that messages must match. Use ".*" to disable.
+    matches: Option<String>,
+    /// (Premium) A regex that messages must not match. Use ".*" to disable.
+    #[command(rename = "not-matches")]
+    not_matches: Option<String>,
+    /// Forum tags (names, comma-separated) that posts need one of. Use "none" to remove all.
+    #[command(rename = "required-tags")]
+    required_tags: Option<String>,
+    /// Forum tags (names, comma-separated) that posts can't have. Use "none" to remove all.
+    #[command(rename = "excluded-tags")]
+    excluded_tags: Option<String>,
 }
 
 impl EditAutoStar {
@@ -51,6 +77,7 @@ impl EditAutoStar {
 
         let is_prem = is_guild_premium(&ctx.bot, guild_id_i64, true).await?;
 
+        let clear_attention = asc.needs_attention && self.emojis.is_some();
         if let Some(val) = self.emojis {
             let emojis = SimpleEmoji::from_user_input(&val, &ctx.bot, guild_id).into_stored();
             if let Err(why) = asc.set_emojis(emojis, is_prem) {
@@ -78,13 +105,82 @@ impl EditAutoStar {
         if let Some(val) = self.delete_invalid {
             asc.delete_invalid = val;
         }
+        if let Some(val) = self.required_format {
+            match validation::regex::validate_format_regex(val, is_prem) {
+                Err(why) => {
+                    ctx.respond_str(&why, true).await?;
+                    return Ok(());
+                }
+                Ok(val) => asc.required_format_regex = val,
+            }
+        }
+        if let Some(val) = self.format_example {
+            let val = if val == "none" { None } else { Some(val) };
+            if let Err(why) = asc.set_format_example(val) {
+                ctx.respond_str(&why, true).await?;
+                return Ok(());
+            }
+        }
+
+        if let Some(val) = self.max_attachments {
+            let max_attachments = if val == -1 { None } else { Some(val as i16) };
+            if let Err(why) = asc.set_max_attachments(max_attachments) {
+                ctx.respond_str(&why, true).await?;
+                return Ok(());
+            }
+        }
+        if let Some(val) = self.matches {
+            match validation::regex::validate_regex(val, is_prem) {
+                Err(why) => {
+                    ctx.respond_str(&why, true).await?;
+                    return Ok(());
+                }
+                Ok(val) => asc.matches = val,
+            }
+        }
+        if let Some(val) = self.not_matches {
+            match validation::regex::validate_regex(val, is_prem) {
+                Err(why) => {
+                    ctx.respond_str(&why, true).await?;
+                    return Ok(());
+                }
+                Ok(val) => asc.not_matches = val,
+            }
+        }
+
+        if let Some(val) = self.required_tags {
+            match parse_forum_tags(&ctx.bot, guild_id, &val) {
+                Err(why) => {
+                    ctx.respond_str(&why, true).await?;
+                    return Ok(());
+                }
+                Ok(val) => asc.required_tags = val,
+            }
+        }
+        if let Some(val) = self.excluded_tags {
+            match parse_forum_tags(&ctx.bot, guild_id, &val) {
+                Err(why) => {
+                    ctx.respond_str(&why, true).await?;
+                    return Ok(());
+                }
+                Ok(val) => asc.excluded_tags = val,
+            }
+        }
+        if let Err(why) = validate_tag_lists(&asc.required_tags, &asc.excluded_tags) {
+            ctx.respond_str(&why, true).await?;
+            return Ok(());
+        }
 
         let asc = asc.update_settings(&ctx.bot.pool).await?;
 
-        if asc.is_none() {
+        let Some(asc) = asc else {
             ctx.respond_str("No autostar channels with that name were found.", true)
                 .await?;
             return Ok(());
+        };
+        if clear_attention {
+            // emojis from user input are always stored in the current format
+            AutoStarChannel::set_emojis_raw(&ctx.bot.pool, asc.id, &asc.emojis, false).await?;
         }
 
         // set the emojis
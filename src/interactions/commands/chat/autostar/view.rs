@@ -8,7 +8,10 @@ use twilight_util::builder::embed::EmbedFieldBuilder;
 use crate::{
     client::bot::StarboardBot,
     concat_format,
-    core::emoji::{EmojiCommon, SimpleEmoji},
+    core::{
+        emoji::{EmojiCommon, SimpleEmoji},
+        forum_tags::format_tags,
+    },
     database::{
         models::{
             autostar_channel_filter_group::AutostarChannelFilterGroup, filter_group::FilterGroup,
@@ -66,6 +69,9 @@ impl ViewAutoStarChannels {
             if a.premium_locked {
                 label.push_str(" (premium-locked)");
             }
+            if a.channel_deleted {
+                label.push_str(" (channel deleted)");
+            }
 
             let emb = autostar_embed(&bot, guild_id, a).await?;
 
@@ -115,6 +121,27 @@ async fn autostar_embed(
         .map(|v| v.to_string())
         .unwrap_or_else(|| "none".to_string());
 
+    let required_format = asc
+        .required_format_regex
+        .as_ref()
+        .map(|re| format!("`{re}`"))
+        .unwrap_or_else(|| "none".to_string());
+    let format_example = asc.format_example.as_deref().unwrap_or("none");
+    let max_attachments = asc
+        .max_attachments
+        .map(|v| v.to_string())
+        .unwrap_or_else(|| "none".to_string());
+    let matches = asc
+        .matches
+        .as_ref()
+        .map(|re| format!("`{re}`"))
+        .unwrap_or_else(|| "none".to_string());
+    let not_matches = asc
+        .not_matches
+        .as_ref()
+        .map(|re| format!("`{re}`"))
+        .unwrap_or_else(|| "none".to_string());
+
     let note = if asc.premium_locked {
         concat!(
             "This autostar channel is locked because it exceeds the non-premium ",
@@ -123,15 +150,37 @@ async fn autostar_embed(
     } else {
         ""
     };
+    let deleted = if asc.channel_deleted {
+        "This autostar channel's channel was deleted, so it doesn't do anything.\n\n"
+    } else {
+        ""
+    };
+    let attention = if asc.needs_attention {
+        concat!(
+            "Some of the emojis for this autostar channel are stored in a format that can't ",
+            "be read anymore. Please set them again.\n\n"
+        )
+    } else {
+        ""
+    };
 
     let asc_settings = concat_format!(
         "{}" <- note;
+        "{}" <- deleted;
+        "{}" <- attention;
         "This autostar channel is in <#{}>.\n\n" <- asc.channel_id;
         "emojis: {}\n" <- emojis;
         "min-chars: {}\n" <- asc.min_chars;
         "max-chars: {}\n" <- max_chars;
+        "max-attachments: {}\n" <- max_attachments;
         "require-image: {}\n" <- asc.require_image;
-        "delete-invalid: {}" <- asc.delete_invalid;
+        "delete-invalid: {}\n" <- asc.delete_invalid;
+        "matches: {}\n" <- matches;
+        "not-matches: {}\n" <- not_matches;
+        "required-tags: {}\n" <- format_tags(bot, guild_id, &asc.required_tags);
+        "excluded-tags: {}\n" <- format_tags(bot, guild_id, &asc.excluded_tags);
+        "required-format: {}\n" <- required_format;
+        "format-example: {}" <- format_example;
     );
 
     let emb = embed::build()
@@ -0,0 +1,71 @@
+use twilight_interactions::command::{CommandModel, CreateCommand};
+use twilight_model::id::{marker::UserMarker, Id};
+
+use crate::{
+    constants,
+    database::{BlockedVoter, DbGuild},
+    errors::StarboardResult,
+    get_guild_id,
+    interactions::{
+        commands::visibility::{CommandVisibility, VisibilityPolicy},
+        context::CommandCtx,
+    },
+    utils::id_as_i64::GetI64,
+};
+
+#[derive(CommandModel, CreateCommand)]
+#[command(name = "add", desc = "Stop a user's votes from counting.")]
+pub struct Add {
+    /// The user to block.
+    user: Id<UserMarker>,
+    /// Whether to also remove their vote reactions. Requires Manage Messages.
+    #[command(rename = "remove-reactions")]
+    remove_reactions: Option<bool>,
+}
+
+impl CommandVisibility for Add {
+    const VISIBILITY: VisibilityPolicy = VisibilityPolicy::EPHEMERAL;
+}
+
+impl Add {
+    pub async fn callback(self, mut ctx: CommandCtx) -> StarboardResult<()> {
+        let guild_id = get_guild_id!(ctx).get_i64();
+        let user_id = self.user.get_i64();
+        let blocked_by = ctx.interaction.author_id().unwrap().get_i64();
+
+        let count = BlockedVoter::count_by_guild(&ctx.bot.pool, guild_id).await?;
+        if count >= constants::MAX_BLOCKED_VOTERS {
+            ctx.respond_str(
+                &format!(
+                    "You can only block up to {} users.",
+                    constants::MAX_BLOCKED_VOTERS
+                ),
+                true,
+            )
+            .await?;
+            return Ok(());
+        }
+
+        DbGuild::create(&ctx.bot.pool, guild_id).await?;
+        BlockedVoter::create(
+            &ctx.bot.pool,
+            guild_id,
+            user_id,
+            self.remove_reactions.unwrap_or(false),
+            blocked_by,
+        )
+        .await?;
+        ctx.bot.cache.guild_blocked_voters.remove(&guild_id);
+
+        ctx.respond_str(
+            &format!(
+                "Votes from <@{user_id}> won't count anymore. Votes they already cast are \
+                removed the next time a message is recounted."
+            ),
+            false,
+        )
+        .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,121 @@
+use twilight_interactions::command::{CommandModel, CreateCommand};
+use twilight_model::channel::Attachment;
+
+use crate::{
+    constants,
+    core::{
+        blocked_voters::get_blocked_voters,
+        id_import::{download, parse_ids},
+    },
+    database::{BlockedVoter, DbGuild},
+    errors::StarboardResult,
+    get_guild_id,
+    interactions::context::CommandCtx,
+    utils::{id_as_i64::GetI64, views::confirm},
+};
+
+#[derive(CommandModel, CreateCommand)]
+#[command(
+    name = "import",
+    desc = "Block many users at once, from a file of user IDs or mentions."
+)]
+pub struct Import {
+    /// A text or CSV file with one user per line, or separated by commas.
+    file: Attachment,
+    /// Whether to also remove their vote reactions. Requires Manage Messages.
+    #[command(rename = "remove-reactions")]
+    remove_reactions: Option<bool>,
+    /// Preview the import without changing anything.
+    #[command(rename = "dry-run")]
+    dry_run: Option<bool>,
+}
+
+impl Import {
+    pub async fn callback(self, mut ctx: CommandCtx) -> StarboardResult<()> {
+        let guild_id = get_guild_id!(ctx).get_i64();
+        let blocked_by = ctx.interaction.author_id().unwrap().get_i64();
+
+        let Some(file) = download(&ctx.bot, &self.file).await? else {
+            ctx.respond_str(
+                &format!(
+                    "That file is too large. The maximum size is {} KB.",
+                    constants::MAX_ID_IMPORT_SIZE / 1024
+                ),
+                true,
+            )
+            .await?;
+            return Ok(());
+        };
+        let parsed = match parse_ids(&file) {
+            Ok(parsed) => parsed,
+            Err(why) => {
+                ctx.respond_str(&why, true).await?;
+                return Ok(());
+            }
+        };
+
+        let blocked = get_blocked_voters(&ctx.bot, guild_id).await?;
+        let new: Vec<_> = parsed
+            .ids
+            .iter()
+            .copied()
+            .filter(|id| !blocked.contains_key(id))
+            .collect();
+        let summary = parsed.summary(parsed.ids.len() - new.len());
+
+        if self.dry_run == Some(true) || new.is_empty() {
+            ctx.respond_str(&summary, true).await?;
+            return Ok(());
+        }
+        if blocked.len() + new.len() > constants::MAX_BLOCKED_VOTERS as usize {
+            ctx.respond_str(
+                &format!(
+                    "{summary}\nYou can only block up to {} users, and {} are already blocked.",
+                    constants::MAX_BLOCKED_VOTERS,
+                    blocked.len()
+                ),
+                true,
+            )
+            .await?;
+            return Ok(());
+        }
+
+        let prompt = format!("{summary}\nBlock {} user(s)?", new.len());
+        let Some(mut btn_ctx) = confirm::simple(&mut ctx, &prompt, false).await? else {
+            return Ok(());
+        };
+
+        DbGuild::create(&ctx.bot.pool, guild_id).await?;
+        let imported = BlockedVoter::create_bulk(
+            &ctx.bot.pool,
+            guild_id,
+            &new,
+            self.remove_reactions.unwrap_or(false),
+            blocked_by,
+            &ctx.bot.shutting_down,
+        )
+        .await?;
+        ctx.bot.cache.guild_blocked_voters.remove(&guild_id);
+
+        tracing::info!(
+            "Blocklist import: guild {} by user {}, {}/{} users blocked.",
+            guild_id,
+            blocked_by,
+            imported,
+            new.len(),
+        );
+
+        let msg = if imported == new.len() {
+            format!("Blocked {imported} user(s).")
+        } else {
+            format!(
+                "Only {imported} of {} users were blocked because the bot is restarting. Run \
+                the import again to finish it.",
+                new.len()
+            )
+        };
+        btn_ctx.edit_str(&msg, true).await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,64 @@
+use twilight_interactions::command::{CommandModel, CreateCommand};
+
+use crate::{
+    constants,
+    database::BlockedVoter,
+    errors::StarboardResult,
+    get_guild_id,
+    interactions::{
+        commands::visibility::{CommandVisibility, VisibilityPolicy},
+        context::CommandCtx,
+    },
+    utils::{embed, id_as_i64::GetI64, views::paginator},
+};
+
+#[derive(CommandModel, CreateCommand)]
+#[command(name = "list", desc = "List the users whose votes don't count.")]
+pub struct List;
+
+impl CommandVisibility for List {
+    const VISIBILITY: VisibilityPolicy = VisibilityPolicy::EPHEMERAL;
+}
+
+impl List {
+    pub async fn callback(self, mut ctx: CommandCtx) -> StarboardResult<()> {
+        let guild_id = get_guild_id!(ctx).get_i64();
+
+        let blocked = BlockedVoter::list_by_guild(&ctx.bot.pool, guild_id).await?;
+        if blocked.is_empty() {
+            ctx.respond_str("No users are blocked.", true).await?;
+            return Ok(());
+        }
+
+        let lines: Vec<_> = blocked
+            .iter()
+            .map(|b| {
+                format!(
+                    "- <@{}> (`{}`){}",
+                    b.user_id,
+                    b.user_id,
+                    if b.remove_reactions {
+                        ", reactions removed"
+                    } else {
+                        ""
+                    }
+                )
+            })
+            .collect();
+        let pages = lines
+            .chunks(constants::BLOCKLIST_PAGE_SIZE)
+            .map(|chunk| {
+                let emb = embed::build()
+                    .title("Blocked Voters")
+                    .description(chunk.join("\n"))
+                    .build();
+                (None, Some(vec![emb]))
+            })
+            .collect();
+
+        let author_id = ctx.interaction.author_id().unwrap();
+        paginator::simple(&mut ctx, pages, author_id, false).await?;
+
+        Ok(())
+    }
+}
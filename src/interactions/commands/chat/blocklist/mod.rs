@@ -0,0 +1,40 @@
+pub mod add;
+pub mod import;
+pub mod list;
+pub mod remove;
+
+use twilight_interactions::command::{CommandModel, CreateCommand};
+
+use crate::{
+    errors::StarboardResult,
+    interactions::{commands::permissions::manage_messages, context::CommandCtx},
+};
+
+#[derive(CommandModel, CreateCommand)]
+#[command(
+    name = "blocklist",
+    desc = "Manage users whose votes never count.",
+    default_permissions = "manage_messages",
+    dm_permission = false
+)]
+pub enum Blocklist {
+    #[command(name = "add")]
+    Add(add::Add),
+    #[command(name = "import")]
+    Import(Box<import::Import>),
+    #[command(name = "remove")]
+    Remove(remove::Remove),
+    #[command(name = "list")]
+    List(list::List),
+}
+
+impl Blocklist {
+    pub async fn callback(self, ctx: CommandCtx) -> StarboardResult<()> {
+        match self {
+            Self::Add(cmd) => cmd.callback(ctx).await,
+            Self::Import(cmd) => cmd.callback(ctx).await,
+            Self::Remove(cmd) => cmd.callback(ctx).await,
+            Self::List(cmd) => cmd.callback(ctx).await,
+        }
+    }
+}
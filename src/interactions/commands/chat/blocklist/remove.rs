@@ -0,0 +1,44 @@
+use twilight_interactions::command::{CommandModel, CreateCommand};
+use twilight_model::id::{marker::UserMarker, Id};
+
+use crate::{
+    database::BlockedVoter,
+    errors::StarboardResult,
+    get_guild_id,
+    interactions::{
+        commands::visibility::{CommandVisibility, VisibilityPolicy},
+        context::CommandCtx,
+    },
+    utils::id_as_i64::GetI64,
+};
+
+#[derive(CommandModel, CreateCommand)]
+#[command(name = "remove", desc = "Let a blocked user's votes count again.")]
+pub struct Remove {
+    /// The user to unblock.
+    user: Id<UserMarker>,
+}
+
+impl CommandVisibility for Remove {
+    const VISIBILITY: VisibilityPolicy = VisibilityPolicy::EPHEMERAL;
+}
+
+impl Remove {
+    pub async fn callback(self, mut ctx: CommandCtx) -> StarboardResult<()> {
+        let guild_id = get_guild_id!(ctx).get_i64();
+        let user_id = self.user.get_i64();
+
+        let removed = BlockedVoter::delete(&ctx.bot.pool, guild_id, user_id).await?;
+        if removed.is_none() {
+            ctx.respond_str(&format!("<@{user_id}> isn't blocked."), true)
+                .await?;
+            return Ok(());
+        }
+        ctx.bot.cache.guild_blocked_voters.remove(&guild_id);
+
+        ctx.respond_str(&format!("Votes from <@{user_id}> will count again."), false)
+            .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,74 @@
+use std::fmt::Write;
+
+use twilight_interactions::command::{CommandModel, CreateCommand};
+use twilight_util::builder::embed::EmbedFieldBuilder;
+
+use crate::{
+    client::build_info,
+    constants,
+    core::features::{is_feature_enabled, Flag},
+    errors::StarboardResult,
+    interactions::context::CommandCtx,
+    utils::{embed, id_as_i64::GetI64},
+};
+
+#[derive(CommandModel, CreateCommand)]
+#[command(name = "botinfo", desc = "Show which version of the bot is running.")]
+pub struct BotInfo;
+
+impl BotInfo {
+    pub async fn callback(self, mut ctx: CommandCtx) -> StarboardResult<()> {
+        let bot = &ctx.bot;
+
+        let version = format!(
+            "Version: {} (`{}`)\nBuilt: <t:{}:f>\nCompiler: {}\nLast Restart: <t:{}:R>",
+            build_info::VERSION,
+            build_info::GIT_HASH,
+            build_info::built_at(),
+            build_info::RUSTC_VERSION,
+            bot.start.timestamp(),
+        );
+
+        let range = bot.config.shard_range();
+        let mut shards = format!(
+            "Process: `{}`\nShards: {}-{} of {}",
+            bot.shards.instance_id,
+            range.start,
+            range.end - 1,
+            bot.config.total_shards,
+        );
+        if let Some(guild_id) = ctx.interaction.guild_id {
+            let shard = bot.config.shard_for_guild(guild_id.get());
+            write!(shards, "\nThis server: shard {shard}").unwrap();
+        }
+
+        let mut embed = embed::build()
+            .title("Starboard's Info")
+            .description(version)
+            .field(EmbedFieldBuilder::new("Shards", shards));
+
+        if let Some(guild_id) = ctx.interaction.guild_id {
+            let mut features = String::new();
+            for flag in Flag::ALL {
+                let enabled = is_feature_enabled(bot, flag, guild_id.get_i64()).await?;
+                let status = if enabled { "on" } else { "off" };
+                writeln!(features, "`{flag}`: {status}").unwrap();
+            }
+            embed = embed.field(EmbedFieldBuilder::new("Features in this Server", features));
+        }
+
+        let links = format!(
+            "[Documentation]({})\n[Support Server]({})\n[Invite]({})\n[Source Code]({})",
+            constants::DOCS_URL,
+            constants::SUPPORT_URL,
+            constants::INVITE_URL,
+            constants::SOURCE_URL,
+        );
+        let embed = embed.field(EmbedFieldBuilder::new("Links", links)).build();
+
+        ctx.respond(ctx.build_resp().embeds([embed]).build())
+            .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,152 @@
+use chrono::Utc;
+use twilight_interactions::command::{CommandModel, CreateCommand};
+use twilight_model::application::interaction::application_command::InteractionChannel;
+
+use crate::{
+    constants,
+    database::{
+        validation::competition::{
+            parse_competition_time, parse_utc_offset, validate_competition_window, windows_overlap,
+        },
+        Competition, DbGuild, Starboard,
+    },
+    errors::StarboardResult,
+    get_guild_id,
+    interactions::context::CommandCtx,
+    utils::id_as_i64::GetI64,
+};
+
+#[derive(CommandModel, CreateCommand)]
+#[command(name = "create", desc = "Create a competition for a starboard.")]
+pub struct Create {
+    /// The starboard to run the competition on.
+    #[command(autocomplete = true)]
+    starboard: String,
+    /// When the competition starts (YYYY-MM-DD or YYYY-MM-DD HH:MM).
+    start: String,
+    /// When the competition ends (YYYY-MM-DD or YYYY-MM-DD HH:MM).
+    end: String,
+    /// Where to announce the winners.
+    #[command(channel_types = r#"
+            guild_text
+            guild_announcement
+            announcement_thread
+            public_thread
+            private_thread
+        "#)]
+    channel: InteractionChannel,
+    /// What the winners get.
+    prize: Option<String>,
+    /// The UTC offset for the start and end times, e.g. "+02:00". Defaults to UTC.
+    #[command(rename = "utc-offset")]
+    utc_offset: Option<String>,
+}
+
+impl Create {
+    pub async fn callback(self, mut ctx: CommandCtx) -> StarboardResult<()> {
+        let guild_id = get_guild_id!(ctx).get_i64();
+        DbGuild::create(&ctx.bot.pool, guild_id).await?;
+
+        let Some(starboard) =
+            Starboard::get_by_name(&ctx.bot.pool, &self.starboard, guild_id).await?
+        else {
+            let msg = ctx
+                .t("errors.unknown-starboard", &[("name", &self.starboard)])
+                .await?;
+            ctx.respond_str(&msg, true).await?;
+            return Ok(());
+        };
+
+        let window = parse_utc_offset(self.utc_offset.as_deref()).and_then(|offset| {
+            let starts_at = parse_competition_time(&self.start, offset)?;
+            let ends_at = parse_competition_time(&self.end, offset)?;
+            validate_competition_window(starts_at, ends_at, Utc::now())?;
+            Ok((starts_at, ends_at))
+        });
+        let (starts_at, ends_at) = match window {
+            Ok(window) => window,
+            Err(why) => {
+                ctx.respond_str(&why, true).await?;
+                return Ok(());
+            }
+        };
+
+        let prize = self.prize.as_deref().map(str::trim);
+        if prize.is_some_and(|p| p.chars().count() > constants::MAX_COMPETITION_PRIZE_LENGTH) {
+            ctx.respond_str(
+                &format!(
+                    "The prize can be at most {} characters long.",
+                    constants::MAX_COMPETITION_PRIZE_LENGTH
+                ),
+                true,
+            )
+            .await?;
+            return Ok(());
+        }
+
+        let mut tx = ctx.bot.pool.begin().await?;
+        Competition::lock_starboard(&mut tx, starboard.id).await?;
+
+        let count = Competition::count_unfinished_by_guild(&mut tx, guild_id).await?;
+        if count >= constants::MAX_UNFINISHED_COMPETITIONS {
+            ctx.respond_str(
+                &format!(
+                    "You can only have up to {} upcoming or running competitions.",
+                    constants::MAX_UNFINISHED_COMPETITIONS
+                ),
+                true,
+            )
+            .await?;
+            return Ok(());
+        }
+
+        let others =
+            Competition::list_by_starboard_ending_after(&mut tx, starboard.id, starts_at).await?;
+        if let Some(other) = others
+            .iter()
+            .find(|c| windows_overlap((c.starts_at, c.ends_at), (starts_at, ends_at)))
+        {
+            ctx.respond_str(
+                &format!(
+                    "That overlaps with another competition on '{}' (<t:{}:f> to <t:{}:f>).",
+                    starboard.name,
+                    other.starts_at.timestamp(),
+                    other.ends_at.timestamp(),
+                ),
+                true,
+            )
+            .await?;
+            return Ok(());
+        }
+
+        Competition::create(
+            &mut tx,
+            guild_id,
+            starboard.id,
+            self.channel.id.get_i64(),
+            prize,
+            starts_at,
+            ends_at,
+        )
+        .await?;
+        tx.commit().await?;
+
+        ctx.respond_str(
+            &format!(
+                concat!(
+                    "Created a competition on '{}' from <t:{}:f> to <t:{}:f>. The posts that ",
+                    "gain the most points during that time win, and the winners will be ",
+                    "announced in <#{}>."
+                ),
+                starboard.name,
+                starts_at.timestamp(),
+                ends_at.timestamp(),
+                self.channel.id,
+            ),
+            false,
+        )
+        .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,70 @@
+use std::fmt::Write;
+
+use twilight_interactions::command::{CommandModel, CreateCommand};
+
+use crate::{
+    constants,
+    core::competitions::format_winner,
+    database::{Competition, Starboard},
+    errors::StarboardResult,
+    get_guild_id,
+    interactions::context::CommandCtx,
+    utils::{embed, id_as_i64::GetI64},
+};
+
+#[derive(CommandModel, CreateCommand)]
+#[command(name = "history", desc = "View past competitions and their winners.")]
+pub struct History;
+
+impl History {
+    pub async fn callback(self, mut ctx: CommandCtx) -> StarboardResult<()> {
+        let guild_id = get_guild_id!(ctx).get_i64();
+
+        let competitions = Competition::list_finished_by_guild(
+            &ctx.bot.pool,
+            guild_id,
+            constants::COMPETITION_HISTORY_LENGTH,
+        )
+        .await?;
+        if competitions.is_empty() {
+            ctx.respond_str("This server hasn't finished any competitions yet.", true)
+                .await?;
+            return Ok(());
+        }
+
+        let mut description = String::new();
+        for competition in competitions {
+            let starboard = Starboard::get(&ctx.bot.pool, competition.starboard_id).await?;
+            let name = starboard.map_or("deleted starboard".to_string(), |s| s.name);
+
+            writeln!(
+                description,
+                "**'{}'**, <t:{}:d> to <t:{}:d>",
+                name,
+                competition.starts_at.timestamp(),
+                competition.ends_at.timestamp(),
+            )
+            .unwrap();
+            if let Some(prize) = &competition.prize {
+                writeln!(description, "Prize: {prize}").unwrap();
+            }
+
+            let winners = competition.list_winners(&ctx.bot.pool).await?;
+            if winners.is_empty() {
+                writeln!(description, "No winners.").unwrap();
+            }
+            for winner in &winners {
+                writeln!(description, "{}", format_winner(&competition, winner)).unwrap();
+            }
+            description.push('\n');
+        }
+
+        let emb = embed::build()
+            .title("Past Competitions")
+            .description(description)
+            .build();
+        ctx.respond(ctx.build_resp().embeds([emb]).build()).await?;
+
+        Ok(())
+    }
+}
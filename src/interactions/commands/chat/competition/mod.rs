@@ -0,0 +1,33 @@
+mod create;
+mod history;
+
+use twilight_interactions::command::{CommandModel, CreateCommand};
+
+use crate::{
+    errors::StarboardResult,
+    interactions::{commands::permissions::manage_guild, context::CommandCtx},
+};
+
+#[allow(clippy::large_enum_variant)] // Create(create::Create) being the culprit
+#[derive(CommandModel, CreateCommand)]
+#[command(
+    name = "competition",
+    desc = "Run time-boxed competitions for the best starboard posts.",
+    dm_permission = false,
+    default_permissions = "manage_guild"
+)]
+pub enum Competition {
+    #[command(name = "create")]
+    Create(create::Create),
+    #[command(name = "history")]
+    History(history::History),
+}
+
+impl Competition {
+    pub async fn callback(self, ctx: CommandCtx) -> StarboardResult<()> {
+        match self {
+            Self::Create(cmd) => cmd.callback(ctx).await,
+            Self::History(cmd) => cmd.callback(ctx).await,
+        }
+    }
+}
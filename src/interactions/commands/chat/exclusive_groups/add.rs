@@ -0,0 +1,63 @@
+use twilight_interactions::command::{CommandModel, CreateCommand};
+
+use crate::{
+    database::{ExclusiveGroup, Starboard},
+    errors::StarboardResult,
+    get_guild_id,
+    interactions::context::CommandCtx,
+    utils::id_as_i64::GetI64,
+};
+
+#[derive(CommandModel, CreateCommand)]
+#[command(name = "add", desc = "Add a starboard to an exclusive group.")]
+pub struct Add {
+    /// The starboard to add.
+    #[command(autocomplete = true)]
+    starboard: String,
+    /// The exclusive group to add it to. A starboard can only be in one group.
+    #[command(autocomplete = true)]
+    group: String,
+    /// Posts move to the starboard with the highest priority in the group.
+    #[command(min_value = -50, max_value = 50)]
+    priority: Option<i64>,
+}
+
+impl Add {
+    pub async fn callback(self, mut ctx: CommandCtx) -> StarboardResult<()> {
+        let guild_id = get_guild_id!(ctx).get_i64();
+
+        let Some(group) = ExclusiveGroup::get_by_name(&ctx.bot.pool, guild_id, &self.group).await?
+        else {
+            ctx.respond_str(
+                &format!("Exclusive group '{}' does not exist.", self.group),
+                true,
+            )
+            .await?;
+            return Ok(());
+        };
+        let Some(mut starboard) =
+            Starboard::get_by_name(&ctx.bot.pool, &self.starboard, guild_id).await?
+        else {
+            ctx.respond_str("No starboard with that name was found.", true)
+                .await?;
+            return Ok(());
+        };
+
+        starboard.settings.exclusive_group = Some(group.id);
+        if let Some(priority) = self.priority {
+            starboard.settings.exclusive_group_priority = priority as i16;
+        }
+        starboard.update_settings(&ctx.bot.pool).await?;
+
+        ctx.respond_str(
+            &format!(
+                "Added starboard '{}' to exclusive group '{}'.",
+                self.starboard, group.name
+            ),
+            false,
+        )
+        .await?;
+
+        Ok(())
+    }
+}
@@ -1,5 +1,7 @@
+mod add;
 mod create;
 mod delete;
+mod remove;
 mod rename;
 
 use twilight_interactions::command::{CommandModel, CreateCommand};
@@ -23,6 +25,10 @@ pub enum ExclusiveGroups {
     Delete(delete::Delete),
     #[command(name = "rename")]
     Rename(rename::Rename),
+    #[command(name = "add")]
+    Add(add::Add),
+    #[command(name = "remove")]
+    Remove(remove::Remove),
 }
 
 impl ExclusiveGroups {
@@ -31,6 +37,8 @@ impl ExclusiveGroups {
             Self::Create(cmd) => cmd.callback(ctx).await,
             Self::Delete(cmd) => cmd.callback(ctx).await,
             Self::Rename(cmd) => cmd.callback(ctx).await,
+            Self::Add(cmd) => cmd.callback(ctx).await,
+            Self::Remove(cmd) => cmd.callback(ctx).await,
         }
     }
 }
@@ -0,0 +1,53 @@
+use twilight_interactions::command::{CommandModel, CreateCommand};
+
+use crate::{
+    database::Starboard, errors::StarboardResult, get_guild_id, interactions::context::CommandCtx,
+    utils::id_as_i64::GetI64,
+};
+
+#[derive(CommandModel, CreateCommand)]
+#[command(name = "remove", desc = "Remove a starboard from its exclusive group.")]
+pub struct Remove {
+    /// The starboard to remove.
+    #[command(autocomplete = true)]
+    starboard: String,
+}
+
+impl Remove {
+    pub async fn callback(self, mut ctx: CommandCtx) -> StarboardResult<()> {
+        let guild_id = get_guild_id!(ctx).get_i64();
+
+        let Some(mut starboard) =
+            Starboard::get_by_name(&ctx.bot.pool, &self.starboard, guild_id).await?
+        else {
+            ctx.respond_str("No starboard with that name was found.", true)
+                .await?;
+            return Ok(());
+        };
+        if starboard.settings.exclusive_group.is_none() {
+            ctx.respond_str(
+                &format!(
+                    "Starboard '{}' isn't in an exclusive group.",
+                    self.starboard
+                ),
+                true,
+            )
+            .await?;
+            return Ok(());
+        }
+
+        starboard.settings.exclusive_group = None;
+        starboard.update_settings(&ctx.bot.pool).await?;
+
+        ctx.respond_str(
+            &format!(
+                "Removed starboard '{}' from its exclusive group.",
+                self.starboard
+            ),
+            false,
+        )
+        .await?;
+
+        Ok(())
+    }
+}
@@ -44,7 +44,7 @@ impl Rename {
             }
             Ok(None) => format!("Exclusive group '{}' does not exist.", self.original_name),
             Ok(Some(_)) => {
-                ctx.respond_str("Done.", true).await?;
+                ctx.respond_str("Done.", false).await?;
                 return Ok(());
             }
         };
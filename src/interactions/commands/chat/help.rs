@@ -5,35 +5,41 @@ use twilight_model::channel::message::{
 };
 use twilight_util::builder::embed::EmbedFieldBuilder;
 
-use crate::{
-    concat_format, constants, errors::StarboardResult, interactions::context::CommandCtx,
-    utils::embed,
-};
+use crate::{constants, errors::StarboardResult, interactions::context::CommandCtx, utils::embed};
 
-fn buttons() -> Vec<Component> {
-    let link_btn = |name: &str, link: &str| {
+async fn buttons(ctx: &CommandCtx) -> StarboardResult<Vec<Component>> {
+    let link_btn = |name: String, link: &str| {
         Component::Button(Button {
             custom_id: None,
             disabled: false,
             emoji: None,
-            label: Some(name.into()),
+            label: Some(name),
             style: ButtonStyle::Link,
             url: Some(link.into()),
         })
     };
 
     let buttons = vec![
-        link_btn("Invite", constants::INVITE_URL),
-        link_btn("Support", constants::SUPPORT_URL),
-        link_btn("Documentation", constants::DOCS_URL),
-        link_btn("Source", constants::SOURCE_URL),
+        link_btn(
+            ctx.t("help.buttons.invite", &[]).await?,
+            constants::INVITE_URL,
+        ),
+        link_btn(
+            ctx.t("help.buttons.support", &[]).await?,
+            constants::SUPPORT_URL,
+        ),
+        link_btn(ctx.t("help.buttons.docs", &[]).await?, constants::DOCS_URL),
+        link_btn(
+            ctx.t("help.buttons.source", &[]).await?,
+            constants::SOURCE_URL,
+        ),
     ];
 
     let row = Component::ActionRow(ActionRow {
         components: buttons,
     });
 
-    vec![row]
+    Ok(vec![row])
 }
 
 #[derive(CommandModel, CreateCommand)]
@@ -42,42 +48,42 @@ pub struct Help;
 
 impl Help {
     pub async fn callback(self, mut ctx: CommandCtx) -> StarboardResult<()> {
+        let support = ctx
+            .t(
+                "help.support.value",
+                &[
+                    ("vote", &constants::VOTE_URL),
+                    ("review", &constants::REVIEW_URL),
+                    ("patreon", &constants::PATREON_URL),
+                ],
+            )
+            .await?;
+
         let emb = embed::build()
-            .title("Starboard - The Best Discord Starboard")
-            .description("Starboard is a reliable and feature-rich starboard bot for Discord.")
+            .title(ctx.t("help.title", &[]).await?)
+            .description(ctx.t("help.description", &[]).await?)
             .field(EmbedFieldBuilder::new(
-                "Useful Commands",
-                concat!(
-                    "`/starboards view`: View all of your starboards.\n",
-                    "`/starboards create`: Create a new starboard.\n",
-                    "`/starboards edit`: Edit the configuration for a starboard.\n",
-                ),
+                ctx.t("help.commands.name", &[]).await?,
+                ctx.t("help.commands.value", &[]).await?,
             ))
             .field(EmbedFieldBuilder::new(
-                "Starboard's Features",
-                concat!(
-                    "Starboard's key free features are:\n",
-                    "- Multiple starboards\n",
-                    "- Custom avatar/username for starboards (via webhooks)\n",
-                    "- Autostar channels\n",
-                    "- Complete per-channel starboard configuration\n",
-                    "- Limited per-role starboard configuration\n",
-                ),
+                ctx.t("help.features.name", &[]).await?,
+                ctx.t("help.features.value", &[]).await?,
             ))
             .field(EmbedFieldBuilder::new(
-                "Support Starboard",
-                concat_format!(
-                    "If you like Starboard and want to support it, you can do ";
-                    "so by [voting]({}) or " <- constants::VOTE_URL;
-                    "[leaving a review]({}) " <- constants::REVIEW_URL;
-                    "on Top.GG.\n\nIf you really want, you can also get Starboard Premium ";
-                    "by [becoming a patron]({})." <- constants::PATREON_URL;
-                ),
+                ctx.t("help.support.name", &[]).await?,
+                support,
             ))
             .build();
 
-        ctx.respond(ctx.build_resp().embeds([emb]).components(buttons()).build())
-            .await?;
+        let components = buttons(&ctx).await?;
+        ctx.respond(
+            ctx.build_resp()
+                .embeds([emb])
+                .components(components)
+                .build(),
+        )
+        .await?;
 
         Ok(())
     }
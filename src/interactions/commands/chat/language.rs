@@ -0,0 +1,101 @@
+use twilight_interactions::command::{CommandModel, CreateCommand};
+use twilight_model::guild::Permissions;
+
+use crate::{
+    database::{DbGuild, DbUser},
+    errors::StarboardResult,
+    get_guild_id,
+    interactions::{commands::choices::locale::LanguageChoice, context::CommandCtx},
+    locale::Locale,
+    utils::id_as_i64::GetI64,
+};
+
+#[derive(CommandModel, CreateCommand)]
+#[command(
+    name = "server",
+    desc = "Set the language Starboard uses in this server."
+)]
+pub struct Server {
+    /// The language to use.
+    language: LanguageChoice,
+}
+
+impl Server {
+    pub async fn callback(self, mut ctx: CommandCtx) -> StarboardResult<()> {
+        let guild_id = get_guild_id!(ctx).get_i64();
+
+        let can_manage = ctx
+            .interaction
+            .member
+            .as_ref()
+            .and_then(|m| m.permissions)
+            .is_some_and(|p| p.contains(Permissions::MANAGE_GUILD));
+        if !can_manage {
+            let msg = ctx.t("language.no-permission", &[]).await?;
+            ctx.respond_str(&msg, true).await?;
+            return Ok(());
+        }
+
+        let locale: Locale = self.language.into();
+        DbGuild::create(&ctx.bot.pool, guild_id).await?;
+        DbGuild::set_locale(&ctx.bot.pool, guild_id, locale.code()).await?;
+        ctx.bot.cache.guild_locales.insert(guild_id, locale);
+
+        let msg = ctx
+            .t("language.server-set", &[("language", &locale.name())])
+            .await?;
+        ctx.respond_str(&msg, false).await?;
+
+        Ok(())
+    }
+}
+
+#[derive(CommandModel, CreateCommand)]
+#[command(
+    name = "personal",
+    desc = "Set the language Starboard uses when replying to you."
+)]
+pub struct Personal {
+    /// The language to use. Leave empty to use the server's language.
+    language: Option<LanguageChoice>,
+}
+
+impl Personal {
+    pub async fn callback(self, mut ctx: CommandCtx) -> StarboardResult<()> {
+        let user_id = ctx.interaction.author_id().unwrap().get_i64();
+
+        let locale: Option<Locale> = self.language.map(|l| l.into());
+        DbUser::create(&ctx.bot.pool, user_id, false).await?;
+        DbUser::set_locale(&ctx.bot.pool, user_id, locale.map(|l| l.code())).await?;
+        ctx.bot.cache.user_locales.insert(user_id, locale).await;
+
+        let msg = match locale {
+            Some(locale) => {
+                ctx.t("language.personal-set", &[("language", &locale.name())])
+                    .await?
+            }
+            None => ctx.t("language.personal-reset", &[]).await?,
+        };
+        ctx.respond_str(&msg, true).await?;
+
+        Ok(())
+    }
+}
+
+#[derive(CommandModel, CreateCommand)]
+#[command(name = "language", desc = "Change the language Starboard responds in.")]
+pub enum Language {
+    #[command(name = "server")]
+    Server(Server),
+    #[command(name = "personal")]
+    Personal(Personal),
+}
+
+impl Language {
+    pub async fn callback(self, ctx: CommandCtx) -> StarboardResult<()> {
+        match self {
+            Self::Server(cmd) => cmd.callback(ctx).await,
+            Self::Personal(cmd) => cmd.callback(ctx).await,
+        }
+    }
+}
@@ -2,7 +2,7 @@ use futures::TryStreamExt;
 use twilight_interactions::command::{CommandModel, CreateCommand};
 
 use crate::{
-    database::DbMember,
+    database::{DbGuild, DbMember, XPSeason},
     errors::StarboardResult,
     get_guild_id,
     interactions::context::CommandCtx,
@@ -19,6 +19,9 @@ pub struct Leaderboard {
     /// Whether to include users who've left. False by default.
     #[command(rename = "include-gone")]
     include_gone: Option<bool>,
+    /// The XP season to show. Defaults to the current season.
+    #[command(min_value = 1, max_value = 32_767)]
+    season: Option<i64>,
 }
 
 impl Leaderboard {
@@ -28,8 +31,27 @@ impl Leaderboard {
 
         let include_gone = self.include_gone == Some(true);
 
-        let lb = if include_gone {
-            DbMember::list_by_xp(&ctx.bot.pool, guild_id_i64, 99).await?
+        let current_season = DbGuild::get(&ctx.bot.pool, guild_id_i64)
+            .await?
+            .map(|g| g.xp_season)
+            .unwrap_or(1);
+        let season = self
+            .season
+            .map(|s| s as i16)
+            .filter(|&s| s != current_season);
+
+        let lb: Vec<(i64, f32)> = if let Some(season) = season {
+            XPSeason::list_by_xp(&ctx.bot.pool, guild_id_i64, season, 99)
+                .await?
+                .into_iter()
+                .map(|m| (m.user_id, m.xp))
+                .collect()
+        } else if include_gone {
+            DbMember::list_by_xp(&ctx.bot.pool, guild_id_i64, 99)
+                .await?
+                .into_iter()
+                .map(|m| (m.user_id, m.xp))
+                .collect()
         } else {
             ctx.defer(false).await?;
 
@@ -46,7 +68,7 @@ impl Leaderboard {
                     continue;
                 }
 
-                lb.push(member);
+                lb.push((member.user_id, member.xp));
 
                 if lb.len() >= 99 {
                     break;
@@ -60,7 +82,7 @@ impl Leaderboard {
         let pages = lb.chunks(9).map(|chunk| {
             chunk
                 .iter()
-                .map(|DbMember { user_id, xp, .. }| {
+                .map(|(user_id, xp)| {
                     idx += 1;
                     format!("`#{idx}` <@{user_id}> - {xp} XP\n")
                 })
@@ -71,10 +93,10 @@ impl Leaderboard {
                 (
                     None,
                     Some(vec![embed::build()
-                        .title(if include_gone {
-                            "Leaderboard (Including Gone)"
-                        } else {
-                            "Leaderboard"
+                        .title(match season {
+                            Some(season) => format!("Leaderboard (Season {season})"),
+                            None if include_gone => "Leaderboard (Including Gone)".to_string(),
+                            None => "Leaderboard".to_string(),
                         })
                         .description(p)
                         .build()]),
@@ -1,8 +1,12 @@
 pub mod autostar;
+pub mod blocklist;
+pub mod botinfo;
 pub mod botstats;
+pub mod competition;
 pub mod exclusive_groups;
 pub mod filters;
 pub mod help;
+pub mod language;
 pub mod leaderboard;
 pub mod moststarred;
 pub mod overrides;
@@ -12,7 +16,10 @@ pub mod posroles;
 pub mod premium;
 pub mod premium_locks;
 pub mod random;
+pub mod settings;
 pub mod starboard;
 pub mod stats;
 pub mod utils;
+pub mod vote;
+pub mod xp;
 pub mod xproles;
@@ -18,7 +18,10 @@ use crate::{
     database::{DbMessage, Starboard, StarboardMessage},
     errors::StarboardResult,
     get_guild_id,
-    interactions::context::{CommandCtx, ComponentCtx},
+    interactions::{
+        context::{CommandCtx, ComponentCtx},
+        custom_id::view,
+    },
     utils::{id_as_i64::GetI64, views::wait_for::wait_for_component},
 };
 
@@ -111,7 +114,7 @@ fn components(
 ) -> Vec<Component> {
     let buttons = vec![
         Component::Button(Button {
-            custom_id: Some("moststarred_scroller::back".to_string()),
+            custom_id: Some(view::MOSTSTARRED_BACK.to_string()),
             disabled: done || current_page == 1,
             emoji: None,
             label: Some("Back".to_string()),
@@ -119,7 +122,7 @@ fn components(
             url: None,
         }),
         Component::Button(Button {
-            custom_id: Some("moststarred_scroller::page_number".to_string()),
+            custom_id: Some(view::MOSTSTARRED_PAGE.to_string()),
             disabled: true,
             emoji: None,
             label: Some(current_page.to_string()),
@@ -127,7 +130,7 @@ fn components(
             url: None,
         }),
         Component::Button(Button {
-            custom_id: Some("moststarred_scroller::next".to_string()),
+            custom_id: Some(view::MOSTSTARRED_NEXT.to_string()),
             disabled: done || Some(current_page) == last_page,
             emoji: None,
             label: Some("Next".to_string()),
@@ -221,7 +224,7 @@ async fn scrolling_paginator(
         // wait for component interaction
         btn_ctx = wait_for_component(
             ctx.bot.clone(),
-            &["moststarred_scroller::next", "moststarred_scroller::back"],
+            &[view::MOSTSTARRED_NEXT, view::MOSTSTARRED_BACK],
             message_id.unwrap(),
             user_id,
             60 * 5,
@@ -230,8 +233,8 @@ async fn scrolling_paginator(
 
         if let Some(btn_ctx) = &btn_ctx {
             match &*btn_ctx.data.custom_id {
-                "moststarred_scroller::next" => current_page += 1,
-                "moststarred_scroller::back" => current_page -= 1,
+                view::MOSTSTARRED_NEXT => current_page += 1,
+                view::MOSTSTARRED_BACK => current_page -= 1,
                 _ => unreachable!(),
             }
         } else {
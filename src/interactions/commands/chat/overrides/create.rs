@@ -34,8 +34,10 @@ impl CreateOverride {
         let starboard = Starboard::get_by_name(&ctx.bot.pool, &self.starboard, guild_id).await?;
         let starboard = match starboard {
             None => {
-                ctx.respond_str(&format!("'{}' is not a starboard.", self.starboard), true)
+                let msg = ctx
+                    .t("errors.unknown-starboard", &[("name", &self.starboard)])
                     .await?;
+                ctx.respond_str(&msg, true).await?;
                 return Ok(());
             }
             Some(val) => val,
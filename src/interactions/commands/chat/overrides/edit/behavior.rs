@@ -8,6 +8,8 @@ use crate::{
     utils::id_as_i64::GetI64,
 };
 
+use super::log_changes;
+
 #[derive(CommandModel, CreateCommand)]
 #[command(name = "behavior", desc = "Edit how the starboard should behave.")]
 pub struct EditBehavior {
@@ -26,6 +28,9 @@ pub struct EditBehavior {
     /// Whether to remove reactions that don't meet requirements.
     #[command(rename = "remove-invalid-reactions")]
     remove_invalid_reactions: Option<bool>,
+    /// Whether removing an invalid reaction also stops that user from voting on the message again.
+    #[command(rename = "lock-vote-after-mod-removal")]
+    lock_vote_after_mod_removal: Option<bool>,
     /// If the original message is deleted, whether to also delete the starboard message.
     #[command(rename = "link-deletes")]
     link_deletes: Option<bool>,
@@ -35,6 +40,15 @@ pub struct EditBehavior {
     /// What to do if a moderator removes a post from the starboard manually.
     #[command(rename = "on-delete")]
     on_delete: Option<OnDelete>,
+    /// Whether to send a post again if a moderator deleted it manually.
+    #[command(rename = "recreate-deleted-posts")]
+    recreate_deleted_posts: Option<bool>,
+    /// Whether to create a discussion thread on each starboard message.
+    #[command(rename = "attach-thread")]
+    attach_thread: Option<bool>,
+    /// Whether to reply to the original message with a link to the starboard message.
+    #[command(rename = "reply-with-link")]
+    reply_with_link: Option<bool>,
     /// Whether to enable the per-user vote cooldown.
     #[command(rename = "cooldown-enabled")]
     cooldown_enabled: Option<bool>,
@@ -76,6 +90,9 @@ impl EditBehavior {
         if let Some(val) = self.remove_invalid_reactions {
             settings.remove_invalid_reactions = Some(val);
         }
+        if let Some(val) = self.lock_vote_after_mod_removal {
+            settings.lock_vote_after_mod_removal = Some(val);
+        }
         if let Some(val) = self.link_deletes {
             settings.link_deletes = Some(val);
         }
@@ -85,6 +102,15 @@ impl EditBehavior {
         if let Some(val) = self.on_delete {
             settings.on_delete = Some(val.value() as i16);
         }
+        if let Some(val) = self.recreate_deleted_posts {
+            settings.recreate_deleted_posts = Some(val);
+        }
+        if let Some(val) = self.attach_thread {
+            settings.attach_thread = Some(val);
+        }
+        if let Some(val) = self.reply_with_link {
+            settings.reply_with_link = Some(val);
+        }
         if let Some(val) = self.cooldown_enabled {
             settings.cooldown_enabled = Some(val);
         }
@@ -119,7 +145,11 @@ impl EditBehavior {
             settings.exclusive_group_priority = Some(val as i16);
         }
 
-        StarboardOverride::update_settings(&ctx.bot.pool, ov.id, settings).await?;
+        if let Some(new) =
+            StarboardOverride::update_settings(&ctx.bot.pool, ov.id, settings).await?
+        {
+            log_changes(&ctx, &ov.overrides, &new);
+        }
         ctx.respond_str(
             &format!("Updated settings for override '{}'.", self.name),
             false,
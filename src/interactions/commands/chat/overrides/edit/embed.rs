@@ -4,10 +4,12 @@ use crate::{
     database::{validation::color, StarboardOverride},
     errors::StarboardResult,
     get_guild_id,
-    interactions::context::CommandCtx,
+    interactions::{commands::choices::media_selection::MediaSelection, context::CommandCtx},
     utils::id_as_i64::GetI64,
 };
 
+use super::log_changes;
+
 #[derive(CommandModel, CreateCommand)]
 #[command(
     name = "embed",
@@ -26,6 +28,15 @@ pub struct EditEmbedStyle {
     /// Whether to include the message that was replied to, if any.
     #[command(rename = "replied-to")]
     replied_to: Option<bool>,
+    /// Which image to show when a message has several.
+    #[command(rename = "media-selection")]
+    media_selection: Option<MediaSelection>,
+    /// Whether to show GIF and image links in the content as the embed's image.
+    #[command(rename = "extract-media-links")]
+    extract_media_links: Option<bool>,
+    /// Whether to re-upload small images and videos instead of linking to them. Premium only.
+    #[command(rename = "clone-attachments")]
+    clone_attachments: Option<bool>,
 }
 
 impl EditEmbedStyle {
@@ -62,8 +73,21 @@ impl EditEmbedStyle {
         if let Some(val) = self.replied_to {
             settings.replied_to = Some(val);
         }
+        if let Some(val) = self.media_selection {
+            settings.media_selection = Some(val.value() as i16);
+        }
+        if let Some(val) = self.extract_media_links {
+            settings.extract_media_links = Some(val);
+        }
+        if let Some(val) = self.clone_attachments {
+            settings.clone_attachments = Some(val);
+        }
 
-        StarboardOverride::update_settings(&ctx.bot.pool, ov.id, settings).await?;
+        if let Some(new) =
+            StarboardOverride::update_settings(&ctx.bot.pool, ov.id, settings).await?
+        {
+            log_changes(&ctx, &ov.overrides, &new);
+        }
         ctx.respond_str(
             &format!("Updated settings for override '{}'.", self.name),
             false,
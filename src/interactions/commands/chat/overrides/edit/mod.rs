@@ -6,8 +6,12 @@ pub mod style;
 
 use twilight_interactions::command::{CommandModel, CreateCommand};
 
-use crate::{errors::StarboardResult, interactions::context::CommandCtx};
+use crate::{
+    database::StarboardOverride, errors::StarboardResult, interactions::context::CommandCtx,
+    utils::into_id::IntoId,
+};
 
+#[allow(clippy::large_enum_variant)] // Requirements(requirements::EditRequirements) being the culprit
 #[derive(CommandModel, CreateCommand)]
 #[command(name = "edit", desc = "Edit an override.")]
 pub enum EditOverride {
@@ -34,3 +38,15 @@ impl EditOverride {
         }
     }
 }
+
+/// Logs the settings an edit changed to the guild's log channel.
+fn log_changes(ctx: &CommandCtx, old: &serde_json::Value, new: &StarboardOverride) {
+    ctx.bot.guild_log.log_settings(
+        &ctx.bot,
+        new.guild_id.into_id(),
+        ctx.interaction.author_id(),
+        format!("override '{}'", new.name),
+        old,
+        &new.overrides,
+    );
+}
@@ -3,13 +3,19 @@ use twilight_interactions::command::{CommandModel, CreateCommand};
 use crate::{
     core::{
         emoji::{EmojiCommon, SimpleEmoji},
+        emoji_migration::refresh_needs_attention,
         premium::is_premium::is_guild_premium,
         starboard::config::StarboardConfig,
+        vote_weights::weights_from_user_input,
     },
     database::{
         validation::{
             self,
-            starboard_settings::{validate_required, validate_required_remove},
+            starboard_settings::{
+                validate_char_limits, validate_emoji_weights, validate_max_chars,
+                validate_max_posts_per_author, validate_min_chars, validate_min_unique_voters,
+                validate_min_vote_spread, validate_required, validate_required_remove,
+            },
             time_delta::{parse_time_delta, validate_relative_duration},
         },
         Starboard, StarboardOverride,
@@ -20,6 +26,8 @@ use crate::{
     utils::id_as_i64::GetI64,
 };
 
+use super::log_changes;
+
 #[derive(CommandModel, CreateCommand)]
 #[command(
     name = "requirements",
@@ -35,27 +43,54 @@ pub struct EditRequirements {
     /// How few points the message can have before a starboarded post is removed. Use "none" to unset.
     #[command(rename = "required-remove")]
     required_remove: Option<String>,
+    /// How many different users must upvote a message, regardless of points. Use "none" to unset.
+    #[command(rename = "min-unique-voters")]
+    min_unique_voters: Option<String>,
     /// The emojis that can be used to upvote a post. Use 'none' to remove all.
     #[command(rename = "upvote-emojis")]
     upvote_emojis: Option<String>,
     /// The emojis that can be used to downvote a post. Use 'none' to remove all.
     #[command(rename = "downvote-emojis")]
     downvote_emojis: Option<String>,
+    /// Points per vote emoji, like "⭐=1, 💎=3". Others are worth 1. Use 'none' to remove all.
+    #[command(rename = "vote-emoji-weights")]
+    vote_emoji_weights: Option<String>,
     /// Whether to allow users to vote on their own posts.
     #[command(rename = "self-vote")]
     self_vote: Option<bool>,
+    /// Whether reactions on the starboard post count as votes.
+    #[command(rename = "votes-on-post")]
+    votes_on_post: Option<bool>,
     /// Whether to allow bot messages to be on the starboard.
     #[command(rename = "allow-bots")]
     allow_bots: Option<bool>,
     /// Whether to require posts to have an image to appear on the starboard.
     #[command(rename = "require-image")]
     require_image: Option<bool>,
+    /// How many characters of text a message needs. Images and stickers don't count.
+    #[command(rename = "min-chars", min_value = 0, max_value = 5_000)]
+    min_chars: Option<i64>,
+    /// How many characters of text a message can have. Use "none" to unset.
+    #[command(rename = "max-chars")]
+    max_chars: Option<String>,
+    /// Whether to require posts to include a link to appear on the starboard.
+    #[command(rename = "require-link")]
+    require_link: Option<bool>,
+    /// Whether to allow system messages (e.g. joins, boosts and pins) to be on the starboard.
+    #[command(rename = "allow-system-messages")]
+    allow_system_messages: Option<bool>,
     /// How old a post must be in order for it to be voted on (e.g. "1 hour"). Use 0 to disable.
     #[command(rename = "older-than")]
     older_than: Option<String>,
     /// How new a post must be in order for it to be voted on (e.g. "1 hour"). Use 0 to disable.
     #[command(rename = "newer-than")]
     newer_than: Option<String>,
+    /// Minimum time between the first and latest upvote (e.g. "5 minutes"). Use 0 to disable.
+    #[command(rename = "min-vote-spread")]
+    min_vote_spread: Option<String>,
+    /// How many posts one author can get per 24 hours. Later messages wait. Use "none" to unset.
+    #[command(rename = "max-posts-per-author-per-day")]
+    max_posts_per_author_per_day: Option<String>,
     /// (Premium) Content that messages must match to be starred (supports regex). Use ".*" to disable.
     matches: Option<String>,
     #[command(rename = "not-matches")]
@@ -75,13 +110,14 @@ impl EditRequirements {
             }
             Some(starboard) => starboard,
         };
-        let (ov, resolved) = {
+        let (ov, resolved, aggregate) = {
             let starboard = Starboard::get(&ctx.bot.pool, ov.starboard_id)
                 .await?
                 .unwrap();
+            let aggregate = starboard.aggregate;
             let mut resolved = StarboardConfig::new(starboard, &[], vec![ov])?;
 
-            (resolved.overrides.remove(0), resolved.resolved)
+            (resolved.overrides.remove(0), resolved.resolved, aggregate)
         };
         let mut settings = ov.get_overrides()?;
 
@@ -107,6 +143,16 @@ impl EditRequirements {
             };
             settings.required_remove = Some(val);
         }
+        if let Some(val) = self.min_unique_voters {
+            let val = match validate_min_unique_voters(val) {
+                Ok(val) => val,
+                Err(why) => {
+                    ctx.respond_str(&why, true).await?;
+                    return Ok(());
+                }
+            };
+            settings.min_unique_voters = Some(val);
+        }
 
         if let Some(val) = self.upvote_emojis {
             let emojis = SimpleEmoji::from_user_input(&val, &ctx.bot, guild_id).into_stored();
@@ -136,16 +182,78 @@ impl EditRequirements {
             ctx.respond_str(&why, true).await?;
             return Ok(());
         }
+        if aggregate {
+            if let Err(why) = validation::starboard_settings::validate_aggregate_emojis(
+                settings.upvote_emojis.as_deref().unwrap_or_default(),
+                settings.downvote_emojis.as_deref().unwrap_or_default(),
+            ) {
+                ctx.respond_str(&why, true).await?;
+                return Ok(());
+            }
+        }
+
+        if let Some(val) = self.vote_emoji_weights {
+            let weights = if val == "none" {
+                Vec::new()
+            } else {
+                match weights_from_user_input(&val, &ctx.bot, guild_id) {
+                    Ok(weights) => weights,
+                    Err(why) => {
+                        ctx.respond_str(&why, true).await?;
+                        return Ok(());
+                    }
+                }
+            };
+            if let Err(why) = validate_emoji_weights(&weights) {
+                ctx.respond_str(&why, true).await?;
+                return Ok(());
+            }
+            settings.vote_emoji_weights = Some(serde_json::to_value(weights)?);
+        }
 
         if let Some(val) = self.self_vote {
             settings.self_vote = Some(val);
         }
+        if let Some(val) = self.votes_on_post {
+            settings.votes_on_post = Some(val);
+        }
         if let Some(val) = self.allow_bots {
             settings.allow_bots = Some(val);
         }
         if let Some(val) = self.require_image {
             settings.require_image = Some(val);
         }
+        if let Some(val) = self.min_chars {
+            match validate_min_chars(val) {
+                Err(why) => {
+                    ctx.respond_str(&why, true).await?;
+                    return Ok(());
+                }
+                Ok(val) => settings.min_chars = Some(val),
+            }
+        }
+        if let Some(val) = self.max_chars {
+            match validate_max_chars(val) {
+                Err(why) => {
+                    ctx.respond_str(&why, true).await?;
+                    return Ok(());
+                }
+                Ok(val) => settings.max_chars = Some(val),
+            }
+        }
+        if let Err(why) = validate_char_limits(
+            settings.min_chars.unwrap_or(resolved.min_chars),
+            settings.max_chars.unwrap_or(resolved.max_chars),
+        ) {
+            ctx.respond_str(&why, true).await?;
+            return Ok(());
+        }
+        if let Some(val) = self.require_link {
+            settings.require_link = Some(val);
+        }
+        if let Some(val) = self.allow_system_messages {
+            settings.allow_system_messages = Some(val);
+        }
         if let Some(val) = self.older_than {
             let delta = match parse_time_delta(&val) {
                 Err(why) => {
@@ -167,6 +275,26 @@ impl EditRequirements {
             settings.newer_than = Some(delta);
         }
 
+        if let Some(val) = self.max_posts_per_author_per_day {
+            match validate_max_posts_per_author(val) {
+                Err(why) => {
+                    ctx.respond_str(&why, true).await?;
+                    return Ok(());
+                }
+                Ok(val) => settings.max_posts_per_author_per_day = Some(val),
+            }
+        }
+        if let Some(val) = self.min_vote_spread {
+            let val = match validate_min_vote_spread(&val) {
+                Err(why) => {
+                    ctx.respond_str(&why, true).await?;
+                    return Ok(());
+                }
+                Ok(val) => val,
+            };
+            settings.min_vote_spread_seconds = Some(val);
+        }
+
         if let Err(why) = validate_relative_duration(
             Some(settings.newer_than.unwrap_or(resolved.newer_than)),
             Some(settings.older_than.unwrap_or(resolved.older_than)),
@@ -194,7 +322,12 @@ impl EditRequirements {
             }
         }
 
-        StarboardOverride::update_settings(&ctx.bot.pool, ov.id, settings).await?;
+        if let Some(new) =
+            StarboardOverride::update_settings(&ctx.bot.pool, ov.id, settings).await?
+        {
+            log_changes(&ctx, &ov.overrides, &new);
+        }
+        refresh_needs_attention(&ctx.bot, ov.starboard_id).await?;
         ctx.respond_str(
             &format!("Updated settings for override '{}'.", self.name),
             false,
@@ -10,6 +10,8 @@ use crate::{
     utils::id_as_i64::GetI64,
 };
 
+use super::log_changes;
+
 macro_rules! reset_settings {
     ($overrides: expr, $reset: expr, $($setting: ident),*) => {{
         $(
@@ -64,7 +66,11 @@ impl ResetOverrideSettings {
 
         call_with_override_settings!(reset_settings, settings, reset);
 
-        StarboardOverride::update_settings(&ctx.bot.pool, ov.id, settings).await?;
+        if let Some(new) =
+            StarboardOverride::update_settings(&ctx.bot.pool, ov.id, settings).await?
+        {
+            log_changes(&ctx, &ov.overrides, &new);
+        }
         ctx.respond_str(
             &format!(
                 "Reset {} setting(s) for override '{}'.",
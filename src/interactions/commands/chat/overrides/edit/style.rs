@@ -3,15 +3,21 @@ use twilight_interactions::command::{CommandModel, CreateCommand};
 use crate::{
     core::{
         emoji::{EmojiCommon, SimpleEmoji},
+        emoji_migration::refresh_needs_attention,
+        emoji_tiers::tiers_from_user_input,
         starboard::webhooks::create_webhook,
     },
-    database::{Starboard, StarboardOverride},
+    database::{
+        validation::starboard_settings::validate_emoji_tiers, Starboard, StarboardOverride,
+    },
     errors::StarboardResult,
     get_guild_id,
     interactions::{commands::choices::go_to_message::GoToMessage, context::CommandCtx},
     utils::id_as_i64::GetI64,
 };
 
+use super::log_changes;
+
 #[derive(CommandModel, CreateCommand)]
 #[command(name = "style", desc = "Edit the general style of your starboard.")]
 pub struct EditGeneralStyle {
@@ -22,6 +28,9 @@ pub struct EditGeneralStyle {
     /// The emoji to show next to the point count. Use 'none' for nothing.
     #[command(rename = "display-emoji")]
     display_emoji: Option<String>,
+    /// Emojis that replace the display emoji at certain points, e.g. "10 🌟, 25 ✨". Use 'none' to remove.
+    #[command(rename = "emoji-tiers")]
+    emoji_tiers: Option<String>,
     /// Whether to mention the author on starboard posts.
     #[command(rename = "ping-author")]
     ping_author: Option<bool>,
@@ -37,6 +46,15 @@ pub struct EditGeneralStyle {
     /// Whether to use a webhook for starboard messages.
     #[command(rename = "use-webhook")]
     use_webhook: Option<bool>,
+    /// Whether to add a "Translate" button to starboard messages.
+    #[command(rename = "translate-button")]
+    translate_button: Option<bool>,
+    /// Whether to add buttons for voting to starboard messages.
+    #[command(rename = "vote-buttons")]
+    vote_buttons: Option<bool>,
+    /// Whether to add a button that lists who voted to starboard messages.
+    #[command(rename = "show-votes-button")]
+    show_votes_button: Option<bool>,
 }
 
 impl EditGeneralStyle {
@@ -75,6 +93,25 @@ impl EditGeneralStyle {
             };
             settings.display_emoji = Some(emoji.map(|emoji| emoji.into_stored()));
         }
+        if let Some(val) = self.emoji_tiers {
+            let tiers = if val == "none" {
+                Vec::new()
+            } else {
+                match tiers_from_user_input(&val, &ctx.bot, guild_id) {
+                    Ok(tiers) => tiers,
+                    Err(why) => {
+                        ctx.respond_str(&why, true).await?;
+                        return Ok(());
+                    }
+                }
+            };
+            if let Err(why) = validate_emoji_tiers(&tiers) {
+                ctx.respond_str(&why, true).await?;
+                return Ok(());
+            }
+            let tiers = serde_json::to_value(tiers)?;
+            settings.display_emoji_tiers = Some(tiers);
+        }
         if let Some(val) = self.ping_author {
             settings.ping_author = Some(val);
         }
@@ -98,8 +135,22 @@ impl EditGeneralStyle {
         } else {
             message = None;
         }
+        if let Some(val) = self.translate_button {
+            settings.translate_button = Some(val);
+        }
+        if let Some(val) = self.vote_buttons {
+            settings.vote_buttons = Some(val);
+        }
+        if let Some(val) = self.show_votes_button {
+            settings.show_votes_button = Some(val);
+        }
 
-        StarboardOverride::update_settings(&ctx.bot.pool, ov.id, settings).await?;
+        if let Some(new) =
+            StarboardOverride::update_settings(&ctx.bot.pool, ov.id, settings).await?
+        {
+            log_changes(&ctx, &ov.overrides, &new);
+        }
+        refresh_needs_attention(&ctx.bot, ov.starboard_id).await?;
 
         let mut response = format!("Updated settings for override '{}'.", self.name);
         if let Some(message) = message {
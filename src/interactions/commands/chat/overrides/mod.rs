@@ -12,6 +12,7 @@ use crate::{
     interactions::{commands::permissions::manage_channels, context::CommandCtx},
 };
 
+#[allow(clippy::large_enum_variant)] // Edit(edit::EditOverride) being the culprit
 #[derive(CommandModel, CreateCommand)]
 #[command(
     name = "overrides",
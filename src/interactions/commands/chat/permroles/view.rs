@@ -90,7 +90,7 @@ async fn permrole_embed(bot: &StarboardBot, pr: PermRole) -> StarboardResult<Emb
         let sb = Starboard::get(&bot.pool, pr_sb.starboard_id).await?;
         let sb = match sb {
             None => {
-                eprintln!("Starboard for PermRole didn't exist. This shouldn't happen.");
+                tracing::error!("Starboard for PermRole didn't exist. This shouldn't happen.");
                 continue;
             }
             Some(sb) => sb,
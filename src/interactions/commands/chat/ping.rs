@@ -8,7 +8,8 @@ pub struct Ping;
 
 impl Ping {
     pub async fn callback(self, mut ctx: CommandCtx) -> StarboardResult<()> {
-        ctx.respond_str("Pong! I'm here.", false).await?;
+        let msg = ctx.t("ping.pong", &[]).await?;
+        ctx.respond_str(&msg, false).await?;
 
         Ok(())
     }
@@ -1,7 +1,7 @@
-mod delete;
-mod refresh;
-mod set_max_members;
-mod view;
+pub mod delete;
+pub mod refresh;
+pub mod set_max_members;
+pub mod view;
 
 use twilight_interactions::command::{CommandModel, CreateCommand};
 
@@ -5,7 +5,10 @@ use crate::{
     core::{posroles::update_posroles_for_guild, premium::is_premium::is_guild_premium},
     errors::StarboardResult,
     get_guild_id,
-    interactions::context::CommandCtx,
+    interactions::{
+        commands::visibility::{CommandVisibility, VisibilityPolicy},
+        context::CommandCtx,
+    },
     utils::id_as_i64::GetI64,
 };
 
@@ -13,6 +16,10 @@ use crate::{
 #[command(name = "refresh", desc = "Refresh the PosRoles for the server.")]
 pub struct Refresh;
 
+impl CommandVisibility for Refresh {
+    const VISIBILITY: VisibilityPolicy = VisibilityPolicy::EPHEMERAL;
+}
+
 impl Refresh {
     pub async fn callback(self, mut ctx: CommandCtx) -> StarboardResult<()> {
         let guild_id = get_guild_id!(ctx);
@@ -23,7 +30,7 @@ impl Refresh {
             return Ok(());
         }
 
-        ctx.defer(true).await?;
+        ctx.defer(false).await?;
 
         let ret = update_posroles_for_guild(ctx.bot.clone(), guild_id).await?;
 
@@ -34,7 +41,7 @@ impl Refresh {
                     "Added {} roles, {} failed.\n" <- ret.added_roles, ret.failed_adds;
                     "Removed {} roles, {} failed." <- ret.removed_roles, ret.failed_removals;
                 ),
-                true,
+                false,
             )
             .await?;
         } else {
@@ -43,7 +43,7 @@ impl Disable {
 
         DbMember::set_autoredeem_enabled(&ctx.bot.pool, user_id, guild_id, false).await?;
 
-        ctx.respond_str("Autoredeem disabled.", true).await?;
+        ctx.respond_str("Autoredeem disabled.", false).await?;
 
         Ok(())
     }
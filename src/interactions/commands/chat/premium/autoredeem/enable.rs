@@ -24,7 +24,7 @@ impl Enable {
         DbMember::create(&ctx.bot.pool, user_id, guild_id).await?;
         DbMember::set_autoredeem_enabled(&ctx.bot.pool, user_id, guild_id, true).await?;
 
-        ctx.respond_str("Autoredeem enabled.", true).await?;
+        ctx.respond_str("Autoredeem enabled.", false).await?;
 
         Ok(())
     }
@@ -3,7 +3,13 @@ mod enable;
 
 use twilight_interactions::command::{CommandModel, CreateCommand};
 
-use crate::{errors::StarboardResult, interactions::context::CommandCtx};
+use crate::{
+    errors::StarboardResult,
+    interactions::{
+        commands::visibility::{CommandVisibility, VisibilityPolicy},
+        context::CommandCtx,
+    },
+};
 
 #[derive(CommandModel, CreateCommand)]
 #[command(name = "autoredeem", desc = "Manage autoredeem.")]
@@ -14,6 +20,10 @@ pub enum Autoredeem {
     Enable(enable::Enable),
 }
 
+impl CommandVisibility for Autoredeem {
+    const VISIBILITY: VisibilityPolicy = VisibilityPolicy::EPHEMERAL;
+}
+
 impl Autoredeem {
     pub async fn callback(self, ctx: CommandCtx) -> StarboardResult<()> {
         match self {
@@ -1,14 +1,16 @@
 use std::{borrow::Cow, fmt::Write};
 
 use twilight_interactions::command::{CommandModel, CreateCommand};
-use twilight_model::channel::message::MessageFlags;
 use twilight_util::builder::embed::EmbedFieldBuilder;
 
 use crate::{
     concat_format, constants,
     database::{DbGuild, DbMember, DbUser},
     errors::StarboardResult,
-    interactions::context::CommandCtx,
+    interactions::{
+        commands::visibility::{CommandVisibility, VisibilityPolicy},
+        context::CommandCtx,
+    },
     utils::{embed, id_as_i64::GetI64, into_id::IntoId},
 };
 
@@ -16,6 +18,10 @@ use crate::{
 #[command(name = "info", desc = "Get premium info.")]
 pub struct Info;
 
+impl CommandVisibility for Info {
+    const VISIBILITY: VisibilityPolicy = VisibilityPolicy::PRIVATE;
+}
+
 impl Info {
     pub async fn callback(self, mut ctx: CommandCtx) -> StarboardResult<()> {
         let user_id = ctx.interaction.author_id().unwrap().get_i64();
@@ -85,13 +91,8 @@ impl Info {
             emb = emb.field(EmbedFieldBuilder::new("Server Premium", value));
         };
 
-        ctx.respond(
-            ctx.build_resp()
-                .embeds([emb.build()])
-                .flags(MessageFlags::EPHEMERAL)
-                .build(),
-        )
-        .await?;
+        ctx.respond(ctx.build_resp().embeds([emb.build()]).build())
+            .await?;
 
         Ok(())
     }
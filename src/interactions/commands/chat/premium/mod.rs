@@ -1,6 +1,6 @@
-mod autoredeem;
-mod info;
-mod redeem;
+pub mod autoredeem;
+pub mod info;
+pub mod redeem;
 
 use twilight_interactions::command::{CommandModel, CreateCommand};
 
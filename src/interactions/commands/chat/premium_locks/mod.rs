@@ -1,6 +1,6 @@
-mod move_autostar;
-mod move_starboard;
-mod refresh;
+pub mod move_autostar;
+pub mod move_starboard;
+pub mod refresh;
 
 use twilight_interactions::command::{CommandModel, CreateCommand};
 
@@ -1,7 +1,12 @@
 use twilight_interactions::command::{CommandModel, CreateCommand};
 
 use crate::{
-    database::AutoStarChannel, errors::StarboardResult, interactions::context::CommandCtx,
+    database::AutoStarChannel,
+    errors::StarboardResult,
+    interactions::{
+        commands::visibility::{CommandVisibility, VisibilityPolicy},
+        context::CommandCtx,
+    },
     utils::id_as_i64::GetI64,
 };
 
@@ -19,6 +24,10 @@ pub struct MoveAutostar {
     autostar_to: String,
 }
 
+impl CommandVisibility for MoveAutostar {
+    const VISIBILITY: VisibilityPolicy = VisibilityPolicy::EPHEMERAL;
+}
+
 impl MoveAutostar {
     pub async fn callback(self, mut ctx: CommandCtx) -> StarboardResult<()> {
         let Some(guild_id) = ctx.interaction.guild_id else {
@@ -68,7 +77,7 @@ impl MoveAutostar {
 
         tx.commit().await?;
 
-        ctx.respond_str("Done.", true).await?;
+        ctx.respond_str("Done.", false).await?;
 
         Ok(())
     }
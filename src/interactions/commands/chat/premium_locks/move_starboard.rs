@@ -3,7 +3,10 @@ use twilight_interactions::command::{CommandModel, CreateCommand};
 use crate::{
     database::{models::starboard::starboard_from_record, Starboard},
     errors::StarboardResult,
-    interactions::context::CommandCtx,
+    interactions::{
+        commands::visibility::{CommandVisibility, VisibilityPolicy},
+        context::CommandCtx,
+    },
     utils::id_as_i64::GetI64,
 };
 
@@ -21,6 +24,10 @@ pub struct MoveStarboard {
     starboard_to: String,
 }
 
+impl CommandVisibility for MoveStarboard {
+    const VISIBILITY: VisibilityPolicy = VisibilityPolicy::EPHEMERAL;
+}
+
 impl MoveStarboard {
     pub async fn callback(self, mut ctx: CommandCtx) -> StarboardResult<()> {
         let Some(guild_id) = ctx.interaction.guild_id else {
@@ -70,7 +77,7 @@ impl MoveStarboard {
 
         tx.commit().await?;
 
-        ctx.respond_str("Done.", true).await?;
+        ctx.respond_str("Done.", false).await?;
 
         Ok(())
     }
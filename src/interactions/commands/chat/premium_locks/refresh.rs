@@ -3,7 +3,10 @@ use twilight_interactions::command::{CommandModel, CreateCommand};
 use crate::{
     core::premium::{is_premium::is_guild_premium, locks::refresh_premium_locks},
     errors::StarboardResult,
-    interactions::context::CommandCtx,
+    interactions::{
+        commands::visibility::{CommandVisibility, VisibilityPolicy},
+        context::CommandCtx,
+    },
     utils::id_as_i64::GetI64,
 };
 
@@ -11,6 +14,10 @@ use crate::{
 #[command(name = "refresh", desc = "Refreshes premium locks.")]
 pub struct Refresh;
 
+impl CommandVisibility for Refresh {
+    const VISIBILITY: VisibilityPolicy = VisibilityPolicy::EPHEMERAL;
+}
+
 impl Refresh {
     pub async fn callback(self, mut ctx: CommandCtx) -> StarboardResult<()> {
         let Some(guild_id) = ctx.interaction.guild_id else {
@@ -26,7 +33,7 @@ impl Refresh {
         )
         .await?;
 
-        ctx.respond_str("Refreshed locks.", true).await?;
+        ctx.respond_str("Refreshed locks.", false).await?;
 
         Ok(())
     }
@@ -106,6 +106,8 @@ pub async fn get_embedder(
     let embedder = Embedder {
         bot,
         points: msg.last_known_point_count as i32,
+        vote_counts: None,
+        emoji_points: Vec::new(),
         config,
         orig_message: Some(orig_msg).into(),
         referenced_message: ref_msg,
@@ -0,0 +1,72 @@
+use twilight_interactions::command::{CommandModel, CreateCommand};
+use twilight_model::application::interaction::application_command::InteractionChannel;
+
+use crate::{
+    core::guild_log::{LogEntry, SettingChange},
+    errors::StarboardResult,
+    get_guild_id,
+    interactions::context::CommandCtx,
+};
+
+#[derive(CommandModel, CreateCommand)]
+#[command(
+    name = "log-channel",
+    desc = "View or change where notable events in this server are logged."
+)]
+pub struct LogChannel {
+    /// The channel to log to.
+    #[command(channel_types = r#"
+            guild_text
+            guild_announcement
+            announcement_thread
+            public_thread
+            private_thread
+        "#)]
+    channel: Option<InteractionChannel>,
+    /// Stop logging.
+    remove: Option<bool>,
+}
+
+impl LogChannel {
+    pub async fn callback(self, mut ctx: CommandCtx) -> StarboardResult<()> {
+        let guild_id = get_guild_id!(ctx);
+        let old = ctx.bot.guild_log.channel(&ctx.bot, guild_id).await?;
+
+        let new = if self.remove == Some(true) {
+            None
+        } else if let Some(channel) = &self.channel {
+            Some(channel.id)
+        } else {
+            old
+        };
+        let fmt = |channel| match channel {
+            Some(channel_id) => format!("<#{channel_id}>"),
+            None => "none".to_string(),
+        };
+        if new != old {
+            ctx.bot
+                .guild_log
+                .set_channel(&ctx.bot, guild_id, new)
+                .await?;
+
+            // logged to the new channel, so there's nowhere to log removing it
+            if let (Some(_), Some(moderator)) = (new, ctx.interaction.author_id()) {
+                let entry = LogEntry::SettingsChanged {
+                    moderator,
+                    target: "this server".to_string(),
+                    changes: vec![SettingChange {
+                        name: "log-channel".to_string(),
+                        old: fmt(old),
+                        new: fmt(new),
+                    }],
+                };
+                ctx.bot.guild_log.log(&ctx.bot, guild_id, entry);
+            }
+        }
+
+        ctx.respond_str(&format!("Log channel: {}", fmt(new)), true)
+            .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,28 @@
+mod log_channel;
+
+use twilight_interactions::command::{CommandModel, CreateCommand};
+
+use crate::{
+    errors::StarboardResult,
+    interactions::{commands::permissions::manage_guild, context::CommandCtx},
+};
+
+#[derive(CommandModel, CreateCommand)]
+#[command(
+    name = "settings",
+    desc = "Manage server-wide settings.",
+    default_permissions = "manage_guild",
+    dm_permission = false
+)]
+pub enum Settings {
+    #[command(name = "log-channel")]
+    LogChannel(log_channel::LogChannel),
+}
+
+impl Settings {
+    pub async fn callback(self, ctx: CommandCtx) -> StarboardResult<()> {
+        match self {
+            Self::LogChannel(cmd) => cmd.callback(ctx).await,
+        }
+    }
+}
@@ -1,7 +1,10 @@
 use twilight_interactions::command::{CommandModel, CreateCommand};
 
 use crate::{
-    core::premium::{is_premium::is_guild_premium, locks::refresh_premium_locks},
+    core::{
+        premium::{is_premium::is_guild_premium, locks::refresh_premium_locks},
+        starboard::cleanup::{queue_cleanup, vote_emojis},
+    },
     database::Starboard,
     errors::StarboardResult,
     get_guild_id,
@@ -15,6 +18,9 @@ pub struct DeleteStarboard {
     /// The name of the starboard to delete.
     #[command(autocomplete = true)]
     name: String,
+    /// Also remove my reactions from this starboard's posts.
+    #[command(rename = "cleanup-reactions")]
+    cleanup_reactions: Option<bool>,
 }
 
 impl DeleteStarboard {
@@ -22,20 +28,37 @@ impl DeleteStarboard {
         let guild_id = get_guild_id!(ctx);
         let guild_id_i64 = guild_id.get_i64();
 
-        let mut btn_ctx = match confirm::simple(
-            &mut ctx,
-            &format!(
+        let cleanup = self.cleanup_reactions.unwrap_or(false);
+        let prompt = if cleanup {
+            format!(
+                concat!(
+                    "Are you sure you want to delete the starboard '{}'? I will also remove my ",
+                    "reactions from its posts."
+                ),
+                self.name
+            )
+        } else {
+            format!(
                 "Are you sure you want to delete the starboard '{}'?",
                 self.name
-            ),
-            true,
-        )
-        .await?
-        {
+            )
+        };
+
+        let mut btn_ctx = match confirm::simple(&mut ctx, &prompt, true).await? {
             None => return Ok(()),
             Some(btn_ctx) => btn_ctx,
         };
 
+        // the posts are queued first, since they're deleted with the starboard
+        let mut queued = 0;
+        if cleanup {
+            if let Some(sb) =
+                Starboard::get_by_name(&ctx.bot.pool, &self.name, guild_id_i64).await?
+            {
+                queued = queue_cleanup(&ctx.bot, &sb, &vote_emojis(&sb.settings)).await?;
+            }
+        }
+
         let ret = Starboard::delete(&ctx.bot.pool, &self.name, guild_id_i64).await?;
         refresh_premium_locks(
             &ctx.bot,
@@ -43,14 +66,29 @@ impl DeleteStarboard {
             is_guild_premium(&ctx.bot, guild_id.get_i64(), true).await?,
         )
         .await?;
-        if ret.is_none() {
-            btn_ctx
-                .edit_str("No starboard with that name was found.", true)
-                .await?;
-        } else {
+        if let Some(starboard) = ret {
             ctx.bot.cache.guild_vote_emojis.remove(&guild_id_i64);
+
+            let aggregates =
+                Starboard::remove_aggregate_source(&ctx.bot.pool, guild_id_i64, starboard.id)
+                    .await?;
+            let mut msg = format!("Deleted starboard '{}'.", self.name);
+            if !aggregates.is_empty() {
+                msg.push_str(&format!(
+                    " It was removed as a source from these aggregate starboards: {}.",
+                    aggregates.join(", ")
+                ));
+            }
+
+            if queued != 0 {
+                msg.push_str(&format!(
+                    " I'll remove my reactions from {queued} of its posts in the background."
+                ));
+            }
+            btn_ctx.edit_str(&msg, true).await?;
+        } else {
             btn_ctx
-                .edit_str(&format!("Deleted starboard '{}'.", self.name), true)
+                .edit_str("No starboard with that name was found.", true)
                 .await?;
         }
         Ok(())
@@ -0,0 +1,58 @@
+use std::fmt::Write;
+
+use twilight_interactions::command::{CommandModel, CreateCommand};
+
+use crate::{
+    core::removed_emojis::find_missing, errors::StarboardResult, get_guild_id,
+    interactions::context::CommandCtx, utils::embed,
+};
+
+#[derive(CreateCommand, CommandModel)]
+#[command(
+    name = "doctor",
+    desc = "Find vote emojis that were deleted from this server."
+)]
+pub struct Doctor;
+
+impl Doctor {
+    pub async fn callback(self, mut ctx: CommandCtx) -> StarboardResult<()> {
+        let guild_id = get_guild_id!(ctx);
+
+        // every emoji would look deleted if the server isn't cached
+        if !ctx.bot.cache.guilds.contains_key(&guild_id) {
+            ctx.respond_str(
+                "This server isn't loaded yet. Please try again in a minute.",
+                true,
+            )
+            .await?;
+            return Ok(());
+        }
+
+        let bot = ctx.bot.clone();
+        let missing = find_missing(&bot, guild_id, |id| {
+            bot.cache.guild_emoji_exists(guild_id, id)
+        })
+        .await?;
+        if missing.is_empty() {
+            ctx.respond_str("All vote emojis exist.", true).await?;
+            return Ok(());
+        }
+
+        let mut description = String::new();
+        for m in &missing {
+            writeln!(description, "- {}: emoji `{}`", m.location(), m.emoji_id).unwrap();
+        }
+        description.push_str(
+            "\nVotes with these emojis can't be cast. Remove them with \
+            `/starboards edit requirements` or `/overrides edit requirements`.",
+        );
+
+        let emb = embed::build()
+            .title("Missing Vote Emojis")
+            .description(description)
+            .build();
+        ctx.respond(ctx.build_resp().embeds([emb]).build()).await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,107 @@
+use twilight_interactions::command::{CommandModel, CreateCommand};
+
+use crate::{
+    core::starboard::cleanup::queue_removed_emojis, database::Starboard, errors::StarboardResult,
+    get_guild_id, interactions::context::CommandCtx, utils::id_as_i64::GetI64,
+};
+
+#[derive(CommandModel, CreateCommand)]
+#[command(
+    name = "aggregate",
+    desc = "Make a starboard that shows messages with enough points on other starboards."
+)]
+pub struct EditAggregate {
+    /// The starboard to edit.
+    #[command(autocomplete = true)]
+    name: String,
+
+    /// Whether this is an aggregate starboard. Enabling this removes its emojis.
+    aggregate: Option<bool>,
+    /// A starboard to use points from.
+    #[command(rename = "add-source", autocomplete = true)]
+    add_source: Option<String>,
+    /// A starboard to stop using points from.
+    #[command(rename = "remove-source", autocomplete = true)]
+    remove_source: Option<String>,
+}
+
+impl EditAggregate {
+    pub async fn callback(self, mut ctx: CommandCtx) -> StarboardResult<()> {
+        let guild_id = get_guild_id!(ctx).get_i64();
+        let mut starboard =
+            match Starboard::get_by_name(&ctx.bot.pool, &self.name, guild_id).await? {
+                None => {
+                    ctx.respond_str("No starboard with that name was found.", true)
+                        .await?;
+                    return Ok(());
+                }
+                Some(starboard) => starboard,
+            };
+
+        let mut queued = 0;
+        if let Some(val) = self.aggregate {
+            if val && !starboard.aggregate {
+                // aggregate starboards don't have emojis of their own
+                let old_settings = starboard.settings.clone();
+                starboard.settings.upvote_emojis.clear();
+                starboard.settings.downvote_emojis.clear();
+                starboard = match starboard.update_settings(&ctx.bot.pool).await? {
+                    Some(starboard) => starboard,
+                    None => return Ok(()),
+                };
+                ctx.bot.cache.guild_vote_emojis.remove(&guild_id);
+                queued = queue_removed_emojis(&ctx.bot, &old_settings, &starboard).await?;
+            }
+            starboard.aggregate = val;
+        }
+        if let Some(name) = self.add_source {
+            let Some(source) = Starboard::get_by_name(&ctx.bot.pool, &name, guild_id).await? else {
+                ctx.respond_str(&format!("Starboard '{name}' does not exist."), true)
+                    .await?;
+                return Ok(());
+            };
+            if source.id == starboard.id {
+                ctx.respond_str("A starboard can't be its own source.", true)
+                    .await?;
+                return Ok(());
+            }
+            if source.aggregate {
+                ctx.respond_str(
+                    "Aggregate starboards can't be used as a source for other aggregates.",
+                    true,
+                )
+                .await?;
+                return Ok(());
+            }
+            if !starboard.source_starboards.contains(&source.id) {
+                starboard.source_starboards.push(source.id);
+            }
+        }
+        if let Some(name) = self.remove_source {
+            let Some(source) = Starboard::get_by_name(&ctx.bot.pool, &name, guild_id).await? else {
+                ctx.respond_str(&format!("Starboard '{name}' does not exist."), true)
+                    .await?;
+                return Ok(());
+            };
+            starboard.source_starboards.retain(|id| *id != source.id);
+        }
+
+        Starboard::set_aggregate(
+            &ctx.bot.pool,
+            starboard.id,
+            starboard.aggregate,
+            &starboard.source_starboards,
+        )
+        .await?;
+        let mut msg = format!("Updated settings for starboard '{}'.", self.name);
+        if queued != 0 {
+            msg.push_str(&format!(
+                " I'll remove my reactions with its old vote emojis from {queued} posts in the \
+                background."
+            ));
+        }
+        ctx.respond_str(&msg, false).await?;
+
+        Ok(())
+    }
+}
@@ -1,16 +1,19 @@
 use twilight_interactions::command::{CommandModel, CreateCommand};
 
 use crate::{
+    core::starboard::{anonymous::anonymizes_existing, schedule::spawn_enable_refresh},
     database::{
         validation::{self, cooldown::parse_cooldown},
-        ExclusiveGroup, Starboard,
+        ExclusiveGroup, Starboard, Vote,
     },
     errors::StarboardResult,
     get_guild_id,
     interactions::{commands::choices::on_delete::OnDelete, context::CommandCtx},
-    utils::id_as_i64::GetI64,
+    utils::{id_as_i64::GetI64, views::confirm},
 };
 
+use super::log_changes;
+
 #[derive(CommandModel, CreateCommand)]
 #[command(name = "behavior", desc = "Edit how the starboard should behave.")]
 pub struct EditBehavior {
@@ -18,7 +21,7 @@ pub struct EditBehavior {
     #[command(autocomplete = true)]
     name: String,
 
-    /// Whether the starboard is enabled.
+    /// Whether the starboard sends posts. Votes are still counted while it's disabled.
     enabled: Option<bool>,
     /// Whether to automatically react to starboard messages with the upvote emojis.
     #[command(rename = "autoreact-upvote")]
@@ -29,6 +32,9 @@ pub struct EditBehavior {
     /// Whether to remove reactions that don't meet requirements.
     #[command(rename = "remove-invalid-reactions")]
     remove_invalid_reactions: Option<bool>,
+    /// Whether removing an invalid reaction also stops that user from voting on the message again.
+    #[command(rename = "lock-vote-after-mod-removal")]
+    lock_vote_after_mod_removal: Option<bool>,
     /// If the original message is deleted, whether to also delete the starboard message.
     #[command(rename = "link-deletes")]
     link_deletes: Option<bool>,
@@ -38,8 +44,23 @@ pub struct EditBehavior {
     /// What to do if a moderator removes a post from the starboard manually.
     #[command(rename = "on-delete")]
     on_delete: Option<OnDelete>,
+    /// Whether to send a post again if a moderator deleted it manually.
+    #[command(rename = "recreate-deleted-posts")]
+    recreate_deleted_posts: Option<bool>,
+    /// Whether to create a discussion thread on each starboard message.
+    #[command(rename = "attach-thread")]
+    attach_thread: Option<bool>,
+    /// Whether to reply to the original message with a link to the starboard message.
+    #[command(rename = "reply-with-link")]
+    reply_with_link: Option<bool>,
     /// If true, prevents /random and /moststarred from pulling from this starboard.
     private: Option<bool>,
+    /// If true, the votes button only shows the number of votes, not who voted.
+    #[command(rename = "private-votes")]
+    private_votes: Option<bool>,
+    /// If true, votes are stored without who cast them. Enabling this anonymizes existing votes.
+    #[command(rename = "anonymous-votes")]
+    anonymous_votes: Option<bool>,
     /// How much XP each upvote on this starboard counts for.
     #[command(rename = "xp-multiplier", min_value = -10, max_value = 10)]
     xp_multiplier: Option<f64>,
@@ -71,6 +92,7 @@ impl EditBehavior {
                 }
                 Some(starboard) => starboard,
             };
+        let old_settings = starboard.settings.clone();
 
         if let Some(val) = self.enabled {
             starboard.settings.enabled = val;
@@ -84,6 +106,9 @@ impl EditBehavior {
         if let Some(val) = self.remove_invalid_reactions {
             starboard.settings.remove_invalid_reactions = val;
         }
+        if let Some(val) = self.lock_vote_after_mod_removal {
+            starboard.settings.lock_vote_after_mod_removal = val;
+        }
         if let Some(val) = self.link_deletes {
             starboard.settings.link_deletes = val;
         }
@@ -93,9 +118,26 @@ impl EditBehavior {
         if let Some(val) = self.on_delete {
             starboard.settings.on_delete = val.value() as i16;
         }
+        if let Some(val) = self.recreate_deleted_posts {
+            starboard.settings.recreate_deleted_posts = val;
+        }
+        if let Some(val) = self.attach_thread {
+            starboard.settings.attach_thread = val;
+        }
+        if let Some(val) = self.reply_with_link {
+            starboard.settings.reply_with_link = val;
+        }
         if let Some(val) = self.private {
             starboard.settings.private = val;
         }
+        if let Some(val) = self.private_votes {
+            starboard.settings.private_votes = val;
+        }
+        let mut anonymize = false;
+        if let Some(val) = self.anonymous_votes {
+            anonymize = anonymizes_existing(starboard.settings.anonymous_votes, val);
+            starboard.settings.anonymous_votes = val;
+        }
         if let Some(val) = self.xp_multiplier {
             let val = val.to_string().parse().unwrap();
             if let Err(why) = validation::starboard_settings::validate_xp_multiplier(val) {
@@ -138,12 +180,56 @@ impl EditBehavior {
             starboard.settings.exclusive_group_priority = val as i16;
         }
 
-        starboard.update_settings(&ctx.bot.pool).await?;
-        ctx.respond_str(
-            &format!("Updated settings for starboard '{}'.", self.name),
-            false,
+        if !anonymize {
+            if let Some(new) = starboard.update_settings(&ctx.bot.pool).await? {
+                log_changes(&ctx, &old_settings, &new);
+                if !old_settings.enabled && new.settings.enabled {
+                    spawn_enable_refresh(ctx.bot.clone(), new);
+                }
+            }
+            ctx.respond_str(
+                &format!("Updated settings for starboard '{}'.", self.name),
+                false,
+            )
+            .await?;
+            return Ok(());
+        }
+
+        let btn_ctx = confirm::simple(
+            &mut ctx,
+            &format!(
+                concat!(
+                    "Enabling `anonymous-votes` also anonymizes the existing votes on '{}'. ",
+                    "This can't be undone, even by disabling it again. Are you sure?"
+                ),
+                self.name
+            ),
+            true,
         )
         .await?;
+        let Some(mut btn_ctx) = btn_ctx else {
+            return Ok(());
+        };
+
+        let starboard_id = starboard.id;
+        if let Some(new) = starboard.update_settings(&ctx.bot.pool).await? {
+            log_changes(&ctx, &old_settings, &new);
+            if !old_settings.enabled && new.settings.enabled {
+                spawn_enable_refresh(ctx.bot.clone(), new);
+            }
+        }
+        // waiting votes were made before the setting changed
+        ctx.bot.vote_batcher.flush(&ctx.bot).await;
+        let changed = Vote::anonymize_starboard(&ctx.bot.pool, starboard_id).await?;
+        btn_ctx
+            .edit_str(
+                &format!(
+                    "Updated settings for starboard '{}' and anonymized {changed} vote(s).",
+                    self.name
+                ),
+                true,
+            )
+            .await?;
 
         Ok(())
     }
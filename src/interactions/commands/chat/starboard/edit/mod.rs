@@ -1,3 +1,4 @@
+pub mod aggregate;
 pub mod behavior;
 pub mod embed;
 pub mod requirements;
@@ -5,8 +6,14 @@ pub mod style;
 
 use twilight_interactions::command::{CommandModel, CreateCommand};
 
-use crate::{errors::StarboardResult, interactions::context::CommandCtx};
+use crate::{
+    database::{Starboard, StarboardSettings},
+    errors::StarboardResult,
+    interactions::context::CommandCtx,
+    utils::into_id::IntoId,
+};
 
+#[allow(clippy::large_enum_variant)] // Requirements(requirements::EditRequirements) being the culprit
 #[derive(CommandModel, CreateCommand)]
 #[command(name = "edit", desc = "Edit a starboard")]
 pub enum EditStarboard {
@@ -18,6 +25,8 @@ pub enum EditStarboard {
     Requirements(requirements::EditRequirements),
     #[command(name = "behavior")]
     Behaviour(behavior::EditBehavior),
+    #[command(name = "aggregate")]
+    Aggregate(aggregate::EditAggregate),
 }
 
 impl EditStarboard {
@@ -27,6 +36,19 @@ impl EditStarboard {
             Self::Style(cmd) => cmd.callback(ctx).await,
             Self::Requirements(cmd) => cmd.callback(ctx).await,
             Self::Behaviour(cmd) => cmd.callback(ctx).await,
+            Self::Aggregate(cmd) => cmd.callback(ctx).await,
         }
     }
 }
+
+/// Logs the settings an edit changed to the guild's log channel.
+fn log_changes(ctx: &CommandCtx, old: &StarboardSettings, new: &Starboard) {
+    ctx.bot.guild_log.log_settings(
+        &ctx.bot,
+        new.guild_id.into_id(),
+        ctx.interaction.author_id(),
+        format!("starboard '{}'", new.name),
+        old,
+        &new.settings,
+    );
+}
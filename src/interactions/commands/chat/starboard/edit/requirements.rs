@@ -3,12 +3,22 @@ use twilight_interactions::command::{CommandModel, CreateCommand};
 use crate::{
     core::{
         emoji::{EmojiCommon, SimpleEmoji},
+        emoji_migration::refresh_needs_attention,
         premium::is_premium::is_guild_premium,
+        starboard::cleanup::queue_removed_emojis,
+        vote_weights::{parse_weights, weights_from_user_input},
     },
     database::{
         validation::{
             self,
-            starboard_settings::{validate_required, validate_required_remove},
+            forum_tags::{parse_forum_tags, validate_tag_lists},
+            mentions::textable_channel_ids,
+            starboard_settings::{
+                min_unique_voters_warning, validate_channel_lists, validate_char_limits,
+                validate_emoji_weights, validate_max_chars, validate_max_posts_per_author,
+                validate_min_chars, validate_min_unique_voters, validate_min_vote_spread,
+                validate_required, validate_required_remove,
+            },
             time_delta::{parse_time_delta, validate_relative_duration},
         },
         Starboard,
@@ -19,6 +29,8 @@ use crate::{
     utils::id_as_i64::GetI64,
 };
 
+use super::log_changes;
+
 #[derive(CommandModel, CreateCommand)]
 #[command(
     name = "requirements",
@@ -34,32 +46,71 @@ pub struct EditRequirements {
     /// How few points the message can have before a starboarded post is removed. Use "none" to unset.
     #[command(rename = "required-remove")]
     required_remove: Option<String>,
+    /// How many different users must upvote a message, regardless of points. Use "none" to unset.
+    #[command(rename = "min-unique-voters")]
+    min_unique_voters: Option<String>,
     /// The emojis that can be used to upvote a post. Use 'none' to remove all.
     #[command(rename = "upvote-emojis")]
     upvote_emojis: Option<String>,
     /// The emojis that can be used to downvote a post. Use 'none' to remove all.
     #[command(rename = "downvote-emojis")]
     downvote_emojis: Option<String>,
+    /// Points per vote emoji, like "⭐=1, 💎=3". Others are worth 1. Use 'none' to remove all.
+    #[command(rename = "vote-emoji-weights")]
+    vote_emoji_weights: Option<String>,
     /// Whether to allow users to vote on their own posts.
     #[command(rename = "self-vote")]
     self_vote: Option<bool>,
+    /// Whether reactions on the starboard post count as votes.
+    #[command(rename = "votes-on-post")]
+    votes_on_post: Option<bool>,
     /// Whether to allow bot messages to be on the starboard.
     #[command(rename = "allow-bots")]
     allow_bots: Option<bool>,
     /// Whether to require posts to have an image to appear on the starboard.
     #[command(rename = "require-image")]
     require_image: Option<bool>,
+    /// How many characters of text a message needs. Images and stickers don't count.
+    #[command(rename = "min-chars", min_value = 0, max_value = 5_000)]
+    min_chars: Option<i64>,
+    /// How many characters of text a message can have. Use "none" to unset.
+    #[command(rename = "max-chars")]
+    max_chars: Option<String>,
+    /// Whether to require posts to include a link to appear on the starboard.
+    #[command(rename = "require-link")]
+    require_link: Option<bool>,
+    /// Whether to allow system messages (e.g. joins, boosts and pins) to be on the starboard.
+    #[command(rename = "allow-system-messages")]
+    allow_system_messages: Option<bool>,
     /// How old a post must be in order for it to be voted on (e.g. "1 hour"). Use 0 to disable.
     #[command(rename = "older-than")]
     older_than: Option<String>,
     /// How new a post must be in order for it to be voted on (e.g. "1 hour"). Use 0 to disable.
     #[command(rename = "newer-than")]
     newer_than: Option<String>,
+    /// Minimum time between the first and latest upvote (e.g. "5 minutes"). Use 0 to disable.
+    #[command(rename = "min-vote-spread")]
+    min_vote_spread: Option<String>,
+    /// How many posts one author can get per 24 hours. Later messages wait. Use "none" to unset.
+    #[command(rename = "max-posts-per-author-per-day")]
+    max_posts_per_author_per_day: Option<String>,
     /// (Premium) Content that messages must match to be starred (supports regex). Use ".*" to disable.
     matches: Option<String>,
     #[command(rename = "not-matches")]
     /// (Premium) content that messages must not match to be starred (supports regex). Use ".*" to disable.
     not_matches: Option<String>,
+    /// Channels or categories that votes count in. Use 'none' to allow all channels.
+    #[command(rename = "channel-whitelist")]
+    channel_whitelist: Option<String>,
+    /// Channels or categories that votes don't count in. Use 'none' to remove all.
+    #[command(rename = "channel-blacklist")]
+    channel_blacklist: Option<String>,
+    /// Forum tags that posts need one of for votes to count (comma-separated). Use 'none' to remove all.
+    #[command(rename = "required-tags")]
+    required_tags: Option<String>,
+    /// Forum tags that stop votes from counting on a post (comma-separated). Use 'none' to remove all.
+    #[command(rename = "excluded-tags")]
+    excluded_tags: Option<String>,
 }
 
 impl EditRequirements {
@@ -75,6 +126,7 @@ impl EditRequirements {
                 }
                 Some(starboard) => starboard,
             };
+        let old_settings = starboard.settings.clone();
 
         let is_prem = is_guild_premium(&ctx.bot, guild_id_i64, true).await?;
 
@@ -98,6 +150,16 @@ impl EditRequirements {
             };
             starboard.settings.required_remove = val;
         }
+        if let Some(val) = self.min_unique_voters {
+            let val = match validate_min_unique_voters(val) {
+                Ok(val) => val,
+                Err(why) => {
+                    ctx.respond_str(&why, true).await?;
+                    return Ok(());
+                }
+            };
+            starboard.settings.min_unique_voters = val;
+        }
         if let Some(val) = self.upvote_emojis {
             let emojis = SimpleEmoji::from_user_input(&val, &ctx.bot, guild_id).into_stored();
             starboard.settings.upvote_emojis = emojis;
@@ -121,16 +183,77 @@ impl EditRequirements {
             ctx.respond_str(&why, true).await?;
             return Ok(());
         }
+        if starboard.aggregate {
+            if let Err(why) = validation::starboard_settings::validate_aggregate_emojis(
+                &starboard.settings.upvote_emojis,
+                &starboard.settings.downvote_emojis,
+            ) {
+                ctx.respond_str(&why, true).await?;
+                return Ok(());
+            }
+        }
+
+        if let Some(val) = self.vote_emoji_weights {
+            let weights = if val == "none" {
+                Vec::new()
+            } else {
+                match weights_from_user_input(&val, &ctx.bot, guild_id) {
+                    Ok(weights) => weights,
+                    Err(why) => {
+                        ctx.respond_str(&why, true).await?;
+                        return Ok(());
+                    }
+                }
+            };
+            if let Err(why) = validate_emoji_weights(&weights) {
+                ctx.respond_str(&why, true).await?;
+                return Ok(());
+            }
+            starboard.settings.vote_emoji_weights = serde_json::to_value(weights)?;
+        }
 
         if let Some(val) = self.self_vote {
             starboard.settings.self_vote = val;
         }
+        if let Some(val) = self.votes_on_post {
+            starboard.settings.votes_on_post = val;
+        }
         if let Some(val) = self.allow_bots {
             starboard.settings.allow_bots = val;
         }
         if let Some(val) = self.require_image {
             starboard.settings.require_image = val;
         }
+        if let Some(val) = self.min_chars {
+            match validate_min_chars(val) {
+                Err(why) => {
+                    ctx.respond_str(&why, true).await?;
+                    return Ok(());
+                }
+                Ok(val) => starboard.settings.min_chars = val,
+            }
+        }
+        if let Some(val) = self.max_chars {
+            match validate_max_chars(val) {
+                Err(why) => {
+                    ctx.respond_str(&why, true).await?;
+                    return Ok(());
+                }
+                Ok(val) => starboard.settings.max_chars = val,
+            }
+        }
+        if let Err(why) =
+            validate_char_limits(starboard.settings.min_chars, starboard.settings.max_chars)
+        {
+            ctx.respond_str(&why, true).await?;
+            return Ok(());
+        }
+        if let Some(val) = self.require_link {
+            starboard.settings.require_link = val;
+        }
+        if let Some(val) = self.allow_system_messages {
+            starboard.settings.allow_system_messages = val;
+        }
         if let Some(val) = self.older_than {
             let delta = match parse_time_delta(&val) {
                 Err(why) => {
@@ -152,6 +275,26 @@ impl EditRequirements {
             starboard.settings.newer_than = delta;
         }
 
+        if let Some(val) = self.max_posts_per_author_per_day {
+            match validate_max_posts_per_author(val) {
+                Err(why) => {
+                    ctx.respond_str(&why, true).await?;
+                    return Ok(());
+                }
+                Ok(val) => starboard.settings.max_posts_per_author_per_day = val,
+            }
+        }
+        if let Some(val) = self.min_vote_spread {
+            let val = match validate_min_vote_spread(&val) {
+                Err(why) => {
+                    ctx.respond_str(&why, true).await?;
+                    return Ok(());
+                }
+                Ok(val) => val,
+            };
+            starboard.settings.min_vote_spread_seconds = val;
+        }
+
         if let Err(why) = validate_relative_duration(
             Some(starboard.settings.newer_than),
             Some(starboard.settings.older_than),
@@ -178,13 +321,73 @@ impl EditRequirements {
                 Ok(val) => starboard.settings.not_matches = val,
             }
         }
+        if let Some(val) = self.channel_whitelist {
+            let channels = textable_channel_ids(&ctx.bot, guild_id, &val).await?;
+            starboard.settings.channel_whitelist = channels.into_iter().collect();
+        }
+        if let Some(val) = self.channel_blacklist {
+            let channels = textable_channel_ids(&ctx.bot, guild_id, &val).await?;
+            starboard.settings.channel_blacklist = channels.into_iter().collect();
+        }
+
+        if let Err(why) = validate_channel_lists(
+            &starboard.settings.channel_whitelist,
+            &starboard.settings.channel_blacklist,
+        ) {
+            ctx.respond_str(&why, true).await?;
+            return Ok(());
+        }
+
+        if let Some(val) = self.required_tags {
+            match parse_forum_tags(&ctx.bot, guild_id, &val) {
+                Err(why) => {
+                    ctx.respond_str(&why, true).await?;
+                    return Ok(());
+                }
+                Ok(val) => starboard.settings.required_tags = val,
+            }
+        }
+        if let Some(val) = self.excluded_tags {
+            match parse_forum_tags(&ctx.bot, guild_id, &val) {
+                Err(why) => {
+                    ctx.respond_str(&why, true).await?;
+                    return Ok(());
+                }
+                Ok(val) => starboard.settings.excluded_tags = val,
+            }
+        }
+        if let Err(why) = validate_tag_lists(
+            &starboard.settings.required_tags,
+            &starboard.settings.excluded_tags,
+        ) {
+            ctx.respond_str(&why, true).await?;
+            return Ok(());
+        }
 
-        starboard.update_settings(&ctx.bot.pool).await?;
-        ctx.respond_str(
-            &format!("Updated settings for starboard '{}'.", self.name),
-            false,
-        )
-        .await?;
+        let mut msg = format!("Updated settings for starboard '{}'.", self.name);
+        if let Some(warning) = min_unique_voters_warning(
+            starboard.settings.min_unique_voters,
+            starboard.settings.required,
+            &parse_weights(&starboard.settings.vote_emoji_weights),
+        ) {
+            msg.push_str("\n\n");
+            msg.push_str(&warning);
+        }
+
+        let starboard_id = starboard.id;
+        if let Some(new) = starboard.update_settings(&ctx.bot.pool).await? {
+            log_changes(&ctx, &old_settings, &new);
+
+            let queued = queue_removed_emojis(&ctx.bot, &old_settings, &new).await?;
+            if queued != 0 {
+                msg.push_str(&format!(
+                    "\n\nI'll remove my reactions with the old vote emojis from {queued} posts in \
+                    the background."
+                ));
+            }
+        }
+        refresh_needs_attention(&ctx.bot, starboard_id).await?;
+        ctx.respond_str(&msg, false).await?;
 
         Ok(())
     }
@@ -3,15 +3,19 @@ use twilight_interactions::command::{CommandModel, CreateCommand};
 use crate::{
     core::{
         emoji::{EmojiCommon, SimpleEmoji},
+        emoji_migration::refresh_needs_attention,
+        emoji_tiers::tiers_from_user_input,
         starboard::webhooks::create_webhook,
     },
-    database::Starboard,
+    database::{validation::starboard_settings::validate_emoji_tiers, Starboard},
     errors::StarboardResult,
     get_guild_id,
     interactions::{commands::choices::go_to_message::GoToMessage, context::CommandCtx},
     utils::id_as_i64::GetI64,
 };
 
+use super::log_changes;
+
 #[derive(CommandModel, CreateCommand)]
 #[command(name = "style", desc = "Edit the general style of your starboard.")]
 pub struct EditGeneralStyle {
@@ -22,6 +26,9 @@ pub struct EditGeneralStyle {
     /// The emoji to show next to the point count. Use 'none' for nothing.
     #[command(rename = "display-emoji")]
     display_emoji: Option<String>,
+    /// Emojis that replace the display emoji at certain points, e.g. "10 🌟, 25 ✨". Use 'none' to remove.
+    #[command(rename = "emoji-tiers")]
+    emoji_tiers: Option<String>,
     /// Whether to mention the author on starboard posts.
     #[command(rename = "ping-author")]
     ping_author: Option<bool>,
@@ -37,6 +44,15 @@ pub struct EditGeneralStyle {
     /// Whether to use a webhook for starboard messages.
     #[command(rename = "use-webhook")]
     use_webhook: Option<bool>,
+    /// Whether to add a "Translate" button to starboard messages.
+    #[command(rename = "translate-button")]
+    translate_button: Option<bool>,
+    /// Whether to add buttons for voting to starboard messages.
+    #[command(rename = "vote-buttons")]
+    vote_buttons: Option<bool>,
+    /// Whether to add a button that lists who voted to starboard messages.
+    #[command(rename = "show-votes-button")]
+    show_votes_button: Option<bool>,
 }
 
 impl EditGeneralStyle {
@@ -53,6 +69,7 @@ impl EditGeneralStyle {
             }
             Some(starboard) => starboard,
         };
+        let old_settings = starboard.settings.clone();
 
         if let Some(val) = self.display_emoji {
             let emoji = if val == "none" {
@@ -75,6 +92,25 @@ impl EditGeneralStyle {
             };
             starboard.settings.display_emoji = emoji.map(|emoji| emoji.into_stored());
         }
+        if let Some(val) = self.emoji_tiers {
+            let tiers = if val == "none" {
+                Vec::new()
+            } else {
+                match tiers_from_user_input(&val, &ctx.bot, guild_id) {
+                    Ok(tiers) => tiers,
+                    Err(why) => {
+                        ctx.respond_str(&why, true).await?;
+                        return Ok(());
+                    }
+                }
+            };
+            if let Err(why) = validate_emoji_tiers(&tiers) {
+                ctx.respond_str(&why, true).await?;
+                return Ok(());
+            }
+            let tiers = serde_json::to_value(tiers)?;
+            starboard.settings.display_emoji_tiers = tiers;
+        }
         if let Some(val) = self.ping_author {
             starboard.settings.ping_author = val;
         }
@@ -95,6 +131,15 @@ impl EditGeneralStyle {
         } else {
             message = None;
         }
+        if let Some(val) = self.translate_button {
+            starboard.settings.translate_button = val;
+        }
+        if let Some(val) = self.vote_buttons {
+            starboard.settings.vote_buttons = val;
+        }
+        if let Some(val) = self.show_votes_button {
+            starboard.settings.show_votes_button = val;
+        }
 
         let mut response = format!("Updated settings for '{}'.", self.name);
         if let Some(message) = message {
@@ -102,7 +147,11 @@ impl EditGeneralStyle {
             response.push_str(message);
         }
 
-        starboard.update_settings(&ctx.bot.pool).await?;
+        let starboard_id = starboard.id;
+        if let Some(new) = starboard.update_settings(&ctx.bot.pool).await? {
+            log_changes(&ctx, &old_settings, &new);
+        }
+        refresh_needs_attention(&ctx.bot, starboard_id).await?;
         ctx.respond_str(&response, false).await?;
         Ok(())
     }
@@ -0,0 +1,133 @@
+use twilight_interactions::command::{CommandModel, CreateCommand};
+
+use crate::{
+    core::emoji::{EmojiCommon, SimpleEmoji},
+    database::{EmojiUsage, Starboard, StarboardOverride},
+    errors::StarboardResult,
+    get_guild_id,
+    interactions::context::CommandCtx,
+    utils::{embed, id_as_i64::GetI64},
+};
+
+const BAR_WIDTH: i64 = 10;
+
+#[derive(CreateCommand, CommandModel)]
+#[command(name = "emoji-stats", desc = "See which emojis are used to vote.")]
+pub struct EmojiStats {
+    /// The starboard to show the emoji usage of.
+    #[command(autocomplete = true)]
+    name: String,
+}
+
+impl EmojiStats {
+    pub async fn callback(self, mut ctx: CommandCtx) -> StarboardResult<()> {
+        let guild_id = get_guild_id!(ctx);
+
+        let Some(starboard) =
+            Starboard::get_by_name(&ctx.bot.pool, &self.name, guild_id.get_i64()).await?
+        else {
+            let msg = ctx
+                .t("errors.unknown-starboard", &[("name", &self.name)])
+                .await?;
+            ctx.respond_str(&msg, true).await?;
+            return Ok(());
+        };
+
+        ctx.bot.emoji_usage.flush(&ctx.bot).await;
+        let usage = EmojiUsage::list_by_starboard(&ctx.bot.pool, starboard.id).await?;
+        let total: i64 = usage.iter().map(|u| u.count).sum();
+        if total == 0 {
+            ctx.respond_str(
+                &format!("No votes have been counted on '{}' yet.", starboard.name),
+                true,
+            )
+            .await?;
+            return Ok(());
+        }
+
+        // emojis that are only used by overrides aren't removed
+        let mut current = starboard.settings.upvote_emojis.clone();
+        current.extend(starboard.settings.downvote_emojis.iter().cloned());
+        for ov in StarboardOverride::list_by_starboard(&ctx.bot.pool, starboard.id).await? {
+            let Ok(values) = ov.get_overrides() else {
+                continue;
+            };
+            current.extend(values.upvote_emojis.into_iter().flatten());
+            current.extend(values.downvote_emojis.into_iter().flatten());
+        }
+
+        let max = usage[0].count;
+        let mut description = String::new();
+        for row in usage {
+            let emoji = SimpleEmoji::from_stored(row.emoji);
+            let removed = !current.iter().any(|e| emoji == *e);
+            description.push_str(&usage_line(
+                &emoji.into_readable(&ctx.bot, guild_id),
+                row.count,
+                max,
+                total,
+                removed,
+            ));
+        }
+
+        let emb = embed::build()
+            .title(format!("Emoji Usage for '{}'", starboard.name))
+            .description(description)
+            .build();
+        ctx.respond(ctx.build_resp().embeds([emb]).build()).await?;
+
+        Ok(())
+    }
+}
+
+/// One emoji's line, with a bar relative to the most used emoji.
+fn usage_line(emoji: &str, count: i64, max: i64, total: i64, removed: bool) -> String {
+    let bar_len = (count * BAR_WIDTH + max - 1) / max;
+
+    let mut line = format!(
+        "{} `{}{}` {} ({:.1}%)",
+        emoji,
+        "█".repeat(bar_len as usize),
+        " ".repeat((BAR_WIDTH - bar_len) as usize),
+        count,
+        count as f64 * 100.0 / total as f64,
+    );
+    if removed {
+        line.push_str(" (removed)");
+    }
+    line.push('\n');
+    line
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn most_used() {
+        assert_eq!(
+            usage_line("⭐", 30, 30, 40, false),
+            "⭐ `██████████` 30 (75.0%)\n"
+        );
+    }
+
+    #[test]
+    fn bars_round_up() {
+        assert_eq!(
+            usage_line("💎", 1, 30, 40, false),
+            "💎 `█         ` 1 (2.5%)\n"
+        );
+        assert_eq!(
+            usage_line("💎", 16, 30, 40, false),
+            "💎 `██████    ` 16 (40.0%)\n"
+        );
+    }
+
+    #[test]
+    fn removed() {
+        assert_eq!(
+            usage_line("👍", 9, 30, 40, true),
+            "👍 `███       ` 9 (22.5%) (removed)\n"
+        );
+    }
+}
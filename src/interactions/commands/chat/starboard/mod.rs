@@ -1,8 +1,12 @@
 pub mod create;
 pub mod delete;
+pub mod doctor;
 pub mod edit;
+pub mod emoji_stats;
 pub mod filters;
+pub mod move_channel;
 pub mod rename;
+pub mod schedule;
 pub mod view;
 
 use twilight_interactions::command::{CommandModel, CreateCommand};
@@ -12,6 +16,7 @@ use crate::{
     interactions::{commands::permissions::manage_channels, context::CommandCtx},
 };
 
+#[allow(clippy::large_enum_variant)] // Edit(edit::EditStarboard) being the culprit
 #[derive(CommandModel, CreateCommand)]
 #[command(
     name = "starboards",
@@ -30,8 +35,16 @@ pub enum Starboard {
     Rename(rename::RenameStarboard),
     #[command(name = "edit")]
     Edit(edit::EditStarboard),
+    #[command(name = "emoji-stats")]
+    EmojiStats(emoji_stats::EmojiStats),
     #[command(name = "filters")]
     Filters(filters::Filters),
+    #[command(name = "move")]
+    Move(move_channel::MoveStarboard),
+    #[command(name = "schedule")]
+    Schedule(schedule::ScheduleStarboard),
+    #[command(name = "doctor")]
+    Doctor(doctor::Doctor),
 }
 
 impl Starboard {
@@ -42,7 +55,11 @@ impl Starboard {
             Self::View(cmd) => cmd.callback(ctx).await,
             Self::Rename(cmd) => cmd.callback(ctx).await,
             Self::Edit(cmd) => cmd.callback(ctx).await,
+            Self::EmojiStats(cmd) => cmd.callback(ctx).await,
             Self::Filters(cmd) => cmd.callback(ctx).await,
+            Self::Move(cmd) => cmd.callback(ctx).await,
+            Self::Schedule(cmd) => cmd.callback(ctx).await,
+            Self::Doctor(cmd) => cmd.callback(ctx).await,
         }
     }
 }
@@ -0,0 +1,65 @@
+use twilight_interactions::command::{CommandModel, CreateCommand};
+use twilight_model::application::interaction::application_command::InteractionChannel;
+
+use crate::{
+    database::Starboard, errors::StarboardResult, get_guild_id, interactions::context::CommandCtx,
+    utils::id_as_i64::GetI64,
+};
+
+#[derive(CommandModel, CreateCommand)]
+#[command(
+    name = "move",
+    desc = "Move a starboard to another channel, e.g. after its channel was deleted."
+)]
+pub struct MoveStarboard {
+    /// The name of the starboard to move.
+    #[command(autocomplete = true)]
+    name: String,
+    /// The channel to move the starboard to.
+    #[command(channel_types = r#"
+            guild_text
+            guild_voice
+            guild_stage_voice
+            guild_announcement
+            announcement_thread
+            public_thread
+            private_thread
+            guild_forum
+        "#)]
+    channel: InteractionChannel,
+}
+
+impl MoveStarboard {
+    pub async fn callback(self, mut ctx: CommandCtx) -> StarboardResult<()> {
+        let guild_id = get_guild_id!(ctx).get_i64();
+        let channel_id = self.channel.id.get_i64();
+
+        let Some(starboard) = Starboard::get_by_name(&ctx.bot.pool, &self.name, guild_id).await?
+        else {
+            ctx.respond_str("No starboard with that name was found.", true)
+                .await?;
+            return Ok(());
+        };
+        if starboard.channel_id == channel_id && !starboard.channel_deleted {
+            ctx.respond_str(
+                &format!("Starboard '{}' is already in <#{channel_id}>.", self.name),
+                true,
+            )
+            .await?;
+            return Ok(());
+        }
+
+        Starboard::set_channel(&ctx.bot.pool, starboard.id, channel_id).await?;
+        ctx.respond_str(
+            &format!(
+                "Moved starboard '{}' to <#{channel_id}>. Posts that were already sent stay \
+                where they are.",
+                self.name
+            ),
+            false,
+        )
+        .await?;
+
+        Ok(())
+    }
+}
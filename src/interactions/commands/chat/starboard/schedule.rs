@@ -0,0 +1,134 @@
+use chrono::{DateTime, Utc};
+use twilight_interactions::command::{CommandModel, CreateCommand};
+
+use crate::{
+    core::guild_log::{LogEntry, SettingChange},
+    database::{
+        validation::competition::{parse_competition_time, parse_utc_offset},
+        Starboard,
+    },
+    errors::StarboardResult,
+    get_guild_id,
+    interactions::context::CommandCtx,
+    utils::id_as_i64::GetI64,
+};
+
+#[derive(CommandModel, CreateCommand)]
+#[command(
+    name = "schedule",
+    desc = "Enable or disable a starboard at a later time, e.g. during an event."
+)]
+pub struct ScheduleStarboard {
+    /// The starboard to schedule.
+    #[command(autocomplete = true)]
+    name: String,
+    /// When to enable the starboard (YYYY-MM-DD or YYYY-MM-DD HH:MM).
+    #[command(rename = "enable-at")]
+    enable_at: Option<String>,
+    /// When to disable the starboard (YYYY-MM-DD or YYYY-MM-DD HH:MM).
+    #[command(rename = "disable-at")]
+    disable_at: Option<String>,
+    /// The UTC offset for the times, e.g. "+02:00". Defaults to UTC.
+    #[command(rename = "utc-offset")]
+    utc_offset: Option<String>,
+    /// Cancel the scheduled times that aren't set by this command.
+    clear: Option<bool>,
+}
+
+impl ScheduleStarboard {
+    pub async fn callback(self, mut ctx: CommandCtx) -> StarboardResult<()> {
+        let guild_id = get_guild_id!(ctx);
+
+        let Some(starboard) =
+            Starboard::get_by_name(&ctx.bot.pool, &self.name, guild_id.get_i64()).await?
+        else {
+            let msg = ctx
+                .t("errors.unknown-starboard", &[("name", &self.name)])
+                .await?;
+            ctx.respond_str(&msg, true).await?;
+            return Ok(());
+        };
+
+        let (mut enable_at, mut disable_at) = match self.clear {
+            Some(true) => (None, None),
+            _ => (starboard.enable_at, starboard.disable_at),
+        };
+        let parsed = parse_utc_offset(self.utc_offset.as_deref()).and_then(|offset| {
+            let parse = |inp: &Option<String>| -> Result<_, String> {
+                let Some(inp) = inp else {
+                    return Ok(None);
+                };
+                let time = parse_competition_time(inp, offset)?;
+                if time <= Utc::now() {
+                    return Err(format!("`{inp}` is in the past."));
+                }
+                Ok(Some(time))
+            };
+            Ok((parse(&self.enable_at)?, parse(&self.disable_at)?))
+        });
+        match parsed {
+            Err(why) => {
+                ctx.respond_str(&why, true).await?;
+                return Ok(());
+            }
+            Ok((new_enable_at, new_disable_at)) => {
+                enable_at = new_enable_at.or(enable_at);
+                disable_at = new_disable_at.or(disable_at);
+            }
+        }
+
+        Starboard::set_schedule(&ctx.bot.pool, starboard.id, enable_at, disable_at).await?;
+
+        let changes: Vec<_> = [
+            ("enable-at", starboard.enable_at, enable_at),
+            ("disable-at", starboard.disable_at, disable_at),
+        ]
+        .into_iter()
+        .filter(|(_, old, new)| old != new)
+        .map(|(name, old, new)| SettingChange {
+            name: name.to_string(),
+            old: fmt_log_time(old),
+            new: fmt_log_time(new),
+        })
+        .collect();
+        if let (Some(moderator), false) = (ctx.interaction.author_id(), changes.is_empty()) {
+            let entry = LogEntry::SettingsChanged {
+                moderator,
+                target: format!("starboard '{}'", starboard.name),
+                changes,
+            };
+            ctx.bot.guild_log.log(&ctx.bot, guild_id, entry);
+        }
+
+        let status = match starboard.settings.enabled {
+            true => "enabled",
+            false => "disabled",
+        };
+        let mut msg = format!("Starboard '{}' is {status}.", starboard.name);
+        if let Some(at) = enable_at {
+            msg.push_str(&format!(
+                "\nIt will be enabled <t:{0}:F> (<t:{0}:R>).",
+                at.timestamp()
+            ));
+        }
+        if let Some(at) = disable_at {
+            msg.push_str(&format!(
+                "\nIt will be disabled <t:{0}:F> (<t:{0}:R>).",
+                at.timestamp()
+            ));
+        }
+        if enable_at.is_none() && disable_at.is_none() {
+            msg.push_str(" Nothing is scheduled.");
+        }
+        ctx.respond_str(&msg, false).await?;
+
+        Ok(())
+    }
+}
+
+fn fmt_log_time(time: Option<DateTime<Utc>>) -> String {
+    match time {
+        Some(time) => format!("<t:{}:f>", time.timestamp()),
+        None => "none".to_string(),
+    }
+}
@@ -9,7 +9,10 @@ use twilight_util::builder::embed::EmbedFieldBuilder;
 
 use crate::{
     client::bot::StarboardBot,
-    core::starboard::config::StarboardConfig,
+    core::{
+        config_drift::describe_warnings, post_latency::summarize_time_to_post,
+        premium::is_premium::is_guild_premium, starboard::config::StarboardConfig,
+    },
     database::Starboard,
     errors::StarboardResult,
     get_guild_id,
@@ -53,9 +56,16 @@ impl ViewStarboard {
             if sb.premium_locked {
                 label.push_str(" (premium-locked)");
             }
+            if sb.channel_deleted {
+                label.push_str(" (channel deleted)");
+            }
+            let warnings = describe_warnings(&bot, sb.id).await?;
+            if !warnings.is_empty() {
+                label.push_str(" (warnings)");
+            }
 
             let page = SelectPaginatorPageBuilder::new(label.clone())
-                .add_embed(starboard_embed(&bot, guild_id, sb).await?);
+                .add_embed(starboard_embed(&bot, guild_id, sb, &warnings).await?);
             paginator = paginator.add_page(page);
         }
 
@@ -73,6 +83,7 @@ async fn starboard_embed(
     bot: &StarboardBot,
     guild_id: Id<GuildMarker>,
     starboard: Starboard,
+    warnings: &[&str],
 ) -> StarboardResult<Embed> {
     let config = StarboardConfig::new(starboard, &[], vec![])?;
     let pretty = format_settings(bot, guild_id, &config).await?;
@@ -83,12 +94,49 @@ async fn starboard_embed(
             "This starboard is locked because it exceeds the non-premium limit.\n\n"
         ));
     }
+    if config.starboard.channel_deleted {
+        desc.push_str(concat!(
+            "This starboard's channel was deleted, so it won't post anything. Use ",
+            "`/starboards move` to move it to another channel.\n\n"
+        ));
+    }
+    if config.starboard.needs_attention {
+        desc.push_str(concat!(
+            "Some of the emojis for this starboard (or its overrides) are stored in a format ",
+            "that can't be read anymore. Please set them again.\n\n"
+        ));
+    }
+    if !warnings.is_empty() {
+        desc.push_str("Some settings can't work in this starboard's channel:\n");
+        for warning in warnings {
+            writeln!(desc, "- {warning}").unwrap();
+        }
+        desc.push('\n');
+    }
     write!(
         desc,
         "This starboard is in <#{}>.",
         config.starboard.channel_id
     )
     .unwrap();
+    if config.starboard.aggregate {
+        let mut sources = Vec::new();
+        for id in &config.starboard.source_starboards {
+            if let Some(sb) = Starboard::get(&bot.pool, *id).await? {
+                sources.push(sb.name);
+            }
+        }
+        let sources = if sources.is_empty() {
+            "no starboards".to_string()
+        } else {
+            sources.join(", ")
+        };
+        write!(
+            desc,
+            "\n\nThis is an aggregate starboard, using points from: {sources}."
+        )
+        .unwrap();
+    }
 
     let embed = embed::build()
         .title(format!("Starboard '{}'", &config.starboard.name))
@@ -114,8 +162,21 @@ async fn starboard_embed(
                 .build(),
         )
         .field(EmbedFieldBuilder::new("Regex Matching", pretty.regex).build())
-        .field(EmbedFieldBuilder::new("Filters", pretty.filters))
-        .build();
+        .field(EmbedFieldBuilder::new("Filters", pretty.filters));
+
+    let time_to_post = if is_guild_premium(bot, guild_id.get_i64(), true).await? {
+        summarize_time_to_post(bot, guild_id.get_i64()).await?
+    } else {
+        None
+    };
+    let embed = match time_to_post {
+        Some(summary) => embed.field(EmbedFieldBuilder::new(
+            "Time to Post (24h, whole server)",
+            summary,
+        )),
+        None => embed,
+    }
+    .build();
 
     Ok(embed)
 }
@@ -38,13 +38,14 @@ impl Stats {
         };
 
         let emb = {
+            let season_xp = stats.season_xp.separate_with_commas();
             let xp = stats.xp.separate_with_commas();
             let recv_up = stats.received_upvotes.separate_with_commas();
             let recv_down = stats.received_downvotes.separate_with_commas();
             let give_up = stats.given_upvotes.separate_with_commas();
             let give_down = stats.given_downvotes.separate_with_commas();
 
-            let pad = [&xp, &recv_up, &recv_down, &give_up, &give_down]
+            let pad = [&season_xp, &xp, &recv_up, &recv_down, &give_up, &give_down]
                 .into_iter()
                 .map(|s| s.len())
                 .max()
@@ -54,7 +55,8 @@ impl Stats {
                 .title("User Stats")
                 .description(concat_format!(
                     "Showing Stats for <@{user_id}>\n\n";
-                    "`{: >pad$}` - Total XP\n" <- stats.xp;
+                    "`{: >pad$}` - Season XP\n" <- stats.season_xp;
+                    "`{: >pad$}` - Lifetime XP\n" <- stats.xp;
                     "`{: >pad$}` - Total Upvotes Received\n" <- stats.received_upvotes;
                     "`{: >pad$}` - Total Downvotes Received\n\n" <- stats.received_downvotes;
                     "`{: >pad$}` - Total Upvotes Given\n" <- stats.given_upvotes;
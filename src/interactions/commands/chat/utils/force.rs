@@ -2,16 +2,22 @@ use twilight_interactions::command::{CommandModel, CreateCommand};
 
 use crate::{
     core::{
+        guild_log::LogEntry,
         premium::is_premium::is_guild_premium,
         starboard::{handle::RefreshMessage, message::get_or_create_original},
     },
     database::{DbMessage, Starboard},
     errors::StarboardResult,
     get_guild_id,
-    interactions::context::CommandCtx,
-    utils::{id_as_i64::GetI64, into_id::IntoId, message_link::parse_message_link},
+    interactions::{
+        commands::visibility::{CommandVisibility, VisibilityPolicy},
+        context::CommandCtx,
+    },
+    utils::{id_as_i64::GetI64, into_id::IntoId},
 };
 
+use super::{get_message_ref, log_action};
+
 #[derive(CommandModel, CreateCommand)]
 #[command(name = "force", desc = "Force a message to one or all starboards.")]
 pub struct Force {
@@ -23,10 +29,13 @@ pub struct Force {
     starboard: Option<String>,
 }
 
+impl CommandVisibility for Force {
+    const VISIBILITY: VisibilityPolicy = VisibilityPolicy::EPHEMERAL;
+}
+
 impl Force {
     pub async fn callback(self, mut ctx: CommandCtx) -> StarboardResult<()> {
-        let Some((channel_id, message_id)) = parse_message_link(&self.message) else {
-            ctx.respond_str("Invalid message link.", true).await?;
+        let Some((channel_id, message_id)) = get_message_ref(&mut ctx, &self.message).await? else {
             return Ok(());
         };
 
@@ -36,7 +45,7 @@ impl Force {
         if !ctx
             .bot
             .cache
-            .guild_has_channel(&ctx.bot, guild_id, channel_id.into_id())
+            .guild_has_channel(&ctx.bot, guild_id, channel_id)
             .await?
         {
             ctx.respond_str("That message belongs to a different server.", true)
@@ -44,14 +53,14 @@ impl Force {
             return Ok(());
         }
 
-        let forced: Vec<_> = match self.starboard {
+        let forced: Vec<_> = match &self.starboard {
             None => Starboard::list_by_guild(&ctx.bot.pool, guild_id.get_i64())
                 .await?
                 .into_iter()
                 .map(|s| s.id)
                 .collect(),
             Some(name) => {
-                let Some(sb) = Starboard::get_by_name(&ctx.bot.pool, &name, guild_id.get_i64()).await? else {
+                let Some(sb) = Starboard::get_by_name(&ctx.bot.pool, name, guild_id.get_i64()).await? else {
                     ctx.respond_str(&format!("Starboard '{name}' does not exist."), true).await?;
                     return Ok(());
                 };
@@ -71,13 +80,7 @@ impl Force {
             return Ok(());
         }
 
-        let ret = get_or_create_original(
-            &ctx.bot,
-            guild_id,
-            channel_id.into_id(),
-            message_id.into_id(),
-        )
-        .await?;
+        let ret = get_or_create_original(&ctx.bot, guild_id, channel_id, message_id).await?;
         let (Some(orig), _) = ret else {
             ctx.respond_str(
                 concat!(
@@ -89,7 +92,7 @@ impl Force {
         };
 
         let mut forced = forced;
-        for already_forced in orig.forced_to {
+        for &already_forced in &orig.forced_to {
             if !forced.contains(&already_forced) {
                 forced.push(already_forced);
             }
@@ -97,10 +100,17 @@ impl Force {
 
         let is_premium = is_guild_premium(&ctx.bot, guild_id.get_i64(), true).await?;
         DbMessage::set_forced(&ctx.bot.pool, orig.message_id, &forced).await?;
+        log_action(&ctx, &orig, |moderator, message_link| {
+            LogEntry::MessageForced {
+                moderator,
+                message_link,
+                starboard: self.starboard,
+            }
+        });
         RefreshMessage::new(ctx.bot.clone(), orig.message_id.into_id(), is_premium)
             .refresh(true)
             .await?;
-        ctx.respond_str("Message forced.", true).await?;
+        ctx.respond_str("Message forced.", false).await?;
 
         Ok(())
     }
@@ -0,0 +1,32 @@
+use twilight_interactions::command::{CommandModel, CreateCommand};
+
+use crate::{
+    core::ignored_sources::MessageSource, errors::StarboardResult,
+    interactions::context::CommandCtx,
+};
+
+use super::edit_ignored_source;
+
+#[derive(CommandModel, CreateCommand)]
+#[command(name = "app", desc = "Ignore messages sent by a bot or app.")]
+pub struct IgnoreApp {
+    /// The ID of the app (for bots, this is the same as the bot's user ID).
+    id: String,
+    /// Whether to stop ignoring the app instead.
+    remove: Option<bool>,
+}
+
+impl IgnoreApp {
+    pub async fn callback(self, ctx: CommandCtx) -> StarboardResult<()> {
+        let source = self
+            .id
+            .trim()
+            .trim_start_matches("<@")
+            .trim_start_matches('!')
+            .trim_end_matches('>')
+            .parse()
+            .ok()
+            .map(MessageSource::Application);
+        edit_ignored_source(ctx, source, self.remove.unwrap_or(false)).await
+    }
+}
@@ -0,0 +1,126 @@
+use twilight_interactions::command::{CommandModel, CreateCommand};
+use twilight_model::channel::Attachment;
+
+use crate::{
+    constants,
+    core::id_import::{download, parse_ids},
+    database::DbGuild,
+    errors::StarboardResult,
+    get_guild_id,
+    interactions::{commands::choices::ignore_kind::IgnoreKind, context::CommandCtx},
+    utils::{id_as_i64::GetI64, views::confirm},
+};
+
+use super::can_manage;
+
+#[derive(CommandModel, CreateCommand)]
+#[command(
+    name = "import",
+    desc = "Ignore many webhooks or apps at once, from a file of IDs."
+)]
+pub struct Import {
+    /// Whether the file lists webhooks or apps.
+    kind: IgnoreKind,
+    /// A text or CSV file with one ID per line, or separated by commas.
+    file: Attachment,
+    /// Preview the import without changing anything.
+    #[command(rename = "dry-run")]
+    dry_run: Option<bool>,
+}
+
+impl Import {
+    pub async fn callback(self, mut ctx: CommandCtx) -> StarboardResult<()> {
+        let guild_id = get_guild_id!(ctx).get_i64();
+        let user_id = ctx.interaction.author_id().unwrap().get_i64();
+
+        if !can_manage(&ctx) {
+            ctx.respond_str(
+                "You need the Manage Server permission to use this command.",
+                true,
+            )
+            .await?;
+            return Ok(());
+        }
+
+        let Some(file) = download(&ctx.bot, &self.file).await? else {
+            ctx.respond_str(
+                &format!(
+                    "That file is too large. The maximum size is {} KB.",
+                    constants::MAX_ID_IMPORT_SIZE / 1024
+                ),
+                true,
+            )
+            .await?;
+            return Ok(());
+        };
+        let parsed = match parse_ids(&file) {
+            Ok(parsed) => parsed,
+            Err(why) => {
+                ctx.respond_str(&why, true).await?;
+                return Ok(());
+            }
+        };
+
+        DbGuild::create(&ctx.bot.pool, guild_id).await?;
+        let guild = DbGuild::get(&ctx.bot.pool, guild_id).await?.unwrap();
+        let (mut ids, noun) = match self.kind {
+            IgnoreKind::Webhook => (guild.ignored_webhook_ids, "webhook"),
+            IgnoreKind::App => (guild.ignored_application_ids, "app"),
+        };
+
+        let new: Vec<_> = parsed
+            .ids
+            .iter()
+            .copied()
+            .filter(|id| !ids.contains(id))
+            .collect();
+        let summary = parsed.summary(parsed.ids.len() - new.len());
+
+        if self.dry_run == Some(true) || new.is_empty() {
+            ctx.respond_str(&summary, true).await?;
+            return Ok(());
+        }
+        if ids.len() + new.len() > constants::MAX_IGNORED_SOURCES {
+            ctx.respond_str(
+                &format!(
+                    "{summary}\nYou can only ignore up to {} {noun}s, and {} are already ignored.",
+                    constants::MAX_IGNORED_SOURCES,
+                    ids.len()
+                ),
+                true,
+            )
+            .await?;
+            return Ok(());
+        }
+
+        let prompt = format!("{summary}\nIgnore {} {noun}(s)?", new.len());
+        let Some(mut btn_ctx) = confirm::simple(&mut ctx, &prompt, false).await? else {
+            return Ok(());
+        };
+
+        ids.extend(&new);
+        match self.kind {
+            IgnoreKind::Webhook => {
+                DbGuild::set_ignored_webhook_ids(&ctx.bot.pool, guild_id, &ids).await?
+            }
+            IgnoreKind::App => {
+                DbGuild::set_ignored_application_ids(&ctx.bot.pool, guild_id, &ids).await?
+            }
+        }
+        ctx.bot.cache.guild_ignored_sources.remove(&guild_id);
+
+        tracing::info!(
+            "Ignore import: guild {} by user {}, {} {}s ignored.",
+            guild_id,
+            user_id,
+            new.len(),
+            noun,
+        );
+
+        btn_ctx
+            .edit_str(&format!("Ignored {} {noun}(s).", new.len()), true)
+            .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,71 @@
+use twilight_interactions::command::{CommandModel, CreateCommand};
+
+use crate::{
+    constants,
+    core::ignored_sources::{get_ignored_sources, top_message_sources, MessageSource},
+    errors::StarboardResult,
+    get_guild_id,
+    interactions::context::CommandCtx,
+    utils::{embed, id_as_i64::GetI64},
+};
+
+#[derive(CommandModel, CreateCommand)]
+#[command(
+    name = "list",
+    desc = "List the ignored webhooks and apps, and the ones that sent the most messages today."
+)]
+pub struct List;
+
+impl List {
+    pub async fn callback(self, mut ctx: CommandCtx) -> StarboardResult<()> {
+        let guild_id = get_guild_id!(ctx).get_i64();
+        let ignored = get_ignored_sources(&ctx.bot, guild_id).await?;
+
+        let mut ignored_lines: Vec<_> = ignored
+            .webhook_ids
+            .iter()
+            .map(|id| format!("- {}", MessageSource::Webhook(*id)))
+            .chain(
+                ignored
+                    .application_ids
+                    .iter()
+                    .map(|id| format!("- {}", MessageSource::Application(*id))),
+            )
+            .collect();
+        ignored_lines.sort();
+        let ignored_desc = if ignored_lines.is_empty() {
+            "Nothing is ignored.".to_string()
+        } else {
+            ignored_lines.join("\n")
+        };
+
+        let top = top_message_sources(&ctx.bot, guild_id, constants::IGNORE_SUGGESTIONS);
+        let top_desc = if top.is_empty() {
+            "No messages from webhooks or apps were seen recently.".to_string()
+        } else {
+            top.into_iter()
+                .map(|(source, count)| {
+                    let is_ignored = match source {
+                        MessageSource::Webhook(id) => ignored.webhook_ids.contains(&id),
+                        MessageSource::Application(id) => ignored.application_ids.contains(&id),
+                    };
+                    format!(
+                        "- {source}: {count} messages{}",
+                        if is_ignored { " (ignored)" } else { "" }
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join("\n")
+        };
+
+        let emb = embed::build()
+            .title("Ignored Webhooks and Apps")
+            .description(format!(
+                "{ignored_desc}\n\n**Most active in the last day:**\n{top_desc}"
+            ))
+            .build();
+        ctx.respond(ctx.build_resp().embeds([emb]).build()).await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,121 @@
+pub mod app;
+pub mod import;
+pub mod list;
+pub mod webhook;
+
+use twilight_interactions::command::{CommandModel, CreateCommand};
+use twilight_model::guild::Permissions;
+
+use crate::{
+    constants, core::ignored_sources::MessageSource, database::DbGuild, errors::StarboardResult,
+    get_guild_id, interactions::context::CommandCtx, utils::id_as_i64::GetI64,
+};
+
+#[derive(CommandModel, CreateCommand)]
+#[command(
+    name = "ignore",
+    desc = "Ignore messages from webhooks and apps, such as bridges and logging bots."
+)]
+pub enum Ignore {
+    #[command(name = "webhook")]
+    Webhook(webhook::IgnoreWebhook),
+    #[command(name = "app")]
+    App(app::IgnoreApp),
+    #[command(name = "import")]
+    Import(Box<import::Import>),
+    #[command(name = "list")]
+    List(list::List),
+}
+
+impl Ignore {
+    pub async fn callback(self, ctx: CommandCtx) -> StarboardResult<()> {
+        match self {
+            Self::Webhook(cmd) => cmd.callback(ctx).await,
+            Self::App(cmd) => cmd.callback(ctx).await,
+            Self::Import(cmd) => cmd.callback(ctx).await,
+            Self::List(cmd) => cmd.callback(ctx).await,
+        }
+    }
+}
+
+/// Whether the user can edit the ignore lists, which needs Manage Server.
+fn can_manage(ctx: &CommandCtx) -> bool {
+    ctx.interaction
+        .member
+        .as_ref()
+        .and_then(|m| m.permissions)
+        .is_some_and(|p| p.contains(Permissions::MANAGE_GUILD))
+}
+
+/// Adds the source to (or removes it from) the guild's ignore lists.
+async fn edit_ignored_source(
+    mut ctx: CommandCtx,
+    source: Option<MessageSource>,
+    remove: bool,
+) -> StarboardResult<()> {
+    let guild_id = get_guild_id!(ctx).get_i64();
+
+    if !can_manage(&ctx) {
+        ctx.respond_str(
+            "You need the Manage Server permission to use this command.",
+            true,
+        )
+        .await?;
+        return Ok(());
+    }
+
+    let Some(source) = source else {
+        ctx.respond_str("That isn't a valid ID.", true).await?;
+        return Ok(());
+    };
+
+    DbGuild::create(&ctx.bot.pool, guild_id).await?;
+    let guild = DbGuild::get(&ctx.bot.pool, guild_id).await?.unwrap();
+    let (mut ids, id) = match source {
+        MessageSource::Webhook(id) => (guild.ignored_webhook_ids, id),
+        MessageSource::Application(id) => (guild.ignored_application_ids, id),
+    };
+
+    let msg = if remove {
+        if !ids.contains(&id) {
+            ctx.respond_str(&format!("The {source} isn't ignored."), true)
+                .await?;
+            return Ok(());
+        }
+        ids.retain(|ignored| *ignored != id);
+        format!("Stopped ignoring the {source}.")
+    } else {
+        if ids.contains(&id) {
+            ctx.respond_str(&format!("The {source} is already ignored."), true)
+                .await?;
+            return Ok(());
+        }
+        if ids.len() >= constants::MAX_IGNORED_SOURCES {
+            ctx.respond_str(
+                &format!(
+                    "You can only ignore up to {} webhooks and {0} apps.",
+                    constants::MAX_IGNORED_SOURCES
+                ),
+                true,
+            )
+            .await?;
+            return Ok(());
+        }
+        ids.push(id);
+        format!("Messages from the {source} will be ignored.")
+    };
+
+    match source {
+        MessageSource::Webhook(_) => {
+            DbGuild::set_ignored_webhook_ids(&ctx.bot.pool, guild_id, &ids).await?
+        }
+        MessageSource::Application(_) => {
+            DbGuild::set_ignored_application_ids(&ctx.bot.pool, guild_id, &ids).await?
+        }
+    }
+    ctx.bot.cache.guild_ignored_sources.remove(&guild_id);
+
+    ctx.respond_str(&msg, false).await?;
+
+    Ok(())
+}
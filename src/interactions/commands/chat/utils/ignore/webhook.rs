@@ -0,0 +1,24 @@
+use twilight_interactions::command::{CommandModel, CreateCommand};
+
+use crate::{
+    core::ignored_sources::MessageSource, errors::StarboardResult,
+    interactions::context::CommandCtx,
+};
+
+use super::edit_ignored_source;
+
+#[derive(CommandModel, CreateCommand)]
+#[command(name = "webhook", desc = "Ignore messages sent by a webhook.")]
+pub struct IgnoreWebhook {
+    /// The ID of the webhook.
+    id: String,
+    /// Whether to stop ignoring the webhook instead.
+    remove: Option<bool>,
+}
+
+impl IgnoreWebhook {
+    pub async fn callback(self, ctx: CommandCtx) -> StarboardResult<()> {
+        let source = self.id.trim().parse().ok().map(MessageSource::Webhook);
+        edit_ignored_source(ctx, source, self.remove.unwrap_or(false)).await
+    }
+}
@@ -1,5 +1,4 @@
 use twilight_interactions::command::{CommandModel, CreateCommand};
-use twilight_model::channel::message::MessageFlags;
 use twilight_util::builder::embed::EmbedFieldBuilder;
 
 use crate::{
@@ -8,16 +7,19 @@ use crate::{
     database::{DbMessage, Starboard, StarboardMessage, StarboardOverride, Vote},
     errors::StarboardResult,
     get_guild_id,
-    interactions::context::CommandCtx,
+    interactions::{
+        commands::visibility::{CommandVisibility, VisibilityPolicy},
+        context::CommandCtx,
+    },
     utils::{
         embed,
         id_as_i64::GetI64,
         into_id::IntoId,
-        message_link::{fmt_message_link, parse_message_link},
+        message_link::fmt_message_link,
     },
 };
 
-use super::INVALID_MESSAGE_ERR;
+use super::{get_message_ref, INVALID_MESSAGE_ERR};
 
 #[derive(CommandModel, CreateCommand)]
 #[command(name = "info", desc = "Get info for a message.")]
@@ -26,17 +28,20 @@ pub struct Info {
     message: String,
 }
 
+impl CommandVisibility for Info {
+    const VISIBILITY: VisibilityPolicy = VisibilityPolicy::EPHEMERAL;
+}
+
 impl Info {
     pub async fn callback(self, mut ctx: CommandCtx) -> StarboardResult<()> {
         let guild_id = get_guild_id!(ctx);
         let guild_id_i64 = guild_id.get_i64();
 
-        let Some((_channel_id, message_id)) = parse_message_link(&self.message) else {
-            ctx.respond_str("Invalid message link.", true).await?;
+        let Some((_channel_id, message_id)) = get_message_ref(&mut ctx, &self.message).await? else {
             return Ok(());
         };
 
-        let Some(sql_msg) = DbMessage::get_original(&ctx.bot.pool, message_id).await? else {
+        let Some(sql_msg) = DbMessage::get_original(&ctx.bot.pool, message_id.get_i64()).await? else {
             ctx.respond_str(INVALID_MESSAGE_ERR, true).await?;
             return Ok(());
         };
@@ -57,7 +62,7 @@ impl Info {
         ));
 
         for starboard in Starboard::list_by_guild(&ctx.bot.pool, guild_id_i64).await? {
-            let points = Vote::count(&ctx.bot.pool, sql_msg.message_id, starboard.id).await?;
+            let count = Vote::count(&ctx.bot.pool, sql_msg.message_id, starboard.id).await?;
 
             let channel_ids = ctx
                 .bot
@@ -105,10 +110,14 @@ impl Info {
                     config.starboard.name,
                     concat_format!(
                         "{}\n" <- link;
-                        "points: {}/{}\n" <- points,
+                        "points: {}/{}\n" <- count.points,
                             config.resolved.required
                                 .map(|v| v.to_string())
                                 .unwrap_or_else(|| "unset".to_string());
+                        "unique voters: {}/{}\n" <- count.unique_voters,
+                            config.resolved.min_unique_voters
+                                .map(|v| v.to_string())
+                                .unwrap_or_else(|| "unset".to_string());
                         "forced: {}" <- sql_msg.forced_to.contains(&config.starboard.id);
                     ),
                 )
@@ -116,13 +125,8 @@ impl Info {
             );
         }
 
-        ctx.respond(
-            ctx.build_resp()
-                .embeds([emb.build()])
-                .flags(MessageFlags::EPHEMERAL)
-                .build(),
-        )
-        .await?;
+        ctx.respond(ctx.build_resp().embeds([emb.build()]).build())
+            .await?;
 
         Ok(())
     }
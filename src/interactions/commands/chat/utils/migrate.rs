@@ -0,0 +1,136 @@
+use std::sync::atomic::Ordering;
+
+use twilight_interactions::command::{CommandModel, CreateCommand};
+use twilight_model::guild::Permissions;
+
+use crate::{
+    constants,
+    core::starboard::migrate::migrate_page,
+    database::{MigrationCursor, Starboard},
+    errors::StarboardResult,
+    get_guild_id,
+    interactions::{
+        commands::visibility::{CommandVisibility, VisibilityPolicy},
+        context::CommandCtx,
+    },
+    utils::id_as_i64::GetI64,
+};
+
+#[derive(CommandModel, CreateCommand)]
+#[command(
+    name = "migrate",
+    desc = "Import existing posts from a starboard's channel, e.g. after switching bots."
+)]
+pub struct Migrate {
+    /// The starboard whose channel should be scanned.
+    #[command(autocomplete = true)]
+    starboard: String,
+    /// Start over instead of continuing where the last migration stopped.
+    restart: Option<bool>,
+}
+
+impl CommandVisibility for Migrate {
+    const VISIBILITY: VisibilityPolicy = VisibilityPolicy::EPHEMERAL;
+}
+
+impl Migrate {
+    pub async fn callback(self, mut ctx: CommandCtx) -> StarboardResult<()> {
+        let guild_id = get_guild_id!(ctx);
+        let guild_id_i64 = guild_id.get_i64();
+
+        let is_admin = ctx
+            .interaction
+            .member
+            .as_ref()
+            .and_then(|m| m.permissions)
+            .is_some_and(|p| p.contains(Permissions::ADMINISTRATOR));
+        let is_owner = ctx
+            .interaction
+            .author_id()
+            .is_some_and(|id| ctx.bot.config.owner_ids.contains(&id.get()));
+        if !is_admin && !is_owner {
+            ctx.respond_str("Only server administrators can use this command.", true)
+                .await?;
+            return Ok(());
+        }
+
+        let Some(starboard) =
+            Starboard::get_by_name(&ctx.bot.pool, &self.starboard, guild_id_i64).await?
+        else {
+            let msg = ctx
+                .t("errors.unknown-starboard", &[("name", &self.starboard)])
+                .await?;
+            ctx.respond_str(&msg, true).await?;
+            return Ok(());
+        };
+
+        let bot = ctx.bot.clone();
+        let Some(_lock) = bot.locks.starboard_migrate.lock(starboard.channel_id) else {
+            ctx.respond_str("That channel is already being migrated.", true)
+                .await?;
+            return Ok(());
+        };
+
+        if self.restart == Some(true) {
+            MigrationCursor::delete(&ctx.bot.pool, starboard.channel_id).await?;
+        }
+        let mut cursor = MigrationCursor::get_or_create(
+            &ctx.bot.pool,
+            starboard.channel_id,
+            guild_id_i64,
+            starboard.id,
+        )
+        .await?;
+
+        if cursor.finished {
+            ctx.respond_str(
+                &format!(
+                    "<#{}> was already migrated ({} posts imported). Use `restart` to scan it again.",
+                    starboard.channel_id, cursor.imported
+                ),
+                true,
+            )
+            .await?;
+            return Ok(());
+        }
+
+        ctx.defer(false).await?;
+
+        let i = ctx.bot.interaction_client().await;
+        while !cursor.finished && !ctx.bot.shutting_down.load(Ordering::Relaxed) {
+            migrate_page(&ctx.bot, guild_id, &mut cursor).await?;
+
+            let progress = format!(
+                "Migrating <#{}>... scanned {} messages, imported {} posts.",
+                starboard.channel_id, cursor.scanned, cursor.imported
+            );
+            // the interaction token expires after 15 minutes, but the migration
+            // should keep going regardless.
+            let _ = i
+                .update_response(&ctx.interaction.token)
+                .content(Some(&progress))?
+                .await;
+
+            if !cursor.finished {
+                tokio::time::sleep(constants::MIGRATE_PAGE_DELAY).await;
+            }
+        }
+
+        let status = if cursor.finished {
+            "Finished migrating"
+        } else {
+            "Paused migrating (the bot is restarting, run this again to continue)"
+        };
+        let done = format!(
+            "{status} <#{}>. Scanned {} messages and imported {} posts.",
+            starboard.channel_id, cursor.scanned, cursor.imported
+        );
+        tracing::info!("Migration in guild {guild_id}: {done}");
+        let _ = i
+            .update_response(&ctx.interaction.token)
+            .content(Some(&done))?
+            .await;
+
+        Ok(())
+    }
+}
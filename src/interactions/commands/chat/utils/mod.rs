@@ -1,17 +1,30 @@
 pub mod force;
 pub mod freeze;
+pub mod ignore;
 pub mod info;
+pub mod migrate;
+pub mod mod_notes;
+pub mod purge_user;
 pub mod recount;
 pub mod refresh;
+pub mod simulate;
 pub mod trash;
 pub mod trashcan;
 pub mod unforce;
+pub mod visibility;
 
 use twilight_interactions::command::{CommandModel, CreateCommand};
+use twilight_model::id::{
+    marker::{ChannelMarker, MessageMarker, UserMarker},
+    Id,
+};
 
 use crate::{
+    core::guild_log::LogEntry,
+    database::DbMessage,
     errors::StarboardResult,
     interactions::{commands::permissions::manage_messages, context::CommandCtx},
+    utils::{into_id::IntoId, message_link::fmt_message_link, message_ref::parse_message_ref},
 };
 
 const INVALID_MESSAGE_ERR: &str = concat!(
@@ -21,6 +34,38 @@ const INVALID_MESSAGE_ERR: &str = concat!(
     "\n- The message doesn't have any upvotes, so it isn't in the database.",
 );
 
+/// Parses the `message` option of a command, telling the user what's wrong
+/// with it if it doesn't refer to a message.
+pub async fn get_message_ref(
+    ctx: &mut CommandCtx,
+    input: &str,
+) -> StarboardResult<Option<(Id<ChannelMarker>, Id<MessageMarker>)>> {
+    let current_channel = ctx.interaction.channel.as_ref().map(|c| c.id);
+    match parse_message_ref(input, current_channel) {
+        Ok(ids) => Ok(Some(ids)),
+        Err(why) => {
+            ctx.respond_str(&why.to_string(), true).await?;
+            Ok(None)
+        }
+    }
+}
+
+/// Logs what a moderator did to a message, given the moderator and a link to
+/// the message.
+fn log_action(
+    ctx: &CommandCtx,
+    orig: &DbMessage,
+    entry: impl FnOnce(Id<UserMarker>, String) -> LogEntry,
+) {
+    let Some(moderator) = ctx.interaction.author_id() else {
+        return;
+    };
+    let link = fmt_message_link(orig.guild_id, orig.channel_id, orig.message_id);
+    ctx.bot
+        .guild_log
+        .log(&ctx.bot, orig.guild_id.into_id(), entry(moderator, link));
+}
+
 #[derive(CommandModel, CreateCommand)]
 #[command(
     name = "utils",
@@ -53,6 +98,18 @@ pub enum Utils {
     Refresh(refresh::Refresh),
     #[command(name = "recount")]
     Recount(recount::Recount),
+    #[command(name = "simulate")]
+    Simulate(simulate::Simulate),
+    #[command(name = "migrate")]
+    Migrate(migrate::Migrate),
+    #[command(name = "visibility")]
+    Visibility(visibility::Visibility),
+    #[command(name = "ignore")]
+    Ignore(ignore::Ignore),
+    #[command(name = "delete-mod-note")]
+    DeleteModNote(mod_notes::DeleteModNote),
+    #[command(name = "purge-user")]
+    PurgeUser(purge_user::PurgeUser),
 }
 
 impl Utils {
@@ -72,6 +129,12 @@ impl Utils {
 
             Self::Refresh(cmd) => cmd.callback(ctx).await,
             Self::Recount(cmd) => cmd.callback(ctx).await,
+            Self::Simulate(cmd) => cmd.callback(ctx).await,
+            Self::Migrate(cmd) => cmd.callback(ctx).await,
+            Self::Visibility(cmd) => cmd.callback(ctx).await,
+            Self::Ignore(cmd) => cmd.callback(ctx).await,
+            Self::DeleteModNote(cmd) => cmd.callback(ctx).await,
+            Self::PurgeUser(cmd) => cmd.callback(ctx).await,
         }
     }
 }
@@ -0,0 +1,48 @@
+use twilight_interactions::command::{CommandModel, CreateCommand};
+
+use crate::{
+    database::ModNote,
+    errors::StarboardResult,
+    get_guild_id,
+    interactions::{
+        commands::visibility::{CommandVisibility, VisibilityPolicy},
+        context::CommandCtx,
+    },
+    utils::id_as_i64::GetI64,
+};
+
+#[derive(CommandModel, CreateCommand)]
+#[command(name = "delete-mod-note", desc = "Delete a mod note.")]
+pub struct DeleteModNote {
+    /// The ID of the note, shown by "View notes" (e.g. 12 for #12).
+    #[command(min_value = 1)]
+    id: i64,
+}
+
+impl CommandVisibility for DeleteModNote {
+    const VISIBILITY: VisibilityPolicy = VisibilityPolicy::EPHEMERAL;
+}
+
+impl DeleteModNote {
+    pub async fn callback(self, mut ctx: CommandCtx) -> StarboardResult<()> {
+        let guild_id = get_guild_id!(ctx).get_i64();
+
+        let Ok(id) = i32::try_from(self.id) else {
+            ctx.respond_str("No note with that ID was found.", true)
+                .await?;
+            return Ok(());
+        };
+        match ModNote::delete(&ctx.bot.pool, guild_id, id).await? {
+            None => {
+                ctx.respond_str("No note with that ID was found.", true)
+                    .await?
+            }
+            Some(note) => {
+                ctx.respond_str(&format!("Deleted note `#{}`.", note.id), true)
+                    .await?
+            }
+        };
+
+        Ok(())
+    }
+}
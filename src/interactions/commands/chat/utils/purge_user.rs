@@ -0,0 +1,209 @@
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+};
+
+use twilight_interactions::command::{CommandModel, CreateCommand};
+use twilight_model::{
+    channel::message::{
+        component::{ActionRow, Button, ButtonStyle},
+        Component,
+    },
+    id::{marker::UserMarker, Id},
+};
+
+use crate::{
+    constants,
+    core::user_purge::{delete_batch, plan_batches},
+    database::{DbMessage, Starboard, StarboardMessage},
+    errors::StarboardResult,
+    get_guild_id,
+    interactions::{context::CommandCtx, custom_id::view},
+    utils::{
+        id_as_i64::GetI64,
+        into_id::IntoId,
+        views::{confirm, wait_for::wait_for_component},
+    },
+};
+
+const TRASH_REASON: &str = "Purged with /utils purge-user.";
+
+fn stop_button() -> Vec<Component> {
+    vec![Component::ActionRow(ActionRow {
+        components: vec![Component::Button(Button {
+            custom_id: Some(view::PURGE_USER_STOP.to_string()),
+            disabled: false,
+            emoji: None,
+            label: Some("Stop".to_string()),
+            style: ButtonStyle::Danger,
+            url: None,
+        })],
+    })]
+}
+
+#[derive(CommandModel, CreateCommand)]
+#[command(
+    name = "purge-user",
+    desc = "Delete the starboard posts of all of a user's messages."
+)]
+pub struct PurgeUser {
+    /// The user whose posts to delete.
+    user: Id<UserMarker>,
+    /// Only delete posts on this starboard. Leave blank to delete from all.
+    #[command(autocomplete = true)]
+    starboard: Option<String>,
+}
+
+impl PurgeUser {
+    pub async fn callback(self, mut ctx: CommandCtx) -> StarboardResult<()> {
+        let guild_id = get_guild_id!(ctx).get_i64();
+
+        let starboards = Starboard::list_by_guild(&ctx.bot.pool, guild_id).await?;
+        let starboard_id = match &self.starboard {
+            None => None,
+            Some(name) => match starboards.iter().find(|sb| &sb.name == name) {
+                Some(sb) => Some(sb.id),
+                None => {
+                    let msg = ctx.t("errors.unknown-starboard", &[("name", name)]).await?;
+                    ctx.respond_str(&msg, true).await?;
+                    return Ok(());
+                }
+            },
+        };
+        let channels: HashMap<_, _> = starboards
+            .iter()
+            .filter(|sb| !sb.channel_deleted)
+            .map(|sb| {
+                let is_forum = ctx
+                    .bot
+                    .cache
+                    .is_channel_forum(guild_id.into_id(), sb.channel_id.into_id());
+                (sb.id, (sb.channel_id, is_forum))
+            })
+            .collect();
+
+        let bot = ctx.bot.clone();
+        let Some(_lock) = bot.locks.user_purge.lock(guild_id) else {
+            ctx.respond_str("A purge is already running in this server.", true)
+                .await?;
+            return Ok(());
+        };
+
+        let posts = StarboardMessage::list_by_author(
+            &ctx.bot.pool,
+            guild_id,
+            self.user.get_i64(),
+            starboard_id,
+        )
+        .await?;
+        if posts.is_empty() {
+            ctx.respond_str(&format!("<@{}> has no posts to delete.", self.user), true)
+                .await?;
+            return Ok(());
+        }
+
+        let prompt = match &self.starboard {
+            None => format!(
+                concat!(
+                    "This deletes {} post(s) of messages by <@{}> and trashes all of their ",
+                    "messages, so that they aren't sent again. Are you sure?"
+                ),
+                posts.len(),
+                self.user
+            ),
+            Some(name) => format!(
+                concat!(
+                    "This deletes {} post(s) of messages by <@{}> from '{}'. They're treated ",
+                    "as deleted by hand, so `recreate-deleted-posts` decides whether they're ",
+                    "sent again. Are you sure?"
+                ),
+                posts.len(),
+                self.user,
+                name
+            ),
+        };
+        let Some(mut btn_ctx) = confirm::simple(&mut ctx, &prompt, true).await? else {
+            return Ok(());
+        };
+
+        if starboard_id.is_none() {
+            DbMessage::trash_by_author(&ctx.bot.pool, guild_id, self.user.get_i64(), TRASH_REASON)
+                .await?;
+        }
+
+        let total = posts.len();
+        let batches = plan_batches(posts, &channels);
+        let msg = btn_ctx
+            .edit(
+                btn_ctx
+                    .build_resp()
+                    .content(format!("Deleting posts... 0/{total}"))
+                    .components(stop_button())
+                    .build(),
+            )
+            .await?
+            .model()
+            .await?;
+
+        let stop = Arc::new(AtomicBool::new(false));
+        let stop_clone = stop.clone();
+        let bot_clone = ctx.bot.clone();
+        let user_id = btn_ctx.interaction.author_id().unwrap();
+        let waiter = tokio::spawn(async move {
+            let ret = wait_for_component(
+                bot_clone,
+                &[view::PURGE_USER_STOP],
+                msg.id,
+                user_id,
+                60 * 60,
+            )
+            .await;
+            if let Some(mut stop_ctx) = ret {
+                stop_clone.store(true, Ordering::Relaxed);
+                let _ = stop_ctx.edit_str("Stopping...", true).await;
+            }
+        });
+
+        let i = ctx.bot.interaction_client().await;
+        let mut removed = 0;
+        let mut last_reported = 0;
+        let mut stopped = false;
+        for batch in batches {
+            if stop.load(Ordering::Relaxed) || ctx.bot.shutting_down.load(Ordering::Relaxed) {
+                stopped = true;
+                break;
+            }
+
+            removed += delete_batch(&ctx.bot, &batch, starboard_id.is_some()).await?;
+
+            if removed - last_reported >= constants::PURGE_USER_PROGRESS_EVERY {
+                last_reported = removed;
+                // the interaction token expires after 15 minutes, but the
+                // purge should keep going regardless.
+                let _ = i
+                    .update_response(&btn_ctx.interaction.token)
+                    .content(Some(&format!("Deleting posts... {removed}/{total}")))?
+                    .await;
+            }
+
+            tokio::time::sleep(constants::PURGE_USER_DELETE_DELAY).await;
+        }
+        waiter.abort();
+
+        let done = if stopped {
+            format!("Stopped after deleting {removed} of {total} post(s).")
+        } else {
+            format!("Deleted {removed} post(s).")
+        };
+        let _ = i
+            .update_response(&btn_ctx.interaction.token)
+            .content(Some(&done))?
+            .components(Some(&[]))?
+            .await;
+
+        Ok(())
+    }
+}
@@ -1,13 +1,19 @@
+use std::fmt::Write;
+
 use twilight_interactions::command::{CommandModel, CreateCommand};
 
 use crate::{
     core::starboard::recount::{recount_votes, RecountResult},
     errors::StarboardResult,
     get_guild_id,
-    interactions::context::CommandCtx,
-    utils::{into_id::IntoId, message_link::parse_message_link},
+    interactions::{
+        commands::visibility::{CommandVisibility, VisibilityPolicy},
+        context::CommandCtx,
+    },
 };
 
+use super::get_message_ref;
+
 #[derive(CommandModel, CreateCommand)]
 #[command(name = "recount", desc = "Recount all the reactions on a message.")]
 pub struct Recount {
@@ -15,34 +21,43 @@ pub struct Recount {
     message: String,
 }
 
+impl CommandVisibility for Recount {
+    const VISIBILITY: VisibilityPolicy = VisibilityPolicy::EPHEMERAL;
+}
+
 impl Recount {
     pub async fn callback(self, mut ctx: CommandCtx) -> StarboardResult<()> {
         let guild_id = get_guild_id!(ctx);
 
-        let Some((channel_id, message_id)) = parse_message_link(&self.message) else {
-            ctx.respond_str("Invalid message link.", true).await?;
+        let Some((channel_id, message_id)) = get_message_ref(&mut ctx, &self.message).await? else {
             return Ok(());
         };
 
-        ctx.defer(true).await?;
+        ctx.defer(false).await?;
 
-        let ret = recount_votes(
-            ctx.bot.clone(),
-            guild_id,
-            channel_id.into_id(),
-            message_id.into_id(),
-        )
-        .await?;
+        let ret = recount_votes(ctx.bot.clone(), guild_id, channel_id, message_id).await?;
         let msg = match ret {
-            RecountResult::UnkownMessage => "I couldn't find that message.",
+            RecountResult::UnkownMessage => "I couldn't find that message.".to_string(),
             RecountResult::AlreadyRecounting => {
-                "I'm already recounting the reactions on that message."
+                "I'm already recounting the reactions on that message.".to_string()
+            }
+            RecountResult::Cooldown(retry) => format!(
+                "Messages in that channel were recounted recently. Try again in {} seconds.",
+                retry.as_secs().max(1)
+            ),
+            RecountResult::Done(starboards) if starboards.is_empty() => {
+                "Finished, but that message isn't in a channel with any starboards.".to_string()
+            }
+            RecountResult::Done(starboards) => {
+                let mut msg = "Finished! Points before and after recounting:".to_string();
+                for sb in starboards {
+                    write!(msg, "\n- '{}': {} → {}", sb.name, sb.before, sb.after).unwrap();
+                }
+                msg
             }
-            RecountResult::Cooldown(_) => "You're using this command too much.",
-            RecountResult::Done => "Finished!",
         };
 
-        ctx.respond_str(msg, true).await?;
+        ctx.respond_str(&msg, false).await?;
 
         Ok(())
     }
@@ -5,11 +5,14 @@ use crate::{
     database::DbMessage,
     errors::StarboardResult,
     get_guild_id,
-    interactions::context::CommandCtx,
-    utils::{id_as_i64::GetI64, into_id::IntoId, message_link::parse_message_link},
+    interactions::{
+        commands::visibility::{CommandVisibility, VisibilityPolicy},
+        context::CommandCtx,
+    },
+    utils::id_as_i64::GetI64,
 };
 
-use super::INVALID_MESSAGE_ERR;
+use super::{get_message_ref, INVALID_MESSAGE_ERR};
 
 #[derive(CommandModel, CreateCommand)]
 #[command(
@@ -21,16 +24,19 @@ pub struct Refresh {
     message: String,
 }
 
+impl CommandVisibility for Refresh {
+    const VISIBILITY: VisibilityPolicy = VisibilityPolicy::EPHEMERAL;
+}
+
 impl Refresh {
     pub async fn callback(self, mut ctx: CommandCtx) -> StarboardResult<()> {
         let guild_id = get_guild_id!(ctx).get_i64();
 
-        let Some((_channel_id, message_id)) = parse_message_link(&self.message) else {
-            ctx.respond_str("Invalid message link.", true).await?;
+        let Some((_channel_id, message_id)) = get_message_ref(&mut ctx, &self.message).await? else {
             return Ok(());
         };
 
-        let Some(orig) = DbMessage::get_original(&ctx.bot.pool, message_id).await? else {
+        let Some(orig) = DbMessage::get_original(&ctx.bot.pool, message_id.get_i64()).await? else {
             ctx.respond_str(INVALID_MESSAGE_ERR, true).await?;
             return Ok(());
         };
@@ -41,14 +47,14 @@ impl Refresh {
             return Ok(());
         }
 
-        ctx.defer(true).await?;
+        ctx.defer(false).await?;
 
         let is_premium = is_guild_premium(&ctx.bot, guild_id, true).await?;
-        RefreshMessage::new(ctx.bot.clone(), message_id.into_id(), is_premium)
+        RefreshMessage::new(ctx.bot.clone(), message_id, is_premium)
             .refresh(true)
             .await?;
 
-        ctx.respond_str("Message refreshed.", true).await?;
+        ctx.respond_str("Message refreshed.", false).await?;
 
         Ok(())
     }
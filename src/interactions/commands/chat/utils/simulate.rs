@@ -0,0 +1,97 @@
+use twilight_interactions::command::{CommandModel, CreateCommand};
+use twilight_model::id::{marker::UserMarker, Id};
+
+use crate::{
+    core::{emoji::SimpleEmoji, starboard::simulation::simulate_vote},
+    errors::StarboardResult,
+    get_guild_id,
+    interactions::{
+        commands::visibility::{CommandVisibility, VisibilityPolicy},
+        context::CommandCtx,
+    },
+    utils::embed,
+};
+
+use super::get_message_ref;
+
+#[derive(CommandModel, CreateCommand)]
+#[command(name = "simulate", desc = "Simulate actions without performing them.")]
+pub enum Simulate {
+    #[command(name = "vote")]
+    Vote(SimulateVote),
+}
+
+impl CommandVisibility for Simulate {
+    const VISIBILITY: VisibilityPolicy = VisibilityPolicy::PRIVATE;
+}
+
+impl Simulate {
+    pub async fn callback(self, ctx: CommandCtx) -> StarboardResult<()> {
+        match self {
+            Self::Vote(cmd) => cmd.callback(ctx).await,
+        }
+    }
+}
+
+#[derive(CommandModel, CreateCommand)]
+#[command(
+    name = "vote",
+    desc = "See what would happen if a user reacted to a message with an emoji."
+)]
+pub struct SimulateVote {
+    /// Link to the message to simulate a vote on.
+    message: String,
+    /// The user who would react.
+    user: Id<UserMarker>,
+    /// The emoji they would react with.
+    emoji: String,
+}
+
+impl SimulateVote {
+    pub async fn callback(self, mut ctx: CommandCtx) -> StarboardResult<()> {
+        let guild_id = get_guild_id!(ctx);
+
+        let Some((channel_id, message_id)) = get_message_ref(&mut ctx, &self.message).await? else {
+            return Ok(());
+        };
+
+        let mut emojis = SimpleEmoji::from_user_input(&self.emoji, &ctx.bot, guild_id);
+        if emojis.len() != 1 {
+            ctx.respond_str("Please specify exactly one emoji.", true)
+                .await?;
+            return Ok(());
+        }
+        let emoji = emojis.remove(0);
+
+        ctx.defer(false).await?;
+
+        let sim = simulate_vote(
+            &ctx.bot,
+            guild_id,
+            channel_id,
+            message_id,
+            self.user,
+            &emoji,
+        )
+        .await?;
+
+        let steps = sim
+            .steps
+            .iter()
+            .enumerate()
+            .map(|(idx, step)| format!("{}. {step}", idx + 1))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let emb = embed::build()
+            .title("Vote Simulation")
+            .description(format!(
+                "Simulating a vote by <@{}>. Nothing was changed.\n\n{steps}",
+                self.user
+            ))
+            .build();
+
+        ctx.respond(ctx.build_resp().embeds([emb]).build()).await?;
+
+        Ok(())
+    }
+}
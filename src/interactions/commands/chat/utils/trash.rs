@@ -1,20 +1,27 @@
 use twilight_interactions::command::{CommandModel, CreateCommand};
 
 use crate::{
-    core::{premium::is_premium::is_guild_premium, starboard::handle::RefreshMessage},
+    core::{
+        guild_log::LogEntry,
+        premium::is_premium::is_guild_premium,
+        starboard::{handle::RefreshMessage, message::get_or_create_original},
+    },
     database::DbMessage,
     errors::StarboardResult,
     get_guild_id,
-    interactions::context::CommandCtx,
-    utils::{id_as_i64::GetI64, into_id::IntoId, message_link::parse_message_link},
+    interactions::{
+        commands::visibility::{CommandVisibility, VisibilityPolicy},
+        context::CommandCtx,
+    },
+    utils::{id_as_i64::GetI64, into_id::IntoId},
 };
 
-use super::INVALID_MESSAGE_ERR;
+use super::{get_message_ref, log_action, INVALID_MESSAGE_ERR};
 
 #[derive(CommandModel, CreateCommand)]
 #[command(
     name = "trash",
-    desc = "Trash a message so that it is removed from all starboards."
+    desc = "Trash a message so that it is hidden on all starboards."
 )]
 pub struct Trash {
     /// Link to the message to trash.
@@ -24,21 +31,46 @@ pub struct Trash {
     reason: Option<String>,
 }
 
+impl CommandVisibility for Trash {
+    const VISIBILITY: VisibilityPolicy = VisibilityPolicy::EPHEMERAL;
+}
+
 impl Trash {
     pub async fn callback(self, mut ctx: CommandCtx) -> StarboardResult<()> {
-        let guild_id = get_guild_id!(ctx).get_i64();
+        let guild_id = get_guild_id!(ctx);
 
-        let Some((_channel_id, message_id)) = parse_message_link(&self.message) else {
-            ctx.respond_str("Invalid message link.", true).await?;
+        let Some((channel_id, message_id)) = get_message_ref(&mut ctx, &self.message).await? else {
             return Ok(());
         };
 
-        let Some(orig) = DbMessage::get_original(&ctx.bot.pool, message_id).await? else {
-            ctx.respond_str(INVALID_MESSAGE_ERR, true).await?;
+        if !ctx
+            .bot
+            .cache
+            .guild_has_channel(&ctx.bot, guild_id, channel_id)
+            .await?
+        {
+            ctx.respond_str("That message belongs to a different server.", true)
+                .await?;
+            return Ok(());
+        }
+
+        // create the message if needed, so that trashing a message before it
+        // receives any votes still sticks
+        let ret = get_or_create_original(&ctx.bot, guild_id, channel_id, message_id).await?;
+        let (Some(orig), _) = ret else {
+            ctx.respond_str(
+                concat!(
+                    "I don't have the necessary permissions to see that message. Make ",
+                    "sure I have the 'view channel' and 'read message history' ",
+                    "permissions in that channel."
+                ),
+                true,
+            )
+            .await?;
             return Ok(());
         };
 
-        if orig.guild_id != guild_id {
+        if orig.guild_id != guild_id.get_i64() {
             ctx.respond_str("That message belongs to a different server.", true)
                 .await?;
             return Ok(());
@@ -46,9 +78,16 @@ impl Trash {
 
         DbMessage::set_trashed(&ctx.bot.pool, orig.message_id, true, self.reason.as_deref())
             .await?;
-        ctx.respond_str("Message trashed.", true).await?;
-
-        let is_premium = is_guild_premium(&ctx.bot, guild_id, true).await?;
+        log_action(&ctx, &orig, |moderator, message_link| {
+            LogEntry::MessageTrashed {
+                moderator,
+                message_link,
+                reason: self.reason,
+            }
+        });
+        ctx.respond_str("Message trashed.", false).await?;
+
+        let is_premium = is_guild_premium(&ctx.bot, guild_id.get_i64(), true).await?;
         RefreshMessage::new(ctx.bot, orig.message_id.into_id(), is_premium)
             .refresh(true)
             .await?;
@@ -64,16 +103,19 @@ pub struct UnTrash {
     message: String,
 }
 
+impl CommandVisibility for UnTrash {
+    const VISIBILITY: VisibilityPolicy = VisibilityPolicy::EPHEMERAL;
+}
+
 impl UnTrash {
     pub async fn callback(self, mut ctx: CommandCtx) -> StarboardResult<()> {
         let guild_id = get_guild_id!(ctx).get_i64();
 
-        let Some((_channel_id, message_id)) = parse_message_link(&self.message) else {
-            ctx.respond_str("Invalid message link.", true).await?;
+        let Some((_channel_id, message_id)) = get_message_ref(&mut ctx, &self.message).await? else {
             return Ok(());
         };
 
-        let Some(orig) = DbMessage::get_original(&ctx.bot.pool, message_id).await? else {
+        let Some(orig) = DbMessage::get_original(&ctx.bot.pool, message_id.get_i64()).await? else {
             ctx.respond_str(INVALID_MESSAGE_ERR, true).await?;
             return Ok(());
         };
@@ -85,7 +127,13 @@ impl UnTrash {
         }
 
         DbMessage::set_trashed(&ctx.bot.pool, orig.message_id, false, None).await?;
-        ctx.respond_str("Message untrashed.", true).await?;
+        log_action(&ctx, &orig, |moderator, message_link| {
+            LogEntry::MessageUntrashed {
+                moderator,
+                message_link,
+            }
+        });
+        ctx.respond_str("Message untrashed.", false).await?;
         let is_premium = is_guild_premium(&ctx.bot, guild_id, true).await?;
         RefreshMessage::new(ctx.bot, orig.message_id.into_id(), is_premium)
             .refresh(true)
@@ -1,14 +1,22 @@
 use twilight_interactions::command::{CommandModel, CreateCommand};
 
 use crate::{
-    core::{premium::is_premium::is_guild_premium, starboard::handle::RefreshMessage},
+    core::{
+        guild_log::LogEntry, premium::is_premium::is_guild_premium,
+        starboard::handle::RefreshMessage,
+    },
     database::{DbMessage, Starboard},
     errors::StarboardResult,
     get_guild_id,
-    interactions::context::CommandCtx,
-    utils::{id_as_i64::GetI64, into_id::IntoId, message_link::parse_message_link},
+    interactions::{
+        commands::visibility::{CommandVisibility, VisibilityPolicy},
+        context::CommandCtx,
+    },
+    utils::{id_as_i64::GetI64, into_id::IntoId},
 };
 
+use super::{get_message_ref, log_action};
+
 #[derive(CommandModel, CreateCommand)]
 #[command(name = "unforce", desc = "Unforce a message.")]
 pub struct UnForce {
@@ -20,16 +28,19 @@ pub struct UnForce {
     starboard: Option<String>,
 }
 
+impl CommandVisibility for UnForce {
+    const VISIBILITY: VisibilityPolicy = VisibilityPolicy::EPHEMERAL;
+}
+
 impl UnForce {
     pub async fn callback(self, mut ctx: CommandCtx) -> StarboardResult<()> {
         let guild_id = get_guild_id!(ctx).get_i64();
 
-        let Some((_channel_id, message_id)) = parse_message_link(&self.message) else {
-            ctx.respond_str("Invalid message link.", true).await?;
+        let Some((_channel_id, message_id)) = get_message_ref(&mut ctx, &self.message).await? else {
             return Ok(());
         };
 
-        let Some(msg) = DbMessage::get_original(&ctx.bot.pool, message_id).await? else {
+        let Some(msg) = DbMessage::get_original(&ctx.bot.pool, message_id.get_i64()).await? else {
             ctx.respond_str("That message isn't forced.", true).await?;
             return Ok(())
         };
@@ -40,9 +51,9 @@ impl UnForce {
             return Ok(());
         }
 
-        match self.starboard {
+        match &self.starboard {
             Some(name) => {
-                let Some(starboard) = Starboard::get_by_name(&ctx.bot.pool, &name, guild_id).await? else {
+                let Some(starboard) = Starboard::get_by_name(&ctx.bot.pool, name, guild_id).await? else {
                     ctx.respond_str(&format!("Starboard '{name}' does not exist."), true).await?;
                     return Ok(());
                 };
@@ -61,7 +72,15 @@ impl UnForce {
             }
         }
 
-        ctx.respond_str("Message unforced.", true).await?;
+        log_action(&ctx, &msg, |moderator, message_link| {
+            LogEntry::MessageUnforced {
+                moderator,
+                message_link,
+                starboard: self.starboard,
+            }
+        });
+
+        ctx.respond_str("Message unforced.", false).await?;
         let is_premium = is_guild_premium(&ctx.bot, guild_id, true).await?;
         RefreshMessage::new(ctx.bot, msg.message_id.into_id(), is_premium)
             .refresh(true)
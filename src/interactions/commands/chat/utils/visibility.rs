@@ -0,0 +1,98 @@
+use twilight_interactions::command::{CommandModel, CreateCommand};
+use twilight_model::guild::Permissions;
+
+use crate::{
+    database::DbGuild,
+    errors::StarboardResult,
+    get_guild_id,
+    interactions::{
+        commands::{
+            choices::visibility::VisibilityChoice,
+            visibility::{self, all_command_names, parse_overrides, policy},
+        },
+        context::CommandCtx,
+    },
+    utils::id_as_i64::GetI64,
+};
+
+#[derive(CommandModel, CreateCommand)]
+#[command(
+    name = "visibility",
+    desc = "Choose whether a command's responses are public or only visible to the user."
+)]
+pub struct Visibility {
+    /// The command, e.g. "utils recount". Parent commands apply to their subcommands.
+    #[command(autocomplete = true)]
+    command: String,
+    /// The visibility to use. "Default" removes the override.
+    visibility: VisibilityChoice,
+}
+
+impl Visibility {
+    pub async fn callback(self, mut ctx: CommandCtx) -> StarboardResult<()> {
+        let guild_id = get_guild_id!(ctx).get_i64();
+
+        let can_manage = ctx
+            .interaction
+            .member
+            .as_ref()
+            .and_then(|m| m.permissions)
+            .is_some_and(|p| p.contains(Permissions::MANAGE_GUILD));
+        if !can_manage {
+            ctx.respond_str(
+                "You need the Manage Server permission to use this command.",
+                true,
+            )
+            .await?;
+            return Ok(());
+        }
+
+        let command = self.command.trim().trim_start_matches('/').to_lowercase();
+        if !all_command_names().contains(&command) {
+            ctx.respond_str(&format!("There is no command named `/{command}`."), true)
+                .await?;
+            return Ok(());
+        }
+        if !policy(&command).overridable {
+            ctx.respond_str(
+                &format!("The visibility of `/{command}` can't be changed."),
+                true,
+            )
+            .await?;
+            return Ok(());
+        }
+
+        DbGuild::create(&ctx.bot.pool, guild_id).await?;
+        let guild = DbGuild::get(&ctx.bot.pool, guild_id).await?.unwrap();
+        let mut overrides = parse_overrides(&guild.command_visibility_overrides);
+        let new_visibility = self.visibility.as_visibility();
+        match new_visibility {
+            Some(visibility) => overrides.insert(command.clone(), visibility),
+            None => overrides.remove(&command),
+        };
+
+        DbGuild::set_command_visibility_overrides(
+            &ctx.bot.pool,
+            guild_id,
+            serde_json::to_value(&overrides)?,
+        )
+        .await?;
+        ctx.bot
+            .cache
+            .guild_command_visibility
+            .insert(guild_id, overrides);
+
+        let msg = match new_visibility {
+            Some(visibility::Visibility::Public) => {
+                format!("`/{command}` will now respond publicly.")
+            }
+            Some(visibility::Visibility::Ephemeral) => {
+                format!("`/{command}` will now respond ephemerally.")
+            }
+            None => format!("`/{command}` will now use its default visibility."),
+        };
+        ctx.respond_str(&msg, false).await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,228 @@
+use std::fmt::Write;
+
+use twilight_interactions::command::{CommandModel, CreateCommand};
+
+use crate::{
+    core::{
+        emoji::{EmojiCommon, SimpleEmoji},
+        starboard::{
+            config::StarboardConfig,
+            manual_vote::{cast_vote, remove_vote, ManualVoteResult},
+            message::get_or_create_original,
+        },
+    },
+    database::{DbMessage, Vote as DbVote},
+    errors::StarboardResult,
+    get_guild_id,
+    interactions::context::CommandCtx,
+    utils::id_as_i64::GetI64,
+};
+
+use super::utils::get_message_ref;
+
+#[derive(CommandModel, CreateCommand)]
+#[command(
+    name = "vote",
+    desc = "Vote on a message without reacting to it.",
+    dm_permission = false
+)]
+pub enum Vote {
+    #[command(name = "up")]
+    Up(VoteUp),
+    #[command(name = "down")]
+    Down(VoteDown),
+    #[command(name = "remove")]
+    Remove(VoteRemove),
+}
+
+impl Vote {
+    pub async fn callback(self, ctx: CommandCtx) -> StarboardResult<()> {
+        match self {
+            Self::Up(cmd) => vote(ctx, cmd.message, cmd.starboard, Some(false)).await,
+            Self::Down(cmd) => vote(ctx, cmd.message, cmd.starboard, Some(true)).await,
+            Self::Remove(cmd) => vote(ctx, cmd.message, cmd.starboard, None).await,
+        }
+    }
+}
+
+#[derive(CommandModel, CreateCommand)]
+#[command(name = "up", desc = "Upvote a message.")]
+pub struct VoteUp {
+    /// Link to the message to upvote.
+    message: String,
+    /// The starboard to vote for. Leave blank to vote for all.
+    #[command(autocomplete = true)]
+    starboard: Option<String>,
+}
+
+#[derive(CommandModel, CreateCommand)]
+#[command(name = "down", desc = "Downvote a message.")]
+pub struct VoteDown {
+    /// Link to the message to downvote.
+    message: String,
+    /// The starboard to vote for. Leave blank to vote for all.
+    #[command(autocomplete = true)]
+    starboard: Option<String>,
+}
+
+#[derive(CommandModel, CreateCommand)]
+#[command(name = "remove", desc = "Remove your vote on a message.")]
+pub struct VoteRemove {
+    /// Link to the message to remove your vote from.
+    message: String,
+    /// The starboard to remove your vote from. Leave blank to remove it from
+    /// all.
+    #[command(autocomplete = true)]
+    starboard: Option<String>,
+}
+
+/// Upvotes, downvotes (`Some(true)`) or removes the vote (`None`) on a
+/// message, for one or all starboards.
+async fn vote(
+    mut ctx: CommandCtx,
+    message: String,
+    starboard: Option<String>,
+    is_downvote: Option<bool>,
+) -> StarboardResult<()> {
+    let guild_id = get_guild_id!(ctx);
+    let Some(user_id) = ctx.interaction.author_id() else {
+        return Ok(());
+    };
+    let Some((channel_id, message_id)) = get_message_ref(&mut ctx, &message).await? else {
+        return Ok(());
+    };
+
+    if !ctx
+        .bot
+        .cache
+        .guild_has_channel(&ctx.bot, guild_id, channel_id)
+        .await?
+    {
+        ctx.respond_str("That message belongs to a different server.", true)
+            .await?;
+        return Ok(());
+    }
+
+    // refreshing the posts can take a while
+    ctx.defer(true).await?;
+
+    let orig = match is_downvote {
+        Some(_) => {
+            get_or_create_original(&ctx.bot, guild_id, channel_id, message_id)
+                .await?
+                .0
+        }
+        None => DbMessage::get_original(&ctx.bot.pool, message_id.get_i64()).await?,
+    };
+    let Some(orig) = orig else {
+        let msg = match is_downvote {
+            Some(_) => "I couldn't find that message.",
+            None => "You haven't voted on that message.",
+        };
+        ctx.respond_str(msg, true).await?;
+        return Ok(());
+    };
+
+    let mut configs = StarboardConfig::list_for_channel(&ctx.bot, guild_id, channel_id).await?;
+    if let Some(name) = &starboard {
+        configs.retain(|c| &c.starboard.name == name);
+    }
+    configs.retain(|c| !c.starboard.premium_locked);
+    if configs.is_empty() {
+        let msg = match starboard {
+            Some(name) => format!("Starboard '{name}' doesn't count votes in that channel."),
+            None => "No starboards count votes in that channel.".to_string(),
+        };
+        ctx.respond_str(&msg, true).await?;
+        return Ok(());
+    }
+
+    let mut response = String::new();
+    for config in configs {
+        let sb = &config.starboard;
+        let result = match is_downvote {
+            None => {
+                let removed =
+                    remove_vote(ctx.bot.clone(), guild_id, user_id, orig.clone(), sb.id).await?;
+                if removed {
+                    "removed your vote"
+                } else {
+                    "you hadn't voted"
+                }
+            }
+            Some(is_downvote) => {
+                let emojis = match is_downvote {
+                    false => &config.resolved.upvote_emojis,
+                    true => &config.resolved.downvote_emojis,
+                };
+                let Some(emoji) = emojis.first() else {
+                    let kind = if is_downvote { "downvotes" } else { "upvotes" };
+                    writeln!(response, "'{}': doesn't use {kind}", sb.name).unwrap();
+                    continue;
+                };
+                let emoji = SimpleEmoji::from_stored(emoji.clone());
+
+                let ret = cast_vote(
+                    ctx.bot.clone(),
+                    guild_id,
+                    user_id,
+                    orig.clone(),
+                    sb.id,
+                    &emoji,
+                )
+                .await?;
+                let Some(result) = describe_result(ret) else {
+                    writeln!(response, "'{}': you can't vote on this message", sb.name).unwrap();
+                    continue;
+                };
+                result
+            }
+        };
+
+        let count = DbVote::count(&ctx.bot.pool, orig.message_id, sb.id).await?;
+        writeln!(
+            response,
+            "'{}': {result} ({} points)",
+            sb.name, count.points
+        )
+        .unwrap();
+    }
+
+    ctx.respond_str(&response, true).await?;
+
+    Ok(())
+}
+
+/// What happened to the vote, or `None` if the user can't vote.
+fn describe_result(result: ManualVoteResult) -> Option<&'static str> {
+    let text = match result {
+        ManualVoteResult::Added { is_downvote: false } => "upvoted",
+        ManualVoteResult::Added { is_downvote: true } => "downvoted",
+        ManualVoteResult::Removed => "removed your vote",
+        ManualVoteResult::Unchanged => "you already voted like this",
+        ManualVoteResult::NotAllowed => return None,
+    };
+    Some(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn results() {
+        assert_eq!(
+            describe_result(ManualVoteResult::Added { is_downvote: false }),
+            Some("upvoted")
+        );
+        assert_eq!(
+            describe_result(ManualVoteResult::Added { is_downvote: true }),
+            Some("downvoted")
+        );
+        assert_eq!(
+            describe_result(ManualVoteResult::Unchanged),
+            Some("you already voted like this")
+        );
+        assert_eq!(describe_result(ManualVoteResult::NotAllowed), None);
+    }
+}
@@ -0,0 +1,42 @@
+use twilight_interactions::command::{CommandModel, CreateCommand};
+use twilight_model::http::attachment::Attachment;
+
+use crate::{
+    core::xp_transfer::export_csv,
+    errors::StarboardResult,
+    get_guild_id,
+    interactions::{
+        commands::visibility::{CommandVisibility, VisibilityPolicy},
+        context::CommandCtx,
+    },
+    utils::id_as_i64::GetI64,
+};
+
+#[derive(CommandModel, CreateCommand)]
+#[command(name = "export", desc = "Export the XP of all members as a CSV file.")]
+pub struct Export;
+
+impl CommandVisibility for Export {
+    const VISIBILITY: VisibilityPolicy = VisibilityPolicy::PRIVATE;
+}
+
+impl Export {
+    pub async fn callback(self, mut ctx: CommandCtx) -> StarboardResult<()> {
+        let guild_id = get_guild_id!(ctx);
+
+        ctx.defer(false).await?;
+
+        let csv = export_csv(&ctx.bot.pool, guild_id.get_i64()).await?;
+        let attachment = Attachment::from_bytes("xp.csv".into(), csv.into_bytes(), 0);
+
+        ctx.respond(
+            ctx.build_resp()
+                .content("Here's the XP of every member in this server.")
+                .attachments([attachment])
+                .build(),
+        )
+        .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,148 @@
+use std::fmt::Write;
+
+use twilight_interactions::command::{CommandModel, CreateCommand};
+use twilight_model::channel::Attachment;
+
+use crate::{
+    constants,
+    core::{
+        xp_transfer::{apply_import, parse_csv, plan_import, ImportMode},
+        xproles::refresh_xpr_for_members,
+    },
+    database::DbGuild,
+    errors::StarboardResult,
+    get_guild_id,
+    interactions::{commands::choices::xp_import_mode::XPImportMode, context::CommandCtx},
+    utils::{embed, id_as_i64::GetI64},
+};
+
+#[derive(CommandModel, CreateCommand)]
+#[command(
+    name = "import",
+    desc = "Import member XP from a CSV file (user_id, xp), such as from another bot."
+)]
+pub struct Import {
+    /// The CSV file to import.
+    file: Attachment,
+    /// How to combine the imported XP with the current XP.
+    mode: XPImportMode,
+    /// Preview the import without changing anything.
+    #[command(rename = "dry-run")]
+    dry_run: Option<bool>,
+}
+
+impl Import {
+    pub async fn callback(self, mut ctx: CommandCtx) -> StarboardResult<()> {
+        let guild_id = get_guild_id!(ctx);
+        let guild_id_i64 = guild_id.get_i64();
+        let dry_run = self.dry_run.unwrap_or(false);
+        let mode: ImportMode = self.mode.into();
+
+        if self.file.size > constants::MAX_XP_IMPORT_SIZE {
+            ctx.respond_str(
+                &format!(
+                    "That file is too large. The maximum size is {} MB.",
+                    constants::MAX_XP_IMPORT_SIZE / 1024 / 1024
+                ),
+                true,
+            )
+            .await?;
+            return Ok(());
+        }
+
+        ctx.defer(dry_run).await?;
+
+        let file = ctx
+            .bot
+            .reqwest
+            .get(&self.file.url)
+            .send()
+            .await?
+            .bytes()
+            .await?;
+        let parsed = match parse_csv(&String::from_utf8_lossy(&file)) {
+            Ok(parsed) => parsed,
+            Err(why) => {
+                ctx.respond_str(&why, true).await?;
+                return Ok(());
+            }
+        };
+
+        if parsed.rows.is_empty() {
+            ctx.respond_str("That file doesn't contain any valid rows.", true)
+                .await?;
+            return Ok(());
+        }
+
+        let mut changes = plan_import(&ctx.bot.pool, guild_id_i64, &parsed.rows, mode).await?;
+
+        let mut applied = true;
+        if !dry_run {
+            DbGuild::create(&ctx.bot.pool, guild_id_i64).await?;
+            applied = apply_import(&ctx.bot, guild_id_i64, &changes).await?;
+
+            tracing::info!(
+                "XP import: guild {} by user {:?}, mode {}, {} rows {}.",
+                guild_id,
+                ctx.interaction.author_id(),
+                mode.name(),
+                changes.len(),
+                if applied { "applied" } else { "cancelled" },
+            );
+
+            if applied {
+                let user_ids = changes.iter().map(|c| c.user_id).collect();
+                tokio::spawn(refresh_xpr_for_members(ctx.bot.clone(), guild_id, user_ids));
+            }
+        }
+
+        let mut description = format!(
+            "Mode: {}\nValid rows: {}\nInvalid rows: {}\n",
+            mode.name(),
+            parsed.rows.len(),
+            parsed.error_count,
+        );
+        if !applied {
+            description.push_str(
+                "**Nothing was imported because the bot is restarting. Run the import \
+                again once it's back.**\n",
+            );
+        }
+
+        changes.sort_by(|a, b| b.new.total_cmp(&a.new));
+        description.push_str("\n**Top members (current → new)**\n");
+        for change in changes.iter().take(constants::XP_IMPORT_PREVIEW) {
+            writeln!(
+                description,
+                "<@{}>: {} → {}",
+                change.user_id, change.current, change.new
+            )
+            .unwrap();
+        }
+
+        if !parsed.errors.is_empty() {
+            description.push_str("\n**Invalid rows**\n```\n");
+            for error in &parsed.errors {
+                writeln!(description, "{error}").unwrap();
+            }
+            description.push_str("```");
+            let hidden = parsed.error_count - parsed.errors.len();
+            if hidden != 0 {
+                write!(description, "...and {hidden} more.").unwrap();
+            }
+        }
+
+        let title = if dry_run {
+            "XP Import Preview"
+        } else if !applied {
+            "XP Import Cancelled"
+        } else {
+            "XP Imported"
+        };
+        let emb = embed::build().title(title).description(description).build();
+
+        ctx.respond(ctx.build_resp().embeds([emb]).build()).await?;
+
+        Ok(())
+    }
+}
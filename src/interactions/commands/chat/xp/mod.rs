@@ -0,0 +1,37 @@
+pub mod export;
+pub mod import;
+pub mod season;
+
+use twilight_interactions::command::{CommandModel, CreateCommand};
+
+use crate::{
+    errors::StarboardResult,
+    interactions::{commands::permissions::manage_guild, context::CommandCtx},
+};
+
+#[allow(clippy::large_enum_variant)] // Import(import::Import) being the culprit
+#[derive(CommandModel, CreateCommand)]
+#[command(
+    name = "xp",
+    desc = "Import, export and reset member XP.",
+    dm_permission = false,
+    default_permissions = "manage_guild"
+)]
+pub enum XP {
+    #[command(name = "import")]
+    Import(import::Import),
+    #[command(name = "export")]
+    Export(export::Export),
+    #[command(name = "season")]
+    Season(season::Season),
+}
+
+impl XP {
+    pub async fn callback(self, ctx: CommandCtx) -> StarboardResult<()> {
+        match self {
+            Self::Import(cmd) => cmd.callback(ctx).await,
+            Self::Export(cmd) => cmd.callback(ctx).await,
+            Self::Season(cmd) => cmd.callback(ctx).await,
+        }
+    }
+}
@@ -0,0 +1,24 @@
+mod new;
+mod settings;
+
+use twilight_interactions::command::{CommandModel, CreateCommand};
+
+use crate::{errors::StarboardResult, interactions::context::CommandCtx};
+
+#[derive(CommandModel, CreateCommand)]
+#[command(name = "season", desc = "Manage XP seasons.")]
+pub enum Season {
+    #[command(name = "new")]
+    New(new::New),
+    #[command(name = "settings")]
+    Settings(settings::Settings),
+}
+
+impl Season {
+    pub async fn callback(self, ctx: CommandCtx) -> StarboardResult<()> {
+        match self {
+            Self::New(cmd) => cmd.callback(ctx).await,
+            Self::Settings(cmd) => cmd.callback(ctx).await,
+        }
+    }
+}
@@ -0,0 +1,79 @@
+use twilight_interactions::command::{CommandModel, CreateCommand};
+use twilight_model::guild::Permissions;
+
+use crate::{
+    core::xp_seasons::{format_season_member, start_new_season},
+    database::DbGuild,
+    errors::StarboardResult,
+    get_guild_id,
+    interactions::context::CommandCtx,
+    utils::{id_as_i64::GetI64, views::confirm},
+};
+
+#[derive(CommandModel, CreateCommand)]
+#[command(
+    name = "new",
+    desc = "End the current XP season, archiving the leaderboard and resetting XP."
+)]
+pub struct New;
+
+impl New {
+    pub async fn callback(self, mut ctx: CommandCtx) -> StarboardResult<()> {
+        let guild_id = get_guild_id!(ctx);
+
+        let is_admin = ctx
+            .interaction
+            .member
+            .as_ref()
+            .and_then(|m| m.permissions)
+            .is_some_and(|p| p.contains(Permissions::ADMINISTRATOR));
+        if !is_admin {
+            ctx.respond_str("Only server administrators can use this command.", true)
+                .await?;
+            return Ok(());
+        }
+
+        let guild = DbGuild::get(&ctx.bot.pool, guild_id.get_i64()).await?;
+        let (season, carryover) = guild
+            .map(|g| (g.xp_season, g.xp_carryover))
+            .unwrap_or((1, 0));
+
+        let Some(mut btn_ctx) = confirm::simple(
+            &mut ctx,
+            &format!(
+                "Are you sure you want to end season {season}? Everyone's XP will be reset, \
+                keeping {carryover}% of it. The leaderboard for this season will still be \
+                available with `/leaderboard season:{season}`."
+            ),
+            true,
+        )
+        .await?
+        else {
+            return Ok(());
+        };
+
+        let closed = start_new_season(ctx.bot.clone(), guild_id).await?;
+
+        let mut msg = format!(
+            "Ended season {}. Season {} has started.",
+            closed.season,
+            closed.season + 1
+        );
+        if !closed.top.is_empty() {
+            msg.push_str("\n\nTop members:\n");
+            for (idx, member) in closed.top.iter().enumerate() {
+                msg.push_str(&format_season_member(idx + 1, member));
+                msg.push('\n');
+            }
+        }
+        if !closed.announced {
+            msg.push_str(
+                "\nThe results weren't announced. Set a channel with `/xp season settings`.",
+            );
+        }
+
+        btn_ctx.edit_str(&msg, true).await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,63 @@
+use twilight_interactions::command::{CommandModel, CreateCommand};
+use twilight_model::application::interaction::application_command::InteractionChannel;
+
+use crate::{
+    database::DbGuild, errors::StarboardResult, get_guild_id, interactions::context::CommandCtx,
+    utils::id_as_i64::GetI64,
+};
+
+#[derive(CommandModel, CreateCommand)]
+#[command(
+    name = "settings",
+    desc = "View or change the settings for XP seasons."
+)]
+pub struct Settings {
+    /// The percentage of XP members keep when a new season starts.
+    #[command(min_value = 0, max_value = 100)]
+    carryover: Option<i64>,
+    /// Where to announce the top members when a season ends.
+    #[command(channel_types = r#"
+            guild_text
+            guild_announcement
+            announcement_thread
+            public_thread
+            private_thread
+        "#)]
+    channel: Option<InteractionChannel>,
+    /// Stop announcing the top members when a season ends.
+    #[command(rename = "remove-channel")]
+    remove_channel: Option<bool>,
+}
+
+impl Settings {
+    pub async fn callback(self, mut ctx: CommandCtx) -> StarboardResult<()> {
+        let guild_id = get_guild_id!(ctx).get_i64();
+        DbGuild::create(&ctx.bot.pool, guild_id).await?;
+
+        if let Some(carryover) = self.carryover {
+            DbGuild::set_xp_carryover(&ctx.bot.pool, guild_id, carryover as i16).await?;
+        }
+        if self.remove_channel == Some(true) {
+            DbGuild::set_xp_season_channel_id(&ctx.bot.pool, guild_id, None).await?;
+        } else if let Some(channel) = &self.channel {
+            DbGuild::set_xp_season_channel_id(&ctx.bot.pool, guild_id, Some(channel.id.get_i64()))
+                .await?;
+        }
+
+        let guild = DbGuild::get(&ctx.bot.pool, guild_id).await?.unwrap();
+        let channel = match guild.xp_season_channel_id {
+            Some(channel_id) => format!("<#{channel_id}>"),
+            None => "none".to_string(),
+        };
+        ctx.respond_str(
+            &format!(
+                "Current season: {}\nCarryover: {}%\nAnnouncement channel: {channel}",
+                guild.xp_season, guild.xp_carryover
+            ),
+            true,
+        )
+        .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,9 @@
+use twilight_interactions::command::{CommandOption, CreateOption};
+
+#[derive(CommandOption, CreateOption, Clone, Copy)]
+pub enum IgnoreKind {
+    #[option(name = "Webhooks", value = 0)]
+    Webhook,
+    #[option(name = "Apps", value = 1)]
+    App,
+}
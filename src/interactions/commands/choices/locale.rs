@@ -0,0 +1,20 @@
+use twilight_interactions::command::{CommandOption, CreateOption};
+
+use crate::locale::Locale;
+
+#[derive(CommandOption, CreateOption)]
+pub enum LanguageChoice {
+    #[option(name = "English", value = 0)]
+    English,
+    #[option(name = "Español", value = 1)]
+    Spanish,
+}
+
+impl From<LanguageChoice> for Locale {
+    fn from(value: LanguageChoice) -> Self {
+        match value {
+            LanguageChoice::English => Self::English,
+            LanguageChoice::Spanish => Self::Spanish,
+        }
+    }
+}
@@ -0,0 +1,11 @@
+use twilight_interactions::command::{CommandOption, CreateOption};
+
+#[derive(CommandOption, CreateOption)]
+pub enum MediaSelection {
+    #[option(name = "First", value = 0)]
+    First,
+    #[option(name = "Largest", value = 1)]
+    Largest,
+    #[option(name = "All (Gallery)", value = 2)]
+    All,
+}
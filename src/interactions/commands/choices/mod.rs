@@ -1,3 +1,8 @@
 pub mod go_to_message;
+pub mod ignore_kind;
+pub mod locale;
+pub mod media_selection;
 pub mod on_delete;
 pub mod tribool;
+pub mod visibility;
+pub mod xp_import_mode;
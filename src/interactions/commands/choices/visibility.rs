@@ -0,0 +1,23 @@
+use twilight_interactions::command::{CommandOption, CreateOption};
+
+use crate::interactions::commands::visibility::Visibility;
+
+#[derive(CommandOption, CreateOption)]
+pub enum VisibilityChoice {
+    #[option(name = "Default", value = "default")]
+    Default,
+    #[option(name = "Public", value = "public")]
+    Public,
+    #[option(name = "Ephemeral", value = "ephemeral")]
+    Ephemeral,
+}
+
+impl VisibilityChoice {
+    pub fn as_visibility(&self) -> Option<Visibility> {
+        match self {
+            Self::Default => None,
+            Self::Public => Some(Visibility::Public),
+            Self::Ephemeral => Some(Visibility::Ephemeral),
+        }
+    }
+}
@@ -0,0 +1,23 @@
+use twilight_interactions::command::{CommandOption, CreateOption};
+
+use crate::core::xp_transfer::ImportMode;
+
+#[derive(CommandOption, CreateOption)]
+pub enum XPImportMode {
+    #[option(name = "Replace", value = 0)]
+    Replace,
+    #[option(name = "Add", value = 1)]
+    Add,
+    #[option(name = "Keep Higher", value = 2)]
+    KeepHigher,
+}
+
+impl From<XPImportMode> for ImportMode {
+    fn from(value: XPImportMode) -> Self {
+        match value {
+            XPImportMode::Replace => Self::Replace,
+            XPImportMode::Add => Self::Add,
+            XPImportMode::KeepHigher => Self::KeepHigher,
+        }
+    }
+}
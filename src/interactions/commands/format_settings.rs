@@ -13,7 +13,10 @@ use crate::{
     constants,
     core::{
         emoji::{EmojiCommon, SimpleEmoji},
+        emoji_tiers::{format_tiers, parse_tiers},
+        forum_tags::format_tags,
         starboard::config::StarboardConfig,
+        vote_weights::{format_weights, parse_weights},
     },
     database::{
         models::{filter_group::FilterGroup, starboard_filter_group::StarboardFilterGroup},
@@ -88,9 +91,22 @@ pub async fn format_settings(
     )
     .into_readable(bot, guild_id);
 
+    let display_emoji_tiers = parse_tiers(&res.display_emoji_tiers);
+    let display_emoji_tiers = if display_emoji_tiers.is_empty() {
+        "none".to_string()
+    } else {
+        format_tiers(bot, guild_id, &display_emoji_tiers)
+    };
+
     let upvote_emojis = Vec::from_stored(res.upvote_emojis.clone()).into_readable(bot, guild_id);
     let downvote_emojis =
         Vec::from_stored(res.downvote_emojis.clone()).into_readable(bot, guild_id);
+    let vote_emoji_weights = parse_weights(&res.vote_emoji_weights);
+    let vote_emoji_weights = if vote_emoji_weights.is_empty() {
+        "none".to_string()
+    } else {
+        format_weights(bot, guild_id, &vote_emoji_weights)
+    };
 
     let older_than = if res.older_than <= 0 {
         "disabled".to_string()
@@ -103,6 +119,11 @@ pub async fn format_settings(
         format_duration(Duration::from_secs(res.newer_than as u64)).to_string()
     };
 
+    let min_vote_spread = match res.min_vote_spread_seconds {
+        None => "disabled".to_string(),
+        Some(seconds) => format_duration(Duration::from_secs(seconds as u64)).to_string(),
+    };
+
     let owner: String;
     let exclusive_group = {
         match res.exclusive_group {
@@ -146,19 +167,31 @@ pub async fn format_settings(
         3 => "Mention",
         _ => "Invalid",
     };
+    let media_selection = match res.media_selection {
+        0 => "First",
+        1 => "Largest",
+        2 => "All (Gallery)",
+        _ => "Invalid",
+    };
 
     let behavior = settings!(
         enabled, "enabled", res.enabled;
         autoreact_upvote, "autoreact-upvote", res.autoreact_upvote;
         autoreact_downvote, "autoreact-downvote", res.autoreact_downvote;
         remove_invalid_reactions, "remove-invalid-reactions", res.remove_invalid_reactions;
+        lock_vote_after_mod_removal, "lock-vote-after-mod-removal", res.lock_vote_after_mod_removal;
         link_deletes, "link-deletes", res.link_deletes;
         link_edits, "link-edits", res.link_edits;
         on_delete, "on-delete", on_delete;
+        recreate_deleted_posts, "recreate-deleted-posts", res.recreate_deleted_posts;
+        attach_thread, "attach-thread", res.attach_thread;
+        reply_with_link, "reply-with-link", res.reply_with_link;
         cooldown_enabled, "cooldown-enabled", res.cooldown_enabled;
     ) + &cooldown
         + &format!("xp-multiplier: {}\n", res.xp_multiplier)
         + &format!("private: {}\n", res.private)
+        + &format!("private-votes: {}\n", res.private_votes)
+        + &format!("anonymous-votes: {}\n", res.anonymous_votes)
         + &settings!(
             exclusive_group, "exclusive-group", exclusive_group;
             exclusive_group_priority, "exclusive-group-priority", res.exclusive_group_priority;
@@ -192,15 +225,52 @@ pub async fn format_settings(
         Some(req) => req.to_string(),
         None => "unset".to_string(),
     };
+    let min_unique_voters = match res.min_unique_voters {
+        Some(min) => min.to_string(),
+        None => "unset".to_string(),
+    };
+    let max_chars = match res.max_chars {
+        Some(max) => max.to_string(),
+        None => "unset".to_string(),
+    };
+    let max_posts_per_author = match res.max_posts_per_author_per_day {
+        Some(max) => max.to_string(),
+        None => "unset".to_string(),
+    };
+
+    let format_channels = |channels: &[i64]| {
+        channels
+            .iter()
+            .map(|id| format!("<#{id}>"))
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+    let channel_whitelist = if res.channel_whitelist.is_empty() {
+        "all channels".to_string()
+    } else {
+        format_channels(&res.channel_whitelist)
+    };
+    let channel_blacklist = if res.channel_blacklist.is_empty() {
+        "none".to_string()
+    } else {
+        format_channels(&res.channel_blacklist)
+    };
+
+    let required_tags = format_tags(bot, guild_id, &res.required_tags);
+    let excluded_tags = format_tags(bot, guild_id, &res.excluded_tags);
 
     let settings = FormattedStarboardSettings {
         style: settings!(
             display_emoji, "display-emoji", display_emoji;
+            display_emoji_tiers, "emoji-tiers", display_emoji_tiers;
             ping_author, "ping-author", res.ping_author;
             use_server_profile, "use-server-profile", res.use_server_profile;
             extra_embeds, "extra-embeds", res.extra_embeds;
             go_to_message, "go-to-message", go_to_message;
             use_webhook, "use-webhook", res.use_webhook;
+            translate_button, "translate-button", res.translate_button;
+            vote_buttons, "vote-buttons", res.vote_buttons;
+            show_votes_button, "show-votes-button", res.show_votes_button;
         ),
         embed: settings!(
             color, "color", &format!(
@@ -208,18 +278,33 @@ pub async fn format_settings(
             );
             attachments_list, "attachments-list", res.attachments_list;
             replied_to, "replied-to", res.replied_to;
+            media_selection, "media-selection", media_selection;
+            extract_media_links, "extract-media-links", res.extract_media_links;
+            clone_attachments, "clone-attachments", res.clone_attachments;
         ),
         requirements: settings!(
             required, "required", required;
             required_remove, "required-remove", required_remove;
+            min_unique_voters, "min-unique-voters", min_unique_voters;
             upvote_emojis, "upvote-emojis", upvote_emojis;
             downvote_emojis, "downvote-emojis", downvote_emojis;
+            vote_emoji_weights, "vote-emoji-weights", vote_emoji_weights;
             self_vote, "self-vote", res.self_vote;
+            votes_on_post, "votes-on-post", res.votes_on_post;
             allow_bots, "allow-bots", res.allow_bots;
             require_image, "require-image", res.require_image;
+            min_chars, "min-chars", res.min_chars;
+            max_chars, "max-chars", max_chars;
+            require_link, "require-link", res.require_link;
+            allow_system_messages, "allow-system-messages", res.allow_system_messages;
             older_than, "older-than", older_than;
             newer_than, "newer-than", newer_than;
-        ),
+            min_vote_spread_seconds, "min-vote-spread", min_vote_spread;
+            max_posts_per_author_per_day, "max-posts-per-author-per-day", max_posts_per_author;
+        ) + &format!("channel-whitelist: {channel_whitelist}\n")
+            + &format!("channel-blacklist: {channel_blacklist}\n")
+            + &format!("required-tags: {required_tags}\n")
+            + &format!("excluded-tags: {excluded_tags}\n"),
         behavior,
         regex: format!(
             concat!(
@@ -1,8 +1,17 @@
+use std::time::Instant;
+
 use twilight_interactions::command::CommandModel;
+use twilight_model::{application::command::CommandType, http::attachment::Attachment};
 
 use crate::{
+    client::metrics::FailureReport,
+    constants,
     errors::StarboardResult,
-    interactions::{commands::chat, context::CommandCtx},
+    interactions::{
+        commands::{chat, message, visibility},
+        context::CommandCtx,
+    },
+    utils::{id_as_i64::GetI64, into_id::IntoId},
 };
 
 macro_rules! match_commands {
@@ -12,17 +21,80 @@ macro_rules! match_commands {
             $(
                 $cmd_name => <$command>::from_interaction(cmd_inp_data)?.callback($ctx).await?,
             )*
-            unknown => eprintln!("Unknown command: {}", unknown),
+            unknown => tracing::warn!("Unknown command: {}", unknown),
         }
     };
 }
 
+/// Runs the command, recording its latency and whether it failed. Once a
+/// command fails often, a single warning is posted to the error channel
+/// instead of every failure.
 pub async fn handle_command(ctx: CommandCtx) -> StarboardResult<()> {
+    let bot = ctx.bot.clone();
+    let command = visibility::qualified_name(&ctx.data);
+
+    let start = Instant::now();
+    let ret = run_command(ctx, &command).await;
+    let report = bot
+        .metrics
+        .record_command(&command, start.elapsed(), ret.is_err());
+
+    let Err(err) = ret else {
+        return Ok(());
+    };
+    match report {
+        Some(FailureReport::Failing { failures, calls }) => {
+            bot.log_error(&err);
+            let Some(error_channel) = bot.config.error_channel else {
+                return Ok(());
+            };
+            let msg = format!(
+                "`/{command}` is failing: {failures} of {calls} calls failed in the last {} \
+                minutes. Further failures won't be posted for {} minutes. Latest error:",
+                constants::COMMAND_ERROR_WINDOW.as_secs() / 60,
+                constants::COMMAND_ERROR_SUPPRESSION.as_secs() / 60,
+            );
+            let error = format!("{err:#?}");
+            bot.http
+                .create_message(error_channel.into_id())
+                .content(&msg)?
+                .attachments(&[Attachment::from_bytes(
+                    "error.rs".into(),
+                    error.into_bytes(),
+                    1,
+                )])?
+                .await?;
+            Ok(())
+        }
+        Some(FailureReport::Suppressed) => {
+            bot.log_error(&err);
+            Ok(())
+        }
+        Some(FailureReport::Single) | None => Err(err),
+    }
+}
+
+async fn run_command(mut ctx: CommandCtx, command: &str) -> StarboardResult<()> {
+    if ctx.data.kind == CommandType::Message {
+        return message::handle_message_command(ctx).await;
+    }
+
+    let visibility = visibility::command_visibility(
+        &ctx.bot,
+        ctx.interaction.guild_id.map(|id| id.get_i64()),
+        command,
+    )
+    .await?;
+    ctx.set_visibility(visibility);
+
     match_commands!(
         ctx,
         "ping" => chat::ping::Ping,
         "help" => chat::help::Help,
+        "language" => chat::language::Language,
+        "competition" => chat::competition::Competition,
         "botstats" => chat::botstats::BotStats,
+        "botinfo" => chat::botinfo::BotInfo,
         "leaderboard" => chat::leaderboard::Leaderboard,
         "stats" => chat::stats::Stats,
         "random" => chat::random::RandomPost,
@@ -31,11 +103,15 @@ pub async fn handle_command(ctx: CommandCtx) -> StarboardResult<()> {
         "starboards" => chat::starboard::Starboard,
         "overrides" => chat::overrides::Overrides,
         "exclusive-groups" => chat::exclusive_groups::ExclusiveGroups,
+        "blocklist" => chat::blocklist::Blocklist,
         "permroles" => chat::permroles::PermRoles,
         "filters" => chat::filters::Filters,
         "xproles" => chat::xproles::XPRoles,
         "posroles" => chat::posroles::PosRoles,
         "utils" => chat::utils::Utils,
+        "xp" => chat::xp::XP,
+        "settings" => chat::settings::Settings,
+        "vote" => chat::vote::Vote,
     );
 
     Ok(())
@@ -0,0 +1,28 @@
+//! Message context menu commands ("Apps" when right-clicking a message).
+
+pub mod mod_notes;
+pub mod vote_locks;
+
+use twilight_model::application::command::Command;
+
+use crate::{errors::StarboardResult, interactions::context::CommandCtx};
+
+pub fn commands() -> Vec<Command> {
+    vec![
+        mod_notes::add_note_command(),
+        mod_notes::view_notes_command(),
+        vote_locks::lock_vote_command(),
+    ]
+}
+
+pub async fn handle_message_command(ctx: CommandCtx) -> StarboardResult<()> {
+    match &*ctx.data.name {
+        mod_notes::ADD_NOTE => mod_notes::add_note(ctx).await,
+        mod_notes::VIEW_NOTES => mod_notes::view_notes(ctx).await,
+        vote_locks::LOCK_VOTE => vote_locks::lock_vote(ctx).await,
+        unknown => {
+            tracing::warn!("Unknown message command: {}", unknown);
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,130 @@
+use twilight_model::{
+    application::command::{Command, CommandType},
+    channel::message::{
+        component::{ActionRow, TextInput, TextInputStyle},
+        Component,
+    },
+    guild::Permissions,
+};
+use twilight_util::builder::command::CommandBuilder;
+
+use crate::{
+    constants,
+    core::mod_notes::{format_note, original_message_id},
+    database::{validation::mod_note::validate_mod_note_count, ModNote},
+    errors::StarboardResult,
+    get_guild_id,
+    interactions::{context::CommandCtx, custom_id::CustomId},
+    utils::{id_as_i64::GetI64, views::paginator},
+};
+
+pub const ADD_NOTE: &str = "Add mod note";
+pub const VIEW_NOTES: &str = "View notes";
+
+pub fn add_note_command() -> Command {
+    CommandBuilder::new(ADD_NOTE, "", CommandType::Message)
+        .default_member_permissions(Permissions::MANAGE_MESSAGES)
+        .dm_permission(false)
+        .build()
+}
+
+pub fn view_notes_command() -> Command {
+    CommandBuilder::new(VIEW_NOTES, "", CommandType::Message)
+        .default_member_permissions(Permissions::MANAGE_MESSAGES)
+        .dm_permission(false)
+        .build()
+}
+
+/// Whether the user can manage messages. Checked again here, since guilds can
+/// change who can use the commands.
+pub fn can_manage_notes(member_permissions: Option<Permissions>) -> bool {
+    member_permissions.is_some_and(|p| p.contains(Permissions::MANAGE_MESSAGES))
+}
+
+pub async fn add_note(mut ctx: CommandCtx) -> StarboardResult<()> {
+    get_guild_id!(ctx);
+    let Some(message_id) = ctx.data.target_id else {
+        return Ok(());
+    };
+
+    let permissions = ctx.interaction.member.as_ref().and_then(|m| m.permissions);
+    if !can_manage_notes(permissions) {
+        ctx.respond_str(
+            "You need the Manage Messages permission to add notes.",
+            true,
+        )
+        .await?;
+        return Ok(());
+    }
+
+    let message_id = original_message_id(&ctx.bot.pool, message_id.get_i64()).await?;
+    let count = ModNote::count_by_message(&ctx.bot.pool, message_id).await?;
+    if let Err(why) = validate_mod_note_count(count) {
+        ctx.respond_str(&why, true).await?;
+        return Ok(());
+    }
+
+    let input = Component::TextInput(TextInput {
+        custom_id: "note".to_string(),
+        label: "Note".to_string(),
+        max_length: Some(constants::MAX_MOD_NOTE_LEN),
+        min_length: Some(1),
+        placeholder: Some("Only moderators can see this note.".to_string()),
+        required: Some(true),
+        style: TextInputStyle::Paragraph,
+        value: None,
+    });
+    ctx.respond_modal(
+        CustomId::ModNote { message_id }.encode(),
+        "Add mod note".to_string(),
+        vec![Component::ActionRow(ActionRow {
+            components: vec![input],
+        })],
+    )
+    .await?;
+
+    Ok(())
+}
+
+pub async fn view_notes(mut ctx: CommandCtx) -> StarboardResult<()> {
+    let guild_id = get_guild_id!(ctx).get_i64();
+    let Some(message_id) = ctx.data.target_id else {
+        return Ok(());
+    };
+
+    let permissions = ctx.interaction.member.as_ref().and_then(|m| m.permissions);
+    if !can_manage_notes(permissions) {
+        ctx.respond_str(
+            "You need the Manage Messages permission to view notes.",
+            true,
+        )
+        .await?;
+        return Ok(());
+    }
+
+    let message_id = original_message_id(&ctx.bot.pool, message_id.get_i64()).await?;
+    let notes: Vec<_> = ModNote::list_by_message(&ctx.bot.pool, message_id)
+        .await?
+        .into_iter()
+        .filter(|note| note.guild_id == guild_id)
+        .collect();
+    if notes.is_empty() {
+        ctx.respond_str("This message has no notes.", true).await?;
+        return Ok(());
+    }
+
+    let pages = notes
+        .chunks(constants::MOD_NOTES_PER_PAGE)
+        .map(|chunk| {
+            let text = chunk
+                .iter()
+                .map(format_note)
+                .collect::<Vec<_>>()
+                .join("\n\n");
+            (Some(text), None)
+        })
+        .collect();
+
+    let user_id = ctx.interaction.author_id().unwrap();
+    paginator::simple(&mut ctx, pages, user_id, true).await
+}
@@ -0,0 +1,87 @@
+use twilight_model::{
+    application::command::{Command, CommandType},
+    channel::message::{
+        component::{ActionRow, TextInput, TextInputStyle},
+        Component,
+    },
+    guild::Permissions,
+};
+use twilight_util::builder::command::CommandBuilder;
+
+use crate::{
+    core::mod_notes::original_message_id,
+    errors::StarboardResult,
+    get_guild_id,
+    interactions::{context::CommandCtx, custom_id::CustomId},
+    utils::id_as_i64::GetI64,
+};
+
+pub const LOCK_VOTE: &str = "Remove & lock vote";
+
+pub fn lock_vote_command() -> Command {
+    CommandBuilder::new(LOCK_VOTE, "", CommandType::Message)
+        .default_member_permissions(Permissions::MANAGE_MESSAGES)
+        .dm_permission(false)
+        .build()
+}
+
+/// Whether the user can lock votes. Checked again here, since guilds can
+/// change who can use the command.
+pub fn can_lock_votes(member_permissions: Option<Permissions>) -> bool {
+    member_permissions.is_some_and(|p| p.contains(Permissions::MANAGE_MESSAGES))
+}
+
+pub async fn lock_vote(mut ctx: CommandCtx) -> StarboardResult<()> {
+    get_guild_id!(ctx);
+    let Some(message_id) = ctx.data.target_id else {
+        return Ok(());
+    };
+
+    let permissions = ctx.interaction.member.as_ref().and_then(|m| m.permissions);
+    if !can_lock_votes(permissions) {
+        ctx.respond_str(
+            "You need the Manage Messages permission to lock votes.",
+            true,
+        )
+        .await?;
+        return Ok(());
+    }
+
+    let message_id = original_message_id(&ctx.bot.pool, message_id.get_i64()).await?;
+
+    let input = Component::TextInput(TextInput {
+        custom_id: "user".to_string(),
+        label: "User ID or mention".to_string(),
+        max_length: Some(32),
+        min_length: Some(1),
+        placeholder: Some("The user whose vote to remove and lock.".to_string()),
+        required: Some(true),
+        style: TextInputStyle::Short,
+        value: None,
+    });
+    ctx.respond_modal(
+        CustomId::VoteLock { message_id }.encode(),
+        "Remove & lock vote".to_string(),
+        vec![Component::ActionRow(ActionRow {
+            components: vec![input],
+        })],
+    )
+    .await?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn needs_manage_messages() {
+        assert!(can_lock_votes(Some(Permissions::MANAGE_MESSAGES)));
+        assert!(can_lock_votes(Some(
+            Permissions::MANAGE_MESSAGES | Permissions::SEND_MESSAGES
+        )));
+        assert!(!can_lock_votes(Some(Permissions::SEND_MESSAGES)));
+        assert!(!can_lock_votes(None));
+    }
+}
@@ -3,5 +3,7 @@ mod choices;
 mod deleted_roles;
 mod format_settings;
 pub mod handle;
+pub mod message;
 pub mod permissions;
 pub mod register;
+pub mod visibility;
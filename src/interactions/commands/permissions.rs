@@ -15,3 +15,7 @@ pub fn manage_roles_channels() -> Permissions {
 pub fn manage_messages() -> Permissions {
     Permissions::MANAGE_MESSAGES
 }
+
+pub fn manage_guild() -> Permissions {
+    Permissions::MANAGE_GUILD
+}
@@ -1,8 +1,16 @@
 use std::sync::Arc;
 
 use twilight_interactions::command::CreateCommand;
+use twilight_model::{
+    application::command::Command,
+    id::{marker::GuildMarker, Id},
+};
 
-use crate::{client::bot::StarboardBot, interactions::commands::chat};
+use crate::{
+    client::bot::StarboardBot,
+    errors::StarboardResult,
+    interactions::commands::{chat, message},
+};
 
 macro_rules! commands_to_create {
     ($( $command: ty ),* $(,)? ) => {
@@ -14,13 +22,14 @@ macro_rules! commands_to_create {
     };
 }
 
-pub async fn post_commands(bot: Arc<StarboardBot>) {
-    let inter_client = bot.interaction_client().await;
-
-    let commands = commands_to_create!(
+pub fn commands() -> Vec<Command> {
+    let mut commands = commands_to_create!(
         chat::ping::Ping,
         chat::help::Help,
+        chat::language::Language,
+        chat::competition::Competition,
         chat::botstats::BotStats,
+        chat::botinfo::BotInfo,
         chat::leaderboard::Leaderboard,
         chat::stats::Stats,
         chat::random::RandomPost,
@@ -29,15 +38,42 @@ pub async fn post_commands(bot: Arc<StarboardBot>) {
         chat::starboard::Starboard,
         chat::overrides::Overrides,
         chat::exclusive_groups::ExclusiveGroups,
+        chat::blocklist::Blocklist,
         chat::permroles::PermRoles,
         chat::filters::Filters,
         chat::xproles::XPRoles,
         chat::posroles::PosRoles,
         chat::utils::Utils,
+        chat::xp::XP,
+        chat::settings::Settings,
+        chat::vote::Vote,
     );
+    commands.extend(message::commands());
+    commands
+}
+
+/// Replaces the registered commands with `commands()`, globally or only in
+/// one guild. Discord only applies the differences. Returns the number of
+/// commands registered.
+pub async fn sync_commands(
+    bot: &StarboardBot,
+    guild_id: Option<Id<GuildMarker>>,
+) -> StarboardResult<usize> {
+    let inter_client = bot.interaction_client().await;
 
-    match inter_client.set_global_commands(&commands).await {
-        Ok(_) => println!("Successfully registered commands"),
-        Err(e) => eprintln!("Failed to register commands: {e}"),
+    let commands = commands();
+
+    match guild_id {
+        None => inter_client.set_global_commands(&commands).await?,
+        Some(guild_id) => inter_client.set_guild_commands(guild_id, &commands).await?,
+    };
+
+    Ok(commands.len())
+}
+
+pub async fn post_commands(bot: Arc<StarboardBot>) {
+    match sync_commands(&bot, None).await {
+        Ok(_) => tracing::info!("Successfully registered commands"),
+        Err(e) => tracing::error!("Failed to register commands: {e}"),
     }
 }
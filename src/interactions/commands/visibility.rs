@@ -0,0 +1,294 @@
+//! Whether a command's response is public or ephemeral. Every command has a
+//! default, which guilds can override per command with `/utils visibility`.
+
+use std::collections::HashMap;
+
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+use twilight_interactions::command::CreateCommand;
+use twilight_model::application::{
+    command::{Command, CommandOption, CommandOptionType},
+    interaction::application_command::{CommandData, CommandDataOption, CommandOptionValue},
+};
+
+use crate::{
+    client::bot::StarboardBot,
+    database::DbGuild,
+    errors::StarboardResult,
+    interactions::commands::{
+        chat::{
+            blocklist::{self, Blocklist},
+            posroles::{self, PosRoles},
+            premium::{self, Premium},
+            premium_locks::{self, PremiumLocks},
+            utils::{self, Utils},
+            xp::{self, XP},
+        },
+        register::commands,
+    },
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Visibility {
+    Public,
+    Ephemeral,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VisibilityPolicy {
+    pub default: Visibility,
+    /// Commands whose output is sensitive (vote breakdowns, account details)
+    /// ignore guild overrides.
+    pub overridable: bool,
+}
+
+impl VisibilityPolicy {
+    pub const PUBLIC: Self = Self {
+        default: Visibility::Public,
+        overridable: true,
+    };
+    pub const EPHEMERAL: Self = Self {
+        default: Visibility::Ephemeral,
+        overridable: true,
+    };
+    /// Always ephemeral, whatever the guild's overrides say.
+    pub const PRIVATE: Self = Self {
+        default: Visibility::Ephemeral,
+        overridable: false,
+    };
+}
+
+/// Implemented by commands that don't use `VisibilityPolicy::PUBLIC`. The
+/// policy applies to the command's subcommands too, unless they have their
+/// own. Commands implementing this must also be listed in `POLICIES`.
+pub trait CommandVisibility: CreateCommand {
+    const VISIBILITY: VisibilityPolicy;
+}
+
+/// The qualified name and policy of a command, given the path of command
+/// types leading to it.
+macro_rules! policy {
+    ($command:ty) => {
+        (
+            <$command as CreateCommand>::NAME.to_string(),
+            <$command as CommandVisibility>::VISIBILITY,
+        )
+    };
+    ($parent:ty, $($rest:ty),+) => {{
+        let (name, policy) = policy!($($rest),+);
+        (format!("{} {name}", <$parent as CreateCommand>::NAME), policy)
+    }};
+}
+
+lazy_static! {
+    static ref POLICIES: HashMap<String, VisibilityPolicy> = HashMap::from([
+        policy!(Utils, utils::info::Info),
+        policy!(Utils, utils::refresh::Refresh),
+        policy!(Utils, utils::recount::Recount),
+        policy!(Utils, utils::migrate::Migrate),
+        policy!(Utils, utils::freeze::Freeze),
+        policy!(Utils, utils::freeze::UnFreeze),
+        policy!(Utils, utils::force::Force),
+        policy!(Utils, utils::unforce::UnForce),
+        policy!(Utils, utils::trash::Trash),
+        policy!(Utils, utils::trash::UnTrash),
+        policy!(Utils, utils::mod_notes::DeleteModNote),
+        policy!(Utils, utils::simulate::Simulate),
+        policy!(Blocklist, blocklist::add::Add),
+        policy!(Blocklist, blocklist::remove::Remove),
+        policy!(Blocklist, blocklist::list::List),
+        policy!(PosRoles, posroles::refresh::Refresh),
+        policy!(Premium, premium::info::Info),
+        policy!(Premium, premium::autoredeem::Autoredeem),
+        policy!(PremiumLocks, premium_locks::refresh::Refresh),
+        policy!(PremiumLocks, premium_locks::move_autostar::MoveAutostar),
+        policy!(PremiumLocks, premium_locks::move_starboard::MoveStarboard),
+        policy!(XP, xp::export::Export),
+    ]);
+}
+
+/// The policy for a command, by its qualified name (e.g. "utils recount").
+/// Subcommands inherit the policy of their closest parent that has one.
+pub fn policy(command: &str) -> VisibilityPolicy {
+    let mut name = command;
+    loop {
+        if let Some(policy) = POLICIES.get(name) {
+            return *policy;
+        }
+        match name.rsplit_once(' ') {
+            Some((parent, _)) => name = parent,
+            None => return VisibilityPolicy::PUBLIC,
+        }
+    }
+}
+
+/// The visibility a command responds with. An override on a parent command
+/// (e.g. "utils") applies to its subcommands unless they have their own.
+pub fn resolve(command: &str, overrides: &HashMap<String, Visibility>) -> Visibility {
+    let policy = policy(command);
+    if !policy.overridable {
+        return policy.default;
+    }
+
+    let mut name = command;
+    loop {
+        if let Some(visibility) = overrides.get(name) {
+            return *visibility;
+        }
+        match name.rsplit_once(' ') {
+            Some((parent, _)) => name = parent,
+            None => return policy.default,
+        }
+    }
+}
+
+/// The command name including any subcommand groups and subcommands.
+pub fn qualified_name(data: &CommandData) -> String {
+    fn walk(name: &mut String, options: &[CommandDataOption]) {
+        for option in options {
+            if let CommandOptionValue::SubCommand(inner)
+            | CommandOptionValue::SubCommandGroup(inner) = &option.value
+            {
+                name.push(' ');
+                name.push_str(&option.name);
+                walk(name, inner);
+            }
+        }
+    }
+
+    let mut name = data.name.clone();
+    walk(&mut name, &data.options);
+    name
+}
+
+/// Every qualified command name, including parent commands.
+pub fn all_command_names() -> Vec<String> {
+    fn walk(names: &mut Vec<String>, prefix: &str, options: &[CommandOption]) {
+        for option in options {
+            if !matches!(
+                option.kind,
+                CommandOptionType::SubCommand | CommandOptionType::SubCommandGroup
+            ) {
+                continue;
+            }
+            let name = format!("{prefix} {}", option.name);
+            if let Some(inner) = &option.options {
+                walk(names, &name, inner);
+            }
+            names.push(name);
+        }
+    }
+
+    let mut names = Vec::new();
+    for Command { name, options, .. } in commands() {
+        walk(&mut names, &name, &options);
+        names.push(name);
+    }
+    names
+}
+
+pub fn parse_overrides(value: &serde_json::Value) -> HashMap<String, Visibility> {
+    serde_json::from_value(value.clone()).unwrap_or_default()
+}
+
+/// The visibility of a command's responses in this guild.
+pub async fn command_visibility(
+    bot: &StarboardBot,
+    guild_id: Option<i64>,
+    command: &str,
+) -> StarboardResult<Visibility> {
+    let Some(guild_id) = guild_id else {
+        return Ok(policy(command).default);
+    };
+
+    let cached = bot
+        .cache
+        .guild_command_visibility
+        .with(&guild_id, |_, overrides| {
+            overrides.as_ref().map(|o| resolve(command, o.value()))
+        });
+    let visibility = match cached {
+        Some(visibility) => visibility,
+        None => {
+            let overrides = DbGuild::get(&bot.pool, guild_id)
+                .await?
+                .map(|guild| parse_overrides(&guild.command_visibility_overrides))
+                .unwrap_or_default();
+            let visibility = resolve(command, &overrides);
+            bot.cache
+                .guild_command_visibility
+                .insert(guild_id, overrides);
+            visibility
+        }
+    };
+
+    Ok(visibility)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn overrides(entries: &[(&str, Visibility)]) -> HashMap<String, Visibility> {
+        entries
+            .iter()
+            .map(|(name, visibility)| (name.to_string(), *visibility))
+            .collect()
+    }
+
+    #[test]
+    fn policy_from_command_type() {
+        assert_eq!(policy("utils recount"), VisibilityPolicy::EPHEMERAL);
+        assert_eq!(policy("xp export"), VisibilityPolicy::PRIVATE);
+        assert_eq!(policy("ping"), VisibilityPolicy::PUBLIC);
+        // subcommands inherit their group's policy
+        assert_eq!(policy("utils simulate vote"), VisibilityPolicy::PRIVATE);
+        assert_eq!(
+            policy("premium autoredeem enable"),
+            VisibilityPolicy::EPHEMERAL
+        );
+    }
+
+    #[test]
+    fn resolve_default() {
+        let none = overrides(&[]);
+        assert_eq!(resolve("utils recount", &none), Visibility::Ephemeral);
+        assert_eq!(resolve("leaderboard", &none), Visibility::Public);
+    }
+
+    #[test]
+    fn resolve_precedence() {
+        let o = overrides(&[
+            ("utils", Visibility::Public),
+            ("utils recount", Visibility::Ephemeral),
+            ("leaderboard", Visibility::Ephemeral),
+        ]);
+
+        // the most specific override wins
+        assert_eq!(resolve("utils recount", &o), Visibility::Ephemeral);
+        // then the parent's, over the command's default
+        assert_eq!(resolve("utils refresh", &o), Visibility::Public);
+        assert_eq!(resolve("leaderboard", &o), Visibility::Ephemeral);
+        // overrides on other commands don't leak
+        assert_eq!(resolve("posroles refresh", &o), Visibility::Ephemeral);
+        assert_eq!(resolve("stats", &o), Visibility::Public);
+    }
+
+    #[test]
+    fn resolve_not_overridable() {
+        let o = overrides(&[
+            ("utils", Visibility::Public),
+            ("utils simulate", Visibility::Public),
+            ("utils simulate vote", Visibility::Public),
+            ("xp", Visibility::Public),
+            ("xp export", Visibility::Public),
+        ]);
+
+        assert_eq!(resolve("utils simulate vote", &o), Visibility::Ephemeral);
+        assert_eq!(resolve("xp export", &o), Visibility::Ephemeral);
+        // siblings still take the overrides
+        assert_eq!(resolve("utils recount", &o), Visibility::Public);
+        assert_eq!(resolve("xp import", &o), Visibility::Public);
+    }
+}
@@ -1,14 +1,55 @@
-use crate::{errors::StarboardResult, interactions::context::ComponentCtx};
+use crate::{
+    errors::StarboardResult,
+    interactions::{context::ComponentCtx, custom_id::CustomId},
+};
 
-use super::dismiss::handle_dismiss;
+use super::{
+    dismiss::handle_dismiss,
+    inspect::handle_inspect_fix,
+    translate::handle_translate,
+    vote::{handle_vote, handle_vote_select},
+    votes::handle_votes,
+};
 
 pub async fn handle_component(ctx: ComponentCtx) -> StarboardResult<()> {
-    let Some(id) = ctx.data.custom_id.strip_prefix("stateless::") else { return Ok(()); };
-
-    match id {
-        "dismiss_notification" => handle_dismiss(&ctx).await?,
-        _ => unreachable!("Unhandled stateless component: {id}"),
+    match CustomId::decode(&ctx.data.custom_id) {
+        Ok(CustomId::DismissNotification) => handle_dismiss(&ctx).await?,
+        Ok(CustomId::Translate) => handle_translate(ctx).await?,
+        Ok(CustomId::InspectFix {
+            fix,
+            channel_id,
+            message_id,
+        }) => handle_inspect_fix(ctx, fix, channel_id, message_id).await?,
+        Ok(CustomId::Vote { emoji }) => handle_vote(ctx, emoji).await?,
+        Ok(CustomId::VoteSelect) => handle_vote_select(ctx).await?,
+        Ok(CustomId::Votes {
+            message_id,
+            starboard_id,
+        }) => handle_votes(ctx, message_id, starboard_id).await?,
+        // views handle their own buttons, so nothing is waiting for this one
+        Ok(_) | Err(_) => handle_expired(ctx).await?,
     }
 
     Ok(())
 }
+
+/// Answers clicks on controls that aren't handled anymore, for example the
+/// buttons of a paginator from before a restart.
+async fn handle_expired(mut ctx: ComponentCtx) -> StarboardResult<()> {
+    let owner_id = ctx
+        .interaction
+        .message
+        .as_ref()
+        .and_then(|msg| msg.interaction.as_ref())
+        .map(|int| int.user.id);
+    let is_owner = owner_id.is_none() || owner_id == ctx.interaction.author_id();
+
+    let msg = if is_owner {
+        "This control expired. Run the command again."
+    } else {
+        "Only the person who ran the command can use this."
+    };
+    ctx.respond_str(msg, true).await?;
+
+    Ok(())
+}
@@ -0,0 +1,74 @@
+use crate::{
+    core::{
+        inspect::{refresh_message, unlink_missing_posts, InspectFix},
+        starboard::recount::{recount_votes, RecountResult},
+    },
+    database::DbMessage,
+    errors::StarboardResult,
+    interactions::context::ComponentCtx,
+    utils::into_id::IntoId,
+};
+
+/// Runs a fix suggested by `star inspect`.
+pub async fn handle_inspect_fix(
+    mut ctx: ComponentCtx,
+    fix: InspectFix,
+    channel_id: i64,
+    message_id: i64,
+) -> StarboardResult<()> {
+    let Some(user_id) = ctx.interaction.author_id() else {
+        return Ok(());
+    };
+    if !ctx.bot.config.owner_ids.contains(&user_id.get()) {
+        ctx.respond_str("Only the bot owners can use this.", true)
+            .await?;
+        return Ok(());
+    }
+    if let Some(retry) = ctx.bot.cooldowns.inspect.trigger(&user_id) {
+        let msg = format!(
+            "You're inspecting too quickly. Try again in {} seconds.",
+            retry.as_secs().max(1)
+        );
+        ctx.respond_str(&msg, true).await?;
+        return Ok(());
+    }
+
+    let Some(orig) = DbMessage::get(&ctx.bot.pool, message_id).await? else {
+        ctx.respond_str("That message isn't in the database.", true)
+            .await?;
+        return Ok(());
+    };
+
+    ctx.defer(true).await?;
+
+    let msg = match fix {
+        InspectFix::Recount => {
+            let ret = recount_votes(
+                ctx.bot.clone(),
+                orig.guild_id.into_id(),
+                channel_id.into_id(),
+                message_id.into_id(),
+            )
+            .await?;
+            match ret {
+                RecountResult::Done(_) => "Recounted the votes.".to_string(),
+                RecountResult::Cooldown(_) => "That channel was recounted recently.".to_string(),
+                RecountResult::AlreadyRecounting => "Already recounting that message.".to_string(),
+                RecountResult::UnkownMessage => "Couldn't find that message.".to_string(),
+            }
+        }
+        InspectFix::Refresh => {
+            refresh_message(ctx.bot.clone(), orig).await?;
+            "Refreshed the message.".to_string()
+        }
+        InspectFix::Relink => {
+            let unlinked = unlink_missing_posts(&ctx.bot, &orig).await?;
+            refresh_message(ctx.bot.clone(), orig).await?;
+            format!("Forgot {unlinked} deleted posts and refreshed the message.")
+        }
+    };
+
+    ctx.respond_str(&msg, true).await?;
+
+    Ok(())
+}
@@ -2,3 +2,7 @@
 
 pub mod dismiss;
 pub mod handle;
+pub mod inspect;
+pub mod translate;
+pub mod vote;
+pub mod votes;
@@ -0,0 +1,75 @@
+use crate::{
+    core::translate::{target_language, translate_and_cache, truncate_for_translation},
+    database::DbMessage,
+    errors::StarboardResult,
+    interactions::context::ComponentCtx,
+    utils::{id_as_i64::GetI64, into_id::IntoId},
+};
+
+pub async fn handle_translate(mut ctx: ComponentCtx) -> StarboardResult<()> {
+    let Some(user_id) = ctx.interaction.author_id() else {
+        return Ok(());
+    };
+    let post = ctx.interaction.message.as_ref().unwrap();
+
+    if ctx.bot.cooldowns.translate.trigger(&user_id).is_some() {
+        let msg = ctx.t("errors.cooldown", &[]).await?;
+        ctx.respond_str(&msg, true).await?;
+        return Ok(());
+    }
+
+    let Some(orig) = DbMessage::get_original(&ctx.bot.pool, post.id.get_i64()).await? else {
+        ctx.respond_str("I couldn't find the original message.", true)
+            .await?;
+        return Ok(());
+    };
+
+    let locale = ctx.interaction.locale.as_deref().unwrap_or("en-US");
+    let target = target_language(locale);
+    let cache_key = (orig.message_id.into_id(), target.clone());
+
+    let translated = match ctx.bot.cache.translations.get(&cache_key) {
+        Some(translated) => translated,
+        None => {
+            let message = ctx
+                .bot
+                .cache
+                .fog_message(&ctx.bot, orig.channel_id.into_id(), cache_key.0)
+                .await?
+                .into_option();
+            let content = match &message {
+                Some(msg) if !msg.content.trim().is_empty() => {
+                    truncate_for_translation(&msg.content)
+                }
+                _ => {
+                    ctx.respond_str("There's nothing to translate.", true)
+                        .await?;
+                    return Ok(());
+                }
+            };
+
+            ctx.defer(true).await?;
+
+            let translated = translate_and_cache(
+                &*ctx.bot.translator,
+                &ctx.bot.cache.translations,
+                cache_key.0,
+                target,
+                &content,
+            )
+            .await;
+            match translated {
+                Ok(translated) => translated,
+                Err(why) => {
+                    ctx.respond_str(&why, true).await?;
+                    return Ok(());
+                }
+            }
+        }
+    };
+
+    ctx.respond_str(&truncate_for_translation(&translated), true)
+        .await?;
+
+    Ok(())
+}
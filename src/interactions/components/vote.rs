@@ -0,0 +1,68 @@
+use crate::{
+    core::{
+        emoji::{EmojiCommon, SimpleEmoji},
+        starboard::{
+            manual_vote::{cast_vote, toggle_vote, ManualVoteResult},
+            message::get_post_original,
+        },
+    },
+    database::DbMessage,
+    errors::StarboardResult,
+    get_guild_id,
+    interactions::context::ComponentCtx,
+};
+
+/// A vote button, which toggles the vote with its emoji.
+pub async fn handle_vote(ctx: ComponentCtx, emoji: String) -> StarboardResult<()> {
+    vote_on_post(ctx, emoji, true).await
+}
+
+/// The vote select menu. Choosing an emoji casts a vote with it, like adding
+/// its reaction, and never removes the vote.
+pub async fn handle_vote_select(ctx: ComponentCtx) -> StarboardResult<()> {
+    let Some(emoji) = ctx.data.values.first().cloned() else {
+        return Ok(());
+    };
+    vote_on_post(ctx, emoji, false).await
+}
+
+async fn vote_on_post(mut ctx: ComponentCtx, emoji: String, toggle: bool) -> StarboardResult<()> {
+    let guild_id = get_guild_id!(ctx);
+    let Some(user_id) = ctx.interaction.author_id() else {
+        return Ok(());
+    };
+    let post = ctx.interaction.message.as_ref().unwrap();
+
+    let Some((orig_id, starboard_id)) = get_post_original(&ctx.bot, post.id).await? else {
+        ctx.respond_str("I couldn't find the original message.", true)
+            .await?;
+        return Ok(());
+    };
+    let Some(orig) = DbMessage::get(&ctx.bot.pool, orig_id).await? else {
+        ctx.respond_str("I couldn't find the original message.", true)
+            .await?;
+        return Ok(());
+    };
+
+    // refreshing the post can take a while
+    ctx.defer(true).await?;
+
+    let emoji = SimpleEmoji::from_stored(emoji);
+    let bot = ctx.bot.clone();
+    let ret = if toggle {
+        toggle_vote(bot, guild_id, user_id, orig, starboard_id, &emoji).await?
+    } else {
+        cast_vote(bot, guild_id, user_id, orig, starboard_id, &emoji).await?
+    };
+
+    let msg = match ret {
+        ManualVoteResult::Added { is_downvote: false } => "Upvoted.",
+        ManualVoteResult::Added { is_downvote: true } => "Downvoted.",
+        ManualVoteResult::Removed => "Removed your vote.",
+        ManualVoteResult::Unchanged => "You already voted like this.",
+        ManualVoteResult::NotAllowed => "You can't vote on this message.",
+    };
+    ctx.respond_str(msg, true).await?;
+
+    Ok(())
+}
@@ -0,0 +1,175 @@
+use crate::{
+    constants,
+    core::starboard::{config::StarboardConfig, message::get_post_original},
+    database::{DbMessage, Vote},
+    errors::StarboardResult,
+    get_guild_id,
+    interactions::context::ComponentCtx,
+    utils::{embed, into_id::IntoId, views::paginator},
+};
+
+pub async fn handle_votes(
+    mut ctx: ComponentCtx,
+    message_id: i64,
+    starboard_id: i32,
+) -> StarboardResult<()> {
+    let guild_id = get_guild_id!(ctx);
+    let Some(user_id) = ctx.interaction.author_id() else {
+        return Ok(());
+    };
+    let post = ctx.interaction.message.as_ref().unwrap();
+
+    // anyone can send a button with any id, so check that it's this post's
+    let original = get_post_original(&ctx.bot, post.id).await?;
+    if original != Some((message_id, starboard_id)) {
+        ctx.respond_str("This button isn't valid.", true).await?;
+        return Ok(());
+    }
+    let Some(orig) = DbMessage::get(&ctx.bot.pool, message_id).await? else {
+        ctx.respond_str("I couldn't find the original message.", true)
+            .await?;
+        return Ok(());
+    };
+
+    let configs =
+        StarboardConfig::list_for_channel(&ctx.bot, guild_id, orig.channel_id.into_id()).await?;
+    let Some(config) = configs.into_iter().find(|c| c.starboard.id == starboard_id) else {
+        ctx.respond_str("That starboard doesn't exist anymore.", true)
+            .await?;
+        return Ok(());
+    };
+    if !config.resolved.show_votes_button {
+        ctx.respond_str("The votes button is turned off for this starboard.", true)
+            .await?;
+        return Ok(());
+    }
+
+    let votes = Vote::list_by_message(&ctx.bot.pool, message_id, starboard_id).await?;
+    let totals_only = config.resolved.private_votes || config.resolved.anonymous_votes;
+    let (totals, pages) = list_votes(votes, totals_only);
+    if pages.is_empty() {
+        ctx.respond_str(&totals, true).await?;
+        return Ok(());
+    }
+
+    let pages = pages
+        .into_iter()
+        .map(|page| {
+            let emb = embed::build().title("Votes").description(page).build();
+            (None, Some(vec![emb]))
+        })
+        .collect();
+
+    paginator::simple(&mut ctx, pages, user_id, true).await?;
+
+    Ok(())
+}
+
+/// The vote totals, and the pages listing the voters. There are no pages if
+/// nobody voted, or if only the totals are shown.
+fn list_votes(votes: Vec<Vote>, totals_only: bool) -> (String, Vec<String>) {
+    let (downvotes, upvotes): (Vec<_>, Vec<_>) = votes.into_iter().partition(|v| v.is_downvote);
+    let totals = format!(
+        "{} upvote(s), {} downvote(s)",
+        upvotes.len(),
+        downvotes.len()
+    );
+    if totals_only {
+        return (totals, Vec::new());
+    }
+
+    // votes cast while `anonymous-votes` was enabled stay anonymous
+    let voter = |v: &Vote| match v.user_id {
+        Some(user_id) => format!("<@{user_id}>"),
+        None => "anonymous".to_string(),
+    };
+    let voters: Vec<_> = upvotes
+        .iter()
+        .map(|v| format!("⬆️ {}", voter(v)))
+        .chain(downvotes.iter().map(|v| format!("⬇️ {}", voter(v))))
+        .collect();
+    let pages = voters
+        .chunks(constants::VOTES_PAGE_SIZE)
+        .map(|chunk| format!("{totals}\n\n{}", chunk.join("\n")))
+        .collect();
+
+    (totals, pages)
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+
+    use super::*;
+
+    fn vote(user_id: i64, is_downvote: bool) -> Vote {
+        Vote {
+            message_id: 1,
+            starboard_id: 2,
+            user_id: Some(user_id),
+            voter_hash: None,
+            voter_id: user_id,
+            target_author_id: 3,
+            is_downvote,
+            created_at: Utc::now(),
+            weight: 1,
+            emoji: None,
+        }
+    }
+
+    #[test]
+    fn lists_upvotes_first() {
+        let votes = vec![vote(10, true), vote(11, false)];
+
+        assert_eq!(
+            list_votes(votes, false),
+            (
+                "1 upvote(s), 1 downvote(s)".to_string(),
+                vec!["1 upvote(s), 1 downvote(s)\n\n⬆️ <@11>\n⬇️ <@10>".to_string()]
+            )
+        );
+    }
+
+    #[test]
+    fn no_votes() {
+        assert_eq!(
+            list_votes(Vec::new(), false),
+            ("0 upvote(s), 0 downvote(s)".to_string(), Vec::new())
+        );
+    }
+
+    #[test]
+    fn totals_only() {
+        let votes = vec![vote(10, false), vote(11, false)];
+
+        assert_eq!(
+            list_votes(votes, true),
+            ("2 upvote(s), 0 downvote(s)".to_string(), Vec::new())
+        );
+    }
+
+    #[test]
+    fn anonymous_voters() {
+        let mut hashed = vote(-5, false);
+        hashed.user_id = None;
+        hashed.voter_hash = Some(-5);
+        let votes = vec![hashed, vote(10, true)];
+
+        let (_, pages) = list_votes(votes, false);
+        assert_eq!(
+            pages,
+            vec!["1 upvote(s), 1 downvote(s)\n\n⬆️ anonymous\n⬇️ <@10>".to_string()]
+        );
+    }
+
+    #[test]
+    fn pages() {
+        let size = constants::VOTES_PAGE_SIZE;
+        let votes = (0..size as i64 + 1).map(|id| vote(id, false)).collect();
+
+        let (_, pages) = list_votes(votes, false);
+        assert_eq!(pages.len(), 2);
+        assert_eq!(pages[0].matches("<@").count(), size);
+        assert_eq!(pages[1].matches("<@").count(), 1);
+    }
+}
@@ -4,20 +4,27 @@ use twilight_http::Response;
 use twilight_model::{
     application::interaction::{
         application_command::CommandData, message_component::MessageComponentInteractionData,
-        Interaction,
+        modal::ModalInteractionData, Interaction,
     },
     channel::{
-        message::{AllowedMentions, MessageFlags},
+        message::{AllowedMentions, Component, MessageFlags},
         Message,
     },
     http::interaction::{InteractionResponse, InteractionResponseData, InteractionResponseType},
 };
 use twilight_util::builder::InteractionResponseDataBuilder;
 
-use crate::{client::bot::StarboardBot, errors::StarboardResult};
+use crate::{
+    client::bot::StarboardBot,
+    errors::StarboardResult,
+    interactions::commands::visibility::Visibility,
+    locale::{resolve_locale, translate, Args},
+    utils::id_as_i64::GetI64,
+};
 
 pub type CommandCtx = Ctx<CommandData>;
 pub type ComponentCtx = Ctx<MessageComponentInteractionData>;
+pub type ModalCtx = Ctx<ModalInteractionData>;
 
 #[derive(Debug)]
 pub struct Ctx<T> {
@@ -25,6 +32,7 @@ pub struct Ctx<T> {
     pub interaction: Interaction,
     pub data: T,
     responded: bool,
+    visibility: Visibility,
 }
 
 type TwResult = StarboardResult<Response<Message>>;
@@ -36,11 +44,41 @@ impl<T> Ctx<T> {
             interaction,
             data,
             responded: false,
+            visibility: Visibility::Public,
         }
     }
 
+    /// The visibility of the command's output. Resolved from the command's
+    /// visibility policy before the command runs.
+    pub fn visibility(&self) -> Visibility {
+        self.visibility
+    }
+
+    pub fn set_visibility(&mut self, visibility: Visibility) {
+        self.visibility = visibility;
+    }
+
+    /// Looks up a message in the user's locale, or the guild's if they haven't
+    /// set one.
+    pub async fn t(&self, key: &str, args: Args<'_>) -> StarboardResult<String> {
+        let locale = resolve_locale(
+            &self.bot,
+            self.interaction.guild_id.map(|id| id.get_i64()),
+            self.interaction.author_id().map(|id| id.get_i64()),
+        )
+        .await?;
+        Ok(translate(locale, key, args))
+    }
+
+    /// A response builder, made ephemeral if the command's visibility is.
     pub fn build_resp(&self) -> InteractionResponseDataBuilder {
-        InteractionResponseDataBuilder::new().allowed_mentions(AllowedMentions::default())
+        let data =
+            InteractionResponseDataBuilder::new().allowed_mentions(AllowedMentions::default());
+        if self.visibility == Visibility::Ephemeral {
+            data.flags(MessageFlags::EPHEMERAL)
+        } else {
+            data
+        }
     }
 
     pub async fn raw_respond(
@@ -96,6 +134,8 @@ impl<T> Ctx<T> {
         }
     }
 
+    /// `ephemeral` forces an ephemeral response. Otherwise, the command's
+    /// visibility is used.
     pub async fn defer(&mut self, ephemeral: bool) -> TwResult {
         let mut data = self.build_resp();
         if ephemeral {
@@ -117,6 +157,8 @@ impl<T> Ctx<T> {
         .await
     }
 
+    /// `ephemeral` forces an ephemeral response. Otherwise, the command's
+    /// visibility is used.
     pub async fn respond_str(&mut self, response: &str, ephemeral: bool) -> TwResult {
         let mut data = self.build_resp().content(response);
         if ephemeral {
@@ -130,6 +172,35 @@ impl<T> Ctx<T> {
         .await
     }
 
+    /// Responds with a modal. Its submission is a separate interaction,
+    /// handled by `handle_modal`.
+    pub async fn respond_modal(
+        &mut self,
+        custom_id: String,
+        title: String,
+        components: Vec<Component>,
+    ) -> StarboardResult<()> {
+        let data = InteractionResponseDataBuilder::new()
+            .custom_id(custom_id)
+            .title(title)
+            .components(components)
+            .build();
+
+        let i = self.bot.interaction_client().await;
+        i.create_response(
+            self.interaction.id,
+            &self.interaction.token,
+            &InteractionResponse {
+                kind: InteractionResponseType::Modal,
+                data: Some(data),
+            },
+        )
+        .await?;
+
+        self.responded = true;
+        Ok(())
+    }
+
     pub async fn edit(&mut self, data: InteractionResponseData) -> TwResult {
         self.raw_respond(Some(data), InteractionResponseType::UpdateMessage)
             .await
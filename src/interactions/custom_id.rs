@@ -0,0 +1,366 @@
+//! Custom ids for message components and modals.
+//!
+//! Every custom id describes itself: the version of this format, what kind of
+//! control it is, and the ids of whatever it acts on. Handlers rebuild what
+//! they need from the id and the database, so controls on long-lived messages
+//! keep working after a restart. The exception are views (`utils::views`),
+//! which wait for their buttons in memory; clicks that no view is waiting for
+//! are answered with "this control expired" instead of failing silently.
+
+use std::{fmt, str::FromStr};
+
+use crate::core::inspect::InspectFix;
+
+/// Bumped when the meaning of existing ids changes. Ids with another version
+/// are treated as expired.
+pub const VERSION: &str = "v1";
+/// Discord's limit for custom ids.
+pub const MAX_LENGTH: usize = 100;
+
+/// Ids before versioning that are still decoded, since buttons with them
+/// exist on posts and in DMs, and modals with them might still be open.
+const LEGACY_PREFIXES: [&str; 3] = ["stateless", "mod_note", "vote_lock"];
+
+/// Ids for the buttons of views that wait for them in memory.
+pub mod view {
+    pub const CONFIRM_YES: &str = "v1::view::confirm::yes";
+    pub const CONFIRM_NO: &str = "v1::view::confirm::no";
+
+    pub const PAGINATOR_TO_START: &str = "v1::view::paginator::to_start";
+    pub const PAGINATOR_BACK: &str = "v1::view::paginator::back";
+    pub const PAGINATOR_META: &str = "v1::view::paginator::meta";
+    pub const PAGINATOR_NEXT: &str = "v1::view::paginator::next";
+    pub const PAGINATOR_TO_END: &str = "v1::view::paginator::to_end";
+
+    pub const SELECT_PAGINATOR_SELECT: &str = "v1::view::select_paginator::select";
+    pub const SELECT_PAGINATOR_BACK: &str = "v1::view::select_paginator::back";
+    pub const SELECT_PAGINATOR_META: &str = "v1::view::select_paginator::meta";
+    pub const SELECT_PAGINATOR_NEXT: &str = "v1::view::select_paginator::next";
+
+    pub const MOSTSTARRED_BACK: &str = "v1::view::moststarred::back";
+    pub const MOSTSTARRED_PAGE: &str = "v1::view::moststarred::page";
+    pub const MOSTSTARRED_NEXT: &str = "v1::view::moststarred::next";
+
+    pub const PURGE_USER_STOP: &str = "v1::view::purge_user::stop";
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CustomId {
+    /// A button of a view, see `view`. The string is what follows "view::".
+    View(String),
+    DismissNotification,
+    Translate,
+    InspectFix {
+        fix: InspectFix,
+        channel_id: i64,
+        message_id: i64,
+    },
+    /// The "Add mod note" modal, for an original message.
+    ModNote {
+        message_id: i64,
+    },
+    /// The "Remove & lock vote" modal, for an original message.
+    VoteLock {
+        message_id: i64,
+    },
+    /// A vote button on a post. The emoji is stored like in the settings, as
+    /// an id for custom emojis and as the emoji itself for unicode ones.
+    Vote {
+        emoji: String,
+    },
+    /// The vote select menu on a post. The chosen option is the stored emoji.
+    VoteSelect,
+    /// The "Votes" button on a post, for an original message and the post's
+    /// starboard.
+    Votes {
+        message_id: i64,
+        starboard_id: i32,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DecodeError {
+    /// The id was made by another version of the bot.
+    VersionMismatch,
+    /// The id isn't one this version makes.
+    Unknown,
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::VersionMismatch => f.write_str("custom id from another version"),
+            Self::Unknown => f.write_str("unknown custom id"),
+        }
+    }
+}
+
+impl CustomId {
+    pub fn encode(&self) -> String {
+        let id = match self {
+            Self::View(id) => format!("{VERSION}::view::{id}"),
+            Self::DismissNotification => format!("{VERSION}::dismiss"),
+            Self::Translate => format!("{VERSION}::translate"),
+            Self::InspectFix {
+                fix,
+                channel_id,
+                message_id,
+            } => format!(
+                "{VERSION}::inspect::{}::{channel_id}-{message_id}",
+                fix.name()
+            ),
+            Self::ModNote { message_id } => format!("{VERSION}::mod_note::{message_id}"),
+            Self::VoteLock { message_id } => format!("{VERSION}::vote_lock::{message_id}"),
+            Self::Vote { emoji } => format!("{VERSION}::vote::{}", encode_emoji(emoji)),
+            Self::VoteSelect => format!("{VERSION}::vote_select"),
+            Self::Votes {
+                message_id,
+                starboard_id,
+            } => format!("{VERSION}::votes::{message_id}-{starboard_id}"),
+        };
+        debug_assert!(id.len() <= MAX_LENGTH, "custom id too long: {id}");
+
+        id
+    }
+
+    pub fn decode(id: &str) -> Result<Self, DecodeError> {
+        let (version, rest) = id.split_once("::").ok_or(DecodeError::Unknown)?;
+        if LEGACY_PREFIXES.contains(&version) {
+            return Self::decode_legacy(version, rest);
+        }
+        if version != VERSION {
+            return if is_version(version) {
+                Err(DecodeError::VersionMismatch)
+            } else {
+                Err(DecodeError::Unknown)
+            };
+        }
+
+        let (kind, args) = rest.split_once("::").unwrap_or((rest, ""));
+        match kind {
+            "view" if !args.is_empty() => Ok(Self::View(args.to_string())),
+            "dismiss" => Ok(Self::DismissNotification),
+            "translate" => Ok(Self::Translate),
+            "inspect" => decode_inspect(args),
+            "mod_note" => Ok(Self::ModNote {
+                message_id: parse_id(args)?,
+            }),
+            "vote_lock" => Ok(Self::VoteLock {
+                message_id: parse_id(args)?,
+            }),
+            "vote" => Ok(Self::Vote {
+                emoji: decode_emoji(args)?,
+            }),
+            "vote_select" => Ok(Self::VoteSelect),
+            "votes" => decode_votes(args),
+            _ => Err(DecodeError::Unknown),
+        }
+    }
+
+    fn decode_legacy(prefix: &str, rest: &str) -> Result<Self, DecodeError> {
+        match (prefix, rest.split_once("::")) {
+            ("stateless", None) if rest == "dismiss_notification" => Ok(Self::DismissNotification),
+            ("stateless", None) if rest == "translate" => Ok(Self::Translate),
+            ("stateless", Some(("inspect", args))) => decode_inspect(args),
+            ("mod_note", None) => Ok(Self::ModNote {
+                message_id: parse_id(rest)?,
+            }),
+            ("vote_lock", None) => Ok(Self::VoteLock {
+                message_id: parse_id(rest)?,
+            }),
+            _ => Err(DecodeError::Unknown),
+        }
+    }
+}
+
+fn is_version(version: &str) -> bool {
+    version
+        .strip_prefix('v')
+        .is_some_and(|n| !n.is_empty() && n.bytes().all(|b| b.is_ascii_digit()))
+}
+
+fn parse_id(input: &str) -> Result<i64, DecodeError> {
+    input.parse().map_err(|_| DecodeError::Unknown)
+}
+
+/// "<fix>::<channel>-<message>"
+fn decode_inspect(args: &str) -> Result<CustomId, DecodeError> {
+    let (fix, link) = args.split_once("::").ok_or(DecodeError::Unknown)?;
+    let (channel_id, message_id) = link.split_once('-').ok_or(DecodeError::Unknown)?;
+
+    Ok(CustomId::InspectFix {
+        fix: InspectFix::from_str(fix).map_err(|_| DecodeError::Unknown)?,
+        channel_id: parse_id(channel_id)?,
+        message_id: parse_id(message_id)?,
+    })
+}
+
+/// "<message>-<starboard>"
+fn decode_votes(args: &str) -> Result<CustomId, DecodeError> {
+    let (message_id, starboard_id) = args.split_once('-').ok_or(DecodeError::Unknown)?;
+
+    Ok(CustomId::Votes {
+        message_id: parse_id(message_id)?,
+        starboard_id: starboard_id.parse().map_err(|_| DecodeError::Unknown)?,
+    })
+}
+
+/// "c<id>" for custom emojis, and "u" followed by the hex codepoints joined
+/// with "-" for unicode ones, so that even long ZWJ sequences fit.
+fn encode_emoji(emoji: &str) -> String {
+    if emoji.bytes().all(|b| b.is_ascii_digit()) {
+        return format!("c{emoji}");
+    }
+
+    let codepoints: Vec<_> = emoji.chars().map(|c| format!("{:x}", c as u32)).collect();
+    format!("u{}", codepoints.join("-"))
+}
+
+fn decode_emoji(args: &str) -> Result<String, DecodeError> {
+    if let Some(id) = args.strip_prefix('c') {
+        return parse_id(id).map(|id| id.to_string());
+    }
+
+    let codepoints = args.strip_prefix('u').ok_or(DecodeError::Unknown)?;
+    codepoints
+        .split('-')
+        .map(|hex| {
+            u32::from_str_radix(hex, 16)
+                .ok()
+                .and_then(char::from_u32)
+                .ok_or(DecodeError::Unknown)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn all_ids() -> Vec<CustomId> {
+        vec![
+            CustomId::View("confirm::yes".to_string()),
+            CustomId::DismissNotification,
+            CustomId::Translate,
+            CustomId::InspectFix {
+                fix: InspectFix::Relink,
+                channel_id: 1_100_000_000_000_000_001,
+                message_id: 1_100_000_000_000_000_002,
+            },
+            CustomId::ModNote {
+                message_id: 1_100_000_000_000_000_003,
+            },
+            CustomId::VoteLock {
+                message_id: 1_100_000_000_000_000_004,
+            },
+            CustomId::Vote {
+                emoji: "1100000000000000005".to_string(),
+            },
+            CustomId::Vote {
+                emoji: "⭐".to_string(),
+            },
+            CustomId::VoteSelect,
+            CustomId::Votes {
+                message_id: 1_100_000_000_000_000_006,
+                starboard_id: i32::MAX,
+            },
+        ]
+    }
+
+    #[test]
+    fn roundtrip() {
+        for id in all_ids() {
+            let encoded = id.encode();
+            assert!(encoded.starts_with("v1::"), "{encoded}");
+            assert!(encoded.len() <= MAX_LENGTH, "{encoded}");
+            assert_eq!(CustomId::decode(&encoded), Ok(id));
+        }
+    }
+
+    #[test]
+    fn encode_format() {
+        let id = CustomId::Votes {
+            message_id: 10,
+            starboard_id: 3,
+        };
+        assert_eq!(id.encode(), "v1::votes::10-3");
+        assert_eq!(
+            CustomId::View("paginator::next".to_string()).encode(),
+            view::PAGINATOR_NEXT
+        );
+    }
+
+    #[test]
+    fn long_emoji() {
+        // family emoji, a ZWJ sequence of 8 codepoints
+        let emoji = "👨\u{200d}👩\u{200d}👧\u{200d}👦\u{fe0f}".to_string();
+        let id = CustomId::Vote {
+            emoji: emoji.clone(),
+        };
+        let encoded = id.encode();
+        assert_eq!(
+            encoded,
+            "v1::vote::u1f468-200d-1f469-200d-1f467-200d-1f466-fe0f"
+        );
+        assert_eq!(CustomId::decode(&encoded), Ok(CustomId::Vote { emoji }));
+    }
+
+    #[test]
+    fn version_mismatch() {
+        for id in ["v0::dismiss", "v2::votes::10-3", "v12::translate"] {
+            assert_eq!(CustomId::decode(id), Err(DecodeError::VersionMismatch));
+        }
+    }
+
+    #[test]
+    fn unknown() {
+        for id in [
+            "",
+            "dismiss",
+            "v::dismiss",
+            "vx::dismiss",
+            "other::dismiss",
+            "v1::unknown",
+            "v1::view",
+            "v1::mod_note::abc",
+            "v1::votes::10",
+            "v1::inspect::explode::1-2",
+            "v1::vote::x123",
+            "v1::vote::ud800",
+        ] {
+            assert_eq!(CustomId::decode(id), Err(DecodeError::Unknown), "{id}");
+        }
+    }
+
+    #[test]
+    fn legacy() {
+        assert_eq!(
+            CustomId::decode("stateless::dismiss_notification"),
+            Ok(CustomId::DismissNotification)
+        );
+        assert_eq!(
+            CustomId::decode("stateless::translate"),
+            Ok(CustomId::Translate)
+        );
+        assert_eq!(
+            CustomId::decode("stateless::inspect::recount::1-2"),
+            Ok(CustomId::InspectFix {
+                fix: InspectFix::Recount,
+                channel_id: 1,
+                message_id: 2,
+            })
+        );
+        assert_eq!(
+            CustomId::decode("mod_note::5"),
+            Ok(CustomId::ModNote { message_id: 5 })
+        );
+        assert_eq!(
+            CustomId::decode("vote_lock::6"),
+            Ok(CustomId::VoteLock { message_id: 6 })
+        );
+        assert_eq!(
+            CustomId::decode("stateless::unknown"),
+            Err(DecodeError::Unknown)
+        );
+    }
+}
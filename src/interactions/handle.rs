@@ -6,7 +6,7 @@ use crate::{client::bot::StarboardBot, errors::StarboardResult};
 
 use super::{
     autocomplete::handle::handle_autocomplete, commands::handle::handle_command,
-    components::handle::handle_component, context::Ctx,
+    components::handle::handle_component, context::Ctx, modals::handle::handle_modal,
 };
 
 pub async fn handle_interaction(
@@ -32,6 +32,12 @@ pub async fn handle_interaction(
 
             handle_component(ctx).await?;
         }
+        InteractionData::ModalSubmit(data) => {
+            let data = data.to_owned();
+            let ctx = Ctx::new(bot, interaction, data);
+
+            handle_modal(ctx).await?;
+        }
         _ => {}
     }
 
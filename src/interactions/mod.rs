@@ -2,4 +2,6 @@ pub mod autocomplete;
 pub mod commands;
 pub mod components;
 pub mod context;
+pub mod custom_id;
 pub mod handle;
+pub mod modals;
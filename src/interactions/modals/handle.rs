@@ -0,0 +1,19 @@
+use crate::{
+    errors::StarboardResult,
+    interactions::{context::ModalCtx, custom_id::CustomId},
+};
+
+use super::{mod_note::handle_mod_note, vote_lock::handle_vote_lock};
+
+pub async fn handle_modal(mut ctx: ModalCtx) -> StarboardResult<()> {
+    match CustomId::decode(&ctx.data.custom_id) {
+        Ok(CustomId::ModNote { message_id }) => handle_mod_note(ctx, message_id).await?,
+        Ok(CustomId::VoteLock { message_id }) => handle_vote_lock(ctx, message_id).await?,
+        _ => {
+            ctx.respond_str("This form expired. Run the command again.", true)
+                .await?;
+        }
+    }
+
+    Ok(())
+}
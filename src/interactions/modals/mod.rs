@@ -0,0 +1,3 @@
+pub mod handle;
+pub mod mod_note;
+pub mod vote_lock;
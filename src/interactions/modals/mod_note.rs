@@ -0,0 +1,57 @@
+use crate::{
+    database::{
+        validation::mod_note::{validate_mod_note, validate_mod_note_count},
+        DbGuild, ModNote,
+    },
+    errors::StarboardResult,
+    get_guild_id,
+    interactions::{commands::message::mod_notes::can_manage_notes, context::ModalCtx},
+    utils::id_as_i64::GetI64,
+};
+
+/// Saves a note from the "Add mod note" modal. `message_id` is already the
+/// original message.
+pub async fn handle_mod_note(mut ctx: ModalCtx, message_id: i64) -> StarboardResult<()> {
+    let guild_id = get_guild_id!(ctx).get_i64();
+
+    let member = ctx.interaction.member.as_ref();
+    if !can_manage_notes(member.and_then(|m| m.permissions)) {
+        ctx.respond_str(
+            "You need the Manage Messages permission to add notes.",
+            true,
+        )
+        .await?;
+        return Ok(());
+    }
+    let author_id = ctx.interaction.author_id().unwrap().get_i64();
+
+    let note = ctx
+        .data
+        .components
+        .iter()
+        .flat_map(|row| &row.components)
+        .find(|input| input.custom_id == "note")
+        .and_then(|input| input.value.as_deref())
+        .unwrap_or_default();
+    let note = match validate_mod_note(note) {
+        Ok(note) => note,
+        Err(why) => {
+            ctx.respond_str(&why, true).await?;
+            return Ok(());
+        }
+    };
+
+    // checked again, in case notes were added while the modal was open
+    let count = ModNote::count_by_message(&ctx.bot.pool, message_id).await?;
+    if let Err(why) = validate_mod_note_count(count) {
+        ctx.respond_str(&why, true).await?;
+        return Ok(());
+    }
+
+    DbGuild::create(&ctx.bot.pool, guild_id).await?;
+    let note = ModNote::create(&ctx.bot.pool, guild_id, message_id, author_id, &note).await?;
+    ctx.respond_str(&format!("Added note `#{}`.", note.id), true)
+        .await?;
+
+    Ok(())
+}
@@ -0,0 +1,101 @@
+use twilight_model::id::{marker::UserMarker, Id};
+
+use crate::{
+    core::starboard::vote_locks::{lock_vote, LockVoteResult},
+    database::DbMessage,
+    errors::StarboardResult,
+    get_guild_id,
+    interactions::{commands::message::vote_locks::can_lock_votes, context::ModalCtx},
+    utils::id_as_i64::GetI64,
+};
+
+/// Parses a user id, or a mention of one.
+fn parse_user(input: &str) -> Option<Id<UserMarker>> {
+    let input = input.trim();
+    let input = input
+        .strip_prefix("<@")
+        .and_then(|i| i.strip_suffix('>'))
+        .map_or(input, |i| i.trim_start_matches('!'));
+
+    input.parse().ok()
+}
+
+/// Locks a vote from the "Remove & lock vote" modal. `message_id` is already
+/// the original message.
+pub async fn handle_vote_lock(mut ctx: ModalCtx, message_id: i64) -> StarboardResult<()> {
+    let guild_id = get_guild_id!(ctx).get_i64();
+
+    let member = ctx.interaction.member.as_ref();
+    if !can_lock_votes(member.and_then(|m| m.permissions)) {
+        ctx.respond_str(
+            "You need the Manage Messages permission to lock votes.",
+            true,
+        )
+        .await?;
+        return Ok(());
+    }
+    let moderator_id = ctx.interaction.author_id().unwrap();
+
+    let input = ctx
+        .data
+        .components
+        .iter()
+        .flat_map(|row| &row.components)
+        .find(|input| input.custom_id == "user")
+        .and_then(|input| input.value.as_deref())
+        .unwrap_or_default();
+    let Some(user_id) = parse_user(input) else {
+        ctx.respond_str("That isn't a user ID or mention.", true)
+            .await?;
+        return Ok(());
+    };
+
+    let orig = DbMessage::get(&ctx.bot.pool, message_id).await?;
+    let Some(orig) = orig.filter(|orig| orig.guild_id == guild_id) else {
+        ctx.respond_str("Nobody has voted on that message.", true)
+            .await?;
+        return Ok(());
+    };
+
+    ctx.defer(true).await?;
+    let ret = lock_vote(ctx.bot.clone(), orig, user_id, moderator_id).await?;
+    let msg = match ret {
+        LockVoteResult::Locked(0) => {
+            format!("<@{user_id}> hadn't voted on that message, but they won't be able to.")
+        }
+        LockVoteResult::Locked(_) => {
+            format!("Removed <@{user_id}>'s vote, and they won't be able to vote again.")
+        }
+        LockVoteResult::AlreadyLocked => {
+            format!("<@{user_id}>'s vote on that message is already locked.")
+        }
+        LockVoteResult::UnknownUser => "I couldn't find that user.".to_string(),
+    };
+    ctx.respond_str(&msg, true).await?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_users() {
+        let user_id = Some(Id::new(123456789012345678));
+
+        assert_eq!(parse_user("123456789012345678"), user_id);
+        assert_eq!(parse_user(" 123456789012345678 "), user_id);
+        assert_eq!(parse_user("<@123456789012345678>"), user_id);
+        assert_eq!(parse_user("<@!123456789012345678>"), user_id);
+    }
+
+    #[test]
+    fn rejects_other_input() {
+        assert_eq!(parse_user(""), None);
+        assert_eq!(parse_user("0"), None);
+        assert_eq!(parse_user("someone"), None);
+        assert_eq!(parse_user("<#123456789012345678>"), None);
+        assert_eq!(parse_user("<@&123456789012345678>"), None);
+    }
+}
@@ -0,0 +1,56 @@
+pub fn get(key: &str) -> Option<&'static str> {
+    let template = match key {
+        // errors
+        "errors.not-in-guild" => "Please invite me to the server first.",
+        "errors.unknown-starboard" => "'{name}' is not a starboard.",
+        "errors.cooldown" => "You're doing that too quickly. Try again in a minute.",
+
+        // /ping
+        "ping.pong" => "Pong! I'm here.",
+
+        // /help
+        "help.title" => "Starboard - The Best Discord Starboard",
+        "help.description" => "Starboard is a reliable and feature-rich starboard bot for Discord.",
+        "help.commands.name" => "Useful Commands",
+        "help.commands.value" => concat!(
+            "`/starboards view`: View all of your starboards.\n",
+            "`/starboards create`: Create a new starboard.\n",
+            "`/starboards edit`: Edit the configuration for a starboard.\n",
+        ),
+        "help.features.name" => "Starboard's Features",
+        "help.features.value" => concat!(
+            "Starboard's key free features are:\n",
+            "- Multiple starboards\n",
+            "- Custom avatar/username for starboards (via webhooks)\n",
+            "- Autostar channels\n",
+            "- Complete per-channel starboard configuration\n",
+            "- Limited per-role starboard configuration\n",
+        ),
+        "help.support.name" => "Support Starboard",
+        "help.support.value" => concat!(
+            "If you like Starboard and want to support it, you can do ",
+            "so by [voting]({vote}) or [leaving a review]({review}) ",
+            "on Top.GG.\n\nIf you really want, you can also get Starboard Premium ",
+            "by [becoming a patron]({patreon}).",
+        ),
+        "help.buttons.invite" => "Invite",
+        "help.buttons.support" => "Support",
+        "help.buttons.docs" => "Documentation",
+        "help.buttons.source" => "Source",
+
+        // starboard messages
+        "link-reply.view" => "View on {starboard}",
+
+        // /language
+        "language.server-set" => "The server's language is now {language}.",
+        "language.personal-set" => "Your language is now {language}.",
+        "language.personal-reset" => "You'll now see messages in the server's language.",
+        "language.no-permission" => {
+            "You need the Manage Server permission to change the server's language."
+        }
+
+        _ => return None,
+    };
+
+    Some(template)
+}
@@ -0,0 +1,56 @@
+pub fn get(key: &str) -> Option<&'static str> {
+    let template = match key {
+        // errors
+        "errors.not-in-guild" => "Primero invítame al servidor.",
+        "errors.unknown-starboard" => "'{name}' no es un starboard.",
+        "errors.cooldown" => "Vas demasiado rápido. Inténtalo de nuevo en un minuto.",
+
+        // /ping
+        "ping.pong" => "¡Pong! Aquí estoy.",
+
+        // /help
+        "help.title" => "Starboard - El mejor starboard de Discord",
+        "help.description" => "Starboard es un bot de starboard fiable y completo para Discord.",
+        "help.commands.name" => "Comandos útiles",
+        "help.commands.value" => concat!(
+            "`/starboards view`: Ver todos tus starboards.\n",
+            "`/starboards create`: Crear un starboard nuevo.\n",
+            "`/starboards edit`: Editar la configuración de un starboard.\n",
+        ),
+        "help.features.name" => "Funciones de Starboard",
+        "help.features.value" => concat!(
+            "Las principales funciones gratuitas de Starboard son:\n",
+            "- Varios starboards\n",
+            "- Avatar y nombre personalizados para los starboards (con webhooks)\n",
+            "- Canales de autostar\n",
+            "- Configuración completa por canal\n",
+            "- Configuración limitada por rol\n",
+        ),
+        "help.support.name" => "Apoya a Starboard",
+        "help.support.value" => concat!(
+            "Si te gusta Starboard y quieres apoyarlo, puedes ",
+            "[votar]({vote}) o [dejar una reseña]({review}) ",
+            "en Top.GG.\n\nSi de verdad quieres, también puedes conseguir Starboard Premium ",
+            "[haciéndote patrocinador]({patreon}).",
+        ),
+        "help.buttons.invite" => "Invitar",
+        "help.buttons.support" => "Soporte",
+        "help.buttons.docs" => "Documentación",
+        "help.buttons.source" => "Código fuente",
+
+        // starboard messages
+        "link-reply.view" => "Ver en {starboard}",
+
+        // /language
+        "language.server-set" => "El idioma del servidor ahora es {language}.",
+        "language.personal-set" => "Tu idioma ahora es {language}.",
+        "language.personal-reset" => "Ahora verás los mensajes en el idioma del servidor.",
+        "language.no-permission" => {
+            "Necesitas el permiso Gestionar servidor para cambiar el idioma del servidor."
+        }
+
+        _ => return None,
+    };
+
+    Some(template)
+}
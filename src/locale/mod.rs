@@ -0,0 +1,116 @@
+//! A small message catalog for localized responses. Each language maps keys
+//! to templates, where `{name}` placeholders are filled in from the args.
+//! Keys that are missing in a language fall back to English.
+
+mod en;
+mod es;
+
+use std::fmt::Display;
+
+use crate::{
+    client::bot::StarboardBot,
+    database::{DbGuild, DbUser},
+    errors::StarboardResult,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Locale {
+    #[default]
+    English,
+    Spanish,
+}
+
+impl Locale {
+    pub fn from_code(code: &str) -> Option<Self> {
+        match code {
+            "en" => Some(Self::English),
+            "es" => Some(Self::Spanish),
+            _ => None,
+        }
+    }
+
+    pub fn code(self) -> &'static str {
+        match self {
+            Self::English => "en",
+            Self::Spanish => "es",
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::English => "English",
+            Self::Spanish => "Español",
+        }
+    }
+
+    fn template(self, key: &str) -> Option<&'static str> {
+        match self {
+            Self::English => en::get(key),
+            Self::Spanish => es::get(key),
+        }
+    }
+}
+
+pub type Args<'a> = &'a [(&'a str, &'a (dyn Display + Sync))];
+
+/// Looks up and fills in a message. Unknown keys fall back to English, and
+/// then to the key itself.
+pub fn translate(locale: Locale, key: &str, args: Args) -> String {
+    let template = locale.template(key).or_else(|| en::get(key)).unwrap_or(key);
+
+    let mut message = template.to_string();
+    for (name, value) in args {
+        message = message.replace(&format!("{{{name}}}"), &value.to_string());
+    }
+    message
+}
+
+pub async fn guild_locale(bot: &StarboardBot, guild_id: i64) -> StarboardResult<Locale> {
+    let cached = bot
+        .cache
+        .guild_locales
+        .with(&guild_id, |_, locale| locale.as_ref().map(|l| *l.value()));
+    if let Some(cached) = cached {
+        return Ok(cached);
+    }
+
+    let locale = DbGuild::get(&bot.pool, guild_id)
+        .await?
+        .and_then(|guild| Locale::from_code(&guild.locale))
+        .unwrap_or_default();
+
+    bot.cache.guild_locales.insert(guild_id, locale);
+    Ok(locale)
+}
+
+pub async fn user_locale(bot: &StarboardBot, user_id: i64) -> StarboardResult<Option<Locale>> {
+    if let Some(cached) = bot.cache.user_locales.get(&user_id) {
+        return Ok(cached);
+    }
+
+    let locale = DbUser::get(&bot.pool, user_id)
+        .await?
+        .and_then(|user| user.locale)
+        .and_then(|code| Locale::from_code(&code));
+
+    bot.cache.user_locales.insert(user_id, locale).await;
+    Ok(locale)
+}
+
+/// The user's own locale if they set one, otherwise the guild's.
+pub async fn resolve_locale(
+    bot: &StarboardBot,
+    guild_id: Option<i64>,
+    user_id: Option<i64>,
+) -> StarboardResult<Locale> {
+    if let Some(user_id) = user_id {
+        if let Some(locale) = user_locale(bot, user_id).await? {
+            return Ok(locale);
+        }
+    }
+
+    match guild_id {
+        Some(guild_id) => guild_locale(bot, guild_id).await,
+        None => Ok(Locale::default()),
+    }
+}
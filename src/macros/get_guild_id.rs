@@ -5,8 +5,8 @@ macro_rules! get_guild_id {
     ($ctx: expr) => {
         match $ctx.interaction.guild_id {
             None => {
-                $ctx.respond_str("Please invite me to the server first.", true)
-                    .await?;
+                let msg = $ctx.t("errors.not-in-guild", &[]).await?;
+                $ctx.respond_str(&msg, true).await?;
                 return Ok(());
             }
             Some(value) => value,
@@ -8,6 +8,7 @@ pub mod database;
 pub mod errors;
 pub mod events;
 pub mod interactions;
+pub mod locale;
 pub mod macros;
 pub mod owner;
 pub mod utils;
@@ -16,22 +17,43 @@ use snafu::ErrorCompat;
 use tokio::main;
 use tracing_subscriber::{fmt, EnvFilter};
 
-use crate::client::{bot::StarboardBot, config::Config, runner::run};
+use crate::client::{
+    bot::StarboardBot,
+    config::{Config, LogFormat},
+    runner::run,
+    seed::{self, SeedMode},
+    self_check::self_check,
+};
 
-fn init_tracing() {
-    tracing::subscriber::set_global_default(
-        fmt::Subscriber::builder()
-            .with_env_filter(EnvFilter::from_default_env())
-            .finish(),
-    )
-    .expect("Unable to set global tracing subscriber");
+fn init_tracing(config: &Config) {
+    let builder = fmt::Subscriber::builder().with_env_filter(EnvFilter::from_default_env());
+    let ret = match config.log_format {
+        LogFormat::Text => tracing::subscriber::set_global_default(builder.finish()),
+        LogFormat::Pretty => tracing::subscriber::set_global_default(builder.pretty().finish()),
+        LogFormat::Json => {
+            tracing::subscriber::set_global_default(builder.json().with_current_span(true).finish())
+        }
+    };
+    ret.expect("Unable to set global tracing subscriber");
 }
 
 #[main]
 async fn main() {
-    init_tracing();
+    let config = match Config::from_env() {
+        Ok(config) => config,
+        Err(why) => {
+            eprintln!("{why}");
+            std::process::exit(1);
+        }
+    };
+    init_tracing(&config);
 
-    let config = Config::from_env();
+    if let Some(mode) = SeedMode::from_args() {
+        if let Err(why) = seed::run(&config, mode).await {
+            tracing::error!("{why}");
+        }
+        return;
+    }
 
     let _sentry_guard = config.sentry.as_ref().map(|url| {
         sentry::init((
@@ -43,12 +65,17 @@ async fn main() {
         ))
     });
 
+    if let Err(why) = self_check(&config).await {
+        tracing::error!("{why}");
+        return;
+    }
+
     let bot = match StarboardBot::new(config).await {
         Ok(val) => val,
         Err(why) => {
-            eprintln!("{}", &why);
+            tracing::error!("{why}");
             if let Some(bt) = ErrorCompat::backtrace(&why) {
-                eprintln!("{:#?}", &bt);
+                tracing::error!("{bt:#?}");
             }
             sentry::capture_error(&why);
             return;
@@ -0,0 +1,78 @@
+use twilight_model::id::{
+    marker::{ChannelMarker, MessageMarker},
+    Id,
+};
+
+use crate::{
+    client::bot::StarboardBot, core::premium::is_premium::is_guild_premium, errors::StarboardResult,
+};
+
+async fn parse_guild_id(
+    bot: &StarboardBot,
+    channel_id: Id<ChannelMarker>,
+    message_id: Id<MessageMarker>,
+    guild_id: Option<&str>,
+    usage: &str,
+) -> StarboardResult<Option<i64>> {
+    if let Some(guild_id) = guild_id.and_then(|id| id.parse().ok()) {
+        return Ok(Some(guild_id));
+    }
+
+    bot.http
+        .create_message(channel_id)
+        .content(&format!("Usage: `{usage}`."))?
+        .reply(message_id)
+        .await?;
+    Ok(None)
+}
+
+/// `star cache-invalidate guild <id>`: drops the guild's cached settings, e.g.
+/// after editing them with `star sql`.
+pub async fn invalidate_guild(
+    bot: &StarboardBot,
+    channel_id: Id<ChannelMarker>,
+    message_id: Id<MessageMarker>,
+    guild_id: Option<&str>,
+) -> StarboardResult<()> {
+    let usage = "star cache-invalidate guild <guild_id>";
+    let Some(guild_id) = parse_guild_id(bot, channel_id, message_id, guild_id, usage).await? else {
+        return Ok(());
+    };
+
+    bot.cache.invalidate_guild(guild_id).await;
+    bot.http
+        .create_message(channel_id)
+        .content(&format!("Invalidated the cache for guild {guild_id}."))?
+        .reply(message_id)
+        .await?;
+
+    Ok(())
+}
+
+/// `star reload-premium <guild_id>`: checks the guild's premium status again,
+/// skipping the cache.
+pub async fn reload_premium(
+    bot: &StarboardBot,
+    channel_id: Id<ChannelMarker>,
+    message_id: Id<MessageMarker>,
+    guild_id: Option<&str>,
+) -> StarboardResult<()> {
+    let usage = "star reload-premium <guild_id>";
+    let Some(guild_id) = parse_guild_id(bot, channel_id, message_id, guild_id, usage).await? else {
+        return Ok(());
+    };
+
+    let is_premium = is_guild_premium(bot, guild_id, false).await?;
+    let msg = if is_premium {
+        format!("Guild {guild_id} has premium.")
+    } else {
+        format!("Guild {guild_id} doesn't have premium.")
+    };
+    bot.http
+        .create_message(channel_id)
+        .content(&msg)?
+        .reply(message_id)
+        .await?;
+
+    Ok(())
+}
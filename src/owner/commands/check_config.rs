@@ -0,0 +1,44 @@
+use twilight_model::id::{
+    marker::{ChannelMarker, MessageMarker},
+    Id,
+};
+
+use crate::{
+    client::bot::StarboardBot, core::config_drift::check_guild, errors::StarboardResult,
+    utils::into_id::IntoId,
+};
+
+/// `star check-config <guild>`: checks a guild's starboards for settings that
+/// can't work in their channel, without waiting for the weekly check.
+pub async fn check_config(
+    bot: &StarboardBot,
+    channel_id: Id<ChannelMarker>,
+    message_id: Id<MessageMarker>,
+    guild_id: Option<&str>,
+) -> StarboardResult<()> {
+    let Some(guild_id) = guild_id
+        .and_then(|id| id.parse::<u64>().ok())
+        .filter(|id| *id != 0)
+    else {
+        bot.http
+            .create_message(channel_id)
+            .content("Usage: `star check-config <guild id>`.")?
+            .reply(message_id)
+            .await?;
+        return Ok(());
+    };
+
+    bot.http.create_typing_trigger(channel_id).await?;
+    let content = match check_guild(bot, guild_id.into_id()).await? {
+        None => "I couldn't check that guild.".to_string(),
+        Some(count) => format!("Checked guild {guild_id}: {count} starboard(s) have warnings."),
+    };
+
+    bot.http
+        .create_message(channel_id)
+        .content(&content)?
+        .reply(message_id)
+        .await?;
+
+    Ok(())
+}
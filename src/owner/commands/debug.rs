@@ -0,0 +1,155 @@
+use twilight_model::id::{
+    marker::{ChannelMarker, MessageMarker},
+    Id,
+};
+
+use crate::{client::bot::StarboardBot, constants, errors::StarboardResult, utils::embed};
+
+pub async fn debug_cache(
+    bot: &StarboardBot,
+    channel_id: Id<ChannelMarker>,
+    message_id: Id<MessageMarker>,
+) -> StarboardResult<()> {
+    let stats = bot.cache.stats().await;
+    let emb = embed::build()
+        .title("Cache Stats")
+        .description(format!("```\n{stats}```"))
+        .build();
+
+    bot.http
+        .create_message(channel_id)
+        .embeds(&[emb])?
+        .reply(message_id)
+        .await?;
+
+    Ok(())
+}
+
+pub async fn debug_events(
+    bot: &StarboardBot,
+    channel_id: Id<ChannelMarker>,
+    message_id: Id<MessageMarker>,
+) -> StarboardResult<()> {
+    let stats = bot.events.stats();
+    let emb = embed::build()
+        .title("Event Queue Stats")
+        .description(format!("```\n{stats}```"))
+        .build();
+
+    bot.http
+        .create_message(channel_id)
+        .embeds(&[emb])?
+        .reply(message_id)
+        .await?;
+
+    Ok(())
+}
+
+pub async fn debug_refreshes(
+    bot: &StarboardBot,
+    channel_id: Id<ChannelMarker>,
+    message_id: Id<MessageMarker>,
+) -> StarboardResult<()> {
+    let emb = embed::build()
+        .title("Refresh Stats")
+        .description(format!("```\n{}```", bot.refresh_stats))
+        .build();
+
+    bot.http
+        .create_message(channel_id)
+        .embeds(&[emb])?
+        .reply(message_id)
+        .await?;
+
+    Ok(())
+}
+
+pub async fn debug_limits(
+    bot: &StarboardBot,
+    channel_id: Id<ChannelMarker>,
+    message_id: Id<MessageMarker>,
+) -> StarboardResult<()> {
+    let emb = embed::build()
+        .title("Discord Limit Stats")
+        .description(format!("```\n{}```", bot.limit_stats))
+        .build();
+
+    bot.http
+        .create_message(channel_id)
+        .embeds(&[emb])?
+        .reply(message_id)
+        .await?;
+
+    Ok(())
+}
+
+pub async fn debug_votes(
+    bot: &StarboardBot,
+    channel_id: Id<ChannelMarker>,
+    message_id: Id<MessageMarker>,
+) -> StarboardResult<()> {
+    let stats = bot.vote_batcher.stats();
+    let emb = embed::build()
+        .title("Vote Batcher Stats")
+        .description(format!("```\n{stats}```"))
+        .build();
+
+    bot.http
+        .create_message(channel_id)
+        .embeds(&[emb])?
+        .reply(message_id)
+        .await?;
+
+    Ok(())
+}
+
+/// `star debug commands`: calls, errors and latencies per command, slowest
+/// first.
+pub async fn debug_commands(
+    bot: &StarboardBot,
+    channel_id: Id<ChannelMarker>,
+    message_id: Id<MessageMarker>,
+) -> StarboardResult<()> {
+    let mut commands = bot.metrics.commands();
+    commands.sort_by_key(|(_, metrics)| {
+        std::cmp::Reverse(metrics.latency_sum_ms / metrics.calls.max(1))
+    });
+
+    let mut stats = String::new();
+    for (command, metrics) in commands {
+        let (failures, calls) = metrics.recent_errors();
+        let quantile = |q| match metrics.latency_quantile(q) {
+            Some(quantile) => quantile.to_string(),
+            None => "-".to_string(),
+        };
+        let line = format!(
+            "{command}: {} calls, {} errors ({failures}/{calls} in {}m), p50 {}, p95 {}\n",
+            metrics.calls,
+            metrics.errors,
+            constants::COMMAND_ERROR_WINDOW.as_secs() / 60,
+            quantile(0.5),
+            quantile(0.95),
+        );
+        if stats.len() + line.len() > constants::MAX_EMBED_DESC_LENGTH - 10 {
+            stats.push_str("...\n");
+            break;
+        }
+        stats.push_str(&line);
+    }
+    if stats.is_empty() {
+        stats.push_str("No commands were run yet.\n");
+    }
+
+    let emb = embed::build()
+        .title("Command Stats")
+        .description(format!("```\n{stats}```"))
+        .build();
+
+    bot.http
+        .create_message(channel_id)
+        .embeds(&[emb])?
+        .reply(message_id)
+        .await?;
+
+    Ok(())
+}
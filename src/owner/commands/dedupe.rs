@@ -0,0 +1,42 @@
+use twilight_model::id::{
+    marker::{ChannelMarker, MessageMarker},
+    Id,
+};
+
+use crate::{
+    client::bot::StarboardBot, core::starboard::dedupe::delete_duplicate_posts,
+    errors::StarboardResult,
+};
+
+/// `star dedupe-posts [guild]`: deletes the queued duplicate posts now instead
+/// of waiting for the next restart.
+pub async fn dedupe_posts(
+    bot: &StarboardBot,
+    channel_id: Id<ChannelMarker>,
+    message_id: Id<MessageMarker>,
+    guild_id: Option<&str>,
+) -> StarboardResult<()> {
+    let guild_id = match guild_id.map(|id| id.parse::<i64>()) {
+        None => None,
+        Some(Ok(id)) => Some(id),
+        Some(Err(_)) => {
+            bot.http
+                .create_message(channel_id)
+                .content("Invalid guild id.")?
+                .reply(message_id)
+                .await?;
+            return Ok(());
+        }
+    };
+
+    bot.http.create_typing_trigger(channel_id).await?;
+    let count = delete_duplicate_posts(bot, guild_id).await?;
+
+    bot.http
+        .create_message(channel_id)
+        .content(&format!("Deleted {count} duplicate posts."))?
+        .reply(message_id)
+        .await?;
+
+    Ok(())
+}
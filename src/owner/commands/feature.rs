@@ -0,0 +1,126 @@
+use std::fmt::Write;
+
+use twilight_model::id::{
+    marker::{ChannelMarker, MessageMarker},
+    Id,
+};
+
+use crate::{
+    client::bot::StarboardBot,
+    constants,
+    core::features::{is_enabled, rollout_bucket, Flag},
+    database::FeatureFlag,
+    errors::StarboardResult,
+};
+
+const USAGE: &str = "Usage: `star feature status [guild id]`, `star feature set <flag> <0-100>` \
+or `star feature set <flag> <allow|deny|unlist> <guild id>`.";
+
+/// `star feature status [guild]`: lists the flags, and whether the guild has
+/// each of them.
+pub async fn status(
+    bot: &StarboardBot,
+    channel_id: Id<ChannelMarker>,
+    message_id: Id<MessageMarker>,
+    guild_id: Option<&str>,
+) -> StarboardResult<()> {
+    let guild_id = guild_id.and_then(|id| id.parse::<i64>().ok());
+    let rows = FeatureFlag::list_all(&bot.pool).await?;
+
+    let mut content = String::new();
+    for flag in Flag::ALL {
+        let row = rows.iter().find(|row| row.name == flag.name());
+        let _ = match row {
+            None => write!(content, "`{flag}`: {}% (default)", flag.default_rollout()),
+            Some(row) => write!(
+                content,
+                "`{flag}`: {}%, {} allowed, {} denied",
+                row.rollout,
+                row.allowlist.len(),
+                row.denylist.len()
+            ),
+        };
+        if let Some(guild_id) = guild_id {
+            let enabled = is_enabled(flag, row, guild_id);
+            let _ = write!(
+                content,
+                " - {} for {guild_id} (bucket {})",
+                if enabled { "on" } else { "off" },
+                rollout_bucket(flag, guild_id)
+            );
+        }
+        content.push('\n');
+    }
+
+    bot.http
+        .create_message(channel_id)
+        .content(&content)?
+        .reply(message_id)
+        .await?;
+
+    Ok(())
+}
+
+/// `star feature set <flag> ...`: changes a flag's rollout or lists.
+pub async fn set(
+    bot: &StarboardBot,
+    channel_id: Id<ChannelMarker>,
+    message_id: Id<MessageMarker>,
+    args: &[&str],
+) -> StarboardResult<()> {
+    let content = match set_inner(bot, args).await? {
+        Some(msg) => msg,
+        None => USAGE.to_string(),
+    };
+
+    bot.http
+        .create_message(channel_id)
+        .content(&content)?
+        .reply(message_id)
+        .await?;
+
+    Ok(())
+}
+
+async fn set_inner(bot: &StarboardBot, args: &[&str]) -> StarboardResult<Option<String>> {
+    let Some(flag) = args.first().and_then(|flag| flag.parse::<Flag>().ok()) else {
+        return Ok(None);
+    };
+    let name = flag.name();
+
+    let msg = match (args.get(1).copied(), args.get(2)) {
+        (Some(action @ ("allow" | "deny" | "unlist")), Some(guild_id)) => {
+            let Ok(guild_id) = guild_id.parse::<i64>() else {
+                return Ok(None);
+            };
+
+            FeatureFlag::create(&bot.pool, name, flag.default_rollout()).await?;
+            match action {
+                "allow" => FeatureFlag::allow(&bot.pool, name, guild_id).await?,
+                "deny" => FeatureFlag::deny(&bot.pool, name, guild_id).await?,
+                _ => FeatureFlag::unlist(&bot.pool, name, guild_id).await?,
+            }
+            format!("Updated `{flag}` for guild {guild_id} ({action}).")
+        }
+        (Some(rollout), None) => {
+            let Some(rollout) = rollout
+                .parse::<i16>()
+                .ok()
+                .filter(|r| (0..=100).contains(r))
+            else {
+                return Ok(None);
+            };
+
+            FeatureFlag::set_rollout(&bot.pool, name, rollout).await?;
+            format!("Set the rollout of `{flag}` to {rollout}%.")
+        }
+        _ => return Ok(None),
+    };
+
+    bot.cache.feature_flags.invalidate(&flag).await;
+
+    Ok(Some(format!(
+        "{msg} Other processes pick this up within {} seconds.",
+        constants::FEATURE_FLAGS_TTL.as_secs()
+    )))
+}
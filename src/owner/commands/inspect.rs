@@ -0,0 +1,176 @@
+use std::fmt::Write;
+
+use twilight_model::{
+    channel::message::{
+        component::{ActionRow, Button, ButtonStyle},
+        Component,
+    },
+    id::{
+        marker::{ChannelMarker, MessageMarker, UserMarker},
+        Id,
+    },
+};
+
+use crate::{
+    client::bot::StarboardBot,
+    core::{
+        emoji::EmojiCommon,
+        inspect::{inspect_message, CacheState, Inspection},
+    },
+    errors::StarboardResult,
+    utils::{embed, into_id::IntoId, message_link::parse_message_link},
+};
+
+/// `star inspect <message link>`: shows what the cache, the database and
+/// Discord know about a message, with buttons for the suggested fixes.
+pub async fn inspect(
+    bot: &StarboardBot,
+    channel_id: Id<ChannelMarker>,
+    message_id: Id<MessageMarker>,
+    author_id: Id<UserMarker>,
+    link: Option<&str>,
+) -> StarboardResult<()> {
+    let Some((inspect_channel_id, inspect_message_id)) = link.and_then(parse_message_link) else {
+        bot.http
+            .create_message(channel_id)
+            .content("Usage: `star inspect <message link>`.")?
+            .reply(message_id)
+            .await?;
+        return Ok(());
+    };
+
+    if let Some(retry) = bot.cooldowns.inspect.trigger(&author_id) {
+        bot.http
+            .create_message(channel_id)
+            .content(&format!(
+                "You're inspecting too quickly. Try again in {} seconds.",
+                retry.as_secs().max(1)
+            ))?
+            .reply(message_id)
+            .await?;
+        return Ok(());
+    }
+
+    let inspection = inspect_message(
+        bot,
+        inspect_channel_id.into_id(),
+        inspect_message_id.into_id(),
+    )
+    .await?;
+
+    let emb = build_embed(bot, &inspection);
+    let buttons: Vec<_> = inspection
+        .fixes()
+        .into_iter()
+        .map(|fix| {
+            Component::Button(Button {
+                custom_id: Some(fix.custom_id(inspection.channel_id, inspection.message_id)),
+                disabled: false,
+                emoji: None,
+                label: Some(fix.label().to_string()),
+                style: ButtonStyle::Primary,
+                url: None,
+            })
+        })
+        .collect();
+    let components = if buttons.is_empty() {
+        vec![]
+    } else {
+        vec![Component::ActionRow(ActionRow {
+            components: buttons,
+        })]
+    };
+
+    bot.http
+        .create_message(channel_id)
+        .embeds(&[emb])?
+        .components(&components)?
+        .reply(message_id)
+        .await?;
+
+    Ok(())
+}
+
+fn build_embed(
+    bot: &StarboardBot,
+    inspection: &Inspection,
+) -> twilight_model::channel::message::Embed {
+    let cache = match &inspection.cache {
+        CacheState::Missing => "not cached".to_string(),
+        CacheState::CachedAsDeleted => "cached as deleted".to_string(),
+        CacheState::Cached(msg) => format!(
+            "{} characters, {} attachments",
+            msg.content.chars().count(),
+            msg.attachments.len()
+        ),
+    };
+    let live = match &inspection.live_content {
+        None => "deleted or inaccessible".to_string(),
+        Some(content) => format!("{} characters", content.chars().count()),
+    };
+    let db = match &inspection.db_message {
+        None => "no row".to_string(),
+        Some(orig) => format!(
+            "author <@{}>, frozen: {}, trashed: {}, forced to: {:?}",
+            orig.author_id, orig.frozen, orig.trashed, orig.forced_to
+        ),
+    };
+
+    let mut reactions = String::new();
+    for reaction in &inspection.reactions {
+        let emoji = match &inspection.db_message {
+            Some(orig) => reaction
+                .emoji
+                .clone()
+                .into_readable(bot, orig.guild_id.into_id()),
+            None => reaction.emoji.clone().into_stored(),
+        };
+        let classification = if reaction.classification.is_empty() {
+            "not a vote emoji".to_string()
+        } else {
+            reaction.classification.join(", ")
+        };
+        writeln!(reactions, "{emoji} ×{}: {classification}", reaction.count).unwrap();
+    }
+    if reactions.is_empty() {
+        reactions.push_str("none");
+    }
+
+    let mut starboards = String::new();
+    for sb in &inspection.starboards {
+        let post = match sb.post_id {
+            None => "no post".to_string(),
+            Some(post_id) if sb.post_missing => format!("post `{post_id}` (deleted)"),
+            Some(post_id) => format!("post `{post_id}`"),
+        };
+        writeln!(
+            starboards,
+            "'{}': {} points stored, {} by reactions, {post}",
+            sb.name, sb.points, sb.live_points
+        )
+        .unwrap();
+    }
+    if starboards.is_empty() {
+        starboards.push_str("none");
+    }
+
+    let mut diff = "```diff\n".to_string();
+    for mismatch in &inspection.mismatches {
+        writeln!(diff, "- {mismatch} (fix: {})", mismatch.fix().label()).unwrap();
+    }
+    if inspection.mismatches.is_empty() {
+        diff.push_str("+ no mismatches found\n");
+    }
+    diff.push_str("```");
+
+    embed::build()
+        .title(format!(
+            "Inspecting {}-{}",
+            inspection.channel_id, inspection.message_id
+        ))
+        .description(format!(
+            "**Cache:** {cache}\n**Discord:** {live}\n**Database:** {db}\n\n\
+            **Reactions:**\n{reactions}\n**Starboards:**\n{starboards}\n{diff}"
+        ))
+        .build()
+}
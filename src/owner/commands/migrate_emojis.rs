@@ -0,0 +1,43 @@
+use twilight_model::id::{
+    marker::{ChannelMarker, MessageMarker},
+    Id,
+};
+
+use crate::{client::bot::StarboardBot, core::emoji_migration, errors::StarboardResult};
+
+/// `star migrate-emojis [--dry-run|--confirm]`: reports emoji settings stored
+/// in legacy formats, and rewrites them with `--confirm`.
+pub async fn migrate_emojis(
+    bot: &StarboardBot,
+    channel_id: Id<ChannelMarker>,
+    message_id: Id<MessageMarker>,
+    flag: Option<&str>,
+) -> StarboardResult<()> {
+    let write = match flag {
+        None | Some("--dry-run") => false,
+        Some("--confirm") => true,
+        Some(_) => {
+            bot.http
+                .create_message(channel_id)
+                .content("Usage: `star migrate-emojis [--dry-run|--confirm]`.")?
+                .reply(message_id)
+                .await?;
+            return Ok(());
+        }
+    };
+
+    bot.http.create_typing_trigger(channel_id).await?;
+    let report = emoji_migration::migrate_emojis(bot, write).await?;
+
+    let mut msg = format!("```\n{report}```");
+    if !write && !report.guilds.is_empty() {
+        msg.push_str("Run `star migrate-emojis --confirm` to rewrite them.");
+    }
+    bot.http
+        .create_message(channel_id)
+        .content(&msg)?
+        .reply(message_id)
+        .await?;
+
+    Ok(())
+}
@@ -1,2 +1,12 @@
+pub mod cache;
+pub mod check_config;
+pub mod debug;
+pub mod dedupe;
+pub mod feature;
+pub mod inspect;
 // pub mod embed_test;
+pub mod migrate_emojis;
+pub mod purge_guild;
+pub mod reload;
+pub mod shards;
 pub mod sql;
@@ -0,0 +1,48 @@
+use twilight_model::id::{
+    marker::{ChannelMarker, MessageMarker},
+    Id,
+};
+
+use crate::{
+    client::bot::StarboardBot, core::guild_purge::purge_guild as purge, database::DbGuild,
+    errors::StarboardResult, utils::into_id::IntoId,
+};
+
+/// `star purge-guild <guild>`: deletes everything stored for a guild the bot
+/// isn't in anymore, without waiting for `GUILD_RETENTION_DAYS`.
+pub async fn purge_guild(
+    bot: &StarboardBot,
+    channel_id: Id<ChannelMarker>,
+    message_id: Id<MessageMarker>,
+    guild_id: Option<&str>,
+) -> StarboardResult<()> {
+    let Some(guild_id) = guild_id.and_then(|id| id.parse::<i64>().ok()) else {
+        bot.http
+            .create_message(channel_id)
+            .content("Usage: `star purge-guild <guild id>`.")?
+            .reply(message_id)
+            .await?;
+        return Ok(());
+    };
+
+    let content = if bot.cache.guilds.contains_key(&guild_id.into_id()) {
+        "I'm still in that guild.".to_string()
+    } else if DbGuild::get(&bot.pool, guild_id).await?.is_none() {
+        "That guild isn't in the database.".to_string()
+    } else {
+        bot.http.create_typing_trigger(channel_id).await?;
+        let purged = purge(bot, guild_id).await?;
+        format!(
+            "Purged guild {guild_id} ({} votes, {} messages).",
+            purged.votes, purged.messages
+        )
+    };
+
+    bot.http
+        .create_message(channel_id)
+        .content(&content)?
+        .reply(message_id)
+        .await?;
+
+    Ok(())
+}
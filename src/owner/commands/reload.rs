@@ -0,0 +1,66 @@
+use twilight_model::id::{
+    marker::{ChannelMarker, GuildMarker, MessageMarker},
+    Id,
+};
+
+use crate::{
+    client::bot::StarboardBot, errors::StarboardResult,
+    interactions::commands::register::sync_commands,
+};
+
+/// `star reload commands`: registers the commands in `MAIN_GUILD`, where
+/// changes show up immediately instead of waiting for global commands to
+/// propagate. Only available in development.
+pub async fn reload_commands(
+    bot: &StarboardBot,
+    channel_id: Id<ChannelMarker>,
+    message_id: Id<MessageMarker>,
+) -> StarboardResult<()> {
+    let msg = match reload_guild(bot.config.development, bot.config.main_guild) {
+        Ok(guild_id) => {
+            let count = sync_commands(bot, Some(guild_id)).await?;
+            format!("Registered {count} commands in {guild_id}.")
+        }
+        Err(why) => why.to_string(),
+    };
+
+    bot.http
+        .create_message(channel_id)
+        .content(&msg)?
+        .reply(message_id)
+        .await?;
+
+    Ok(())
+}
+
+/// The guild to register the commands in, or why they can't be reloaded.
+fn reload_guild(
+    development: bool,
+    main_guild: Option<u64>,
+) -> Result<Id<GuildMarker>, &'static str> {
+    if !development {
+        Err("`star reload` is only available in development.")
+    } else if let Some(guild_id) = main_guild.and_then(Id::new_checked) {
+        Ok(guild_id)
+    } else {
+        Err("`MAIN_GUILD` isn't set, so there's no guild to register the commands in.")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_in_development() {
+        assert!(reload_guild(false, Some(1)).is_err());
+        assert!(reload_guild(false, None).is_err());
+    }
+
+    #[test]
+    fn needs_main_guild() {
+        assert!(reload_guild(true, None).is_err());
+        assert!(reload_guild(true, Some(0)).is_err());
+        assert_eq!(reload_guild(true, Some(1)), Ok(Id::new(1)));
+    }
+}
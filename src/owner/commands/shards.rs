@@ -0,0 +1,97 @@
+use chrono::Utc;
+use twilight_model::id::{
+    marker::{ChannelMarker, MessageMarker},
+    Id,
+};
+
+use crate::{
+    client::{
+        bot::StarboardBot,
+        shards::{parse_shard_range, shard_ids},
+    },
+    constants,
+    database::ShardStatus,
+    errors::StarboardResult,
+};
+
+/// `star drain <shards>`: marks the shards (e.g. "4..8") as draining, so they
+/// can be taken over by a process started with `--takeover`.
+pub async fn drain(
+    bot: &StarboardBot,
+    channel_id: Id<ChannelMarker>,
+    message_id: Id<MessageMarker>,
+    range: Option<&str>,
+) -> StarboardResult<()> {
+    let Some(range) = range.and_then(parse_shard_range) else {
+        bot.http
+            .create_message(channel_id)
+            .content("Usage: `star drain <start>..<end>`.")?
+            .reply(message_id)
+            .await?;
+        return Ok(());
+    };
+
+    let marked = ShardStatus::set_draining(&bot.pool, &shard_ids(range.clone())).await?;
+    let msg = if marked.is_empty() {
+        format!(
+            "None of the shards {}..{} are running.",
+            range.start, range.end
+        )
+    } else {
+        format!(
+            "Marked shards {marked:?} as draining. Use `star shards` to see when they're drained."
+        )
+    };
+    bot.http
+        .create_message(channel_id)
+        .content(&msg)?
+        .reply(message_id)
+        .await?;
+
+    Ok(())
+}
+
+/// `star shards`: lists the owner and drain state of each shard.
+pub async fn shard_status(
+    bot: &StarboardBot,
+    channel_id: Id<ChannelMarker>,
+    message_id: Id<MessageMarker>,
+) -> StarboardResult<()> {
+    let timeout = chrono::Duration::seconds(constants::SHARD_HEARTBEAT_TIMEOUT);
+    let now = Utc::now();
+
+    let mut lines = Vec::new();
+    for row in ShardStatus::list(&bot.pool).await? {
+        let state = if row.owner.is_none() {
+            "unowned"
+        } else if row.heartbeat_at <= now - timeout {
+            "stale"
+        } else if row.drained {
+            "drained"
+        } else if row.draining {
+            "draining"
+        } else {
+            "running"
+        };
+        lines.push(format!(
+            "{:>4} {:<9} {} (heartbeat {}s ago)",
+            row.shard_id,
+            state,
+            row.owner.as_deref().unwrap_or("-"),
+            (now - row.heartbeat_at).num_seconds(),
+        ));
+    }
+
+    let msg = if lines.is_empty() {
+        "No shards have been claimed yet.".to_string()
+    } else {
+        format!("```\n{}\n```", lines.join("\n"))
+    };
+    bot.http
+        .create_message(channel_id)
+        .content(&msg)?
+        .reply(message_id)
+        .await?;
+
+    Ok(())
+}
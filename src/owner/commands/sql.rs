@@ -4,6 +4,7 @@ use std::{
     time::{Duration, Instant},
 };
 
+use futures::{StreamExt, TryStreamExt};
 use sqlx::{postgres::PgRow, Column, Executor, Row, ValueRef};
 use twilight_model::id::{
     marker::{ChannelMarker, MessageMarker},
@@ -11,16 +12,26 @@ use twilight_model::id::{
 };
 
 use crate::{
-    cache::models::message::CachedMessage, client::bot::StarboardBot, concat_format,
+    cache::models::message::CachedMessage, client::bot::StarboardBot, concat_format, constants,
     errors::StarboardResult, owner::code_block::parse_code_blocks,
 };
 
+/// `star sql [--write]`: runs the queries in the message's code blocks.
+/// Without `--write` the transaction is read-only, and changes are only ever
+/// committed in development (and with `rollback=false`).
+///
+/// Read-only blocks are sent as prepared statements, which can only hold a
+/// single statement. Otherwise something like `COMMIT; DELETE ...` would end
+/// the read-only transaction and autocommit the rest.
+///
+/// Results are only sent as a reply, never to the error channel.
 pub async fn run_sql(
     bot: &StarboardBot,
     channel_id: Id<ChannelMarker>,
     message_id: Id<MessageMarker>,
     message: &CachedMessage,
     is_edit: bool,
+    write: bool,
 ) -> StarboardResult<()> {
     let to_edit = if !is_edit {
         bot.http.create_typing_trigger(channel_id).await?;
@@ -35,6 +46,17 @@ pub async fn run_sql(
     let mut results = Vec::new();
 
     let mut tx = bot.pool.begin().await?;
+    if !write {
+        tx.execute("SET TRANSACTION READ ONLY").await?;
+    }
+    tx.execute(
+        format!(
+            "SET LOCAL statement_timeout = {}",
+            constants::SQL_STATEMENT_TIMEOUT.as_millis()
+        )
+        .as_str(),
+    )
+    .await?;
     for (code, meta) in &blocks {
         if meta.get("rollback").map_or(true, |v| v.parse().unwrap()) {
             rollback = true;
@@ -49,7 +71,19 @@ pub async fn run_sql(
         for _ in 0..total_execs {
             let elapsed = if return_results {
                 let start = Instant::now();
-                let rows = tx.fetch_all(code.as_str()).await;
+                // fetch one extra row to know whether there are more
+                let rows: Result<Vec<_>, _> = if write {
+                    tx.fetch(code.as_str())
+                        .take(constants::SQL_MAX_ROWS + 1)
+                        .try_collect()
+                        .await
+                } else {
+                    sqlx::query(code)
+                        .fetch(&mut tx)
+                        .take(constants::SQL_MAX_ROWS + 1)
+                        .try_collect()
+                        .await
+                };
 
                 let elapsed = start.elapsed();
                 match rows {
@@ -63,7 +97,11 @@ pub async fn run_sql(
                 elapsed
             } else {
                 let start = Instant::now();
-                let ret = tx.execute(code.as_str()).await;
+                let ret = if write {
+                    tx.execute(code.as_str()).await
+                } else {
+                    sqlx::query(code).execute(&mut tx).await
+                };
                 if let Err(why) = ret {
                     err = Some(why.to_string());
                 }
@@ -83,7 +121,7 @@ pub async fn run_sql(
 
     let mut final_result = String::new();
 
-    if rollback || !bot.config.development {
+    if rollback || !write || !bot.config.development {
         tx.rollback().await?;
     } else {
         tx.commit().await?;
@@ -98,10 +136,8 @@ pub async fn run_sql(
 
         if let Some(inspect) = result.inspect {
             final_result.push_str("```rs\n");
-            let mut x = 0;
-            for row in inspect {
-                x += 1;
-                if x == 5 {
+            for (x, row) in inspect.into_iter().enumerate() {
+                if x == constants::SQL_MAX_ROWS {
                     final_result.push_str("- and more...");
                     break;
                 }
@@ -51,7 +51,76 @@ pub async fn handle_message(
 
     // match second token to a command, if any
     let ret = match tokens[1] {
-        "sql" => commands::sql::run_sql(bot, channel_id, message_id, message, is_edit).await,
+        "sql" => {
+            let write = tokens.get(2) == Some(&"--write");
+            commands::sql::run_sql(bot, channel_id, message_id, message, is_edit, write).await
+        }
+        "cache-invalidate" => match tokens.get(2) {
+            Some(&"guild") => {
+                commands::cache::invalidate_guild(bot, channel_id, message_id, tokens.get(3).copied())
+                    .await
+            }
+            _ => Ok(()),
+        },
+        "reload-premium" => {
+            commands::cache::reload_premium(bot, channel_id, message_id, tokens.get(2).copied())
+                .await
+        }
+        "debug" => match tokens.get(2) {
+            Some(&"cache") => commands::debug::debug_cache(bot, channel_id, message_id).await,
+            Some(&"events") => commands::debug::debug_events(bot, channel_id, message_id).await,
+            Some(&"limits") => commands::debug::debug_limits(bot, channel_id, message_id).await,
+            Some(&"votes") => commands::debug::debug_votes(bot, channel_id, message_id).await,
+            Some(&"refreshes") => {
+                commands::debug::debug_refreshes(bot, channel_id, message_id).await
+            }
+            Some(&"commands") => {
+                commands::debug::debug_commands(bot, channel_id, message_id).await
+            }
+            _ => Ok(()),
+        },
+        "reload" => match tokens.get(2) {
+            Some(&"commands") => {
+                commands::reload::reload_commands(bot, channel_id, message_id).await
+            }
+            _ => Ok(()),
+        },
+        "check-config" => {
+            commands::check_config::check_config(bot, channel_id, message_id, tokens.get(2).copied())
+                .await
+        }
+        "dedupe-posts" => {
+            commands::dedupe::dedupe_posts(bot, channel_id, message_id, tokens.get(2).copied())
+                .await
+        }
+        "drain" => {
+            commands::shards::drain(bot, channel_id, message_id, tokens.get(2).copied()).await
+        }
+        "purge-guild" => {
+            commands::purge_guild::purge_guild(bot, channel_id, message_id, tokens.get(2).copied())
+                .await
+        }
+        "feature" => match tokens.get(2) {
+            Some(&"status") => {
+                commands::feature::status(bot, channel_id, message_id, tokens.get(3).copied()).await
+            }
+            Some(&"set") => commands::feature::set(bot, channel_id, message_id, &tokens[3..]).await,
+            _ => Ok(()),
+        },
+        "shards" => commands::shards::shard_status(bot, channel_id, message_id).await,
+        "inspect" => {
+            commands::inspect::inspect(bot, channel_id, message_id, author_id, tokens.get(2).copied())
+                .await
+        }
+        "migrate-emojis" => {
+            commands::migrate_emojis::migrate_emojis(
+                bot,
+                channel_id,
+                message_id,
+                tokens.get(2).copied(),
+            )
+            .await
+        }
         // "embed" => commands::embed_test::test_starboard_embed(bot, event).await?,
         _ => Ok(()),
     };
@@ -38,6 +38,14 @@ where
         self.map.contains_key(key)
     }
 
+    /// Folds over every entry. Like `with`, the closure must not await, since
+    /// shards of the map are locked while iterating.
+    pub fn fold<R>(&self, init: R, mut f: impl FnMut(R, &K, &V) -> R) -> R {
+        self.map
+            .iter()
+            .fold(init, |acc, entry| f(acc, entry.key(), entry.value()))
+    }
+
     pub fn len(&self) -> usize {
         self.map.len()
     }
@@ -72,4 +80,12 @@ where
     pub fn contains(&self, key: &K) -> bool {
         self.set.contains(key)
     }
+
+    pub fn len(&self) -> usize {
+        self.set.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.set.is_empty()
+    }
 }
@@ -6,6 +6,15 @@ use twilight_model::{
     util::ImageHash,
 };
 
+/// Animated hashes (prefixed with `a_`) are served as gifs.
+fn extension(hash: &ImageHash) -> &'static str {
+    if hash.is_animated() {
+        "gif"
+    } else {
+        "png"
+    }
+}
+
 pub trait ImageHashAvatar {
     fn global_avatar(&self, user_id: Id<UserMarker>) -> String;
     fn guild_avatar(&self, user_id: Id<UserMarker>, guild_id: Id<GuildMarker>) -> String;
@@ -13,10 +22,16 @@ pub trait ImageHashAvatar {
 
 impl ImageHashAvatar for ImageHash {
     fn global_avatar(&self, user_id: Id<UserMarker>) -> String {
-        format!("https://cdn.discordapp.com/avatars/{user_id}/{self}.png")
+        format!(
+            "https://cdn.discordapp.com/avatars/{user_id}/{self}.{}",
+            extension(self)
+        )
     }
 
     fn guild_avatar(&self, user_id: Id<UserMarker>, guild_id: Id<GuildMarker>) -> String {
-        format!("https://cdn.discordapp.com/guilds/{guild_id}/users/{user_id}/avatars/{self}.png")
+        format!(
+            "https://cdn.discordapp.com/guilds/{guild_id}/users/{user_id}/avatars/{self}.{}",
+            extension(self)
+        )
     }
 }
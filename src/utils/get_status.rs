@@ -1,4 +1,4 @@
-use twilight_http::error::ErrorType;
+use twilight_http::{api_error::ApiError, error::ErrorType};
 
 pub fn get_status(error: &twilight_http::Error) -> Option<u16> {
     if let ErrorType::Response {
@@ -12,3 +12,16 @@ pub fn get_status(error: &twilight_http::Error) -> Option<u16> {
         None
     }
 }
+
+/// Discord's error code, like 10008 for "Unknown Message".
+pub fn get_error_code(error: &twilight_http::Error) -> Option<u64> {
+    if let ErrorType::Response {
+        error: ApiError::General(error),
+        ..
+    } = error.kind()
+    {
+        Some(error.code)
+    } else {
+        None
+    }
+}
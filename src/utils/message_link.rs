@@ -17,14 +17,19 @@ pub fn parse_message_link(link: &str) -> Option<(i64, i64)> {
         return Some((channel_id, message_id));
     }
 
+    find_message_link(link)
+}
+
+/// Finds the first message link anywhere in `text`.
+pub fn find_message_link(text: &str) -> Option<(i64, i64)> {
     lazy_static! {
         static ref RE: Regex = Regex::new(r#"/channels/(\d+)/(\d+)/(\d+)"#).unwrap();
     }
 
-    let ret = RE.captures(link)?;
+    let ret = RE.captures(text)?;
 
-    let channel_id: i64 = ret.get(2).unwrap().as_str().parse().unwrap();
-    let message_id: i64 = ret.get(3).unwrap().as_str().parse().unwrap();
+    let channel_id: i64 = ret.get(2).unwrap().as_str().parse().ok()?;
+    let message_id: i64 = ret.get(3).unwrap().as_str().parse().ok()?;
 
     Some((channel_id, message_id))
 }
@@ -0,0 +1,202 @@
+//! Parses the ways users refer to a message in command options: jump links,
+//! the `channel-message` pair copied by shift-clicking "Copy ID", or a bare
+//! message id for a message in the current channel.
+
+use std::fmt;
+
+use lazy_static::lazy_static;
+use regex::Regex;
+use twilight_model::id::{
+    marker::{ChannelMarker, MessageMarker},
+    Id,
+};
+
+/// The hosts Discord serves jump links on, including the canary and ptb
+/// clients.
+const DISCORD_HOSTS: &[&str] = &[
+    "discord.com",
+    "canary.discord.com",
+    "ptb.discord.com",
+    "discordapp.com",
+    "canary.discordapp.com",
+    "ptb.discordapp.com",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParseError {
+    /// Nothing was entered.
+    Empty,
+    /// The input isn't a link, id pair or id.
+    NotAMessage,
+    /// A link to something other than Discord.
+    NotDiscord,
+    /// A link to a channel rather than a message.
+    ChannelLink,
+    /// An id that isn't a valid snowflake, e.g. zero or too large.
+    InvalidId,
+    /// A bare message id, but there is no current channel to look in.
+    NoChannel,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let msg = match self {
+            Self::Empty | Self::NotAMessage => {
+                "That doesn't look like a message link. Right-click a message and use \
+                \"Copy Message Link\"."
+            }
+            Self::NotDiscord => {
+                "That isn't a Discord link. Right-click a message and use \"Copy Message Link\"."
+            }
+            Self::ChannelLink => {
+                "That's a link to a channel, not a message. Right-click a message and use \
+                \"Copy Message Link\"."
+            }
+            Self::InvalidId => "That message link contains an invalid ID.",
+            Self::NoChannel => {
+                "I don't know which channel that message is in. Use a message link instead."
+            }
+        };
+        f.write_str(msg)
+    }
+}
+
+/// Parses a reference to a message. Bare message ids are assumed to be in
+/// `current_channel`.
+pub fn parse_message_ref(
+    input: &str,
+    current_channel: Option<Id<ChannelMarker>>,
+) -> Result<(Id<ChannelMarker>, Id<MessageMarker>), ParseError> {
+    lazy_static! {
+        static ref LINK: Regex =
+            Regex::new(r#"^https?://([^/]+)/channels/(?:\d+|@me)/(\d+)(?:/(\d+))?/?(?:[?#].*)?$"#)
+                .unwrap();
+    }
+
+    // Discord wraps links in <> when embeds are suppressed
+    let input = input.trim();
+    let input = input
+        .strip_prefix('<')
+        .and_then(|i| i.strip_suffix('>'))
+        .unwrap_or(input);
+    if input.is_empty() {
+        return Err(ParseError::Empty);
+    }
+
+    if let Some(caps) = LINK.captures(input) {
+        let host = caps[1].to_ascii_lowercase();
+        if !DISCORD_HOSTS.contains(&host.as_str()) {
+            return Err(ParseError::NotDiscord);
+        }
+        let Some(message_id) = caps.get(3) else {
+            return Err(ParseError::ChannelLink);
+        };
+
+        return Ok((parse_id(&caps[2])?, parse_id(message_id.as_str())?));
+    }
+    if input.contains("://") {
+        return Err(ParseError::NotAMessage);
+    }
+
+    if let Some((channel_id, message_id)) = input.split_once('-') {
+        if !is_digits(channel_id) || !is_digits(message_id) {
+            return Err(ParseError::NotAMessage);
+        }
+        return Ok((parse_id(channel_id)?, parse_id(message_id)?));
+    }
+
+    if is_digits(input) {
+        let channel_id = current_channel.ok_or(ParseError::NoChannel)?;
+        return Ok((channel_id, parse_id(input)?));
+    }
+
+    Err(ParseError::NotAMessage)
+}
+
+fn is_digits(text: &str) -> bool {
+    !text.is_empty() && text.bytes().all(|b| b.is_ascii_digit())
+}
+
+fn parse_id<T>(text: &str) -> Result<Id<T>, ParseError> {
+    text.parse::<u64>()
+        .ok()
+        .filter(|id| *id <= i64::MAX as u64)
+        .and_then(Id::new_checked)
+        .ok_or(ParseError::InvalidId)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ids(
+        channel_id: u64,
+        message_id: u64,
+    ) -> Result<(Id<ChannelMarker>, Id<MessageMarker>), ParseError> {
+        Ok((Id::new(channel_id), Id::new(message_id)))
+    }
+
+    #[test]
+    fn links() {
+        for link in [
+            "https://discord.com/channels/1/2/3",
+            "https://canary.discord.com/channels/1/2/3",
+            "https://PTB.discordapp.com/channels/1/2/3/",
+            "http://discord.com/channels/1/2/3?x=y",
+            "<https://discord.com/channels/1/2/3>",
+            "  https://discord.com/channels/@me/2/3  ",
+        ] {
+            assert_eq!(parse_message_ref(link, None), ids(2, 3), "{link}");
+        }
+    }
+
+    #[test]
+    fn bad_links() {
+        assert_eq!(
+            parse_message_ref("https://example.com/channels/1/2/3", None),
+            Err(ParseError::NotDiscord)
+        );
+        assert_eq!(
+            parse_message_ref("https://discord.com/channels/1/2", None),
+            Err(ParseError::ChannelLink)
+        );
+        assert_eq!(
+            parse_message_ref("https://discord.com/channels/1/2/0", None),
+            Err(ParseError::InvalidId)
+        );
+        assert_eq!(
+            parse_message_ref("https://discord.com/invite/abc", None),
+            Err(ParseError::NotAMessage)
+        );
+    }
+
+    #[test]
+    fn id_pairs() {
+        assert_eq!(parse_message_ref("2-3", None), ids(2, 3));
+        assert_eq!(parse_message_ref("2-x", None), Err(ParseError::NotAMessage));
+        assert_eq!(
+            parse_message_ref("2-9223372036854775808", None),
+            Err(ParseError::InvalidId)
+        );
+    }
+
+    #[test]
+    fn bare_ids() {
+        assert_eq!(parse_message_ref("3", Some(Id::new(2))), ids(2, 3));
+        assert_eq!(parse_message_ref("3", None), Err(ParseError::NoChannel));
+        assert_eq!(
+            parse_message_ref("0", Some(Id::new(2))),
+            Err(ParseError::InvalidId)
+        );
+    }
+
+    #[test]
+    fn not_a_message() {
+        assert_eq!(parse_message_ref(" ", None), Err(ParseError::Empty));
+        assert_eq!(parse_message_ref("<>", None), Err(ParseError::Empty));
+        assert_eq!(
+            parse_message_ref("hello", None),
+            Err(ParseError::NotAMessage)
+        );
+    }
+}
@@ -4,10 +4,12 @@ pub mod dashset_lock;
 pub mod div_ceil;
 pub mod dm;
 pub mod embed;
+pub mod fnv;
 pub mod get_status;
 pub mod id_as_i64;
 pub mod into_id;
 pub mod message_link;
+pub mod message_ref;
 pub mod notify;
 pub mod pg_error;
 pub mod snowflake_age;
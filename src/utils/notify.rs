@@ -3,7 +3,9 @@ use twilight_model::{
     id::{marker::UserMarker, Id},
 };
 
-use crate::{client::bot::StarboardBot, errors::StarboardResult};
+use crate::{
+    client::bot::StarboardBot, errors::StarboardResult, interactions::custom_id::CustomId,
+};
 
 use super::dm;
 
@@ -13,8 +15,7 @@ pub async fn notify(
     message: &str,
 ) -> StarboardResult<()> {
     if bot.config.development {
-        println!("Development, skipping notification:");
-        println!("{message}");
+        tracing::info!("Development, skipping notification:\n{message}");
         return Ok(());
     }
 
@@ -23,7 +24,7 @@ pub async fn notify(
             label: Some("Dismiss".to_string()),
             url: None,
             style: ButtonStyle::Secondary,
-            custom_id: Some("stateless::dismiss_notification".to_string()),
+            custom_id: Some(CustomId::DismissNotification.encode()),
             disabled: false,
             emoji: None,
         })],
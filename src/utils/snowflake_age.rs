@@ -1,5 +1,6 @@
 use std::time::Duration;
 
+use chrono::{DateTime, Utc};
 use twilight_util::snowflake::Snowflake;
 
 pub trait SnowflakeAge {
@@ -14,3 +15,45 @@ impl<T: Snowflake> SnowflakeAge for T {
         Duration::from_millis(age_millis as u64)
     }
 }
+
+const DISCORD_EPOCH_MS: i64 = 1_420_070_400_000;
+
+/// The smallest snowflake created at `time`, so that ids can be compared by
+/// when they were created.
+pub fn snowflake_at(time: DateTime<Utc>) -> i64 {
+    (time.timestamp_millis() - DISCORD_EPOCH_MS).max(0) << 22
+}
+
+/// When the snowflake was created.
+pub fn snowflake_time(id: i64) -> DateTime<Utc> {
+    DateTime::from_timestamp_millis((id >> 22) + DISCORD_EPOCH_MS).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    #[test]
+    fn snowflake_round_trip() {
+        let time = Utc.with_ymd_and_hms(2023, 7, 15, 12, 0, 0).unwrap();
+
+        assert_eq!(snowflake_time(snowflake_at(time)), time);
+        // any id created in the same millisecond
+        assert_eq!(snowflake_time(snowflake_at(time) + 4095), time);
+    }
+
+    #[test]
+    fn known_snowflake() {
+        // the id from Discord's documentation
+        let time = snowflake_time(175928847299117063);
+        assert_eq!(time.timestamp_millis(), 1462015105796);
+    }
+
+    #[test]
+    fn before_discord_epoch() {
+        let time = Utc.with_ymd_and_hms(2010, 1, 1, 0, 0, 0).unwrap();
+        assert_eq!(snowflake_at(time), 0);
+    }
+}
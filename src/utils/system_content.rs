@@ -8,6 +8,24 @@ use twilight_model::channel::{message::MessageType, Message};
 
 use crate::concat_format;
 
+/// Whether the message was sent by a user, as opposed to a system message such
+/// as a join or boost notification.
+pub fn is_user_message(kind: MessageType) -> bool {
+    matches!(
+        kind,
+        MessageType::Regular
+            | MessageType::Reply
+            | MessageType::ChatInputCommand
+            | MessageType::ContextMenuCommand
+    )
+}
+
+/// Whether the message can be shown on a starboard at all. Call notifications
+/// have nothing meaningful to render.
+pub fn is_renderable(kind: MessageType) -> bool {
+    !matches!(kind, MessageType::Call)
+}
+
 pub trait SystemContent {
     fn system_content(&self) -> String;
 }
@@ -21,7 +39,7 @@ impl SystemContent for Message {
             | MessageType::ChatInputCommand
             | MessageType::ContextMenuCommand
             | MessageType::AutoModerationAction => self.content.to_string(),
-            MessageType::Call => unreachable!(),
+            MessageType::Call => format!("**{}** started a call.", self.author.name),
             // doesn't handle group DMs
             MessageType::RecipientAdd => format!(
                 "**{}** added **{}** to the thread.",
@@ -162,9 +180,29 @@ impl SystemContent for Message {
             ),
             MessageType::StageSpeaker => format!("{} is now a speaker.", self.author.name),
             unkown => {
-                eprintln!("Warning: unkown message type {unkown:?}");
+                tracing::warn!("Unkown message type {unkown:?}");
                 self.content.clone()
             }
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn user_messages() {
+        assert!(is_user_message(MessageType::Regular));
+        assert!(is_user_message(MessageType::Reply));
+        assert!(!is_user_message(MessageType::UserJoin));
+        assert!(!is_user_message(MessageType::ChannelMessagePinned));
+    }
+
+    #[test]
+    fn renderable_messages() {
+        assert!(is_renderable(MessageType::Regular));
+        assert!(is_renderable(MessageType::UserJoin));
+        assert!(!is_renderable(MessageType::Call));
+    }
+}
@@ -14,7 +14,10 @@ use twilight_model::{
 use crate::{
     client::bot::StarboardBot,
     errors::StarboardResult,
-    interactions::context::{CommandCtx, ComponentCtx},
+    interactions::{
+        context::{CommandCtx, ComponentCtx},
+        custom_id::view,
+    },
 };
 
 use super::wait_for::wait_for_component;
@@ -22,7 +25,7 @@ use super::wait_for::wait_for_component;
 pub fn components(danger: bool) -> Vec<Component> {
     let buttons = vec![
         Component::Button(Button {
-            custom_id: Some("confirm::no".to_string()),
+            custom_id: Some(view::CONFIRM_NO.to_string()),
             disabled: false,
             emoji: None,
             label: Some("Cancel".to_string()),
@@ -30,7 +33,7 @@ pub fn components(danger: bool) -> Vec<Component> {
             url: None,
         }),
         Component::Button(Button {
-            custom_id: Some("confirm::yes".to_string()),
+            custom_id: Some(view::CONFIRM_YES.to_string()),
             disabled: false,
             emoji: None,
             label: Some("Confirm".to_string()),
@@ -57,7 +60,7 @@ pub async fn wait_for_result(
 ) -> Option<(ComponentCtx, bool)> {
     let btn_ctx = wait_for_component(
         bot,
-        &["confirm::yes", "confirm::no"],
+        &[view::CONFIRM_YES, view::CONFIRM_NO],
         message_id,
         user_id,
         30,
@@ -65,8 +68,8 @@ pub async fn wait_for_result(
     .await?;
 
     let conf = match &*btn_ctx.data.custom_id {
-        "confirm::yes" => true,
-        "confirm::no" => false,
+        view::CONFIRM_YES => true,
+        view::CONFIRM_NO => false,
         _ => unreachable!(),
     };
 
@@ -11,7 +11,10 @@ use twilight_model::{
 
 use crate::{
     errors::StarboardResult,
-    interactions::context::{CommandCtx, ComponentCtx},
+    interactions::{
+        context::{ComponentCtx, Ctx},
+        custom_id::view,
+    },
 };
 
 use super::wait_for::wait_for_component;
@@ -19,7 +22,7 @@ use super::wait_for::wait_for_component;
 pub fn components(current_page: usize, last_page: usize, done: bool) -> Vec<Component> {
     let buttons = vec![
         Component::Button(Button {
-            custom_id: Some("paginator::to_start".to_string()),
+            custom_id: Some(view::PAGINATOR_TO_START.to_string()),
             disabled: current_page == 0 || done,
             emoji: None,
             label: Some("<<".to_string()),
@@ -27,7 +30,7 @@ pub fn components(current_page: usize, last_page: usize, done: bool) -> Vec<Comp
             url: None,
         }),
         Component::Button(Button {
-            custom_id: Some("paginator::back".to_string()),
+            custom_id: Some(view::PAGINATOR_BACK.to_string()),
             disabled: current_page == 0 || done,
             emoji: None,
             label: Some("<".to_string()),
@@ -35,7 +38,7 @@ pub fn components(current_page: usize, last_page: usize, done: bool) -> Vec<Comp
             url: None,
         }),
         Component::Button(Button {
-            custom_id: Some("paginator::meta".to_string()),
+            custom_id: Some(view::PAGINATOR_META.to_string()),
             disabled: true,
             emoji: None,
             label: Some(format!("{}/{}", current_page + 1, last_page + 1)),
@@ -43,7 +46,7 @@ pub fn components(current_page: usize, last_page: usize, done: bool) -> Vec<Comp
             url: None,
         }),
         Component::Button(Button {
-            custom_id: Some("paginator::next".to_string()),
+            custom_id: Some(view::PAGINATOR_NEXT.to_string()),
             disabled: current_page == last_page || done,
             emoji: None,
             label: Some(">".to_string()),
@@ -51,7 +54,7 @@ pub fn components(current_page: usize, last_page: usize, done: bool) -> Vec<Comp
             url: None,
         }),
         Component::Button(Button {
-            custom_id: Some("paginator::to_end".to_string()),
+            custom_id: Some(view::PAGINATOR_TO_END.to_string()),
             disabled: current_page == last_page || done,
             emoji: None,
             label: Some(">>".to_string()),
@@ -67,8 +70,8 @@ pub fn components(current_page: usize, last_page: usize, done: bool) -> Vec<Comp
     vec![row]
 }
 
-pub async fn simple(
-    ctx: &mut CommandCtx,
+pub async fn simple<T>(
+    ctx: &mut Ctx<T>,
     pages: Vec<(Option<String>, Option<Vec<Embed>>)>,
     user_id: Id<UserMarker>,
     ephemeral: bool,
@@ -118,10 +121,10 @@ pub async fn simple(
         btn_ctx = wait_for_component(
             ctx.bot.clone(),
             &[
-                "paginator::to_start",
-                "paginator::back",
-                "paginator::next",
-                "paginator::to_end",
+                view::PAGINATOR_TO_START,
+                view::PAGINATOR_BACK,
+                view::PAGINATOR_NEXT,
+                view::PAGINATOR_TO_END,
             ],
             msg_id,
             user_id,
@@ -131,10 +134,10 @@ pub async fn simple(
 
         if let Some(known_btn_ctx) = &btn_ctx {
             current_page = match &*known_btn_ctx.data.custom_id {
-                "paginator::to_start" => 0,
-                "paginator::back" => current_page - 1,
-                "paginator::next" => current_page + 1,
-                "paginator::to_end" => last_page,
+                view::PAGINATOR_TO_START => 0,
+                view::PAGINATOR_BACK => current_page - 1,
+                view::PAGINATOR_NEXT => current_page + 1,
+                view::PAGINATOR_TO_END => last_page,
                 _ => unreachable!(),
             };
         } else {
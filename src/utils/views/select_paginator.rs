@@ -14,7 +14,10 @@ use twilight_model::{
 use crate::{
     client::bot::StarboardBot,
     errors::StarboardResult,
-    interactions::context::{CommandCtx, ComponentCtx},
+    interactions::{
+        context::{CommandCtx, ComponentCtx},
+        custom_id::view,
+    },
     utils::div_ceil::div_ceil,
 };
 
@@ -72,9 +75,9 @@ impl SelectPaginator {
             let select_ctx = wait_for_component(
                 self.bot.clone(),
                 &[
-                    "select_paginator::select",
-                    "select_paginator::next",
-                    "select_paginator::back",
+                    view::SELECT_PAGINATOR_SELECT,
+                    view::SELECT_PAGINATOR_NEXT,
+                    view::SELECT_PAGINATOR_BACK,
                 ],
                 message,
                 self.user,
@@ -92,15 +95,15 @@ impl SelectPaginator {
             };
 
             match &*ctx.data.custom_id {
-                "select_paginator::select" => {
+                view::SELECT_PAGINATOR_SELECT => {
                     let choice: usize = ctx.data.values[0].parse().unwrap();
                     self.current = choice;
                 }
-                "select_paginator::next" => {
+                view::SELECT_PAGINATOR_NEXT => {
                     let current = self.get_chunks().1;
                     self.current = self.get_chunk_range(current + 1).0;
                 }
-                "select_paginator::back" => {
+                view::SELECT_PAGINATOR_BACK => {
                     let current = self.get_chunks().1;
                     self.current = self.get_chunk_range(current - 1).0;
                 }
@@ -190,7 +193,7 @@ impl SelectPaginator {
 
         let buttons = vec![
             Component::Button(Button {
-                custom_id: Some("select_paginator::back".to_string()),
+                custom_id: Some(view::SELECT_PAGINATOR_BACK.to_string()),
                 disabled: current == 0 || self.done,
                 emoji: None,
                 label: Some("<".to_string()),
@@ -198,7 +201,7 @@ impl SelectPaginator {
                 url: None,
             }),
             Component::Button(Button {
-                custom_id: Some("select_paginator::meta".to_string()),
+                custom_id: Some(view::SELECT_PAGINATOR_META.to_string()),
                 disabled: true,
                 emoji: None,
                 label: Some(format!("{}/{}", current + 1, last_chunk + 1)),
@@ -206,7 +209,7 @@ impl SelectPaginator {
                 url: None,
             }),
             Component::Button(Button {
-                custom_id: Some("select_paginator::next".to_string()),
+                custom_id: Some(view::SELECT_PAGINATOR_NEXT.to_string()),
                 disabled: current == last_chunk || self.done,
                 emoji: None,
                 label: Some(">".to_string()),
@@ -233,7 +236,7 @@ impl SelectPaginator {
         }
 
         Component::SelectMenu(SelectMenu {
-            custom_id: "select_paginator::select".to_string(),
+            custom_id: view::SELECT_PAGINATOR_SELECT.to_string(),
             disabled: self.done || options.len() == 1,
             max_values: Some(1),
             min_values: Some(1),